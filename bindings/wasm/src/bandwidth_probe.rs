@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use moqt::wire::{
+    BANDWIDTH_PROBE_NAMESPACE, BandwidthEstimate, BandwidthProbeMeter, bandwidth_probe_track_name,
+};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(js_name = bandwidthProbeNamespace)]
+pub fn bandwidth_probe_namespace() -> Vec<String> {
+    vec![BANDWIDTH_PROBE_NAMESPACE.to_string()]
+}
+
+#[wasm_bindgen(js_name = bandwidthProbeTrackName)]
+pub fn probe_track_name(size_bytes: u64) -> String {
+    bandwidth_probe_track_name(size_bytes)
+}
+
+/// Times the objects of a bandwidth probe track; feed it every object of the
+/// probe subscription with `performance.now()`.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct BandwidthProbe {
+    meter: BandwidthProbeMeter,
+}
+
+#[wasm_bindgen]
+impl BandwidthProbe {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[wasm_bindgen(js_name = recordObject)]
+    pub fn record_object(&mut self, payload_length: u32, now_ms: f64) {
+        self.meter.record(
+            payload_length as usize,
+            Duration::from_secs_f64(now_ms.max(0.0) / 1000.0),
+        );
+    }
+
+    #[wasm_bindgen(getter, js_name = receivedBytes)]
+    pub fn received_bytes(&self) -> u64 {
+        self.meter.received_bytes()
+    }
+
+    pub fn estimate(&self) -> Option<BandwidthEstimateMessage> {
+        self.meter.estimate().map(BandwidthEstimateMessage::from)
+    }
+}
+
+#[wasm_bindgen]
+pub struct BandwidthEstimateMessage {
+    received_bytes: u64,
+    elapsed_ms: f64,
+    throughput_bps: u64,
+    safe_bitrate_bps: u64,
+}
+
+#[wasm_bindgen]
+impl BandwidthEstimateMessage {
+    #[wasm_bindgen(getter, js_name = receivedBytes)]
+    pub fn received_bytes(&self) -> u64 {
+        self.received_bytes
+    }
+
+    #[wasm_bindgen(getter, js_name = elapsedMs)]
+    pub fn elapsed_ms(&self) -> f64 {
+        self.elapsed_ms
+    }
+
+    #[wasm_bindgen(getter, js_name = throughputBps)]
+    pub fn throughput_bps(&self) -> u64 {
+        self.throughput_bps
+    }
+
+    /// Bitrate to use for the initial catalog selection.
+    #[wasm_bindgen(getter, js_name = safeBitrateBps)]
+    pub fn safe_bitrate_bps(&self) -> u64 {
+        self.safe_bitrate_bps
+    }
+}
+
+impl From<BandwidthEstimate> for BandwidthEstimateMessage {
+    fn from(estimate: BandwidthEstimate) -> Self {
+        Self {
+            received_bytes: estimate.received_bytes,
+            elapsed_ms: estimate.elapsed.as_secs_f64() * 1000.0,
+            throughput_bps: estimate.throughput_bps,
+            safe_bitrate_bps: estimate.safe_bitrate_bps(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+mod datagrams;
+mod events;
+mod fetch;
+mod namespace;
+mod publish;
+mod reconnect;
+mod session;
+mod subgroups;
+mod subscribe;
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use bytes::{Bytes, BytesMut};
+use futures::channel::{mpsc, oneshot};
+use moqt::wire::{
+    AuthorizationToken, ClientSetup, ControlMessageType, ExtensionHeaders, RequestError,
+    RequestsBlocked, SetupParameter, encode_control_message,
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{WebTransport, WritableStreamDefaultWriter};
+
+use crate::{
+    client_state::{ClientState, ResponseReceiver},
+    events::{ClientEvent, MOQTCallbacks, dispatch},
+    js_error,
+};
+
+pub(crate) type WriterKey = (u64, u64, u64);
+
+/// An encoded control message and where to report its write result.
+type ControlSend = (BytesMut, oneshot::Sender<Result<(), JsValue>>);
+
+pub(crate) type StreamWriters = Rc<RefCell<HashMap<WriterKey, WritableStreamDefaultWriter>>>;
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct MOQTClient {
+    url: String,
+    pub(crate) state: Rc<RefCell<ClientState>>,
+    transport: Rc<RefCell<Option<WebTransport>>>,
+    /// Feeds the single task that writes the control stream, so messages
+    /// go out whole and in call order whatever the JS-side concurrency.
+    control_queue: Rc<RefCell<Option<mpsc::UnboundedSender<ControlSend>>>>,
+    datagram_writer: Rc<RefCell<Option<WritableStreamDefaultWriter>>>,
+    stream_writers: StreamWriters,
+    stream_object_numbers: Rc<RefCell<HashMap<WriterKey, u64>>>,
+    /// Bytes per track alias handed to subgroup stream writes that have
+    /// not completed yet, including those waiting on `writer.ready`.
+    send_buffered_bytes: Rc<RefCell<HashMap<u64, usize>>>,
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    reconnect: Rc<RefCell<reconnect::ReconnectState>>,
+}
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(constructor)]
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            state: Rc::new(RefCell::new(ClientState::default())),
+            transport: Rc::new(RefCell::new(None)),
+            control_queue: Rc::new(RefCell::new(None)),
+            datagram_writer: Rc::new(RefCell::new(None)),
+            stream_writers: Rc::new(RefCell::new(HashMap::new())),
+            stream_object_numbers: Rc::new(RefCell::new(HashMap::new())),
+            send_buffered_bytes: Rc::new(RefCell::new(HashMap::new())),
+            callbacks: Rc::new(RefCell::new(MOQTCallbacks::default())),
+            reconnect: Rc::new(RefCell::new(reconnect::ReconnectState::default())),
+        }
+    }
+
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    #[wasm_bindgen(js_name = isGoingAway)]
+    pub fn is_going_away(&self) -> bool {
+        self.state.borrow().going_away.is_some()
+    }
+
+    #[wasm_bindgen(js_name = maxRequestId)]
+    pub fn max_request_id(&self) -> u64 {
+        self.state.borrow().max_request_id
+    }
+
+    #[wasm_bindgen(js_name = isConnected)]
+    pub fn is_connected(&self) -> bool {
+        self.transport.borrow().is_some()
+    }
+
+    /// Largest datagram the browser will send on this session, in bytes,
+    /// including the object datagram header.
+    #[wasm_bindgen(js_name = getMaxDatagramSize)]
+    pub fn get_max_datagram_size(&self) -> Result<u32, JsValue> {
+        let transport = self
+            .transport
+            .borrow()
+            .clone()
+            .ok_or_else(|| js_error("transport is None"))?;
+        Ok(transport.datagrams().max_datagram_size())
+    }
+
+    #[wasm_bindgen(js_name = sendClientSetup)]
+    pub async fn send_client_setup(
+        &self,
+        versions: Vec<u64>,
+        max_request_id: u64,
+    ) -> Result<(), JsValue> {
+        self.reconnect.borrow_mut().client_setup = Some((versions.clone(), max_request_id));
+        let supported_versions = versions.into_iter().map(|version| version as u32).collect();
+        let payload =
+            ClientSetup::new(supported_versions, default_setup_parameters(max_request_id)).encode();
+        self.state.borrow_mut().configure(max_request_id);
+        self.send_control_message(ControlMessageType::ClientSetup, payload)
+            .await
+    }
+
+    /// The next client Request ID no request of this session has used,
+    /// including ids passed explicitly to `send*` / `subscribe*`. Each call
+    /// reserves a new one.
+    #[wasm_bindgen(js_name = allocateRequestId)]
+    pub fn allocate_request_id(&self) -> u64 {
+        self.state.borrow_mut().allocate_request_id()
+    }
+
+    /// Rejects a new request when the session is going away or `request_id`
+    /// is not below the server's Maximum Request ID. The first refusal at a
+    /// given limit sends REQUESTS_BLOCKED so the server can raise it.
+    async fn ensure_request_allowed(&self, request_id: u64) -> Result<(), JsValue> {
+        self.state.borrow().ensure_not_going_away()?;
+        self.state.borrow_mut().note_request_id(request_id);
+        let max_request_id = self.state.borrow().max_request_id;
+        if request_id < max_request_id {
+            return Ok(());
+        }
+        if self.state.borrow_mut().block_requests() {
+            let payload = RequestsBlocked {
+                maximum_request_id: max_request_id,
+            }
+            .encode();
+            self.send_control_message(ControlMessageType::RequestsBlocked, payload)
+                .await?;
+        }
+        dispatch(&self.callbacks, ClientEvent::RequestsBlocked, None, || {
+            vec![JsValue::from(max_request_id)]
+        });
+        Err(js_error(format!(
+            "request id {request_id} is not below the maximum request id {max_request_id}"
+        )))
+    }
+
+    async fn send_control_message(
+        &self,
+        message_type: ControlMessageType,
+        payload: BytesMut,
+    ) -> Result<(), JsValue> {
+        let queue = self
+            .control_queue
+            .borrow()
+            .clone()
+            .ok_or_else(|| js_error("control stream is not open"))?;
+        let bytes = encode_control_message(message_type, payload);
+        let (written, result) = oneshot::channel();
+        queue
+            .unbounded_send((bytes, written))
+            .map_err(|_| js_error("control stream is closed"))?;
+        result
+            .await
+            .unwrap_or_else(|_| Err(js_error("control stream is closed")))
+    }
+
+    async fn send_request_error(
+        &self,
+        message_type: ControlMessageType,
+        request_id: u64,
+        error_code: u64,
+        reason_phrase: String,
+    ) -> Result<(), JsValue> {
+        self.send_control_message(
+            message_type,
+            RequestError {
+                request_id,
+                error_code,
+                reason_phrase,
+            }
+            .encode(),
+        )
+        .await
+    }
+}
+
+fn default_setup_parameters(max_request_id: u64) -> SetupParameter {
+    SetupParameter {
+        path: None,
+        max_request_id,
+        authorization_token: vec![],
+        max_auth_token_cache_size: None,
+        authority: None,
+        moq_implementation: Some("moqt-client-wasm".to_string()),
+    }
+}
+
+fn authorization_tokens(auth_info: &str) -> Vec<AuthorizationToken> {
+    if auth_info.trim().is_empty() {
+        return vec![];
+    }
+
+    vec![AuthorizationToken::UseValue {
+        token_type: 0,
+        token_value: Bytes::copy_from_slice(auth_info.as_bytes()),
+    }]
+}
+
+fn empty_extension_headers() -> ExtensionHeaders {
+    ExtensionHeaders::default()
+}
+
+/// Resolves with the *_OK of a request, or rejects with its
+/// `RequestErrorMessage`, or with an error when the session closed first.
+async fn await_response<T>(response: ResponseReceiver<T>) -> Result<T, JsValue> {
+    match response.await {
+        Ok(Ok(message)) => Ok(message),
+        Ok(Err(error)) => Err(JsValue::from(error)),
+        Err(_) => Err(js_error("session closed before the response arrived")),
+    }
+}
+
+/// Waits for `writer.ready` first, so callers are held back while the
+/// stream's queue is above its high-water mark instead of growing it.
+async fn write_to_writer(
+    writer: &WritableStreamDefaultWriter,
+    bytes: &[u8],
+) -> Result<(), JsValue> {
+    JsFuture::from(writer.ready()).await?;
+    let buffer = js_sys::Uint8Array::new_with_length(bytes.len() as u32);
+    buffer.copy_from(bytes);
+    JsFuture::from(writer.write_with_chunk(&buffer)).await?;
+    Ok(())
+}
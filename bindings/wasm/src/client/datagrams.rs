@@ -0,0 +1,116 @@
+use bytes::Bytes;
+use moqt::wire::{DatagramField, ObjectDatagram, ObjectStatus};
+use wasm_bindgen::prelude::*;
+
+use super::{MOQTClient, empty_extension_headers, write_to_writer};
+use crate::js_error;
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(js_name = sendObjectDatagram)]
+    pub async fn send_object_datagram(
+        &self,
+        track_alias: u64,
+        group_id: u64,
+        object_id: u64,
+        publisher_priority: u8,
+        object_payload: Vec<u8>,
+        #[wasm_bindgen(unchecked_optional_param_type = "LocHeader | null")] loc_header: JsValue,
+    ) -> Result<(), JsValue> {
+        let extension_headers = match crate::loc::parse_loc_header(loc_header)
+            .map_err(|error| js_error(error.to_string()))?
+        {
+            Some(header) => crate::loc::loc_header_to_extension_headers(&header),
+            None => empty_extension_headers(),
+        };
+        let payload_len = object_payload.len();
+
+        let field = if extension_headers == empty_extension_headers() {
+            DatagramField::Payload0x00 {
+                object_id,
+                publisher_priority,
+                payload: Bytes::from(object_payload),
+            }
+        } else {
+            DatagramField::Payload0x01 {
+                object_id,
+                publisher_priority,
+                extension_headers,
+                payload: Bytes::from(object_payload),
+            }
+        };
+
+        let payload = ObjectDatagram::new(track_alias, group_id, field).encode();
+        self.send_datagram_bytes(&payload).await?;
+        self.state
+            .borrow_mut()
+            .traffic
+            .record_sent(track_alias, payload_len);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = sendObjectDatagramStatus)]
+    pub async fn send_object_datagram_status(
+        &self,
+        track_alias: u64,
+        group_id: u64,
+        object_id: u64,
+        publisher_priority: u8,
+        object_status: u8,
+        #[wasm_bindgen(unchecked_optional_param_type = "LocHeader | null")] loc_header: JsValue,
+    ) -> Result<(), JsValue> {
+        let object_status =
+            ObjectStatus::try_from(object_status).map_err(|_| js_error("invalid object status"))?;
+        let extension_headers = match crate::loc::parse_loc_header(loc_header)
+            .map_err(|error| js_error(error.to_string()))?
+        {
+            Some(header) => crate::loc::loc_header_to_extension_headers(&header),
+            None => empty_extension_headers(),
+        };
+
+        let field = if extension_headers == empty_extension_headers() {
+            DatagramField::Status0x20 {
+                object_id,
+                publisher_priority,
+                status: object_status,
+            }
+        } else {
+            DatagramField::Status0x21 {
+                object_id,
+                publisher_priority,
+                extension_headers,
+                status: object_status,
+            }
+        };
+
+        let payload = ObjectDatagram::new(track_alias, group_id, field).encode();
+        self.send_datagram_bytes(&payload).await?;
+        self.state.borrow_mut().traffic.record_sent(track_alias, 0);
+        Ok(())
+    }
+
+    /// Rejects datagrams over `getMaxDatagramSize()` with a
+    /// `DatagramTooLargeError` before the browser drops or throws on them.
+    async fn send_datagram_bytes(&self, payload: &[u8]) -> Result<(), JsValue> {
+        let max_datagram_size = self.get_max_datagram_size()?;
+        if payload.len() > max_datagram_size as usize {
+            return Err(datagram_too_large_error(payload.len(), max_datagram_size));
+        }
+        let writer = self
+            .datagram_writer
+            .borrow()
+            .clone()
+            .ok_or_else(|| js_error("datagram_writer is None"))?;
+        write_to_writer(&writer, payload).await
+    }
+}
+
+/// An `Error` named `DatagramTooLargeError`, so callers can tell an oversized
+/// object apart and send it on a subgroup stream instead.
+fn datagram_too_large_error(size: usize, max_datagram_size: u32) -> JsValue {
+    let error = js_sys::Error::new(&format!(
+        "datagram of {size} bytes exceeds the maximum datagram size {max_datagram_size}"
+    ));
+    error.set_name("DatagramTooLargeError");
+    error.into()
+}
@@ -0,0 +1,269 @@
+use wasm_bindgen::prelude::*;
+
+use super::MOQTClient;
+use crate::{events::ClientEvent, js_error};
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(js_name = onServerSetup)]
+    pub fn set_server_setup_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "ServerSetupCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::ServerSetup, callback);
+    }
+
+    #[wasm_bindgen(js_name = onPublishNamespace)]
+    pub fn set_publish_namespace_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "PublishNamespaceCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::PublishNamespace, callback);
+    }
+
+    #[wasm_bindgen(js_name = onPublishNamespaceDone)]
+    pub fn set_publish_namespace_done_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "PublishNamespaceDoneCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::PublishNamespaceDone, callback);
+    }
+
+    #[wasm_bindgen(js_name = onPublishNamespaceResponse)]
+    pub fn set_publish_namespace_response_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "NamespaceResponseCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::PublishNamespaceResponse, callback);
+    }
+
+    #[wasm_bindgen(js_name = onSubscribeNamespaceResponse)]
+    pub fn set_subscribe_namespace_response_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "NamespaceResponseCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::SubscribeNamespaceResponse, callback);
+    }
+
+    #[wasm_bindgen(js_name = onNamespaceAdded)]
+    pub fn set_namespace_added_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "NamespaceDeltaCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::NamespaceAdded, callback);
+    }
+
+    #[wasm_bindgen(js_name = onNamespaceRemoved)]
+    pub fn set_namespace_removed_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "NamespaceDeltaCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::NamespaceRemoved, callback);
+    }
+
+    #[wasm_bindgen(js_name = onPublish)]
+    pub fn set_publish_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "PublishCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::Publish, callback);
+    }
+
+    #[wasm_bindgen(js_name = onPublishResponse)]
+    pub fn set_publish_response_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "PublishResponseCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::PublishResponse, callback);
+    }
+
+    #[wasm_bindgen(js_name = onSubscribe)]
+    pub fn set_subscribe_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "SubscribeCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::Subscribe, callback);
+    }
+
+    #[wasm_bindgen(js_name = onSubscribeResponse)]
+    pub fn set_subscribe_response_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "SubscribeResponseCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::SubscribeResponse, callback);
+    }
+
+    /// Called with a `PublishDoneMessage` when the publisher ends one of this
+    /// client's subscriptions, including those made through
+    /// `subscribeTrack` (whose handle also gets `onDone`).
+    #[wasm_bindgen(js_name = onPublishDone)]
+    pub fn set_publish_done_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "PublishDoneCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::PublishDone, callback);
+    }
+
+    #[wasm_bindgen(js_name = onIncomingUnsubscribe)]
+    pub fn set_incoming_unsubscribe_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "IncomingUnsubscribeCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::IncomingUnsubscribe, callback);
+    }
+
+    #[wasm_bindgen(js_name = onObjectDatagram)]
+    pub fn set_object_datagram_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "ObjectDatagramCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::ObjectDatagram, callback);
+    }
+
+    #[wasm_bindgen(js_name = onObjectDatagramStatus)]
+    pub fn set_object_datagram_status_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "ObjectDatagramStatusCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::ObjectDatagramStatus, callback);
+    }
+
+    #[wasm_bindgen(js_name = onSubgroupHeader)]
+    pub fn set_subgroup_header_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "SubgroupHeaderCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::SubgroupHeader, callback);
+    }
+
+    #[wasm_bindgen(js_name = onSubgroupObject)]
+    pub fn set_subgroup_object_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "SubgroupObjectCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::SubgroupObject, callback);
+    }
+
+    /// Like `onSubgroupObject`, but the callback receives a single
+    /// `SubgroupStreamObjectMessage` carrying the subgroup header context
+    /// (Subgroup ID, Publisher Priority) and the absolute Object ID.
+    #[wasm_bindgen(js_name = onSubgroupStreamObject)]
+    pub fn set_subgroup_stream_object_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "SubgroupStreamObjectCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::SubgroupStreamObject, callback);
+    }
+
+    #[wasm_bindgen(js_name = onFetchResponse)]
+    pub fn set_fetch_response_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "FetchResponseCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::FetchResponse, callback);
+    }
+
+    #[wasm_bindgen(js_name = onTrackStatus)]
+    pub fn set_track_status_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "TrackStatusCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::TrackStatus, callback);
+    }
+
+    #[wasm_bindgen(js_name = onFetchObject)]
+    pub fn set_fetch_object_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "FetchObjectCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::FetchObject, callback);
+    }
+
+    /// Registers `callback` for `event` alongside the `on*` handler and any
+    /// other listeners. Object events (`objectDatagram`,
+    /// `objectDatagramStatus`, `subgroupHeader`, `subgroupObject`,
+    /// `subgroupStreamObject`) may be narrowed to one track with
+    /// `trackAlias`.
+    #[wasm_bindgen(js_name = addEventListener)]
+    pub fn add_event_listener(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "ClientEventName")] event: String,
+        callback: js_sys::Function,
+        #[wasm_bindgen(js_name = trackAlias)] track_alias: Option<u64>,
+    ) -> Result<(), JsValue> {
+        let event = parse_client_event(&event)?;
+        self.callbacks
+            .borrow_mut()
+            .add_listener(event, callback, track_alias)
+    }
+
+    /// Removes every registration of `callback` for `event`. The `on*`
+    /// handler is left in place.
+    #[wasm_bindgen(js_name = removeEventListener)]
+    pub fn remove_event_listener(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "ClientEventName")] event: String,
+        callback: js_sys::Function,
+    ) -> Result<(), JsValue> {
+        let event = parse_client_event(&event)?;
+        self.callbacks
+            .borrow_mut()
+            .remove_listener(event, &callback);
+        Ok(())
+    }
+}
+
+fn parse_client_event(name: &str) -> Result<ClientEvent, JsValue> {
+    ClientEvent::from_name(name).ok_or_else(|| js_error(format!("unknown event: {name}")))
+}
@@ -0,0 +1,95 @@
+use moqt::wire::{
+    ControlMessageType, Fetch, FetchCancel, FetchParams, GroupOrder, Location, TrackStatusRequest,
+};
+use wasm_bindgen::prelude::*;
+
+use super::{MOQTClient, authorization_tokens};
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(js_name = sendFetch)]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_fetch(
+        &self,
+        request_id: u64,
+        track_namespace: Vec<String>,
+        track_name: String,
+        start_group: u64,
+        start_object: u64,
+        end_group: u64,
+        end_object: u64,
+    ) -> Result<(), JsValue> {
+        self.ensure_request_allowed(request_id).await?;
+        let payload = Fetch {
+            request_id,
+            subscriber_priority: 0,
+            group_order: GroupOrder::Ascending,
+            fetch_params: FetchParams::Standalone {
+                track_namespace,
+                track_name,
+                start_location: Location {
+                    group_id: start_group,
+                    object_id: start_object,
+                },
+                end_location: Location {
+                    group_id: end_group,
+                    object_id: end_object,
+                },
+            },
+            authorization_tokens: vec![],
+        }
+        .encode();
+        self.send_control_message(ControlMessageType::Fetch, payload)
+            .await
+    }
+
+    #[wasm_bindgen(js_name = sendRelativeJoiningFetch)]
+    pub async fn send_relative_joining_fetch(
+        &self,
+        request_id: u64,
+        joining_request_id: u64,
+        joining_start: u64,
+    ) -> Result<(), JsValue> {
+        self.ensure_request_allowed(request_id).await?;
+        let payload = Fetch {
+            request_id,
+            subscriber_priority: 0,
+            group_order: GroupOrder::Ascending,
+            fetch_params: FetchParams::RelativeJoining {
+                joining_request_id,
+                joining_start,
+            },
+            authorization_tokens: vec![],
+        }
+        .encode();
+        self.send_control_message(ControlMessageType::Fetch, payload)
+            .await
+    }
+
+    #[wasm_bindgen(js_name = sendFetchCancel)]
+    pub async fn send_fetch_cancel(&self, request_id: u64) -> Result<(), JsValue> {
+        let payload = FetchCancel { request_id }.encode();
+        self.send_control_message(ControlMessageType::FetchCancel, payload)
+            .await
+    }
+
+    #[wasm_bindgen(js_name = sendTrackStatusRequest)]
+    pub async fn send_track_status_request(
+        &self,
+        request_id: u64,
+        track_namespace: Vec<String>,
+        track_name: String,
+        auth_info: String,
+    ) -> Result<(), JsValue> {
+        self.ensure_request_allowed(request_id).await?;
+        let payload = TrackStatusRequest {
+            request_id,
+            track_namespace,
+            track_name,
+            authorization_tokens: authorization_tokens(&auth_info),
+        }
+        .encode();
+        self.send_control_message(ControlMessageType::TrackStatusRequest, payload)
+            .await
+    }
+}
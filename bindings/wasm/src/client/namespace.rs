@@ -0,0 +1,210 @@
+use moqt::wire::{
+    ControlMessageType, NamespaceOk, PublishNamespace, SubscribeNamespace, UnsubscribeNamespace,
+};
+use wasm_bindgen::prelude::*;
+
+use super::{MOQTClient, authorization_tokens, await_response};
+use crate::{NamespaceOkMessage, ReceivedAnnouncement, js_error};
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(js_name = sendPublishNamespace)]
+    pub async fn send_publish_namespace(
+        &self,
+        request_id: u64,
+        track_namespace: Vec<String>,
+        auth_info: String,
+    ) -> Result<(), JsValue> {
+        self.ensure_request_allowed(request_id).await?;
+        if self
+            .state
+            .borrow()
+            .contains_published_namespace(&track_namespace)
+        {
+            return Ok(());
+        }
+
+        let payload = PublishNamespace::new(
+            request_id,
+            track_namespace.clone(),
+            authorization_tokens(&auth_info),
+        )
+        .encode();
+        self.state.borrow_mut().register_publish_namespace_request(
+            request_id,
+            track_namespace,
+            auth_info,
+        );
+        self.send_control_message(ControlMessageType::PublishNamespace, payload)
+            .await
+    }
+
+    #[wasm_bindgen(js_name = sendPublishNamespaceOk)]
+    pub async fn send_publish_namespace_ok(&self, request_id: u64) -> Result<(), JsValue> {
+        self.send_control_message(
+            ControlMessageType::PublishNamespaceOk,
+            NamespaceOk { request_id }.encode(),
+        )
+        .await
+    }
+
+    #[wasm_bindgen(js_name = sendPublishNamespaceError)]
+    pub async fn send_publish_namespace_error(
+        &self,
+        request_id: u64,
+        error_code: u64,
+        reason_phrase: String,
+    ) -> Result<(), JsValue> {
+        self.send_request_error(
+            ControlMessageType::PublishNamespaceError,
+            request_id,
+            error_code,
+            reason_phrase,
+        )
+        .await?;
+        self.state.borrow_mut().reject_peer_namespace(request_id);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = sendSubscribeNamespace)]
+    pub async fn send_subscribe_namespace(
+        &self,
+        request_id: u64,
+        track_namespace_prefix: Vec<String>,
+        auth_info: String,
+    ) -> Result<(), JsValue> {
+        self.ensure_request_allowed(request_id).await?;
+        if self
+            .state
+            .borrow()
+            .subscribed_namespace_prefixes
+            .contains_key(&track_namespace_prefix)
+        {
+            return Ok(());
+        }
+
+        let payload = SubscribeNamespace::new(
+            request_id,
+            track_namespace_prefix.clone(),
+            authorization_tokens(&auth_info),
+        )
+        .encode();
+        self.state
+            .borrow_mut()
+            .register_subscribe_namespace_request(request_id, track_namespace_prefix, auth_info);
+        self.send_control_message(ControlMessageType::SubscribeNamespace, payload)
+            .await
+    }
+
+    /// `sendPublishNamespace` that resolves with PUBLISH_NAMESPACE_OK and
+    /// rejects with the `RequestErrorMessage` of PUBLISH_NAMESPACE_ERROR.
+    /// `onPublishNamespaceResponse` still fires.
+    #[wasm_bindgen(js_name = publishNamespace)]
+    pub async fn publish_namespace(
+        &self,
+        request_id: u64,
+        track_namespace: Vec<String>,
+        auth_info: String,
+    ) -> Result<NamespaceOkMessage, JsValue> {
+        if self
+            .state
+            .borrow()
+            .contains_published_namespace(&track_namespace)
+        {
+            return Err(js_error("namespace is already published"));
+        }
+        let response = self
+            .state
+            .borrow_mut()
+            .expect_namespace_response(request_id);
+        if let Err(error) = self
+            .send_publish_namespace(request_id, track_namespace, auth_info)
+            .await
+        {
+            self.state
+                .borrow_mut()
+                .pending_namespace_requests
+                .remove(&request_id);
+            return Err(error);
+        }
+        await_response(response).await
+    }
+
+    /// `sendSubscribeNamespace` that resolves with SUBSCRIBE_NAMESPACE_OK and
+    /// rejects with the `RequestErrorMessage` of SUBSCRIBE_NAMESPACE_ERROR.
+    /// `onSubscribeNamespaceResponse` still fires.
+    #[wasm_bindgen(js_name = subscribeNamespace)]
+    pub async fn subscribe_namespace(
+        &self,
+        request_id: u64,
+        track_namespace_prefix: Vec<String>,
+        auth_info: String,
+    ) -> Result<NamespaceOkMessage, JsValue> {
+        if self
+            .state
+            .borrow()
+            .subscribed_namespace_prefixes
+            .contains_key(&track_namespace_prefix)
+        {
+            return Err(js_error("namespace prefix is already subscribed"));
+        }
+        let response = self
+            .state
+            .borrow_mut()
+            .expect_namespace_response(request_id);
+        if let Err(error) = self
+            .send_subscribe_namespace(request_id, track_namespace_prefix, auth_info)
+            .await
+        {
+            self.state
+                .borrow_mut()
+                .pending_namespace_requests
+                .remove(&request_id);
+            return Err(error);
+        }
+        await_response(response).await
+    }
+
+    /// Stops namespace discovery under `track_namespace_prefix`. Unknown
+    /// prefixes are ignored.
+    #[wasm_bindgen(js_name = sendUnsubscribeNamespace)]
+    pub async fn send_unsubscribe_namespace(
+        &self,
+        track_namespace_prefix: Vec<String>,
+    ) -> Result<(), JsValue> {
+        if !self
+            .state
+            .borrow_mut()
+            .remove_subscribe_namespace(&track_namespace_prefix)
+        {
+            return Ok(());
+        }
+        let payload = UnsubscribeNamespace::new(track_namespace_prefix).encode();
+        self.send_control_message(ControlMessageType::UnSubscribeNamespace, payload)
+            .await
+    }
+
+    /// Namespaces this client has announced with PUBLISH_NAMESPACE, in
+    /// sorted order. Requests still awaiting their response are included.
+    #[wasm_bindgen(js_name = getPublishedNamespaces, unchecked_return_type = "string[][]")]
+    pub fn get_published_namespaces(&self) -> js_sys::Array {
+        let state = self.state.borrow();
+        let mut namespaces: Vec<_> = state.published_namespaces.keys().collect();
+        namespaces.sort();
+        namespaces
+            .into_iter()
+            .map(|namespace| {
+                namespace
+                    .iter()
+                    .map(|element| JsValue::from_str(element))
+                    .collect::<js_sys::Array>()
+            })
+            .collect()
+    }
+
+    /// Namespaces the peer has announced to this client and not withdrawn.
+    #[wasm_bindgen(js_name = getReceivedAnnouncements)]
+    pub fn get_received_announcements(&self) -> Vec<ReceivedAnnouncement> {
+        self.state.borrow().received_announcements()
+    }
+}
@@ -0,0 +1,281 @@
+use moqt::wire::{
+    ContentExists, ControlMessageType, GroupOrder, Location, Publish, PublishDone, PublishOk,
+    SubscribeOk,
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use super::{MOQTClient, StreamWriters, authorization_tokens, subscribe::filter_type_from_fields};
+use crate::{PublishTrackHandle, client_state::TrackKey, js_error, publish_track};
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(js_name = sendPublish)]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_publish(
+        &self,
+        request_id: u64,
+        track_namespace: Vec<String>,
+        track_name: String,
+        track_alias: Option<u64>,
+        group_order: u8,
+        content_exists: bool,
+        largest_group_id: Option<u64>,
+        largest_object_id: Option<u64>,
+        forward: bool,
+        auth_info: String,
+    ) -> Result<u64, JsValue> {
+        self.ensure_request_allowed(request_id).await?;
+        let group_order =
+            GroupOrder::try_from(group_order).map_err(|_| js_error("invalid group order"))?;
+        let content_exists =
+            content_exists_from_fields(content_exists, largest_group_id, largest_object_id);
+        let track_alias = self
+            .state
+            .borrow_mut()
+            .claim_publish_track_alias(track_alias)
+            .map_err(|error| js_error(error.to_string()))?;
+        let payload = Publish {
+            request_id,
+            track_namespace_tuple: track_namespace.clone(),
+            track_name: track_name.clone(),
+            track_alias,
+            group_order,
+            content_exists,
+            forward,
+            authorization_tokens: authorization_tokens(&auth_info),
+            delivery_timeout: None,
+            max_duration: None,
+        }
+        .encode();
+        self.state.borrow_mut().register_publish_request(
+            request_id,
+            TrackKey::new(track_namespace, track_name),
+            track_alias,
+        );
+        if let Err(error) = self
+            .send_control_message(ControlMessageType::Publish, payload)
+            .await
+        {
+            self.state
+                .borrow_mut()
+                .finish_publish_request(request_id, false);
+            return Err(error);
+        }
+        Ok(track_alias)
+    }
+
+    /// Returns a Track Alias from `sendPublish` to the pool once the
+    /// publication has ended, so later PUBLISH / SUBSCRIBE_OK can reuse it.
+    #[wasm_bindgen(js_name = releaseTrackAlias)]
+    pub fn release_track_alias(&self, track_alias: u64) {
+        self.state.borrow_mut().release_track_alias(track_alias);
+    }
+
+    /// Creates a handle that publishes one track object by object. See
+    /// `PublishTrackOptions` for `options`.
+    #[wasm_bindgen(js_name = createPublishTrack)]
+    pub fn create_publish_track(
+        &self,
+        track_namespace: Vec<String>,
+        track_name: String,
+        #[wasm_bindgen(unchecked_optional_param_type = "PublishTrackOptions")] options: JsValue,
+    ) -> Result<PublishTrackHandle, JsValue> {
+        let options = publish_track::PublishTrackOptions::parse(options)?;
+        let (track_alias, owns_track_alias) = self
+            .state
+            .borrow_mut()
+            .claim_track_handle_alias(options.track_alias)
+            .map_err(|error| js_error(error.to_string()))?;
+        Ok(PublishTrackHandle::new(
+            self.clone(),
+            track_namespace,
+            track_name,
+            track_alias,
+            owns_track_alias,
+            &options,
+        ))
+    }
+
+    #[wasm_bindgen(js_name = sendPublishOk)]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_publish_ok(
+        &self,
+        request_id: u64,
+        subscriber_priority: u8,
+        group_order: u8,
+        filter_type: u8,
+        start_group: Option<u64>,
+        start_object: Option<u64>,
+        end_group: Option<u64>,
+        delivery_timeout: Option<u64>,
+        forward: bool,
+    ) -> Result<(), JsValue> {
+        let group_order =
+            GroupOrder::try_from(group_order).map_err(|_| js_error("invalid group order"))?;
+        let filter_type =
+            filter_type_from_fields(filter_type, start_group, start_object, end_group)?;
+        let payload = PublishOk {
+            request_id,
+            forward,
+            subscriber_priority,
+            group_order,
+            filter_type,
+            delivery_timeout,
+        }
+        .encode();
+        self.send_control_message(ControlMessageType::PublishOk, payload)
+            .await
+    }
+
+    #[wasm_bindgen(js_name = sendPublishError)]
+    pub async fn send_publish_error(
+        &self,
+        request_id: u64,
+        error_code: u64,
+        reason_phrase: String,
+    ) -> Result<(), JsValue> {
+        self.send_request_error(
+            ControlMessageType::PublishError,
+            request_id,
+            error_code,
+            reason_phrase,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[wasm_bindgen(js_name = sendSubscribeOk)]
+    pub async fn send_subscribe_ok(
+        &self,
+        request_id: u64,
+        expires: u64,
+        content_exists: bool,
+        largest_group_id: Option<u64>,
+        largest_object_id: Option<u64>,
+        delivery_timeout: Option<u64>,
+        max_duration: Option<u64>,
+    ) -> Result<u64, JsValue> {
+        let (track_alias, group_order) = {
+            let mut state = self.state.borrow_mut();
+            let group_order = state
+                .incoming_subscribe_group_order(request_id)
+                .map_err(|error| js_error(error.to_string()))?;
+            let group_order = if group_order == GroupOrder::Publisher {
+                GroupOrder::Ascending
+            } else {
+                group_order
+            };
+            let track_alias = state
+                .activate_incoming_subscribe(request_id)
+                .map_err(|error| js_error(error.to_string()))?;
+            (track_alias, group_order)
+        };
+
+        let payload = SubscribeOk {
+            request_id,
+            track_alias,
+            expires,
+            group_order,
+            content_exists: content_exists_from_fields(
+                content_exists,
+                largest_group_id,
+                largest_object_id,
+            ),
+            delivery_timeout,
+            max_duration,
+        }
+        .encode();
+        self.send_control_message(ControlMessageType::SubscribeOk, payload)
+            .await?;
+        Ok(track_alias)
+    }
+
+    #[wasm_bindgen(js_name = sendSubscribeError)]
+    pub async fn send_subscribe_error(
+        &self,
+        request_id: u64,
+        error_code: u64,
+        reason_phrase: String,
+    ) -> Result<(), JsValue> {
+        self.send_request_error(
+            ControlMessageType::SubscribeError,
+            request_id,
+            error_code,
+            reason_phrase,
+        )
+        .await
+    }
+
+    /// Ends the incoming subscription `request_id` with PUBLISH_DONE
+    /// (draft-14's SUBSCRIBE_DONE), e.g. status 0x2 TRACK_ENDED when the
+    /// source stops. Its Stream Count is the number of subgroup streams
+    /// opened for the subscription; those still open are closed, so the
+    /// subscriber receives everything written to them.
+    #[wasm_bindgen(js_name = sendPublishDone)]
+    pub async fn send_publish_done(
+        &self,
+        request_id: u64,
+        status_code: u64,
+        reason_phrase: String,
+    ) -> Result<(), JsValue> {
+        let stream_count = self
+            .state
+            .borrow()
+            .incoming_subscribe_stream_count(request_id)
+            .map_err(|error| js_error(error.to_string()))?;
+        let payload =
+            PublishDone::new(request_id, status_code, stream_count, reason_phrase).encode();
+        self.send_control_message(ControlMessageType::PublishDone, payload)
+            .await?;
+        let track_alias = self
+            .state
+            .borrow_mut()
+            .remove_incoming_subscribe(request_id);
+        if let Some(track_alias) = track_alias {
+            close_track_streams(&self.stream_writers, track_alias).await;
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = getTrackSubscribers)]
+    pub fn get_track_subscribers(
+        &self,
+        track_namespace: Vec<String>,
+        track_name: String,
+    ) -> Vec<u64> {
+        self.state
+            .borrow()
+            .get_track_subscribers(track_namespace, track_name)
+    }
+}
+
+fn content_exists_from_fields(
+    content_exists: bool,
+    largest_group_id: Option<u64>,
+    largest_object_id: Option<u64>,
+) -> ContentExists {
+    if content_exists {
+        ContentExists::True {
+            location: Location {
+                group_id: largest_group_id.unwrap_or(0),
+                object_id: largest_object_id.unwrap_or(0),
+            },
+        }
+    } else {
+        ContentExists::False
+    }
+}
+
+/// Finishes the subgroup streams still open on `track_alias`, whose
+/// subscription this client ended with PUBLISH_DONE.
+async fn close_track_streams(stream_writers: &StreamWriters, track_alias: u64) {
+    let writers: Vec<_> = stream_writers
+        .borrow_mut()
+        .extract_if(|(alias, _, _), _| *alias == track_alias)
+        .map(|(_, writer)| writer)
+        .collect();
+    for writer in writers {
+        let _ = JsFuture::from(writer.close()).await;
+    }
+}
@@ -0,0 +1,252 @@
+use moqt::wire::Subscribe;
+use serde::Deserialize;
+use wasm_bindgen::{JsCast, prelude::*};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::WebTransport;
+
+use super::{MOQTClient, await_response};
+use crate::{
+    client_state::SessionReplay,
+    events::{ClientEvent, dispatch},
+    js_error,
+};
+
+/// Options of `enableAutoReconnect`; every field is optional.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(super) struct ReconnectOptions {
+    max_attempts: u32,
+    /// Attempt n waits n times this first.
+    delay_ms: u32,
+}
+
+impl Default for ReconnectOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            delay_ms: 1000,
+        }
+    }
+}
+
+/// What a reconnect needs to re-dial and set the session up again.
+#[derive(Debug, Default)]
+pub(super) struct ReconnectState {
+    /// Set by `enableAutoReconnect`, cleared by `close`.
+    pub(super) options: Option<ReconnectOptions>,
+    /// The options `start` was called with.
+    pub(super) transport_options: JsValue,
+    /// Versions and Maximum Request ID of the last CLIENT_SETUP.
+    pub(super) client_setup: Option<(Vec<u64>, u64)>,
+    /// Set while attempts run, so the loss of a half-set-up session does
+    /// not start another reconnect.
+    reconnecting: bool,
+}
+
+#[wasm_bindgen]
+impl MOQTClient {
+    /// When the session is lost rather than ended with `close`, re-dials
+    /// the URL with the `start` options, replays CLIENT_SETUP and re-issues
+    /// the PUBLISH_NAMESPACE, SUBSCRIBE_NAMESPACE and SUBSCRIBE requests
+    /// still active, then fires `onReconnected`. `onConnectionClosed` only
+    /// fires once every attempt has failed. See `ReconnectOptions` for
+    /// `options`.
+    #[wasm_bindgen(js_name = enableAutoReconnect)]
+    pub fn enable_auto_reconnect(
+        &self,
+        #[wasm_bindgen(unchecked_optional_param_type = "ReconnectOptions")] options: JsValue,
+    ) -> Result<(), JsValue> {
+        let options = if options.is_undefined() || options.is_null() {
+            ReconnectOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)
+                .map_err(|error| js_error(format!("invalid reconnect options: {error}")))?
+        };
+        self.reconnect.borrow_mut().options = Some(options);
+        Ok(())
+    }
+
+    /// Called once auto-reconnect has set the session up again, with the
+    /// old to new Request IDs and Track Aliases of the subscriptions the
+    /// publisher accepted again. `SubscribeHandle`s follow on their own.
+    #[wasm_bindgen(js_name = onReconnected)]
+    pub fn set_reconnected_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "ReconnectedCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::Reconnected, callback);
+    }
+}
+
+impl MOQTClient {
+    /// Runs once `transport` has closed: reconnects when enabled, otherwise
+    /// ends the session and fires `onConnectionClosed`.
+    pub(super) async fn transport_closed(
+        &self,
+        transport: WebTransport,
+        close_code: u32,
+        reason: String,
+    ) {
+        let current = self.transport.borrow().clone();
+        if current.is_some_and(|current| current != transport) {
+            return;
+        }
+        self.transport.borrow_mut().take();
+        self.close_transport(None, None).await;
+        if self.reconnect.borrow().reconnecting {
+            return;
+        }
+        let Some(options) = self.reconnect.borrow().options else {
+            self.state.borrow().close_track_streams();
+            self.notify_connection_closed(close_code, &reason);
+            return;
+        };
+
+        self.reconnect.borrow_mut().reconnecting = true;
+        let replay = self.state.borrow_mut().detach_session();
+        let replayed = self.reconnect_session(options, &replay).await;
+        self.reconnect.borrow_mut().reconnecting = false;
+        match replayed {
+            Some((request_ids, track_aliases)) => {
+                dispatch(&self.callbacks, ClientEvent::Reconnected, None, || {
+                    vec![request_ids.into(), track_aliases.into()]
+                });
+            }
+            None => {
+                for subscription in &replay.subscriptions {
+                    if let Some(receiver) = &subscription.receiver {
+                        receiver.close_stream();
+                    }
+                }
+                self.notify_connection_closed(close_code, &reason);
+            }
+        }
+    }
+
+    /// Attempts to set the session up again; `None` once every attempt has
+    /// failed or `close` was called meanwhile.
+    async fn reconnect_session(
+        &self,
+        options: ReconnectOptions,
+        replay: &SessionReplay,
+    ) -> Option<(js_sys::Map, js_sys::Map)> {
+        for attempt in 1..=options.max_attempts {
+            sleep(options.delay_ms.saturating_mul(attempt)).await;
+            // Cleared when `close` was called meanwhile.
+            self.reconnect.borrow().options?;
+            match self.replay_session(replay).await {
+                Ok(replayed) => return Some(replayed),
+                Err(_) => {
+                    self.close_transport(None, None).await;
+                    self.state.borrow_mut().detach_session();
+                }
+            }
+        }
+        None
+    }
+
+    /// One attempt: re-dial and re-issue what `replay` holds. Resolves with
+    /// the old to new Request IDs and Track Aliases of the subscriptions
+    /// accepted again.
+    async fn replay_session(
+        &self,
+        replay: &SessionReplay,
+    ) -> Result<(js_sys::Map, js_sys::Map), JsValue> {
+        let transport_options = self.reconnect.borrow().transport_options.clone();
+        self.start(transport_options).await?;
+        let client_setup = self.reconnect.borrow().client_setup.clone();
+        if let Some((versions, max_request_id)) = client_setup {
+            let server_setup = self.state.borrow_mut().expect_server_setup();
+            self.send_client_setup(versions, max_request_id).await?;
+            server_setup
+                .await
+                .map_err(|_| js_error("session closed before SERVER_SETUP"))?;
+        }
+
+        for (namespace, auth_info) in &replay.published_namespaces {
+            let request_id = self.allocate_request_id();
+            let response = self
+                .state
+                .borrow_mut()
+                .expect_namespace_response(request_id);
+            self.send_publish_namespace(request_id, namespace.clone(), auth_info.clone())
+                .await?;
+            let _ = await_response(response).await;
+        }
+        for (namespace_prefix, auth_info) in &replay.subscribed_namespace_prefixes {
+            let request_id = self.allocate_request_id();
+            let response = self
+                .state
+                .borrow_mut()
+                .expect_namespace_response(request_id);
+            self.send_subscribe_namespace(request_id, namespace_prefix.clone(), auth_info.clone())
+                .await?;
+            let _ = await_response(response).await;
+        }
+
+        let request_ids = js_sys::Map::new();
+        let track_aliases = js_sys::Map::new();
+        for subscription in &replay.subscriptions {
+            let request_id = self.allocate_request_id();
+            if let Some(receiver) = &subscription.receiver {
+                self.state
+                    .borrow_mut()
+                    .register_track_receiver(request_id, receiver.clone());
+            }
+            let response = self
+                .state
+                .borrow_mut()
+                .expect_subscribe_response(request_id);
+            self.send_subscribe_message(Subscribe {
+                request_id,
+                ..subscription.subscribe.clone()
+            })
+            .await?;
+            let Ok(subscribe_ok) = await_response(response).await else {
+                continue;
+            };
+            set_bigint(&request_ids, subscription.subscribe.request_id, request_id);
+            if let Some(track_alias) = subscription.track_alias {
+                set_bigint(&track_aliases, track_alias, subscribe_ok.track_alias());
+            }
+        }
+        if !self.is_connected() {
+            return Err(js_error("session closed while it was being set up"));
+        }
+        Ok((request_ids, track_aliases))
+    }
+
+    fn notify_connection_closed(&self, close_code: u32, reason: &str) {
+        dispatch(&self.callbacks, ClientEvent::ConnectionClosed, None, || {
+            vec![JsValue::from(close_code), JsValue::from_str(reason)]
+        });
+    }
+}
+
+fn set_bigint(map: &js_sys::Map, key: u64, value: u64) {
+    map.set(
+        &js_sys::BigInt::from(key).into(),
+        &js_sys::BigInt::from(value).into(),
+    );
+}
+
+/// Resolves after `ms` through the global `setTimeout`, which windows and
+/// workers both have.
+async fn sleep(ms: u32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let global = js_sys::global();
+        let set_timeout = js_sys::Reflect::get(&global, &JsValue::from_str("setTimeout"))
+            .and_then(|set_timeout| set_timeout.dyn_into::<js_sys::Function>());
+        match set_timeout {
+            Ok(set_timeout) => {
+                let _ = set_timeout.call2(&global, &resolve, &JsValue::from(ms));
+            }
+            Err(_) => {
+                let _ = resolve.call0(&JsValue::NULL);
+            }
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use futures::{StreamExt, channel::mpsc};
+use wasm_bindgen::{JsCast, prelude::*};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    ReadableStreamDefaultReader, WebTransport, WebTransportCloseInfo, WritableStreamDefaultWriter,
+};
+
+use super::{ControlSend, MOQTClient, write_to_writer};
+use crate::{
+    TransportStats,
+    events::ClientEvent,
+    js_error,
+    receive::{control_stream_read_thread, datagram_read_thread, receive_unidirectional_thread},
+    stream_reader::StreamReader,
+};
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(js_name = onConnectionClosed)]
+    pub fn set_connection_closed_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "ConnectionClosedCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::ConnectionClosed, callback);
+    }
+
+    #[wasm_bindgen(js_name = onGoaway)]
+    pub fn set_goaway_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "GoawayCallback")] callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::Goaway, callback);
+    }
+
+    /// Called with the server's Maximum Request ID when a request is refused
+    /// because the session has run out of Request IDs.
+    #[wasm_bindgen(js_name = onRequestsBlocked)]
+    pub fn set_requests_blocked_callback(
+        &mut self,
+        #[wasm_bindgen(unchecked_param_type = "RequestsBlockedCallback")]
+        callback: js_sys::Function,
+    ) {
+        self.callbacks
+            .borrow_mut()
+            .set_handler(ClientEvent::RequestsBlocked, callback);
+    }
+
+    /// Opens the WebTransport session. `options` goes to the `WebTransport`
+    /// constructor as is, e.g. `serverCertificateHashes` to reach a relay
+    /// with a self-signed certificate, or a `congestionControl` hint.
+    pub async fn start(
+        &self,
+        #[wasm_bindgen(unchecked_optional_param_type = "WebTransportOptions")] options: JsValue,
+    ) -> Result<(), JsValue> {
+        self.reconnect.borrow_mut().transport_options = options.clone();
+        let transport = if options.is_undefined() || options.is_null() {
+            WebTransport::new(&self.url)?
+        } else if options.is_object() {
+            WebTransport::new_with_options(&self.url, options.unchecked_ref())?
+        } else {
+            return Err(js_error("WebTransport options must be an object"));
+        };
+        *self.transport.borrow_mut() = Some(transport.clone());
+
+        if let Err(error) = JsFuture::from(transport.ready()).await {
+            self.transport.borrow_mut().take();
+            return Err(error);
+        }
+
+        if let Err(error) = self.setup_transport(&transport).await {
+            self.transport.borrow_mut().take();
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Closes the session. `closeCode` and `reason` are sent to the peer as
+    /// the application close (`WebTransport.close({closeCode, reason})`);
+    /// without them the session closes with code 0 and an empty reason.
+    #[wasm_bindgen(js_name = close)]
+    pub async fn close(
+        &self,
+        #[wasm_bindgen(js_name = closeCode)] close_code: Option<u32>,
+        reason: Option<String>,
+    ) -> Result<(), JsValue> {
+        // Explicit close is driven by the JS wrapper, so suppress the async
+        // `onConnectionClosed` callback path to avoid re-entrant cleanup,
+        // and do not reconnect.
+        self.callbacks
+            .borrow_mut()
+            .clear(ClientEvent::ConnectionClosed);
+        self.reconnect.borrow_mut().options = None;
+        self.close_transport(close_code, reason).await;
+        self.state.borrow().close_track_streams();
+        Ok(())
+    }
+
+    /// Closes the current transport, if any, and drops what belonged to
+    /// it. Requests awaiting a response are rejected.
+    pub(super) async fn close_transport(&self, close_code: Option<u32>, reason: Option<String>) {
+        let transport = self.transport.borrow().clone();
+        if let Some(transport) = transport {
+            let closed = webtransport_closed_promise(&transport);
+            let close_info = WebTransportCloseInfo::new();
+            close_info.set_close_code(close_code.unwrap_or(0));
+            close_info.set_reason(reason.as_deref().unwrap_or(""));
+            transport.close_with_close_info(&close_info);
+            if let Some(closed) = closed {
+                let _ = JsFuture::from(closed).await;
+            }
+        }
+        self.transport.borrow_mut().take();
+        self.control_queue.borrow_mut().take();
+        self.datagram_writer.borrow_mut().take();
+        self.stream_writers.borrow_mut().clear();
+        self.stream_object_numbers.borrow_mut().clear();
+        self.send_buffered_bytes.borrow_mut().clear();
+        self.state.borrow_mut().cancel_pending_responses();
+    }
+
+    /// The browser's WebTransport statistics plus this client's object
+    /// counters per track and its open subgroup streams, for a debug view.
+    #[wasm_bindgen(js_name = getTransportStats)]
+    pub async fn get_transport_stats(&self) -> Result<TransportStats, JsValue> {
+        let transport = self.transport.borrow().clone();
+        let transport_stats =
+            match transport.and_then(|transport| webtransport_stats_promise(&transport)) {
+                Some(stats) => JsFuture::from(stats).await?,
+                None => JsValue::UNDEFINED,
+            };
+        let open_subgroup_streams = self.stream_writers.borrow().len();
+        Ok(TransportStats::new(
+            transport_stats,
+            open_subgroup_streams,
+            &self.state.borrow().traffic,
+        ))
+    }
+
+    async fn setup_transport(&self, transport: &WebTransport) -> Result<(), JsValue> {
+        if let Some(closed) = webtransport_closed_promise(transport) {
+            let client = self.clone();
+            let transport = transport.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let (close_code, reason) = connection_close_info(JsFuture::from(closed).await);
+                client.transport_closed(transport, close_code, reason).await;
+            });
+        }
+
+        let control_stream = JsFuture::from(transport.create_bidirectional_stream()).await?;
+        let mut control_reader = StreamReader::new(&control_stream.readable().into())?;
+        let control_writer = control_stream.writable().get_writer()?;
+        let (control_queue, queued) = mpsc::unbounded();
+        *self.control_queue.borrow_mut() = Some(control_queue);
+        wasm_bindgen_futures::spawn_local(control_stream_write_thread(control_writer, queued));
+
+        let datagram_writer = transport.datagrams().writable().get_writer()?;
+        *self.datagram_writer.borrow_mut() = Some(datagram_writer);
+
+        let callbacks = self.callbacks.clone();
+        let state = self.state.clone();
+        let stream_writers = self.stream_writers.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ =
+                control_stream_read_thread(callbacks, state, stream_writers, &mut control_reader)
+                    .await;
+        });
+
+        let datagram_reader = ReadableStreamDefaultReader::new(&transport.datagrams().readable())?;
+        let callbacks = self.callbacks.clone();
+        let state = self.state.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = datagram_read_thread(callbacks, state, &datagram_reader).await;
+        });
+
+        let incoming_uni_streams = transport.incoming_unidirectional_streams();
+        let incoming_uni_streams_reader = ReadableStreamDefaultReader::new(&incoming_uni_streams)?;
+        let callbacks = self.callbacks.clone();
+        let state = self.state.clone();
+        *self.stream_writers.borrow_mut() = HashMap::new();
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ =
+                receive_unidirectional_thread(callbacks, state, &incoming_uni_streams_reader).await;
+        });
+
+        Ok(())
+    }
+}
+
+/// Writes queued control messages one at a time, each as a single chunk.
+async fn control_stream_write_thread(
+    writer: WritableStreamDefaultWriter,
+    mut queued: mpsc::UnboundedReceiver<ControlSend>,
+) {
+    while let Some((bytes, written)) = queued.next().await {
+        let _ = written.send(write_to_writer(&writer, &bytes).await);
+    }
+}
+
+/// `transport.getStats()`, which not every browser implements.
+fn webtransport_stats_promise(transport: &WebTransport) -> Option<js_sys::Promise> {
+    let get_stats = js_sys::Reflect::get(transport.as_ref(), &JsValue::from_str("getStats"))
+        .ok()?
+        .dyn_into::<js_sys::Function>()
+        .ok()?;
+    get_stats
+        .call0(transport.as_ref())
+        .ok()?
+        .dyn_into::<js_sys::Promise>()
+        .ok()
+}
+
+/// Close code and reason of a session from what `WebTransport.closed`
+/// settled with: the close info of whichever side closed it, or code 0 and
+/// the error message when the session was lost without a close.
+fn connection_close_info(closed: Result<JsValue, JsValue>) -> (u32, String) {
+    match closed {
+        Ok(info) => {
+            let info = info.unchecked_into::<WebTransportCloseInfo>();
+            (
+                info.get_close_code().unwrap_or(0),
+                info.get_reason().unwrap_or_default(),
+            )
+        }
+        Err(error) => {
+            let reason = error
+                .dyn_ref::<js_sys::Error>()
+                .map(|error| String::from(error.message()))
+                .unwrap_or_else(|| format!("{error:?}"));
+            (0, reason)
+        }
+    }
+}
+
+fn webtransport_closed_promise(transport: &WebTransport) -> Option<js_sys::Promise> {
+    js_sys::Reflect::get(transport.as_ref(), &JsValue::from_str("closed"))
+        .ok()
+        .and_then(|value| value.dyn_into::<js_sys::Promise>().ok())
+}
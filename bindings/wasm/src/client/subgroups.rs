@@ -0,0 +1,257 @@
+use anyhow::{Result, anyhow};
+use bytes::Bytes;
+use moqt::wire::{ObjectStatus, SubgroupHeader, SubgroupId, SubgroupObject, SubgroupObjectField};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::WritableStreamDefaultWriter;
+
+use super::{MOQTClient, empty_extension_headers, write_to_writer};
+use crate::{SubgroupState, js_error};
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(js_name = sendSubgroupHeader)]
+    pub async fn send_subgroup_header(
+        &self,
+        track_alias: u64,
+        group_id: u64,
+        subgroup_id: u64,
+        publisher_priority: u8,
+    ) -> Result<(), JsValue> {
+        if !self.state.borrow().track_aliases.is_in_use(track_alias) {
+            return Err(js_error(format!(
+                "track alias {track_alias} has no active publication"
+            )));
+        }
+        let writer = self
+            .get_or_create_stream_writer(track_alias, group_id, subgroup_id)
+            .await
+            .map_err(|error| js_error(error.to_string()))?;
+        let header = SubgroupHeader::new(
+            track_alias,
+            group_id,
+            SubgroupId::Value(subgroup_id),
+            publisher_priority,
+            true,
+            true,
+        )
+        .encode();
+        self.write_track_bytes(track_alias, &writer, &header).await
+    }
+
+    #[wasm_bindgen(js_name = sendSubgroupObject)]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_subgroup_object(
+        &self,
+        track_alias: u64,
+        group_id: u64,
+        subgroup_id: u64,
+        object_number: u64,
+        object_status: Option<u8>,
+        object_payload: Vec<u8>,
+        #[wasm_bindgen(unchecked_optional_param_type = "LocHeader | null")] loc_header: JsValue,
+    ) -> Result<(), JsValue> {
+        let writer_key = (track_alias, group_id, subgroup_id);
+        let writer = self
+            .stream_writers
+            .borrow()
+            .get(&writer_key)
+            .cloned()
+            .ok_or_else(|| js_error("subgroup writer is None"))?;
+
+        let extension_headers = match crate::loc::parse_loc_header(loc_header)
+            .map_err(|error| js_error(error.to_string()))?
+        {
+            Some(header) => crate::loc::loc_header_to_extension_headers(&header),
+            None => empty_extension_headers(),
+        };
+        let object_id_delta = {
+            let stream_object_numbers = self.stream_object_numbers.borrow();
+            match stream_object_numbers.get(&writer_key).copied() {
+                Some(previous_object_number) => previous_object_number
+                    .checked_add(1)
+                    .and_then(|next_object_number| object_number.checked_sub(next_object_number)),
+                None => Some(object_number),
+            }
+            .ok_or_else(|| {
+                js_error("object number must increase monotonically within a subgroup stream")
+            })?
+        };
+        let header = SubgroupHeader::new(
+            track_alias,
+            group_id,
+            SubgroupId::Value(subgroup_id),
+            0,
+            true,
+            true,
+        );
+
+        let payload_len = object_payload.len();
+        let subgroup_object = match object_status {
+            Some(status) => SubgroupObject::new_status(
+                ObjectStatus::try_from(status).map_err(|_| js_error("invalid object status"))?
+                    as u64,
+            ),
+            None => SubgroupObject::new_payload(Bytes::from(object_payload)),
+        };
+
+        let bytes = SubgroupObjectField {
+            message_type: header.message_type,
+            object_id_delta,
+            extension_headers,
+            subgroup_object,
+        }
+        .encode();
+
+        self.write_track_bytes(track_alias, &writer, &bytes).await?;
+        self.stream_object_numbers
+            .borrow_mut()
+            .insert(writer_key, object_number);
+        self.state
+            .borrow_mut()
+            .traffic
+            .record_sent(track_alias, payload_len);
+
+        if matches!(
+            object_status.and_then(|status| ObjectStatus::try_from(status).ok()),
+            Some(ObjectStatus::EndOfGroup | ObjectStatus::EndOfTrack)
+        ) {
+            let _ = JsFuture::from(writer.close()).await;
+            self.stream_writers.borrow_mut().remove(&writer_key);
+            self.stream_object_numbers.borrow_mut().remove(&writer_key);
+        }
+
+        Ok(())
+    }
+
+    /// Finishes a subgroup stream opened with `sendSubgroupHeader`: writes
+    /// END_OF_GROUP after the last object sent on it, closes the stream
+    /// (FIN) and drops its writer. A stream that is not open, e.g. one
+    /// already ended by an END_OF_GROUP / END_OF_TRACK object, is ignored.
+    #[wasm_bindgen(js_name = closeSubgroupStream)]
+    pub async fn close_subgroup_stream(
+        &self,
+        track_alias: u64,
+        group_id: u64,
+        subgroup_id: u64,
+    ) -> Result<(), JsValue> {
+        let writer_key = (track_alias, group_id, subgroup_id);
+        if !self.stream_writers.borrow().contains_key(&writer_key) {
+            return Ok(());
+        }
+        let object_number = self
+            .stream_object_numbers
+            .borrow()
+            .get(&writer_key)
+            .map_or(0, |object_number| object_number + 1);
+        let result = self
+            .send_subgroup_object(
+                track_alias,
+                group_id,
+                subgroup_id,
+                object_number,
+                Some(ObjectStatus::EndOfGroup as u8),
+                Vec::new(),
+                JsValue::UNDEFINED,
+            )
+            .await;
+        if result.is_err() {
+            // The stream is unusable either way; do not keep its writer.
+            self.stream_writers.borrow_mut().remove(&writer_key);
+            self.stream_object_numbers.borrow_mut().remove(&writer_key);
+        }
+        result
+    }
+
+    #[wasm_bindgen(js_name = getSubgroupState)]
+    pub fn get_subgroup_state(&self, track_alias: u64) -> SubgroupState {
+        self.state.borrow_mut().current_subgroup_state(track_alias)
+    }
+
+    #[wasm_bindgen(js_name = markSubgroupHeaderSent)]
+    pub fn mark_subgroup_header_sent(&self, track_alias: u64) {
+        self.state
+            .borrow_mut()
+            .mark_subgroup_header_sent(track_alias);
+    }
+
+    #[wasm_bindgen(js_name = incrementSubgroupObject)]
+    pub fn increment_subgroup_object(&self, track_alias: u64) {
+        self.state
+            .borrow_mut()
+            .increment_subgroup_object(track_alias);
+    }
+
+    #[wasm_bindgen(js_name = resetSubgroupState)]
+    pub fn reset_subgroup_state(&self, track_alias: u64) {
+        self.state.borrow_mut().reset_subgroup_state(track_alias);
+    }
+
+    /// Bytes of `trackAlias`'s subgroup stream writes that the transport
+    /// has not accepted yet. Grows when the connection is congested, so an
+    /// encoder can drop or downscale frames instead of queueing them.
+    #[wasm_bindgen(js_name = getSendBufferedAmount)]
+    pub fn get_send_buffered_amount(&self, track_alias: u64) -> usize {
+        self.send_buffered_bytes
+            .borrow()
+            .get(&track_alias)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Writes to one of `track_alias`'s subgroup streams, counting the bytes
+    /// in `getSendBufferedAmount` until the write settles.
+    async fn write_track_bytes(
+        &self,
+        track_alias: u64,
+        writer: &WritableStreamDefaultWriter,
+        bytes: &[u8],
+    ) -> Result<(), JsValue> {
+        *self
+            .send_buffered_bytes
+            .borrow_mut()
+            .entry(track_alias)
+            .or_default() += bytes.len();
+        let result = write_to_writer(writer, bytes).await;
+        let mut send_buffered_bytes = self.send_buffered_bytes.borrow_mut();
+        if let Some(buffered) = send_buffered_bytes.get_mut(&track_alias) {
+            *buffered = buffered.saturating_sub(bytes.len());
+            if *buffered == 0 {
+                send_buffered_bytes.remove(&track_alias);
+            }
+        }
+        result
+    }
+
+    async fn get_or_create_stream_writer(
+        &self,
+        track_alias: u64,
+        group_id: u64,
+        subgroup_id: u64,
+    ) -> Result<WritableStreamDefaultWriter> {
+        let writer_key = (track_alias, group_id, subgroup_id);
+        if let Some(writer) = self.stream_writers.borrow().get(&writer_key).cloned() {
+            return Ok(writer);
+        }
+
+        let transport = self
+            .transport
+            .borrow()
+            .clone()
+            .ok_or_else(|| anyhow!("transport is None"))?;
+        let writable = web_sys::WritableStream::from(
+            JsFuture::from(transport.create_unidirectional_stream())
+                .await
+                .map_err(|error| anyhow!("create_unidirectional_stream: {error:?}"))?,
+        );
+        let writer = writable
+            .get_writer()
+            .map_err(|error| anyhow!("get_writer: {error:?}"))?;
+        self.stream_object_numbers.borrow_mut().remove(&writer_key);
+        self.stream_writers
+            .borrow_mut()
+            .insert(writer_key, writer.clone());
+        self.state.borrow_mut().count_subgroup_stream(track_alias);
+        Ok(writer)
+    }
+}
@@ -0,0 +1,270 @@
+use std::rc::Rc;
+
+use bytes::BytesMut;
+use moqt::wire::{
+    BufPutExt, ControlMessageType, FilterType, GroupOrder, Location, Subscribe, SubscribeUpdate,
+};
+use wasm_bindgen::prelude::*;
+use web_sys::ReadableStream;
+
+use super::{MOQTClient, authorization_tokens, await_response};
+use crate::{
+    SubscribeHandle, SubscribeOkMessage, SubscriptionInfo, js_error,
+    subscribe_track::{SubscribeOptions, TrackReceiver},
+};
+
+#[wasm_bindgen]
+impl MOQTClient {
+    #[wasm_bindgen(js_name = sendSubscribe)]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_subscribe(
+        &self,
+        request_id: u64,
+        track_namespace: Vec<String>,
+        track_name: String,
+        subscriber_priority: u8,
+        group_order: u8,
+        filter_type: u8,
+        start_group: Option<u64>,
+        start_object: Option<u64>,
+        end_group: Option<u64>,
+        auth_info: String,
+        forward: bool,
+        delivery_timeout: Option<u64>,
+        max_cache_duration: Option<u64>,
+    ) -> Result<(), JsValue> {
+        let group_order =
+            GroupOrder::try_from(group_order).map_err(|_| js_error("invalid group order"))?;
+        let filter_type =
+            filter_type_from_fields(filter_type, start_group, start_object, end_group)?;
+        self.send_subscribe_message(Subscribe {
+            request_id,
+            track_namespace,
+            track_name,
+            subscriber_priority,
+            group_order,
+            forward,
+            filter_type,
+            authorization_tokens: authorization_tokens(&auth_info),
+            delivery_timeout,
+            max_cache_duration,
+        })
+        .await
+    }
+
+    /// `sendSubscribe` that resolves with SUBSCRIBE_OK and rejects with the
+    /// `RequestErrorMessage` of SUBSCRIBE_ERROR. `onSubscribeResponse`
+    /// still fires. Without `requestId` in `options` the client allocates
+    /// one; SUBSCRIBE_OK carries it along with the Track Alias the publisher
+    /// assigned (draft-14 leaves the alias to the publisher). See
+    /// `SubscribeOptions` for `options`.
+    #[wasm_bindgen(js_name = subscribe)]
+    pub async fn subscribe(
+        &self,
+        track_namespace: Vec<String>,
+        track_name: String,
+        #[wasm_bindgen(unchecked_optional_param_type = "SubscribeOptions")] options: JsValue,
+    ) -> Result<SubscribeOkMessage, JsValue> {
+        let options = SubscribeOptions::parse(options)?;
+        let request_id = options
+            .request_id
+            .unwrap_or_else(|| self.allocate_request_id());
+        let response = self
+            .state
+            .borrow_mut()
+            .expect_subscribe_response(request_id);
+        if let Err(error) = self
+            .send_subscribe_with_options(request_id, track_namespace, track_name, options)
+            .await
+        {
+            self.state
+                .borrow_mut()
+                .pending_subscribes
+                .remove(&request_id);
+            return Err(error);
+        }
+        await_response(response).await
+    }
+
+    /// Subscribes to a track and returns a handle that receives its objects
+    /// on per-track callbacks. Like `subscribe`, the client allocates the
+    /// Request ID unless `options` gives one. See `SubscribeOptions` for
+    /// `options`.
+    #[wasm_bindgen(js_name = subscribeTrack)]
+    pub async fn subscribe_track(
+        &self,
+        track_namespace: Vec<String>,
+        track_name: String,
+        #[wasm_bindgen(unchecked_optional_param_type = "SubscribeOptions")] options: JsValue,
+    ) -> Result<SubscribeHandle, JsValue> {
+        let options = SubscribeOptions::parse(options)?;
+        let request_id = options
+            .request_id
+            .unwrap_or_else(|| self.allocate_request_id());
+        let receiver = Rc::new(TrackReceiver::default());
+        let handle = SubscribeHandle::new(
+            self.clone(),
+            track_namespace.clone(),
+            track_name.clone(),
+            receiver.clone(),
+            &options,
+        );
+        self.state
+            .borrow_mut()
+            .register_track_receiver(request_id, receiver);
+        let result = self
+            .send_subscribe_with_options(request_id, track_namespace, track_name, options)
+            .await;
+        if let Err(error) = result {
+            self.state.borrow_mut().take_track_receiver(request_id);
+            return Err(error);
+        }
+        Ok(handle)
+    }
+
+    /// The objects of the active subscription delivering `trackAlias` as a
+    /// `ReadableStream`, for `pipeThrough` into `TransformStream`s
+    /// (decryptors, depacketizers). From then on they no longer reach the
+    /// object callbacks. The stream closes on PUBLISH_DONE with TRACK_ENDED /
+    /// SUBSCRIPTION_ENDED, on unsubscribe and when the session ends, errors
+    /// on any other PUBLISH_DONE, and cancelling it sends UNSUBSCRIBE.
+    #[wasm_bindgen(
+        js_name = subscribeAsStream,
+        unchecked_return_type = "ReadableStream<TrackObjectMessage>"
+    )]
+    pub fn subscribe_as_stream(&self, track_alias: u64) -> Result<ReadableStream, JsValue> {
+        let receiver = self
+            .state
+            .borrow_mut()
+            .stream_track_receiver(track_alias)
+            .ok_or_else(|| {
+                js_error(format!(
+                    "no active subscription delivers track alias {track_alias}"
+                ))
+            })?;
+        receiver.open_stream(self.clone())
+    }
+
+    /// Updates the subscription created by `subscription_request_id` in
+    /// place. `end_group` is the last Group ID to deliver; omit it to keep
+    /// the subscription open-ended.
+    #[wasm_bindgen(js_name = sendSubscribeUpdateMessage)]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_subscribe_update_message(
+        &self,
+        request_id: u64,
+        subscription_request_id: u64,
+        start_group: u64,
+        start_object: u64,
+        end_group: Option<u64>,
+        subscriber_priority: u8,
+        forward: bool,
+    ) -> Result<(), JsValue> {
+        self.ensure_request_allowed(request_id).await?;
+        let payload = SubscribeUpdate {
+            request_id,
+            subscription_request_id,
+            start_location: Location {
+                group_id: start_group,
+                object_id: start_object,
+            },
+            end_group: end_group.map_or(0, |end_group| end_group + 1),
+            subscriber_priority,
+            forward,
+            delivery_timeout: None,
+        }
+        .encode();
+        self.send_control_message(ControlMessageType::SubscribeUpdate, payload)
+            .await
+    }
+
+    #[wasm_bindgen(js_name = sendUnsubscribe)]
+    pub async fn send_unsubscribe(&self, request_id: u64) -> Result<(), JsValue> {
+        let mut payload = BytesMut::new();
+        payload.put_varint(request_id);
+        self.send_control_message(ControlMessageType::UnSubscribe, payload)
+            .await?;
+        let receiver = self.state.borrow_mut().take_track_receiver(request_id);
+        if let Some(receiver) = receiver {
+            receiver.close_stream();
+        }
+        self.state
+            .borrow_mut()
+            .remove_outgoing_subscription(request_id);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = isSubscribed)]
+    pub fn is_subscribed(&self, request_id: u64) -> bool {
+        self.state.borrow().is_subscribed(request_id)
+    }
+
+    /// Every subscription of the session: SUBSCRIBEs this client sent and
+    /// has not ended, then those it received as publisher.
+    #[wasm_bindgen(js_name = getSubscriptions)]
+    pub fn get_subscriptions(&self) -> Vec<SubscriptionInfo> {
+        self.state.borrow().subscriptions()
+    }
+
+    /// Sends `subscribe` and records it as an outgoing subscription.
+    pub(super) async fn send_subscribe_message(&self, subscribe: Subscribe) -> Result<(), JsValue> {
+        self.ensure_request_allowed(subscribe.request_id).await?;
+        let payload = subscribe.encode();
+        self.state
+            .borrow_mut()
+            .start_outgoing_subscription(subscribe);
+        self.send_control_message(ControlMessageType::Subscribe, payload)
+            .await
+    }
+
+    async fn send_subscribe_with_options(
+        &self,
+        request_id: u64,
+        track_namespace: Vec<String>,
+        track_name: String,
+        options: SubscribeOptions,
+    ) -> Result<(), JsValue> {
+        self.send_subscribe(
+            request_id,
+            track_namespace,
+            track_name,
+            options.subscriber_priority,
+            options.group_order,
+            options.filter_type,
+            options.start_group,
+            options.start_object,
+            options.end_group,
+            options.auth_info,
+            options.forward,
+            options.delivery_timeout,
+            options.max_cache_duration,
+        )
+        .await
+    }
+}
+
+pub(super) fn filter_type_from_fields(
+    filter_type: u8,
+    start_group: Option<u64>,
+    start_object: Option<u64>,
+    end_group: Option<u64>,
+) -> Result<FilterType, JsValue> {
+    match filter_type {
+        1 => Ok(FilterType::NextGroupStart),
+        2 => Ok(FilterType::LargestObject),
+        3 => Ok(FilterType::AbsoluteStart {
+            location: Location {
+                group_id: start_group.unwrap_or(0),
+                object_id: start_object.unwrap_or(0),
+            },
+        }),
+        4 => Ok(FilterType::AbsoluteRange {
+            location: Location {
+                group_id: start_group.unwrap_or(0),
+                object_id: start_object.unwrap_or(0),
+            },
+            end_group: end_group.unwrap_or(0),
+        }),
+        _ => Err(js_error("invalid filter type")),
+    }
+}
@@ -0,0 +1,237 @@
+mod namespaces;
+mod replay;
+mod subscriptions;
+
+pub(crate) use replay::SessionReplay;
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
+
+use anyhow::{Result, anyhow};
+use futures::channel::oneshot;
+use moqt::{
+    TrackAliasAllocator,
+    wire::{GroupOrder, Subscribe},
+};
+use wasm_bindgen::JsValue;
+
+use crate::{
+    NamespaceOkMessage, RequestErrorMessage, SubgroupState, SubscribeOkMessage, js_error,
+    namespace_registry::NamespaceRegistry, subscribe_track::TrackReceiver,
+    transport_stats::TrafficCounters,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct TrackKey {
+    namespace: Vec<String>,
+    name: String,
+}
+
+impl TrackKey {
+    pub(crate) fn new(namespace: Vec<String>, name: String) -> Self {
+        Self { namespace, name }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OutgoingSubscribeRequest {
+    track_key: TrackKey,
+    track_alias: Option<u64>,
+    /// The SUBSCRIBE as sent, re-issued when the session reconnects.
+    subscribe: Subscribe,
+}
+
+#[derive(Debug, Clone)]
+struct IncomingSubscribeRequest {
+    track_key: TrackKey,
+    group_order: GroupOrder,
+    track_alias: Option<u64>,
+    /// Subgroup streams opened for the subscription: the Stream Count of
+    /// its PUBLISH_DONE.
+    streams_opened: u64,
+}
+
+/// Completes the Promise of a request awaiting its *_OK / *_ERROR.
+pub(crate) type ResponseSender<T> = oneshot::Sender<Result<T, RequestErrorMessage>>;
+
+pub(crate) type ResponseReceiver<T> = oneshot::Receiver<Result<T, RequestErrorMessage>>;
+
+#[derive(Debug, Default)]
+pub(crate) struct ClientState {
+    pub(crate) max_request_id: u64,
+    /// Smallest client Request ID (even, draft-14 §9.1) not yet used by a
+    /// request of this session; see `allocate_request_id`.
+    next_request_id: u64,
+    /// Namespaces announced with PUBLISH_NAMESPACE, to the authorization
+    /// info they were announced with.
+    pub(crate) published_namespaces: HashMap<Vec<String>, String>,
+    /// Prefixes of SUBSCRIBE_NAMESPACE, to their authorization info.
+    pub(crate) subscribed_namespace_prefixes: HashMap<Vec<String>, String>,
+    publish_namespace_requests: HashMap<u64, Vec<String>>,
+    /// Namespaces the peer announced with PUBLISH_NAMESPACE and has not
+    /// withdrawn, to the Request ID of that announcement.
+    peer_published_namespaces: HashMap<Vec<String>, u64>,
+    subscribe_namespace_requests: HashMap<u64, Vec<String>>,
+    namespace_registry: NamespaceRegistry,
+    publish_requests: HashMap<u64, (TrackKey, u64)>,
+    outgoing_subscriptions: HashMap<u64, OutgoingSubscribeRequest>,
+    /// Receivers of subscriptions made through `subscribeTrack`, by
+    /// Request ID.
+    track_receivers: HashMap<u64, Rc<TrackReceiver>>,
+    incoming_subscriptions: HashMap<u64, IncomingSubscribeRequest>,
+    publishing_track_aliases: HashMap<TrackKey, BTreeSet<u64>>,
+    alias_to_track_key: HashMap<u64, TrackKey>,
+    subgroup_states: HashMap<u64, SubgroupState>,
+    pub(crate) track_aliases: TrackAliasAllocator,
+    /// NEW_SESSION_URI from a received GOAWAY; once set, no new requests are
+    /// issued on this session.
+    pub(crate) going_away: Option<String>,
+    /// Maximum Request ID for which REQUESTS_BLOCKED was already sent.
+    requests_blocked_at: Option<u64>,
+    /// `subscribe` calls awaiting SUBSCRIBE_OK / SUBSCRIBE_ERROR, by
+    /// Request ID.
+    pub(crate) pending_subscribes: HashMap<u64, ResponseSender<SubscribeOkMessage>>,
+    /// `publishNamespace` / `subscribeNamespace` calls awaiting their
+    /// response, by Request ID.
+    pub(crate) pending_namespace_requests: HashMap<u64, ResponseSender<NamespaceOkMessage>>,
+    /// Waits for SERVER_SETUP while a reconnect replays CLIENT_SETUP.
+    pending_server_setup: Option<oneshot::Sender<()>>,
+    /// Objects sent and received per track alias, for `getTransportStats`.
+    pub(crate) traffic: TrafficCounters,
+}
+
+impl ClientState {
+    pub(crate) fn configure(&mut self, max_request_id: u64) {
+        self.max_request_id = max_request_id;
+    }
+
+    /// Applies MAX_REQUEST_ID from the server; the limit never shrinks.
+    pub(crate) fn raise_max_request_id(&mut self, max_request_id: u64) {
+        self.max_request_id = self.max_request_id.max(max_request_id);
+    }
+
+    /// Hands out the next unused client Request ID.
+    pub(crate) fn allocate_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 2;
+        request_id
+    }
+
+    /// Keeps `allocate_request_id` past ids the application chose itself.
+    pub(crate) fn note_request_id(&mut self, request_id: u64) {
+        self.next_request_id = self.next_request_id.max((request_id | 1) + 1);
+    }
+
+    /// Returns true the first time a request is blocked at the current limit,
+    /// i.e. when REQUESTS_BLOCKED should be sent.
+    pub(crate) fn block_requests(&mut self) -> bool {
+        self.requests_blocked_at.replace(self.max_request_id) != Some(self.max_request_id)
+    }
+
+    pub(crate) fn ensure_not_going_away(&self) -> Result<(), JsValue> {
+        match self.going_away {
+            Some(_) => Err(js_error(
+                "session received GOAWAY; new requests are not allowed",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn claim_publish_track_alias(&mut self, track_alias: Option<u64>) -> Result<u64> {
+        match track_alias {
+            Some(track_alias) if self.track_aliases.reserve(track_alias) => Ok(track_alias),
+            Some(track_alias) => Err(anyhow!("track alias {track_alias} is already in use")),
+            None => Ok(self.track_aliases.allocate()),
+        }
+    }
+
+    /// Alias for a `PublishTrackHandle`: one this client already handed out,
+    /// or a fresh one the handle owns (`true`).
+    pub(crate) fn claim_track_handle_alias(
+        &mut self,
+        track_alias: Option<u64>,
+    ) -> Result<(u64, bool)> {
+        match track_alias {
+            Some(track_alias) if self.track_aliases.is_in_use(track_alias) => {
+                Ok((track_alias, false))
+            }
+            Some(track_alias) => Err(anyhow!(
+                "track alias {track_alias} was not handed out by this client"
+            )),
+            None => Ok((self.track_aliases.allocate(), true)),
+        }
+    }
+
+    pub(crate) fn release_track_alias(&mut self, track_alias: u64) {
+        self.track_aliases.release(track_alias);
+    }
+
+    pub(crate) fn expect_subscribe_response(
+        &mut self,
+        request_id: u64,
+    ) -> ResponseReceiver<SubscribeOkMessage> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending_subscribes.insert(request_id, sender);
+        receiver
+    }
+
+    pub(crate) fn settle_subscribe(
+        &mut self,
+        request_id: u64,
+        response: Result<SubscribeOkMessage, RequestErrorMessage>,
+    ) {
+        if let Some(sender) = self.pending_subscribes.remove(&request_id) {
+            let _ = sender.send(response);
+        }
+    }
+
+    pub(crate) fn expect_namespace_response(
+        &mut self,
+        request_id: u64,
+    ) -> ResponseReceiver<NamespaceOkMessage> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending_namespace_requests.insert(request_id, sender);
+        receiver
+    }
+
+    pub(crate) fn settle_namespace_request(
+        &mut self,
+        request_id: u64,
+        response: Result<NamespaceOkMessage, RequestErrorMessage>,
+    ) {
+        if let Some(sender) = self.pending_namespace_requests.remove(&request_id) {
+            let _ = sender.send(response);
+        }
+    }
+
+    /// Rejects every request still waiting for its response.
+    pub(crate) fn cancel_pending_responses(&mut self) {
+        self.pending_subscribes.clear();
+        self.pending_namespace_requests.clear();
+        self.pending_server_setup = None;
+    }
+
+    pub(crate) fn subgroup_state_entry(&mut self, track_alias: u64) -> &mut SubgroupState {
+        self.subgroup_states
+            .entry(track_alias)
+            .or_insert_with(|| SubgroupState::with_track(track_alias))
+    }
+
+    pub(crate) fn current_subgroup_state(&mut self, track_alias: u64) -> SubgroupState {
+        self.subgroup_state_entry(track_alias).clone()
+    }
+
+    pub(crate) fn mark_subgroup_header_sent(&mut self, track_alias: u64) {
+        self.subgroup_state_entry(track_alias).mark_header_sent();
+    }
+
+    pub(crate) fn increment_subgroup_object(&mut self, track_alias: u64) {
+        self.subgroup_state_entry(track_alias).increment_object_id();
+    }
+
+    pub(crate) fn reset_subgroup_state(&mut self, track_alias: u64) {
+        self.subgroup_states.remove(&track_alias);
+    }
+}
@@ -0,0 +1,114 @@
+use super::ClientState;
+use crate::ReceivedAnnouncement;
+
+impl ClientState {
+    pub(crate) fn contains_published_namespace(&self, namespace: &[String]) -> bool {
+        self.published_namespaces.contains_key(namespace)
+    }
+
+    pub(crate) fn register_publish_namespace_request(
+        &mut self,
+        request_id: u64,
+        namespace: Vec<String>,
+        auth_info: String,
+    ) {
+        self.published_namespaces
+            .insert(namespace.clone(), auth_info);
+        self.publish_namespace_requests
+            .insert(request_id, namespace);
+    }
+
+    pub(crate) fn finish_publish_namespace_request(&mut self, request_id: u64, success: bool) {
+        if let Some(namespace) = self.publish_namespace_requests.remove(&request_id)
+            && !success
+        {
+            self.published_namespaces.remove(&namespace);
+        }
+    }
+
+    pub(crate) fn register_subscribe_namespace_request(
+        &mut self,
+        request_id: u64,
+        namespace_prefix: Vec<String>,
+        auth_info: String,
+    ) {
+        self.subscribed_namespace_prefixes
+            .insert(namespace_prefix.clone(), auth_info);
+        self.namespace_registry
+            .track_prefix(namespace_prefix.clone());
+        self.subscribe_namespace_requests
+            .insert(request_id, namespace_prefix);
+    }
+
+    pub(crate) fn finish_subscribe_namespace_request(&mut self, request_id: u64, success: bool) {
+        if let Some(namespace_prefix) = self.subscribe_namespace_requests.remove(&request_id)
+            && !success
+        {
+            self.subscribed_namespace_prefixes.remove(&namespace_prefix);
+            self.namespace_registry.untrack_prefix(&namespace_prefix);
+        }
+    }
+
+    /// Forgets a namespace prefix subscription; returns false when it was
+    /// never subscribed.
+    pub(crate) fn remove_subscribe_namespace(&mut self, namespace_prefix: &[String]) -> bool {
+        if self
+            .subscribed_namespace_prefixes
+            .remove(namespace_prefix)
+            .is_none()
+        {
+            return false;
+        }
+        self.namespace_registry.untrack_prefix(namespace_prefix);
+        true
+    }
+
+    /// Records a PUBLISH_NAMESPACE from the peer. For a namespace it already
+    /// announced, keeps the first announcement and returns its Request ID.
+    pub(crate) fn record_peer_namespace(
+        &mut self,
+        namespace: &[String],
+        request_id: u64,
+    ) -> Option<u64> {
+        match self.peer_published_namespaces.get(namespace) {
+            Some(&first_request_id) => Some(first_request_id),
+            None => {
+                self.peer_published_namespaces
+                    .insert(namespace.to_vec(), request_id);
+                None
+            }
+        }
+    }
+
+    /// Forgets a namespace the peer withdrew; the Request ID of its
+    /// announcement, or `None` when it never announced it.
+    pub(crate) fn withdraw_peer_namespace(&mut self, namespace: &[String]) -> Option<u64> {
+        self.peer_published_namespaces.remove(namespace)
+    }
+
+    /// Forgets the announcement `request_id`, rejected by this client.
+    pub(crate) fn reject_peer_namespace(&mut self, request_id: u64) {
+        self.peer_published_namespaces
+            .retain(|_, announced| *announced != request_id);
+    }
+
+    pub(crate) fn received_announcements(&self) -> Vec<ReceivedAnnouncement> {
+        let mut announcements: Vec<_> = self
+            .peer_published_namespaces
+            .iter()
+            .map(|(namespace, &request_id)| {
+                ReceivedAnnouncement::new(request_id, namespace.clone())
+            })
+            .collect();
+        announcements.sort_by_key(ReceivedAnnouncement::request_id);
+        announcements
+    }
+
+    pub(crate) fn add_announced_namespace(&mut self, namespace: &[String]) -> Vec<Vec<String>> {
+        self.namespace_registry.add_namespace(namespace)
+    }
+
+    pub(crate) fn remove_announced_namespace(&mut self, namespace: &[String]) -> Vec<Vec<String>> {
+        self.namespace_registry.remove_namespace(namespace)
+    }
+}
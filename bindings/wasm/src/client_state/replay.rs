@@ -0,0 +1,131 @@
+use std::{mem, rc::Rc};
+
+use futures::channel::oneshot;
+use moqt::wire::Subscribe;
+
+use super::ClientState;
+use crate::subscribe_track::TrackReceiver;
+
+/// What a session established, re-issued on the next one when the client
+/// reconnects.
+#[derive(Debug, Default)]
+pub(crate) struct SessionReplay {
+    /// Namespaces and their authorization info, in sorted order.
+    pub(crate) published_namespaces: Vec<(Vec<String>, String)>,
+    pub(crate) subscribed_namespace_prefixes: Vec<(Vec<String>, String)>,
+    /// Outgoing subscriptions by their old Request ID.
+    pub(crate) subscriptions: Vec<ReplayedSubscription>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ReplayedSubscription {
+    pub(crate) subscribe: Subscribe,
+    /// Alias from the old SUBSCRIBE_OK, if it had arrived.
+    pub(crate) track_alias: Option<u64>,
+    /// Set for a subscription made through `subscribeTrack` or streamed
+    /// with `subscribeAsStream`.
+    pub(crate) receiver: Option<Rc<TrackReceiver>>,
+}
+
+impl ClientState {
+    /// Forgets everything tied to the closed session and returns what to
+    /// replay on the next one. Aliases held by publish track handles and
+    /// the traffic counters survive.
+    pub(crate) fn detach_session(&mut self) -> SessionReplay {
+        let incoming: Vec<u64> = self.incoming_subscriptions.keys().copied().collect();
+        for request_id in incoming {
+            self.remove_incoming_subscribe(request_id);
+        }
+        let mut track_receivers = mem::take(&mut self.track_receivers);
+        let mut subscriptions: Vec<_> = self
+            .outgoing_subscriptions
+            .drain()
+            .map(|(request_id, subscription)| ReplayedSubscription {
+                subscribe: subscription.subscribe,
+                track_alias: subscription.track_alias,
+                receiver: track_receivers.remove(&request_id),
+            })
+            .collect();
+        subscriptions.sort_by_key(|subscription| subscription.subscribe.request_id);
+        let replay = SessionReplay {
+            published_namespaces: sorted(mem::take(&mut self.published_namespaces)),
+            subscribed_namespace_prefixes: sorted(mem::take(
+                &mut self.subscribed_namespace_prefixes,
+            )),
+            subscriptions,
+        };
+        *self = Self {
+            track_aliases: mem::take(&mut self.track_aliases),
+            traffic: mem::take(&mut self.traffic),
+            ..Self::default()
+        };
+        replay
+    }
+
+    pub(crate) fn expect_server_setup(&mut self) -> oneshot::Receiver<()> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending_server_setup = Some(sender);
+        receiver
+    }
+
+    pub(crate) fn settle_server_setup(&mut self) {
+        if let Some(sender) = self.pending_server_setup.take() {
+            let _ = sender.send(());
+        }
+    }
+}
+
+fn sorted(
+    namespaces: impl IntoIterator<Item = (Vec<String>, String)>,
+) -> Vec<(Vec<String>, String)> {
+    let mut namespaces: Vec<_> = namespaces.into_iter().collect();
+    namespaces.sort();
+    namespaces
+}
+
+#[cfg(test)]
+mod tests {
+    use moqt::wire::{FilterType, GroupOrder, Subscribe};
+
+    use super::ClientState;
+
+    fn subscribe(request_id: u64) -> Subscribe {
+        Subscribe {
+            request_id,
+            track_namespace: vec!["live".to_string()],
+            track_name: format!("track{request_id}"),
+            subscriber_priority: 0,
+            group_order: GroupOrder::Ascending,
+            forward: true,
+            filter_type: FilterType::LargestObject,
+            authorization_tokens: vec![],
+            delivery_timeout: None,
+            max_cache_duration: None,
+        }
+    }
+
+    #[test]
+    fn detach_session_hands_over_what_to_replay_and_resets_the_session() {
+        // Arrange
+        let mut state = ClientState::default();
+        state.register_publish_namespace_request(0, vec!["live".to_string()], "token".to_string());
+        let request_id = state.allocate_request_id();
+        state.start_outgoing_subscription(subscribe(request_id));
+        state.activate_outgoing_subscription(request_id, 7);
+        state.configure(100);
+        // Act
+        let replay = state.detach_session();
+        // Assert: the old session's requests move to the replay
+        assert_eq!(
+            replay.published_namespaces,
+            vec![(vec!["live".to_string()], "token".to_string())]
+        );
+        assert_eq!(replay.subscriptions.len(), 1);
+        assert_eq!(replay.subscriptions[0].subscribe, subscribe(request_id));
+        assert_eq!(replay.subscriptions[0].track_alias, Some(7));
+        assert!(!state.is_subscribed(request_id));
+        assert!(!state.contains_published_namespace(&["live".to_string()]));
+        assert_eq!(state.max_request_id, 0);
+        assert_eq!(state.allocate_request_id(), 0);
+    }
+}
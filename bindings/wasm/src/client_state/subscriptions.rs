@@ -0,0 +1,264 @@
+use std::rc::Rc;
+
+use anyhow::{Result, anyhow};
+use moqt::wire::{GroupOrder, Subscribe};
+
+use super::{ClientState, IncomingSubscribeRequest, OutgoingSubscribeRequest, TrackKey};
+use crate::{SubscriptionInfo, subscribe_track::TrackReceiver};
+
+impl ClientState {
+    /// Outgoing then incoming subscriptions, each by Request ID.
+    pub(crate) fn subscriptions(&self) -> Vec<SubscriptionInfo> {
+        let outgoing = self
+            .outgoing_subscriptions
+            .iter()
+            .map(|(&request_id, subscription)| {
+                (
+                    true,
+                    request_id,
+                    &subscription.track_key,
+                    subscription.track_alias,
+                )
+            });
+        let incoming = self
+            .incoming_subscriptions
+            .iter()
+            .map(|(&request_id, subscription)| {
+                (
+                    false,
+                    request_id,
+                    &subscription.track_key,
+                    subscription.track_alias,
+                )
+            });
+        let mut subscriptions: Vec<_> = outgoing.chain(incoming).collect();
+        subscriptions.sort_by_key(|&(outgoing, request_id, _, _)| (!outgoing, request_id));
+        subscriptions
+            .into_iter()
+            .map(|(outgoing, request_id, track_key, track_alias)| {
+                SubscriptionInfo::new(
+                    outgoing,
+                    request_id,
+                    track_key.namespace.clone(),
+                    track_key.name.clone(),
+                    track_alias,
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn register_publish_request(
+        &mut self,
+        request_id: u64,
+        track_key: TrackKey,
+        track_alias: u64,
+    ) {
+        self.publish_requests
+            .insert(request_id, (track_key, track_alias));
+    }
+
+    pub(crate) fn finish_publish_request(&mut self, request_id: u64, success: bool) {
+        if let Some((_, track_alias)) = self.publish_requests.remove(&request_id)
+            && !success
+        {
+            self.track_aliases.release(track_alias);
+        }
+    }
+
+    pub(crate) fn start_outgoing_subscription(&mut self, subscribe: Subscribe) {
+        self.outgoing_subscriptions.insert(
+            subscribe.request_id,
+            OutgoingSubscribeRequest {
+                track_key: TrackKey::new(
+                    subscribe.track_namespace.clone(),
+                    subscribe.track_name.clone(),
+                ),
+                track_alias: None,
+                subscribe,
+            },
+        );
+    }
+
+    pub(crate) fn activate_outgoing_subscription(&mut self, request_id: u64, track_alias: u64) {
+        if let Some(subscription) = self.outgoing_subscriptions.get_mut(&request_id) {
+            subscription.track_alias = Some(track_alias);
+            self.alias_to_track_key
+                .insert(track_alias, subscription.track_key.clone());
+        }
+    }
+
+    pub(crate) fn is_subscribed(&self, request_id: u64) -> bool {
+        self.outgoing_subscriptions.contains_key(&request_id)
+    }
+
+    pub(crate) fn outgoing_subscription_alias(&self, request_id: u64) -> Option<u64> {
+        self.outgoing_subscriptions
+            .get(&request_id)
+            .and_then(|subscription| subscription.track_alias)
+    }
+
+    pub(crate) fn register_track_receiver(&mut self, request_id: u64, receiver: Rc<TrackReceiver>) {
+        receiver.set_request_id(request_id);
+        self.track_receivers.insert(request_id, receiver);
+    }
+
+    /// Receiver of the active subscription delivering `track_alias`, if it
+    /// was made through `subscribeTrack`.
+    pub(crate) fn track_receiver(&self, track_alias: u64) -> Option<Rc<TrackReceiver>> {
+        self.outgoing_subscriptions
+            .iter()
+            .find(|(_, subscription)| subscription.track_alias == Some(track_alias))
+            .and_then(|(request_id, _)| self.track_receivers.get(request_id))
+            .cloned()
+    }
+
+    /// Receiver of the subscription delivering `track_alias`, registering
+    /// one for a subscription not made through `subscribeTrack`.
+    pub(crate) fn stream_track_receiver(&mut self, track_alias: u64) -> Option<Rc<TrackReceiver>> {
+        let request_id = self
+            .outgoing_subscriptions
+            .iter()
+            .find(|(_, subscription)| subscription.track_alias == Some(track_alias))
+            .map(|(&request_id, _)| request_id)?;
+        let receiver = self
+            .track_receivers
+            .entry(request_id)
+            .or_insert_with(|| {
+                let receiver = Rc::new(TrackReceiver::default());
+                receiver.set_request_id(request_id);
+                receiver
+            })
+            .clone();
+        Some(receiver)
+    }
+
+    /// Closes every `subscribeAsStream` stream when the session ends.
+    pub(crate) fn close_track_streams(&self) {
+        for receiver in self.track_receivers.values() {
+            receiver.close_stream();
+        }
+    }
+
+    pub(crate) fn take_track_receiver(&mut self, request_id: u64) -> Option<Rc<TrackReceiver>> {
+        self.track_receivers.remove(&request_id)
+    }
+
+    pub(crate) fn remove_outgoing_subscription(&mut self, request_id: u64) -> Option<u64> {
+        self.track_receivers.remove(&request_id);
+        let track_alias = self
+            .outgoing_subscriptions
+            .remove(&request_id)
+            .and_then(|subscription| subscription.track_alias);
+        if let Some(track_alias) = track_alias {
+            self.alias_to_track_key.remove(&track_alias);
+            self.subgroup_states.remove(&track_alias);
+        }
+        track_alias
+    }
+
+    pub(crate) fn validate_incoming_subscribe(&self, message: &Subscribe) -> u64 {
+        if !self.contains_published_namespace(&message.track_namespace) {
+            return 404;
+        }
+        if self
+            .incoming_subscriptions
+            .contains_key(&message.request_id)
+        {
+            return 409;
+        }
+        0
+    }
+
+    pub(crate) fn register_incoming_subscribe(&mut self, message: &Subscribe) {
+        self.incoming_subscriptions.insert(
+            message.request_id,
+            IncomingSubscribeRequest {
+                track_key: TrackKey::new(
+                    message.track_namespace.clone(),
+                    message.track_name.clone(),
+                ),
+                group_order: message.group_order,
+                track_alias: None,
+                streams_opened: 0,
+            },
+        );
+    }
+
+    pub(crate) fn activate_incoming_subscribe(&mut self, request_id: u64) -> Result<u64> {
+        let (track_alias, track_key) = {
+            let entry = self
+                .incoming_subscriptions
+                .get_mut(&request_id)
+                .ok_or_else(|| anyhow!("unknown subscribe request: {request_id}"))?;
+            let track_alias = match entry.track_alias {
+                Some(track_alias) => track_alias,
+                None => self.track_aliases.allocate(),
+            };
+            entry.track_alias = Some(track_alias);
+            (track_alias, entry.track_key.clone())
+        };
+
+        self.alias_to_track_key
+            .insert(track_alias, track_key.clone());
+        self.publishing_track_aliases
+            .entry(track_key)
+            .or_default()
+            .insert(track_alias);
+
+        Ok(track_alias)
+    }
+
+    pub(crate) fn incoming_subscribe_group_order(&self, request_id: u64) -> Result<GroupOrder> {
+        self.incoming_subscriptions
+            .get(&request_id)
+            .map(|entry| entry.group_order)
+            .ok_or_else(|| anyhow!("unknown subscribe request: {request_id}"))
+    }
+
+    pub(crate) fn incoming_subscribe_stream_count(&self, request_id: u64) -> Result<u64> {
+        self.incoming_subscriptions
+            .get(&request_id)
+            .map(|entry| entry.streams_opened)
+            .ok_or_else(|| anyhow!("unknown subscribe request: {request_id}"))
+    }
+
+    /// Counts a subgroup stream opened on `track_alias` for the incoming
+    /// subscription delivering it.
+    pub(crate) fn count_subgroup_stream(&mut self, track_alias: u64) {
+        if let Some(entry) = self
+            .incoming_subscriptions
+            .values_mut()
+            .find(|entry| entry.track_alias == Some(track_alias))
+        {
+            entry.streams_opened += 1;
+        }
+    }
+
+    pub(crate) fn remove_incoming_subscribe(&mut self, request_id: u64) -> Option<u64> {
+        let removed = self.incoming_subscriptions.remove(&request_id)?;
+        if let Some(track_alias) = removed.track_alias {
+            self.alias_to_track_key.remove(&track_alias);
+            self.subgroup_states.remove(&track_alias);
+            self.track_aliases.release(track_alias);
+            if let Some(aliases) = self.publishing_track_aliases.get_mut(&removed.track_key) {
+                aliases.remove(&track_alias);
+                if aliases.is_empty() {
+                    self.publishing_track_aliases.remove(&removed.track_key);
+                }
+            }
+            return Some(track_alias);
+        }
+        None
+    }
+
+    pub(crate) fn get_track_subscribers(
+        &self,
+        namespace: Vec<String>,
+        track_name: String,
+    ) -> Vec<u64> {
+        self.publishing_track_aliases
+            .get(&TrackKey::new(namespace, track_name))
+            .map(|aliases| aliases.iter().copied().collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+/// Events a `MOQTClient` dispatches. Each has one handler set through its
+/// `on*` method and any number of `addEventListener` listeners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ClientEvent {
+    ServerSetup,
+    PublishNamespace,
+    PublishNamespaceDone,
+    PublishNamespaceResponse,
+    SubscribeNamespaceResponse,
+    NamespaceAdded,
+    NamespaceRemoved,
+    Publish,
+    PublishResponse,
+    Subscribe,
+    SubscribeResponse,
+    PublishDone,
+    IncomingUnsubscribe,
+    ObjectDatagram,
+    ObjectDatagramStatus,
+    SubgroupHeader,
+    SubgroupObject,
+    SubgroupStreamObject,
+    FetchResponse,
+    FetchObject,
+    TrackStatus,
+    Goaway,
+    RequestsBlocked,
+    ConnectionClosed,
+    Reconnected,
+}
+
+impl ClientEvent {
+    const ALL: [ClientEvent; 25] = [
+        ClientEvent::ServerSetup,
+        ClientEvent::PublishNamespace,
+        ClientEvent::PublishNamespaceDone,
+        ClientEvent::PublishNamespaceResponse,
+        ClientEvent::SubscribeNamespaceResponse,
+        ClientEvent::NamespaceAdded,
+        ClientEvent::NamespaceRemoved,
+        ClientEvent::Publish,
+        ClientEvent::PublishResponse,
+        ClientEvent::Subscribe,
+        ClientEvent::SubscribeResponse,
+        ClientEvent::PublishDone,
+        ClientEvent::IncomingUnsubscribe,
+        ClientEvent::ObjectDatagram,
+        ClientEvent::ObjectDatagramStatus,
+        ClientEvent::SubgroupHeader,
+        ClientEvent::SubgroupObject,
+        ClientEvent::SubgroupStreamObject,
+        ClientEvent::FetchResponse,
+        ClientEvent::FetchObject,
+        ClientEvent::TrackStatus,
+        ClientEvent::Goaway,
+        ClientEvent::RequestsBlocked,
+        ClientEvent::ConnectionClosed,
+        ClientEvent::Reconnected,
+    ];
+
+    /// The name `addEventListener` takes: the `on*` method without `on`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ClientEvent::ServerSetup => "serverSetup",
+            ClientEvent::PublishNamespace => "publishNamespace",
+            ClientEvent::PublishNamespaceDone => "publishNamespaceDone",
+            ClientEvent::PublishNamespaceResponse => "publishNamespaceResponse",
+            ClientEvent::SubscribeNamespaceResponse => "subscribeNamespaceResponse",
+            ClientEvent::NamespaceAdded => "namespaceAdded",
+            ClientEvent::NamespaceRemoved => "namespaceRemoved",
+            ClientEvent::Publish => "publish",
+            ClientEvent::PublishResponse => "publishResponse",
+            ClientEvent::Subscribe => "subscribe",
+            ClientEvent::SubscribeResponse => "subscribeResponse",
+            ClientEvent::PublishDone => "publishDone",
+            ClientEvent::IncomingUnsubscribe => "incomingUnsubscribe",
+            ClientEvent::ObjectDatagram => "objectDatagram",
+            ClientEvent::ObjectDatagramStatus => "objectDatagramStatus",
+            ClientEvent::SubgroupHeader => "subgroupHeader",
+            ClientEvent::SubgroupObject => "subgroupObject",
+            ClientEvent::SubgroupStreamObject => "subgroupStreamObject",
+            ClientEvent::FetchResponse => "fetchResponse",
+            ClientEvent::FetchObject => "fetchObject",
+            ClientEvent::TrackStatus => "trackStatus",
+            ClientEvent::Goaway => "goaway",
+            ClientEvent::RequestsBlocked => "requestsBlocked",
+            ClientEvent::ConnectionClosed => "connectionClosed",
+            ClientEvent::Reconnected => "reconnected",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.name() == name)
+    }
+
+    /// Whether the event is about one track, so listeners may filter on
+    /// its Track Alias.
+    fn is_per_track(self) -> bool {
+        matches!(
+            self,
+            ClientEvent::ObjectDatagram
+                | ClientEvent::ObjectDatagramStatus
+                | ClientEvent::SubgroupHeader
+                | ClientEvent::SubgroupObject
+                | ClientEvent::SubgroupStreamObject
+        )
+    }
+}
+
+#[derive(Debug)]
+struct EventListener {
+    callback: js_sys::Function,
+    /// Only objects of this track reach the listener.
+    track_alias: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MOQTCallbacks {
+    handlers: HashMap<ClientEvent, js_sys::Function>,
+    listeners: HashMap<ClientEvent, Vec<EventListener>>,
+}
+
+impl MOQTCallbacks {
+    /// Replaces the `on*` handler of `event`.
+    pub(crate) fn set_handler(&mut self, event: ClientEvent, callback: js_sys::Function) {
+        self.handlers.insert(event, callback);
+    }
+
+    /// Drops the handler and every listener of `event`.
+    pub(crate) fn clear(&mut self, event: ClientEvent) {
+        self.handlers.remove(&event);
+        self.listeners.remove(&event);
+    }
+
+    pub(crate) fn add_listener(
+        &mut self,
+        event: ClientEvent,
+        callback: js_sys::Function,
+        track_alias: Option<u64>,
+    ) -> Result<(), JsValue> {
+        if track_alias.is_some() && !event.is_per_track() {
+            return Err(JsValue::from_str(&format!(
+                "{} listeners cannot filter on a track alias",
+                event.name()
+            )));
+        }
+        self.listeners
+            .entry(event)
+            .or_default()
+            .push(EventListener {
+                callback,
+                track_alias,
+            });
+        Ok(())
+    }
+
+    /// Removes every registration of `callback` for `event`.
+    pub(crate) fn remove_listener(&mut self, event: ClientEvent, callback: &js_sys::Function) {
+        if let Some(listeners) = self.listeners.get_mut(&event) {
+            listeners.retain(|listener| !js_sys::Object::is(&listener.callback, callback));
+        }
+    }
+
+    /// The handler of `event` followed by its listeners, skipping those that
+    /// filter on a track other than `track_alias`. Collected up front so the
+    /// callbacks may (un)register listeners while they run.
+    pub(crate) fn targets(
+        &self,
+        event: ClientEvent,
+        track_alias: Option<u64>,
+    ) -> Vec<js_sys::Function> {
+        let listeners = self
+            .listeners
+            .get(&event)
+            .into_iter()
+            .flatten()
+            .filter(|listener| {
+                listener.track_alias.is_none() || listener.track_alias == track_alias
+            })
+            .map(|listener| listener.callback.clone());
+        self.handlers
+            .get(&event)
+            .cloned()
+            .into_iter()
+            .chain(listeners)
+            .collect()
+    }
+}
+
+/// Calls every target of `event` with the arguments `args` builds, which
+/// is only invoked when there is at least one.
+pub(crate) fn dispatch(
+    callbacks: &std::cell::RefCell<MOQTCallbacks>,
+    event: ClientEvent,
+    track_alias: Option<u64>,
+    args: impl FnOnce() -> Vec<JsValue>,
+) {
+    let targets = callbacks.borrow().targets(event, track_alias);
+    if targets.is_empty() {
+        return;
+    }
+    let args: js_sys::Array = args().into_iter().collect();
+    for target in targets {
+        let _ = target.apply(&JsValue::NULL, &args);
+    }
+}
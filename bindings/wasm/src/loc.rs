@@ -1,6 +1,15 @@
 use anyhow::Result;
 use moqt::wire::ExtensionHeaders;
-use packages::loc::LocHeader;
+use packages::loc::{
+    CaptureTimestamp, LocHeader, LocHeaderExtension, VideoConfig, VideoFrameMarking,
+};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+/// RFC 9626 frame marking: Start and End of a frame carried whole in one
+/// object, and Independent for a keyframe.
+const FRAME_MARKING_START_END: u64 = 0xC0;
+const FRAME_MARKING_INDEPENDENT: u64 = 0x20;
 
 pub fn loc_header_to_extension_headers(header: &LocHeader) -> ExtensionHeaders {
     header.to_extension_headers()
@@ -23,3 +32,148 @@ pub fn parse_loc_header(value: wasm_bindgen::JsValue) -> Result<Option<LocHeader
 pub fn encode_loc_header(header: &LocHeader) -> Result<wasm_bindgen::JsValue> {
     serde_wasm_bindgen::to_value(header).map_err(|err| anyhow::anyhow!("loc header: {err}"))
 }
+
+/// The known LOC extensions of an object's `locHeader`, decoded. Each is
+/// `undefined` when the object does not carry it.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct LocMetadata {
+    capture_timestamp_micros: Option<u64>,
+    video_frame_marking: Option<u64>,
+    audio_level: Option<u8>,
+    video_config: Option<Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl LocMetadata {
+    /// Microseconds since the Unix epoch at capture, for end-to-end latency.
+    #[wasm_bindgen(getter, js_name = captureTimestampMicros)]
+    pub fn capture_timestamp_micros(&self) -> Option<u64> {
+        self.capture_timestamp_micros
+    }
+
+    /// RFC 9626 frame marking flags; the I bit (0x20) marks a keyframe.
+    #[wasm_bindgen(getter, js_name = videoFrameMarking)]
+    pub fn video_frame_marking(&self) -> Option<u64> {
+        self.video_frame_marking
+    }
+
+    #[wasm_bindgen(getter, js_name = audioLevel)]
+    pub fn audio_level(&self) -> Option<u8> {
+        self.audio_level
+    }
+
+    /// Decoder configuration record, e.g. for `VideoDecoder.configure`.
+    #[wasm_bindgen(getter, js_name = videoConfig)]
+    pub fn video_config(&self) -> Option<Vec<u8>> {
+        self.video_config.clone()
+    }
+}
+
+impl From<&LocHeader> for LocMetadata {
+    fn from(header: &LocHeader) -> Self {
+        Self {
+            capture_timestamp_micros: header.capture_timestamp(),
+            video_frame_marking: header.video_frame_marking(),
+            audio_level: header.audio_level(),
+            video_config: header.video_config().map(<[u8]>::to_vec),
+        }
+    }
+}
+
+/// `EncodedVideoChunkInit` for an object carrying one encoded frame with
+/// LOC extensions. The Capture Timestamp is the chunk's timestamp and the
+/// Frame Marking I bit its type; objects without them fall back to the
+/// Object ID as timestamp and a keyframe at Object ID 0, as the browser
+/// examples publish.
+pub(crate) fn encoded_video_chunk_init(
+    header: &LocHeader,
+    object_id: u64,
+    data: &js_sys::Uint8Array,
+    duration_micros: Option<f64>,
+) -> Result<js_sys::Object, JsValue> {
+    let is_key = header
+        .video_frame_marking()
+        .map_or(object_id == 0, |flags| {
+            flags & FRAME_MARKING_INDEPENDENT != 0
+        });
+    let timestamp = header.capture_timestamp().unwrap_or(object_id);
+    let init = js_sys::Object::new();
+    let chunk_type = if is_key { "key" } else { "delta" };
+    js_sys::Reflect::set(&init, &"type".into(), &chunk_type.into())?;
+    js_sys::Reflect::set(&init, &"timestamp".into(), &(timestamp as f64).into())?;
+    if let Some(duration) = duration_micros {
+        js_sys::Reflect::set(&init, &"duration".into(), &duration.into())?;
+    }
+    js_sys::Reflect::set(&init, &"data".into(), data)?;
+    Ok(init)
+}
+
+/// Options of `locObjectFromVideoChunk`; every field is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct VideoChunkLocOptions {
+    capture_timestamp_micros: Option<u64>,
+    decoder_config: Option<Vec<u8>>,
+}
+
+/// The payload and LOC header to publish an `EncodedVideoChunk` with, e.g.
+/// through `PublishTrackHandle.sendObject`. The header carries the Capture
+/// Timestamp (`captureTimestampMicros`, else the chunk's timestamp), a
+/// Frame Marking with the I bit on keyframes and, when given, the
+/// `decoderConfig` (`EncodedVideoChunkMetadata.decoderConfig.description`)
+/// as Video Config.
+#[wasm_bindgen(
+    js_name = locObjectFromVideoChunk,
+    unchecked_return_type = "{ payload: Uint8Array; locHeader: LocHeader }"
+)]
+pub fn loc_object_from_video_chunk(
+    #[wasm_bindgen(unchecked_param_type = "EncodedVideoChunk")] chunk: JsValue,
+    #[wasm_bindgen(
+        unchecked_optional_param_type = "{ captureTimestampMicros?: number | bigint; decoderConfig?: Uint8Array }"
+    )]
+    options: JsValue,
+) -> Result<js_sys::Object, JsValue> {
+    let options: VideoChunkLocOptions = if options.is_undefined() || options.is_null() {
+        VideoChunkLocOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)
+            .map_err(|error| JsValue::from_str(&format!("invalid video chunk options: {error}")))?
+    };
+    let byte_length = js_sys::Reflect::get(&chunk, &"byteLength".into())?
+        .as_f64()
+        .ok_or_else(|| JsValue::from_str("chunk has no byteLength"))?;
+    let payload = js_sys::Uint8Array::new_with_length(byte_length as u32);
+    let copy_to: js_sys::Function = js_sys::Reflect::get(&chunk, &"copyTo".into())?.dyn_into()?;
+    copy_to.call1(&chunk, &payload)?;
+
+    let is_key = js_sys::Reflect::get(&chunk, &"type".into())?
+        .as_string()
+        .as_deref()
+        == Some("key");
+    let timestamp = match options.capture_timestamp_micros {
+        Some(timestamp) => timestamp,
+        None => js_sys::Reflect::get(&chunk, &"timestamp".into())?
+            .as_f64()
+            .unwrap_or(0.0)
+            .max(0.0) as u64,
+    };
+    let mut extensions = vec![
+        LocHeaderExtension::CaptureTimestamp(CaptureTimestamp {
+            micros_since_unix_epoch: timestamp,
+        }),
+        LocHeaderExtension::VideoFrameMarking(VideoFrameMarking {
+            flags: FRAME_MARKING_START_END | if is_key { FRAME_MARKING_INDEPENDENT } else { 0 },
+        }),
+    ];
+    if let Some(data) = options.decoder_config {
+        extensions.push(LocHeaderExtension::VideoConfig(VideoConfig { data }));
+    }
+    let loc_header = encode_loc_header(&LocHeader { extensions })
+        .map_err(|error| JsValue::from_str(&error.to_string()))?;
+
+    let object = js_sys::Object::new();
+    js_sys::Reflect::set(&object, &"payload".into(), &payload)?;
+    js_sys::Reflect::set(&object, &"locHeader".into(), &loc_header)?;
+    Ok(object)
+}
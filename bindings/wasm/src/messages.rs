@@ -2,13 +2,30 @@ mod subgroup_state;
 
 use moqt::wire::{
     ContentExists, FetchObject, FetchObjectField, FetchOk, FilterType, NamespaceOk, ObjectStatus,
-    Publish, PublishNamespace, PublishNamespaceDone, PublishOk, RequestError, ServerSetup,
-    Subscribe, SubscribeNamespace, SubscribeOk,
+    Publish, PublishDone, PublishNamespace, PublishNamespaceDone, PublishOk, RequestError,
+    ServerSetup, SubgroupObject, SubgroupStreamObject, Subscribe, SubscribeNamespace, SubscribeOk,
+    TrackStatus,
 };
 use packages::loc::LocHeader;
 pub use subgroup_state::SubgroupState;
 use wasm_bindgen::prelude::*;
 
+/// Copies a received payload out of wasm memory once, into a `Uint8Array`
+/// the message keeps and hands out as is. JS gets one buffer per object,
+/// which it may transfer (e.g. to a decoder worker) without another copy.
+fn object_payload_array(payload: &[u8]) -> js_sys::Uint8Array {
+    js_sys::Uint8Array::from(payload)
+}
+
+/// The object `toObject` returns, holding `fields` as own properties.
+fn plain_object(fields: &[(&str, JsValue)]) -> js_sys::Object {
+    let object = js_sys::Object::new();
+    for (name, value) in fields {
+        let _ = js_sys::Reflect::set(&object, &JsValue::from_str(name), value);
+    }
+    object
+}
+
 fn filter_fields(filter_type: FilterType) -> (u8, Option<u64>, Option<u64>, Option<u64>) {
     match filter_type {
         FilterType::NextGroupStart => (1, None, None, None),
@@ -71,6 +88,7 @@ impl From<&ServerSetup> for ServerSetupMessage {
 pub struct PublishNamespaceMessage {
     request_id: u64,
     track_namespace: Vec<String>,
+    duplicate_of: Option<u64>,
 }
 
 #[wasm_bindgen]
@@ -84,13 +102,22 @@ impl PublishNamespaceMessage {
     pub fn track_namespace(&self) -> Vec<String> {
         self.track_namespace.clone()
     }
+
+    /// Request ID of an earlier PUBLISH_NAMESPACE of the same namespace
+    /// that the peer has not withdrawn; such a duplicate is best answered
+    /// with `sendPublishNamespaceError`.
+    #[wasm_bindgen(getter, js_name = duplicateOf)]
+    pub fn duplicate_of(&self) -> Option<u64> {
+        self.duplicate_of
+    }
 }
 
-impl From<&PublishNamespace> for PublishNamespaceMessage {
-    fn from(message: &PublishNamespace) -> Self {
+impl PublishNamespaceMessage {
+    pub(crate) fn new(message: &PublishNamespace, duplicate_of: Option<u64>) -> Self {
         Self {
             request_id: message.request_id,
             track_namespace: message.track_namespace.clone(),
+            duplicate_of,
         }
     }
 }
@@ -98,20 +125,28 @@ impl From<&PublishNamespace> for PublishNamespaceMessage {
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct PublishNamespaceDoneMessage {
+    request_id: u64,
     track_namespace: Vec<String>,
 }
 
 #[wasm_bindgen]
 impl PublishNamespaceDoneMessage {
+    /// Request ID of the PUBLISH_NAMESPACE this withdraws.
+    #[wasm_bindgen(getter, js_name = requestId)]
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
     #[wasm_bindgen(getter, js_name = trackNamespace)]
     pub fn track_namespace(&self) -> Vec<String> {
         self.track_namespace.clone()
     }
 }
 
-impl From<&PublishNamespaceDone> for PublishNamespaceDoneMessage {
-    fn from(message: &PublishNamespaceDone) -> Self {
+impl PublishNamespaceDoneMessage {
+    pub(crate) fn new(message: &PublishNamespaceDone, request_id: u64) -> Self {
         Self {
+            request_id,
             track_namespace: message.track_namespace.clone(),
         }
     }
@@ -146,6 +181,36 @@ impl From<&SubscribeNamespace> for SubscribeNamespaceMessage {
     }
 }
 
+/// A namespace that appeared under, or disappeared from, a subscribed prefix.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct NamespaceDeltaMessage {
+    track_namespace: Vec<String>,
+    track_namespace_prefix: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl NamespaceDeltaMessage {
+    #[wasm_bindgen(getter, js_name = trackNamespace)]
+    pub fn track_namespace(&self) -> Vec<String> {
+        self.track_namespace.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = trackNamespacePrefix)]
+    pub fn track_namespace_prefix(&self) -> Vec<String> {
+        self.track_namespace_prefix.clone()
+    }
+}
+
+impl NamespaceDeltaMessage {
+    pub(crate) fn new(track_namespace: Vec<String>, track_namespace_prefix: Vec<String>) -> Self {
+        Self {
+            track_namespace,
+            track_namespace_prefix,
+        }
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct NamespaceOkMessage {
@@ -204,6 +269,86 @@ impl From<&RequestError> for RequestErrorMessage {
     }
 }
 
+/// PUBLISH_DONE status codes that end a subscription without an error
+/// (draft-14 §9.12): TRACK_ENDED and SUBSCRIPTION_ENDED.
+pub(crate) const PUBLISH_DONE_TRACK_ENDED: u64 = 0x2;
+pub(crate) const PUBLISH_DONE_SUBSCRIPTION_ENDED: u64 = 0x3;
+
+/// PUBLISH_DONE of a subscription this client made, with the last object
+/// it received on it when the subscription came from `subscribeTrack`.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct PublishDoneMessage {
+    request_id: u64,
+    status_code: u64,
+    stream_count: u64,
+    reason_phrase: String,
+    final_location: Option<(u64, u64)>,
+}
+
+#[wasm_bindgen]
+impl PublishDoneMessage {
+    #[wasm_bindgen(getter, js_name = requestId)]
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    #[wasm_bindgen(getter, js_name = statusCode)]
+    pub fn status_code(&self) -> u64 {
+        self.status_code
+    }
+
+    /// Data streams the publisher opened for the subscription.
+    #[wasm_bindgen(getter, js_name = streamCount)]
+    pub fn stream_count(&self) -> u64 {
+        self.stream_count
+    }
+
+    #[wasm_bindgen(getter, js_name = reasonPhrase)]
+    pub fn reason_phrase(&self) -> String {
+        self.reason_phrase.clone()
+    }
+
+    /// `true` for TRACK_ENDED and SUBSCRIPTION_ENDED, `false` for the
+    /// error codes (INTERNAL_ERROR, UNAUTHORIZED, EXPIRED, ...).
+    #[wasm_bindgen(getter, js_name = endedNormally)]
+    pub fn ended_normally(&self) -> bool {
+        matches!(
+            self.status_code,
+            PUBLISH_DONE_TRACK_ENDED | PUBLISH_DONE_SUBSCRIPTION_ENDED
+        )
+    }
+
+    /// Whether an object of the subscription was received. Only objects of
+    /// `subscribeTrack` subscriptions are tracked; `false` for the others.
+    #[wasm_bindgen(getter, js_name = contentExists)]
+    pub fn content_exists(&self) -> bool {
+        self.final_location.is_some()
+    }
+
+    #[wasm_bindgen(getter, js_name = finalGroupId)]
+    pub fn final_group_id(&self) -> Option<u64> {
+        self.final_location.map(|(group_id, _)| group_id)
+    }
+
+    #[wasm_bindgen(getter, js_name = finalObjectId)]
+    pub fn final_object_id(&self) -> Option<u64> {
+        self.final_location.map(|(_, object_id)| object_id)
+    }
+}
+
+impl PublishDoneMessage {
+    pub(crate) fn new(message: &PublishDone, final_location: Option<(u64, u64)>) -> Self {
+        Self {
+            request_id: message.request_id,
+            status_code: message.status_code,
+            stream_count: message.stream_count,
+            reason_phrase: message.reason_phrase.clone(),
+            final_location,
+        }
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct PublishMessage {
@@ -377,6 +522,7 @@ pub struct SubscribeMessage {
     start_group: Option<u64>,
     start_object: Option<u64>,
     end_group: Option<u64>,
+    max_cache_duration: Option<u64>,
 }
 
 #[wasm_bindgen]
@@ -430,6 +576,12 @@ impl SubscribeMessage {
     pub fn end_group(&self) -> Option<u64> {
         self.end_group
     }
+
+    /// MAX_CACHE_DURATION (ms) the subscriber asked for, if any.
+    #[wasm_bindgen(getter, js_name = maxCacheDuration)]
+    pub fn max_cache_duration(&self) -> Option<u64> {
+        self.max_cache_duration
+    }
 }
 
 impl From<&Subscribe> for SubscribeMessage {
@@ -447,6 +599,7 @@ impl From<&Subscribe> for SubscribeMessage {
             start_group,
             start_object,
             end_group,
+            max_cache_duration: message.max_cache_duration,
         }
     }
 }
@@ -531,6 +684,57 @@ impl From<&SubscribeOk> for SubscribeOkMessage {
     }
 }
 
+#[wasm_bindgen]
+pub struct TrackStatusMessage {
+    request_id: u64,
+    group_order: u8,
+    content_exists: bool,
+    largest_group_id: Option<u64>,
+    largest_object_id: Option<u64>,
+}
+
+#[wasm_bindgen]
+impl TrackStatusMessage {
+    #[wasm_bindgen(getter, js_name = requestId)]
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    #[wasm_bindgen(getter, js_name = groupOrder)]
+    pub fn group_order(&self) -> u8 {
+        self.group_order
+    }
+
+    #[wasm_bindgen(getter, js_name = contentExists)]
+    pub fn content_exists(&self) -> bool {
+        self.content_exists
+    }
+
+    #[wasm_bindgen(getter, js_name = largestGroupId)]
+    pub fn largest_group_id(&self) -> Option<u64> {
+        self.largest_group_id
+    }
+
+    #[wasm_bindgen(getter, js_name = largestObjectId)]
+    pub fn largest_object_id(&self) -> Option<u64> {
+        self.largest_object_id
+    }
+}
+
+impl From<&TrackStatus> for TrackStatusMessage {
+    fn from(message: &TrackStatus) -> Self {
+        let (content_exists, largest_group_id, largest_object_id) =
+            content_exists_fields(message.content_exists);
+        Self {
+            request_id: message.request_id,
+            group_order: message.group_order as u8,
+            content_exists,
+            largest_group_id,
+            largest_object_id,
+        }
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct SubgroupHeaderMessage {
@@ -561,6 +765,17 @@ impl SubgroupHeaderMessage {
     pub fn publisher_priority(&self) -> u8 {
         self.publisher_priority
     }
+
+    /// The getters above as a plain object that `postMessage` can clone.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "PlainMessage<SubgroupHeaderMessage>")]
+    pub fn to_object(&self) -> js_sys::Object {
+        plain_object(&[
+            ("trackAlias", self.track_alias.into()),
+            ("groupId", self.group_id.into()),
+            ("subgroupId", self.subgroup_id.into()),
+            ("publisherPriority", self.publisher_priority.into()),
+        ])
+    }
 }
 
 impl SubgroupHeaderMessage {
@@ -587,7 +802,7 @@ pub struct ObjectDatagramMessage {
     object_id: Option<u64>,
     publisher_priority: u8,
     object_payload_length: u32,
-    object_payload: Vec<u8>,
+    object_payload: js_sys::Uint8Array,
     loc_header: LocHeader,
 }
 
@@ -618,16 +833,55 @@ impl ObjectDatagramMessage {
         self.object_payload_length
     }
 
+    /// The same `Uint8Array` on every read, not a fresh copy.
     #[wasm_bindgen(getter, js_name = objectPayload)]
-    pub fn object_payload(&self) -> Vec<u8> {
+    pub fn object_payload(&self) -> js_sys::Uint8Array {
         self.object_payload.clone()
     }
 
-    #[wasm_bindgen(getter, js_name = locHeader)]
+    #[wasm_bindgen(getter, js_name = locHeader, unchecked_return_type = "LocHeader")]
     pub fn loc_header(&self) -> Result<JsValue, JsValue> {
         crate::loc::encode_loc_header(&self.loc_header)
             .map_err(|error| JsValue::from_str(&error.to_string()))
     }
+
+    #[wasm_bindgen(getter, js_name = locMetadata)]
+    pub fn loc_metadata(&self) -> crate::loc::LocMetadata {
+        crate::loc::LocMetadata::from(&self.loc_header)
+    }
+
+    /// The getters above, except `locMetadata`, as a plain object that
+    /// `postMessage` can clone. Put `objectPayload.buffer` in the transfer
+    /// list to move the payload instead of copying it; this message's
+    /// `objectPayload` is detached afterwards.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "PlainMessage<ObjectDatagramMessage>")]
+    pub fn to_object(&self) -> Result<js_sys::Object, JsValue> {
+        Ok(plain_object(&[
+            ("trackAlias", self.track_alias.into()),
+            ("groupId", self.group_id.into()),
+            ("objectId", self.object_id.into()),
+            ("publisherPriority", self.publisher_priority.into()),
+            ("objectPayloadLength", self.object_payload_length.into()),
+            ("objectPayload", self.object_payload.clone().into()),
+            ("locHeader", self.loc_header()?),
+        ]))
+    }
+
+    /// `EncodedVideoChunkInit` for `new EncodedVideoChunk(...)`: this
+    /// object's payload, typed and timestamped from its LOC Frame Marking and
+    /// Capture Timestamp (see `locObjectFromVideoChunk` for the reverse).
+    #[wasm_bindgen(js_name = toEncodedVideoChunkInit, unchecked_return_type = "EncodedVideoChunkInit")]
+    pub fn to_encoded_video_chunk_init(
+        &self,
+        #[wasm_bindgen(js_name = durationMicros)] duration_micros: Option<f64>,
+    ) -> Result<js_sys::Object, JsValue> {
+        crate::loc::encoded_video_chunk_init(
+            &self.loc_header,
+            self.object_id.unwrap_or(0),
+            &self.object_payload,
+            duration_micros,
+        )
+    }
 }
 
 impl ObjectDatagramMessage {
@@ -636,7 +890,7 @@ impl ObjectDatagramMessage {
         group_id: u64,
         object_id: Option<u64>,
         publisher_priority: u8,
-        object_payload: Vec<u8>,
+        object_payload: &[u8],
         loc_header: LocHeader,
     ) -> Self {
         Self {
@@ -645,7 +899,7 @@ impl ObjectDatagramMessage {
             object_id,
             publisher_priority,
             object_payload_length: object_payload.len() as u32,
-            object_payload,
+            object_payload: object_payload_array(object_payload),
             loc_header,
         }
     }
@@ -689,11 +943,30 @@ impl ObjectDatagramStatusMessage {
         self.object_status
     }
 
-    #[wasm_bindgen(getter, js_name = locHeader)]
+    #[wasm_bindgen(getter, js_name = locHeader, unchecked_return_type = "LocHeader")]
     pub fn loc_header(&self) -> Result<JsValue, JsValue> {
         crate::loc::encode_loc_header(&self.loc_header)
             .map_err(|error| JsValue::from_str(&error.to_string()))
     }
+
+    #[wasm_bindgen(getter, js_name = locMetadata)]
+    pub fn loc_metadata(&self) -> crate::loc::LocMetadata {
+        crate::loc::LocMetadata::from(&self.loc_header)
+    }
+
+    /// The getters above, except `locMetadata`, as a plain object that
+    /// `postMessage` can clone.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "PlainMessage<ObjectDatagramStatusMessage>")]
+    pub fn to_object(&self) -> Result<js_sys::Object, JsValue> {
+        Ok(plain_object(&[
+            ("trackAlias", self.track_alias.into()),
+            ("groupId", self.group_id.into()),
+            ("objectId", self.object_id.into()),
+            ("publisherPriority", self.publisher_priority.into()),
+            ("objectStatus", self.object_status.into()),
+            ("locHeader", self.loc_header()?),
+        ]))
+    }
 }
 
 impl ObjectDatagramStatusMessage {
@@ -723,7 +996,7 @@ pub struct SubgroupObjectMessage {
     object_id_delta: u64,
     object_status: Option<u8>,
     object_payload_length: u32,
-    object_payload: Vec<u8>,
+    object_payload: js_sys::Uint8Array,
     loc_header: LocHeader,
 }
 
@@ -754,16 +1027,39 @@ impl SubgroupObjectMessage {
         self.object_payload_length
     }
 
+    /// The same `Uint8Array` on every read, not a fresh copy.
     #[wasm_bindgen(getter, js_name = objectPayload)]
-    pub fn object_payload(&self) -> Vec<u8> {
+    pub fn object_payload(&self) -> js_sys::Uint8Array {
         self.object_payload.clone()
     }
 
-    #[wasm_bindgen(getter, js_name = locHeader)]
+    #[wasm_bindgen(getter, js_name = locHeader, unchecked_return_type = "LocHeader")]
     pub fn loc_header(&self) -> Result<JsValue, JsValue> {
         crate::loc::encode_loc_header(&self.loc_header)
             .map_err(|error| JsValue::from_str(&error.to_string()))
     }
+
+    #[wasm_bindgen(getter, js_name = locMetadata)]
+    pub fn loc_metadata(&self) -> crate::loc::LocMetadata {
+        crate::loc::LocMetadata::from(&self.loc_header)
+    }
+
+    /// The getters above, except `locMetadata`, as a plain object that
+    /// `postMessage` can clone. Put `objectPayload.buffer` in the transfer
+    /// list to move the payload instead of copying it; this message's
+    /// `objectPayload` is detached afterwards.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "PlainMessage<SubgroupObjectMessage>")]
+    pub fn to_object(&self) -> Result<js_sys::Object, JsValue> {
+        Ok(plain_object(&[
+            ("subgroupId", self.subgroup_id.into()),
+            ("objectIdDelta", self.object_id_delta.into()),
+            ("objectId", self.object_id_delta.into()),
+            ("objectStatus", self.object_status.into()),
+            ("objectPayloadLength", self.object_payload_length.into()),
+            ("objectPayload", self.object_payload.clone().into()),
+            ("locHeader", self.loc_header()?),
+        ]))
+    }
 }
 
 impl SubgroupObjectMessage {
@@ -771,7 +1067,7 @@ impl SubgroupObjectMessage {
         subgroup_id: Option<u64>,
         object_id_delta: u64,
         object_status: Option<ObjectStatus>,
-        object_payload: Vec<u8>,
+        object_payload: &[u8],
         loc_header: LocHeader,
     ) -> Self {
         Self {
@@ -779,8 +1075,156 @@ impl SubgroupObjectMessage {
             object_id_delta,
             object_status: object_status.map(|value| value as u8),
             object_payload_length: object_payload.len() as u32,
+            object_payload: object_payload_array(object_payload),
+            loc_header,
+        }
+    }
+}
+
+/// A subgroup stream object with the header context (Track Alias, Group ID,
+/// Subgroup ID, Publisher Priority) and its absolute Object ID.
+#[wasm_bindgen]
+pub struct SubgroupStreamObjectMessage {
+    track_alias: u64,
+    group_id: u64,
+    subgroup_id: u64,
+    publisher_priority: u8,
+    object_id: u64,
+    object_status: Option<u8>,
+    object_payload: js_sys::Uint8Array,
+    loc_header: LocHeader,
+    extension_headers: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl SubgroupStreamObjectMessage {
+    #[wasm_bindgen(getter, js_name = trackAlias)]
+    pub fn track_alias(&self) -> u64 {
+        self.track_alias
+    }
+
+    #[wasm_bindgen(getter, js_name = groupId)]
+    pub fn group_id(&self) -> u64 {
+        self.group_id
+    }
+
+    #[wasm_bindgen(getter, js_name = subgroupId)]
+    pub fn subgroup_id(&self) -> u64 {
+        self.subgroup_id
+    }
+
+    #[wasm_bindgen(getter, js_name = publisherPriority)]
+    pub fn publisher_priority(&self) -> u8 {
+        self.publisher_priority
+    }
+
+    #[wasm_bindgen(getter, js_name = objectId)]
+    pub fn object_id(&self) -> u64 {
+        self.object_id
+    }
+
+    #[wasm_bindgen(getter, js_name = objectStatus)]
+    pub fn object_status(&self) -> Option<u8> {
+        self.object_status
+    }
+
+    #[wasm_bindgen(getter, js_name = objectPayloadLength)]
+    pub fn object_payload_length(&self) -> u32 {
+        self.object_payload.length()
+    }
+
+    /// The same `Uint8Array` on every read, not a fresh copy.
+    #[wasm_bindgen(getter, js_name = objectPayload)]
+    pub fn object_payload(&self) -> js_sys::Uint8Array {
+        self.object_payload.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = locHeader, unchecked_return_type = "LocHeader")]
+    pub fn loc_header(&self) -> Result<JsValue, JsValue> {
+        crate::loc::encode_loc_header(&self.loc_header)
+            .map_err(|error| JsValue::from_str(&error.to_string()))
+    }
+
+    #[wasm_bindgen(getter, js_name = locMetadata)]
+    pub fn loc_metadata(&self) -> crate::loc::LocMetadata {
+        crate::loc::LocMetadata::from(&self.loc_header)
+    }
+
+    /// The object's Extension Headers block (length, then Key-Value-Pairs)
+    /// in wire encoding; empty when it has none.
+    #[wasm_bindgen(getter, js_name = extensionHeaders)]
+    pub fn extension_headers(&self) -> Vec<u8> {
+        self.extension_headers.clone()
+    }
+
+    /// The getters above, except `locMetadata`, as a plain object that
+    /// `postMessage` can clone. Put `objectPayload.buffer` in the transfer
+    /// list to move the payload instead of copying it; this message's
+    /// `objectPayload` is detached afterwards.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "PlainMessage<SubgroupStreamObjectMessage>")]
+    pub fn to_object(&self) -> Result<js_sys::Object, JsValue> {
+        Ok(plain_object(&[
+            ("trackAlias", self.track_alias.into()),
+            ("groupId", self.group_id.into()),
+            ("subgroupId", self.subgroup_id.into()),
+            ("publisherPriority", self.publisher_priority.into()),
+            ("objectId", self.object_id.into()),
+            ("objectStatus", self.object_status.into()),
+            ("objectPayloadLength", self.object_payload_length().into()),
+            ("objectPayload", self.object_payload.clone().into()),
+            ("locHeader", self.loc_header()?),
+            (
+                "extensionHeaders",
+                js_sys::Uint8Array::from(self.extension_headers.as_slice()).into(),
+            ),
+        ]))
+    }
+
+    /// `EncodedVideoChunkInit` for `new EncodedVideoChunk(...)`: this
+    /// object's payload, typed and timestamped from its LOC Frame Marking and
+    /// Capture Timestamp (see `locObjectFromVideoChunk` for the reverse).
+    #[wasm_bindgen(js_name = toEncodedVideoChunkInit, unchecked_return_type = "EncodedVideoChunkInit")]
+    pub fn to_encoded_video_chunk_init(
+        &self,
+        #[wasm_bindgen(js_name = durationMicros)] duration_micros: Option<f64>,
+    ) -> Result<js_sys::Object, JsValue> {
+        crate::loc::encoded_video_chunk_init(
+            &self.loc_header,
+            self.object_id,
+            &self.object_payload,
+            duration_micros,
+        )
+    }
+}
+
+impl From<SubgroupStreamObject> for SubgroupStreamObjectMessage {
+    fn from(object: SubgroupStreamObject) -> Self {
+        let loc_header =
+            crate::loc::extension_headers_to_loc_header(&object.field.extension_headers);
+        let extension_headers = if object.field.extension_headers.key_value_pairs.is_empty() {
+            Vec::new()
+        } else {
+            object.field.extension_headers.encode().to_vec()
+        };
+        let (object_status, object_payload) = match object.field.subgroup_object {
+            SubgroupObject::Payload { data, .. } => (None, object_payload_array(&data)),
+            SubgroupObject::Status { code, .. } => (
+                ObjectStatus::try_from(code as u8)
+                    .ok()
+                    .map(|status| status as u8),
+                object_payload_array(&[]),
+            ),
+        };
+        Self {
+            track_alias: object.track_alias,
+            group_id: object.group_id,
+            subgroup_id: object.subgroup_id,
+            publisher_priority: object.publisher_priority,
+            object_id: object.object_id,
+            object_status,
             object_payload,
             loc_header,
+            extension_headers,
         }
     }
 }
@@ -832,7 +1276,7 @@ pub struct FetchObjectMessage {
     request_id: u64,
     group_id: u64,
     object_id: u64,
-    object_payload: Vec<u8>,
+    object_payload: js_sys::Uint8Array,
     loc_header: LocHeader,
 }
 
@@ -853,23 +1297,44 @@ impl FetchObjectMessage {
         self.object_id
     }
 
+    /// The same `Uint8Array` on every read, not a fresh copy.
     #[wasm_bindgen(getter, js_name = objectPayload)]
-    pub fn object_payload(&self) -> Vec<u8> {
+    pub fn object_payload(&self) -> js_sys::Uint8Array {
         self.object_payload.clone()
     }
 
-    #[wasm_bindgen(getter, js_name = locHeader)]
+    #[wasm_bindgen(getter, js_name = locHeader, unchecked_return_type = "LocHeader")]
     pub fn loc_header(&self) -> Result<JsValue, JsValue> {
         crate::loc::encode_loc_header(&self.loc_header)
             .map_err(|error| JsValue::from_str(&error.to_string()))
     }
+
+    #[wasm_bindgen(getter, js_name = locMetadata)]
+    pub fn loc_metadata(&self) -> crate::loc::LocMetadata {
+        crate::loc::LocMetadata::from(&self.loc_header)
+    }
+
+    /// The getters above, except `locMetadata`, as a plain object that
+    /// `postMessage` can clone. Put `objectPayload.buffer` in the transfer
+    /// list to move the payload instead of copying it; this message's
+    /// `objectPayload` is detached afterwards.
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "PlainMessage<FetchObjectMessage>")]
+    pub fn to_object(&self) -> Result<js_sys::Object, JsValue> {
+        Ok(plain_object(&[
+            ("requestId", self.request_id.into()),
+            ("groupId", self.group_id.into()),
+            ("objectId", self.object_id.into()),
+            ("objectPayload", self.object_payload.clone().into()),
+            ("locHeader", self.loc_header()?),
+        ]))
+    }
 }
 
 impl FetchObjectMessage {
     pub(crate) fn new(request_id: u64, field: &FetchObjectField) -> Self {
         let payload = match &field.fetch_object {
-            FetchObject::Payload(data) => data.to_vec(),
-            FetchObject::Status(_) => Vec::new(),
+            FetchObject::Payload(data) => object_payload_array(data),
+            FetchObject::Status(_) => object_payload_array(&[]),
         };
         Self {
             request_id,
@@ -50,6 +50,14 @@ impl SubgroupState {
         }
     }
 
+    pub(crate) fn starting_at(track_alias: u64, group_id: u64, subgroup_id: u64) -> Self {
+        Self {
+            group_id,
+            subgroup_id,
+            ..Self::with_track(track_alias)
+        }
+    }
+
     pub(crate) fn mark_header_sent(&mut self) {
         self.header_sent = true;
     }
@@ -57,4 +65,11 @@ impl SubgroupState {
     pub(crate) fn increment_object_id(&mut self) {
         self.object_id = self.object_id.saturating_add(1);
     }
+
+    /// Moves to the first object of the next group; its header is not sent yet.
+    pub(crate) fn advance_group(&mut self) {
+        self.group_id = self.group_id.saturating_add(1);
+        self.object_id = 0;
+        self.header_sent = false;
+    }
 }
@@ -0,0 +1,138 @@
+use std::collections::{BTreeSet, HashMap};
+
+/// Namespaces announced to this client, grouped by the SUBSCRIBE_NAMESPACE
+/// prefix that granted them.
+///
+/// The relay forwards PUBLISH_NAMESPACE / PUBLISH_NAMESPACE_DONE for every
+/// namespace under a subscribed prefix. The registry turns that raw stream into
+/// add/remove deltas per prefix so duplicate announcements and withdrawals of
+/// unknown namespaces never reach the application.
+#[derive(Debug, Default)]
+pub(crate) struct NamespaceRegistry {
+    namespaces_by_prefix: HashMap<Vec<String>, BTreeSet<Vec<String>>>,
+}
+
+impl NamespaceRegistry {
+    pub(crate) fn track_prefix(&mut self, prefix: Vec<String>) {
+        self.namespaces_by_prefix.entry(prefix).or_default();
+    }
+
+    pub(crate) fn untrack_prefix(&mut self, prefix: &[String]) {
+        self.namespaces_by_prefix.remove(prefix);
+    }
+
+    /// Records `namespace` under every tracked prefix it falls under and
+    /// returns the prefixes for which it is new.
+    pub(crate) fn add_namespace(&mut self, namespace: &[String]) -> Vec<Vec<String>> {
+        self.namespaces_by_prefix
+            .iter_mut()
+            .filter(|(prefix, _)| namespace.starts_with(prefix))
+            .filter_map(|(prefix, namespaces)| {
+                namespaces
+                    .insert(namespace.to_vec())
+                    .then(|| prefix.clone())
+            })
+            .collect()
+    }
+
+    /// Forgets `namespace` under every tracked prefix and returns the prefixes
+    /// it was actually removed from.
+    pub(crate) fn remove_namespace(&mut self, namespace: &[String]) -> Vec<Vec<String>> {
+        self.namespaces_by_prefix
+            .iter_mut()
+            .filter_map(|(prefix, namespaces)| namespaces.remove(namespace).then(|| prefix.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NamespaceRegistry;
+
+    fn namespace(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|field| field.to_string()).collect()
+    }
+
+    #[test]
+    fn duplicate_add_is_reported_once() {
+        // Arrange
+        let mut registry = NamespaceRegistry::default();
+        registry.track_prefix(namespace(&["live"]));
+        // Act
+        let first = registry.add_namespace(&namespace(&["live", "cam1"]));
+        let second = registry.add_namespace(&namespace(&["live", "cam1"]));
+        // Assert: only the first announcement is a delta
+        assert_eq!(first, vec![namespace(&["live"])]);
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn add_outside_every_prefix_is_ignored() {
+        // Arrange
+        let mut registry = NamespaceRegistry::default();
+        registry.track_prefix(namespace(&["live"]));
+        // Act
+        let added = registry.add_namespace(&namespace(&["vod", "cam1"]));
+        // Assert
+        assert!(added.is_empty());
+    }
+
+    #[test]
+    fn removing_unknown_namespace_reports_nothing() {
+        // Arrange
+        let mut registry = NamespaceRegistry::default();
+        registry.track_prefix(namespace(&["live"]));
+        registry.add_namespace(&namespace(&["live", "cam1"]));
+        // Act
+        let removed = registry.remove_namespace(&namespace(&["live", "cam2"]));
+        // Assert
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn namespace_is_removed_only_once() {
+        // Arrange
+        let mut registry = NamespaceRegistry::default();
+        registry.track_prefix(namespace(&["live"]));
+        registry.add_namespace(&namespace(&["live", "cam1"]));
+        // Act
+        let first = registry.remove_namespace(&namespace(&["live", "cam1"]));
+        let second = registry.remove_namespace(&namespace(&["live", "cam1"]));
+        // Assert: the second PUBLISH_NAMESPACE_DONE is a withdrawal of an unknown namespace
+        assert_eq!(first, vec![namespace(&["live"])]);
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn overlapping_prefixes_each_see_the_namespace() {
+        // Arrange
+        let mut registry = NamespaceRegistry::default();
+        registry.track_prefix(namespace(&["live"]));
+        registry.track_prefix(namespace(&["live", "cam1"]));
+        registry.track_prefix(namespace(&["live", "cam2"]));
+        // Act
+        let mut added = registry.add_namespace(&namespace(&["live", "cam1", "hd"]));
+        added.sort();
+        let mut removed = registry.remove_namespace(&namespace(&["live", "cam1", "hd"]));
+        removed.sort();
+        // Assert: both the outer and inner prefix match, the sibling does not
+        let expected = vec![namespace(&["live"]), namespace(&["live", "cam1"])];
+        assert_eq!(added, expected);
+        assert_eq!(removed, expected);
+    }
+
+    #[test]
+    fn untracked_prefix_no_longer_receives_namespaces() {
+        // Arrange
+        let mut registry = NamespaceRegistry::default();
+        registry.track_prefix(namespace(&["live"]));
+        registry.add_namespace(&namespace(&["live", "cam1"]));
+        // Act
+        registry.untrack_prefix(&namespace(&["live"]));
+        let added = registry.add_namespace(&namespace(&["live", "cam2"]));
+        let removed = registry.remove_namespace(&namespace(&["live", "cam1"]));
+        // Assert
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+    }
+}
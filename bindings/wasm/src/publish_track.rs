@@ -0,0 +1,193 @@
+use std::cell::{Cell, RefCell};
+
+use moqt::wire::ObjectStatus;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{MOQTClient, SubgroupState, js_error};
+
+/// Options of `createPublishTrack`; every field is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct PublishTrackOptions {
+    /// Alias already handed out by `sendPublish` or `sendSubscribeOk`.
+    /// Without it the handle allocates one and releases it on `endTrack`.
+    pub(crate) track_alias: Option<u64>,
+    pub(crate) publisher_priority: u8,
+    pub(crate) start_group: u64,
+    pub(crate) subgroup_id: u64,
+}
+
+impl PublishTrackOptions {
+    pub(crate) fn parse(value: JsValue) -> Result<Self, JsValue> {
+        if value.is_undefined() || value.is_null() {
+            return Ok(Self::default());
+        }
+        serde_wasm_bindgen::from_value(value)
+            .map_err(|error| js_error(format!("invalid publish track options: {error}")))
+    }
+}
+
+/// One track being published on a `MOQTClient` session. Owns the track
+/// alias, the group / object counters and the subgroup stream writers, so
+/// a frame is one `sendObject` call. Await each call before the next.
+#[wasm_bindgen]
+pub struct PublishTrackHandle {
+    client: MOQTClient,
+    track_namespace: Vec<String>,
+    track_name: String,
+    publisher_priority: u8,
+    owns_track_alias: bool,
+    state: RefCell<SubgroupState>,
+    ended: Cell<bool>,
+}
+
+impl PublishTrackHandle {
+    pub(crate) fn new(
+        client: MOQTClient,
+        track_namespace: Vec<String>,
+        track_name: String,
+        track_alias: u64,
+        owns_track_alias: bool,
+        options: &PublishTrackOptions,
+    ) -> Self {
+        Self {
+            client,
+            track_namespace,
+            track_name,
+            publisher_priority: options.publisher_priority,
+            owns_track_alias,
+            state: RefCell::new(SubgroupState::starting_at(
+                track_alias,
+                options.start_group,
+                options.subgroup_id,
+            )),
+            ended: Cell::new(false),
+        }
+    }
+
+    fn ensure_active(&self) -> Result<(), JsValue> {
+        if self.ended.get() {
+            return Err(js_error("publish track has ended"));
+        }
+        Ok(())
+    }
+
+    /// Opens the current group's subgroup stream unless it is open already.
+    async fn ensure_header_sent(&self) -> Result<SubgroupState, JsValue> {
+        let state = self.state.borrow().clone();
+        if !state.header_sent() {
+            self.client
+                .send_subgroup_header(
+                    state.track_alias(),
+                    state.group_id(),
+                    state.subgroup_id(),
+                    self.publisher_priority,
+                )
+                .await?;
+            self.state.borrow_mut().mark_header_sent();
+        }
+        Ok(state)
+    }
+
+    /// Writes `status` as the next object, which closes the subgroup stream.
+    async fn send_closing_status(&self, status: ObjectStatus) -> Result<(), JsValue> {
+        let state = self.ensure_header_sent().await?;
+        self.client
+            .send_subgroup_object(
+                state.track_alias(),
+                state.group_id(),
+                state.subgroup_id(),
+                state.object_id(),
+                Some(status as u8),
+                Vec::new(),
+                JsValue::UNDEFINED,
+            )
+            .await
+    }
+}
+
+#[wasm_bindgen]
+impl PublishTrackHandle {
+    #[wasm_bindgen(getter, js_name = trackAlias)]
+    pub fn track_alias(&self) -> u64 {
+        self.state.borrow().track_alias()
+    }
+
+    #[wasm_bindgen(getter, js_name = trackNamespace)]
+    pub fn track_namespace(&self) -> Vec<String> {
+        self.track_namespace.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = trackName)]
+    pub fn track_name(&self) -> String {
+        self.track_name.clone()
+    }
+
+    /// Group and object the next `sendObject` writes.
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> SubgroupState {
+        self.state.borrow().clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ended(&self) -> bool {
+        self.ended.get()
+    }
+
+    /// The track's `MOQTClient.getSendBufferedAmount`.
+    #[wasm_bindgen(getter, js_name = bufferedAmount)]
+    pub fn buffered_amount(&self) -> usize {
+        self.client.get_send_buffered_amount(self.track_alias())
+    }
+
+    /// Sends `payload` as the next object of the current group, opening the
+    /// group's subgroup stream first if needed. Resolves to the object id.
+    #[wasm_bindgen(js_name = sendObject)]
+    pub async fn send_object(
+        &self,
+        payload: Vec<u8>,
+        #[wasm_bindgen(unchecked_optional_param_type = "LocHeader | null")] loc_header: JsValue,
+    ) -> Result<u64, JsValue> {
+        self.ensure_active()?;
+        let state = self.ensure_header_sent().await?;
+        self.client
+            .send_subgroup_object(
+                state.track_alias(),
+                state.group_id(),
+                state.subgroup_id(),
+                state.object_id(),
+                None,
+                payload,
+                loc_header,
+            )
+            .await?;
+        self.state.borrow_mut().increment_object_id();
+        Ok(state.object_id())
+    }
+
+    /// Ends the current group with END_OF_GROUP; the next object starts the
+    /// following group. A group without objects is skipped silently.
+    #[wasm_bindgen(js_name = endGroup)]
+    pub async fn end_group(&self) -> Result<(), JsValue> {
+        self.ensure_active()?;
+        if self.state.borrow().header_sent() {
+            self.send_closing_status(ObjectStatus::EndOfGroup).await?;
+        }
+        self.state.borrow_mut().advance_group();
+        Ok(())
+    }
+
+    /// Ends the track with END_OF_TRACK. The handle cannot send afterwards,
+    /// and an alias it allocated itself goes back to the client's pool.
+    #[wasm_bindgen(js_name = endTrack)]
+    pub async fn end_track(&self) -> Result<(), JsValue> {
+        self.ensure_active()?;
+        self.ended.set(true);
+        let result = self.send_closing_status(ObjectStatus::EndOfTrack).await;
+        if self.owns_track_alias {
+            self.client.release_track_alias(self.track_alias());
+        }
+        result
+    }
+}
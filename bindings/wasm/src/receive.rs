@@ -0,0 +1,102 @@
+mod control;
+mod namespace_messages;
+mod objects;
+mod uni_stream;
+
+use std::{cell::RefCell, rc::Rc};
+
+use bytes::BytesMut;
+use moqt::wire::{ObjectDatagram, ObjectDatagramPayload, take_control_message};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{ReadableStream, ReadableStreamDefaultReader};
+
+use crate::{
+    client::StreamWriters,
+    client_state::ClientState,
+    events::MOQTCallbacks,
+    js_error,
+    stream_reader::{StreamReader, read_datagram},
+};
+use control::handle_control_message;
+use objects::emit_object_datagram;
+use uni_stream::uni_directional_stream_read_thread;
+
+pub(crate) async fn receive_unidirectional_thread(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    state: Rc<RefCell<ClientState>>,
+    reader: &ReadableStreamDefaultReader,
+) -> Result<(), JsValue> {
+    while let Some(value) = read_reader_value(reader).await? {
+        let stream = ReadableStream::from(value);
+        let callbacks = callbacks.clone();
+        let state = state.clone();
+        let mut stream_reader = StreamReader::new(&stream)?;
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = uni_directional_stream_read_thread(callbacks, state, &mut stream_reader).await;
+        });
+    }
+    Ok(())
+}
+
+pub(crate) async fn control_stream_read_thread(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    state: Rc<RefCell<ClientState>>,
+    stream_writers: StreamWriters,
+    reader: &mut StreamReader,
+) -> Result<(), JsValue> {
+    let mut buf = BytesMut::new();
+
+    while reader.read_into(&mut buf).await? {
+        while let Some((message_type, payload)) =
+            take_control_message(&mut buf).map_err(|error| js_error(error.to_string()))?
+        {
+            handle_control_message(
+                callbacks.clone(),
+                state.clone(),
+                &stream_writers,
+                message_type,
+                payload,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn datagram_read_thread(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    state: Rc<RefCell<ClientState>>,
+    reader: &ReadableStreamDefaultReader,
+) -> Result<(), JsValue> {
+    let mut buf = BytesMut::new();
+    while read_datagram(reader, &mut buf).await? {
+        if let Some(datagram) = ObjectDatagram::decode(&mut buf) {
+            let payload_len = match datagram.field.payload() {
+                ObjectDatagramPayload::Payload(payload) => payload.len(),
+                ObjectDatagramPayload::Status(_) => 0,
+            };
+            state
+                .borrow_mut()
+                .traffic
+                .record_received(datagram.track_alias, payload_len);
+            let receiver = state.borrow().track_receiver(datagram.track_alias);
+            emit_object_datagram(callbacks.clone(), receiver, datagram)?;
+        }
+    }
+    Ok(())
+}
+
+async fn read_reader_value(
+    reader: &ReadableStreamDefaultReader,
+) -> Result<Option<JsValue>, JsValue> {
+    let result = JsFuture::from(reader.read()).await?;
+    let done = js_sys::Boolean::from(js_sys::Reflect::get(&result, &JsValue::from_str("done"))?)
+        .value_of();
+    if done {
+        return Ok(None);
+    }
+    let value = js_sys::Reflect::get(&result, &JsValue::from_str("value"))?;
+    Ok(Some(value))
+}
@@ -0,0 +1,222 @@
+use std::{cell::RefCell, io::Cursor, rc::Rc};
+
+use anyhow::{Result, anyhow};
+use bytes::BytesMut;
+use moqt::wire::{
+    BufGetExt, ControlMessageType, FetchOk, GoAway, MaxRequestId, Publish, PublishDone, PublishOk,
+    RequestError, ServerSetup, Subscribe, SubscribeOk, TrackStatus,
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use super::namespace_messages::handle_namespace_message;
+
+use crate::{
+    FetchOkMessage, PublishDoneMessage, PublishMessage, PublishOkMessage, RequestErrorMessage,
+    ServerSetupMessage, SubscribeMessage, SubscribeOkMessage, TrackStatusMessage,
+    client::StreamWriters,
+    client_state::ClientState,
+    events::{ClientEvent, MOQTCallbacks, dispatch},
+    js_error,
+};
+
+pub(super) async fn handle_control_message(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    state: Rc<RefCell<ClientState>>,
+    stream_writers: &StreamWriters,
+    message_type: ControlMessageType,
+    payload: BytesMut,
+) -> Result<(), JsValue> {
+    let mut cursor = Cursor::new(payload.as_ref());
+
+    match message_type {
+        ControlMessageType::ServerSetup => {
+            let message = ServerSetup::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode SERVER_SETUP"))?;
+            state
+                .borrow_mut()
+                .configure(message.setup_parameters.max_request_id);
+            state.borrow_mut().settle_server_setup();
+            dispatch(&callbacks, ClientEvent::ServerSetup, None, || {
+                vec![JsValue::from(ServerSetupMessage::from(&message))]
+            });
+        }
+        ControlMessageType::PublishNamespace
+        | ControlMessageType::PublishNamespaceDone
+        | ControlMessageType::PublishNamespaceOk
+        | ControlMessageType::PublishNamespaceError
+        | ControlMessageType::SubscribeNamespaceOk
+        | ControlMessageType::SubscribeNamespaceError => {
+            handle_namespace_message(&callbacks, &state, message_type, &mut cursor)?;
+        }
+        ControlMessageType::Publish => {
+            let message =
+                Publish::decode(&mut cursor).ok_or_else(|| js_error("failed to decode PUBLISH"))?;
+            dispatch(&callbacks, ClientEvent::Publish, None, || {
+                vec![JsValue::from(PublishMessage::from(&message))]
+            });
+        }
+        ControlMessageType::PublishOk => {
+            let message = PublishOk::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode PUBLISH_OK"))?;
+            state
+                .borrow_mut()
+                .finish_publish_request(message.request_id, true);
+            dispatch(&callbacks, ClientEvent::PublishResponse, None, || {
+                vec![JsValue::from(PublishOkMessage::from(&message))]
+            });
+        }
+        ControlMessageType::PublishError => {
+            let message = RequestError::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode PUBLISH_ERROR"))?;
+            state
+                .borrow_mut()
+                .finish_publish_request(message.request_id, false);
+            dispatch(&callbacks, ClientEvent::PublishResponse, None, || {
+                vec![JsValue::from(RequestErrorMessage::from(&message))]
+            });
+        }
+        ControlMessageType::Subscribe => {
+            let message = Subscribe::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode SUBSCRIBE"))?;
+            let validation_code = state.borrow().validate_incoming_subscribe(&message);
+            if validation_code == 0 {
+                state.borrow_mut().register_incoming_subscribe(&message);
+            }
+            dispatch(&callbacks, ClientEvent::Subscribe, None, || {
+                vec![
+                    JsValue::from(SubscribeMessage::from(&message)),
+                    JsValue::from_bool(validation_code == 0),
+                    JsValue::from_f64(validation_code as f64),
+                ]
+            });
+        }
+        ControlMessageType::SubscribeOk => {
+            let message = SubscribeOk::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode SUBSCRIBE_OK"))?;
+            state
+                .borrow_mut()
+                .activate_outgoing_subscription(message.request_id, message.track_alias);
+            state
+                .borrow_mut()
+                .settle_subscribe(message.request_id, Ok((&message).into()));
+            dispatch(&callbacks, ClientEvent::SubscribeResponse, None, || {
+                vec![JsValue::from(SubscribeOkMessage::from(&message))]
+            });
+        }
+        ControlMessageType::SubscribeError => {
+            let message = RequestError::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode SUBSCRIBE_ERROR"))?;
+            let receiver = state.borrow_mut().take_track_receiver(message.request_id);
+            state
+                .borrow_mut()
+                .remove_outgoing_subscription(message.request_id);
+            state
+                .borrow_mut()
+                .settle_subscribe(message.request_id, Err((&message).into()));
+            if let Some(receiver) = receiver {
+                receiver.finish(message.error_code, &message.reason_phrase);
+            }
+            dispatch(&callbacks, ClientEvent::SubscribeResponse, None, || {
+                vec![JsValue::from(RequestErrorMessage::from(&message))]
+            });
+        }
+        ControlMessageType::PublishDone => {
+            let message = PublishDone::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode PUBLISH_DONE"))?;
+            let receiver = state.borrow_mut().take_track_receiver(message.request_id);
+            state
+                .borrow_mut()
+                .remove_outgoing_subscription(message.request_id);
+            let final_location = receiver
+                .as_ref()
+                .and_then(|receiver| receiver.largest_location());
+            if let Some(receiver) = receiver {
+                receiver.finish(message.status_code, &message.reason_phrase);
+            }
+            dispatch(&callbacks, ClientEvent::PublishDone, None, || {
+                vec![JsValue::from(PublishDoneMessage::new(
+                    &message,
+                    final_location,
+                ))]
+            });
+        }
+        ControlMessageType::UnSubscribe => {
+            let request_id =
+                decode_request_id(&mut cursor).map_err(|error| js_error(error.to_string()))?;
+            let track_alias = state.borrow_mut().remove_incoming_subscribe(request_id);
+            if let Some(track_alias) = track_alias {
+                abort_track_streams(stream_writers, track_alias).await;
+            }
+            dispatch(&callbacks, ClientEvent::IncomingUnsubscribe, None, || {
+                vec![JsValue::from(js_sys::BigInt::from(request_id))]
+            });
+        }
+        ControlMessageType::FetchOk => {
+            let message = FetchOk::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode FETCH_OK"))?;
+            dispatch(&callbacks, ClientEvent::FetchResponse, None, || {
+                vec![JsValue::from(FetchOkMessage::from(&message))]
+            });
+        }
+        ControlMessageType::FetchError => {
+            let message = RequestError::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode FETCH_ERROR"))?;
+            dispatch(&callbacks, ClientEvent::FetchResponse, None, || {
+                vec![JsValue::from(RequestErrorMessage::from(&message))]
+            });
+        }
+        ControlMessageType::TrackStatus => {
+            let message = TrackStatus::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode TRACK_STATUS"))?;
+            dispatch(&callbacks, ClientEvent::TrackStatus, None, || {
+                vec![JsValue::from(TrackStatusMessage::from(&message))]
+            });
+        }
+        ControlMessageType::TrackStatusError => {
+            let message = RequestError::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode TRACK_STATUS_ERROR"))?;
+            dispatch(&callbacks, ClientEvent::TrackStatus, None, || {
+                vec![JsValue::from(RequestErrorMessage::from(&message))]
+            });
+        }
+        ControlMessageType::MaxSubscribeId => {
+            let message = MaxRequestId::decode(&mut cursor)
+                .ok_or_else(|| js_error("failed to decode MAX_REQUEST_ID"))?;
+            state.borrow_mut().raise_max_request_id(message.request_id);
+        }
+        ControlMessageType::GoAway => {
+            let message =
+                GoAway::decode(&mut cursor).ok_or_else(|| js_error("failed to decode GOAWAY"))?;
+            console_log!("Received GOAWAY: {:?}", message.new_session_uri);
+            state.borrow_mut().going_away = Some(message.new_session_uri.clone());
+            dispatch(&callbacks, ClientEvent::Goaway, None, || {
+                vec![JsValue::from_str(&message.new_session_uri)]
+            });
+        }
+        _ => {
+            console_log!("Unhandled control message: {:?}", message_type);
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_request_id(cursor: &mut Cursor<&[u8]>) -> Result<u64> {
+    cursor
+        .try_get_varint()
+        .map_err(|error| anyhow!("failed to decode request id: {error}"))
+}
+
+/// Resets the subgroup streams still open on `track_alias`, whose subscriber
+/// has unsubscribed, so nothing more is written to them.
+async fn abort_track_streams(stream_writers: &StreamWriters, track_alias: u64) {
+    let writers: Vec<_> = stream_writers
+        .borrow_mut()
+        .extract_if(|(alias, _, _), _| *alias == track_alias)
+        .map(|(_, writer)| writer)
+        .collect();
+    for writer in writers {
+        let _ = JsFuture::from(writer.abort()).await;
+    }
+}
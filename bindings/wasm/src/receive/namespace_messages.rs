@@ -0,0 +1,161 @@
+use std::{cell::RefCell, io::Cursor};
+
+use moqt::wire::{
+    ControlMessageType, NamespaceOk, PublishNamespace, PublishNamespaceDone, RequestError,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    NamespaceDeltaMessage, NamespaceOkMessage, PublishNamespaceDoneMessage,
+    PublishNamespaceMessage, RequestErrorMessage,
+    client_state::ClientState,
+    events::{ClientEvent, MOQTCallbacks, dispatch},
+    js_error,
+};
+
+/// Handles the namespace messages: the peer's PUBLISH_NAMESPACE /
+/// PUBLISH_NAMESPACE_DONE and the responses to this client's
+/// PUBLISH_NAMESPACE / SUBSCRIBE_NAMESPACE.
+pub(super) fn handle_namespace_message(
+    callbacks: &RefCell<MOQTCallbacks>,
+    state: &RefCell<ClientState>,
+    message_type: ControlMessageType,
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<(), JsValue> {
+    match message_type {
+        ControlMessageType::PublishNamespace => {
+            let message = PublishNamespace::decode(cursor)
+                .ok_or_else(|| js_error("failed to decode PUBLISH_NAMESPACE"))?;
+            let duplicate_of = state
+                .borrow_mut()
+                .record_peer_namespace(&message.track_namespace, message.request_id);
+            dispatch(callbacks, ClientEvent::PublishNamespace, None, || {
+                vec![JsValue::from(PublishNamespaceMessage::new(
+                    &message,
+                    duplicate_of,
+                ))]
+            });
+            let prefixes = state
+                .borrow_mut()
+                .add_announced_namespace(&message.track_namespace);
+            emit_namespace_deltas(
+                callbacks,
+                ClientEvent::NamespaceAdded,
+                &message.track_namespace,
+                prefixes,
+            );
+        }
+        ControlMessageType::PublishNamespaceDone => {
+            let message = PublishNamespaceDone::decode(cursor)
+                .ok_or_else(|| js_error("failed to decode PUBLISH_NAMESPACE_DONE"))?;
+            let request_id = state
+                .borrow_mut()
+                .withdraw_peer_namespace(&message.track_namespace);
+            match request_id {
+                Some(request_id) => {
+                    dispatch(callbacks, ClientEvent::PublishNamespaceDone, None, || {
+                        vec![JsValue::from(PublishNamespaceDoneMessage::new(
+                            &message, request_id,
+                        ))]
+                    });
+                }
+                None => console_log!(
+                    "Ignoring PUBLISH_NAMESPACE_DONE for unannounced namespace {:?}",
+                    message.track_namespace
+                ),
+            }
+            let prefixes = state
+                .borrow_mut()
+                .remove_announced_namespace(&message.track_namespace);
+            emit_namespace_deltas(
+                callbacks,
+                ClientEvent::NamespaceRemoved,
+                &message.track_namespace,
+                prefixes,
+            );
+        }
+        ControlMessageType::PublishNamespaceOk => {
+            let message = NamespaceOk::decode(cursor)
+                .ok_or_else(|| js_error("failed to decode PUBLISH_NAMESPACE_OK"))?;
+            state
+                .borrow_mut()
+                .finish_publish_namespace_request(message.request_id, true);
+            state
+                .borrow_mut()
+                .settle_namespace_request(message.request_id, Ok((&message).into()));
+            dispatch(
+                callbacks,
+                ClientEvent::PublishNamespaceResponse,
+                None,
+                || vec![JsValue::from(NamespaceOkMessage::from(&message))],
+            );
+        }
+        ControlMessageType::PublishNamespaceError => {
+            let message = RequestError::decode(cursor)
+                .ok_or_else(|| js_error("failed to decode PUBLISH_NAMESPACE_ERROR"))?;
+            state
+                .borrow_mut()
+                .finish_publish_namespace_request(message.request_id, false);
+            state
+                .borrow_mut()
+                .settle_namespace_request(message.request_id, Err((&message).into()));
+            dispatch(
+                callbacks,
+                ClientEvent::PublishNamespaceResponse,
+                None,
+                || vec![JsValue::from(RequestErrorMessage::from(&message))],
+            );
+        }
+        ControlMessageType::SubscribeNamespaceOk => {
+            let message = NamespaceOk::decode(cursor)
+                .ok_or_else(|| js_error("failed to decode SUBSCRIBE_NAMESPACE_OK"))?;
+            state
+                .borrow_mut()
+                .finish_subscribe_namespace_request(message.request_id, true);
+            state
+                .borrow_mut()
+                .settle_namespace_request(message.request_id, Ok((&message).into()));
+            dispatch(
+                callbacks,
+                ClientEvent::SubscribeNamespaceResponse,
+                None,
+                || vec![JsValue::from(NamespaceOkMessage::from(&message))],
+            );
+        }
+        ControlMessageType::SubscribeNamespaceError => {
+            let message = RequestError::decode(cursor)
+                .ok_or_else(|| js_error("failed to decode SUBSCRIBE_NAMESPACE_ERROR"))?;
+            state
+                .borrow_mut()
+                .finish_subscribe_namespace_request(message.request_id, false);
+            state
+                .borrow_mut()
+                .settle_namespace_request(message.request_id, Err((&message).into()));
+            dispatch(
+                callbacks,
+                ClientEvent::SubscribeNamespaceResponse,
+                None,
+                || vec![JsValue::from(RequestErrorMessage::from(&message))],
+            );
+        }
+        // Only namespace messages are routed here.
+        _ => unreachable!("not a namespace message: {message_type:?}"),
+    }
+    Ok(())
+}
+
+fn emit_namespace_deltas(
+    callbacks: &RefCell<MOQTCallbacks>,
+    event: ClientEvent,
+    namespace: &[String],
+    prefixes: Vec<Vec<String>>,
+) {
+    for prefix in prefixes {
+        dispatch(callbacks, event, None, || {
+            vec![JsValue::from(NamespaceDeltaMessage::new(
+                namespace.to_vec(),
+                prefix,
+            ))]
+        });
+    }
+}
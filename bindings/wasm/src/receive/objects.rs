@@ -0,0 +1,271 @@
+use std::{cell::RefCell, rc::Rc};
+
+use moqt::wire::{
+    DatagramField, FetchObjectField, ObjectDatagram, ObjectStatus, SubgroupHeader, SubgroupId,
+    SubgroupObject, SubgroupObjectField, SubgroupStreamObject,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    FetchObjectMessage, ObjectDatagramMessage, ObjectDatagramStatusMessage, SubgroupHeaderMessage,
+    SubgroupObjectMessage, SubgroupStreamObjectMessage,
+    events::{ClientEvent, MOQTCallbacks, dispatch},
+    subscribe_track::TrackReceiver,
+};
+
+pub(super) fn emit_object_datagram(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    receiver: Option<Rc<TrackReceiver>>,
+    datagram: ObjectDatagram,
+) -> Result<(), JsValue> {
+    let (track_alias, group_id) = (datagram.track_alias, datagram.group_id);
+    let (message, object_id, payload_len, is_status) = object_datagram_message(datagram);
+    if let Some(receiver) = receiver {
+        if !receiver.is_done() {
+            receiver.deliver(group_id, object_id.unwrap_or(0), payload_len, message);
+        }
+        return Ok(());
+    }
+    let event = if is_status {
+        ClientEvent::ObjectDatagramStatus
+    } else {
+        ClientEvent::ObjectDatagram
+    };
+    dispatch(&callbacks, event, Some(track_alias), || vec![message]);
+    Ok(())
+}
+
+/// The JS message for `datagram`, with its Object ID, payload length and
+/// whether it carries an Object Status rather than a payload.
+fn object_datagram_message(datagram: ObjectDatagram) -> (JsValue, Option<u64>, usize, bool) {
+    match datagram.field {
+        DatagramField::Payload0x00 {
+            object_id,
+            publisher_priority,
+            payload,
+        }
+        | DatagramField::Payload0x02WithEndOfGroup {
+            object_id,
+            publisher_priority,
+            payload,
+        }
+        | DatagramField::Payload0x04 {
+            object_id,
+            publisher_priority,
+            payload,
+        } => {
+            let wrapper = ObjectDatagramMessage::new(
+                datagram.track_alias,
+                datagram.group_id,
+                Some(object_id),
+                publisher_priority,
+                &payload,
+                packages::loc::LocHeader::default(),
+            );
+            (
+                JsValue::from(wrapper),
+                Some(object_id),
+                payload.len(),
+                false,
+            )
+        }
+        DatagramField::Payload0x01 {
+            object_id,
+            publisher_priority,
+            extension_headers,
+            payload,
+        }
+        | DatagramField::Payload0x03WithEndOfGroup {
+            object_id,
+            publisher_priority,
+            extension_headers,
+            payload,
+        } => {
+            let wrapper = ObjectDatagramMessage::new(
+                datagram.track_alias,
+                datagram.group_id,
+                Some(object_id),
+                publisher_priority,
+                &payload,
+                crate::loc::extension_headers_to_loc_header(&extension_headers),
+            );
+            (
+                JsValue::from(wrapper),
+                Some(object_id),
+                payload.len(),
+                false,
+            )
+        }
+        DatagramField::Payload0x05 {
+            publisher_priority,
+            extension_headers,
+            payload,
+        }
+        | DatagramField::Payload0x07WithEndOfGroup {
+            publisher_priority,
+            extension_headers,
+            payload,
+        } => {
+            let wrapper = ObjectDatagramMessage::new(
+                datagram.track_alias,
+                datagram.group_id,
+                None,
+                publisher_priority,
+                &payload,
+                crate::loc::extension_headers_to_loc_header(&extension_headers),
+            );
+            (JsValue::from(wrapper), None, payload.len(), false)
+        }
+        DatagramField::Payload0x06WithEndOfGroup {
+            publisher_priority,
+            payload,
+        } => {
+            let wrapper = ObjectDatagramMessage::new(
+                datagram.track_alias,
+                datagram.group_id,
+                None,
+                publisher_priority,
+                &payload,
+                packages::loc::LocHeader::default(),
+            );
+            (JsValue::from(wrapper), None, payload.len(), false)
+        }
+        DatagramField::Status0x20 {
+            object_id,
+            publisher_priority,
+            status,
+        } => {
+            let wrapper = ObjectDatagramStatusMessage::new(
+                datagram.track_alias,
+                datagram.group_id,
+                Some(object_id),
+                publisher_priority,
+                status,
+                packages::loc::LocHeader::default(),
+            );
+            (JsValue::from(wrapper), Some(object_id), 0, true)
+        }
+        DatagramField::Status0x21 {
+            object_id,
+            publisher_priority,
+            extension_headers,
+            status,
+        } => {
+            let wrapper = ObjectDatagramStatusMessage::new(
+                datagram.track_alias,
+                datagram.group_id,
+                Some(object_id),
+                publisher_priority,
+                status,
+                crate::loc::extension_headers_to_loc_header(&extension_headers),
+            );
+            (JsValue::from(wrapper), Some(object_id), 0, true)
+        }
+    }
+}
+
+pub(super) fn emit_subgroup_header(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    header: &SubgroupHeader,
+) -> Result<(), JsValue> {
+    dispatch(
+        &callbacks,
+        ClientEvent::SubgroupHeader,
+        Some(header.track_alias),
+        || {
+            let subgroup_id = match header.subgroup_id {
+                SubgroupId::Value(value) => Some(value),
+                _ => None,
+            };
+            vec![JsValue::from(SubgroupHeaderMessage::new(
+                header.track_alias,
+                header.group_id,
+                subgroup_id,
+                header.publisher_priority,
+            ))]
+        },
+    );
+    Ok(())
+}
+
+pub(super) fn emit_fetch_object(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    request_id: u64,
+    field: &FetchObjectField,
+) -> Result<(), JsValue> {
+    dispatch(&callbacks, ClientEvent::FetchObject, None, || {
+        vec![JsValue::from(FetchObjectMessage::new(request_id, field))]
+    });
+    Ok(())
+}
+
+pub(super) fn emit_subgroup_stream_object(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    object: SubgroupStreamObject,
+) {
+    let track_alias = object.track_alias;
+    dispatch(
+        &callbacks,
+        ClientEvent::SubgroupStreamObject,
+        Some(track_alias),
+        || vec![JsValue::from(SubgroupStreamObjectMessage::from(object))],
+    );
+}
+
+pub(super) fn deliver_subgroup_stream_object(
+    receiver: &TrackReceiver,
+    object: SubgroupStreamObject,
+) {
+    let payload_len = match &object.field.subgroup_object {
+        SubgroupObject::Payload { data, .. } => data.len(),
+        SubgroupObject::Status { .. } => 0,
+    };
+    let (group_id, object_id) = (object.group_id, object.object_id);
+    let wrapper = SubgroupStreamObjectMessage::from(object);
+    receiver.deliver(group_id, object_id, payload_len, JsValue::from(wrapper));
+}
+
+pub(super) fn emit_subgroup_object(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    header: &SubgroupHeader,
+    field: SubgroupObjectField,
+    object_id_delta: u64,
+) -> Result<(), JsValue> {
+    dispatch(
+        &callbacks,
+        ClientEvent::SubgroupObject,
+        Some(header.track_alias),
+        || {
+            let loc_header = crate::loc::extension_headers_to_loc_header(&field.extension_headers);
+            let subgroup_id = match header.subgroup_id {
+                SubgroupId::Value(value) => Some(value),
+                _ => None,
+            };
+            let wrapper = match field.subgroup_object {
+                SubgroupObject::Payload { data, .. } => SubgroupObjectMessage::new(
+                    subgroup_id,
+                    object_id_delta,
+                    None,
+                    &data,
+                    loc_header,
+                ),
+                SubgroupObject::Status { code, .. } => {
+                    let status = ObjectStatus::try_from(code as u8).ok();
+                    SubgroupObjectMessage::new(
+                        subgroup_id,
+                        object_id_delta,
+                        status,
+                        &[],
+                        loc_header,
+                    )
+                }
+            };
+            vec![
+                JsValue::from(js_sys::BigInt::from(header.track_alias)),
+                JsValue::from(js_sys::BigInt::from(header.group_id)),
+                JsValue::from(wrapper),
+            ]
+        },
+    );
+    Ok(())
+}
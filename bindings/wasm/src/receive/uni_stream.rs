@@ -0,0 +1,163 @@
+use std::{cell::RefCell, io::Cursor, rc::Rc};
+
+use anyhow::{Result, anyhow};
+use bytes::{Buf, BytesMut};
+use moqt::wire::{
+    BufGetExt, FetchHeader, FetchObjectField, SubgroupHeader, SubgroupObject, SubgroupObjectField,
+    SubgroupStreamContext,
+};
+use wasm_bindgen::prelude::*;
+
+use super::objects::{
+    deliver_subgroup_stream_object, emit_fetch_object, emit_subgroup_header, emit_subgroup_object,
+    emit_subgroup_stream_object,
+};
+use crate::{
+    client_state::ClientState, events::MOQTCallbacks, js_error, stream_reader::StreamReader,
+    subscribe_track::TrackReceiver,
+};
+
+pub(super) async fn uni_directional_stream_read_thread(
+    callbacks: Rc<RefCell<MOQTCallbacks>>,
+    state: Rc<RefCell<ClientState>>,
+    reader: &mut StreamReader,
+) -> Result<(), JsValue> {
+    let mut buf = BytesMut::new();
+    // fetch stream state
+    let mut fetch_request_id: Option<u64> = None;
+    // subgroup stream state
+    let mut subgroup_header: Option<SubgroupHeader> = None;
+    let mut subgroup_context: Option<SubgroupStreamContext> = None;
+    // set when the stream's track was subscribed through `subscribeTrack`
+    let mut track_receiver: Option<Rc<TrackReceiver>> = None;
+    // None = undecided, Some(true) = fetch, Some(false) = subgroup
+    let mut is_fetch_stream: Option<bool> = None;
+    // bytes `buf` needs before the next subgroup object is decoded again
+    let mut pending_object_len = 0;
+
+    while reader.read_into(&mut buf).await? {
+        // Detect stream type from the first varint if not yet determined.
+        if is_fetch_stream.is_none() {
+            let first_varint = {
+                let mut cursor = Cursor::new(buf.as_ref());
+                cursor.try_get_varint().ok()
+            };
+            match first_varint {
+                Some(FetchHeader::TYPE) => is_fetch_stream = Some(true),
+                Some(_) => is_fetch_stream = Some(false),
+                None => continue,
+            }
+        }
+
+        if is_fetch_stream == Some(true) {
+            // Parse FetchHeader to obtain request_id if not yet done.
+            if fetch_request_id.is_none() {
+                let mut cursor = Cursor::new(buf.as_ref());
+                match FetchHeader::decode(&mut cursor) {
+                    Ok(header) => {
+                        let consumed = cursor.position() as usize;
+                        buf.advance(consumed);
+                        fetch_request_id = Some(header.request_id);
+                    }
+                    Err(moqt::wire::DecodeError::NeedMoreData) => continue,
+                    Err(moqt::wire::DecodeError::Fatal(error)) => return Err(js_error(error)),
+                }
+            }
+            let request_id = fetch_request_id.expect("request_id");
+            loop {
+                match FetchObjectField::decode(&mut buf) {
+                    Ok(field) => {
+                        emit_fetch_object(callbacks.clone(), request_id, &field)?;
+                        continue;
+                    }
+                    Err(moqt::wire::DecodeError::NeedMoreData) => break,
+                    Err(moqt::wire::DecodeError::Fatal(error)) => return Err(js_error(error)),
+                }
+            }
+        } else {
+            loop {
+                if subgroup_header.is_none() {
+                    match take_subgroup_header(&mut buf) {
+                        Ok(Some(parsed_header)) => {
+                            emit_subgroup_header(callbacks.clone(), &parsed_header)?;
+                            track_receiver =
+                                state.borrow().track_receiver(parsed_header.track_alias);
+                            subgroup_context =
+                                Some(SubgroupStreamContext::new(parsed_header.clone()));
+                            subgroup_header = Some(parsed_header);
+                            continue;
+                        }
+                        Ok(None) => break,
+                        Err(error) => return Err(js_error(error.to_string())),
+                    }
+                }
+
+                if buf.len() < pending_object_len {
+                    break;
+                }
+                let parsed_header = subgroup_header.clone().expect("subgroup header");
+                match SubgroupObjectField::decode(parsed_header.message_type, &mut buf) {
+                    Ok(field) => {
+                        pending_object_len = 0;
+                        let payload_len = match &field.subgroup_object {
+                            SubgroupObject::Payload { data, .. } => data.len(),
+                            SubgroupObject::Status { .. } => 0,
+                        };
+                        state
+                            .borrow_mut()
+                            .traffic
+                            .record_received(parsed_header.track_alias, payload_len);
+                        let object_id_delta = field.object_id_delta;
+                        let object = subgroup_context
+                            .as_mut()
+                            .expect("subgroup context")
+                            .next_object(field.clone());
+                        if let Some(receiver) = &track_receiver {
+                            if receiver.is_done() {
+                                break;
+                            }
+                            deliver_subgroup_stream_object(receiver, object);
+                            continue;
+                        }
+                        emit_subgroup_stream_object(callbacks.clone(), object);
+                        emit_subgroup_object(
+                            callbacks.clone(),
+                            &parsed_header,
+                            field,
+                            object_id_delta,
+                        )?;
+                        continue;
+                    }
+                    Err(moqt::wire::DecodeError::NeedMoreData) => {
+                        pending_object_len =
+                            SubgroupObjectField::required_len(parsed_header.message_type, &buf);
+                        break;
+                    }
+                    Err(moqt::wire::DecodeError::Fatal(error)) => return Err(js_error(error)),
+                }
+            }
+            // An unsubscribed or finished track stops reading its streams.
+            if track_receiver
+                .as_ref()
+                .is_some_and(|receiver| receiver.is_done())
+            {
+                break;
+            }
+        }
+    }
+
+    reader.cancel().await;
+    Ok(())
+}
+
+fn take_subgroup_header(buf: &mut BytesMut) -> Result<Option<SubgroupHeader>> {
+    let mut cursor = Cursor::new(buf.as_ref());
+    match SubgroupHeader::decode(&mut cursor) {
+        Ok(header) => {
+            buf.advance(cursor.position() as usize);
+            Ok(Some(header))
+        }
+        Err(moqt::wire::DecodeError::NeedMoreData) => Ok(None),
+        Err(moqt::wire::DecodeError::Fatal(error)) => Err(anyhow!(error)),
+    }
+}
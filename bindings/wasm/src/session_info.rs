@@ -0,0 +1,96 @@
+use wasm_bindgen::prelude::*;
+
+/// One subscription of the session as `getSubscriptions` reports it.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct SubscriptionInfo {
+    outgoing: bool,
+    request_id: u64,
+    track_namespace: Vec<String>,
+    track_name: String,
+    track_alias: Option<u64>,
+}
+
+#[wasm_bindgen]
+impl SubscriptionInfo {
+    /// `"outgoing"` for SUBSCRIBEs this client sent, `"incoming"` for those
+    /// it received as publisher.
+    #[wasm_bindgen(getter, unchecked_return_type = "\"outgoing\" | \"incoming\"")]
+    pub fn direction(&self) -> String {
+        if self.outgoing {
+            "outgoing"
+        } else {
+            "incoming"
+        }
+        .to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = requestId)]
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    #[wasm_bindgen(getter, js_name = trackNamespace)]
+    pub fn track_namespace(&self) -> Vec<String> {
+        self.track_namespace.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = trackName)]
+    pub fn track_name(&self) -> String {
+        self.track_name.clone()
+    }
+
+    /// `undefined` until SUBSCRIBE_OK assigns it.
+    #[wasm_bindgen(getter, js_name = trackAlias)]
+    pub fn track_alias(&self) -> Option<u64> {
+        self.track_alias
+    }
+}
+
+impl SubscriptionInfo {
+    pub(crate) fn new(
+        outgoing: bool,
+        request_id: u64,
+        track_namespace: Vec<String>,
+        track_name: String,
+        track_alias: Option<u64>,
+    ) -> Self {
+        Self {
+            outgoing,
+            request_id,
+            track_namespace,
+            track_name,
+            track_alias,
+        }
+    }
+}
+
+/// A PUBLISH_NAMESPACE the peer sent and has not withdrawn.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct ReceivedAnnouncement {
+    request_id: u64,
+    track_namespace: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl ReceivedAnnouncement {
+    #[wasm_bindgen(getter, js_name = requestId)]
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    #[wasm_bindgen(getter, js_name = trackNamespace)]
+    pub fn track_namespace(&self) -> Vec<String> {
+        self.track_namespace.clone()
+    }
+}
+
+impl ReceivedAnnouncement {
+    pub(crate) fn new(request_id: u64, track_namespace: Vec<String>) -> Self {
+        Self {
+            request_id,
+            track_namespace,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use bytes::BytesMut;
+use wasm_bindgen::{JsCast, prelude::*};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{ReadableStream, ReadableStreamByobReader, ReadableStreamDefaultReader};
+
+/// Size of the buffer a BYOB reader fills per read.
+const BYOB_BUFFER_SIZE: u32 = 64 * 1024;
+
+/// Reads a receive stream into a caller-owned `BytesMut`.
+///
+/// WebTransport receive streams are byte streams, so they are read with a
+/// `ReadableStreamBYOBReader` into one buffer that goes back and forth
+/// between the browser and this reader instead of a new chunk per read.
+/// Browsers without BYOB support get a default reader.
+pub(crate) enum StreamReader {
+    Byob {
+        reader: ReadableStreamByobReader,
+        /// The buffer of the last read, detached from the browser again.
+        buffer: Option<js_sys::Uint8Array>,
+    },
+    Default {
+        reader: ReadableStreamDefaultReader,
+        snapshot: Vec<u8>,
+    },
+}
+
+impl StreamReader {
+    pub(crate) fn new(stream: &ReadableStream) -> Result<Self, JsValue> {
+        if let Ok(reader) = ReadableStreamByobReader::new(stream) {
+            return Ok(Self::Byob {
+                reader,
+                buffer: None,
+            });
+        }
+        Ok(Self::Default {
+            reader: ReadableStreamDefaultReader::new(stream)?,
+            snapshot: Vec::new(),
+        })
+    }
+
+    /// Appends the next bytes of the stream to `buf`. `false` at its end.
+    pub(crate) async fn read_into(&mut self, buf: &mut BytesMut) -> Result<bool, JsValue> {
+        match self {
+            Self::Byob { reader, buffer } => {
+                let view = buffer
+                    .take()
+                    .unwrap_or_else(|| js_sys::Uint8Array::new_with_length(BYOB_BUFFER_SIZE));
+                let result = JsFuture::from(reader.read_with_array_buffer_view(&view)).await?;
+                let (done, value) = read_result(&result)?;
+                let Some(filled) =
+                    value.and_then(|value| value.dyn_into::<js_sys::Uint8Array>().ok())
+                else {
+                    return Ok(!done);
+                };
+                append(buf, &filled);
+                // The read moved `view`'s memory into `filled`; reuse all of it.
+                *buffer = Some(js_sys::Uint8Array::new(&filled.buffer()));
+                Ok(!done)
+            }
+            Self::Default { reader, snapshot } => {
+                let result = JsFuture::from(reader.read()).await?;
+                let (done, value) = read_result(&result)?;
+                if done {
+                    return Ok(false);
+                }
+                if let Some(value) = value {
+                    let chunk = js_sys::Uint8Array::from(value).to_vec();
+                    buf.extend_from_slice(&normalize_stream_chunk(snapshot, chunk));
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    pub(crate) async fn cancel(&self) {
+        let cancelled = match self {
+            Self::Byob { reader, .. } => reader.cancel(),
+            Self::Default { reader, .. } => reader.cancel(),
+        };
+        let _ = JsFuture::from(cancelled).await;
+    }
+}
+
+/// Reads one datagram from `reader` into `buf`, replacing its contents, so
+/// a single buffer serves every datagram. `false` once the readable ends.
+///
+/// Datagrams keep a default reader: a BYOB read may fill its buffer from
+/// several queued datagrams, which would lose their boundaries.
+pub(crate) async fn read_datagram(
+    reader: &ReadableStreamDefaultReader,
+    buf: &mut BytesMut,
+) -> Result<bool, JsValue> {
+    let result = JsFuture::from(reader.read()).await?;
+    let (done, value) = read_result(&result)?;
+    if done {
+        return Ok(false);
+    }
+    buf.clear();
+    if let Some(value) = value {
+        append(buf, &js_sys::Uint8Array::from(value));
+    }
+    Ok(true)
+}
+
+fn read_result(result: &JsValue) -> Result<(bool, Option<JsValue>), JsValue> {
+    let done = js_sys::Reflect::get(result, &JsValue::from_str("done"))?.is_truthy();
+    let value = js_sys::Reflect::get(result, &JsValue::from_str("value"))?;
+    Ok((done, (!value.is_undefined()).then_some(value)))
+}
+
+/// Copies `chunk` onto the end of `buf` in one bulk copy.
+fn append(buf: &mut BytesMut, chunk: &js_sys::Uint8Array) {
+    let start = buf.len();
+    buf.resize(start + chunk.length() as usize, 0);
+    chunk.copy_to(&mut buf[start..]);
+}
+
+fn normalize_stream_chunk(stream_snapshot: &mut Vec<u8>, chunk: Vec<u8>) -> Vec<u8> {
+    if !stream_snapshot.is_empty()
+        && chunk.len() >= stream_snapshot.len()
+        && chunk.starts_with(stream_snapshot.as_slice())
+    {
+        let new_bytes = chunk[stream_snapshot.len()..].to_vec();
+        *stream_snapshot = chunk;
+        return new_bytes;
+    }
+
+    stream_snapshot.extend_from_slice(&chunk);
+    chunk
+}
@@ -0,0 +1,415 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+use web_sys::{ReadableStream, ReadableStreamDefaultController};
+
+use crate::{
+    MOQTClient, js_error,
+    messages::{PUBLISH_DONE_SUBSCRIPTION_ENDED, PUBLISH_DONE_TRACK_ENDED},
+};
+
+/// Options of `subscribe` and `subscribeTrack`; every field is optional.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct SubscribeOptions {
+    /// Allocated by the client when absent; see `allocateRequestId`.
+    pub(crate) request_id: Option<u64>,
+    pub(crate) subscriber_priority: u8,
+    pub(crate) group_order: u8,
+    pub(crate) filter_type: u8,
+    pub(crate) start_group: Option<u64>,
+    pub(crate) start_object: Option<u64>,
+    pub(crate) end_group: Option<u64>,
+    pub(crate) auth_info: String,
+    /// `false` subscribes paused; `resume` starts delivery.
+    pub(crate) forward: bool,
+    pub(crate) delivery_timeout: Option<u64>,
+    pub(crate) max_cache_duration: Option<u64>,
+}
+
+impl Default for SubscribeOptions {
+    fn default() -> Self {
+        Self {
+            request_id: None,
+            subscriber_priority: 0,
+            group_order: 0,
+            filter_type: 1,
+            start_group: None,
+            start_object: None,
+            end_group: None,
+            auth_info: String::new(),
+            forward: true,
+            delivery_timeout: None,
+            max_cache_duration: None,
+        }
+    }
+}
+
+impl SubscribeOptions {
+    pub(crate) fn parse(value: JsValue) -> Result<Self, JsValue> {
+        if value.is_undefined() || value.is_null() {
+            return Ok(Self::default());
+        }
+        serde_wasm_bindgen::from_value(value)
+            .map_err(|error| js_error(format!("invalid subscribe options: {error}")))
+    }
+}
+
+/// Receive counters of one subscription.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SubscribeTrackStats {
+    objects_received: u64,
+    bytes_received: u64,
+    groups_missed: u64,
+    largest_location: Option<(u64, u64)>,
+}
+
+#[wasm_bindgen]
+impl SubscribeTrackStats {
+    #[wasm_bindgen(getter, js_name = objectsReceived)]
+    pub fn objects_received(&self) -> u64 {
+        self.objects_received
+    }
+
+    #[wasm_bindgen(getter, js_name = bytesReceived)]
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
+    /// Groups skipped between the largest group received and a later one,
+    /// summed over every `onGap`.
+    #[wasm_bindgen(getter, js_name = groupsMissed)]
+    pub fn groups_missed(&self) -> u64 {
+        self.groups_missed
+    }
+
+    #[wasm_bindgen(getter, js_name = largestGroupId)]
+    pub fn largest_group_id(&self) -> Option<u64> {
+        self.largest_location.map(|(group_id, _)| group_id)
+    }
+
+    #[wasm_bindgen(getter, js_name = largestObjectId)]
+    pub fn largest_object_id(&self) -> Option<u64> {
+        self.largest_location.map(|(_, object_id)| object_id)
+    }
+}
+
+/// Receive side of a `SubscribeHandle`, shared with the client's read loops.
+/// Objects of its track go to these callbacks instead of the global ones.
+#[derive(Debug, Default)]
+pub(crate) struct TrackReceiver {
+    /// Request ID of the subscription; a reconnect re-issues it under a
+    /// new one.
+    request_id: Cell<u64>,
+    on_object: RefCell<Option<js_sys::Function>>,
+    on_gap: RefCell<Option<js_sys::Function>>,
+    on_done: RefCell<Option<js_sys::Function>>,
+    stats: Cell<SubscribeTrackStats>,
+    /// Set once the subscription is over; stream readers of the track stop.
+    done: Cell<bool>,
+    /// Controller of the `subscribeAsStream` stream, which takes the
+    /// objects in place of `onObject`.
+    stream: RefCell<Option<ReadableStreamDefaultController>>,
+}
+
+impl TrackReceiver {
+    pub(crate) fn request_id(&self) -> u64 {
+        self.request_id.get()
+    }
+
+    pub(crate) fn set_request_id(&self, request_id: u64) {
+        self.request_id.set(request_id);
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.done.get()
+    }
+
+    /// Counts an object and hands `message` to `onObject`, first reporting
+    /// the groups skipped if the object opens a group past the next one.
+    pub(crate) fn deliver(
+        &self,
+        group_id: u64,
+        object_id: u64,
+        payload_len: usize,
+        message: JsValue,
+    ) {
+        let mut stats = self.stats.get();
+        let missed = stats
+            .largest_group_id()
+            .filter(|largest| group_id > largest + 1)
+            .map(|largest| (largest + 1, group_id - 1));
+        stats.objects_received += 1;
+        stats.bytes_received += payload_len as u64;
+        if let Some((first, last)) = missed {
+            stats.groups_missed += last - first + 1;
+        }
+        if stats
+            .largest_location
+            .is_none_or(|largest| (group_id, object_id) > largest)
+        {
+            stats.largest_location = Some((group_id, object_id));
+        }
+        self.stats.set(stats);
+
+        if let Some((first, last)) = missed
+            && let Some(callback) = self.on_gap.borrow().clone()
+        {
+            let _ = callback.call2(
+                &JsValue::NULL,
+                &JsValue::from(js_sys::BigInt::from(first)),
+                &JsValue::from(js_sys::BigInt::from(last)),
+            );
+        }
+        if let Some(controller) = self.stream.borrow().as_ref() {
+            let _ = controller.enqueue_with_chunk(&message);
+        } else if let Some(callback) = self.on_object.borrow().clone() {
+            let _ = callback.call1(&JsValue::NULL, &message);
+        }
+    }
+
+    /// Opens the stream the track's objects are enqueued on from now on.
+    /// Cancelling it unsubscribes the subscription through `client`.
+    pub(crate) fn open_stream(
+        self: &Rc<Self>,
+        client: MOQTClient,
+    ) -> Result<ReadableStream, JsValue> {
+        if self.stream.borrow().is_some() {
+            return Err(js_error("the subscription already has a stream"));
+        }
+        // `start` runs inside the ReadableStream constructor.
+        let started = Rc::new(RefCell::new(None));
+        let start = {
+            let started = started.clone();
+            Closure::once_into_js(move |controller: ReadableStreamDefaultController| {
+                *started.borrow_mut() = Some(controller);
+            })
+        };
+        let receiver = Rc::downgrade(self);
+        let cancel = Closure::once_into_js(move |_reason: JsValue| {
+            wasm_bindgen_futures::future_to_promise(async move {
+                if let Some(receiver) = Weak::upgrade(&receiver) {
+                    client.send_unsubscribe(receiver.request_id()).await?;
+                }
+                Ok(JsValue::UNDEFINED)
+            })
+        });
+        let source = js_sys::Object::new();
+        js_sys::Reflect::set(&source, &JsValue::from_str("start"), &start)?;
+        js_sys::Reflect::set(&source, &JsValue::from_str("cancel"), &cancel)?;
+        let stream = ReadableStream::new_with_underlying_source(&source)?;
+        *self.stream.borrow_mut() = started.borrow_mut().take();
+        Ok(stream)
+    }
+
+    /// Closes the `subscribeAsStream` stream, if any, after what it holds.
+    pub(crate) fn close_stream(&self) {
+        if let Some(controller) = self.stream.take() {
+            let _ = controller.close();
+        }
+    }
+
+    /// Largest (group, object) delivered so far.
+    pub(crate) fn largest_location(&self) -> Option<(u64, u64)> {
+        self.stats.get().largest_location
+    }
+
+    /// Ends the subscription on PUBLISH_DONE or SUBSCRIBE_ERROR and reports
+    /// the status code and reason to `onDone`.
+    pub(crate) fn finish(&self, status_code: u64, reason_phrase: &str) {
+        if self.done.replace(true) {
+            return;
+        }
+        if let Some(callback) = self.on_done.borrow().clone() {
+            let _ = callback.call2(
+                &JsValue::NULL,
+                &JsValue::from(js_sys::BigInt::from(status_code)),
+                &JsValue::from_str(reason_phrase),
+            );
+        }
+        if let Some(controller) = self.stream.take() {
+            if matches!(
+                status_code,
+                PUBLISH_DONE_TRACK_ENDED | PUBLISH_DONE_SUBSCRIPTION_ENDED
+            ) {
+                let _ = controller.close();
+            } else {
+                controller.error_with_e(&js_error(format!(
+                    "subscription ended with status {status_code}: {reason_phrase}"
+                )));
+            }
+        }
+    }
+}
+
+/// One track subscribed on a `MOQTClient` session. Objects, skipped groups
+/// and the end of the subscription arrive on the handle's own callbacks;
+/// `pause` / `resume` toggle Forward with SUBSCRIBE_UPDATE.
+#[wasm_bindgen]
+pub struct SubscribeHandle {
+    client: MOQTClient,
+    track_namespace: Vec<String>,
+    track_name: String,
+    subscriber_priority: u8,
+    end_group: Option<u64>,
+    start_location: (u64, u64),
+    receiver: Rc<TrackReceiver>,
+    paused: Cell<bool>,
+}
+
+impl SubscribeHandle {
+    pub(crate) fn new(
+        client: MOQTClient,
+        track_namespace: Vec<String>,
+        track_name: String,
+        receiver: Rc<TrackReceiver>,
+        options: &SubscribeOptions,
+    ) -> Self {
+        Self {
+            client,
+            track_namespace,
+            track_name,
+            subscriber_priority: options.subscriber_priority,
+            end_group: options.end_group,
+            start_location: (
+                options.start_group.unwrap_or(0),
+                options.start_object.unwrap_or(0),
+            ),
+            receiver,
+            paused: Cell::new(!options.forward),
+        }
+    }
+
+    fn ensure_active(&self) -> Result<(), JsValue> {
+        if self.receiver.is_done() {
+            return Err(js_error("subscription has ended"));
+        }
+        Ok(())
+    }
+
+    /// SUBSCRIBE_UPDATE keeping the range and priority, resuming from the
+    /// start of the largest group received so far.
+    async fn send_forward(&self, request_id: u64, forward: bool) -> Result<(), JsValue> {
+        self.ensure_active()?;
+        let (start_group, start_object) = self
+            .receiver
+            .stats
+            .get()
+            .largest_group_id()
+            .map_or(self.start_location, |group_id| (group_id, 0));
+        self.client
+            .send_subscribe_update_message(
+                request_id,
+                self.receiver.request_id(),
+                start_group,
+                start_object,
+                self.end_group,
+                self.subscriber_priority,
+                forward,
+            )
+            .await?;
+        self.paused.set(!forward);
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl SubscribeHandle {
+    /// Follows the subscription when a reconnect re-issues it.
+    #[wasm_bindgen(getter, js_name = requestId)]
+    pub fn request_id(&self) -> u64 {
+        self.receiver.request_id()
+    }
+
+    /// Alias from SUBSCRIBE_OK; undefined until it arrives.
+    #[wasm_bindgen(getter, js_name = trackAlias)]
+    pub fn track_alias(&self) -> Option<u64> {
+        self.client
+            .state
+            .borrow()
+            .outgoing_subscription_alias(self.receiver.request_id())
+    }
+
+    #[wasm_bindgen(getter, js_name = trackNamespace)]
+    pub fn track_namespace(&self) -> Vec<String> {
+        self.track_namespace.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = trackName)]
+    pub fn track_name(&self) -> String {
+        self.track_name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn paused(&self) -> bool {
+        self.paused.get()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn done(&self) -> bool {
+        self.receiver.is_done()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn stats(&self) -> SubscribeTrackStats {
+        self.receiver.stats.get()
+    }
+
+    /// `callback(object)` receives a `SubgroupStreamObjectMessage` for stream
+    /// objects and an `ObjectDatagramMessage` / `ObjectDatagramStatusMessage`
+    /// for datagrams.
+    #[wasm_bindgen(js_name = onObject)]
+    pub fn set_object_callback(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "TrackObjectCallback")] callback: js_sys::Function,
+    ) {
+        *self.receiver.on_object.borrow_mut() = Some(callback);
+    }
+
+    /// `callback(firstGroupId, lastGroupId)` for groups never received
+    /// before a later group arrived.
+    #[wasm_bindgen(js_name = onGap)]
+    pub fn set_gap_callback(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "TrackGapCallback")] callback: js_sys::Function,
+    ) {
+        *self.receiver.on_gap.borrow_mut() = Some(callback);
+    }
+
+    /// `callback(statusCode, reasonPhrase)` once, on PUBLISH_DONE or
+    /// SUBSCRIBE_ERROR.
+    #[wasm_bindgen(js_name = onDone)]
+    pub fn set_done_callback(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "TrackDoneCallback")] callback: js_sys::Function,
+    ) {
+        *self.receiver.on_done.borrow_mut() = Some(callback);
+    }
+
+    /// Stops delivery without ending the subscription. `request_id` is the
+    /// SUBSCRIBE_UPDATE's own Request ID.
+    pub async fn pause(&self, request_id: u64) -> Result<(), JsValue> {
+        self.send_forward(request_id, false).await
+    }
+
+    /// Restarts delivery from the largest group received so far.
+    pub async fn resume(&self, request_id: u64) -> Result<(), JsValue> {
+        self.send_forward(request_id, true).await
+    }
+
+    /// Sends UNSUBSCRIBE and cancels the track's open stream readers.
+    /// `onDone` is not called for a local unsubscribe.
+    pub async fn unsubscribe(&self) -> Result<(), JsValue> {
+        self.ensure_active()?;
+        self.client
+            .send_unsubscribe(self.receiver.request_id())
+            .await?;
+        self.receiver.done.set(true);
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+/// Objects and payload bytes of one track, in each direction.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrackTrafficStats {
+    track_alias: u64,
+    objects_sent: u64,
+    bytes_sent: u64,
+    objects_received: u64,
+    bytes_received: u64,
+}
+
+#[wasm_bindgen]
+impl TrackTrafficStats {
+    #[wasm_bindgen(getter, js_name = trackAlias)]
+    pub fn track_alias(&self) -> u64 {
+        self.track_alias
+    }
+
+    #[wasm_bindgen(getter, js_name = objectsSent)]
+    pub fn objects_sent(&self) -> u64 {
+        self.objects_sent
+    }
+
+    #[wasm_bindgen(getter, js_name = bytesSent)]
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    #[wasm_bindgen(getter, js_name = objectsReceived)]
+    pub fn objects_received(&self) -> u64 {
+        self.objects_received
+    }
+
+    #[wasm_bindgen(getter, js_name = bytesReceived)]
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+}
+
+/// Per-track object counters of a session, kept by the send methods and
+/// the read loops. FETCH objects are not counted.
+#[derive(Debug, Default)]
+pub(crate) struct TrafficCounters {
+    tracks: BTreeMap<u64, TrackTrafficStats>,
+}
+
+impl TrafficCounters {
+    pub(crate) fn record_sent(&mut self, track_alias: u64, payload_len: usize) {
+        let track = self.track(track_alias);
+        track.objects_sent += 1;
+        track.bytes_sent += payload_len as u64;
+    }
+
+    pub(crate) fn record_received(&mut self, track_alias: u64, payload_len: usize) {
+        let track = self.track(track_alias);
+        track.objects_received += 1;
+        track.bytes_received += payload_len as u64;
+    }
+
+    fn track(&mut self, track_alias: u64) -> &mut TrackTrafficStats {
+        self.tracks
+            .entry(track_alias)
+            .or_insert_with(|| TrackTrafficStats {
+                track_alias,
+                ..Default::default()
+            })
+    }
+}
+
+/// What `getTransportStats` resolves to: the browser's WebTransport
+/// statistics next to the library's own counters.
+#[wasm_bindgen]
+pub struct TransportStats {
+    transport: JsValue,
+    open_subgroup_streams: u32,
+    tracks: Vec<TrackTrafficStats>,
+}
+
+#[wasm_bindgen]
+impl TransportStats {
+    /// `WebTransport.getStats()` as the browser reports it (RTT, bytes and
+    /// packets sent / received / lost, ...); `undefined` when the browser
+    /// does not implement it or the session is closed.
+    #[wasm_bindgen(getter)]
+    pub fn transport(&self) -> JsValue {
+        self.transport.clone()
+    }
+
+    /// Subgroup streams this client has opened and not yet closed.
+    #[wasm_bindgen(getter, js_name = openSubgroupStreams)]
+    pub fn open_subgroup_streams(&self) -> u32 {
+        self.open_subgroup_streams
+    }
+
+    /// One entry per track alias that sent or received an object, in
+    /// track alias order.
+    #[wasm_bindgen(getter)]
+    pub fn tracks(&self) -> Vec<TrackTrafficStats> {
+        self.tracks.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = bytesSent)]
+    pub fn bytes_sent(&self) -> u64 {
+        self.tracks.iter().map(|track| track.bytes_sent).sum()
+    }
+
+    #[wasm_bindgen(getter, js_name = bytesReceived)]
+    pub fn bytes_received(&self) -> u64 {
+        self.tracks.iter().map(|track| track.bytes_received).sum()
+    }
+}
+
+impl TransportStats {
+    pub(crate) fn new(
+        transport: JsValue,
+        open_subgroup_streams: usize,
+        counters: &TrafficCounters,
+    ) -> Self {
+        Self {
+            transport,
+            open_subgroup_streams: open_subgroup_streams as u32,
+            tracks: counters.tracks.values().copied().collect(),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use wasm_bindgen::prelude::*;
+
+// Types for the values that cross the boundary as plain JS objects or
+// functions. wasm-bindgen types those as `any`; the methods that take or
+// return them point at these names through `unchecked_param_type` /
+// `unchecked_return_type` so the generated `.d.ts` is fully typed.
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT_DEFINITIONS: &str = r#"
+/** LOC (Low Overhead Media Container) header, as carried in Object extension headers. */
+export type LocHeader = {
+  extensions: LocHeaderExtension[];
+};
+
+export type LocHeaderExtension =
+  | { type: "captureTimestamp"; value: { microsSinceUnixEpoch: number } }
+  | { type: "videoConfig"; value: { data: number[] | Uint8Array } }
+  | { type: "videoFrameMarking"; value: { flags: number } }
+  | { type: "audioLevel"; value: { level: number } }
+  | { type: "unknown"; value: { id: number; value: LocHeaderValue } };
+
+export type LocHeaderValue = { even: number } | { odd: number[] | Uint8Array };
+
+/** Options of `MOQTClient.createPublishTrack`; every field is optional. */
+export interface PublishTrackOptions {
+  /** Alias already handed out by `sendPublish` or `sendSubscribeOk`. */
+  trackAlias?: number | bigint;
+  publisherPriority?: number;
+  startGroup?: number | bigint;
+  subgroupId?: number | bigint;
+}
+
+/** Options of `MOQTClient.subscribe` and `MOQTClient.subscribeTrack`; every field is optional. */
+export interface SubscribeOptions {
+  /** Allocated by the client when absent; see `allocateRequestId`. */
+  requestId?: number | bigint;
+  subscriberPriority?: number;
+  groupOrder?: number;
+  /** Defaults to 1 (NextGroupStart). */
+  filterType?: number;
+  startGroup?: number | bigint;
+  startObject?: number | bigint;
+  endGroup?: number | bigint;
+  authInfo?: string;
+  /** `false` subscribes paused; `SubscribeHandle.resume` starts delivery. Defaults to `true`. */
+  forward?: boolean;
+  deliveryTimeout?: number | bigint;
+  maxCacheDuration?: number | bigint;
+}
+
+export type ServerSetupCallback = (message: ServerSetupMessage) => void;
+export type PublishNamespaceCallback = (message: PublishNamespaceMessage) => void;
+export type PublishNamespaceDoneCallback = (message: PublishNamespaceDoneMessage) => void;
+export type NamespaceResponseCallback = (response: NamespaceOkMessage | RequestErrorMessage) => void;
+export type NamespaceDeltaCallback = (message: NamespaceDeltaMessage) => void;
+export type PublishCallback = (message: PublishMessage) => void;
+export type PublishResponseCallback = (response: PublishOkMessage | RequestErrorMessage) => void;
+/** `isSuccess` and `code` are the result of validating the SUBSCRIBE locally. */
+export type SubscribeCallback = (
+  message: SubscribeMessage,
+  isSuccess: boolean,
+  code: number,
+) => void | Promise<void>;
+export type SubscribeResponseCallback = (response: SubscribeOkMessage | RequestErrorMessage) => void;
+export type PublishDoneCallback = (message: PublishDoneMessage) => void;
+export type IncomingUnsubscribeCallback = (requestId: bigint) => void;
+export type ObjectDatagramCallback = (message: ObjectDatagramMessage) => void;
+export type ObjectDatagramStatusCallback = (message: ObjectDatagramStatusMessage) => void;
+export type SubgroupHeaderCallback = (message: SubgroupHeaderMessage) => void;
+export type SubgroupObjectCallback = (
+  trackAlias: bigint,
+  groupId: bigint,
+  message: SubgroupObjectMessage,
+) => void;
+export type SubgroupStreamObjectCallback = (message: SubgroupStreamObjectMessage) => void;
+export type FetchResponseCallback = (response: FetchOkMessage | RequestErrorMessage) => void;
+export type TrackStatusCallback = (response: TrackStatusMessage | RequestErrorMessage) => void;
+export type FetchObjectCallback = (message: FetchObjectMessage) => void;
+/**
+ * `closeCode` / `reason` are the application close of whichever side closed
+ * the session; `0` and the error message when it was lost without one.
+ */
+export type ConnectionClosedCallback = (closeCode: number, reason: string) => void;
+export type GoawayCallback = (newSessionUri: string) => void;
+export type RequestsBlockedCallback = (maxRequestId: bigint) => void;
+/**
+ * Old to new Request ID and Track Alias of every subscription re-issued
+ * after a reconnect and accepted again.
+ */
+export type ReconnectedCallback = (
+  subscriptionRequestIds: Map<bigint, bigint>,
+  trackAliases: Map<bigint, bigint>,
+) => void;
+
+/** Options of `MOQTClient.enableAutoReconnect`; every field is optional. */
+export interface ReconnectOptions {
+  /** Attempts before giving up; defaults to 5. */
+  maxAttempts?: number;
+  /** Attempt n waits n times this first; defaults to 1000 ms. */
+  delayMs?: number;
+}
+
+/** Events `MOQTClient.addEventListener` accepts: the `on*` setters without `on`. */
+export type ClientEventName =
+  | "serverSetup"
+  | "publishNamespace"
+  | "publishNamespaceDone"
+  | "publishNamespaceResponse"
+  | "subscribeNamespaceResponse"
+  | "namespaceAdded"
+  | "namespaceRemoved"
+  | "publish"
+  | "publishResponse"
+  | "subscribe"
+  | "subscribeResponse"
+  | "publishDone"
+  | "incomingUnsubscribe"
+  | "objectDatagram"
+  | "objectDatagramStatus"
+  | "subgroupHeader"
+  | "subgroupObject"
+  | "subgroupStreamObject"
+  | "fetchResponse"
+  | "fetchObject"
+  | "trackStatus"
+  | "goaway"
+  | "requestsBlocked"
+  | "connectionClosed"
+  | "reconnected";
+
+/**
+ * What `toObject` returns for a message class: its getters as plain data,
+ * without methods or `locMetadata`, so it survives `postMessage`.
+ */
+export type PlainMessage<T> = {
+  [K in keyof T as K extends "locMetadata"
+    ? never
+    : T[K] extends (...args: never[]) => unknown
+      ? never
+      : K]: T[K];
+};
+
+export type TrackObjectMessage =
+  | SubgroupStreamObjectMessage
+  | ObjectDatagramMessage
+  | ObjectDatagramStatusMessage;
+export type TrackObjectCallback = (object: TrackObjectMessage) => void;
+export type TrackGapCallback = (firstGroupId: bigint, lastGroupId: bigint) => void;
+export type TrackDoneCallback = (statusCode: bigint, reasonPhrase: string) => void;
+"#;
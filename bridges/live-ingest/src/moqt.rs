@@ -311,6 +311,12 @@ impl<T: TransportProtocol> ConnectedPublisher<T> {
                             handler.track_namespace
                         );
                     }
+                    SessionEvent::PublishNamespaceCancel(handler) => {
+                        eprintln!(
+                            "[moqt] publish_namespace_cancel ns={} code={} reason={}",
+                            handler.track_namespace, handler.error_code, handler.reason_phrase
+                        );
+                    }
                     SessionEvent::SubscribeNameSpace(handler) => {
                         if let Err(err) = handler.ok().await {
                             eprintln!(
@@ -373,9 +379,21 @@ impl<T: TransportProtocol> ConnectedPublisher<T> {
                             );
                         }
                     }
+                    SessionEvent::SubscribeUpdate(handler) => {
+                        println!(
+                            "[moqt] subscribe_update received id={}",
+                            handler.subscription_request_id()
+                        );
+                    }
                     SessionEvent::Unsubscribe(handler) => {
                         println!("[moqt] unsubscribe received id={}", handler.subscribe_id());
                     }
+                    SessionEvent::PublishDone(handler) => {
+                        println!(
+                            "[moqt] publish_done received id={} code={} reason={}",
+                            handler.request_id, handler.status_code, handler.reason_phrase
+                        );
+                    }
                     SessionEvent::UnsubscribeNamespace(handler) => {
                         println!(
                             "[moqt] unsubscribe_namespace received prefix={}",
@@ -396,6 +414,18 @@ impl<T: TransportProtocol> ConnectedPublisher<T> {
                     SessionEvent::Fetch(_) => {
                         todo!()
                     }
+                    SessionEvent::FetchCancel(handler) => {
+                        println!("[moqt] fetch_cancel received id={}", handler.request_id());
+                    }
+                    SessionEvent::TrackStatusRequest(handler) => {
+                        println!(
+                            "[moqt] track_status_request received id={}",
+                            handler.request_id()
+                        );
+                    }
+                    SessionEvent::GoAway(new_session_uri) => {
+                        println!("[moqt] goaway received new_session_uri={new_session_uri}");
+                    }
                 }
             }
         })
@@ -782,6 +812,7 @@ fn build_catalog_payload(namespace_path: &str, metadata: &CatalogMetadata) -> Re
         generated_at: Some(now_unix_ms()),
         is_complete: Some(true),
         tracks: Some(tracks),
+        catalog_sequence: None,
     };
 
     serde_json::to_vec(&catalog).context("serialize msf catalog")
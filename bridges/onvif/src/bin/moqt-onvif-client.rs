@@ -424,6 +424,13 @@ async fn handle_session_event(
             );
             Ok(None)
         }
+        SessionEvent::PublishNamespaceCancel(handler) => {
+            eprintln!(
+                "[moqt] publish_namespace_cancel ns={} code={} reason={}",
+                handler.track_namespace, handler.error_code, handler.reason_phrase
+            );
+            Ok(None)
+        }
         SessionEvent::PublishNamespace(handler) => {
             handle_command_namespace_announce(
                 handler,
@@ -450,6 +457,13 @@ async fn handle_session_event(
             .await?;
             Ok(None)
         }
+        SessionEvent::SubscribeUpdate(handler) => {
+            log::info!(
+                "Command/media subscribe update event received: subscribe_id={}",
+                handler.subscription_request_id()
+            );
+            Ok(None)
+        }
         SessionEvent::Unsubscribe(handler) => {
             log::info!(
                 "Command/media unsubscribe event received: subscribe_id={}",
@@ -457,6 +471,15 @@ async fn handle_session_event(
             );
             Ok(None)
         }
+        SessionEvent::PublishDone(handler) => {
+            log::info!(
+                "Command/media publish done event received: request_id={} status_code={} reason={}",
+                handler.request_id,
+                handler.status_code,
+                handler.reason_phrase
+            );
+            Ok(None)
+        }
         SessionEvent::UnsubscribeNamespace(handler) => {
             log::info!(
                 "Command/media unsubscribe namespace event received: prefix={}",
@@ -483,6 +506,24 @@ async fn handle_session_event(
         SessionEvent::Fetch(_) => {
             todo!()
         }
+        SessionEvent::FetchCancel(handler) => {
+            log::info!(
+                "Fetch cancel event received: request_id={}",
+                handler.request_id()
+            );
+            Ok(None)
+        }
+        SessionEvent::TrackStatusRequest(handler) => {
+            log::info!(
+                "Track status request received: request_id={}",
+                handler.request_id()
+            );
+            Ok(None)
+        }
+        SessionEvent::GoAway(new_session_uri) => {
+            log::info!("GOAWAY received: new_session_uri={new_session_uri}");
+            Ok(None)
+        }
     }
 }
 
@@ -716,6 +757,7 @@ async fn ensure_command_track_subscription(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::NextGroupStart,
+                max_cache_duration: None,
             },
         )
         .await
@@ -878,6 +920,7 @@ async fn send_catalog(
         generated_at: Some(now_millis()),
         is_complete: Some(true),
         tracks: Some(tracks),
+        catalog_sequence: None,
     };
     let data = serde_json::to_vec(&catalog).context("serialize catalog json")?;
     let uninit_stream = publisher
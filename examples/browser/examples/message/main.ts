@@ -10,6 +10,7 @@ import {
   SubscribeOkMessage
 } from '../../pkg/moqt_client_wasm'
 import { MoqtClientWrapper } from '../../lib/moqt/moqtClient'
+import { buildLocHeader } from '../../utils/media/loc'
 import { configureRelayUrlControls } from '../../utils/relayPresets'
 
 type HTMLFormControls = HTMLFormElement & {
@@ -232,6 +233,10 @@ function handleSubscribeNamespaceResponse(response: RequestErrorMessage | { requ
 
 function handleObjectDatagram(message: ObjectDatagramMessage, receivedTextElement: HTMLElement): void {
   console.info({ objectDatagram: message })
+  const captureTimestampMicros = message.locMetadata.captureTimestampMicros
+  if (captureTimestampMicros !== undefined) {
+    console.info({ objectDatagramLatencyMs: Date.now() - Number(captureTimestampMicros / 1000n) })
+  }
   if (message.objectPayload.length > 0) {
     describeReceivedObject(message.objectPayload, receivedTextElement)
   }
@@ -283,8 +288,8 @@ function registerClientCallbacks(receivedTextElement: HTMLElement): void {
   moqtClient.setOnObjectDatagramHandler((message) => handleObjectDatagram(message, receivedTextElement))
   moqtClient.setOnObjectDatagramStatusHandler(handleObjectDatagramStatus)
   moqtClient.setOnSubgroupHeaderHandler(handleSubgroupHeader)
-  moqtClient.setOnConnectionClosedHandler(() => {
-    console.info('connection closed')
+  moqtClient.setOnConnectionClosedHandler((closeCode, reason) => {
+    console.info('connection closed', { closeCode, reason })
     subgroupHeaderSent.clear()
     requestTrackAliases.clear()
     requestTrackMetadata.clear()
@@ -423,7 +428,14 @@ function setupActionButtons(): void {
     const objectPayloadString = getField(form, 'payload').value
     const objectPayloadArray = new TextEncoder().encode(objectPayloadString)
 
-    await client.sendObjectDatagram(trackAlias, groupId, objectId++, publisherPriority, objectPayloadArray, undefined)
+    await client.sendObjectDatagram(
+      trackAlias,
+      groupId,
+      objectId++,
+      publisherPriority,
+      objectPayloadArray,
+      buildLocHeader({ captureTimestampMicros: Date.now() * 1000 })
+    )
     if (objectIdElement) {
       objectIdElement.textContent = objectId.toString()
     }
@@ -439,7 +451,14 @@ function setupActionButtons(): void {
     const publisherPriority = Number(getField(form, 'publisher-priority').value)
     const objectStatus = Number(getRadioValue(form, 'object-status'))
 
-    await client.sendObjectDatagramStatus(trackAlias, groupId, objectId++, publisherPriority, objectStatus, undefined)
+    await client.sendObjectDatagramStatus(
+      trackAlias,
+      groupId,
+      objectId++,
+      publisherPriority,
+      objectStatus,
+      buildLocHeader({ captureTimestampMicros: Date.now() * 1000 })
+    )
     if (objectIdElement) {
       objectIdElement.textContent = objectId.toString()
     }
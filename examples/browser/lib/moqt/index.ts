@@ -1,2 +1,4 @@
 export * from './moqtClient'
 export * from './subscriptionStateManager'
+export * from './moqtWorkerBridge'
+export * from './workerProtocol'
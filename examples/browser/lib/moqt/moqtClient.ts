@@ -1,19 +1,28 @@
 import init, {
+  BandwidthEstimateMessage,
+  BandwidthProbe,
+  bandwidthProbeNamespace,
+  bandwidthProbeTrackName,
   FetchObjectMessage,
   FetchOkMessage,
   MOQTClient,
+  NamespaceDeltaMessage,
   NamespaceOkMessage,
   ObjectDatagramMessage,
   ObjectDatagramStatusMessage,
+  PublishDoneMessage,
   PublishNamespaceDoneMessage,
   PublishNamespaceMessage,
+  PublishTrackHandle,
   RequestErrorMessage,
   ServerSetupMessage,
+  SubscribeHandle,
   SubgroupHeaderMessage,
   SubgroupObjectMessage,
-  SubgroupState,
+  SubgroupStreamObjectMessage,
   SubscribeMessage,
-  SubscribeOkMessage
+  SubscribeOkMessage,
+  TrackStatusMessage
 } from '../../pkg/moqt_client_wasm'
 import {
   InMemorySubscriptionStateManager,
@@ -24,20 +33,61 @@ import {
 type SetupResolver = ((value: void) => void) | null
 type PendingVoidResolver = { resolve: () => void; reject: (error: Error) => void }
 type PendingSubscribeResolver = { resolve: (response: SubscribeOkMessage) => void; reject: (error: Error) => void }
+type PendingTrackStatusResolver = { resolve: (response: TrackStatusMessage) => void; reject: (error: Error) => void }
 type FetchResponseHandler = ((response: FetchOkMessage | RequestErrorMessage) => void) | null
 type FetchObjectHandler = ((message: FetchObjectMessage) => void) | null
 type SubscribeResponseHandler = ((response: SubscribeOkMessage | RequestErrorMessage) => void) | null
 type NamespaceResponseHandler = ((response: NamespaceOkMessage | RequestErrorMessage) => void) | null
-type ConnectionClosedHandler = (() => void) | null
+type NamespaceDeltaHandler = ((message: NamespaceDeltaMessage) => void) | null
+type ConnectionClosedHandler = ((closeCode: number, reason: string) => void) | null
 type IncomingUnsubscribeHandler = ((requestId: bigint) => void) | null
+type PublishDoneHandler = ((message: PublishDoneMessage) => void) | null
 type ObjectDatagramHandler = ((message: ObjectDatagramMessage) => void) | null
 type ObjectDatagramStatusHandler = ((message: ObjectDatagramStatusMessage) => void) | null
 type SubgroupHeaderHandler = ((header: SubgroupHeaderMessage) => void) | null
+type SubgroupStreamObjectHandler = ((message: SubgroupStreamObjectMessage) => void) | null
+type GoawayHandler = ((newSessionUri: string) => void) | null
+type RequestsBlockedHandler = ((maxRequestId: bigint) => void) | null
+/** Old request id -> new request id for every subscription replayed on the new session. */
+type MigratedHandler = ((subscriptionRequestIds: Map<bigint, bigint>) => void) | null
+type ReconnectedHandler = MigratedHandler
+
+type PublishNamespaceIntent = { trackNamespace: string[]; authInfo: string }
+type SubscribeNamespaceIntent = { trackNamespacePrefix: string[]; authInfo: string }
+type SubscribeIntent = {
+  trackNamespace: string[]
+  trackName: string
+  authInfo: string
+  options: SubscribeOptions
+}
+type ReplayedSubscription = { requestId: bigint; intent: SubscribeIntent; trackAlias: bigint | undefined }
 
 export interface ConnectOptions {
   sendSetup?: boolean
   versions?: BigUint64Array
   maxRequestId?: bigint
+  /**
+   * On GOAWAY, connect to the new session URI (or the current URL when the
+   * relay sends none) and replay SETUP, PUBLISH_NAMESPACE, SUBSCRIBE_NAMESPACE
+   * and SUBSCRIBE before closing the old session.
+   */
+  autoMigrateOnGoaway?: boolean
+  /**
+   * Forwarded to `MOQTClient.enableAutoReconnect`: when the transport closes
+   * without `disconnect()`, the client re-dials the same URL and replays SETUP,
+   * PUBLISH_NAMESPACE, SUBSCRIBE_NAMESPACE and SUBSCRIBE.
+   * `onConnectionClosed` only fires once every attempt has failed.
+   */
+  autoReconnect?: boolean
+  /** Attempts before giving up; defaults to 5. */
+  maxReconnectAttempts?: number
+  /** Delay before attempt n is n times this; defaults to 1000 ms. */
+  reconnectDelayMs?: number
+  /**
+   * Passed to the `WebTransport` constructor, e.g. `serverCertificateHashes`
+   * for a relay with a self-signed development certificate.
+   */
+  webTransportOptions?: WebTransportOptions
 }
 
 export interface PublishNamespaceOptions {
@@ -48,10 +98,19 @@ export interface SubscribeNamespaceOptions {
   requestId?: bigint
 }
 
+export interface SubscribeUpdateOptions {
+  startGroup: bigint
+  startObject?: bigint
+  /** Last group to deliver; omit to keep the subscription open-ended. */
+  endGroup?: bigint
+  subscriberPriority?: number
+  forward?: boolean
+}
+
 export interface SubscribeOptions {
   /**
    * Override the request id. When omitted, a session-unique id is issued
-   * internally via issueRequestId(). Production code should let it be issued
+   * by MOQTClient.allocateRequestId(). Production code should let it be issued
    * automatically; only manual/test tools that need to control the exact id on
    * the wire should pass this.
    */
@@ -64,6 +123,8 @@ export interface SubscribeOptions {
   endGroup?: bigint
   forward?: boolean
   deliveryTimeout?: bigint
+  /** MAX_CACHE_DURATION (ms) to request; SubscribeResult.maxCacheDuration is what was granted. */
+  maxCacheDuration?: bigint
 }
 
 export interface FetchOptions {
@@ -77,6 +138,11 @@ export interface FetchOptions {
 export interface SubscribeResult {
   requestId: bigint
   subscribeOk: SubscribeOkMessage
+  /**
+   * MAX_CACHE_DURATION (ms) the publisher or relay granted. It can be shorter than
+   * requested; objects older than this may be gone from a later FETCH.
+   */
+  maxCacheDuration?: bigint
 }
 
 /** Result of fetch()/relativeJoiningFetch(): the id we issued plus FETCH_OK. */
@@ -117,22 +183,38 @@ export class MoqtClientWrapper {
   private onPublishNamespaceDoneHandler: IncomingPublishNamespaceDoneHandler | null = null
   private onPublishNamespaceResponseHandler: NamespaceResponseHandler = null
   private onSubscribeNamespaceResponseHandler: NamespaceResponseHandler = null
+  private onNamespaceAddedHandler: NamespaceDeltaHandler = null
+  private onNamespaceRemovedHandler: NamespaceDeltaHandler = null
   private onSubscribeResponseHandler: SubscribeResponseHandler = null
   private onConnectionClosedHandler: ConnectionClosedHandler = null
   private incomingSubscribeHandler: IncomingSubscribeHandler | null = null
   private incomingUnsubscribeHandler: IncomingUnsubscribeHandler = null
+  private onPublishDoneHandler: PublishDoneHandler = null
   private onServerSetupHandler: ((setup: ServerSetupMessage) => void) | null = null
   private onObjectDatagramHandler: ObjectDatagramHandler = null
   private onObjectDatagramStatusHandler: ObjectDatagramStatusHandler = null
   private onSubgroupHeaderHandler: SubgroupHeaderHandler = null
+  private onSubgroupStreamObjectHandler: SubgroupStreamObjectHandler = null
   private onFetchResponseHandler: FetchResponseHandler = null
+  private onGoawayHandler: GoawayHandler = null
+  private onRequestsBlockedHandler: RequestsBlockedHandler = null
+  private onMigratedHandler: MigratedHandler = null
+  private onReconnectedHandler: ReconnectedHandler = null
   private readonly fetchObjectHandlers = new Map<bigint, FetchObjectHandler>()
   private readonly subscriptionState: SubscriptionStateStore
   private readonly pendingPublishNamespace = new Map<bigint, PendingVoidResolver>()
   private readonly pendingSubscribeNamespace = new Map<bigint, PendingVoidResolver>()
   private readonly pendingSubscribe = new Map<bigint, PendingSubscribeResolver>()
+  private readonly pendingTrackStatus = new Map<bigint, PendingTrackStatusResolver>()
   private readonly subscriptionTrackAliases = new Map<bigint, bigint>()
-  private nextRequestId = 0n
+  // Publish handles per SUBSCRIBE_OK track alias, for sendSubgroupTextForTrack.
+  private readonly publishTracks = new Map<bigint, PublishTrackHandle>()
+  // What this session established, so it can be replayed after GOAWAY.
+  private readonly publishNamespaceIntents = new Map<string, PublishNamespaceIntent>()
+  private readonly subscribeNamespaceIntents = new Map<string, SubscribeNamespaceIntent>()
+  private readonly subscribeIntents = new Map<bigint, SubscribeIntent>()
+  private url: string | null = null
+  private connectOptions: ConnectOptions = {}
 
   constructor(subscriptionState?: SubscriptionStateStore) {
     this.subscriptionState = subscriptionState ?? new InMemorySubscriptionStateManager()
@@ -143,17 +225,34 @@ export class MoqtClientWrapper {
       return
     }
 
+    this.url = url
+    this.connectOptions = options
+    try {
+      await this.openSession(url, options)
+    } catch (error) {
+      this.cleanupClient()
+      throw error
+    }
+  }
+
+  private async openSession(url: string, options: ConnectOptions): Promise<void> {
     let wtConnectStartedAtMs: number | null = null
     try {
       await init()
       this.client = new MOQTClient(url)
       wtConnectStartedAtMs = performance.now()
-      await this.client.start()
+      await this.client.start(options.webTransportOptions)
       console.info('[moqt][wt] connected', {
         url,
         elapsedMs: Math.round((performance.now() - wtConnectStartedAtMs) * 100) / 100
       })
       this.setupCallbacks()
+      if (options.autoReconnect) {
+        this.client.enableAutoReconnect({
+          maxAttempts: options.maxReconnectAttempts,
+          delayMs: options.reconnectDelayMs
+        })
+      }
 
       if (options.sendSetup === false) {
         this.serverSetupResolve = null
@@ -169,7 +268,8 @@ export class MoqtClientWrapper {
       await this.client.sendClientSetup(versions, maxRequestId)
       await receiveServerSetup
     } catch (error) {
-      this.cleanupClient()
+      this.client = null
+      this.serverSetupResolve = null
       if (wtConnectStartedAtMs !== null) {
         console.error('[moqt][wt] connect failed', {
           url,
@@ -190,12 +290,13 @@ export class MoqtClientWrapper {
     await receiveServerSetup
   }
 
-  async disconnect(): Promise<void> {
+  /** `closeCode` and `reason` are sent to the relay as the WebTransport application close. */
+  async disconnect(closeCode?: number, reason?: string): Promise<void> {
     if (!this.client) {
       return
     }
     try {
-      await this.client.close()
+      await this.client.close(closeCode, reason)
     } finally {
       this.cleanupClient()
     }
@@ -213,10 +314,39 @@ export class MoqtClientWrapper {
     return this.client
   }
 
-  setOnConnectionClosedHandler(handler: (() => void) | null): void {
+  /** Largest datagram, header included; sendObjectDatagram rejects bigger ones with DatagramTooLargeError. */
+  getMaxDatagramSize(): number {
+    return this.requireConnectedClient().getMaxDatagramSize()
+  }
+
+  /** Called with the close code and reason of the side that closed the session. */
+  setOnConnectionClosedHandler(handler: ConnectionClosedHandler): void {
     this.onConnectionClosedHandler = handler
   }
 
+  /** Called with NEW_SESSION_URI when the relay sends GOAWAY; new requests fail afterwards. */
+  setOnGoawayHandler(handler: GoawayHandler): void {
+    this.onGoawayHandler = handler
+  }
+
+  /**
+   * Called when a request is refused because the relay's MAX_REQUEST_ID is used up.
+   * REQUESTS_BLOCKED has been sent; retry once the relay raises the limit.
+   */
+  setOnRequestsBlockedHandler(handler: RequestsBlockedHandler): void {
+    this.onRequestsBlockedHandler = handler
+  }
+
+  /** Called after autoMigrateOnGoaway has re-established the session. */
+  setOnMigratedHandler(handler: MigratedHandler): void {
+    this.onMigratedHandler = handler
+  }
+
+  /** Called after autoReconnect has re-established the session. */
+  setOnReconnectedHandler(handler: ReconnectedHandler): void {
+    this.onReconnectedHandler = handler
+  }
+
   setOnServerSetupHandler(handler: ((setup: ServerSetupMessage) => void) | null): void {
     this.onServerSetupHandler = handler
   }
@@ -237,6 +367,16 @@ export class MoqtClientWrapper {
     this.onSubscribeNamespaceResponseHandler = handler
   }
 
+  /** Called once per subscribed prefix when a namespace first appears under it. */
+  setOnNamespaceAddedHandler(handler: NamespaceDeltaHandler): void {
+    this.onNamespaceAddedHandler = handler
+  }
+
+  /** Called once per subscribed prefix when a known namespace is withdrawn. */
+  setOnNamespaceRemovedHandler(handler: NamespaceDeltaHandler): void {
+    this.onNamespaceRemovedHandler = handler
+  }
+
   setOnSubscribeResponseHandler(handler: SubscribeResponseHandler): void {
     this.onSubscribeResponseHandler = handler
   }
@@ -249,6 +389,11 @@ export class MoqtClientWrapper {
     this.incomingUnsubscribeHandler = handler
   }
 
+  /** Called when the publisher ends a subscription; `endedNormally` tells TRACK_ENDED apart from errors. */
+  setOnPublishDoneHandler(handler: PublishDoneHandler): void {
+    this.onPublishDoneHandler = handler
+  }
+
   setOnObjectDatagramHandler(handler: ObjectDatagramHandler): void {
     this.onObjectDatagramHandler = handler
   }
@@ -261,6 +406,11 @@ export class MoqtClientWrapper {
     this.onSubgroupHeaderHandler = handler
   }
 
+  /** Receives every subgroup stream object with its Subgroup ID, Publisher Priority and absolute Object ID. */
+  setOnSubgroupStreamObjectHandler(handler: SubgroupStreamObjectHandler): void {
+    this.onSubgroupStreamObjectHandler = handler
+  }
+
   setOnSubgroupObjectHandler(trackAlias: bigint, handler: SubgroupObjectHandler): void {
     this.subscriptionState.setSubgroupObjectHandler(trackAlias, handler)
   }
@@ -285,6 +435,7 @@ export class MoqtClientWrapper {
     })
     await client.sendPublishNamespace(requestId, trackNamespace, authInfo)
     await response
+    this.publishNamespaceIntents.set(namespaceKey(trackNamespace), { trackNamespace, authInfo })
   }
 
   async subscribeNamespace(
@@ -299,6 +450,14 @@ export class MoqtClientWrapper {
     })
     await client.sendSubscribeNamespace(requestId, trackNamespacePrefix, authInfo)
     await response
+    this.subscribeNamespaceIntents.set(namespaceKey(trackNamespacePrefix), { trackNamespacePrefix, authInfo })
+  }
+
+  /** Stops namespace discovery under the prefix; it is not replayed on session migration. */
+  async unsubscribeNamespace(trackNamespacePrefix: string[]): Promise<void> {
+    const client = this.requireConnectedClient()
+    this.subscribeNamespaceIntents.delete(namespaceKey(trackNamespacePrefix))
+    await client.sendUnsubscribeNamespace(trackNamespacePrefix)
   }
 
   async subscribe(
@@ -332,11 +491,53 @@ export class MoqtClientWrapper {
       options.endGroup,
       authInfo,
       options.forward ?? false,
-      options.deliveryTimeout
+      options.deliveryTimeout,
+      options.maxCacheDuration
     )
 
     const subscribeOk = await response
-    return { requestId, subscribeOk }
+    this.subscribeIntents.set(requestId, {
+      trackNamespace,
+      trackName,
+      authInfo,
+      options: { ...options, requestId: undefined }
+    })
+    const maxCacheDuration = subscribeOk.maxDuration
+    if (
+      options.maxCacheDuration !== undefined &&
+      maxCacheDuration !== undefined &&
+      maxCacheDuration < options.maxCacheDuration
+    ) {
+      console.warn(
+        `subscribe: requested maxCacheDuration ${options.maxCacheDuration}ms, granted ${maxCacheDuration}ms`
+      )
+    }
+    return { requestId, subscribeOk, maxCacheDuration }
+  }
+
+  /**
+   * Subscribes with a per-track handle: objects, skipped groups and PUBLISH_DONE
+   * arrive on the handle's onObject / onGap / onDone instead of the global
+   * handlers. Re-issued by autoReconnect, where the handle follows its new
+   * request id, but not replayed on GOAWAY migration.
+   */
+  async subscribeTrack(
+    trackNamespace: string[],
+    trackName: string,
+    authInfo: string,
+    options: SubscribeOptions = {}
+  ): Promise<SubscribeHandle> {
+    const client = this.requireConnectedClient()
+    const requestId = options.requestId ?? this.issueRequestId()
+    return client.subscribeTrack(trackNamespace, trackName, { ...options, requestId, authInfo })
+  }
+
+  async pauseTrack(handle: SubscribeHandle): Promise<void> {
+    await handle.pause(this.issueRequestId())
+  }
+
+  async resumeTrack(handle: SubscribeHandle): Promise<void> {
+    await handle.resume(this.issueRequestId())
   }
 
   async fetch(
@@ -389,6 +590,28 @@ export class MoqtClientWrapper {
     }
   }
 
+  async fetchCancel(requestId: bigint): Promise<void> {
+    const client = this.requireConnectedClient()
+    this.clearFetchObjectHandler(requestId)
+    await client.sendFetchCancel(requestId)
+  }
+
+  /** Probes a track's current state (TRACK_STATUS) without subscribing. */
+  async trackStatus(trackNamespace: string[], trackName: string, authInfo = ''): Promise<TrackStatusMessage> {
+    const client = this.requireConnectedClient()
+    const requestId = this.issueRequestId()
+    const response = new Promise<TrackStatusMessage>((resolve, reject) => {
+      this.pendingTrackStatus.set(requestId, { resolve, reject })
+    })
+    try {
+      await client.sendTrackStatusRequest(requestId, trackNamespace, trackName, authInfo)
+    } catch (error) {
+      this.pendingTrackStatus.delete(requestId)
+      throw error
+    }
+    return response
+  }
+
   private awaitFetchOk(): Promise<FetchOkMessage> {
     return new Promise<FetchOkMessage>((resolve, reject) => {
       this.onFetchResponseHandler = (msg) => {
@@ -410,16 +633,73 @@ export class MoqtClientWrapper {
     this.fetchObjectHandlers.delete(requestId)
   }
 
+  /**
+   * Subscribes to the relay's bandwidth probe track of `sizeBytes`, times its
+   * arrival and unsubscribes. Use `safeBitrateBps` of the result to pick the
+   * first catalog track. The relay must enable probing
+   * (RELAY_BANDWIDTH_PROBE_MAX_BYTES).
+   */
+  async probeBandwidth(sizeBytes: number, timeoutMs = 5000): Promise<BandwidthEstimateMessage> {
+    const probe = new BandwidthProbe()
+    let finish: () => void = () => {}
+    const finished = new Promise<void>((resolve) => {
+      finish = resolve
+    })
+    const { requestId, subscribeOk } = await this.subscribe(
+      bandwidthProbeNamespace(),
+      bandwidthProbeTrackName(BigInt(sizeBytes)),
+      ''
+    )
+    this.setOnSubgroupObjectHandler(subscribeOk.trackAlias, (_groupId, message) => {
+      probe.recordObject(message.objectPayloadLength, performance.now())
+      if (probe.receivedBytes >= BigInt(sizeBytes)) {
+        finish()
+      }
+    })
+    const timer = setTimeout(finish, timeoutMs)
+    try {
+      await finished
+    } finally {
+      clearTimeout(timer)
+      await this.unsubscribe(requestId)
+    }
+    const estimate = probe.estimate()
+    if (!estimate) {
+      throw new Error(`bandwidth probe received ${probe.receivedBytes} bytes; too little to measure`)
+    }
+    return estimate
+  }
+
+  /** Narrows or extends a live subscription without unsubscribing. */
+  async subscribeUpdate(subscriptionRequestId: bigint, options: SubscribeUpdateOptions): Promise<void> {
+    const client = this.requireConnectedClient()
+    await client.sendSubscribeUpdateMessage(
+      this.issueRequestId(),
+      subscriptionRequestId,
+      options.startGroup,
+      options.startObject ?? 0n,
+      options.endGroup,
+      options.subscriberPriority ?? 0,
+      options.forward ?? true
+    )
+  }
+
   async unsubscribe(requestId: bigint): Promise<void> {
     const client = this.requireConnectedClient()
     const trackAlias = this.subscriptionTrackAliases.get(requestId)
     await client.sendUnsubscribe(requestId)
     this.subscriptionTrackAliases.delete(requestId)
+    this.subscribeIntents.delete(requestId)
     if (trackAlias !== undefined) {
       this.clearSubgroupObjectHandler(trackAlias)
     }
   }
 
+  /** Ends an incoming subscription with PUBLISH_DONE, e.g. status 0x2n (TRACK_ENDED) when the source stops. */
+  async sendPublishDone(requestId: bigint, statusCode: bigint, reasonPhrase: string): Promise<void> {
+    await this.requireConnectedClient().sendPublishDone(requestId, statusCode, reasonPhrase)
+  }
+
   async sendSubgroupTextForTrack(trackNamespace: string[], trackName: string, text: string): Promise<void> {
     const client = this.requireConnectedClient()
     const aliases = client.getTrackSubscribers(trackNamespace, trackName)
@@ -428,8 +708,12 @@ export class MoqtClientWrapper {
       return
     }
 
+    this.prunePublishTracks(trackNamespace, trackName, aliases)
     for (const alias of aliases) {
-      await this.sendSubgroupTextForAlias(alias, text)
+      await this.publishTrackFor(client, trackNamespace, trackName, alias).sendObject(
+        new TextEncoder().encode(text),
+        undefined
+      )
     }
   }
 
@@ -458,6 +742,14 @@ export class MoqtClientWrapper {
       this.onPublishNamespaceDoneHandler?.(message)
     })
 
+    this.client.onNamespaceAdded((message: NamespaceDeltaMessage) => {
+      this.onNamespaceAddedHandler?.(message)
+    })
+
+    this.client.onNamespaceRemoved((message: NamespaceDeltaMessage) => {
+      this.onNamespaceRemovedHandler?.(message)
+    })
+
     this.client.onPublishNamespaceResponse((response: NamespaceOkMessage | RequestErrorMessage) => {
       this.onPublishNamespaceResponseHandler?.(response)
       const pending = this.pendingPublishNamespace.get(response.requestId)
@@ -537,6 +829,17 @@ export class MoqtClientWrapper {
       this.incomingUnsubscribeHandler?.(requestId)
     })
 
+    this.client.onPublishDone((message: PublishDoneMessage) => {
+      // The subscription is over: drop it so it is not replayed on reconnect.
+      const trackAlias = this.subscriptionTrackAliases.get(message.requestId)
+      this.subscriptionTrackAliases.delete(message.requestId)
+      this.subscribeIntents.delete(message.requestId)
+      if (trackAlias !== undefined) {
+        this.clearSubgroupObjectHandler(trackAlias)
+      }
+      this.onPublishDoneHandler?.(message)
+    })
+
     this.client.onObjectDatagram((message: ObjectDatagramMessage) => {
       this.onObjectDatagramHandler?.(message)
     })
@@ -560,52 +863,204 @@ export class MoqtClientWrapper {
       }
       this.subscriptionState.bufferSubgroupObject(trackAlias, groupId, subgroupObject)
     })
+    this.client.onSubgroupStreamObject((message: SubgroupStreamObjectMessage) => {
+      this.onSubgroupStreamObjectHandler?.(message)
+    })
     this.client.onFetchResponse((response: FetchOkMessage | RequestErrorMessage) => {
       this.onFetchResponseHandler?.(response)
     })
+    this.client.onTrackStatus((response: TrackStatusMessage | RequestErrorMessage) => {
+      const pending = this.pendingTrackStatus.get(response.requestId)
+      if (!pending) {
+        return
+      }
+      this.pendingTrackStatus.delete(response.requestId)
+      if (isRequestError(response)) {
+        pending.reject(new Error(`TRACK_STATUS_ERROR ${response.errorCode}: ${response.reasonPhrase}`))
+      } else {
+        pending.resolve(response)
+      }
+    })
     this.client.onFetchObject((message: FetchObjectMessage) => {
       const handler = this.fetchObjectHandlers.get(BigInt(message.requestId))
       handler?.(message)
     })
-    this.client.onConnectionClosed(() => this.handleConnectionClosed())
+    this.client.onGoaway((newSessionUri: string) => {
+      this.onGoawayHandler?.(newSessionUri)
+      if (this.connectOptions.autoMigrateOnGoaway) {
+        void this.migrateSession(newSessionUri).catch((error) => {
+          console.error('[moqt] migration after GOAWAY failed', error)
+        })
+      }
+    })
+    this.client.onRequestsBlocked((maxRequestId: bigint) => {
+      this.onRequestsBlockedHandler?.(BigInt(maxRequestId))
+    })
+    this.client.onReconnected((subscriptionRequestIds, trackAliases) =>
+      this.handleReconnected(subscriptionRequestIds, trackAliases)
+    )
+    this.client.onConnectionClosed((closeCode, reason) => this.handleConnectionClosed(closeCode, reason))
   }
 
-  private async sendSubgroupTextForAlias(trackAlias: bigint, text: string): Promise<void> {
-    const client = this.requireConnectedClient()
-    const state = client.getSubgroupState(trackAlias) as SubgroupState
-    const publisherPriority = 0
-
-    if (!state.headerSent) {
-      await client.sendSubgroupHeader(trackAlias, state.groupId, state.subgroupId, publisherPriority)
-      client.markSubgroupHeaderSent(trackAlias)
-    }
-
-    const payload = new TextEncoder().encode(text)
-    await client.sendSubgroupObject(
-      trackAlias,
-      state.groupId,
-      state.subgroupId,
-      state.objectId,
-      undefined,
-      payload,
-      undefined
-    )
-    client.incrementSubgroupObject(trackAlias)
+  private async migrateSession(newSessionUri: string): Promise<void> {
+    const oldClient = this.client
+    const url = newSessionUri || this.url
+    if (!oldClient || !url) {
+      return
+    }
+    // The old session is closed by us once the new one is up; its close must
+    // not tear down the wrapper state that is being carried over.
+    oldClient.onConnectionClosed(() => {})
+    oldClient.onGoaway(() => {})
+
+    const oldSubscriptions = this.detachSession()
+    this.url = url
+
+    try {
+      await this.openSession(url, this.connectOptions)
+    } catch (error) {
+      this.cleanupClient()
+      throw error
+    }
+    console.info('[moqt] migrated after GOAWAY', { url })
+    const subscriptionRequestIds = await this.replaySession(oldSubscriptions)
+
+    try {
+      await oldClient.close()
+    } catch (error) {
+      console.warn('[moqt] failed to close the old session after GOAWAY', error)
+    }
+    this.onMigratedHandler?.(subscriptionRequestIds)
+  }
+
+  // Drops the per-session state and returns the subscriptions to replay on the
+  // next one. Namespace intents and object handlers are kept.
+  private detachSession(): ReplayedSubscription[] {
+    const subscriptions = [...this.subscribeIntents.entries()].map(([requestId, intent]) => ({
+      requestId,
+      intent,
+      trackAlias: this.subscriptionTrackAliases.get(requestId)
+    }))
+    this.client = null
+    this.serverSetupResolve = null
+    this.subscribeIntents.clear()
+    this.subscriptionTrackAliases.clear()
+    this.clearPublishTracks()
+    return subscriptions
+  }
+
+  // Re-establishes namespaces, namespace subscriptions and subscriptions on the
+  // current session; returns old request id -> new request id.
+  private async replaySession(subscriptions: ReplayedSubscription[]): Promise<Map<bigint, bigint>> {
+    for (const { trackNamespace, authInfo } of this.publishNamespaceIntents.values()) {
+      await this.publishNamespace(trackNamespace, authInfo)
+    }
+    for (const { trackNamespacePrefix, authInfo } of this.subscribeNamespaceIntents.values()) {
+      await this.subscribeNamespace(trackNamespacePrefix, authInfo)
+    }
+    const subscriptionRequestIds = new Map<bigint, bigint>()
+    for (const { requestId, intent, trackAlias } of subscriptions) {
+      const handler = trackAlias === undefined ? undefined : this.subscriptionState.getSubgroupObjectHandler(trackAlias)
+      const result = await this.subscribe(intent.trackNamespace, intent.trackName, intent.authInfo, intent.options)
+      if (trackAlias !== undefined) {
+        this.clearSubgroupObjectHandler(trackAlias)
+      }
+      if (handler) {
+        this.setOnSubgroupObjectHandler(result.subscribeOk.trackAlias, handler)
+      }
+      subscriptionRequestIds.set(requestId, result.requestId)
+    }
+    return subscriptionRequestIds
+  }
+
+  private publishTrackFor(
+    client: MOQTClient,
+    trackNamespace: string[],
+    trackName: string,
+    trackAlias: bigint
+  ): PublishTrackHandle {
+    let track = this.publishTracks.get(trackAlias)
+    if (!track) {
+      track = client.createPublishTrack(trackNamespace, trackName, { trackAlias })
+      this.publishTracks.set(trackAlias, track)
+    }
+    return track
+  }
+
+  // Aliases are released on UNSUBSCRIBE and may be reused, so drop the
+  // handles of this track whose subscriber is gone.
+  private prunePublishTracks(trackNamespace: string[], trackName: string, liveAliases: BigUint64Array): void {
+    const key = namespaceKey(trackNamespace)
+    for (const [alias, track] of this.publishTracks) {
+      const sameTrack = namespaceKey(track.trackNamespace) === key && track.trackName === trackName
+      if (sameTrack && !liveAliases.includes(alias)) {
+        this.publishTracks.delete(alias)
+        track.free()
+      }
+    }
+  }
+
+  private clearPublishTracks(): void {
+    for (const track of this.publishTracks.values()) {
+      track.free()
+    }
+    this.publishTracks.clear()
   }
 
-  // MOQ-T draft-14 §9.1: client-initiated Request IDs start at 0 and increase by
-  // 2 (clients use even ids, servers odd). One counter covers every request type
-  // (PUBLISH_NAMESPACE / SUBSCRIBE_NAMESPACE / SUBSCRIBE / FETCH) so ids stay
-  // unique within the session.
+  // MOQ-T draft-14 §9.1: the client's one counter covers every request type
+  // (PUBLISH_NAMESPACE / SUBSCRIBE_NAMESPACE / SUBSCRIBE / FETCH), including the
+  // requests it re-issues itself on reconnect, so ids stay unique within the session.
   private issueRequestId(): bigint {
-    const requestId = this.nextRequestId
-    this.nextRequestId += 2n
-    return requestId
+    return this.requireConnectedClient().allocateRequestId()
+  }
+
+  // MOQTClient has re-issued the subscriptions on a new session; move what this
+  // wrapper keys by request id or track alias over to the new ones.
+  private handleReconnected(subscriptionRequestIds: Map<bigint, bigint>, trackAliases: Map<bigint, bigint>): void {
+    console.info('[moqt] reconnected', { url: this.url })
+    const subscribeIntents = new Map<bigint, SubscribeIntent>()
+    for (const [oldRequestId, newRequestId] of subscriptionRequestIds) {
+      const intent = this.subscribeIntents.get(oldRequestId)
+      if (intent) {
+        subscribeIntents.set(newRequestId, intent)
+      }
+    }
+    this.subscribeIntents.clear()
+    for (const [requestId, intent] of subscribeIntents) {
+      this.subscribeIntents.set(requestId, intent)
+    }
+    const newRequestIds = new Set(subscriptionRequestIds.values())
+    for (const requestId of [...this.subscriptionTrackAliases.keys()]) {
+      if (!newRequestIds.has(requestId)) {
+        this.subscriptionTrackAliases.delete(requestId)
+      }
+    }
+    // Collect first: an old alias may be reused as another track's new one.
+    const handlers = [...trackAliases].map(
+      ([oldAlias, newAlias]) => [newAlias, this.subscriptionState.getSubgroupObjectHandler(oldAlias)] as const
+    )
+    for (const oldAlias of trackAliases.keys()) {
+      this.clearSubgroupObjectHandler(oldAlias)
+    }
+    for (const [newAlias, handler] of handlers) {
+      if (handler) {
+        this.setOnSubgroupObjectHandler(newAlias, handler)
+      }
+    }
+    // The relay's subscriptions to this client ended with the old session.
+    this.clearPublishTracks()
+    this.onReconnectedHandler?.(subscriptionRequestIds)
   }
 
-  private handleConnectionClosed(): void {
+  private handleConnectionClosed(closeCode: number, reason: string): void {
+    console.info('[moqt] connection closed', { closeCode, reason })
+    this.notifyConnectionClosed(closeCode, reason)
+  }
+
+  private notifyConnectionClosed(closeCode: number, reason: string): void {
+    const handler = this.onConnectionClosedHandler
     this.cleanupClient()
-    this.onConnectionClosedHandler?.()
+    handler?.(closeCode, reason)
   }
 
   private cleanupClient(): void {
@@ -614,20 +1069,32 @@ export class MoqtClientWrapper {
     this.pendingPublishNamespace.clear()
     this.pendingSubscribeNamespace.clear()
     this.pendingSubscribe.clear()
+    this.pendingTrackStatus.clear()
     this.subscriptionTrackAliases.clear()
-    this.nextRequestId = 0n
+    this.clearPublishTracks()
+    this.publishNamespaceIntents.clear()
+    this.subscribeNamespaceIntents.clear()
+    this.subscribeIntents.clear()
     this.onPublishNamespaceHandler = null
     this.onPublishNamespaceDoneHandler = null
     this.onPublishNamespaceResponseHandler = null
     this.onSubscribeNamespaceResponseHandler = null
+    this.onNamespaceAddedHandler = null
+    this.onNamespaceRemovedHandler = null
     this.onSubscribeResponseHandler = null
     this.onConnectionClosedHandler = null
     this.incomingSubscribeHandler = null
     this.incomingUnsubscribeHandler = null
+    this.onPublishDoneHandler = null
     this.onServerSetupHandler = null
     this.onObjectDatagramHandler = null
     this.onObjectDatagramStatusHandler = null
     this.onSubgroupHeaderHandler = null
+    this.onSubgroupStreamObjectHandler = null
+    this.onGoawayHandler = null
+    this.onRequestsBlockedHandler = null
+    this.onMigratedHandler = null
+    this.onReconnectedHandler = null
     this.clearSubgroupObjectHandlers()
   }
 
@@ -639,13 +1106,25 @@ export class MoqtClientWrapper {
   }
 }
 
+function namespaceKey(namespace: string[]): string {
+  return JSON.stringify(namespace)
+}
+
 function isRequestError(
-  message: NamespaceOkMessage | RequestErrorMessage | SubscribeOkMessage
+  message: NamespaceOkMessage | RequestErrorMessage | SubscribeOkMessage | TrackStatusMessage
 ): message is RequestErrorMessage {
   return 'errorCode' in message
 }
 
-const defaultIncomingPublishNamespaceHandler: IncomingPublishNamespaceHandler = async ({ respondOk }) => {
+const defaultIncomingPublishNamespaceHandler: IncomingPublishNamespaceHandler = async ({
+  publishNamespace,
+  respondOk,
+  respondError
+}) => {
+  if (publishNamespace.duplicateOf !== undefined) {
+    await respondError(0n, `namespace already announced by request ${publishNamespace.duplicateOf}`)
+    return
+  }
   await respondOk()
 }
 
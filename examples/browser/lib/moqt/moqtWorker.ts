@@ -0,0 +1,96 @@
+// Runs a MOQTClient inside a dedicated Worker so MoQ I/O stays off the UI
+// thread. Driven by MoqtWorkerBridge; see workerProtocol.ts for the messages.
+import init, { MOQTClient, SubscribeHandle, type TrackObjectMessage } from '../../pkg/moqt_client_wasm'
+import type { MoqtWorkerEvent, MoqtWorkerRequest } from './workerProtocol'
+
+const MOQ_TRANSPORT_VERSION = 0xff00000en
+
+let client: MOQTClient | null = null
+const subscriptions = new Map<bigint, SubscribeHandle>()
+
+function post(event: MoqtWorkerEvent, transfer: Transferable[] = []): void {
+  self.postMessage(event, transfer)
+}
+
+function describeError(error: unknown): string {
+  if (error instanceof Error) {
+    return error.message
+  }
+  if (error && typeof error === 'object' && 'reasonPhrase' in error) {
+    return String(error.reasonPhrase)
+  }
+  return String(error)
+}
+
+function requireClient(): MOQTClient {
+  if (!client) {
+    throw new Error('not connected')
+  }
+  return client
+}
+
+function forwardObject(requestId: bigint, message: TrackObjectMessage): void {
+  const object = message.toObject()
+  // Each message owns its payload buffer, so it can be moved rather than copied.
+  const transfer = 'objectPayload' in object ? [object.objectPayload.buffer as ArrayBuffer] : []
+  post({ type: 'object', requestId, object }, transfer)
+}
+
+async function connect(url: string, maxRequestId: bigint, options?: WebTransportOptions): Promise<void> {
+  await init()
+  const connecting = new MOQTClient(url)
+  await connecting.start(options)
+  const serverSetup = new Promise<void>((resolve) => connecting.onServerSetup(() => resolve()))
+  connecting.onConnectionClosed((closeCode, reason) => {
+    client = null
+    subscriptions.clear()
+    post({ type: 'closed', closeCode, reason })
+  })
+  await connecting.sendClientSetup(new BigUint64Array([MOQ_TRANSPORT_VERSION]), maxRequestId)
+  await serverSetup
+  client = connecting
+}
+
+async function handleRequest(request: MoqtWorkerRequest): Promise<bigint | undefined> {
+  switch (request.type) {
+    case 'connect':
+      await connect(request.url, request.maxRequestId ?? 100n, request.webTransportOptions)
+      return undefined
+    case 'subscribeTrack': {
+      const subscription = await requireClient().subscribeTrack(
+        request.trackNamespace,
+        request.trackName,
+        request.options
+      )
+      const requestId = subscription.requestId
+      subscription.onObject((message) => forwardObject(requestId, message))
+      subscription.onDone((statusCode, reasonPhrase) => {
+        subscriptions.delete(requestId)
+        post({ type: 'done', requestId, statusCode, reasonPhrase })
+      })
+      subscriptions.set(requestId, subscription)
+      return requestId
+    }
+    case 'unsubscribe': {
+      const subscription = subscriptions.get(request.requestId)
+      subscriptions.delete(request.requestId)
+      await subscription?.unsubscribe()
+      return undefined
+    }
+    case 'close':
+      subscriptions.clear()
+      await client?.close(request.closeCode, request.reason)
+      client = null
+      return undefined
+  }
+}
+
+self.onmessage = async (event: MessageEvent<MoqtWorkerRequest>) => {
+  const request = event.data
+  try {
+    const requestId = await handleRequest(request)
+    post({ type: 'result', id: request.id, requestId })
+  } catch (error) {
+    post({ type: 'error', id: request.id, message: describeError(error) })
+  }
+}
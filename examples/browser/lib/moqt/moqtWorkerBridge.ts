@@ -0,0 +1,122 @@
+import type { SubscribeOptions } from '../../pkg/moqt_client_wasm'
+import type { MoqtWorkerEvent, MoqtWorkerRequest, WorkerTrackObject } from './workerProtocol'
+
+type WorkerTrackObjectHandler = (object: WorkerTrackObject) => void
+type WorkerTrackDoneHandler = (statusCode: bigint, reasonPhrase: string) => void
+type ConnectionClosedHandler = (closeCode: number, reason: string) => void
+type PendingRequest = { resolve: (requestId: bigint | undefined) => void; reject: (error: Error) => void }
+
+// Distributes Omit over the request union so each variant keeps its fields.
+type WorkerRequestBody = MoqtWorkerRequest extends infer Request
+  ? Request extends MoqtWorkerRequest
+    ? Omit<Request, 'id'>
+    : never
+  : never
+
+/**
+ * Main-thread side of a MOQTClient running in a dedicated Worker
+ * (moqtWorker.ts). Objects arrive as plain `toObject()` data with their
+ * payload buffers transferred, so the UI thread never touches the wasm module.
+ */
+export class MoqtWorkerBridge {
+  private readonly worker: Worker
+  private nextId = 0
+  private readonly pending = new Map<number, PendingRequest>()
+  private readonly objectHandlers = new Map<bigint, WorkerTrackObjectHandler>()
+  private readonly doneHandlers = new Map<bigint, WorkerTrackDoneHandler>()
+  private onConnectionClosedHandler: ConnectionClosedHandler | null = null
+
+  constructor(worker?: Worker) {
+    this.worker = worker ?? new Worker(new URL('./moqtWorker.ts', import.meta.url), { type: 'module' })
+    this.worker.onmessage = (event: MessageEvent<MoqtWorkerEvent>) => this.handleEvent(event.data)
+  }
+
+  async connect(url: string, maxRequestId?: bigint, webTransportOptions?: WebTransportOptions): Promise<void> {
+    await this.request({ type: 'connect', url, maxRequestId, webTransportOptions })
+  }
+
+  /** Resolves to the subscription's request id once SUBSCRIBE is sent. */
+  async subscribeTrack(
+    trackNamespace: string[],
+    trackName: string,
+    onObject: WorkerTrackObjectHandler,
+    options?: SubscribeOptions & { onDone?: WorkerTrackDoneHandler }
+  ): Promise<bigint> {
+    const { onDone, ...subscribeOptions } = options ?? {}
+    const requestId = await this.request({
+      type: 'subscribeTrack',
+      trackNamespace,
+      trackName,
+      options: subscribeOptions
+    })
+    if (requestId === undefined) {
+      throw new Error('worker did not return a request id')
+    }
+    this.objectHandlers.set(requestId, onObject)
+    if (onDone) {
+      this.doneHandlers.set(requestId, onDone)
+    }
+    return requestId
+  }
+
+  async unsubscribe(requestId: bigint): Promise<void> {
+    this.objectHandlers.delete(requestId)
+    this.doneHandlers.delete(requestId)
+    await this.request({ type: 'unsubscribe', requestId })
+  }
+
+  async close(closeCode?: number, reason?: string): Promise<void> {
+    this.objectHandlers.clear()
+    this.doneHandlers.clear()
+    await this.request({ type: 'close', closeCode, reason })
+  }
+
+  setOnConnectionClosedHandler(handler: ConnectionClosedHandler | null): void {
+    this.onConnectionClosedHandler = handler
+  }
+
+  /** Stops the worker; pending requests are rejected. */
+  terminate(): void {
+    this.worker.terminate()
+    for (const pending of this.pending.values()) {
+      pending.reject(new Error('worker terminated'))
+    }
+    this.pending.clear()
+    this.objectHandlers.clear()
+    this.doneHandlers.clear()
+  }
+
+  private request(body: WorkerRequestBody): Promise<bigint | undefined> {
+    const id = this.nextId++
+    return new Promise((resolve, reject) => {
+      this.pending.set(id, { resolve, reject })
+      this.worker.postMessage({ ...body, id } as MoqtWorkerRequest)
+    })
+  }
+
+  private handleEvent(event: MoqtWorkerEvent): void {
+    switch (event.type) {
+      case 'result':
+        this.pending.get(event.id)?.resolve(event.requestId)
+        this.pending.delete(event.id)
+        return
+      case 'error':
+        this.pending.get(event.id)?.reject(new Error(event.message))
+        this.pending.delete(event.id)
+        return
+      case 'object':
+        this.objectHandlers.get(event.requestId)?.(event.object)
+        return
+      case 'done':
+        this.objectHandlers.delete(event.requestId)
+        this.doneHandlers.get(event.requestId)?.(event.statusCode, event.reasonPhrase)
+        this.doneHandlers.delete(event.requestId)
+        return
+      case 'closed':
+        this.objectHandlers.clear()
+        this.doneHandlers.clear()
+        this.onConnectionClosedHandler?.(event.closeCode, event.reason)
+        return
+    }
+  }
+}
@@ -0,0 +1,40 @@
+import type {
+  ObjectDatagramMessage,
+  ObjectDatagramStatusMessage,
+  PlainMessage,
+  SubgroupStreamObjectMessage,
+  SubscribeOptions
+} from '../../pkg/moqt_client_wasm'
+
+/** A track object as `toObject()` returns it, cloned across the worker boundary. */
+export type WorkerTrackObject =
+  | PlainMessage<SubgroupStreamObjectMessage>
+  | PlainMessage<ObjectDatagramMessage>
+  | PlainMessage<ObjectDatagramStatusMessage>
+
+/** Main thread -> worker. `id` pairs the request with its `result` / `error`. */
+export type MoqtWorkerRequest =
+  | {
+      id: number
+      type: 'connect'
+      url: string
+      maxRequestId?: bigint
+      webTransportOptions?: WebTransportOptions
+    }
+  | {
+      id: number
+      type: 'subscribeTrack'
+      trackNamespace: string[]
+      trackName: string
+      options?: SubscribeOptions
+    }
+  | { id: number; type: 'unsubscribe'; requestId: bigint }
+  | { id: number; type: 'close'; closeCode?: number; reason?: string }
+
+/** Worker -> main thread. */
+export type MoqtWorkerEvent =
+  | { type: 'result'; id: number; requestId?: bigint }
+  | { type: 'error'; id: number; message: string }
+  | { type: 'object'; requestId: bigint; object: WorkerTrackObject }
+  | { type: 'done'; requestId: bigint; statusCode: bigint; reasonPhrase: string }
+  | { type: 'closed'; closeCode: number; reason: string }
@@ -55,6 +55,7 @@ pub async fn subscribe_and_receive(namespace: &str, track_name: &str) -> Result<
         group_order: GroupOrder::Ascending,
         forward: true,
         filter_type: FilterType::NextGroupStart,
+        max_cache_duration: None,
     };
 
     info!(namespace, track_name, "subscribing");
@@ -103,6 +103,16 @@ impl<T: TransportProtocol> Client<T> {
                                 publish_namespace_done_handler.track_namespace
                             );
                         }
+                        moqt::SessionEvent::PublishNamespaceCancel(
+                            publish_namespace_cancel_handler,
+                        ) => {
+                            tracing::info!(
+                                "Received: {} Publish Namespace Cancel: {} ({})",
+                                _label,
+                                publish_namespace_cancel_handler.track_namespace,
+                                publish_namespace_cancel_handler.reason_phrase
+                            );
+                        }
                         moqt::SessionEvent::SubscribeNameSpace(subscribe_namespace_handler) => {
                             tracing::info!(
                                 "Received: {} Subscribe Namespace: {}",
@@ -139,6 +149,13 @@ impl<T: TransportProtocol> Client<T> {
                             )
                             .await;
                         }
+                        moqt::SessionEvent::SubscribeUpdate(subscribe_update_handler) => {
+                            tracing::info!(
+                                "Received: {} Subscribe Update {}",
+                                _label,
+                                subscribe_update_handler.subscription_request_id()
+                            );
+                        }
                         moqt::SessionEvent::Unsubscribe(unsubscribe_handler) => {
                             tracing::info!(
                                 "Received: {} Unsubscribe {}",
@@ -146,6 +163,15 @@ impl<T: TransportProtocol> Client<T> {
                                 unsubscribe_handler.subscribe_id()
                             );
                         }
+                        moqt::SessionEvent::PublishDone(publish_done_handler) => {
+                            tracing::info!(
+                                "Received: {} PublishDone {} code {} ({})",
+                                _label,
+                                publish_done_handler.request_id,
+                                publish_done_handler.status_code,
+                                publish_done_handler.reason_phrase
+                            );
+                        }
                         moqt::SessionEvent::UnsubscribeNamespace(unsubscribe_namespace_handler) => {
                             tracing::info!(
                                 "Received: {} Unsubscribe Namespace: {}",
@@ -163,6 +189,27 @@ impl<T: TransportProtocol> Client<T> {
                         moqt::SessionEvent::Fetch(_) => {
                             tracing::info!("Received: {} Fetch", _label);
                         }
+                        moqt::SessionEvent::FetchCancel(fetch_cancel_handler) => {
+                            tracing::info!(
+                                "Received: {} Fetch Cancel {}",
+                                _label,
+                                fetch_cancel_handler.request_id()
+                            );
+                        }
+                        moqt::SessionEvent::TrackStatusRequest(track_status_handler) => {
+                            tracing::info!(
+                                "Received: {} Track Status Request {}",
+                                _label,
+                                track_status_handler.request_id()
+                            );
+                        }
+                        moqt::SessionEvent::GoAway(new_session_uri) => {
+                            tracing::info!(
+                                "Received: {} GoAway new_session_uri={}",
+                                _label,
+                                new_session_uri
+                            );
+                        }
                     };
                 }
             })
@@ -364,6 +411,7 @@ impl<T: TransportProtocol> Client<T> {
             group_order: moqt::GroupOrder::Ascending,
             forward: true,
             filter_type: moqt::FilterType::LargestObject,
+            max_cache_duration: None,
         };
         let mut subscriber = self.session.subscriber();
         let subscription = match subscriber
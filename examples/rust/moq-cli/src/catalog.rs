@@ -2,7 +2,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use media_streaming_format::{
-    Catalog, KnownPackaging, KnownTrackRole, Packaging, Track, TrackRole,
+    Catalog, CatalogObject, KnownPackaging, KnownTrackRole, Packaging, Track, TrackRole,
 };
 
 /// Track name carrying the catalog on every namespace.
@@ -48,6 +48,7 @@ pub fn build_video_catalog(namespace: &str, media_track: &str, codec: &str) -> C
         add_tracks: None,
         remove_tracks: None,
         clone_tracks: None,
+        catalog_sequence: None,
     }
 }
 
@@ -55,8 +56,13 @@ pub fn serialize(catalog: &Catalog) -> Result<Vec<u8>> {
     serde_json::to_vec(catalog).context("serialize catalog json")
 }
 
-pub fn parse(bytes: &[u8]) -> Result<Catalog> {
-    serde_json::from_slice(bytes).context("parse catalog json")
+pub fn serialize_object(object: &CatalogObject) -> Result<Vec<u8>> {
+    object.to_vec().context("serialize catalog object")
+}
+
+/// Parses one catalog track object, a full catalog or a delta.
+pub fn parse_object(bytes: &[u8]) -> Result<CatalogObject> {
+    CatalogObject::from_slice(bytes).context("parse catalog object")
 }
 
 pub fn packaging_str(packaging: &Packaging) -> String {
@@ -83,7 +89,9 @@ mod tests {
     fn round_trips_video_catalog() {
         let catalog = build_video_catalog("tokyo/cam01", "video", "avc3.640028");
         let bytes = serialize(&catalog).unwrap();
-        let parsed = parse(&bytes).unwrap();
+        let CatalogObject::Full(parsed) = parse_object(&bytes).unwrap() else {
+            panic!("expected a full catalog");
+        };
 
         let track = &parsed.tracks.unwrap()[0];
         assert_eq!(track.name, "video");
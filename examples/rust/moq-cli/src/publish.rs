@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use bytes::Bytes;
+use media_streaming_format::{Catalog, CatalogDelta, CatalogObject};
 use moqt::PublishOption;
 use tokio::io::AsyncReadExt;
 use tracing::info;
@@ -113,13 +113,19 @@ pub async fn run(args: PublishArgs) -> Result<()> {
     Ok(())
 }
 
-/// Builds the catalog once the codec is known and re-publishes it at each group
-/// boundary so subscribers that join mid-stream pick it up at the next keyframe.
+/// Groups between full catalogs. Within a group, changes go out as deltas.
+const FULL_CATALOG_INTERVAL_GROUPS: u32 = 10;
+
+/// Publishes the catalog once the codec is known. Each catalog group opens
+/// with the full catalog; a change inside the group is sent as a JSON Patch
+/// delta instead of the whole catalog. A new group (and full catalog) starts
+/// every `FULL_CATALOG_INTERVAL_GROUPS` keyframes so late joiners pick one up.
 struct CatalogPublisher {
     writer: TrackWriter,
     namespace: String,
     name: String,
-    payload: Option<Bytes>,
+    published: Option<Catalog>,
+    keyframes_since_full: u32,
 }
 
 impl CatalogPublisher {
@@ -128,7 +134,8 @@ impl CatalogPublisher {
             writer,
             namespace,
             name,
-            payload: None,
+            published: None,
+            keyframes_since_full: 0,
         }
     }
 
@@ -136,13 +143,49 @@ impl CatalogPublisher {
         if !frame.keyframe {
             return Ok(());
         }
-        if self.payload.is_none() {
-            let built = catalog::build_video_catalog(&self.namespace, &self.name, codec);
-            self.payload = Some(catalog::serialize(&built)?.into());
+        let mut next = catalog::build_video_catalog(&self.namespace, &self.name, codec);
+        let Some(published) = &self.published else {
             info!(codec = %codec, "codec resolved");
+            return self.publish_full(next, 0).await;
+        };
+        let sequence = published.catalog_sequence.unwrap_or(0) + 1;
+        self.keyframes_since_full += 1;
+        if self.keyframes_since_full >= FULL_CATALOG_INTERVAL_GROUPS {
+            return self.publish_full(next, sequence).await;
+        }
+        // generatedAt alone does not make a new revision.
+        let generated_at = std::mem::replace(&mut next.generated_at, published.generated_at);
+        next.catalog_sequence = published.catalog_sequence;
+        if next == *published {
+            return Ok(());
         }
-        let payload = self.payload.clone().expect("catalog payload built above");
-        self.writer.write_group(payload).await
+        next.generated_at = generated_at;
+        next.catalog_sequence = Some(sequence);
+        let delta = CatalogDelta::between(published, &next)?;
+        info!(
+            sequence,
+            operations = delta.patch.len(),
+            "publishing catalog delta"
+        );
+        self.writer
+            .write(
+                catalog::serialize_object(&CatalogObject::Delta(delta))?.into(),
+                vec![],
+            )
+            .await?;
+        self.published = Some(next);
+        Ok(())
+    }
+
+    async fn publish_full(&mut self, mut full: Catalog, sequence: u64) -> Result<()> {
+        full.catalog_sequence = Some(sequence);
+        self.writer.start_group().await?;
+        self.writer
+            .write(catalog::serialize(&full)?.into(), vec![])
+            .await?;
+        self.published = Some(full);
+        self.keyframes_since_full = 0;
+        Ok(())
     }
 
     async fn finish(self) -> Result<()> {
@@ -1,13 +1,14 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use moqt::SessionEvent;
+use media_streaming_format::{Catalog, CatalogReceiver, CatalogUpdate};
+use moqt::{QUIC, Session, SessionEvent};
 use tokio::io::AsyncWriteExt;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::catalog;
 use crate::cli::SubscribeArgs;
-use crate::transport::{TrackReader, connect_session, subscribe_track};
+use crate::transport::{TrackReader, connect_session, fetch_current_group, subscribe_track};
 
 pub async fn run(args: SubscribeArgs) -> Result<()> {
     let track = &args.track;
@@ -35,21 +36,39 @@ pub async fn run(args: SubscribeArgs) -> Result<()> {
     // Subscribe to both tracks up front so NextGroupStart catches the first
     // group; otherwise a burst publisher can finish before we are done
     // reading the catalog and the media subscription would start too late.
-    let mut catalog_reader =
-        TrackReader::new(subscribe_track(&session, &track.namespace, catalog::TRACK).await?);
+    let (catalog_request_id, catalog_factory) =
+        subscribe_track(&session, &track.namespace, catalog::TRACK).await?;
+    let mut catalog_follower = CatalogFollower {
+        reader: TrackReader::new(catalog_factory),
+        session: session.clone(),
+        subscribe_request_id: catalog_request_id,
+        state: CatalogReceiver::new(),
+    };
     info!(
         namespace = track.namespace,
         track = track.name,
         "subscribing"
     );
-    let mut media_reader =
-        TrackReader::new(subscribe_track(&session, &track.namespace, &track.name).await?);
+    let (_, media_factory) = subscribe_track(&session, &track.namespace, &track.name).await?;
+    let mut media_reader = TrackReader::new(media_factory);
     info!("subscribed");
 
-    match catalog_reader.next_object().await? {
-        Some(object) => describe_catalog(&catalog::parse(&object)?),
+    match catalog_follower.first().await? {
+        Some(catalog) => describe_catalog(&catalog),
         None => anyhow::bail!("catalog stream ended before any object"),
     }
+    tokio::spawn(async move {
+        loop {
+            match catalog_follower.next_update().await {
+                Ok(Some(catalog)) => describe_catalog(&catalog),
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("catalog track error: {e:#}");
+                    break;
+                }
+            }
+        }
+    });
 
     let mut out = tokio::io::stdout();
     while let Some(object) = media_reader.next_object().await? {
@@ -60,7 +79,63 @@ pub async fn run(args: SubscribeArgs) -> Result<()> {
     Ok(())
 }
 
-fn describe_catalog(catalog: &media_streaming_format::Catalog) {
+/// Tracks the catalog across full catalogs and deltas. When a delta cannot
+/// be applied (joined mid-group, or an object was missed) it resyncs with a
+/// joining FETCH of the current catalog group, which starts with the full
+/// catalog.
+struct CatalogFollower {
+    reader: TrackReader,
+    session: Arc<Session<QUIC>>,
+    subscribe_request_id: u64,
+    state: CatalogReceiver,
+}
+
+impl CatalogFollower {
+    /// The catalog as of now: fetched from the current group when the
+    /// publisher already sent one, otherwise the first one on the track.
+    async fn first(&mut self) -> Result<Option<Catalog>> {
+        match self.resync().await {
+            Ok(true) => Ok(self.state.catalog().cloned()),
+            Ok(false) => self.next_update().await,
+            Err(e) => {
+                tracing::debug!("no catalog to fetch yet: {e:#}");
+                self.next_update().await
+            }
+        }
+    }
+
+    /// Waits for the next catalog revision, or `None` when the track ends.
+    async fn next_update(&mut self) -> Result<Option<Catalog>> {
+        while let Some(object) = self.reader.next_object().await? {
+            match self.state.receive(catalog::parse_object(&object)?) {
+                CatalogUpdate::Applied => return Ok(self.state.catalog().cloned()),
+                CatalogUpdate::Stale => {}
+                CatalogUpdate::ResyncRequired => {
+                    warn!("catalog delta does not apply; fetching the full catalog");
+                    match self.resync().await {
+                        Ok(true) => return Ok(self.state.catalog().cloned()),
+                        Ok(false) => {}
+                        // The next catalog group starts with a full catalog.
+                        Err(e) => warn!("catalog resync failed: {e:#}"),
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Applies the current catalog group; true when the catalog changed.
+    async fn resync(&mut self) -> Result<bool> {
+        let mut changed = false;
+        for object in fetch_current_group(&self.session, self.subscribe_request_id).await? {
+            changed |=
+                self.state.receive(catalog::parse_object(&object)?) == CatalogUpdate::Applied;
+        }
+        Ok(changed)
+    }
+}
+
+fn describe_catalog(catalog: &Catalog) {
     let Some(track) = catalog.tracks.as_ref().and_then(|tracks| tracks.first()) else {
         info!("catalog has no tracks");
         return;
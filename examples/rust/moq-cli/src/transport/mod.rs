@@ -3,5 +3,5 @@ mod session;
 mod writer;
 
 pub use reader::TrackReader;
-pub use session::{connect_session, subscribe_track};
+pub use session::{connect_session, fetch_current_group, subscribe_track};
 pub use writer::TrackWriter;
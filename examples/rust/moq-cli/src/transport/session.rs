@@ -1,7 +1,8 @@
 use anyhow::{Context, Result};
+use bytes::Bytes;
 use moqt::{
-    ClientConfig, DataReceiver, Endpoint, FilterType, GroupOrder, QUIC, Session,
-    StreamDataReceiverFactory, SubscribeOption,
+    ClientConfig, DataReceiver, Endpoint, Fetch, FetchObject, FetchOption, FilterType, GroupOrder,
+    QUIC, Session, StreamDataReceiverFactory, SubscribeOption,
 };
 use tracing::info;
 
@@ -22,16 +23,19 @@ pub async fn connect_session(relay: &RelayUrl, insecure: bool) -> Result<Session
     Ok(session)
 }
 
+/// Subscribes to a track and returns the SUBSCRIBE request ID (for joining
+/// FETCHes) with its data stream factory.
 pub async fn subscribe_track(
     session: &Session<QUIC>,
     namespace: &str,
     name: &str,
-) -> Result<StreamDataReceiverFactory<QUIC>> {
+) -> Result<(u64, StreamDataReceiverFactory<QUIC>)> {
     let option = SubscribeOption {
         subscriber_priority: SUBSCRIBER_PRIORITY,
         group_order: GroupOrder::Ascending,
         forward: true,
         filter_type: FilterType::NextGroupStart,
+        max_cache_duration: None,
     };
     let subscription = session
         .subscriber()
@@ -46,5 +50,34 @@ pub async fn subscribe_track(
     let DataReceiver::Stream(factory) = receiver else {
         anyhow::bail!("expected stream data receiver");
     };
-    Ok(factory)
+    Ok((subscription.request_id(), factory))
+}
+
+/// Joining FETCH of the current group of a subscribed track, from its first
+/// object. Returns the payloads in order.
+pub async fn fetch_current_group(
+    session: &Session<QUIC>,
+    subscribe_request_id: u64,
+) -> Result<Vec<Bytes>> {
+    let mut subscriber = session.subscriber();
+    let handle = subscriber
+        .fetch_relative_joining(subscribe_request_id, 0, FetchOption::default())
+        .await
+        .context("joining fetch failed")?;
+    let mut receiver = subscriber
+        .accept_fetch_receiver(&handle)
+        .await
+        .context("failed to accept fetch receiver")?;
+    let mut payloads = Vec::new();
+    loop {
+        match receiver.receive().await? {
+            Fetch::Header(_) => {}
+            Fetch::Object(object) => {
+                if let FetchObject::Payload(payload) = object.fetch_object {
+                    payloads.push(payload);
+                }
+            }
+            Fetch::End => return Ok(payloads),
+        }
+    }
 }
@@ -42,16 +42,6 @@ impl TrackWriter {
             .await
     }
 
-    /// Write a single object as its own, immediately-closed group (catalog snapshots).
-    pub async fn write_group(&mut self, object: Bytes) -> Result<()> {
-        if let Some(group) = self.group.take() {
-            group.finish().await?;
-        }
-        let mut group = self.open_group().await?;
-        group.write_object(object, vec![]).await?;
-        group.finish().await
-    }
-
     /// Close the last open group.
     pub async fn finish(mut self) -> Result<()> {
         if let Some(group) = self.group.take() {
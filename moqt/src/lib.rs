@@ -2,6 +2,13 @@ mod modules;
 pub mod wire;
 
 pub use crate::modules::moqt::control_plane::control_messages::messages::parameters::group_order::GroupOrder;
+pub use modules::moqt::control_plane::constants::TerminationErrorCode;
+#[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::control_plane::control_message_limits::ControlMessageLimits;
+#[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::control_plane::control_message_observer::{
+    ControlMessageDirection, ControlMessageObserver,
+};
 pub use modules::moqt::control_plane::control_messages::key_value_pair::{
     KeyValuePair, VariantType,
 };
@@ -11,18 +18,30 @@ pub use modules::moqt::control_plane::control_messages::messages::parameters::lo
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::enums::SessionEvent;
 #[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::control_plane::handler::fetch_cancel_handler::FetchCancelHandler;
+#[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::handler::fetch_handler::FetchHandler;
 #[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::control_plane::handler::publish_done_handler::PublishDoneHandler;
+#[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::handler::publish_handler::PublishHandler;
 #[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::control_plane::handler::publish_namespace_cancel_handler::PublishNamespaceCancelHandler;
+#[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::handler::publish_namespace_done_handler::PublishNamespaceDoneHandler;
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::handler::publish_namespace_handler::PublishNamespaceHandler;
 #[cfg(not(target_arch = "wasm32"))]
-pub use modules::moqt::control_plane::handler::subscribe_handler::SubscribeHandler;
+pub use modules::moqt::control_plane::handler::subscribe_handler::{
+    SubscribeHandler, SubscribeOkError,
+};
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::handler::subscribe_namespace_handler::SubscribeNamespaceHandler;
 #[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::control_plane::handler::subscribe_update_handler::SubscribeUpdateHandler;
+#[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::control_plane::handler::track_status_request_handler::TrackStatusRequestHandler;
+#[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::handler::unsubscribe_handler::UnsubscribeHandler;
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::handler::unsubscribe_namespace_handler::UnsubscribeNamespaceHandler;
@@ -33,6 +52,20 @@ pub use modules::moqt::control_plane::options::PublishOption;
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::control_plane::options::SubscribeOption;
 #[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::control_plane::options::SubscribeUpdateOption;
+pub use modules::moqt::control_plane::track_alias_allocator::{
+    TrackAliasAllocator, TrackAliasInUse,
+};
+pub use modules::moqt::control_plane::version::ProtocolVersion;
+#[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::data_plane::adaptive_sender::{
+    AdaptiveObjectSender, ObjectTransport, TransportSelection,
+};
+pub use modules::moqt::data_plane::bandwidth_probe::{
+    BANDWIDTH_PROBE_NAMESPACE, BANDWIDTH_PROBE_OBJECT_SIZE, BandwidthEstimate, BandwidthProbeMeter,
+    bandwidth_probe_track_name, parse_bandwidth_probe_track_name,
+};
+#[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::data_plane::datagram::datagram_receiver::DatagramReceiver;
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::data_plane::datagram::datagram_sender::DatagramSender;
@@ -51,6 +84,8 @@ pub use modules::moqt::data_plane::object::subgroup::SubgroupHeaderType;
 pub use modules::moqt::data_plane::object::subgroup::SubgroupId;
 pub use modules::moqt::data_plane::object::subgroup::SubgroupObject;
 pub use modules::moqt::data_plane::object::subgroup::SubgroupObjectField;
+pub use modules::moqt::data_plane::object::subgroup_stream_object::SubgroupStreamContext;
+pub use modules::moqt::data_plane::object::subgroup_stream_object::SubgroupStreamObject;
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::data_plane::stream::fetch_data_receiver::Fetch;
 #[cfg(not(target_arch = "wasm32"))]
@@ -88,8 +123,16 @@ pub use modules::moqt::domains::endpoint::ServerConfig;
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::domains::fetch_handle::FetchHandle;
 #[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::domains::multi_session_publisher::{
+    MultiPublication, MultiSessionPublisher, RelayStatus,
+};
+#[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::domains::publisher::Publisher;
 #[cfg(not(target_arch = "wasm32"))]
+pub use modules::moqt::domains::resilient_subscriber::{
+    PathStatus, ResilientObject, ResilientSubscriber, ResilientSubscription,
+};
+#[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::domains::session::Session;
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::domains::session_context::RequestTimeoutError;
@@ -112,4 +155,10 @@ pub use modules::moqt::protocol::TransportProtocol;
 #[cfg(not(target_arch = "wasm32"))]
 pub use modules::moqt::protocol::WEBTRANSPORT;
 #[cfg(not(target_arch = "wasm32"))]
+pub use modules::transport::connection_stats::ConnectionStats;
+#[cfg(not(target_arch = "wasm32"))]
+pub use modules::transport::server_certificate::CertificateReloader;
+#[cfg(not(target_arch = "wasm32"))]
 pub use modules::transport::transport_send_stream::TransportSendError;
+#[cfg(not(target_arch = "wasm32"))]
+pub use modules::transport::transport_tuning::{CongestionController, TransportTuning};
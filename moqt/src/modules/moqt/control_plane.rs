@@ -1,7 +1,13 @@
 pub(crate) mod constants;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod control_message_limits;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod control_message_observer;
 pub(crate) mod control_messages;
 #[cfg(not(target_arch = "wasm32"))]
 pub(crate) mod enums;
 #[cfg(not(target_arch = "wasm32"))]
 pub(crate) mod handler;
 pub(crate) mod options;
+pub(crate) mod track_alias_allocator;
+pub(crate) mod version;
@@ -1,7 +1,12 @@
 use num_enum::IntoPrimitive;
 
-// for draft-ietf-moq-transport-10
-pub const MOQ_TRANSPORT_VERSION: u32 = 0xff00000e;
+use crate::modules::moqt::control_plane::version::ProtocolVersion;
+
+/// Wire value of [`ProtocolVersion::LATEST`].
+pub const MOQ_TRANSPORT_VERSION: u32 = ProtocolVersion::LATEST as u32;
+
+/// MAX_REQUEST_ID advertised in SETUP; later raises grow it by the same step.
+pub const DEFAULT_MAX_REQUEST_ID: u64 = 1000;
 
 /// Session termination error codes, draft-ietf-moq-transport-14 §13.1.1.
 #[derive(Debug, IntoPrimitive, PartialEq, Clone, Copy)]
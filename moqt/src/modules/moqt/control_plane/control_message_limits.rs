@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Bounds on what a peer may send on the control stream of a server
+/// session. A peer past either is closed with PROTOCOL_VIOLATION.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlMessageLimits {
+    /// Largest control message payload accepted, in bytes. Checked against
+    /// the declared Message Length, before the payload is buffered.
+    pub max_message_bytes: usize,
+    /// Control messages accepted per second; 0 is unlimited.
+    pub max_messages_per_sec: u32,
+}
+
+impl Default for ControlMessageLimits {
+    /// Only what the draft-14 16-bit Message Length allows.
+    fn default() -> Self {
+        Self {
+            max_message_bytes: u16::MAX as usize,
+            max_messages_per_sec: 0,
+        }
+    }
+}
+
+/// Counts control messages in one-second windows against
+/// [`ControlMessageLimits::max_messages_per_sec`].
+#[derive(Debug)]
+pub(crate) struct MessageRateWindow {
+    max_messages: u32,
+    started_at: Instant,
+    received: u32,
+}
+
+impl MessageRateWindow {
+    pub(crate) fn new(max_messages_per_sec: u32) -> Self {
+        Self {
+            max_messages: max_messages_per_sec,
+            started_at: Instant::now(),
+            received: 0,
+        }
+    }
+
+    /// Counts a message received at `now`; false once the window's limit
+    /// is exceeded.
+    pub(crate) fn admit(&mut self, now: Instant) -> bool {
+        if self.max_messages == 0 {
+            return true;
+        }
+        if now.duration_since(self.started_at) >= Duration::from_secs(1) {
+            self.started_at = now;
+            self.received = 0;
+        }
+        self.received += 1;
+        self.received <= self.max_messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_admits_up_to_the_limit_each_second() {
+        // Arrange
+        let mut window = MessageRateWindow::new(2);
+        let now = Instant::now();
+
+        // Act
+        let burst = [window.admit(now), window.admit(now), window.admit(now)];
+        let next_second = window.admit(now + Duration::from_secs(1));
+
+        // Assert
+        assert_eq!(burst, [true, true, false]);
+        assert!(next_second);
+    }
+
+    #[test]
+    fn zero_is_unlimited() {
+        // Arrange
+        let mut window = MessageRateWindow::new(0);
+
+        // Act / Assert
+        let now = Instant::now();
+        assert!((0..10_000).all(|_| window.admit(now)));
+    }
+}
@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use crate::modules::moqt::control_plane::control_messages::control_message_type::ControlMessageType;
+
+/// Whether a session wrote or read the control message it reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlMessageDirection {
+    Sent,
+    Received,
+}
+
+/// Called with every control message a session exchanges once it is
+/// established, e.g. to write an event log. Runs on the sending or receiving
+/// task, so it must not block.
+pub type ControlMessageObserver =
+    Arc<dyn Fn(ControlMessageDirection, ControlMessageType) + Send + Sync>;
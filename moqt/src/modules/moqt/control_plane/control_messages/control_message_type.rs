@@ -36,6 +36,7 @@ pub enum ControlMessageType {
     // track
     TrackStatusRequest = 0x0d,
     TrackStatus = 0x0e,
+    TrackStatusError = 0x0f,
 
     // publish namespace
     PublishNamespace = 0x06,
@@ -1,17 +1,25 @@
 pub mod client_setup;
 pub mod fetch;
+pub mod fetch_cancel;
 pub mod fetch_ok;
 pub mod go_away;
+pub mod max_request_id;
 pub mod namespace_ok;
 pub mod parameters;
 pub mod publish;
+pub mod publish_done;
 pub mod publish_namespace;
+pub mod publish_namespace_cancel;
 pub mod publish_namespace_done;
 pub mod publish_ok;
 pub mod request_error;
+pub mod requests_blocked;
 pub mod server_setup;
 pub mod subscribe;
 pub mod subscribe_namespace;
 pub mod subscribe_ok;
+pub mod subscribe_update;
+pub mod track_status;
+pub mod track_status_request;
 pub mod unsubscribe;
 pub mod unsubscribe_namespace;
@@ -0,0 +1,51 @@
+use crate::modules::extensions::{
+    buf_get_ext::BufGetExt, buf_put_ext::BufPutExt, result_ext::ResultExt,
+};
+use bytes::BytesMut;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FetchCancel {
+    pub request_id: u64,
+}
+
+impl FetchCancel {
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let request_id = buf.try_get_varint().log_context("request id").ok()?;
+        Some(Self { request_id })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        let mut payload = BytesMut::new();
+        payload.put_varint(self.request_id);
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FetchCancel;
+
+    #[test]
+    fn encode() {
+        let message = FetchCancel { request_id: 12 };
+        let buf = message.encode();
+
+        let expected = [
+            12, // Request ID (i)
+        ];
+        assert_eq!(buf.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn decode() {
+        let bytes = [
+            12, // Request ID (i)
+        ];
+        let mut cursor = std::io::Cursor::new(bytes.as_slice());
+
+        let message = FetchCancel::decode(&mut cursor).unwrap();
+
+        assert_eq!(message, FetchCancel { request_id: 12 });
+    }
+}
@@ -1,3 +1,61 @@
-// TODO: Not implemented yet
-#[allow(dead_code)]
-pub(crate) struct GoAway {}
+use crate::modules::extensions::{
+    buf_get_ext::BufGetExt, buf_put_ext::BufPutExt, result_ext::ResultExt,
+};
+use bytes::BytesMut;
+use serde::Serialize;
+
+/// GOAWAY (draft-14 §9.4). An empty `new_session_uri` means the peer should
+/// reconnect to the URI it originally used.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct GoAway {
+    pub new_session_uri: String,
+}
+
+impl GoAway {
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let new_session_uri = buf.try_get_string().log_context("new session uri").ok()?;
+        Some(Self { new_session_uri })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        let mut payload = BytesMut::new();
+        payload.put_string(&self.new_session_uri);
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GoAway;
+
+    #[test]
+    fn encode() {
+        let message = GoAway {
+            new_session_uri: "moqt://b".to_string(),
+        };
+        let buf = message.encode();
+
+        let expected = [
+            8, // New Session URI Length (i)
+            b'm', b'o', b'q', b't', b':', b'/', b'/', b'b', // New Session URI (..)
+        ];
+        assert_eq!(buf.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn decode_empty_uri() {
+        let bytes = [
+            0, // New Session URI Length (i)
+        ];
+        let mut cursor = std::io::Cursor::new(bytes.as_slice());
+
+        let message = GoAway::decode(&mut cursor).unwrap();
+
+        assert_eq!(
+            message,
+            GoAway {
+                new_session_uri: String::new(),
+            }
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use crate::modules::extensions::{
+    buf_get_ext::BufGetExt, buf_put_ext::BufPutExt, result_ext::ResultExt,
+};
+use bytes::BytesMut;
+use serde::Serialize;
+
+/// MAX_REQUEST_ID (draft-14 §9.5, type `MaxSubscribeId`). The peer may use
+/// Request IDs strictly below `request_id`; the value only ever grows.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct MaxRequestId {
+    pub request_id: u64,
+}
+
+impl MaxRequestId {
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let request_id = buf.try_get_varint().log_context("max request id").ok()?;
+        Some(Self { request_id })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        let mut payload = BytesMut::new();
+        payload.put_varint(self.request_id);
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MaxRequestId;
+
+    #[test]
+    fn encode_and_decode() {
+        // Arrange
+        let message = MaxRequestId { request_id: 2000 };
+        // Act
+        let buf = message.encode();
+        let decoded = MaxRequestId::decode(&mut std::io::Cursor::new(&buf[..]));
+        // Assert
+        assert_eq!(
+            buf.as_ref(),
+            [0x47, 0xd0] // Max Request ID (i) = 2000
+        );
+        assert_eq!(decoded, Some(message));
+    }
+}
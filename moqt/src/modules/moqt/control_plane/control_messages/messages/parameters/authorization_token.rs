@@ -104,3 +104,44 @@ impl AuthorizationToken {
         }
     }
 }
+
+/// Value of the first token carried in full (REGISTER or USE_VALUE), as
+/// text. Aliases are not resolved: sessions keep no token cache yet.
+pub(crate) fn first_token_value(tokens: &[AuthorizationToken]) -> Option<String> {
+    tokens.iter().find_map(|token| match token {
+        AuthorizationToken::Register { token_value, .. }
+        | AuthorizationToken::UseValue { token_value, .. } => {
+            Some(String::from_utf8_lossy(token_value).into_owned())
+        }
+        AuthorizationToken::Delete | AuthorizationToken::UseAlias { .. } => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    mod success {
+        use bytes::Bytes;
+
+        use crate::modules::moqt::control_plane::control_messages::messages::parameters::authorization_token::{
+            AuthorizationToken, first_token_value,
+        };
+
+        #[test]
+        fn first_token_value_skips_aliases() {
+            // setup
+            let tokens = vec![
+                AuthorizationToken::UseAlias { token_alias: 1 },
+                AuthorizationToken::UseValue {
+                    token_type: 0,
+                    token_value: Bytes::from_static(b"secret"),
+                },
+            ];
+
+            // execution
+            let value = first_token_value(&tokens);
+
+            // validation
+            assert_eq!(value, Some("secret".to_string()));
+        }
+    }
+}
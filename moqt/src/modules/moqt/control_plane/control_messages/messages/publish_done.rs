@@ -0,0 +1,96 @@
+use crate::modules::extensions::{
+    buf_get_ext::BufGetExt, buf_put_ext::BufPutExt, result_ext::ResultExt,
+};
+use bytes::BytesMut;
+
+/// PUBLISH_DONE: the publisher ends a subscription. `stream_count` is the
+/// number of data streams it opened, so the subscriber knows how many to
+/// wait for before tearing down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishDone {
+    pub request_id: u64,
+    pub status_code: u64,
+    pub stream_count: u64,
+    pub reason_phrase: String,
+}
+
+impl PublishDone {
+    pub fn new(
+        request_id: u64,
+        status_code: u64,
+        stream_count: u64,
+        reason_phrase: String,
+    ) -> Self {
+        Self {
+            request_id,
+            status_code,
+            stream_count,
+            reason_phrase,
+        }
+    }
+
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let request_id = buf.try_get_varint().log_context("request id").ok()?;
+        let status_code = buf.try_get_varint().log_context("status code").ok()?;
+        let stream_count = buf.try_get_varint().log_context("stream count").ok()?;
+        let reason_phrase = buf.try_get_string().log_context("reason phrase").ok()?;
+
+        Some(Self {
+            request_id,
+            status_code,
+            stream_count,
+            reason_phrase,
+        })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        let mut payload = BytesMut::new();
+        payload.put_varint(self.request_id);
+        payload.put_varint(self.status_code);
+        payload.put_varint(self.stream_count);
+        payload.put_string(&self.reason_phrase);
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PublishDone;
+
+    #[test]
+    fn encode() {
+        // Arrange
+        let message = PublishDone::new(3, 0x4, 2, "gone".to_string());
+
+        // Act
+        let buf = message.encode();
+
+        // Assert
+        let expected = [
+            3, // Request ID (i)
+            4, // Status Code (i)
+            2, // Stream Count (i)
+            4, // Reason Phrase(b): Length
+            103, 111, 110, 101, // "gone"
+        ];
+        assert_eq!(buf.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn decode() {
+        // Arrange
+        let bytes = [
+            7, // Request ID (i)
+            2, // Status Code (i)
+            0, // Stream Count (i)
+            0, // Reason Phrase(b): Length
+        ];
+        let mut cursor = std::io::Cursor::new(bytes.as_slice());
+
+        // Act
+        let message = PublishDone::decode(&mut cursor).unwrap();
+
+        // Assert
+        assert_eq!(message, PublishDone::new(7, 2, 0, String::new()));
+    }
+}
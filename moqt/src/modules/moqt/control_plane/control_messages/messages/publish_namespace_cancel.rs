@@ -0,0 +1,108 @@
+use crate::modules::extensions::{
+    buf_get_ext::BufGetExt, buf_put_ext::BufPutExt, result_ext::ResultExt,
+};
+use bytes::BytesMut;
+
+/// PUBLISH_NAMESPACE_CANCEL: the receiver of a PUBLISH_NAMESPACE withdraws
+/// its acceptance and stops routing subscriptions for the namespace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishNamespaceCancel {
+    pub track_namespace: Vec<String>,
+    pub error_code: u64,
+    pub reason_phrase: String,
+}
+
+impl PublishNamespaceCancel {
+    pub fn new(track_namespace: Vec<String>, error_code: u64, reason_phrase: String) -> Self {
+        Self {
+            track_namespace,
+            error_code,
+            reason_phrase,
+        }
+    }
+
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let track_namespace_tuple_length = buf
+            .try_get_varint()
+            .log_context("track namespace length")
+            .ok()?;
+        let mut track_namespace_tuple = Vec::new();
+        for _ in 0..track_namespace_tuple_length {
+            let track_namespace = buf.try_get_string().log_context("track namespace").ok()?;
+            track_namespace_tuple.push(track_namespace);
+        }
+        let error_code = buf.try_get_varint().log_context("error code").ok()?;
+        let reason_phrase = buf.try_get_string().log_context("reason phrase").ok()?;
+
+        Some(Self {
+            track_namespace: track_namespace_tuple,
+            error_code,
+            reason_phrase,
+        })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        let mut payload = BytesMut::new();
+        payload.put_varint(self.track_namespace.len() as u64);
+        for track_namespace in &self.track_namespace {
+            payload.put_string(track_namespace);
+        }
+        payload.put_varint(self.error_code);
+        payload.put_string(&self.reason_phrase);
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PublishNamespaceCancel;
+
+    #[test]
+    fn encode() {
+        // Arrange
+        let message =
+            PublishNamespaceCancel::new(vec!["room".to_string()], 0x4, "gone".to_string());
+
+        // Act
+        let buf = message.encode();
+
+        // Assert
+        let expected = [
+            1, // Track Namespace(tuple): Number of elements
+            4, // Track Namespace(b): Length
+            114, 111, 111, 109, // "room"
+            4,   // Error Code (i)
+            4,   // Reason Phrase(b): Length
+            103, 111, 110, 101, // "gone"
+        ];
+        assert_eq!(buf.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn decode() {
+        // Arrange
+        let bytes = [
+            2, // Track Namespace(tuple): Number of elements
+            4, // Track Namespace(b): Length
+            114, 111, 111, 109, // "room"
+            6,   // Track Namespace(b): Length
+            109, 101, 109, 98, 101, 114, // "member"
+            0,   // Error Code (i)
+            0,   // Reason Phrase(b): Length
+        ];
+        let mut cursor = std::io::Cursor::new(bytes.as_slice());
+
+        // Act
+        let message = PublishNamespaceCancel::decode(&mut cursor).unwrap();
+
+        // Assert
+        assert_eq!(
+            message,
+            PublishNamespaceCancel::new(
+                vec!["room".to_string(), "member".to_string()],
+                0,
+                String::new()
+            )
+        );
+    }
+}
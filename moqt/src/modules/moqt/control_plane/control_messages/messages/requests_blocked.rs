@@ -0,0 +1,47 @@
+use crate::modules::extensions::{
+    buf_get_ext::BufGetExt, buf_put_ext::BufPutExt, result_ext::ResultExt,
+};
+use bytes::BytesMut;
+use serde::Serialize;
+
+/// REQUESTS_BLOCKED (draft-14 §9.6): the sender wants to issue a request but
+/// the peer's MAX_REQUEST_ID, echoed in `maximum_request_id`, does not allow it.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RequestsBlocked {
+    pub maximum_request_id: u64,
+}
+
+impl RequestsBlocked {
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let maximum_request_id = buf
+            .try_get_varint()
+            .log_context("maximum request id")
+            .ok()?;
+        Some(Self { maximum_request_id })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        let mut payload = BytesMut::new();
+        payload.put_varint(self.maximum_request_id);
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RequestsBlocked;
+
+    #[test]
+    fn encode_and_decode() {
+        // Arrange
+        let message = RequestsBlocked {
+            maximum_request_id: 10,
+        };
+        // Act
+        let buf = message.encode();
+        let decoded = RequestsBlocked::decode(&mut std::io::Cursor::new(&buf[..]));
+        // Assert
+        assert_eq!(buf.as_ref(), [10]);
+        assert_eq!(decoded, Some(message));
+    }
+}
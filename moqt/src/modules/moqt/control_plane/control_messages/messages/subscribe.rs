@@ -12,7 +12,7 @@ use crate::modules::{
 use bytes::{Buf, BufMut, BytesMut};
 use tracing;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Subscribe {
     pub request_id: u64,
     pub track_namespace: Vec<String>,
@@ -23,6 +23,8 @@ pub struct Subscribe {
     pub filter_type: FilterType,
     pub authorization_tokens: Vec<AuthorizationToken>,
     pub delivery_timeout: Option<u64>,
+    /// MAX_CACHE_DURATION (ms) the subscriber wants the publisher to retain.
+    pub max_cache_duration: Option<u64>,
 }
 
 impl Subscribe {
@@ -74,6 +76,13 @@ impl Subscribe {
                     VariantType::Odd(_) => unreachable!(),
                     VariantType::Even(value) => value,
                 });
+        let max_cache_duration = parameters
+            .iter()
+            .find(|kv_pair| kv_pair.key == 0x04)
+            .and_then(|kv_pair| match kv_pair.value {
+                VariantType::Even(value) => Some(value),
+                VariantType::Odd(_) => None,
+            });
         tracing::trace!("Depacketized Subscribe message.");
 
         Some(Subscribe {
@@ -86,6 +95,7 @@ impl Subscribe {
             filter_type,
             authorization_tokens,
             delivery_timeout,
+            max_cache_duration,
         })
     }
 
@@ -123,6 +133,15 @@ impl Subscribe {
             parameters_payload.unsplit(delivery_timeout_payload);
             number_of_parameters += 1;
         }
+        if let Some(max_cache_duration) = self.max_cache_duration {
+            let max_cache_duration_payload = KeyValuePair {
+                key: 0x04,
+                value: VariantType::Even(max_cache_duration),
+            }
+            .encode();
+            parameters_payload.unsplit(max_cache_duration_payload);
+            number_of_parameters += 1;
+        }
         payload.put_varint(number_of_parameters);
         payload.unsplit(parameters_payload);
 
@@ -165,6 +184,7 @@ mod tests {
                 filter_type,
                 authorization_tokens: vec![],
                 delivery_timeout: None,
+                max_cache_duration: None,
             };
 
             let buf = subscribe.encode();
@@ -213,6 +233,7 @@ mod tests {
                 filter_type,
                 authorization_tokens: vec![],
                 delivery_timeout: None,
+                max_cache_duration: None,
             };
 
             let buf = subscribe.encode();
@@ -264,6 +285,7 @@ mod tests {
                 filter_type,
                 authorization_tokens: vec![],
                 delivery_timeout: None,
+                max_cache_duration: None,
             };
 
             let buf = subscribe.encode();
@@ -330,6 +352,7 @@ mod tests {
                 filter_type,
                 authorization_tokens: vec![],
                 delivery_timeout: None,
+                max_cache_duration: None,
             };
 
             assert_eq!(depacketized_subscribe, expected_subscribe);
@@ -382,6 +405,7 @@ mod tests {
                 filter_type,
                 authorization_tokens: vec![],
                 delivery_timeout: None,
+                max_cache_duration: None,
             };
 
             assert_eq!(depacketized_subscribe, expected_subscribe);
@@ -443,6 +467,7 @@ mod tests {
                 filter_type,
                 authorization_tokens: vec![],
                 delivery_timeout: None,
+                max_cache_duration: None,
             };
             assert_eq!(depacketized_subscribe, expected_subscribe);
         }
@@ -462,6 +487,7 @@ mod tests {
                     token_value: Bytes::from_static(b"secret"),
                 }],
                 delivery_timeout: None,
+                max_cache_duration: None,
             };
 
             let buf = subscribe.encode();
@@ -470,5 +496,28 @@ mod tests {
 
             assert_eq!(depacketized_subscribe, subscribe);
         }
+
+        #[test]
+        fn packetize_and_depacketize_with_max_cache_duration() {
+            // Arrange
+            let subscribe = Subscribe {
+                request_id: 3,
+                track_namespace: vec!["test".to_string()],
+                track_name: "track_name".to_string(),
+                subscriber_priority: 1,
+                group_order: GroupOrder::Ascending,
+                forward: true,
+                filter_type: FilterType::LargestObject,
+                authorization_tokens: vec![],
+                delivery_timeout: Some(100),
+                max_cache_duration: Some(60_000),
+            };
+            // Act
+            let buf = subscribe.encode();
+            let mut buf = std::io::Cursor::new(&buf[..]);
+            let depacketized_subscribe = Subscribe::decode(&mut buf).unwrap();
+            // Assert
+            assert_eq!(depacketized_subscribe, subscribe);
+        }
     }
 }
@@ -0,0 +1,180 @@
+use crate::modules::{
+    extensions::{buf_get_ext::BufGetExt, buf_put_ext::BufPutExt, result_ext::ResultExt},
+    moqt::control_plane::control_messages::{
+        key_value_pair::{KeyValuePair, VariantType},
+        messages::parameters::location::Location,
+        util,
+    },
+};
+use bytes::{Buf, BufMut, BytesMut};
+
+/// SUBSCRIBE_UPDATE narrows or extends an existing subscription in place.
+/// `end_group` follows the wire encoding: the last Group ID plus one, with 0
+/// meaning the subscription stays open-ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscribeUpdate {
+    pub request_id: u64,
+    pub subscription_request_id: u64,
+    pub start_location: Location,
+    pub end_group: u64,
+    pub subscriber_priority: u8,
+    pub forward: bool,
+    pub delivery_timeout: Option<u64>,
+}
+
+impl SubscribeUpdate {
+    /// The last Group ID the subscriber wants, or `None` when open-ended.
+    pub fn end_group_id(&self) -> Option<u64> {
+        self.end_group.checked_sub(1)
+    }
+
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let request_id = buf.try_get_varint().log_context("request id").ok()?;
+        let subscription_request_id = buf
+            .try_get_varint()
+            .log_context("subscription request id")
+            .ok()?;
+        let start_location = Location::decode(buf)?;
+        let end_group = buf.try_get_varint().log_context("end group").ok()?;
+        let subscriber_priority = buf.try_get_u8().log_context("subscriber priority").ok()?;
+        let forward_u8 = buf.try_get_u8().log_context("forward u8").ok()?;
+        let forward = util::u8_to_bool(forward_u8).log_context("forward").ok()?;
+        let number_of_parameters = buf
+            .try_get_varint()
+            .log_context("number of parameters")
+            .ok()?;
+        let mut parameters = vec![];
+        for _ in 0..number_of_parameters {
+            parameters.push(KeyValuePair::decode(buf)?);
+        }
+        let delivery_timeout = parameters
+            .iter()
+            .find(|kv_pair| kv_pair.key == 0x02)
+            .and_then(|kv_pair| match kv_pair.value {
+                VariantType::Even(value) => Some(value),
+                VariantType::Odd(_) => None,
+            });
+        tracing::trace!("Depacketized SubscribeUpdate message.");
+
+        Some(Self {
+            request_id,
+            subscription_request_id,
+            start_location,
+            end_group,
+            subscriber_priority,
+            forward,
+            delivery_timeout,
+        })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        let mut payload = BytesMut::new();
+        payload.put_varint(self.request_id);
+        payload.put_varint(self.subscription_request_id);
+        payload.unsplit(self.start_location.encode());
+        payload.put_varint(self.end_group);
+        payload.put_u8(self.subscriber_priority);
+        payload.put_u8(self.forward as u8);
+        match self.delivery_timeout {
+            Some(delivery_timeout) => {
+                payload.put_varint(1);
+                payload.unsplit(
+                    KeyValuePair {
+                        key: 0x02,
+                        value: VariantType::Even(delivery_timeout),
+                    }
+                    .encode(),
+                );
+            }
+            None => payload.put_varint(0),
+        }
+
+        tracing::trace!("Packetized SubscribeUpdate message.");
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod success {
+        use crate::modules::moqt::control_plane::control_messages::messages::{
+            parameters::location::Location, subscribe_update::SubscribeUpdate,
+        };
+
+        #[test]
+        fn packetize() {
+            // Arrange
+            let subscribe_update = SubscribeUpdate {
+                request_id: 4,
+                subscription_request_id: 2,
+                start_location: Location {
+                    group_id: 10,
+                    object_id: 0,
+                },
+                end_group: 21,
+                subscriber_priority: 8,
+                forward: true,
+                delivery_timeout: None,
+            };
+            // Act
+            let buf = subscribe_update.encode();
+            // Assert
+            let expected_bytes_array = [
+                4,  // Request ID (i)
+                2,  // Subscription Request ID (i)
+                10, // Start Location: group id (i)
+                0,  // Start Location: object id (i)
+                21, // End Group (i)
+                8,  // Subscriber Priority (8)
+                1,  // Forward (8)
+                0,  // Number of Parameters (i)
+            ];
+            assert_eq!(buf.as_ref(), expected_bytes_array.as_slice());
+        }
+
+        #[test]
+        fn packetize_and_depacketize_with_delivery_timeout() {
+            // Arrange
+            let subscribe_update = SubscribeUpdate {
+                request_id: 6,
+                subscription_request_id: 0,
+                start_location: Location {
+                    group_id: 3,
+                    object_id: 1,
+                },
+                end_group: 0,
+                subscriber_priority: 128,
+                forward: false,
+                delivery_timeout: Some(500),
+            };
+            // Act
+            let buf = subscribe_update.encode();
+            let mut cursor = std::io::Cursor::new(&buf[..]);
+            let decoded = SubscribeUpdate::decode(&mut cursor).unwrap();
+            // Assert
+            assert_eq!(decoded, subscribe_update);
+            assert_eq!(decoded.end_group_id(), None);
+        }
+
+        #[test]
+        fn end_group_id_is_wire_value_minus_one() {
+            // Arrange
+            let subscribe_update = SubscribeUpdate {
+                request_id: 0,
+                subscription_request_id: 0,
+                start_location: Location {
+                    group_id: 0,
+                    object_id: 0,
+                },
+                end_group: 21,
+                subscriber_priority: 0,
+                forward: true,
+                delivery_timeout: None,
+            };
+            // Act
+            let end_group_id = subscribe_update.end_group_id();
+            // Assert
+            assert_eq!(end_group_id, Some(20));
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use bytes::BytesMut;
+
+use crate::modules::moqt::control_plane::control_messages::messages::{
+    parameters::{content_exists::ContentExists, group_order::GroupOrder},
+    subscribe_ok::SubscribeOk,
+};
+
+/// TRACK_STATUS (draft-14 calls it TRACK_STATUS_OK, 0x0E).
+///
+/// The wire layout is identical to SUBSCRIBE_OK with a Track Alias of 0:
+/// no subscription is created, only the track's current state is reported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackStatus {
+    pub request_id: u64,
+    pub expires: u64,
+    pub group_order: GroupOrder,
+    pub content_exists: ContentExists,
+}
+
+impl TrackStatus {
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let subscribe_ok = SubscribeOk::decode(buf)?;
+        Some(Self {
+            request_id: subscribe_ok.request_id,
+            expires: subscribe_ok.expires,
+            group_order: subscribe_ok.group_order,
+            content_exists: subscribe_ok.content_exists,
+        })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        SubscribeOk {
+            request_id: self.request_id,
+            track_alias: 0,
+            expires: self.expires,
+            group_order: self.group_order,
+            content_exists: self.content_exists,
+            delivery_timeout: None,
+            max_duration: None,
+        }
+        .encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrackStatus;
+    use crate::modules::moqt::control_plane::control_messages::messages::parameters::{
+        content_exists::ContentExists, group_order::GroupOrder, location::Location,
+    };
+
+    #[test]
+    fn encode() {
+        let message = TrackStatus {
+            request_id: 3,
+            expires: 0,
+            group_order: GroupOrder::Ascending,
+            content_exists: ContentExists::True {
+                location: Location {
+                    group_id: 5,
+                    object_id: 7,
+                },
+            },
+        };
+        let buf = message.encode();
+
+        let expected = [
+            3, // Request ID (i)
+            0, // Track Alias (i)
+            0, // Expires (i)
+            1, // Group Order (8)
+            1, // Content Exists (8)
+            5, // Largest Location Group (i)
+            7, // Largest Location Object (i)
+            0, // Number of Parameters (i)
+        ];
+        assert_eq!(buf.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn decode() {
+        let bytes = [
+            3, // Request ID (i)
+            0, // Track Alias (i)
+            0, // Expires (i)
+            1, // Group Order (8)
+            0, // Content Exists (8)
+            0, // Number of Parameters (i)
+        ];
+        let mut cursor = std::io::Cursor::new(bytes.as_slice());
+
+        let message = TrackStatus::decode(&mut cursor).unwrap();
+
+        assert_eq!(
+            message,
+            TrackStatus {
+                request_id: 3,
+                expires: 0,
+                group_order: GroupOrder::Ascending,
+                content_exists: ContentExists::False,
+            }
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use bytes::BytesMut;
+
+use crate::modules::moqt::control_plane::control_messages::messages::{
+    parameters::{
+        authorization_token::AuthorizationToken, filter_type::FilterType, group_order::GroupOrder,
+    },
+    subscribe::Subscribe,
+};
+
+/// TRACK_STATUS_REQUEST (draft-14 calls it TRACK_STATUS, 0x0D).
+///
+/// The wire layout is identical to SUBSCRIBE; the subscription fields carry
+/// no meaning for a status probe, so they are written with neutral values and
+/// dropped on decode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackStatusRequest {
+    pub request_id: u64,
+    pub track_namespace: Vec<String>,
+    pub track_name: String,
+    pub authorization_tokens: Vec<AuthorizationToken>,
+}
+
+impl TrackStatusRequest {
+    pub fn decode(buf: &mut std::io::Cursor<&[u8]>) -> Option<Self> {
+        let subscribe = Subscribe::decode(buf)?;
+        Some(Self {
+            request_id: subscribe.request_id,
+            track_namespace: subscribe.track_namespace,
+            track_name: subscribe.track_name,
+            authorization_tokens: subscribe.authorization_tokens,
+        })
+    }
+
+    pub fn encode(&self) -> BytesMut {
+        Subscribe {
+            request_id: self.request_id,
+            track_namespace: self.track_namespace.clone(),
+            track_name: self.track_name.clone(),
+            subscriber_priority: 0,
+            group_order: GroupOrder::Publisher,
+            forward: false,
+            filter_type: FilterType::LargestObject,
+            authorization_tokens: self.authorization_tokens.clone(),
+            delivery_timeout: None,
+            max_cache_duration: None,
+        }
+        .encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrackStatusRequest;
+
+    #[test]
+    fn encode() {
+        let message = TrackStatusRequest {
+            request_id: 3,
+            track_namespace: vec!["ns".to_string()],
+            track_name: "t".to_string(),
+            authorization_tokens: vec![],
+        };
+        let buf = message.encode();
+
+        let expected = [
+            3, // Request ID (i)
+            1, // Track Namespace Number of elements (i)
+            2, b'n', b's', // Track Namespace (b)
+            1, b't', // Track Name (b)
+            0,    // Subscriber Priority (8)
+            0,    // Group Order (8)
+            0,    // Forward (8)
+            2,    // Filter Type (i): LargestObject
+            0,    // Number of Parameters (i)
+        ];
+        assert_eq!(buf.as_ref(), expected.as_slice());
+    }
+
+    #[test]
+    fn decode() {
+        let bytes = [
+            3, // Request ID (i)
+            1, // Track Namespace Number of elements (i)
+            2, b'n', b's', // Track Namespace (b)
+            1, b't', // Track Name (b)
+            128,  // Subscriber Priority (8)
+            1,    // Group Order (8)
+            1,    // Forward (8)
+            2,    // Filter Type (i): LargestObject
+            0,    // Number of Parameters (i)
+        ];
+        let mut cursor = std::io::Cursor::new(bytes.as_slice());
+
+        let message = TrackStatusRequest::decode(&mut cursor).unwrap();
+
+        assert_eq!(
+            message,
+            TrackStatusRequest {
+                request_id: 3,
+                track_namespace: vec!["ns".to_string()],
+                track_name: "t".to_string(),
+                authorization_tokens: vec![],
+            }
+        );
+    }
+}
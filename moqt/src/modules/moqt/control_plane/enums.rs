@@ -3,13 +3,18 @@ use crate::{
     modules::moqt::control_plane::{
         control_messages::messages::{
             fetch_ok::FetchOk, publish_ok::PublishOk, subscribe_ok::SubscribeOk,
+            track_status::TrackStatus,
         },
         handler::{
-            fetch_handler::FetchHandler, publish_handler::PublishHandler,
+            fetch_cancel_handler::FetchCancelHandler, fetch_handler::FetchHandler,
+            publish_done_handler::PublishDoneHandler, publish_handler::PublishHandler,
+            publish_namespace_cancel_handler::PublishNamespaceCancelHandler,
             publish_namespace_done_handler::PublishNamespaceDoneHandler,
             publish_namespace_handler::PublishNamespaceHandler,
             subscribe_handler::SubscribeHandler,
             subscribe_namespace_handler::SubscribeNamespaceHandler,
+            subscribe_update_handler::SubscribeUpdateHandler,
+            track_status_request_handler::TrackStatusRequestHandler,
             unsubscribe_handler::UnsubscribeHandler,
             unsubscribe_namespace_handler::UnsubscribeNamespaceHandler,
         },
@@ -26,12 +31,22 @@ pub(crate) type ErrorPhrase = String;
 pub enum SessionEvent<T: TransportProtocol> {
     PublishNamespace(PublishNamespaceHandler<T>),
     PublishNamespaceDone(PublishNamespaceDoneHandler),
+    /// The peer withdrew its acceptance of a namespace this endpoint published.
+    PublishNamespaceCancel(PublishNamespaceCancelHandler),
     SubscribeNameSpace(SubscribeNamespaceHandler<T>),
     UnsubscribeNamespace(UnsubscribeNamespaceHandler),
     Publish(PublishHandler<T>),
     Subscribe(SubscribeHandler<T>),
+    SubscribeUpdate(SubscribeUpdateHandler),
     Unsubscribe(UnsubscribeHandler<T>),
+    /// The publisher ended a subscription this endpoint holds.
+    PublishDone(PublishDoneHandler),
     Fetch(FetchHandler<T>),
+    FetchCancel(FetchCancelHandler),
+    TrackStatusRequest(TrackStatusRequestHandler<T>),
+    /// The peer sent GOAWAY; carries its New Session URI (empty means reuse
+    /// the current one). Requests already in flight still complete.
+    GoAway(String),
     Disconnected(),
     ProtocolViolation(),
 }
@@ -48,4 +63,6 @@ pub(crate) enum ResponseMessage {
     SubscribeError(RequestId, ErrorCode, ErrorPhrase),
     FetchOk(FetchOk),
     FetchError(RequestId, ErrorCode, ErrorPhrase),
+    TrackStatus(TrackStatus),
+    TrackStatusError(RequestId, ErrorCode, ErrorPhrase),
 }
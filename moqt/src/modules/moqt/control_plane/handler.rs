@@ -1,9 +1,14 @@
+pub mod fetch_cancel_handler;
 pub mod fetch_handler;
+pub mod publish_done_handler;
 pub mod publish_handler;
+pub mod publish_namespace_cancel_handler;
 pub mod publish_namespace_done_handler;
 pub mod publish_namespace_handler;
 pub(crate) mod response_guard;
 pub mod subscribe_handler;
 pub mod subscribe_namespace_handler;
+pub mod subscribe_update_handler;
+pub mod track_status_request_handler;
 pub mod unsubscribe_handler;
 pub mod unsubscribe_namespace_handler;
@@ -0,0 +1,18 @@
+use crate::modules::moqt::control_plane::control_messages::messages::fetch_cancel::FetchCancel;
+
+#[derive(Debug, Clone)]
+pub struct FetchCancelHandler {
+    request_id: u64,
+}
+
+impl FetchCancelHandler {
+    pub(crate) fn new(fetch_cancel_message: FetchCancel) -> Self {
+        Self {
+            request_id: fetch_cancel_message.request_id,
+        }
+    }
+
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+}
@@ -0,0 +1,21 @@
+use crate::modules::moqt::control_plane::control_messages::messages::publish_done::PublishDone;
+
+#[derive(Clone, Debug)]
+pub struct PublishDoneHandler {
+    /// Request ID of the SUBSCRIBE or PUBLISH that ended.
+    pub request_id: u64,
+    pub status_code: u64,
+    pub stream_count: u64,
+    pub reason_phrase: String,
+}
+
+impl PublishDoneHandler {
+    pub(crate) fn new(publish_done: PublishDone) -> Self {
+        Self {
+            request_id: publish_done.request_id,
+            status_code: publish_done.status_code,
+            stream_count: publish_done.stream_count,
+            reason_phrase: publish_done.reason_phrase,
+        }
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
             control_messages::{
                 control_message_type::ControlMessageType,
                 messages::{
+                    parameters::authorization_token::first_token_value,
                     parameters::content_exists::ContentExists, publish::Publish,
                     publish_ok::PublishOk, request_error::RequestError,
                 },
@@ -51,7 +52,7 @@ impl<T: TransportProtocol> PublishHandler<T> {
             group_order: publish_message.group_order,
             content_exists: publish_message.content_exists,
             forward: publish_message.forward,
-            authorization_token: None,
+            authorization_token: first_token_value(&publish_message.authorization_tokens),
             max_cache_duration: None,
             delivery_timeout: None,
         }
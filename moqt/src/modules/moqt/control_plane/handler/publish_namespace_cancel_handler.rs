@@ -0,0 +1,22 @@
+use crate::modules::moqt::control_plane::control_messages::messages::publish_namespace_cancel::PublishNamespaceCancel;
+
+#[derive(Clone, Debug)]
+pub struct PublishNamespaceCancelHandler {
+    pub track_namespace: String,
+    pub error_code: u64,
+    pub reason_phrase: String,
+}
+
+impl PublishNamespaceCancelHandler {
+    pub(crate) fn new(publish_namespace_cancel: PublishNamespaceCancel) -> Self {
+        Self {
+            track_namespace: publish_namespace_cancel.track_namespace.join("/"),
+            error_code: publish_namespace_cancel.error_code,
+            reason_phrase: publish_namespace_cancel.reason_phrase,
+        }
+    }
+
+    pub fn track_namespace(&self) -> &str {
+        &self.track_namespace
+    }
+}
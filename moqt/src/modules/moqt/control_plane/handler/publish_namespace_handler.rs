@@ -7,8 +7,8 @@ use crate::{
             control_messages::{
                 control_message_type::ControlMessageType,
                 messages::{
-                    namespace_ok::NamespaceOk, publish_namespace::PublishNamespace,
-                    request_error::RequestError,
+                    namespace_ok::NamespaceOk, parameters::authorization_token::first_token_value,
+                    publish_namespace::PublishNamespace, request_error::RequestError,
                 },
             },
             handler::response_guard::ResponseGuard,
@@ -42,7 +42,7 @@ impl<T: TransportProtocol> PublishNamespaceHandler<T> {
             guard,
             request_id: publish_namespace.request_id,
             track_namespace: publish_namespace.track_namespace.join("/"),
-            authorization_token: None,
+            authorization_token: first_token_value(&publish_namespace.authorization_token),
         }
     }
 
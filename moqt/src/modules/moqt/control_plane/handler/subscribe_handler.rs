@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use thiserror::Error;
+
 use crate::{
     FilterType, GroupOrder, SubscriberInitiatedSubscription, Subscription, TransportProtocol,
     modules::moqt::{
@@ -7,17 +9,28 @@ use crate::{
             control_messages::{
                 control_message_type::ControlMessageType,
                 messages::{
+                    parameters::authorization_token::first_token_value,
                     parameters::content_exists::ContentExists, request_error::RequestError,
                     subscribe::Subscribe, subscribe_ok::SubscribeOk,
                 },
             },
             handler::response_guard::ResponseGuard,
+            track_alias_allocator::TrackAliasInUse,
         },
         domains::session_context::SessionContext,
     },
     modules::transport::transport_send_stream::TransportSendError,
 };
 
+/// Why SUBSCRIBE_OK was not sent.
+#[derive(Debug, Error)]
+pub enum SubscribeOkError {
+    #[error(transparent)]
+    TrackAliasInUse(#[from] TrackAliasInUse),
+    #[error(transparent)]
+    Send(#[from] TransportSendError),
+}
+
 #[derive(Debug, Clone)]
 pub struct SubscribeHandler<T: TransportProtocol> {
     session_context: Arc<SessionContext<T>>,
@@ -54,9 +67,9 @@ impl<T: TransportProtocol> SubscribeHandler<T> {
             group_order: subscribe_message.group_order,
             forward: subscribe_message.forward,
             filter_type: subscribe_message.filter_type,
-            authorization_token: None,
-            max_cache_duration: None,
-            delivery_timeout: None,
+            authorization_token: first_token_value(&subscribe_message.authorization_tokens),
+            max_cache_duration: subscribe_message.max_cache_duration,
+            delivery_timeout: subscribe_message.delivery_timeout,
         }
     }
 
@@ -64,7 +77,7 @@ impl<T: TransportProtocol> SubscribeHandler<T> {
         &self,
         expires: u64,
         content_exists: ContentExists,
-    ) -> Result<u64, TransportSendError> {
+    ) -> Result<u64, SubscribeOkError> {
         let track_alias = self.allocate_track_alias();
         self.ok_with_track_alias(track_alias, expires, content_exists)
             .await?;
@@ -76,7 +89,31 @@ impl<T: TransportProtocol> SubscribeHandler<T> {
         track_alias: u64,
         expires: u64,
         content_exists: ContentExists,
-    ) -> Result<(), TransportSendError> {
+    ) -> Result<(), SubscribeOkError> {
+        self.ok_with_max_cache_duration(
+            track_alias,
+            expires,
+            content_exists,
+            self.max_cache_duration,
+        )
+        .await
+    }
+
+    /// Like `ok_with_track_alias`, but answers with `max_cache_duration`
+    /// instead of echoing the requested one, so a publisher that retains
+    /// objects for less time than asked tells the subscriber so.
+    ///
+    /// An alias that did not come from `allocate_track_alias` is reserved on
+    /// the session first; if it is already in use nothing is sent and
+    /// `SubscribeOkError::TrackAliasInUse` is returned.
+    pub async fn ok_with_max_cache_duration(
+        &self,
+        track_alias: u64,
+        expires: u64,
+        content_exists: ContentExists,
+        max_cache_duration: Option<u64>,
+    ) -> Result<(), SubscribeOkError> {
+        self.session_context.claim_track_alias(track_alias)?;
         self.guard.mark_responded();
         let subscribe_ok = SubscribeOk {
             request_id: self.request_id,
@@ -85,7 +122,7 @@ impl<T: TransportProtocol> SubscribeHandler<T> {
             group_order: self.group_order,
             content_exists,
             delivery_timeout: self.delivery_timeout,
-            max_duration: self.max_cache_duration,
+            max_duration: max_cache_duration,
         };
         self.session_context
             .send_stream
@@ -98,6 +135,12 @@ impl<T: TransportProtocol> SubscribeHandler<T> {
         self.session_context.get_track_alias()
     }
 
+    /// Returns an alias handed out by this handler once the subscription
+    /// ends, so the session can reuse it.
+    pub fn release_track_alias(&self, track_alias: u64) {
+        self.session_context.release_track_alias(track_alias);
+    }
+
     pub async fn error(
         &self,
         error_code: u64,
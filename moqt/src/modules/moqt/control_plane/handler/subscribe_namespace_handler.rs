@@ -7,8 +7,8 @@ use crate::{
             control_messages::{
                 control_message_type::ControlMessageType,
                 messages::{
-                    namespace_ok::NamespaceOk, request_error::RequestError,
-                    subscribe_namespace::SubscribeNamespace,
+                    namespace_ok::NamespaceOk, parameters::authorization_token::first_token_value,
+                    request_error::RequestError, subscribe_namespace::SubscribeNamespace,
                 },
             },
             handler::response_guard::ResponseGuard,
@@ -42,7 +42,7 @@ impl<T: TransportProtocol> SubscribeNamespaceHandler<T> {
             guard,
             request_id: subscribe_namespace.request_id,
             track_namespace_prefix: subscribe_namespace.track_namespace_prefix.join("/"),
-            authorization_token: None,
+            authorization_token: first_token_value(&subscribe_namespace.authorization_token),
         }
     }
 
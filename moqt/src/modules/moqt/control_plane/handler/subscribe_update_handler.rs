@@ -0,0 +1,39 @@
+use crate::{
+    Location,
+    modules::moqt::control_plane::control_messages::messages::subscribe_update::SubscribeUpdate,
+};
+
+#[derive(Debug, Clone)]
+pub struct SubscribeUpdateHandler {
+    request_id: u64,
+    subscription_request_id: u64,
+    pub start_location: Location,
+    /// Last Group ID to deliver; `None` keeps the subscription open-ended.
+    pub end_group: Option<u64>,
+    pub subscriber_priority: u8,
+    pub forward: bool,
+    pub delivery_timeout: Option<u64>,
+}
+
+impl SubscribeUpdateHandler {
+    pub(crate) fn new(subscribe_update_message: SubscribeUpdate) -> Self {
+        Self {
+            request_id: subscribe_update_message.request_id,
+            subscription_request_id: subscribe_update_message.subscription_request_id,
+            start_location: subscribe_update_message.start_location,
+            end_group: subscribe_update_message.end_group_id(),
+            subscriber_priority: subscribe_update_message.subscriber_priority,
+            forward: subscribe_update_message.forward,
+            delivery_timeout: subscribe_update_message.delivery_timeout,
+        }
+    }
+
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    /// Request ID of the SUBSCRIBE this update applies to.
+    pub fn subscription_request_id(&self) -> u64 {
+        self.subscription_request_id
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use crate::{
+    GroupOrder, TransportProtocol,
+    modules::{
+        moqt::{
+            control_plane::{
+                control_messages::{
+                    control_message_type::ControlMessageType,
+                    messages::{
+                        parameters::content_exists::ContentExists, request_error::RequestError,
+                        track_status::TrackStatus, track_status_request::TrackStatusRequest,
+                    },
+                },
+                handler::response_guard::ResponseGuard,
+            },
+            domains::session_context::SessionContext,
+        },
+        transport::transport_send_stream::TransportSendError,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct TrackStatusRequestHandler<T: TransportProtocol> {
+    session_context: Arc<SessionContext<T>>,
+    request_id: u64,
+    pub track_namespace: String,
+    pub track_name: String,
+    guard: ResponseGuard<T>,
+}
+
+impl<T: TransportProtocol> TrackStatusRequestHandler<T> {
+    pub(crate) fn new(
+        session_context: Arc<SessionContext<T>>,
+        track_status_request: TrackStatusRequest,
+    ) -> Self {
+        let guard = ResponseGuard::new(
+            session_context.clone(),
+            track_status_request.request_id,
+            ControlMessageType::TrackStatusError,
+        );
+        Self {
+            session_context,
+            request_id: track_status_request.request_id,
+            track_namespace: track_status_request.track_namespace.join("/"),
+            track_name: track_status_request.track_name,
+            guard,
+        }
+    }
+
+    /// Answers with the track's current state; `content_exists` carries the
+    /// Largest Location when any object has been published.
+    pub async fn ok(
+        &self,
+        group_order: GroupOrder,
+        content_exists: ContentExists,
+    ) -> Result<(), TransportSendError> {
+        self.guard.mark_responded();
+        let track_status = TrackStatus {
+            request_id: self.request_id,
+            expires: 0,
+            group_order,
+            content_exists,
+        };
+        self.session_context
+            .send_stream
+            .send(ControlMessageType::TrackStatus, track_status.encode())
+            .await
+    }
+
+    pub async fn error(
+        &self,
+        error_code: u64,
+        reason_phrase: String,
+    ) -> Result<(), TransportSendError> {
+        self.guard.mark_responded();
+        let err = RequestError {
+            request_id: self.request_id,
+            error_code,
+            reason_phrase,
+        };
+        self.session_context
+            .send_stream
+            .send(ControlMessageType::TrackStatusError, err.encode())
+            .await
+    }
+
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+}
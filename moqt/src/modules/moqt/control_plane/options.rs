@@ -1,12 +1,14 @@
 use crate::{
-    FilterType, GroupOrder,
+    FilterType, GroupOrder, Location,
     modules::moqt::control_plane::control_messages::messages::parameters::content_exists::ContentExists,
 };
 
+#[derive(Clone)]
 pub struct PublishOption {
     pub(crate) group_order: GroupOrder,
     pub(crate) content_exists: ContentExists,
     pub(crate) forward: bool,
+    pub(crate) track_alias: Option<u64>,
 }
 
 impl Default for PublishOption {
@@ -15,10 +17,20 @@ impl Default for PublishOption {
             group_order: GroupOrder::Ascending,
             content_exists: ContentExists::False,
             forward: true,
+            track_alias: None,
         }
     }
 }
 
+impl PublishOption {
+    /// Uses `track_alias` instead of a session-allocated one. PUBLISH fails
+    /// locally when the alias is already in use on the session.
+    pub fn with_track_alias(mut self, track_alias: u64) -> Self {
+        self.track_alias = Some(track_alias);
+        self
+    }
+}
+
 pub struct FetchOption {
     pub subscriber_priority: u8,
     pub group_order: GroupOrder,
@@ -33,11 +45,15 @@ impl Default for FetchOption {
     }
 }
 
+#[derive(Clone)]
 pub struct SubscribeOption {
     pub subscriber_priority: u8,
     pub group_order: GroupOrder,
     pub forward: bool,
     pub filter_type: FilterType,
+    /// MAX_CACHE_DURATION (ms) to request. The publisher answers with the
+    /// duration it can actually honour; see `Subscription::max_cache_duration`.
+    pub max_cache_duration: Option<u64>,
 }
 
 impl Default for SubscribeOption {
@@ -47,6 +63,16 @@ impl Default for SubscribeOption {
             group_order: GroupOrder::Ascending,
             forward: true,
             filter_type: FilterType::LargestObject,
+            max_cache_duration: None,
         }
     }
 }
+
+/// New parameters for an existing subscription. `end_group` is the last Group
+/// ID to deliver; `None` leaves the subscription open-ended.
+pub struct SubscribeUpdateOption {
+    pub start_location: Location,
+    pub end_group: Option<u64>,
+    pub subscriber_priority: u8,
+    pub forward: bool,
+}
@@ -0,0 +1,177 @@
+use std::collections::{HashSet, VecDeque};
+
+use thiserror::Error;
+
+/// An explicitly chosen Track Alias collides with one already in use on the
+/// session, so the message announcing it was not sent.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("track alias {track_alias} is already in use")]
+pub struct TrackAliasInUse {
+    pub track_alias: u64,
+}
+
+/// Track Aliases this endpoint has handed out as a publisher on one session.
+///
+/// PUBLISH and SUBSCRIBE_OK both draw from the same allocator, so an alias is
+/// never reused for two live publications (draft-14 §9.8 makes the peer close
+/// the session with DUPLICATE_TRACK_ALIAS). Explicit aliases — kept for
+/// interop tests — are reserved through the same set.
+///
+/// Aliases from `allocate` stay pending until `claim` binds them to the
+/// message that announces them; any other alias passed to `claim` must be
+/// free.
+///
+/// Released aliases are handed out again, oldest release first, so a
+/// long-lived session with subscription churn keeps its aliases small
+/// instead of growing them for its whole life.
+#[derive(Debug, Default)]
+pub struct TrackAliasAllocator {
+    next: u64,
+    in_use: HashSet<u64>,
+    pending: HashSet<u64>,
+    released: VecDeque<u64>,
+}
+
+impl TrackAliasAllocator {
+    /// Returns the oldest released alias, or else the lowest alias at or
+    /// above the cursor that is not in use.
+    pub fn allocate(&mut self) -> u64 {
+        let track_alias = self.next_free();
+        self.in_use.insert(track_alias);
+        self.pending.insert(track_alias);
+        track_alias
+    }
+
+    fn next_free(&mut self) -> u64 {
+        while let Some(track_alias) = self.released.pop_front() {
+            // Skip aliases reserved explicitly since their release.
+            if !self.in_use.contains(&track_alias) {
+                return track_alias;
+            }
+        }
+        while self.in_use.contains(&self.next) {
+            self.next += 1;
+        }
+        let track_alias = self.next;
+        self.next += 1;
+        track_alias
+    }
+
+    /// Claims `track_alias` for an explicitly chosen alias. Returns `false`
+    /// when it is already in use.
+    pub fn reserve(&mut self, track_alias: u64) -> bool {
+        self.in_use.insert(track_alias)
+    }
+
+    /// Binds `track_alias` to the message that announces it: a pending alias
+    /// from `allocate` is taken as is, any other one is reserved first.
+    pub fn claim(&mut self, track_alias: u64) -> Result<(), TrackAliasInUse> {
+        if self.pending.remove(&track_alias) || self.reserve(track_alias) {
+            Ok(())
+        } else {
+            Err(TrackAliasInUse { track_alias })
+        }
+    }
+
+    pub fn is_in_use(&self, track_alias: u64) -> bool {
+        self.in_use.contains(&track_alias)
+    }
+
+    pub fn release(&mut self, track_alias: u64) -> bool {
+        self.pending.remove(&track_alias);
+        let released = self.in_use.remove(&track_alias);
+        if released {
+            self.released.push_back(track_alias);
+        }
+        released
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TrackAliasAllocator, TrackAliasInUse};
+
+    #[test]
+    fn allocate_skips_explicitly_reserved_aliases() {
+        // Arrange
+        let mut allocator = TrackAliasAllocator::default();
+        assert!(allocator.reserve(1));
+        // Act
+        let aliases = [allocator.allocate(), allocator.allocate()];
+        // Assert
+        assert_eq!(aliases, [0, 2]);
+    }
+
+    #[test]
+    fn reserve_rejects_alias_in_use() {
+        // Arrange
+        let mut allocator = TrackAliasAllocator::default();
+        let allocated = allocator.allocate();
+        // Act / Assert
+        assert!(!allocator.reserve(allocated));
+    }
+
+    #[test]
+    fn released_alias_can_be_reserved_again() {
+        // Arrange
+        let mut allocator = TrackAliasAllocator::default();
+        let allocated = allocator.allocate();
+        // Act
+        assert!(allocator.release(allocated));
+        // Assert: an explicit claim wins over recycling
+        assert!(allocator.reserve(allocated));
+        assert_eq!(allocator.allocate(), 1);
+    }
+
+    #[test]
+    fn allocate_recycles_oldest_release_first() {
+        // Arrange
+        let mut allocator = TrackAliasAllocator::default();
+        let aliases = [
+            allocator.allocate(),
+            allocator.allocate(),
+            allocator.allocate(),
+        ];
+        allocator.release(aliases[2]);
+        allocator.release(aliases[0]);
+        // Act
+        let recycled = [
+            allocator.allocate(),
+            allocator.allocate(),
+            allocator.allocate(),
+        ];
+        // Assert
+        assert_eq!(recycled, [2, 0, 3]);
+        assert!(!allocator.release(5));
+    }
+
+    #[test]
+    fn claim_accepts_each_allocated_alias_once() {
+        // Arrange
+        let mut allocator = TrackAliasAllocator::default();
+        let allocated = allocator.allocate();
+        // Act
+        let first = allocator.claim(allocated);
+        let second = allocator.claim(allocated);
+        // Assert: the pending alias binds once, then it is simply in use
+        assert_eq!(first, Ok(()));
+        assert_eq!(
+            second,
+            Err(TrackAliasInUse {
+                track_alias: allocated
+            })
+        );
+    }
+
+    #[test]
+    fn claim_reserves_explicit_aliases() {
+        // Arrange
+        let mut allocator = TrackAliasAllocator::default();
+        // Act
+        let claimed = allocator.claim(4);
+        // Assert: the explicit alias is now taken for allocate and reserve
+        assert_eq!(claimed, Ok(()));
+        assert!(allocator.is_in_use(4));
+        assert!(!allocator.reserve(4));
+    }
+}
@@ -0,0 +1,101 @@
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// A draft of MOQT this crate can speak. The wire value is what
+/// CLIENT_SETUP offers and SERVER_SETUP selects.
+#[derive(Debug, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive, Copy, Clone)]
+#[repr(u32)]
+pub enum ProtocolVersion {
+    Draft14 = 0xff00000e,
+}
+
+impl ProtocolVersion {
+    /// Every supported draft, most preferred first. CLIENT_SETUP offers them
+    /// in this order.
+    pub const SUPPORTED: &'static [ProtocolVersion] = &[ProtocolVersion::Draft14];
+
+    /// Newest supported draft; its wire value is `MOQ_TRANSPORT_VERSION`.
+    pub const LATEST: ProtocolVersion = ProtocolVersion::Draft14;
+
+    pub fn supported_wire_values() -> Vec<u32> {
+        Self::SUPPORTED
+            .iter()
+            .map(|version| (*version).into())
+            .collect()
+    }
+
+    /// Server side of SETUP: the first draft of the client's list that this
+    /// crate supports, so the client's preference order wins.
+    pub fn negotiate(offered: &[u32]) -> Option<ProtocolVersion> {
+        offered
+            .iter()
+            .find_map(|value| ProtocolVersion::try_from(*value).ok())
+    }
+
+    /// Client side of SETUP: the selected version must be one we offered,
+    /// that is one of [`Self::SUPPORTED`].
+    pub fn accept_selected(selected: u32) -> Option<ProtocolVersion> {
+        Self::SUPPORTED
+            .iter()
+            .copied()
+            .find(|version| u32::from(*version) == selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod success {
+        use crate::modules::moqt::control_plane::version::ProtocolVersion;
+
+        #[test]
+        fn negotiate_picks_first_supported_offer() {
+            // setup
+            let offered = [0xff00000a, 0xff00000e];
+
+            // execution
+            let selected = ProtocolVersion::negotiate(&offered);
+
+            // validation
+            assert_eq!(selected, Some(ProtocolVersion::Draft14));
+        }
+
+        #[test]
+        fn accept_selected_accepts_offered_version() {
+            // execution / validation
+            assert_eq!(
+                ProtocolVersion::accept_selected(0xff00000e),
+                Some(ProtocolVersion::Draft14)
+            );
+        }
+
+        #[test]
+        fn supported_wire_values_match_setup_constant() {
+            // execution
+            let values = ProtocolVersion::supported_wire_values();
+
+            // validation
+            assert_eq!(values, vec![0xff00000e]);
+        }
+    }
+
+    mod failure {
+        use crate::modules::moqt::control_plane::version::ProtocolVersion;
+
+        #[test]
+        fn negotiate_without_common_version() {
+            // setup
+            let offered = [0xff00000a, 0xff00000b];
+
+            // execution
+            let selected = ProtocolVersion::negotiate(&offered);
+
+            // validation
+            assert_eq!(selected, None);
+        }
+
+        #[test]
+        fn accept_selected_rejects_unknown_version() {
+            // execution / validation
+            assert_eq!(ProtocolVersion::accept_selected(0xff00000a), None);
+        }
+    }
+}
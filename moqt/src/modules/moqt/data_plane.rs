@@ -1,4 +1,7 @@
 #[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod adaptive_sender;
+pub(crate) mod bandwidth_probe;
+#[cfg(not(target_arch = "wasm32"))]
 pub(crate) mod codec;
 #[cfg(not(target_arch = "wasm32"))]
 pub(crate) mod datagram;
@@ -0,0 +1,221 @@
+use bytes::Bytes;
+
+use crate::{
+    DatagramField, DatagramSender, ExtensionHeaders, StreamDataSenderFactory, SubgroupId,
+    SubgroupObject, SubgroupObjectSender, TransportProtocol,
+};
+
+/// Path chosen for a single object by [`AdaptiveObjectSender`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectTransport {
+    Datagram,
+    Stream,
+}
+
+/// Thresholds deciding which objects travel as datagrams.
+///
+/// An object is sent as a datagram when its payload fits in
+/// `max_datagram_payload` bytes and its publisher priority is at or below
+/// `urgent_priority` (lower values are more urgent). Everything else goes on
+/// the group's subgroup stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransportSelection {
+    pub max_datagram_payload: usize,
+    pub urgent_priority: u8,
+}
+
+impl Default for TransportSelection {
+    fn default() -> Self {
+        Self {
+            // Leaves room for the datagram header inside a 1200-byte QUIC packet.
+            max_datagram_payload: 1000,
+            urgent_priority: 64,
+        }
+    }
+}
+
+impl TransportSelection {
+    pub fn select(&self, payload_len: usize, publisher_priority: u8) -> ObjectTransport {
+        if payload_len <= self.max_datagram_payload && publisher_priority <= self.urgent_priority {
+            ObjectTransport::Datagram
+        } else {
+            ObjectTransport::Stream
+        }
+    }
+}
+
+struct OpenSubgroup<T: TransportProtocol> {
+    sender: SubgroupObjectSender<T>,
+    last_object_id: u64,
+}
+
+/// Sends a track's objects over datagrams or a subgroup stream, picking the
+/// path per object with [`TransportSelection`].
+///
+/// Object IDs are assigned here and are shared by both paths, so a receiver
+/// (or relay) merging the datagram and stream halves of a group sees one
+/// ascending sequence. Each group uses at most one subgroup stream
+/// (subgroup 0), opened lazily on the first stream-bound object.
+pub struct AdaptiveObjectSender<T: TransportProtocol> {
+    selection: TransportSelection,
+    datagram: DatagramSender<T>,
+    streams: StreamDataSenderFactory<T>,
+    group_id: u64,
+    next_object_id: u64,
+    subgroup: Option<OpenSubgroup<T>>,
+}
+
+impl<T: TransportProtocol> AdaptiveObjectSender<T> {
+    pub(crate) fn new(
+        selection: TransportSelection,
+        datagram: DatagramSender<T>,
+        streams: StreamDataSenderFactory<T>,
+    ) -> Self {
+        Self {
+            selection,
+            datagram,
+            streams,
+            group_id: 0,
+            next_object_id: 0,
+            subgroup: None,
+        }
+    }
+
+    /// Closes the current group's stream (if any) and restarts object
+    /// numbering at 0 for `group_id`.
+    pub async fn start_group(&mut self, group_id: u64) -> anyhow::Result<()> {
+        self.finish_group().await?;
+        self.group_id = group_id;
+        self.next_object_id = 0;
+        Ok(())
+    }
+
+    /// Sends `payload` as the next object of the current group and returns
+    /// its object ID with the path it took.
+    pub async fn send(
+        &mut self,
+        publisher_priority: u8,
+        payload: Bytes,
+    ) -> anyhow::Result<(u64, ObjectTransport)> {
+        let object_id = self.next_object_id;
+        let transport = self.selection.select(payload.len(), publisher_priority);
+        match transport {
+            ObjectTransport::Datagram => {
+                let field = DatagramField::Payload0x00 {
+                    object_id,
+                    publisher_priority,
+                    payload,
+                };
+                let datagram = self.datagram.create_object_datagram(self.group_id, field);
+                self.datagram.send(datagram).await?;
+            }
+            ObjectTransport::Stream => {
+                self.send_on_stream(object_id, publisher_priority, payload)
+                    .await?
+            }
+        }
+        self.next_object_id += 1;
+        Ok((object_id, transport))
+    }
+
+    /// Closes the current group's stream, if one was opened.
+    pub async fn finish_group(&mut self) -> anyhow::Result<()> {
+        if let Some(mut subgroup) = self.subgroup.take() {
+            subgroup.sender.close().await?;
+        }
+        Ok(())
+    }
+
+    async fn send_on_stream(
+        &mut self,
+        object_id: u64,
+        publisher_priority: u8,
+        payload: Bytes,
+    ) -> anyhow::Result<()> {
+        let (mut subgroup, last_object_id) = match self.subgroup.take() {
+            Some(subgroup) => {
+                let last_object_id = Some(subgroup.last_object_id);
+                (subgroup, last_object_id)
+            }
+            None => {
+                // The stream header fixes the priority for the whole subgroup;
+                // the first stream-bound object of the group sets it.
+                let uninit = self.streams.next().await?;
+                let header = uninit.create_header(
+                    self.group_id,
+                    SubgroupId::None,
+                    publisher_priority,
+                    false,
+                    false,
+                );
+                let sender = uninit.send_header(header).await?;
+                (
+                    OpenSubgroup {
+                        sender,
+                        last_object_id: object_id,
+                    },
+                    None,
+                )
+            }
+        };
+        let field = subgroup.sender.create_object_field(
+            object_id_delta(last_object_id, object_id),
+            ExtensionHeaders::default(),
+            SubgroupObject::new_payload(payload),
+        );
+        subgroup.sender.send(field).await?;
+        subgroup.last_object_id = object_id;
+        self.subgroup = Some(subgroup);
+        Ok(())
+    }
+}
+
+/// Object ID delta on a subgroup stream; IDs taken by datagrams show up as gaps.
+fn object_id_delta(last_object_id: Option<u64>, object_id: u64) -> u64 {
+    match last_object_id {
+        Some(last_object_id) => object_id - last_object_id - 1,
+        None => object_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_urgent_objects_use_datagrams() {
+        // Arrange
+        let selection = TransportSelection {
+            max_datagram_payload: 100,
+            urgent_priority: 10,
+        };
+        // Act / Assert
+        assert_eq!(selection.select(100, 10), ObjectTransport::Datagram);
+        assert_eq!(selection.select(0, 0), ObjectTransport::Datagram);
+    }
+
+    #[test]
+    fn large_or_non_urgent_objects_use_streams() {
+        // Arrange
+        let selection = TransportSelection {
+            max_datagram_payload: 100,
+            urgent_priority: 10,
+        };
+        // Act / Assert
+        assert_eq!(selection.select(101, 0), ObjectTransport::Stream);
+        assert_eq!(selection.select(1, 11), ObjectTransport::Stream);
+    }
+
+    #[test]
+    fn stream_deltas_skip_ids_sent_as_datagrams() {
+        // Arrange: objects 0 and 2 went out as datagrams
+        let first_stream_object = 1;
+        let second_stream_object = 3;
+        // Act
+        let first = object_id_delta(None, first_stream_object);
+        let second = object_id_delta(Some(first_stream_object), second_stream_object);
+        // Assert
+        assert_eq!(first, 1);
+        assert_eq!(second, 1);
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+/// Track Namespace under which a relay serves bandwidth probe tracks. The
+/// track name is the probe size in bytes (see [`bandwidth_probe_track_name`]).
+pub const BANDWIDTH_PROBE_NAMESPACE: &str = "moqt-bandwidth-probe";
+
+/// Payload size of each probe object; the last object carries the remainder.
+pub const BANDWIDTH_PROBE_OBJECT_SIZE: usize = 16 * 1024;
+
+/// Share of the measured throughput considered safe for the initial quality
+/// level, leaving headroom for audio, control traffic and rate variation.
+const SAFE_BITRATE_RATIO: f64 = 0.7;
+
+pub fn bandwidth_probe_track_name(size_bytes: u64) -> String {
+    size_bytes.to_string()
+}
+
+/// Returns the requested probe size, or `None` if `track_name` is not a
+/// positive byte count.
+pub fn parse_bandwidth_probe_track_name(track_name: &str) -> Option<u64> {
+    track_name.parse::<u64>().ok().filter(|size| *size > 0)
+}
+
+/// Throughput measured while receiving a probe track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandwidthEstimate {
+    pub received_bytes: u64,
+    pub elapsed: Duration,
+    pub throughput_bps: u64,
+}
+
+impl BandwidthEstimate {
+    /// Bitrate a client can pick for its first catalog selection.
+    pub fn safe_bitrate_bps(&self) -> u64 {
+        (self.throughput_bps as f64 * SAFE_BITRATE_RATIO) as u64
+    }
+}
+
+/// Accumulates probe object arrivals. Timing starts at the first object, so
+/// the request round trip is not counted against the link; the first
+/// object's bytes are excluded for the same reason.
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthProbeMeter {
+    first_arrival: Option<Duration>,
+    last_arrival: Option<Duration>,
+    received_bytes: u64,
+    measured_bytes: u64,
+}
+
+impl BandwidthProbeMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `arrived_at` is measured from any fixed origin, e.g. a monotonic clock
+    /// or `performance.now()`.
+    pub fn record(&mut self, payload_len: usize, arrived_at: Duration) {
+        self.received_bytes += payload_len as u64;
+        if self.first_arrival.is_none() {
+            self.first_arrival = Some(arrived_at);
+        } else {
+            self.measured_bytes += payload_len as u64;
+        }
+        self.last_arrival = Some(arrived_at);
+    }
+
+    pub fn received_bytes(&self) -> u64 {
+        self.received_bytes
+    }
+
+    /// `None` until at least two objects arrived at distinct times.
+    pub fn estimate(&self) -> Option<BandwidthEstimate> {
+        let elapsed = self.last_arrival?.checked_sub(self.first_arrival?)?;
+        if elapsed.is_zero() || self.measured_bytes == 0 {
+            return None;
+        }
+        let throughput_bps = (self.measured_bytes as f64 * 8.0 / elapsed.as_secs_f64()) as u64;
+        Some(BandwidthEstimate {
+            received_bytes: self.received_bytes,
+            elapsed,
+            throughput_bps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{BandwidthProbeMeter, parse_bandwidth_probe_track_name};
+
+    #[test]
+    fn estimate_excludes_first_object_from_throughput() {
+        // Arrange
+        let mut meter = BandwidthProbeMeter::new();
+        // Act
+        meter.record(1_000, Duration::from_millis(100));
+        meter.record(125_000, Duration::from_millis(600));
+        meter.record(125_000, Duration::from_millis(1_100));
+        let estimate = meter.estimate().expect("estimate");
+        // Assert
+        assert_eq!(estimate.received_bytes, 251_000);
+        assert_eq!(estimate.elapsed, Duration::from_secs(1));
+        assert_eq!(estimate.throughput_bps, 2_000_000);
+        assert_eq!(estimate.safe_bitrate_bps(), 1_400_000);
+    }
+
+    #[test]
+    fn estimate_is_none_with_a_single_object() {
+        // Arrange
+        let mut meter = BandwidthProbeMeter::new();
+        // Act
+        meter.record(16_384, Duration::from_millis(5));
+        // Assert
+        assert!(meter.estimate().is_none());
+    }
+
+    #[test]
+    fn parse_track_name_accepts_positive_sizes_only() {
+        // Arrange / Act / Assert
+        assert_eq!(parse_bandwidth_probe_track_name("262144"), Some(262_144));
+        assert_eq!(parse_bandwidth_probe_track_name("0"), None);
+        assert_eq!(parse_bandwidth_probe_track_name("video"), None);
+    }
+}
@@ -7,25 +7,60 @@ use crate::modules::moqt::{
     control_plane::control_messages::{
         control_message_type::ControlMessageType,
         messages::{
-            client_setup::ClientSetup, fetch::Fetch, fetch_ok::FetchOk, namespace_ok::NamespaceOk,
-            publish::Publish, publish_namespace::PublishNamespace,
+            client_setup::ClientSetup, fetch::Fetch, fetch_cancel::FetchCancel, fetch_ok::FetchOk,
+            go_away::GoAway, max_request_id::MaxRequestId, namespace_ok::NamespaceOk,
+            publish::Publish, publish_done::PublishDone, publish_namespace::PublishNamespace,
+            publish_namespace_cancel::PublishNamespaceCancel,
             publish_namespace_done::PublishNamespaceDone, publish_ok::PublishOk,
-            request_error::RequestError, server_setup::ServerSetup, subscribe::Subscribe,
+            request_error::RequestError, requests_blocked::RequestsBlocked,
+            server_setup::ServerSetup, subscribe::Subscribe,
             subscribe_namespace::SubscribeNamespace, subscribe_ok::SubscribeOk,
-            unsubscribe::Unsubscribe, unsubscribe_namespace::UnsubscribeNamespace,
+            subscribe_update::SubscribeUpdate, track_status::TrackStatus,
+            track_status_request::TrackStatusRequest, unsubscribe::Unsubscribe,
+            unsubscribe_namespace::UnsubscribeNamespace,
         },
     },
     data_plane::stream::received_message::ReceivedMessage,
 };
-use crate::wire::take_control_message;
+use crate::wire::{control_message_payload_length, take_control_message};
 
-pub(crate) struct ControlMessageDecoder;
+pub(crate) struct ControlMessageDecoder {
+    max_message_bytes: usize,
+}
+
+impl ControlMessageDecoder {
+    pub(crate) fn new() -> Self {
+        Self {
+            max_message_bytes: u16::MAX as usize,
+        }
+    }
+
+    /// Fails a frame whose declared payload is longer than
+    /// `max_message_bytes`, without waiting for the payload.
+    pub(crate) fn with_max_message_bytes(mut self, max_message_bytes: usize) -> Self {
+        self.max_message_bytes = max_message_bytes;
+        self
+    }
+}
 
 impl Decoder for ControlMessageDecoder {
     type Item = ReceivedMessage;
     type Error = std::io::Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if let Some(length) = control_message_payload_length(src)
+            && length > self.max_message_bytes
+        {
+            tracing::error!(
+                length,
+                max_message_bytes = self.max_message_bytes,
+                "Control message exceeds the maximum length"
+            );
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Control message exceeds the maximum length",
+            ));
+        }
         let (message_type, payload) = match take_control_message(src).map_err(|error| {
             tracing::error!("Failed to decode control message frame: {:?}", error);
             std::io::Error::new(
@@ -71,9 +106,36 @@ impl ControlMessageDecoder {
                     }
                 }
             }
-            ControlMessageType::GoAway => todo!(),
-            ControlMessageType::MaxSubscribeId => todo!(),
-            ControlMessageType::RequestsBlocked => todo!(),
+            ControlMessageType::GoAway => {
+                tracing::debug!("Event: GoAway");
+                match GoAway::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::GoAway(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
+            ControlMessageType::MaxSubscribeId => {
+                tracing::debug!("Event: Max request id");
+                match MaxRequestId::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::MaxRequestId(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
+            ControlMessageType::RequestsBlocked => {
+                tracing::debug!("Event: Requests blocked");
+                match RequestsBlocked::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::RequestsBlocked(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
             ControlMessageType::Subscribe => {
                 tracing::debug!("Event: Subscribe");
                 match Subscribe::decode(&mut cursor_buf) {
@@ -104,7 +166,16 @@ impl ControlMessageDecoder {
                     }
                 }
             }
-            ControlMessageType::SubscribeUpdate => todo!(),
+            ControlMessageType::SubscribeUpdate => {
+                tracing::debug!("Event: Subscribe update");
+                match SubscribeUpdate::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::SubscribeUpdate(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
             ControlMessageType::UnSubscribe => {
                 tracing::debug!("Event: Unsubscribe");
                 match Unsubscribe::decode(&mut cursor_buf) {
@@ -115,7 +186,16 @@ impl ControlMessageDecoder {
                     }
                 }
             }
-            ControlMessageType::PublishDone => todo!(),
+            ControlMessageType::PublishDone => {
+                tracing::debug!("Event: Publish done");
+                match PublishDone::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::PublishDone(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
             ControlMessageType::Publish => {
                 tracing::debug!("Event: Publish");
                 match Publish::decode(&mut cursor_buf) {
@@ -176,9 +256,46 @@ impl ControlMessageDecoder {
                     }
                 }
             }
-            ControlMessageType::FetchCancel => todo!(),
-            ControlMessageType::TrackStatusRequest => todo!(),
-            ControlMessageType::TrackStatus => todo!(),
+            ControlMessageType::FetchCancel => {
+                tracing::debug!("Event: Fetch cancel");
+                match FetchCancel::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::FetchCancel(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
+            ControlMessageType::TrackStatusRequest => {
+                tracing::debug!("Event: Track status request");
+                match TrackStatusRequest::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::TrackStatusRequest(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
+            ControlMessageType::TrackStatus => {
+                tracing::debug!("Event: Track status");
+                match TrackStatus::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::TrackStatus(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
+            ControlMessageType::TrackStatusError => {
+                tracing::debug!("Event: Track status error");
+                match RequestError::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::TrackStatusError(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
             ControlMessageType::PublishNamespace => {
                 tracing::debug!("Event: Publish namespace");
                 match PublishNamespace::decode(&mut cursor_buf) {
@@ -219,7 +336,16 @@ impl ControlMessageDecoder {
                     }
                 }
             }
-            ControlMessageType::PublishNamespaceCancel => todo!(),
+            ControlMessageType::PublishNamespaceCancel => {
+                tracing::debug!("Event: Publish namespace cancel");
+                match PublishNamespaceCancel::decode(&mut cursor_buf) {
+                    Some(v) => ReceivedMessage::PublishNamespaceCancel(v),
+                    None => {
+                        tracing::error!("Protocol violation is detected.");
+                        ReceivedMessage::FatalError()
+                    }
+                }
+            }
             ControlMessageType::SubscribeNamespace => {
                 tracing::debug!("Event: Subscribe namespace");
                 match SubscribeNamespace::decode(&mut cursor_buf) {
@@ -291,12 +417,13 @@ mod tests {
             filter_type: FilterType::LargestObject,
             authorization_tokens: vec![],
             delivery_timeout: None,
+            max_cache_duration: None,
         }
     }
 
     #[test]
     fn decode_returns_none_for_empty_buffer() {
-        let mut decoder = ControlMessageDecoder;
+        let mut decoder = ControlMessageDecoder::new();
         let mut buf = BytesMut::new();
 
         let result = decoder.decode(&mut buf).expect("decode should not fail");
@@ -305,7 +432,7 @@ mod tests {
 
     #[test]
     fn decode_waits_for_full_frame_then_completes() {
-        let mut decoder = ControlMessageDecoder;
+        let mut decoder = ControlMessageDecoder::new();
         let subscribe = make_subscribe();
         let framed = encode_control_message(ControlMessageType::Subscribe, subscribe.encode());
 
@@ -329,7 +456,7 @@ mod tests {
 
     #[test]
     fn decode_multiple_messages_in_one_buffer() {
-        let mut decoder = ControlMessageDecoder;
+        let mut decoder = ControlMessageDecoder::new();
         let subscribe = make_subscribe();
         let unsubscribe = Unsubscribe { request_id: 7 };
 
@@ -365,7 +492,7 @@ mod tests {
 
     #[test]
     fn decode_rejects_unknown_message_type() {
-        let mut decoder = ControlMessageDecoder;
+        let mut decoder = ControlMessageDecoder::new();
         // 0x3f is not a defined control message type
         let mut buf = BytesMut::new();
         buf.put_u8(0x3f);
@@ -375,9 +502,21 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn decode_rejects_a_frame_longer_than_the_limit_before_its_payload() {
+        let mut decoder = ControlMessageDecoder::new().with_max_message_bytes(16);
+        // Subscribe header declaring 1024 payload bytes, none of them sent
+        let mut buf = BytesMut::new();
+        buf.put_u8(ControlMessageType::Subscribe as u8);
+        buf.put_u16(1024);
+
+        let result = decoder.decode(&mut buf);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn decode_malformed_payload_yields_fatal_error() {
-        let mut decoder = ControlMessageDecoder;
+        let mut decoder = ControlMessageDecoder::new();
         // Subscribe frame whose payload is truncated garbage
         let payload = BytesMut::from(&[0x07_u8][..]);
         let mut buf = encode_control_message(ControlMessageType::Subscribe, payload);
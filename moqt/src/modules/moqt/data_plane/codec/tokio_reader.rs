@@ -10,11 +10,17 @@ use crate::{
 #[derive(Debug)]
 pub(crate) struct Reader<T: TransportProtocol, const N: usize> {
     receive_stream: T::ReceiveStream,
+    /// Read buffer owned by this stream and reused by every read, so the
+    /// hot path neither allocates nor zero-fills per read.
+    scratch: BytesMut,
 }
 
 impl<T: TransportProtocol, const N: usize> Reader<T, N> {
     pub(crate) fn new(receive_stream: T::ReceiveStream) -> Self {
-        Self { receive_stream }
+        Self {
+            receive_stream,
+            scratch: BytesMut::zeroed(N),
+        }
     }
 }
 
@@ -24,17 +30,21 @@ impl<T: TransportProtocol, const N: usize> AsyncRead for Reader<T, N> {
         cx: &mut std::task::Context<'_>,
         buf: &mut tokio::io::ReadBuf<'_>,
     ) -> std::task::Poll<std::io::Result<()>> {
-        let mut bytes_mut = BytesMut::with_capacity(N);
+        let this = &mut *self;
         let capacity = buf.remaining().min(N);
-        bytes_mut.resize(capacity, 0);
+        // Splitting and rejoining the halves of one allocation is O(1).
+        let mut window = this.scratch.split_to(capacity);
         // TODO: handle the case where the received message is larger than the buffer size.
-        Pin::new(&mut self.receive_stream)
-            .poll_read(cx, &mut bytes_mut)
+        let result = Pin::new(&mut this.receive_stream)
+            .poll_read(cx, &mut window)
             .map(|result| {
                 result.map(|size| {
-                    buf.put_slice(&bytes_mut[..size]);
+                    buf.put_slice(&window[..size]);
                 })
             })
-            .map_err(|e| std::io::Error::other(format!("read error: {:?}", e)))
+            .map_err(|e| std::io::Error::other(format!("read error: {:?}", e)));
+        window.unsplit(std::mem::take(&mut this.scratch));
+        this.scratch = window;
+        result
     }
 }
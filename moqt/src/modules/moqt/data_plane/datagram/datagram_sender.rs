@@ -2,12 +2,9 @@ use std::sync::Arc;
 
 use crate::{
     DatagramField, TransportProtocol,
-    modules::{
-        moqt::{
-            data_plane::object::object_datagram::ObjectDatagram,
-            domains::session_context::SessionContext,
-        },
-        transport::transport_connection::TransportConnection,
+    modules::moqt::{
+        data_plane::object::object_datagram::ObjectDatagram,
+        domains::session_context::SessionContext,
     },
 };
 
@@ -32,10 +29,7 @@ impl<T: TransportProtocol> DatagramSender<T> {
 
     pub async fn send(&mut self, data: ObjectDatagram) -> anyhow::Result<()> {
         let bytes = data.encode();
-        let result = self
-            .session_context
-            .transport_connection
-            .send_datagram(bytes);
+        let result = self.session_context.send_datagram(bytes);
         tokio::task::yield_now().await;
         result
     }
@@ -5,3 +5,4 @@ pub mod fetch;
 pub mod object_datagram;
 pub mod object_status;
 pub mod subgroup;
+pub mod subgroup_stream_object;
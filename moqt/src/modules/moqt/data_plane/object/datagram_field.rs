@@ -141,6 +141,78 @@ impl DatagramField {
         }
     }
 
+    pub fn publisher_priority(&self) -> u8 {
+        match self {
+            Self::Payload0x00 {
+                publisher_priority, ..
+            }
+            | Self::Payload0x01 {
+                publisher_priority, ..
+            }
+            | Self::Payload0x02WithEndOfGroup {
+                publisher_priority, ..
+            }
+            | Self::Payload0x03WithEndOfGroup {
+                publisher_priority, ..
+            }
+            | Self::Payload0x04 {
+                publisher_priority, ..
+            }
+            | Self::Payload0x05 {
+                publisher_priority, ..
+            }
+            | Self::Payload0x06WithEndOfGroup {
+                publisher_priority, ..
+            }
+            | Self::Payload0x07WithEndOfGroup {
+                publisher_priority, ..
+            }
+            | Self::Status0x20 {
+                publisher_priority, ..
+            }
+            | Self::Status0x21 {
+                publisher_priority, ..
+            } => *publisher_priority,
+        }
+    }
+
+    /// Extension headers, for the types that carry them.
+    pub fn extension_headers(&self) -> Option<&ExtensionHeaders> {
+        match self {
+            Self::Payload0x01 {
+                extension_headers, ..
+            }
+            | Self::Payload0x03WithEndOfGroup {
+                extension_headers, ..
+            }
+            | Self::Payload0x05 {
+                extension_headers, ..
+            }
+            | Self::Payload0x07WithEndOfGroup {
+                extension_headers, ..
+            }
+            | Self::Status0x21 {
+                extension_headers, ..
+            } => Some(extension_headers),
+            _ => None,
+        }
+    }
+
+    /// Whether no object of the group follows this one: the `WithEndOfGroup`
+    /// types and END_OF_GROUP / END_OF_TRACK status datagrams.
+    pub fn ends_group(&self) -> bool {
+        match self {
+            Self::Payload0x02WithEndOfGroup { .. }
+            | Self::Payload0x03WithEndOfGroup { .. }
+            | Self::Payload0x06WithEndOfGroup { .. }
+            | Self::Payload0x07WithEndOfGroup { .. } => true,
+            Self::Status0x20 { status, .. } | Self::Status0x21 { status, .. } => {
+                matches!(status, ObjectStatus::EndOfGroup | ObjectStatus::EndOfTrack)
+            }
+            _ => false,
+        }
+    }
+
     pub fn payload(&self) -> ObjectDatagramPayload {
         match self {
             Self::Payload0x00 { payload, .. }
@@ -430,6 +502,38 @@ mod tests {
 
         use bytes::{Buf, Bytes};
 
+        #[test]
+        fn end_of_group_types_and_statuses_end_the_group() {
+            // setup
+            let payload = |end_of_group| {
+                if end_of_group {
+                    DatagramField::Payload0x02WithEndOfGroup {
+                        object_id: 1,
+                        publisher_priority: 0,
+                        payload: Bytes::from_static(b"x"),
+                    }
+                } else {
+                    DatagramField::Payload0x00 {
+                        object_id: 1,
+                        publisher_priority: 0,
+                        payload: Bytes::from_static(b"x"),
+                    }
+                }
+            };
+            let status = |status| DatagramField::Status0x20 {
+                object_id: 2,
+                publisher_priority: 0,
+                status,
+            };
+
+            // execution / validation
+            assert!(payload(true).ends_group());
+            assert!(!payload(false).ends_group());
+            assert!(status(ObjectStatus::EndOfGroup).ends_group());
+            assert!(status(ObjectStatus::EndOfTrack).ends_group());
+            assert!(!status(ObjectStatus::DoesNotExist).ends_group());
+        }
+
         #[test]
         fn payload0x00_encode_decode() {
             // setup
@@ -151,23 +151,33 @@ impl FetchObjectField {
 
     pub fn encode(&self) -> BytesMut {
         let mut buf = BytesMut::new();
-        buf.put_varint(self.group_id);
-        buf.put_varint(self.subgroup_id);
-        buf.put_varint(self.object_id);
-        buf.put_u8(self.publisher_priority);
-        let ext_headers_buf = self.extension_headers.encode();
-        buf.extend_from_slice(&ext_headers_buf);
-        match &self.fetch_object {
+        for chunk in self.encode_chunks() {
+            buf.extend_from_slice(&chunk);
+        }
+        buf
+    }
+
+    /// Encodes the object as its fields followed by its payload, which is
+    /// shared rather than copied.
+    pub fn encode_chunks(&self) -> Vec<Bytes> {
+        let mut fields = BytesMut::new();
+        fields.put_varint(self.group_id);
+        fields.put_varint(self.subgroup_id);
+        fields.put_varint(self.object_id);
+        fields.put_u8(self.publisher_priority);
+        fields.unsplit(self.extension_headers.encode());
+        let payload = match &self.fetch_object {
             FetchObject::Payload(payload) => {
-                buf.put_varint(payload.len() as u64);
-                buf.put_slice(payload);
+                fields.put_varint(payload.len() as u64);
+                Some(payload.clone())
             }
             FetchObject::Status(status) => {
-                buf.put_varint(0);
-                buf.put_varint(u8::from(*status) as u64);
+                fields.put_varint(0);
+                fields.put_varint(u8::from(*status) as u64);
+                None
             }
-        }
-        buf
+        };
+        std::iter::once(fields.freeze()).chain(payload).collect()
     }
 }
 
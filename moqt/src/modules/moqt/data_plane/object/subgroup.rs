@@ -201,19 +201,20 @@ impl SubgroupObject {
         }
     }
 
-    pub(crate) fn encode(&self) -> BytesMut {
-        let mut buf = BytesMut::new();
+    /// Writes the length (and status code) into `buf` and returns the
+    /// payload, which follows it on the wire, without copying it.
+    fn encode_fields(&self, buf: &mut BytesMut) -> Option<Bytes> {
         match self {
             SubgroupObject::Payload { length, data } => {
                 buf.put_varint(*length as u64);
-                buf.extend_from_slice(data);
+                Some(data.clone())
             }
             SubgroupObject::Status { length, code } => {
                 buf.put_varint(*length as u64); // length 0 indicates status
                 buf.put_varint(*code);
+                None
             }
         }
-        buf
     }
 }
 
@@ -278,6 +279,19 @@ impl SubgroupHeader {
         })
     }
 
+    /// The same header carrying `subgroup_id` in an explicit Subgroup ID
+    /// field, keeping its extension and end-of-group flags.
+    pub fn with_subgroup_id(&self, subgroup_id: u64) -> Self {
+        Self::new(
+            self.track_alias,
+            self.group_id,
+            SubgroupId::Value(subgroup_id),
+            self.publisher_priority,
+            self.message_type.has_extensions(),
+            self.message_type.has_end_of_group(),
+        )
+    }
+
     pub fn encode(&self) -> BytesMut {
         let mut buf = BytesMut::new();
         buf.put_varint(self.message_type.get_value());
@@ -345,16 +359,58 @@ impl SubgroupObjectField {
         })
     }
 
+    /// Bytes `buf` must hold before `decode` can succeed on the object it
+    /// starts with. Once the Payload Length has arrived this is the whole
+    /// object's size, so a reader can wait for the rest of a large payload
+    /// instead of decoding it again on every partial read.
+    pub fn required_len(message_type: SubgroupHeaderType, buf: &[u8]) -> usize {
+        let more = buf.len() + 1;
+        let mut cursor = std::io::Cursor::<&[u8]>::new(buf);
+        if cursor.try_get_varint().is_err() {
+            return more;
+        }
+        if message_type.has_extensions() {
+            let Ok(byte_length) = cursor.try_get_varint() else {
+                return more;
+            };
+            let end = cursor.position() as usize + byte_length as usize;
+            if end >= buf.len() {
+                // The Payload Length follows the extensions.
+                return end + 1;
+            }
+            cursor.set_position(end as u64);
+        }
+        let Ok(length) = cursor.try_get_varint() else {
+            return more;
+        };
+        let fields = cursor.position() as usize;
+        match length {
+            // An Object Status follows instead of a payload.
+            0 => fields + 1,
+            length => fields + length as usize,
+        }
+    }
+
     pub fn encode(&self) -> BytesMut {
         let mut buf = BytesMut::new();
-        buf.put_varint(self.object_id_delta);
-        if self.message_type.has_extensions() {
-            let ext_headers_buf = self.extension_headers.encode();
-            buf.extend_from_slice(&ext_headers_buf);
+        for chunk in self.encode_chunks() {
+            buf.extend_from_slice(&chunk);
         }
-        buf.extend_from_slice(&self.subgroup_object.encode());
         buf
     }
+
+    /// Encodes the object as its fields followed by its payload. The
+    /// payload chunk shares the received buffer, so an object forwarded to
+    /// many subscribers is never copied.
+    pub fn encode_chunks(&self) -> Vec<Bytes> {
+        let mut fields = BytesMut::new();
+        fields.put_varint(self.object_id_delta);
+        if self.message_type.has_extensions() {
+            fields.unsplit(self.extension_headers.encode());
+        }
+        let payload = self.subgroup_object.encode_fields(&mut fields);
+        std::iter::once(fields.freeze()).chain(payload).collect()
+    }
 }
 
 #[cfg(test)]
@@ -480,6 +536,24 @@ mod tests {
             assert_eq!(object_field.subgroup_object, depacketized.subgroup_object);
         }
 
+        #[test]
+        fn subgroup_object_field_chunks_share_the_payload() {
+            let message_type = SubgroupHeaderType::new(0x10).unwrap();
+            let payload = Bytes::from(vec![0xDE, 0xAD, 0xBE, 0xEF]);
+            let object_field = SubgroupObjectField {
+                message_type,
+                object_id_delta: 1,
+                extension_headers: ExtensionHeaders::default(),
+                subgroup_object: SubgroupObject::new_payload(payload.clone()),
+            };
+
+            let chunks = object_field.encode_chunks();
+
+            assert_eq!(chunks.len(), 2);
+            assert_eq!(chunks[1].as_ptr(), payload.as_ptr());
+            assert_eq!(chunks.concat(), object_field.encode().to_vec());
+        }
+
         #[test]
         fn subgroup_object_field_packetize_and_depacketize_with_extensions() {
             let message_type = SubgroupHeaderType::new(0x11).unwrap();
@@ -534,6 +608,36 @@ mod tests {
             assert!(buf.is_empty());
         }
 
+        #[test]
+        fn subgroup_object_field_required_len_waits_for_the_payload() {
+            let message_type = SubgroupHeaderType::new(0x11).unwrap();
+            let mut extension_headers = ExtensionHeaders::default();
+            extension_headers.push_prior_group_id_gap(10);
+            let object_field = SubgroupObjectField {
+                message_type,
+                object_id_delta: 5,
+                extension_headers,
+                subgroup_object: SubgroupObject::new_payload(Bytes::from(vec![0xab; 300])),
+            };
+            let encoded = object_field.encode();
+            let fields_len = encoded.len() - 300;
+
+            for received in 0..encoded.len() {
+                let required =
+                    SubgroupObjectField::required_len(message_type, &encoded[..received]);
+
+                assert!(required > received);
+                assert!(required <= encoded.len());
+                if received >= fields_len {
+                    assert_eq!(required, encoded.len());
+                }
+            }
+            assert_eq!(
+                SubgroupObjectField::required_len(message_type, &encoded),
+                encoded.len()
+            );
+        }
+
         #[test]
         fn subgroup_header_type_selection() {
             // Case 1: No Subgroup ID, No Extensions, No End of Group -> 0x10
@@ -554,6 +658,15 @@ mod tests {
             assert_eq!(buf[0], 0x1D);
         }
 
+        #[test]
+        fn with_subgroup_id_makes_the_id_explicit_and_keeps_the_flags() {
+            let h = SubgroupHeader::new(0, 0, SubgroupId::FirstObjectIdDelta, 0, true, false);
+            let explicit = h.with_subgroup_id(7);
+            assert_eq!(explicit.subgroup_id, SubgroupId::Value(7));
+            // Value, Extensions, No End of Group -> 0x15
+            assert_eq!(explicit.encode()[0], 0x15);
+        }
+
         // --- resolve_object_id Tests ---
 
         fn make_field(delta: u64) -> SubgroupObjectField {
@@ -0,0 +1,107 @@
+use crate::modules::moqt::data_plane::object::subgroup::{
+    SubgroupHeader, SubgroupId, SubgroupObjectField,
+};
+
+/// An object read from a subgroup stream, carrying the header context that
+/// the wire format only sends once per stream.
+#[derive(Debug, Clone)]
+pub struct SubgroupStreamObject {
+    pub track_alias: u64,
+    pub group_id: u64,
+    pub subgroup_id: u64,
+    pub publisher_priority: u8,
+    pub object_id: u64,
+    pub field: SubgroupObjectField,
+}
+
+/// Per-stream state that turns Object ID deltas into absolute Object IDs and
+/// resolves the Subgroup ID, including the "first Object ID" header form.
+#[derive(Debug, Clone)]
+pub struct SubgroupStreamContext {
+    header: SubgroupHeader,
+    subgroup_id: Option<u64>,
+    last_object_id: Option<u64>,
+}
+
+impl SubgroupStreamContext {
+    pub fn new(header: SubgroupHeader) -> Self {
+        let subgroup_id = match header.subgroup_id {
+            SubgroupId::None => Some(0),
+            SubgroupId::Value(value) => Some(value),
+            SubgroupId::FirstObjectIdDelta => None,
+        };
+        Self {
+            header,
+            subgroup_id,
+            last_object_id: None,
+        }
+    }
+
+    pub fn header(&self) -> &SubgroupHeader {
+        &self.header
+    }
+
+    /// Must be called for every object on the stream, in order.
+    pub fn next_object(&mut self, field: SubgroupObjectField) -> SubgroupStreamObject {
+        let object_id = field.resolve_object_id(self.last_object_id);
+        self.last_object_id = Some(object_id);
+        let subgroup_id = *self.subgroup_id.get_or_insert(object_id);
+        SubgroupStreamObject {
+            track_alias: self.header.track_alias,
+            group_id: self.header.group_id,
+            subgroup_id,
+            publisher_priority: self.header.publisher_priority,
+            object_id,
+            field,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SubgroupStreamContext;
+    use crate::modules::moqt::data_plane::object::{
+        extension_headers::ExtensionHeaders,
+        subgroup::{SubgroupHeader, SubgroupId, SubgroupObject, SubgroupObjectField},
+    };
+
+    fn field(header: &SubgroupHeader, object_id_delta: u64) -> SubgroupObjectField {
+        SubgroupObjectField {
+            message_type: header.message_type,
+            object_id_delta,
+            extension_headers: ExtensionHeaders::default(),
+            subgroup_object: SubgroupObject::new_payload(Bytes::from_static(b"x")),
+        }
+    }
+
+    #[test]
+    fn next_object_carries_header_context_and_absolute_object_ids() {
+        // Arrange
+        let header = SubgroupHeader::new(3, 7, SubgroupId::Value(2), 16, false, false);
+        let mut context = SubgroupStreamContext::new(header.clone());
+        // Act
+        let first = context.next_object(field(&header, 4));
+        let second = context.next_object(field(&header, 1));
+        // Assert
+        assert_eq!(
+            (first.track_alias, first.group_id, first.subgroup_id),
+            (3, 7, 2)
+        );
+        assert_eq!(first.publisher_priority, 16);
+        assert_eq!((first.object_id, second.object_id), (4, 6));
+    }
+
+    #[test]
+    fn first_object_id_delta_header_uses_first_object_id_as_subgroup_id() {
+        // Arrange
+        let header = SubgroupHeader::new(0, 0, SubgroupId::FirstObjectIdDelta, 0, false, false);
+        let mut context = SubgroupStreamContext::new(header.clone());
+        // Act
+        let first = context.next_object(field(&header, 5));
+        let second = context.next_object(field(&header, 0));
+        // Assert
+        assert_eq!((first.subgroup_id, second.subgroup_id), (5, 5));
+    }
+}
@@ -1,23 +1,30 @@
+use std::sync::OnceLock;
+
 use bytes::BytesMut;
 
 use crate::{
     TransportProtocol,
     modules::{
-        moqt::control_plane::control_messages::control_message_type::ControlMessageType,
+        moqt::control_plane::{
+            control_message_observer::{ControlMessageDirection, ControlMessageObserver},
+            control_messages::control_message_type::ControlMessageType,
+        },
         transport::transport_send_stream::{TransportSendError, TransportSendStream},
     },
     wire::encode_control_message,
 };
 
-#[derive(Debug)]
 pub(crate) struct BiStreamSender<T: TransportProtocol> {
     stream_sender: tokio::sync::Mutex<T::SendStream>,
+    /// Set at most once, after SETUP; also told about received messages.
+    observer: OnceLock<ControlMessageObserver>,
 }
 
 impl<T: TransportProtocol> BiStreamSender<T> {
     pub(crate) fn new(stream_sender: T::SendStream) -> Self {
         Self {
             stream_sender: tokio::sync::Mutex::new(stream_sender),
+            observer: OnceLock::new(),
         }
     }
 
@@ -28,7 +35,9 @@ impl<T: TransportProtocol> BiStreamSender<T> {
     ) -> Result<(), TransportSendError> {
         let message_bytes = encode_control_message(message_type, bytes);
         let mut stream_sender = self.stream_sender.lock().await;
-        stream_sender.send(&message_bytes).await
+        stream_sender.send(&message_bytes).await?;
+        self.observe(ControlMessageDirection::Sent, message_type);
+        Ok(())
     }
 
     // GoAway message is implemented then we can use this function to send GoAway message.
@@ -37,4 +46,28 @@ impl<T: TransportProtocol> BiStreamSender<T> {
         let mut stream_sender = self.stream_sender.lock().await;
         stream_sender.close().await
     }
+
+    /// `false` if an observer was already set.
+    pub(crate) fn set_observer(&self, observer: ControlMessageObserver) -> bool {
+        self.observer.set(observer).is_ok()
+    }
+
+    pub(crate) fn observe(
+        &self,
+        direction: ControlMessageDirection,
+        message_type: ControlMessageType,
+    ) {
+        if let Some(observer) = self.observer.get() {
+            observer(direction, message_type);
+        }
+    }
+}
+
+impl<T: TransportProtocol> std::fmt::Debug for BiStreamSender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BiStreamSender")
+            .field("stream_sender", &self.stream_sender)
+            .field("observed", &self.observer.get().is_some())
+            .finish()
+    }
 }
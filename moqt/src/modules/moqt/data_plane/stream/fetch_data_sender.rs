@@ -19,8 +19,7 @@ impl<T: TransportProtocol> FetchDataSender<T> {
     }
 
     pub async fn send(&self, object: FetchObjectField) -> anyhow::Result<()> {
-        let bytes = object.encode();
-        self.stream_sender.send(&bytes).await
+        self.stream_sender.send_chunks(object.encode_chunks()).await
     }
 
     pub async fn close(&self) -> anyhow::Result<()> {
@@ -1,19 +1,27 @@
+use crate::modules::moqt::control_plane::control_messages::control_message_type::ControlMessageType;
 use crate::modules::moqt::control_plane::control_messages::messages::{
-    client_setup::ClientSetup, fetch::Fetch, fetch_ok::FetchOk, namespace_ok::NamespaceOk,
-    publish::Publish, publish_namespace::PublishNamespace,
-    publish_namespace_done::PublishNamespaceDone, publish_ok::PublishOk,
-    request_error::RequestError, server_setup::ServerSetup, subscribe::Subscribe,
-    subscribe_namespace::SubscribeNamespace, subscribe_ok::SubscribeOk, unsubscribe::Unsubscribe,
+    client_setup::ClientSetup, fetch::Fetch, fetch_cancel::FetchCancel, fetch_ok::FetchOk,
+    go_away::GoAway, max_request_id::MaxRequestId, namespace_ok::NamespaceOk, publish::Publish,
+    publish_done::PublishDone, publish_namespace::PublishNamespace,
+    publish_namespace_cancel::PublishNamespaceCancel, publish_namespace_done::PublishNamespaceDone,
+    publish_ok::PublishOk, request_error::RequestError, requests_blocked::RequestsBlocked,
+    server_setup::ServerSetup, subscribe::Subscribe, subscribe_namespace::SubscribeNamespace,
+    subscribe_ok::SubscribeOk, subscribe_update::SubscribeUpdate, track_status::TrackStatus,
+    track_status_request::TrackStatusRequest, unsubscribe::Unsubscribe,
     unsubscribe_namespace::UnsubscribeNamespace,
 };
 
 pub(crate) enum ReceivedMessage {
     ClientSetup(ClientSetup),
     ServerSetup(ServerSetup),
+    GoAway(GoAway),
+    MaxRequestId(MaxRequestId),
+    RequestsBlocked(RequestsBlocked),
     PublishNamespace(PublishNamespace),
     PublishNamespaceOk(NamespaceOk),
     PublishNamespaceError(RequestError),
     PublishNamespaceDone(PublishNamespaceDone),
+    PublishNamespaceCancel(PublishNamespaceCancel),
     SubscribeNamespace(SubscribeNamespace),
     SubscribeNamespaceOk(NamespaceOk),
     SubscribeNamespaceError(RequestError),
@@ -24,22 +32,91 @@ pub(crate) enum ReceivedMessage {
     Subscribe(Subscribe),
     SubscribeOk(SubscribeOk),
     SubscribeError(RequestError),
+    SubscribeUpdate(SubscribeUpdate),
     Unsubscribe(Unsubscribe),
+    PublishDone(PublishDone),
     Fetch(Fetch),
     FetchOk(FetchOk),
     FetchError(RequestError),
+    FetchCancel(FetchCancel),
+    TrackStatusRequest(TrackStatusRequest),
+    TrackStatus(TrackStatus),
+    TrackStatusError(RequestError),
     FatalError(),
 }
 
+impl ReceivedMessage {
+    /// Request ID of a new request opened by the peer, which counts against
+    /// the Maximum Request ID this endpoint granted.
+    pub(crate) fn peer_request_id(&self) -> Option<u64> {
+        match self {
+            ReceivedMessage::PublishNamespace(message) => Some(message.request_id),
+            ReceivedMessage::SubscribeNamespace(message) => Some(message.request_id),
+            ReceivedMessage::Publish(message) => Some(message.request_id),
+            ReceivedMessage::Subscribe(message) => Some(message.request_id),
+            ReceivedMessage::SubscribeUpdate(message) => Some(message.request_id),
+            ReceivedMessage::Fetch(message) => Some(message.request_id),
+            ReceivedMessage::TrackStatusRequest(message) => Some(message.request_id),
+            _ => None,
+        }
+    }
+
+    /// Wire type the message was decoded from; `None` for an undecodable one.
+    pub(crate) fn message_type(&self) -> Option<ControlMessageType> {
+        let message_type = match self {
+            ReceivedMessage::ClientSetup(_) => ControlMessageType::ClientSetup,
+            ReceivedMessage::ServerSetup(_) => ControlMessageType::ServerSetup,
+            ReceivedMessage::GoAway(_) => ControlMessageType::GoAway,
+            ReceivedMessage::MaxRequestId(_) => ControlMessageType::MaxSubscribeId,
+            ReceivedMessage::RequestsBlocked(_) => ControlMessageType::RequestsBlocked,
+            ReceivedMessage::PublishNamespace(_) => ControlMessageType::PublishNamespace,
+            ReceivedMessage::PublishNamespaceOk(_) => ControlMessageType::PublishNamespaceOk,
+            ReceivedMessage::PublishNamespaceError(_) => ControlMessageType::PublishNamespaceError,
+            ReceivedMessage::PublishNamespaceDone(_) => ControlMessageType::PublishNamespaceDone,
+            ReceivedMessage::PublishNamespaceCancel(_) => {
+                ControlMessageType::PublishNamespaceCancel
+            }
+            ReceivedMessage::SubscribeNamespace(_) => ControlMessageType::SubscribeNamespace,
+            ReceivedMessage::SubscribeNamespaceOk(_) => ControlMessageType::SubscribeNamespaceOk,
+            ReceivedMessage::SubscribeNamespaceError(_) => {
+                ControlMessageType::SubscribeNamespaceError
+            }
+            ReceivedMessage::UnsubscribeNamespace(_) => ControlMessageType::UnSubscribeNamespace,
+            ReceivedMessage::Publish(_) => ControlMessageType::Publish,
+            ReceivedMessage::PublishOk(_) => ControlMessageType::PublishOk,
+            ReceivedMessage::PublishError(_) => ControlMessageType::PublishError,
+            ReceivedMessage::Subscribe(_) => ControlMessageType::Subscribe,
+            ReceivedMessage::SubscribeOk(_) => ControlMessageType::SubscribeOk,
+            ReceivedMessage::SubscribeError(_) => ControlMessageType::SubscribeError,
+            ReceivedMessage::SubscribeUpdate(_) => ControlMessageType::SubscribeUpdate,
+            ReceivedMessage::Unsubscribe(_) => ControlMessageType::UnSubscribe,
+            ReceivedMessage::PublishDone(_) => ControlMessageType::PublishDone,
+            ReceivedMessage::Fetch(_) => ControlMessageType::Fetch,
+            ReceivedMessage::FetchOk(_) => ControlMessageType::FetchOk,
+            ReceivedMessage::FetchError(_) => ControlMessageType::FetchError,
+            ReceivedMessage::FetchCancel(_) => ControlMessageType::FetchCancel,
+            ReceivedMessage::TrackStatusRequest(_) => ControlMessageType::TrackStatusRequest,
+            ReceivedMessage::TrackStatus(_) => ControlMessageType::TrackStatus,
+            ReceivedMessage::TrackStatusError(_) => ControlMessageType::TrackStatusError,
+            ReceivedMessage::FatalError() => return None,
+        };
+        Some(message_type)
+    }
+}
+
 impl std::fmt::Debug for ReceivedMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let name = match self {
             ReceivedMessage::ClientSetup(_) => "ClientSetup",
             ReceivedMessage::ServerSetup(_) => "ServerSetup",
+            ReceivedMessage::GoAway(_) => "GoAway",
+            ReceivedMessage::MaxRequestId(_) => "MaxRequestId",
+            ReceivedMessage::RequestsBlocked(_) => "RequestsBlocked",
             ReceivedMessage::PublishNamespace(_) => "PublishNamespace",
             ReceivedMessage::PublishNamespaceOk(_) => "PublishNamespaceOk",
             ReceivedMessage::PublishNamespaceError(_) => "PublishNamespaceError",
             ReceivedMessage::PublishNamespaceDone(_) => "PublishNamespaceDone",
+            ReceivedMessage::PublishNamespaceCancel(_) => "PublishNamespaceCancel",
             ReceivedMessage::SubscribeNamespace(_) => "SubscribeNamespace",
             ReceivedMessage::SubscribeNamespaceOk(_) => "SubscribeNamespaceOk",
             ReceivedMessage::SubscribeNamespaceError(_) => "SubscribeNamespaceError",
@@ -50,10 +127,16 @@ impl std::fmt::Debug for ReceivedMessage {
             ReceivedMessage::Subscribe(_) => "Subscribe",
             ReceivedMessage::SubscribeOk(_) => "SubscribeOk",
             ReceivedMessage::SubscribeError(_) => "SubscribeError",
+            ReceivedMessage::SubscribeUpdate(_) => "SubscribeUpdate",
             ReceivedMessage::Unsubscribe(_) => "Unsubscribe",
+            ReceivedMessage::PublishDone(_) => "PublishDone",
             ReceivedMessage::Fetch(_) => "Fetch",
             ReceivedMessage::FetchOk(_) => "FetchOk",
             ReceivedMessage::FetchError(_) => "FetchError",
+            ReceivedMessage::FetchCancel(_) => "FetchCancel",
+            ReceivedMessage::TrackStatusRequest(_) => "TrackStatusRequest",
+            ReceivedMessage::TrackStatus(_) => "TrackStatus",
+            ReceivedMessage::TrackStatusError(_) => "TrackStatusError",
             ReceivedMessage::FatalError() => "FatalError",
         };
 
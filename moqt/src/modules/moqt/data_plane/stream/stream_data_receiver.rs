@@ -2,7 +2,10 @@ use crate::{
     TransportProtocol,
     modules::moqt::data_plane::{
         codec::uni_stream_decoder::UniStreamData,
-        object::subgroup::{SubgroupHeader, SubgroupObjectField},
+        object::{
+            subgroup::{SubgroupHeader, SubgroupObjectField},
+            subgroup_stream_object::{SubgroupStreamContext, SubgroupStreamObject},
+        },
         stream::stream_receiver::{StreamReceiveError, UniStreamReceiver},
     },
 };
@@ -18,6 +21,7 @@ pub struct StreamDataReceiver<T: TransportProtocol> {
     stream_receiver: UniStreamReceiver<T>,
     pub track_alias: u64,
     first_subgroup_header: Option<SubgroupHeader>,
+    context: SubgroupStreamContext,
 }
 
 impl<T: TransportProtocol> StreamDataReceiver<T> {
@@ -29,6 +33,7 @@ impl<T: TransportProtocol> StreamDataReceiver<T> {
         Ok(Self {
             stream_receiver: stream,
             track_alias,
+            context: SubgroupStreamContext::new(subgroup_header.clone()),
             first_subgroup_header: Some(subgroup_header),
         })
     }
@@ -42,7 +47,13 @@ impl<T: TransportProtocol> StreamDataReceiver<T> {
         }
 
         match self.stream_receiver.receive().await {
-            Ok(Some(UniStreamData::Subgroup(subgroup))) => Ok(Some(subgroup)),
+            Ok(Some(UniStreamData::Subgroup(subgroup))) => {
+                if let Subgroup::Object(field) = &subgroup {
+                    // Keep Object ID resolution in step for receive_object.
+                    self.context.next_object(field.clone());
+                }
+                Ok(Some(subgroup))
+            }
             Ok(Some(UniStreamData::Fetch(_))) => {
                 unreachable!("Unexpected fetch data in subgroup stream")
             }
@@ -53,4 +64,31 @@ impl<T: TransportProtocol> StreamDataReceiver<T> {
             Err(error) => Err(error),
         }
     }
+
+    /// Like [`receive`](Self::receive), but skips the header and returns each
+    /// object with its Track Alias, Group ID, Subgroup ID, Publisher Priority
+    /// and absolute Object ID.
+    pub async fn receive_object(
+        &mut self,
+    ) -> Result<Option<SubgroupStreamObject>, StreamReceiveError> {
+        self.first_subgroup_header = None;
+        loop {
+            match self.stream_receiver.receive().await {
+                Ok(Some(UniStreamData::Subgroup(Subgroup::Object(field)))) => {
+                    return Ok(Some(self.context.next_object(field)));
+                }
+                Ok(Some(UniStreamData::Subgroup(Subgroup::Header(header)))) => {
+                    tracing::warn!(?header, "Ignoring repeated subgroup header");
+                }
+                Ok(Some(UniStreamData::Fetch(_))) => {
+                    unreachable!("Unexpected fetch data in subgroup stream")
+                }
+                Ok(None) => {
+                    tracing::debug!("Stream data ended");
+                    return Ok(None);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
 }
@@ -1,13 +1,16 @@
 use std::marker::PhantomData;
 
 use crate::{
-    TransportProtocol,
-    modules::moqt::data_plane::{
-        object::{
-            extension_headers::ExtensionHeaders,
-            subgroup::{SubgroupHeader, SubgroupId, SubgroupObject, SubgroupObjectField},
+    GroupOrder, TransportProtocol,
+    modules::moqt::{
+        data_plane::{
+            object::{
+                extension_headers::ExtensionHeaders,
+                subgroup::{SubgroupHeader, SubgroupId, SubgroupObject, SubgroupObjectField},
+            },
+            stream::stream_sender::StreamSender,
         },
-        stream::stream_sender::StreamSender,
+        domains::open_stream_tracker::OpenStreamGuard,
     },
 };
 
@@ -29,18 +32,76 @@ pub struct StreamDataSender<T: TransportProtocol, S = Uninitialized> {
     stream_sender: StreamSender<T>,
     track_alias: u64,
     subgroup_header: Option<SubgroupHeader>,
+    /// Keeps the stream counted for GOAWAY drain until the sender is dropped.
+    _open_stream: OpenStreamGuard,
     _state: PhantomData<S>,
 }
 
+/// Low bits of the transport priority that order groups of equal priority.
+const GROUP_RANK_BITS: u32 = 15;
+
+/// Maps MOQT priorities to a transport send priority. In MOQT a lower value
+/// is delivered first, the subscriber priority takes precedence over the
+/// publisher priority, and ties go by group order; transports send higher
+/// values first.
+///
+/// Groups are ranked by the low 15 bits of their id, so only streams a few
+/// thousand groups apart can be misordered, at the wrap.
+pub(crate) fn stream_send_priority(
+    subscriber_priority: u8,
+    publisher_priority: u8,
+    group_order: GroupOrder,
+    group_id: u64,
+) -> i32 {
+    let rank_mask = (1u64 << GROUP_RANK_BITS) - 1;
+    let group_rank = match group_order {
+        GroupOrder::Descending => group_id & rank_mask,
+        GroupOrder::Ascending | GroupOrder::Publisher => rank_mask - (group_id & rank_mask),
+    } as i32;
+    let priority = (i32::from(subscriber_priority) << 8) | i32::from(publisher_priority);
+    -(priority << GROUP_RANK_BITS) + group_rank
+}
+
+impl<T: TransportProtocol, S> StreamDataSender<T, S> {
+    /// Orders this stream of `group_id` against other data streams on the
+    /// session. Streams with the same priority share bandwidth round-robin.
+    pub async fn set_priority(
+        &self,
+        subscriber_priority: u8,
+        publisher_priority: u8,
+        group_order: GroupOrder,
+        group_id: u64,
+    ) -> anyhow::Result<()> {
+        self.stream_sender
+            .set_priority(stream_send_priority(
+                subscriber_priority,
+                publisher_priority,
+                group_order,
+                group_id,
+            ))
+            .await
+    }
+
+    /// Abandons the stream with a RESET_STREAM carrying `error_code`.
+    pub async fn reset(&self, error_code: u64) -> anyhow::Result<()> {
+        self.stream_sender.reset(error_code).await
+    }
+}
+
 // ─── Uninitialized State ───────────────────────────────────────────────────────
 
 impl<T: TransportProtocol> StreamDataSender<T, Uninitialized> {
-    pub(crate) fn new(track_alias: u64, send_stream: T::SendStream) -> Self {
+    pub(crate) fn new(
+        track_alias: u64,
+        send_stream: T::SendStream,
+        open_stream: OpenStreamGuard,
+    ) -> Self {
         let stream_sender = StreamSender::new(send_stream);
         Self {
             stream_sender,
             track_alias,
             subgroup_header: None,
+            _open_stream: open_stream,
             _state: PhantomData,
         }
     }
@@ -84,6 +145,7 @@ impl<T: TransportProtocol> StreamDataSender<T, Uninitialized> {
             stream_sender: self.stream_sender,
             track_alias: self.track_alias,
             subgroup_header: Some(header),
+            _open_stream: self._open_stream,
             _state: PhantomData,
         })
     }
@@ -116,11 +178,44 @@ impl<T: TransportProtocol> StreamDataSender<T, HeaderSent> {
     /// Sends an object on the stream.
     pub async fn send(&mut self, data: SubgroupObjectField) -> anyhow::Result<()> {
         tracing::debug!("Sending subgroup object");
-        let bytes = data.encode();
-        self.stream_sender.send(&bytes).await
+        self.stream_sender.send_chunks(data.encode_chunks()).await
     }
 
     pub async fn close(&mut self) -> anyhow::Result<()> {
         self.stream_sender.close().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscriber_priority_dominates_publisher_priority_and_groups() {
+        // Arrange / Act
+        let urgent_subscriber = stream_send_priority(1, 255, GroupOrder::Ascending, 0);
+        let lax_subscriber = stream_send_priority(2, 0, GroupOrder::Ascending, 0);
+        let urgent_publisher = stream_send_priority(1, 0, GroupOrder::Ascending, 9);
+
+        // Assert
+        assert!(urgent_subscriber > lax_subscriber);
+        assert!(urgent_publisher > urgent_subscriber);
+    }
+
+    #[test]
+    fn group_order_breaks_ties() {
+        // Arrange / Act
+        let ascending_old = stream_send_priority(1, 1, GroupOrder::Ascending, 4);
+        let ascending_new = stream_send_priority(1, 1, GroupOrder::Ascending, 5);
+        let descending_old = stream_send_priority(1, 1, GroupOrder::Descending, 4);
+        let descending_new = stream_send_priority(1, 1, GroupOrder::Descending, 5);
+
+        // Assert
+        assert!(ascending_old > ascending_new);
+        assert!(descending_new > descending_old);
+        assert_eq!(
+            stream_send_priority(255, 255, GroupOrder::Ascending, 0),
+            -(0xFFFF << GROUP_RANK_BITS) + 0x7FFF
+        );
+    }
+}
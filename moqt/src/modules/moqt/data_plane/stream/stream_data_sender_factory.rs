@@ -26,6 +26,10 @@ impl<T: TransportProtocol> StreamDataSenderFactory<T> {
 
     pub async fn next(&self) -> anyhow::Result<StreamDataSender<T>> {
         let send_stream = self.session.transport_connection.open_uni().await?;
-        Ok(StreamDataSender::new(self.track_alias, send_stream))
+        Ok(StreamDataSender::new(
+            self.track_alias,
+            send_stream,
+            self.session.track_open_stream(),
+        ))
     }
 }
@@ -1,4 +1,4 @@
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 
 use crate::modules::{
     moqt::protocol::TransportProtocol, transport::transport_send_stream::TransportSendStream,
@@ -21,6 +21,21 @@ impl<T: TransportProtocol> StreamSender<T> {
         Ok(self.send_stream.lock().await.send(bytes).await?)
     }
 
+    /// Sends `chunks` as one message; shared payloads are written from
+    /// where they already are instead of being copied.
+    pub async fn send_chunks(&self, mut chunks: Vec<Bytes>) -> anyhow::Result<()> {
+        tracing::debug!(
+            "bytes length: {}",
+            chunks.iter().map(Bytes::len).sum::<usize>()
+        );
+        Ok(self
+            .send_stream
+            .lock()
+            .await
+            .send_chunks(&mut chunks)
+            .await?)
+    }
+
     pub async fn close(&self) -> anyhow::Result<()> {
         Ok(self.send_stream.lock().await.close().await?)
     }
@@ -28,4 +43,8 @@ impl<T: TransportProtocol> StreamSender<T> {
     pub async fn reset(&self, error_code: u64) -> anyhow::Result<()> {
         Ok(self.send_stream.lock().await.reset(error_code).await?)
     }
+
+    pub async fn set_priority(&self, priority: i32) -> anyhow::Result<()> {
+        Ok(self.send_stream.lock().await.set_priority(priority)?)
+    }
 }
@@ -1,7 +1,12 @@
 pub(crate) mod connecting;
 pub(crate) mod endpoint;
 pub(crate) mod fetch_handle;
+pub(crate) mod multi_session_publisher;
+pub(crate) mod open_stream_tracker;
+pub(crate) mod peer_request_limit;
 pub(crate) mod publisher;
+pub(crate) mod request_id_grant;
+pub(crate) mod resilient_subscriber;
 pub(crate) mod session;
 pub(crate) mod session_context;
 pub(crate) mod session_context_factory;
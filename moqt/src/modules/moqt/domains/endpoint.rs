@@ -1,10 +1,17 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use crate::{
     Connecting, TransportProtocol,
     modules::{
-        moqt::domains::session_creator::SessionCreator,
-        transport::transport_connection_creator::TransportConnectionCreator,
+        moqt::{
+            control_plane::control_message_limits::ControlMessageLimits,
+            domains::session_creator::SessionCreator,
+        },
+        transport::{
+            server_certificate::CertificateReloader,
+            transport_connection_creator::TransportConnectionCreator,
+            transport_tuning::TransportTuning,
+        },
     },
 };
 
@@ -27,7 +34,22 @@ pub struct ServerConfig {
     pub cert_path: String,
     pub key_path: String,
     pub keep_alive_interval_sec: u64,
-    // log_level: String,
+    pub transport: TransportTuning,
+    /// Connections that have not completed CLIENT_SETUP this long after
+    /// being accepted are closed with CONTROL_MESSAGE_TIMEOUT.
+    pub setup_timeout: Option<Duration>,
+    /// Sessions, handshaking or established, the endpoint serves at once.
+    /// Connections beyond it are closed with INTERNAL_ERROR on accept.
+    pub max_sessions: Option<usize>,
+    /// Endpoints [`Endpoint::create_servers`] binds to the port, each with
+    /// its own QUIC driver task and accept loop; 0 and 1 bind one. More
+    /// than one uses SO_REUSEPORT, so the kernel spreads connections by
+    /// address, and a client whose address changes mid-connection (NAT
+    /// rebinding, migration) may land on an endpoint that does not know it.
+    pub accept_workers: usize,
+    /// Largest control message and control message rate accepted from each
+    /// client; a client past them is closed with PROTOCOL_VIOLATION.
+    pub control_message_limits: ControlMessageLimits,
 }
 
 pub struct Endpoint<T: TransportProtocol> {
@@ -39,6 +61,9 @@ impl<T: TransportProtocol> Endpoint<T> {
         let client = T::ConnectionCreator::client(config.port, config.verify_certificate)?;
         let session_creator = SessionCreator {
             transport_creator: client,
+            setup_timeout: None,
+            session_slots: None,
+            control_message_limits: ControlMessageLimits::default(),
         };
         Ok(Self { session_creator })
     }
@@ -50,23 +75,64 @@ impl<T: TransportProtocol> Endpoint<T> {
         let client = T::ConnectionCreator::client_with_custom_cert(port_num, custom_cert_path)?;
         let session_creator = SessionCreator {
             transport_creator: client,
+            setup_timeout: None,
+            session_slots: None,
+            control_message_limits: ControlMessageLimits::default(),
         };
         Ok(Self { session_creator })
     }
 
+    /// Binds one server endpoint, whatever `accept_workers` says.
     pub fn create_server(server_config: &ServerConfig) -> anyhow::Result<Self> {
+        Self::bind_server(server_config, false, Self::session_slots(server_config))
+    }
+
+    /// Binds `accept_workers` server endpoints (at least one) to the port,
+    /// to be accepted from in parallel. They share `max_sessions`.
+    pub fn create_servers(server_config: &ServerConfig) -> anyhow::Result<Vec<Self>> {
+        let workers = server_config.accept_workers.max(1);
+        let session_slots = Self::session_slots(server_config);
+        (0..workers)
+            .map(|_| Self::bind_server(server_config, workers > 1, session_slots.clone()))
+            .collect()
+    }
+
+    fn session_slots(server_config: &ServerConfig) -> Option<Arc<tokio::sync::Semaphore>> {
+        server_config
+            .max_sessions
+            .map(|max_sessions| Arc::new(tokio::sync::Semaphore::new(max_sessions)))
+    }
+
+    fn bind_server(
+        server_config: &ServerConfig,
+        reuse_port: bool,
+        session_slots: Option<Arc<tokio::sync::Semaphore>>,
+    ) -> anyhow::Result<Self> {
         let server = T::ConnectionCreator::server(
             &server_config.cert_path,
             &server_config.key_path,
             server_config.port,
             server_config.keep_alive_interval_sec,
+            &server_config.transport,
+            reuse_port,
         )?;
         let session_creator = SessionCreator {
             transport_creator: server,
+            setup_timeout: server_config.setup_timeout,
+            session_slots,
+            control_message_limits: server_config.control_message_limits,
         };
         Ok(Self { session_creator })
     }
 
+    /// Handle for swapping the server certificate while sessions stay up.
+    /// `None` for clients and for the WebTransport-only server.
+    pub fn certificate_reloader(&self) -> Option<CertificateReloader> {
+        self.session_creator
+            .transport_creator
+            .certificate_reloader()
+    }
+
     pub async fn connect(
         &self,
         remote_address: SocketAddr,
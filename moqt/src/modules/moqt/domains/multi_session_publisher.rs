@@ -0,0 +1,372 @@
+use std::future::Future;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+use std::task::Poll;
+
+use anyhow::bail;
+use bytes::Bytes;
+
+use crate::{
+    AdaptiveObjectSender, ObjectTransport, PublishOption, Publisher, Subscription,
+    TransportSelection, modules::moqt::protocol::TransportProtocol,
+};
+
+/// Health of one relay as seen by [`MultiSessionPublisher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayStatus {
+    pub label: String,
+    pub healthy: bool,
+    /// Failed operations since the relay was added or last replaced.
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct RelayHealth {
+    failed: AtomicBool,
+    failures: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl RelayHealth {
+    fn is_healthy(&self) -> bool {
+        !self.failed.load(Ordering::Relaxed)
+    }
+
+    fn record_failure(&self, label: &str, error: &anyhow::Error) {
+        self.failed.store(true, Ordering::Relaxed);
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock().unwrap() = Some(error.to_string());
+        tracing::warn!(relay = %label, %error, "relay marked unhealthy");
+    }
+
+    fn status(&self, label: &str) -> RelayStatus {
+        RelayStatus {
+            label: label.to_string(),
+            healthy: self.is_healthy(),
+            failures: self.failures.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().unwrap().clone(),
+        }
+    }
+}
+
+struct RelayPublisher<T: TransportProtocol> {
+    label: String,
+    publisher: Publisher<T>,
+    health: Arc<RelayHealth>,
+}
+
+/// Publishes the same namespaces and tracks through several relay sessions
+/// at once (multi-homing), so contribution survives the loss of one relay.
+///
+/// Every operation is mirrored concurrently to the healthy relays. A relay
+/// that fails is marked unhealthy and skipped from then on, until
+/// [`MultiSessionPublisher::replace_relay`] swaps in a reconnected session.
+/// An operation fails only when no relay accepted it. Subscribers need no
+/// changes: each relay sees an ordinary publisher.
+pub struct MultiSessionPublisher<T: TransportProtocol> {
+    relays: Vec<RelayPublisher<T>>,
+}
+
+impl<T: TransportProtocol> MultiSessionPublisher<T> {
+    /// `relays` pairs a label (used in logs and [`RelayStatus`]) with the
+    /// publisher of a session to that relay.
+    pub fn new(relays: impl IntoIterator<Item = (String, Publisher<T>)>) -> Self {
+        Self {
+            relays: relays
+                .into_iter()
+                .map(|(label, publisher)| RelayPublisher {
+                    label,
+                    publisher,
+                    health: Arc::default(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn relay_status(&self) -> Vec<RelayStatus> {
+        self.relays
+            .iter()
+            .map(|relay| relay.health.status(&relay.label))
+            .collect()
+    }
+
+    /// Replaces (or adds) the relay `label` with a new session, typically
+    /// after reconnecting it, and marks it healthy again. Namespaces and
+    /// tracks must be published again for the new session.
+    pub fn replace_relay(&mut self, label: String, publisher: Publisher<T>) {
+        let relay = RelayPublisher {
+            label,
+            publisher,
+            health: Arc::default(),
+        };
+        match self.relays.iter_mut().find(|r| r.label == relay.label) {
+            Some(existing) => *existing = relay,
+            None => self.relays.push(relay),
+        }
+    }
+
+    pub async fn publish_namespace(&self, namespace: String) -> anyhow::Result<()> {
+        let healthy = self.healthy_relays();
+        let results = join_all(
+            healthy
+                .iter()
+                .map(|relay| relay.publisher.publish_namespace(namespace.clone())),
+        )
+        .await;
+        settle(&healthy, results, "PUBLISH_NAMESPACE").map(|_| ())
+    }
+
+    pub async fn publish_namespace_done(&self, namespace: String) -> anyhow::Result<()> {
+        let healthy = self.healthy_relays();
+        let results = join_all(
+            healthy
+                .iter()
+                .map(|relay| relay.publisher.publish_namespace_done(namespace.clone())),
+        )
+        .await;
+        settle(&healthy, results, "PUBLISH_NAMESPACE_DONE").map(|_| ())
+    }
+
+    /// Sends PUBLISH to every healthy relay and returns a track whose objects
+    /// are mirrored to the relays that accepted it.
+    pub async fn publish(
+        &self,
+        track_namespace: String,
+        track_name: String,
+        option: PublishOption,
+        selection: TransportSelection,
+    ) -> anyhow::Result<MultiPublication<T>> {
+        let healthy = self.healthy_relays();
+        let results = join_all(healthy.iter().map(|relay| {
+            relay
+                .publisher
+                .publish(track_namespace.clone(), track_name.clone(), option.clone())
+        }))
+        .await;
+        let accepted = settle(&healthy, results, "PUBLISH")?;
+        let tracks = accepted
+            .into_iter()
+            .map(|(relay, subscription)| MirroredTrack {
+                label: relay.label.clone(),
+                health: relay.health.clone(),
+                sender: relay
+                    .publisher
+                    .create_adaptive_sender(&subscription, selection),
+                subscription,
+            })
+            .collect();
+        Ok(MultiPublication { tracks })
+    }
+
+    fn healthy_relays(&self) -> Vec<&RelayPublisher<T>> {
+        self.relays
+            .iter()
+            .filter(|relay| relay.health.is_healthy())
+            .collect()
+    }
+}
+
+struct MirroredTrack<T: TransportProtocol> {
+    label: String,
+    health: Arc<RelayHealth>,
+    subscription: Subscription,
+    sender: AdaptiveObjectSender<T>,
+}
+
+/// One track published through [`MultiSessionPublisher::publish`]. Objects
+/// go to every relay that is still healthy; a relay that fails a send is
+/// dropped from the track, so object IDs stay aligned on the others.
+pub struct MultiPublication<T: TransportProtocol> {
+    tracks: Vec<MirroredTrack<T>>,
+}
+
+impl<T: TransportProtocol> MultiPublication<T> {
+    /// The PUBLISH result on each relay that carries the track, by label.
+    pub fn subscriptions(&self) -> Vec<(&str, &Subscription)> {
+        self.tracks
+            .iter()
+            .map(|track| (track.label.as_str(), &track.subscription))
+            .collect()
+    }
+
+    pub async fn start_group(&mut self, group_id: u64) -> anyhow::Result<()> {
+        let results = join_all(
+            self.tracks
+                .iter_mut()
+                .map(|track| track.sender.start_group(group_id)),
+        )
+        .await;
+        self.settle(results, "start group").map(|_| ())
+    }
+
+    /// Sends `payload` as the next object on every relay and returns its
+    /// object ID with the path it took.
+    pub async fn send(
+        &mut self,
+        publisher_priority: u8,
+        payload: Bytes,
+    ) -> anyhow::Result<(u64, ObjectTransport)> {
+        let results = join_all(
+            self.tracks
+                .iter_mut()
+                .map(|track| track.sender.send(publisher_priority, payload.clone())),
+        )
+        .await;
+        // Every sender numbers objects identically, so any result will do.
+        Ok(self.settle(results, "send object")?[0])
+    }
+
+    pub async fn finish_group(&mut self) -> anyhow::Result<()> {
+        let results = join_all(
+            self.tracks
+                .iter_mut()
+                .map(|track| track.sender.finish_group()),
+        )
+        .await;
+        self.settle(results, "finish group").map(|_| ())
+    }
+
+    /// Drops the tracks whose relay failed (`results` is in track order) and
+    /// keeps the successes. Fails when nothing succeeded.
+    fn settle<R>(
+        &mut self,
+        results: Vec<anyhow::Result<R>>,
+        operation: &str,
+    ) -> anyhow::Result<Vec<R>> {
+        let mut succeeded = Vec::with_capacity(results.len());
+        let mut results = results.into_iter();
+        self.tracks.retain(|track| match results.next() {
+            Some(Ok(value)) => {
+                succeeded.push(value);
+                true
+            }
+            Some(Err(error)) => {
+                track.health.record_failure(&track.label, &error);
+                false
+            }
+            None => true,
+        });
+        if succeeded.is_empty() {
+            bail!("{operation} failed on every relay")
+        }
+        Ok(succeeded)
+    }
+}
+
+/// Records failures on `relays` (paired with `results` by position) and keeps
+/// the successes. Fails when nothing succeeded.
+fn settle<'r, T: TransportProtocol, R>(
+    relays: &[&'r RelayPublisher<T>],
+    results: Vec<anyhow::Result<R>>,
+    operation: &str,
+) -> anyhow::Result<Vec<(&'r RelayPublisher<T>, R)>> {
+    let mut accepted = Vec::with_capacity(results.len());
+    let mut errors = Vec::new();
+    for (relay, result) in relays.iter().zip(results) {
+        match result {
+            Ok(value) => accepted.push((*relay, value)),
+            Err(error) => {
+                relay.health.record_failure(&relay.label, &error);
+                errors.push(format!("{}: {error}", relay.label));
+            }
+        }
+    }
+    if accepted.is_empty() {
+        if errors.is_empty() {
+            bail!("{operation}: no healthy relay")
+        }
+        bail!("{operation} failed on every relay ({})", errors.join(", "))
+    }
+    Ok(accepted)
+}
+
+/// Drives `futures` concurrently on the current task and returns their
+/// outputs in order.
+pub(crate) async fn join_all<F: Future>(futures: impl IntoIterator<Item = F>) -> Vec<F::Output> {
+    let mut futures: Vec<_> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+    std::future::poll_fn(|cx| {
+        let mut pending = false;
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_some() {
+                continue;
+            }
+            match future.as_mut().poll(cx) {
+                Poll::Ready(value) => *output = Some(value),
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+    .await;
+    outputs.into_iter().map(Option::unwrap).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn join_all_runs_futures_concurrently_and_keeps_order() {
+        // Arrange
+        let (first_tx, first_rx) = tokio::sync::oneshot::channel::<u32>();
+        let first = async move { first_rx.await.unwrap() };
+        let second = async move {
+            // Completes only if `first` is already being polled alongside.
+            first_tx.send(1).unwrap();
+            2
+        };
+        let futures: Vec<Pin<Box<dyn Future<Output = u32>>>> =
+            vec![Box::pin(first), Box::pin(second)];
+
+        // Act
+        let outputs = join_all(futures).await;
+
+        // Assert
+        assert_eq!(outputs, vec![1, 2]);
+    }
+
+    #[test]
+    fn failure_marks_relay_unhealthy_and_keeps_last_error() {
+        // Arrange
+        let health = RelayHealth::default();
+
+        // Act
+        health.record_failure("relay-a", &anyhow::anyhow!("first"));
+        health.record_failure("relay-a", &anyhow::anyhow!("connection lost"));
+
+        // Assert
+        assert_eq!(
+            health.status("relay-a"),
+            RelayStatus {
+                label: "relay-a".to_string(),
+                healthy: false,
+                failures: 2,
+                last_error: Some("connection lost".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn new_relay_is_healthy() {
+        // Arrange
+        let health = RelayHealth::default();
+
+        // Act
+        let status = health.status("relay-b");
+
+        // Assert
+        assert!(status.healthy);
+        assert_eq!(status.failures, 0);
+        assert_eq!(status.last_error, None);
+    }
+}
@@ -0,0 +1,80 @@
+use std::{sync::Arc, time::Duration};
+
+/// Counts subgroup streams this endpoint has opened on a session and not yet
+/// dropped, so a GOAWAY drain can wait for in-flight groups to finish.
+#[derive(Debug)]
+pub(crate) struct OpenStreamTracker {
+    count: Arc<tokio::sync::watch::Sender<usize>>,
+}
+
+impl Default for OpenStreamTracker {
+    fn default() -> Self {
+        Self {
+            count: Arc::new(tokio::sync::watch::Sender::new(0)),
+        }
+    }
+}
+
+impl OpenStreamTracker {
+    /// Registers one open stream; it counts until the guard is dropped.
+    pub(crate) fn track(&self) -> OpenStreamGuard {
+        self.count.send_modify(|count| *count += 1);
+        OpenStreamGuard {
+            count: self.count.clone(),
+        }
+    }
+
+    /// Waits until no tracked stream is open. Returns `false` if streams were
+    /// still open when `timeout` elapsed.
+    pub(crate) async fn wait_until_idle(&self, timeout: Duration) -> bool {
+        let mut receiver = self.count.subscribe();
+        tokio::time::timeout(timeout, receiver.wait_for(|count| *count == 0))
+            .await
+            .is_ok_and(|result| result.is_ok())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct OpenStreamGuard {
+    count: Arc<tokio::sync::watch::Sender<usize>>,
+}
+
+impl Drop for OpenStreamGuard {
+    fn drop(&mut self) {
+        self.count
+            .send_modify(|count| *count = count.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::OpenStreamTracker;
+
+    #[tokio::test]
+    async fn wait_until_idle_returns_once_every_guard_is_dropped() {
+        // Arrange
+        let tracker = OpenStreamTracker::default();
+        let guard = tracker.track();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(guard);
+        });
+        // Act
+        let idle = tracker.wait_until_idle(Duration::from_secs(5)).await;
+        // Assert
+        assert!(idle);
+    }
+
+    #[tokio::test]
+    async fn wait_until_idle_times_out_while_stream_is_open() {
+        // Arrange
+        let tracker = OpenStreamTracker::default();
+        let _guard = tracker.track();
+        // Act
+        let idle = tracker.wait_until_idle(Duration::from_millis(10)).await;
+        // Assert
+        assert!(!idle);
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::Mutex;
+
+use tokio::sync::watch;
+
+/// The Maximum Request ID the peer granted this endpoint, from SETUP and
+/// later MAX_REQUEST_ID. A request whose ID is not below it waits for a
+/// raise instead of being sent, which the peer would treat as
+/// TOO_MANY_REQUESTS.
+#[derive(Debug)]
+pub(crate) struct PeerRequestLimit {
+    maximum: watch::Sender<u64>,
+    /// The maximum REQUESTS_BLOCKED was last sent for, so waiters on the
+    /// same limit send it only once.
+    reported_blocked: Mutex<Option<u64>>,
+}
+
+impl PeerRequestLimit {
+    pub(crate) fn new(initial: u64) -> Self {
+        Self {
+            maximum: watch::Sender::new(initial),
+            reported_blocked: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn maximum(&self) -> u64 {
+        *self.maximum.borrow()
+    }
+
+    /// Applies MAX_REQUEST_ID. Returns `false` for a value that does not
+    /// raise the limit, which the peer must never send.
+    pub(crate) fn raise(&self, maximum_request_id: u64) -> bool {
+        self.maximum.send_if_modified(|maximum| {
+            if maximum_request_id <= *maximum {
+                return false;
+            }
+            *maximum = maximum_request_id;
+            true
+        })
+    }
+
+    /// The maximum to put in REQUESTS_BLOCKED for `request_id`, or `None`
+    /// when it is within the limit or the peer was already told.
+    pub(crate) fn report_blocked(&self, request_id: u64) -> Option<u64> {
+        let maximum = self.maximum();
+        if request_id < maximum {
+            return None;
+        }
+        let mut reported = self
+            .reported_blocked
+            .lock()
+            .expect("peer request limit poisoned");
+        if *reported == Some(maximum) {
+            return None;
+        }
+        *reported = Some(maximum);
+        Some(maximum)
+    }
+
+    /// Resolves once `request_id` is below the peer's maximum.
+    pub(crate) async fn wait_for(&self, request_id: u64) {
+        let mut maximum = self.maximum.subscribe();
+        // The sender lives as long as `self`, so this cannot fail.
+        let _ = maximum.wait_for(|maximum| request_id < *maximum).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::PeerRequestLimit;
+
+    #[test]
+    fn report_blocked_once_per_maximum() {
+        // Arrange
+        let limit = PeerRequestLimit::new(4);
+        // Act
+        let within = limit.report_blocked(3);
+        let first = limit.report_blocked(5);
+        let repeated = limit.report_blocked(7);
+        limit.raise(6);
+        let after_raise = limit.report_blocked(7);
+        // Assert
+        assert_eq!(within, None);
+        assert_eq!(first, Some(4));
+        assert_eq!(repeated, None);
+        assert_eq!(after_raise, Some(6));
+    }
+
+    #[test]
+    fn raise_ignores_lower_maximum() {
+        // Arrange
+        let limit = PeerRequestLimit::new(10);
+        // Act
+        let lowered = limit.raise(8);
+        let raised = limit.raise(12);
+        // Assert
+        assert!(!lowered);
+        assert!(raised);
+        assert_eq!(limit.maximum(), 12);
+    }
+
+    #[tokio::test]
+    async fn wait_for_resumes_once_raised_above_request_id() {
+        // Arrange
+        let limit = PeerRequestLimit::new(3);
+        let waiting = limit.wait_for(5);
+        tokio::pin!(waiting);
+        // Act
+        limit.raise(5);
+        let still_blocked = tokio::time::timeout(Duration::from_millis(20), &mut waiting).await;
+        limit.raise(6);
+        let resumed = tokio::time::timeout(Duration::from_secs(1), &mut waiting).await;
+        // Assert
+        assert!(still_blocked.is_err());
+        assert!(resumed.is_ok());
+    }
+}
@@ -1,17 +1,17 @@
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
 
 use anyhow::bail;
 
 use crate::{
-    DatagramSender,
+    AdaptiveObjectSender, DatagramSender, TransportSelection,
     modules::{
         moqt::{
             control_plane::{
                 control_messages::{
                     control_message_type::ControlMessageType,
                     messages::{
-                        publish::Publish, publish_namespace::PublishNamespace,
+                        publish::Publish, publish_done::PublishDone,
+                        publish_namespace::PublishNamespace,
                         publish_namespace_done::PublishNamespaceDone,
                     },
                 },
@@ -40,17 +40,11 @@ pub struct Publisher<T: TransportProtocol> {
     pub(crate) session: Arc<SessionContext<T>>,
 }
 
-static NEXT_TRACK_ALIAS: AtomicU64 = AtomicU64::new(0);
-
 impl<T: TransportProtocol> Publisher<T> {
-    fn next_track_alias() -> u64 {
-        NEXT_TRACK_ALIAS.fetch_add(1, Ordering::SeqCst)
-    }
-
     pub async fn publish_namespace(&self, namespace: String) -> anyhow::Result<()> {
         let vec_namespace: Vec<String> = namespace.split('/').map(|s| s.to_string()).collect();
         let (sender, receiver) = tokio::sync::oneshot::channel::<ResponseMessage>();
-        let request_id = self.session.get_request_id();
+        let request_id = self.session.get_request_id().await?;
         let _registered_sender = self.session.register_response_sender(
             request_id,
             sender,
@@ -102,17 +96,60 @@ impl<T: TransportProtocol> Publisher<T> {
         Ok(())
     }
 
+    /// Ends the subscription opened by `request_id` (a SUBSCRIBE, or this
+    /// endpoint's PUBLISH). `stream_count` is the number of data streams
+    /// opened for it. Fire-and-forget: PUBLISH_DONE has no response.
+    pub async fn publish_done(
+        &self,
+        request_id: u64,
+        status_code: u64,
+        stream_count: u64,
+        reason_phrase: String,
+    ) -> anyhow::Result<()> {
+        let publish_done = PublishDone::new(request_id, status_code, stream_count, reason_phrase);
+        self.session
+            .send_stream
+            .send(ControlMessageType::PublishDone, publish_done.encode())
+            .await?;
+        Ok(())
+    }
+
     pub async fn publish(
         &self,
         track_namespace: String,
         track_name: String,
         option: PublishOption,
     ) -> anyhow::Result<Subscription> {
-        let track_alias = Self::next_track_alias();
+        let track_alias = option
+            .track_alias
+            .unwrap_or_else(|| self.session.get_track_alias());
+        self.session.claim_track_alias(track_alias)?;
         tracing::debug!("track alias: {}", track_alias);
+        let result = self
+            .send_publish(track_namespace, track_name, track_alias, option)
+            .await;
+        if result.is_err() {
+            self.session.release_track_alias(track_alias);
+        }
+        result
+    }
+
+    /// Returns `track_alias` to the session so a later PUBLISH or
+    /// SUBSCRIBE_OK can reuse it. Call once the publication has ended.
+    pub fn release_track_alias(&self, track_alias: u64) {
+        self.session.release_track_alias(track_alias);
+    }
+
+    async fn send_publish(
+        &self,
+        track_namespace: String,
+        track_name: String,
+        track_alias: u64,
+        option: PublishOption,
+    ) -> anyhow::Result<Subscription> {
         let vec_namespace = track_namespace.split('/').map(|s| s.to_string()).collect();
         let (sender, receiver) = tokio::sync::oneshot::channel::<ResponseMessage>();
-        let request_id = self.session.get_request_id();
+        let request_id = self.session.get_request_id().await?;
         // A late PUBLISH_OK is discarded: the peer keeps subscription state
         // but receives no objects; see LateResponseAction::Discard for why
         // no PUBLISH_DONE is sent yet.
@@ -176,6 +213,20 @@ impl<T: TransportProtocol> Publisher<T> {
         DatagramSender::new(subscription.track_alias(), self.session.clone())
     }
 
+    /// Creates a sender that routes each object to a datagram or the group's
+    /// subgroup stream according to `selection`.
+    pub fn create_adaptive_sender(
+        &self,
+        subscription: &Subscription,
+        selection: TransportSelection,
+    ) -> AdaptiveObjectSender<T> {
+        AdaptiveObjectSender::new(
+            selection,
+            self.create_datagram(subscription),
+            self.create_stream(subscription),
+        )
+    }
+
     pub async fn create_fetch_stream(&self, request_id: u64) -> anyhow::Result<FetchDataSender<T>> {
         let send_stream = self.session.transport_connection.open_uni().await?;
         FetchDataSender::new(send_stream, FetchHeader::new(request_id)).await
@@ -0,0 +1,73 @@
+use std::sync::Mutex;
+
+/// The Maximum Request ID this endpoint granted its peer. It is raised by a
+/// full window once the peer's Request IDs come within half a window of the
+/// limit, so a busy peer never has to stop and send REQUESTS_BLOCKED.
+#[derive(Debug)]
+pub(crate) struct RequestIdGrant {
+    granted: Mutex<u64>,
+    window: u64,
+}
+
+impl RequestIdGrant {
+    /// `initial` is the MAX_REQUEST_ID sent in SETUP and doubles as the window.
+    pub(crate) fn new(initial: u64) -> Self {
+        Self {
+            granted: Mutex::new(initial),
+            window: initial.max(2),
+        }
+    }
+
+    /// Records a Request ID used by the peer. Returns the new Maximum Request
+    /// ID to advertise when the peer is getting close to the current one.
+    pub(crate) fn observe(&self, request_id: u64) -> Option<u64> {
+        let mut granted = self.granted.lock().expect("request id grant poisoned");
+        if request_id.saturating_add(self.window / 2) < *granted {
+            return None;
+        }
+        *granted = request_id.saturating_add(self.window);
+        Some(*granted)
+    }
+
+    /// Handles REQUESTS_BLOCKED. A stale `maximum_request_id` means a raise is
+    /// already on its way, so nothing new is granted.
+    pub(crate) fn unblock(&self, maximum_request_id: u64) -> Option<u64> {
+        let mut granted = self.granted.lock().expect("request id grant poisoned");
+        if maximum_request_id < *granted {
+            return None;
+        }
+        *granted = maximum_request_id.saturating_add(self.window);
+        Some(*granted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RequestIdGrant;
+
+    #[test]
+    fn observe_raises_once_half_the_window_is_used() {
+        // Arrange
+        let grant = RequestIdGrant::new(100);
+        // Act
+        let early = grant.observe(48);
+        let raised = grant.observe(50);
+        let after_raise = grant.observe(52);
+        // Assert
+        assert_eq!(early, None);
+        assert_eq!(raised, Some(150));
+        assert_eq!(after_raise, None);
+    }
+
+    #[test]
+    fn unblock_ignores_stale_maximum() {
+        // Arrange
+        let grant = RequestIdGrant::new(100);
+        // Act
+        let stale = grant.unblock(80);
+        let blocked = grant.unblock(100);
+        // Assert
+        assert_eq!(stale, None);
+        assert_eq!(blocked, Some(200));
+    }
+}
@@ -0,0 +1,472 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+use tokio::{
+    sync::mpsc,
+    task::{JoinHandle, JoinSet},
+};
+
+use crate::{
+    DataReceiver, ObjectDatagram, StreamDataReceiver, SubgroupStreamObject, SubscribeOption,
+    Subscriber, Subscription,
+    modules::moqt::{domains::multi_session_publisher::join_all, protocol::TransportProtocol},
+};
+
+/// Groups behind the newest one whose object IDs are remembered for
+/// duplicate suppression.
+const DEDUP_GROUP_WINDOW: u64 = 8;
+
+/// An object delivered by [`ResilientSubscription`], in the form the first
+/// relay to deliver it used.
+#[derive(Debug, Clone)]
+pub enum ResilientObject {
+    Subgroup(SubgroupStreamObject),
+    Datagram(ObjectDatagram),
+}
+
+impl ResilientObject {
+    pub fn group_id(&self) -> u64 {
+        match self {
+            Self::Subgroup(object) => object.group_id,
+            Self::Datagram(datagram) => datagram.group_id,
+        }
+    }
+
+    pub fn object_id(&self) -> u64 {
+        match self {
+            Self::Subgroup(object) => object.object_id,
+            // Datagram types without an Object ID field carry object 0.
+            Self::Datagram(datagram) => datagram.field.object_id().unwrap_or(0),
+        }
+    }
+}
+
+/// Delivery health of one relay path as seen by [`ResilientSubscription`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathStatus {
+    pub label: String,
+    /// The path delivered nothing for `stall_timeout` while another path
+    /// kept delivering.
+    pub stalled: bool,
+    /// The subscription or its data receiver on this path failed or ended.
+    pub ended: bool,
+    /// Objects received on this path, duplicates included.
+    pub objects: u64,
+    /// Objects this path delivered before any other path.
+    pub first_deliveries: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug)]
+struct PathHealth {
+    subscribed_at: Instant,
+    last_object_at: Mutex<Option<Instant>>,
+    ended: AtomicBool,
+    objects: AtomicU64,
+    first_deliveries: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl PathHealth {
+    fn new() -> Self {
+        Self {
+            subscribed_at: Instant::now(),
+            last_object_at: Mutex::new(None),
+            ended: AtomicBool::new(false),
+            objects: AtomicU64::new(0),
+            first_deliveries: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+        }
+    }
+
+    fn record_object(&self) {
+        *self.last_object_at.lock().unwrap() = Some(Instant::now());
+        self.objects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_end(&self, label: &str, error: Option<&anyhow::Error>) {
+        self.ended.store(true, Ordering::Relaxed);
+        match error {
+            Some(error) => {
+                *self.last_error.lock().unwrap() = Some(error.to_string());
+                tracing::warn!(relay = %label, %error, "subscription path failed");
+            }
+            None => tracing::info!(relay = %label, "subscription path ended"),
+        }
+    }
+
+    /// Latest activity on this path: its last object, or the subscription
+    /// itself when nothing arrived yet.
+    fn last_activity(&self) -> Instant {
+        self.last_object_at
+            .lock()
+            .unwrap()
+            .unwrap_or(self.subscribed_at)
+    }
+
+    fn status(&self, label: &str, stalled: bool) -> PathStatus {
+        PathStatus {
+            label: label.to_string(),
+            stalled,
+            ended: self.ended.load(Ordering::Relaxed),
+            objects: self.objects.load(Ordering::Relaxed),
+            first_deliveries: self.first_deliveries.load(Ordering::Relaxed),
+            last_error: self.last_error.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// Subscribes to the same track through several relay sessions at once and
+/// merges what they deliver into one receiver, the subscriber-side
+/// counterpart of [`MultiSessionPublisher`](crate::MultiSessionPublisher).
+///
+/// Every path delivers concurrently and the first copy of each
+/// `(group, object)` wins, so a relay that stalls or drops out costs no
+/// failover time: the other path was already delivering.
+pub struct ResilientSubscriber<T: TransportProtocol> {
+    relays: Vec<(String, Subscriber<T>)>,
+    stall_timeout: Duration,
+}
+
+impl<T: TransportProtocol> ResilientSubscriber<T> {
+    /// `relays` pairs a label (used in logs and [`PathStatus`]) with the
+    /// subscriber of a session to that relay. A path is reported stalled
+    /// once it lags the others by `stall_timeout`.
+    pub fn new(
+        relays: impl IntoIterator<Item = (String, Subscriber<T>)>,
+        stall_timeout: Duration,
+    ) -> Self {
+        Self {
+            relays: relays.into_iter().collect(),
+            stall_timeout,
+        }
+    }
+
+    /// Sends SUBSCRIBE to every relay and starts merging the paths that
+    /// accepted. Fails only when no relay accepted.
+    pub async fn subscribe(
+        &mut self,
+        track_namespace: String,
+        track_name: String,
+        option: SubscribeOption,
+    ) -> anyhow::Result<ResilientSubscription<T>> {
+        let results = join_all(self.relays.iter_mut().map(|(_, subscriber)| {
+            subscriber.subscribe(track_namespace.clone(), track_name.clone(), option.clone())
+        }))
+        .await;
+
+        let (sender, receiver) = mpsc::channel(256);
+        let mut paths = Vec::with_capacity(results.len());
+        let mut errors = Vec::new();
+        for ((label, subscriber), result) in self.relays.iter().zip(results) {
+            let health = Arc::new(PathHealth::new());
+            let subscriber = Subscriber {
+                session: subscriber.session.clone(),
+            };
+            let (subscription, task) = match result {
+                Ok(subscription) => {
+                    let task = tokio::spawn(run_path(
+                        label.clone(),
+                        Subscriber {
+                            session: subscriber.session.clone(),
+                        },
+                        subscription.clone(),
+                        paths.len(),
+                        sender.clone(),
+                        health.clone(),
+                    ));
+                    (Some(subscription), Some(task))
+                }
+                Err(error) => {
+                    health.record_end(label, Some(&error));
+                    errors.push(format!("{label}: {error}"));
+                    (None, None)
+                }
+            };
+            paths.push(SubscriptionPath {
+                label: label.clone(),
+                subscriber,
+                subscription,
+                health,
+                task,
+            });
+        }
+        if paths.iter().all(|path| path.subscription.is_none()) {
+            bail!("SUBSCRIBE failed on every relay ({})", errors.join(", "))
+        }
+        Ok(ResilientSubscription {
+            paths,
+            receiver,
+            deduplicator: ObjectDeduplicator::new(DEDUP_GROUP_WINDOW),
+            stall_timeout: self.stall_timeout,
+        })
+    }
+}
+
+struct SubscriptionPath<T: TransportProtocol> {
+    label: String,
+    subscriber: Subscriber<T>,
+    subscription: Option<Subscription>,
+    health: Arc<PathHealth>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// One track subscribed through [`ResilientSubscriber::subscribe`].
+/// Dropping it stops reading from every path; call
+/// [`unsubscribe`](Self::unsubscribe) to also tell the relays.
+pub struct ResilientSubscription<T: TransportProtocol> {
+    paths: Vec<SubscriptionPath<T>>,
+    receiver: mpsc::Receiver<(usize, ResilientObject)>,
+    deduplicator: ObjectDeduplicator,
+    stall_timeout: Duration,
+}
+
+impl<T: TransportProtocol> ResilientSubscription<T> {
+    /// The SUBSCRIBE_OK on each relay that accepted, by label.
+    pub fn subscriptions(&self) -> Vec<(&str, &Subscription)> {
+        self.paths
+            .iter()
+            .filter_map(|path| Some((path.label.as_str(), path.subscription.as_ref()?)))
+            .collect()
+    }
+
+    /// Returns the next object not yet delivered by another path, or `None`
+    /// once every path has ended.
+    pub async fn receive(&mut self) -> Option<ResilientObject> {
+        loop {
+            let (index, object) = self.receiver.recv().await?;
+            if self
+                .deduplicator
+                .first_copy(object.group_id(), object.object_id())
+            {
+                self.paths[index]
+                    .health
+                    .first_deliveries
+                    .fetch_add(1, Ordering::Relaxed);
+                return Some(object);
+            }
+        }
+    }
+
+    pub fn path_status(&self) -> Vec<PathStatus> {
+        let latest = self
+            .paths
+            .iter()
+            .map(|path| path.health.last_activity())
+            .max();
+        self.paths
+            .iter()
+            .map(|path| {
+                let stalled = latest.is_some_and(|latest| {
+                    is_stalled(path.health.last_activity(), latest, self.stall_timeout)
+                });
+                path.health.status(&path.label, stalled)
+            })
+            .collect()
+    }
+
+    /// Sends UNSUBSCRIBE on every path that accepted and stops reading.
+    pub async fn unsubscribe(mut self) -> anyhow::Result<()> {
+        let paths = std::mem::take(&mut self.paths);
+        let results = join_all(paths.iter().filter_map(|path| {
+            let subscription = path.subscription.as_ref()?;
+            Some(path.subscriber.unsubscribe(subscription.request_id()))
+        }))
+        .await;
+        for path in &paths {
+            if let Some(task) = &path.task {
+                task.abort();
+            }
+        }
+        results.into_iter().collect()
+    }
+}
+
+impl<T: TransportProtocol> Drop for ResilientSubscription<T> {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            if let Some(task) = &path.task {
+                task.abort();
+            }
+        }
+    }
+}
+
+/// A path lags when another path was active `stall_timeout` after it.
+fn is_stalled(last_activity: Instant, latest_activity: Instant, stall_timeout: Duration) -> bool {
+    latest_activity.saturating_duration_since(last_activity) > stall_timeout
+}
+
+/// Reads one relay path and forwards every object, tagged with `index`.
+async fn run_path<T: TransportProtocol>(
+    label: String,
+    mut subscriber: Subscriber<T>,
+    subscription: Subscription,
+    index: usize,
+    sender: mpsc::Sender<(usize, ResilientObject)>,
+    health: Arc<PathHealth>,
+) {
+    let receiver = match subscriber.accept_data_receiver(&subscription).await {
+        Ok(receiver) => receiver,
+        Err(error) => {
+            health.record_end(&label, Some(&error));
+            return;
+        }
+    };
+    let error = match receiver {
+        DataReceiver::Datagram(mut receiver) => loop {
+            match receiver.receive().await {
+                Ok(datagram) => {
+                    health.record_object();
+                    if sender
+                        .send((index, ResilientObject::Datagram(datagram)))
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+                Err(error) => break Some(error),
+            }
+        },
+        DataReceiver::Stream(mut factory) => {
+            let mut streams = JoinSet::new();
+            loop {
+                tokio::select! {
+                    next = factory.next() => match next {
+                        Ok(stream) => {
+                            streams.spawn(read_stream(
+                                stream,
+                                index,
+                                sender.clone(),
+                                health.clone(),
+                            ));
+                        }
+                        Err(error) => break Some(error),
+                    },
+                    Some(_) = streams.join_next() => {}
+                }
+            }
+        }
+    };
+    health.record_end(&label, error.as_ref());
+}
+
+async fn read_stream<T: TransportProtocol>(
+    mut stream: StreamDataReceiver<T>,
+    index: usize,
+    sender: mpsc::Sender<(usize, ResilientObject)>,
+    health: Arc<PathHealth>,
+) {
+    loop {
+        match stream.receive_object().await {
+            Ok(Some(object)) => {
+                health.record_object();
+                if sender
+                    .send((index, ResilientObject::Subgroup(object)))
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            Ok(None) => return,
+            Err(error) => {
+                tracing::debug!(?error, "subgroup stream ended with error");
+                return;
+            }
+        }
+    }
+}
+
+/// Remembers which `(group, object)` pairs were already delivered, for the
+/// newest `group_window` groups. Objects of older groups are treated as
+/// duplicates: they are too late to be useful and can no longer be told
+/// apart from one.
+struct ObjectDeduplicator {
+    seen: BTreeMap<u64, HashSet<u64>>,
+    group_window: u64,
+}
+
+impl ObjectDeduplicator {
+    fn new(group_window: u64) -> Self {
+        Self {
+            seen: BTreeMap::new(),
+            group_window,
+        }
+    }
+
+    /// Returns true the first time `(group_id, object_id)` is seen.
+    fn first_copy(&mut self, group_id: u64, object_id: u64) -> bool {
+        let newest = self.seen.keys().next_back().copied().unwrap_or(group_id);
+        if group_id.saturating_add(self.group_window) <= newest {
+            return false;
+        }
+        let first = self.seen.entry(group_id).or_default().insert(object_id);
+        let oldest_kept = group_id.max(newest).saturating_sub(self.group_window - 1);
+        self.seen = self.seen.split_off(&oldest_kept);
+        first
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deduplicator_passes_only_the_first_copy() {
+        // Arrange
+        let mut deduplicator = ObjectDeduplicator::new(4);
+
+        // Act
+        let from_first_relay = deduplicator.first_copy(10, 0);
+        let from_second_relay = deduplicator.first_copy(10, 0);
+        let next_object = deduplicator.first_copy(10, 1);
+
+        // Assert
+        assert!(from_first_relay);
+        assert!(!from_second_relay);
+        assert!(next_object);
+    }
+
+    #[test]
+    fn deduplicator_forgets_groups_outside_the_window() {
+        // Arrange
+        let mut deduplicator = ObjectDeduplicator::new(2);
+        deduplicator.first_copy(1, 0);
+
+        // Act
+        deduplicator.first_copy(2, 0);
+        deduplicator.first_copy(3, 0);
+        let late_object = deduplicator.first_copy(1, 1);
+
+        // Assert
+        assert!(!late_object);
+        assert_eq!(
+            deduplicator.seen.keys().copied().collect::<Vec<_>>(),
+            [2, 3]
+        );
+    }
+
+    #[test]
+    fn path_is_stalled_only_when_another_path_kept_delivering() {
+        // Arrange
+        let start = Instant::now();
+        let timeout = Duration::from_millis(500);
+
+        // Act
+        let lagging = is_stalled(start, start + Duration::from_secs(1), timeout);
+        let keeping_up = is_stalled(start, start + Duration::from_millis(100), timeout);
+        let newest = is_stalled(start + Duration::from_secs(1), start, timeout);
+
+        // Assert
+        assert!(lagging);
+        assert!(!keeping_up);
+        assert!(!newest);
+    }
+}
@@ -1,19 +1,33 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::bail;
 use tracing::Span;
 
 use crate::Publisher;
 use crate::Subscriber;
+use crate::modules::moqt::control_plane::constants::TerminationErrorCode;
+use crate::modules::moqt::control_plane::control_message_limits::{
+    ControlMessageLimits, MessageRateWindow,
+};
+use crate::modules::moqt::control_plane::control_message_observer::ControlMessageObserver;
+use crate::modules::moqt::control_plane::control_messages::{
+    control_message_type::ControlMessageType, messages::go_away::GoAway,
+};
 use crate::modules::moqt::control_plane::enums::SessionEvent;
+use crate::modules::moqt::control_plane::version::ProtocolVersion;
 use crate::modules::moqt::data_plane::stream::stream_receiver::BiStreamReceiver;
 use crate::modules::moqt::domains::session_context::SessionContext;
 use crate::modules::moqt::protocol::TransportProtocol;
 use crate::modules::moqt::runtime::tasks::{
+    bi_stream_reject_task::BiStreamRejectTask,
     control_message_receive_task::ControlMessageReceiveTask,
     datagram_receive_task::DatagramReceiveTask, disconnect_watch_task::DisconnectWatchTask,
     uni_stream_receive_task::UniStreamReceiveTask,
 };
+use crate::modules::transport::{
+    connection_stats::ConnectionStats, transport_connection::TransportConnection,
+};
 
 pub struct Session<T: TransportProtocol> {
     inner: Arc<SessionContext<T>>,
@@ -22,7 +36,10 @@ pub struct Session<T: TransportProtocol> {
     control_message_receive_task: tokio::task::JoinHandle<()>,
     datagram_receive_task: tokio::task::JoinHandle<()>,
     uni_stream_receive_task: tokio::task::JoinHandle<()>,
+    bi_stream_reject_task: tokio::task::JoinHandle<()>,
     disconnect_watch_task: tokio::task::JoinHandle<()>,
+    /// Counts against the server's session limit until the session drops.
+    _session_slot: Option<tokio::sync::OwnedSemaphorePermit>,
 }
 
 impl<T: TransportProtocol> Session<T> {
@@ -30,6 +47,7 @@ impl<T: TransportProtocol> Session<T> {
         receive_stream: BiStreamReceiver<T>,
         inner: SessionContext<T>,
         event_receiver: tokio::sync::mpsc::UnboundedReceiver<SessionEvent<T>>,
+        control_message_limits: ControlMessageLimits,
     ) -> Self {
         let inner = Arc::new(inner);
         let parent_span = Span::current();
@@ -42,17 +60,21 @@ impl<T: TransportProtocol> Session<T> {
             tracing::info_span!(parent: &session_span, "data_plane.datagram_receiver");
         let uni_stream_receiver_span =
             tracing::info_span!(parent: &session_span, "data_plane.uni_stream_receiver");
+        let bi_stream_rejecter_span =
+            tracing::info_span!(parent: &session_span, "moqt.bi_stream_rejecter");
         let transport_close_watcher_span =
             tracing::info_span!(parent: &session_span, "moqt.transport.close_watcher");
 
         let control_message_receive_task = ControlMessageReceiveTask::run(
             receive_stream,
             Arc::downgrade(&inner),
+            MessageRateWindow::new(control_message_limits.max_messages_per_sec),
             control_plane_receiver_span,
         );
         let datagram_receive_task = DatagramReceiveTask::run(inner.clone(), datagram_receiver_span);
         let uni_stream_receive_task =
             UniStreamReceiveTask::run(inner.clone(), uni_stream_receiver_span);
+        let bi_stream_reject_task = BiStreamRejectTask::run(inner.clone(), bi_stream_rejecter_span);
         let disconnect_watch_task =
             DisconnectWatchTask::run(inner.clone(), transport_close_watcher_span);
 
@@ -63,7 +85,35 @@ impl<T: TransportProtocol> Session<T> {
             control_message_receive_task,
             datagram_receive_task,
             uni_stream_receive_task,
+            bi_stream_reject_task,
             disconnect_watch_task,
+            _session_slot: None,
+        }
+    }
+
+    pub(crate) fn with_session_slot(
+        mut self,
+        session_slot: Option<tokio::sync::OwnedSemaphorePermit>,
+    ) -> Self {
+        self._session_slot = session_slot;
+        self
+    }
+
+    /// MOQT draft negotiated in SETUP.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.inner.version()
+    }
+
+    /// Samples the connection's RTT, congestion window and losses.
+    pub fn connection_stats(&self) -> ConnectionStats {
+        self.inner.connection_stats()
+    }
+
+    /// Reports every control message sent or received from now on to
+    /// `observer`. A session keeps the first observer it is given.
+    pub fn set_control_message_observer(&self, observer: ControlMessageObserver) {
+        if !self.inner.send_stream.set_observer(observer) {
+            tracing::warn!("control message observer already set");
         }
     }
 
@@ -83,6 +133,44 @@ impl<T: TransportProtocol> Session<T> {
         (self.publisher(), self.subscriber())
     }
 
+    /// Sends GOAWAY and starts draining: SUBSCRIBEs received from now on are
+    /// rejected, while existing subscriptions keep delivering.
+    pub async fn go_away(&self, new_session_uri: &str) -> anyhow::Result<()> {
+        self.inner.start_draining();
+        let go_away = GoAway {
+            new_session_uri: new_session_uri.to_string(),
+        };
+        self.inner
+            .send_stream
+            .send(ControlMessageType::GoAway, go_away.encode())
+            .await?;
+        Ok(())
+    }
+
+    /// Waits until every subgroup stream opened on this session has been
+    /// dropped. Returns `false` if some were still open at `timeout`.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.inner.wait_for_open_streams(timeout).await
+    }
+
+    /// Closes the session with NO_ERROR.
+    pub fn close(&self) {
+        self.terminate(TerminationErrorCode::NoError as u32, "session closed");
+    }
+
+    /// Closes a session the endpoint cannot serve right now. Draft-14 has no
+    /// setup error or overload code, so this is INTERNAL_ERROR with `reason`
+    /// telling the peer it may retry.
+    pub fn refuse(&self, reason: &str) {
+        self.terminate(TerminationErrorCode::InternalError as u32, reason);
+    }
+
+    /// Closes the session with `error_code`, one of the draft-14 §13.1.1
+    /// session termination codes.
+    pub fn terminate(&self, error_code: u32, reason: &str) {
+        self.inner.transport_connection.close(error_code, reason);
+    }
+
     pub async fn receive_event(&self) -> anyhow::Result<SessionEvent<T>> {
         match self.event_receiver.lock().await.recv().await {
             Some(v) => Ok(v),
@@ -99,6 +187,7 @@ impl<T: TransportProtocol> Drop for Session<T> {
         self.control_message_receive_task.abort();
         self.datagram_receive_task.abort();
         self.uni_stream_receive_task.abort();
+        self.bi_stream_reject_task.abort();
         self.disconnect_watch_task.abort();
     }
 }
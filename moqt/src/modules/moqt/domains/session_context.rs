@@ -3,7 +3,7 @@ use std::{
     fmt,
     sync::{
         Arc,
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
     },
     time::Duration,
 };
@@ -13,26 +13,39 @@ use crate::{
     modules::{
         moqt::{
             control_plane::{
-                constants::TerminationErrorCode,
+                constants::{DEFAULT_MAX_REQUEST_ID, TerminationErrorCode},
                 control_messages::{
                     control_message_type::ControlMessageType,
                     messages::{
-                        publish_namespace_done::PublishNamespaceDone, unsubscribe::Unsubscribe,
+                        fetch_cancel::FetchCancel, max_request_id::MaxRequestId,
+                        publish_namespace_done::PublishNamespaceDone, request_error::RequestError,
+                        requests_blocked::RequestsBlocked, unsubscribe::Unsubscribe,
                         unsubscribe_namespace::UnsubscribeNamespace,
                     },
                 },
                 enums::{RequestId, ResponseMessage},
+                track_alias_allocator::{TrackAliasAllocator, TrackAliasInUse},
+                version::ProtocolVersion,
             },
             data_plane::stream::bi_stream_sender::BiStreamSender,
+            domains::{
+                open_stream_tracker::{OpenStreamGuard, OpenStreamTracker},
+                peer_request_limit::PeerRequestLimit,
+                request_id_grant::RequestIdGrant,
+            },
             runtime::dispatch::incoming_object::IncomingObject,
         },
         transport::{
-            transport_connection::TransportConnection, transport_send_stream::TransportSendError,
+            connection_stats::ConnectionStats, transport_connection::TransportConnection,
+            transport_send_stream::TransportSendError,
         },
     },
 };
 
 const CONTROL_MESSAGE_RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+/// SUBSCRIBE_ERROR INTERNAL_ERROR (draft-14 §9.9); draft-14 has no dedicated
+/// code for a session that is going away.
+const SUBSCRIBE_ERROR_INTERNAL_ERROR: u64 = 0x0;
 
 /// Returned by [`SessionContext::await_response`] when the peer does
 /// not answer in time. Callers map this to a per-request failure (e.g.
@@ -53,11 +66,12 @@ impl std::error::Error for RequestTimeoutError {}
 /// a withdrawal and are always discarded.
 #[derive(Debug)]
 pub(crate) enum LateResponseAction {
-    /// Discard the late response without a withdrawal message.
+    /// Discard the late response without a withdrawal message. TRACK_STATUS
+    /// needs nothing more: answering it creates no state on the peer.
     ///
-    /// FIXME: PUBLISH and FETCH should withdraw with PUBLISH_DONE /
-    /// FETCH_CANCEL, but decoding those messages is still `todo!()`, so
-    /// sending them would crash a peer running this crate.
+    /// FIXME: PUBLISH should withdraw with PUBLISH_DONE, but decoding that
+    /// message is still `todo!()`, so sending it would crash a peer running
+    /// this crate.
     Discard,
     /// A late SUBSCRIBE_OK established a subscription nobody consumes.
     Unsubscribe,
@@ -65,6 +79,8 @@ pub(crate) enum LateResponseAction {
     UnsubscribeNamespace { namespace: Vec<String> },
     /// A late PUBLISH_NAMESPACE_OK accepted an announcement we gave up on.
     PublishNamespaceDone { namespace: Vec<String> },
+    /// A late FETCH_OK started a fetch whose objects nobody will read.
+    FetchCancel,
 }
 
 /// A request awaiting its response on this session. Kept in `sender_map`
@@ -82,8 +98,19 @@ pub(crate) enum InflightRequest {
 pub(crate) struct SessionContext<T: TransportProtocol> {
     pub(crate) transport_connection: T::Connection,
     pub(crate) send_stream: BiStreamSender<T>,
+    /// Draft negotiated in SETUP.
+    version: ProtocolVersion,
     request_id: AtomicU64,
-    track_alias: AtomicU64,
+    /// Maximum Request ID granted to the peer, raised as it is used up.
+    request_id_grant: RequestIdGrant,
+    /// Maximum Request ID the peer granted us; requests past it wait.
+    peer_request_limit: PeerRequestLimit,
+    track_aliases: std::sync::Mutex<TrackAliasAllocator>,
+    /// Set once this endpoint has sent GOAWAY; new SUBSCRIBEs are rejected.
+    draining: AtomicBool,
+    open_streams: OpenStreamTracker,
+    /// Datagrams the transport refused to send.
+    dropped_datagrams: AtomicU64,
     pub(crate) event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent<T>>,
     pub(crate) sender_map: std::sync::Mutex<HashMap<RequestId, InflightRequest>>,
     pub(crate) receiver_map:
@@ -144,14 +171,22 @@ impl<T: TransportProtocol> SessionContext<T> {
     pub(crate) fn new(
         transport_connection: T::Connection,
         send_stream: BiStreamSender<T>,
+        version: ProtocolVersion,
         request_id: AtomicU64,
+        peer_max_request_id: u64,
         event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent<T>>,
     ) -> Self {
         Self {
             transport_connection,
             send_stream,
+            version,
             request_id,
-            track_alias: AtomicU64::new(0),
+            request_id_grant: RequestIdGrant::new(DEFAULT_MAX_REQUEST_ID),
+            peer_request_limit: PeerRequestLimit::new(peer_max_request_id),
+            track_aliases: std::sync::Mutex::new(TrackAliasAllocator::default()),
+            draining: AtomicBool::new(false),
+            open_streams: OpenStreamTracker::default(),
+            dropped_datagrams: AtomicU64::new(0),
             event_sender,
             sender_map: std::sync::Mutex::new(HashMap::new()),
             receiver_map: tokio::sync::Mutex::new(HashMap::new()),
@@ -161,19 +196,174 @@ impl<T: TransportProtocol> SessionContext<T> {
         }
     }
 
-    pub(crate) fn get_request_id(&self) -> u64 {
-        let id = self.request_id.load(Ordering::SeqCst);
+    pub(crate) fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Allocates the next Request ID. When it is not below the peer's
+    /// Maximum Request ID, sends REQUESTS_BLOCKED and waits for
+    /// MAX_REQUEST_ID to raise it, failing with [`RequestTimeoutError`] if
+    /// the peer does not within the response timeout.
+    pub(crate) async fn get_request_id(&self) -> anyhow::Result<u64> {
+        let id = self.request_id.fetch_add(2, Ordering::SeqCst);
         tracing::debug!("request_id: {}", id);
-        self.request_id.fetch_add(2, Ordering::SeqCst);
-        id
+        if let Some(maximum_request_id) = self.peer_request_limit.report_blocked(id) {
+            self.send_requests_blocked(maximum_request_id).await;
+        }
+        tokio::time::timeout(
+            CONTROL_MESSAGE_RESPONSE_TIMEOUT,
+            self.peer_request_limit.wait_for(id),
+        )
+        .await
+        .map_err(|_| {
+            tracing::warn!(request_id = id, "Peer did not raise Maximum Request ID");
+            RequestTimeoutError
+        })?;
+        Ok(id)
     }
 
     pub(crate) fn get_track_alias(&self) -> u64 {
-        let track_alias = self.track_alias.fetch_add(1, Ordering::SeqCst);
+        let track_alias = self
+            .track_aliases
+            .lock()
+            .expect("track_aliases poisoned")
+            .allocate();
         tracing::debug!("track_alias: {}", track_alias);
         track_alias
     }
 
+    /// Binds `track_alias` to the PUBLISH or SUBSCRIBE_OK announcing it; see
+    /// `TrackAliasAllocator::claim`.
+    pub(crate) fn claim_track_alias(&self, track_alias: u64) -> Result<(), TrackAliasInUse> {
+        self.track_aliases
+            .lock()
+            .expect("track_aliases poisoned")
+            .claim(track_alias)
+    }
+
+    pub(crate) fn release_track_alias(&self, track_alias: u64) {
+        self.track_aliases
+            .lock()
+            .expect("track_aliases poisoned")
+            .release(track_alias);
+    }
+
+    pub(crate) fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Sends `bytes` as a datagram, counting it as dropped if the transport
+    /// refuses it.
+    pub(crate) fn send_datagram(&self, bytes: bytes::BytesMut) -> anyhow::Result<()> {
+        let result = self.transport_connection.send_datagram(bytes);
+        if result.is_err() {
+            self.dropped_datagrams.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    pub(crate) fn connection_stats(&self) -> ConnectionStats {
+        ConnectionStats {
+            dropped_datagrams: self.dropped_datagrams.load(Ordering::Relaxed),
+            ..self.transport_connection.stats()
+        }
+    }
+
+    pub(crate) fn track_open_stream(&self) -> OpenStreamGuard {
+        self.open_streams.track()
+    }
+
+    pub(crate) async fn wait_for_open_streams(&self, timeout: Duration) -> bool {
+        self.open_streams.wait_until_idle(timeout).await
+    }
+
+    /// Answers a SUBSCRIBE that arrived after this endpoint sent GOAWAY. The
+    /// request never reaches the application.
+    pub(crate) async fn reject_subscribe_while_draining(&self, request_id: RequestId) {
+        tracing::info!(request_id, "Rejecting SUBSCRIBE: session is draining");
+        let error = RequestError {
+            request_id,
+            error_code: SUBSCRIBE_ERROR_INTERNAL_ERROR,
+            reason_phrase: "session is going away".to_string(),
+        };
+        if let Err(error) = self
+            .send_stream
+            .send(ControlMessageType::SubscribeError, error.encode())
+            .await
+        {
+            tracing::warn!(
+                ?error,
+                request_id,
+                "failed to reject SUBSCRIBE while draining"
+            );
+        }
+    }
+
+    /// Raises the peer's Maximum Request ID when `request_id`, taken from a
+    /// request the peer sent, is close to it.
+    pub(crate) async fn observe_peer_request_id(&self, request_id: RequestId) {
+        if let Some(max_request_id) = self.request_id_grant.observe(request_id) {
+            self.send_max_request_id(max_request_id).await;
+        }
+    }
+
+    /// Answers REQUESTS_BLOCKED with a higher MAX_REQUEST_ID unless one is
+    /// already on its way.
+    pub(crate) async fn unblock_peer_requests(&self, maximum_request_id: u64) {
+        tracing::info!(maximum_request_id, "Peer is blocked on Maximum Request ID");
+        if let Some(max_request_id) = self.request_id_grant.unblock(maximum_request_id) {
+            self.send_max_request_id(max_request_id).await;
+        }
+    }
+
+    /// Applies the peer's MAX_REQUEST_ID, resuming requests waiting on it.
+    pub(crate) fn raise_peer_request_limit(&self, max_request_id: u64) {
+        if self.peer_request_limit.raise(max_request_id) {
+            tracing::debug!(max_request_id, "Peer raised Maximum Request ID");
+        } else {
+            tracing::warn!(
+                max_request_id,
+                current = self.peer_request_limit.maximum(),
+                "Ignoring MAX_REQUEST_ID that does not raise the limit"
+            );
+        }
+    }
+
+    async fn send_requests_blocked(&self, maximum_request_id: u64) {
+        tracing::info!(maximum_request_id, "Blocked on peer Maximum Request ID");
+        let payload = RequestsBlocked { maximum_request_id }.encode();
+        if let Err(error) = self
+            .send_stream
+            .send(ControlMessageType::RequestsBlocked, payload)
+            .await
+        {
+            tracing::warn!(
+                ?error,
+                maximum_request_id,
+                "failed to send REQUESTS_BLOCKED"
+            );
+        }
+    }
+
+    async fn send_max_request_id(&self, max_request_id: u64) {
+        tracing::info!(max_request_id, "Raising peer Maximum Request ID");
+        let payload = MaxRequestId {
+            request_id: max_request_id,
+        }
+        .encode();
+        if let Err(error) = self
+            .send_stream
+            .send(ControlMessageType::MaxSubscribeId, payload)
+            .await
+        {
+            tracing::warn!(?error, max_request_id, "failed to send MAX_REQUEST_ID");
+        }
+    }
+
     /// Inserts the sender into `sender_map` and returns a `RegisteredSender`
     /// that marks the request abandoned on drop. `on_late_response` is the
     /// withdrawal to send if a success response arrives after abandonment.
@@ -342,9 +532,24 @@ impl<T: TransportProtocol> SessionContext<T> {
                 }
                 _ => self.close_on_mismatched_late_response(request_id, &response),
             },
-            // Late error responses, and late FETCH_OK / PUBLISH_OK for
-            // requests registered with `Discard`. FETCH_OK / PUBLISH_OK for
-            // a request of another kind is not detected here: `Discard` does
+            ResponseMessage::FetchOk(_) => match &action {
+                LateResponseAction::FetchCancel => {
+                    tracing::warn!(
+                        request_id,
+                        "FETCH_OK arrived after the request was abandoned; sending FETCH_CANCEL"
+                    );
+                    self.send_stream
+                        .send(
+                            ControlMessageType::FetchCancel,
+                            FetchCancel { request_id }.encode(),
+                        )
+                        .await
+                }
+                _ => self.close_on_mismatched_late_response(request_id, &response),
+            },
+            // Late error responses, and late PUBLISH_OK / TRACK_STATUS for
+            // requests registered with `Discard`. A success response for a
+            // request of another kind is not detected here: `Discard` does
             // not record which request kind it was registered for.
             _ => Self::discard_late_response(request_id, &response),
         };
@@ -408,6 +613,5 @@ impl<T: TransportProtocol> fmt::Debug for SessionContext<T> {
 impl<T: TransportProtocol> Drop for SessionContext<T> {
     fn drop(&mut self) {
         tracing::info!("SessionContext dropped.");
-        // send goaway
     }
 }
@@ -1,28 +1,44 @@
-use std::sync::atomic::AtomicU64;
+use std::{fmt, sync::atomic::AtomicU64};
 
 use crate::{
     SessionEvent, TransportProtocol,
-    modules::moqt::{
-        control_plane::{
-            constants::{self, MOQ_TRANSPORT_VERSION},
-            control_messages::{
-                control_message_type::ControlMessageType,
-                messages::{
-                    client_setup::ClientSetup, parameters::setup_parameters::SetupParameter,
-                    server_setup::ServerSetup,
+    modules::{
+        moqt::{
+            control_plane::{
+                constants::{self, TerminationErrorCode},
+                control_messages::{
+                    control_message_type::ControlMessageType,
+                    messages::{
+                        client_setup::ClientSetup, parameters::setup_parameters::SetupParameter,
+                        server_setup::ServerSetup,
+                    },
                 },
+                version::ProtocolVersion,
             },
+            data_plane::stream::{
+                bi_stream_sender::BiStreamSender, received_message::ReceivedMessage,
+                stream_receiver::BiStreamReceiver,
+            },
+            domains::session_context::SessionContext,
         },
-        data_plane::stream::{
-            bi_stream_sender::BiStreamSender, received_message::ReceivedMessage,
-            stream_receiver::BiStreamReceiver,
-        },
-        domains::session_context::SessionContext,
+        transport::transport_connection::TransportConnection,
     },
 };
 
 pub(crate) struct SessionContextFactory;
 
+/// SETUP found no draft both endpoints support.
+#[derive(Debug)]
+struct VersionNegotiationError(String);
+
+impl fmt::Display for VersionNegotiationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "version negotiation failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for VersionNegotiationError {}
+
 impl SessionContextFactory {
     pub(crate) async fn client<T: TransportProtocol>(
         transport_connection: T::Connection,
@@ -31,12 +47,16 @@ impl SessionContextFactory {
         event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent<T>>,
     ) -> anyhow::Result<SessionContext<T>> {
         let mut send_stream = BiStreamSender::new(send_stream);
-        Self::setup_client(&mut send_stream, receive_stream).await?;
+        let (version, peer_max_request_id) = Self::setup_client(&mut send_stream, receive_stream)
+            .await
+            .inspect_err(|error| Self::close_on_version_mismatch(&transport_connection, error))?;
 
         Ok(SessionContext::new(
             transport_connection,
             send_stream,
+            version,
             AtomicU64::new(1),
+            peer_max_request_id,
             event_sender,
         ))
     }
@@ -46,32 +66,76 @@ impl SessionContextFactory {
         send_stream: T::SendStream,
         receive_stream: &mut BiStreamReceiver<T>,
         event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent<T>>,
+        setup_deadline: Option<tokio::time::Instant>,
     ) -> anyhow::Result<SessionContext<T>> {
         let mut send_stream = BiStreamSender::new(send_stream);
-        Self::setup_server(&mut send_stream, receive_stream).await?;
+        let setup = Self::setup_server(&mut send_stream, receive_stream);
+        let Some(setup) = Self::before_deadline(setup_deadline, setup).await else {
+            Self::close_on_setup_timeout(&transport_connection);
+            anyhow::bail!("CLIENT_SETUP was not received before the setup deadline");
+        };
+        let (version, peer_max_request_id) = setup
+            .inspect_err(|error| Self::close_on_version_mismatch(&transport_connection, error))?;
 
         Ok(SessionContext::new(
             transport_connection,
             send_stream,
+            version,
             AtomicU64::new(1),
+            peer_max_request_id,
             event_sender,
         ))
     }
 
+    /// Closes with VERSION_NEGOTIATION_FAILED when SETUP failed for lack of
+    /// a common draft; other SETUP failures just drop the connection.
+    fn close_on_version_mismatch<C: TransportConnection>(
+        transport_connection: &C,
+        error: &anyhow::Error,
+    ) {
+        if let Some(error) = error.downcast_ref::<VersionNegotiationError>() {
+            transport_connection.close(
+                TerminationErrorCode::VersionNegotiationFailed as u32,
+                &error.to_string(),
+            );
+        }
+    }
+
+    /// `None` when `deadline` passed before `future` finished.
+    pub(crate) async fn before_deadline<F: Future>(
+        deadline: Option<tokio::time::Instant>,
+        future: F,
+    ) -> Option<F::Output> {
+        match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+            None => Some(future.await),
+        }
+    }
+
+    /// Closes a connection that did not finish SETUP in time with
+    /// CONTROL_MESSAGE_TIMEOUT.
+    pub(crate) fn close_on_setup_timeout<C: TransportConnection>(transport_connection: &C) {
+        tracing::warn!("Closing connection that did not complete SETUP in time");
+        transport_connection.close(
+            TerminationErrorCode::ControlMessageTimeout as u32,
+            "setup timeout",
+        );
+    }
+
     async fn setup_client<T: TransportProtocol>(
         send_stream: &mut BiStreamSender<T>,
         receive_stream: &mut BiStreamReceiver<T>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<(ProtocolVersion, u64)> {
         let setup_param = SetupParameter {
             path: None,
-            max_request_id: 1000,
+            max_request_id: constants::DEFAULT_MAX_REQUEST_ID,
             authorization_token: vec![],
             max_auth_token_cache_size: None,
             authority: None,
             moq_implementation: Some("MOQ-WASM".to_string()),
         };
         let payload =
-            ClientSetup::new(vec![constants::MOQ_TRANSPORT_VERSION], setup_param).encode();
+            ClientSetup::new(ProtocolVersion::supported_wire_values(), setup_param).encode();
         send_stream
             .send(ControlMessageType::ClientSetup, payload)
             .await
@@ -95,7 +159,18 @@ impl SessionContextFactory {
                     "Received server setup. selected_version: {}",
                     server_setup.selected_version
                 );
-                Ok(())
+                let version = ProtocolVersion::accept_selected(server_setup.selected_version)
+                    .ok_or_else(|| {
+                        tracing::error!(
+                            selected_version = server_setup.selected_version,
+                            "Server selected a version that was not offered."
+                        );
+                        VersionNegotiationError(format!(
+                            "server selected unoffered version {:#x}",
+                            server_setup.selected_version
+                        ))
+                    })?;
+                Ok((version, server_setup.setup_parameters.max_request_id))
             }
             _ => {
                 tracing::error!("Protocol violation.");
@@ -107,7 +182,7 @@ impl SessionContextFactory {
     async fn setup_server<T: TransportProtocol>(
         send_stream: &mut BiStreamSender<T>,
         receive_stream: &mut BiStreamReceiver<T>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<(ProtocolVersion, u64)> {
         let received_message = match receive_stream.receive().await {
             Ok(Some(b)) => b,
             Ok(None) => {
@@ -119,12 +194,25 @@ impl SessionContextFactory {
                 anyhow::bail!("Stream failed before receiving client setup: {error}")
             }
         };
-        match received_message {
+        let (version, peer_max_request_id) = match received_message {
             ReceivedMessage::ClientSetup(client_setup) => {
                 tracing::info!(
                     "Received client setup. supported_versions: {:?}",
                     client_setup.supported_versions
                 );
+                let version = ProtocolVersion::negotiate(&client_setup.supported_versions)
+                    .ok_or_else(|| {
+                        tracing::error!(
+                            offered = ?client_setup.supported_versions,
+                            supported = ?ProtocolVersion::SUPPORTED,
+                            "No common version in client setup."
+                        );
+                        VersionNegotiationError(format!(
+                            "none of {:x?} is supported",
+                            client_setup.supported_versions
+                        ))
+                    })?;
+                (version, client_setup.setup_parameters.max_request_id)
             }
             _ => {
                 tracing::error!("Protocol violation.");
@@ -133,17 +221,18 @@ impl SessionContextFactory {
         };
         let setup_param = SetupParameter {
             path: None,
-            max_request_id: 1000,
+            max_request_id: constants::DEFAULT_MAX_REQUEST_ID,
             authorization_token: vec![],
             authority: None,
             max_auth_token_cache_size: None,
             moq_implementation: Some("MOQ-WASM".to_string()),
         };
-        let payload = ServerSetup::new(MOQ_TRANSPORT_VERSION, setup_param).encode();
-        Ok(send_stream
+        let payload = ServerSetup::new(version.into(), setup_param).encode();
+        send_stream
             .send(ControlMessageType::ServerSetup, payload)
             .await
             .inspect_err(|e| tracing::error!("failed to send. :{}", e.to_string()))
-            .inspect(|_| tracing::debug!("ServerSetup is sent."))?)
+            .inspect(|_| tracing::debug!(?version, "ServerSetup is sent."))?;
+        Ok((version, peer_max_request_id))
     }
 }
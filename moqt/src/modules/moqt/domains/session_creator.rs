@@ -1,6 +1,10 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use tokio::sync::Semaphore;
 
 use crate::Connecting;
+use crate::modules::moqt::control_plane::constants::TerminationErrorCode;
+use crate::modules::moqt::control_plane::control_message_limits::ControlMessageLimits;
 use crate::modules::moqt::data_plane::codec::control_message_decoder::ControlMessageDecoder;
 use crate::modules::moqt::data_plane::stream::stream_receiver::BiStreamReceiver;
 use crate::modules::moqt::domains::session::Session;
@@ -11,6 +15,12 @@ use crate::modules::transport::transport_connection_creator::TransportConnection
 
 pub(crate) struct SessionCreator<T: TransportProtocol> {
     pub(crate) transport_creator: T::ConnectionCreator,
+    /// Server only: time a connection has to complete SETUP once accepted.
+    pub(crate) setup_timeout: Option<Duration>,
+    /// Server only: one permit per session, handshaking or established.
+    pub(crate) session_slots: Option<Arc<Semaphore>>,
+    /// Default (no limit beyond the wire format) for clients.
+    pub(crate) control_message_limits: ControlMessageLimits,
 }
 
 impl<T: TransportProtocol> SessionCreator<T> {
@@ -23,9 +33,14 @@ impl<T: TransportProtocol> SessionCreator<T> {
             .transport_creator
             .create_new_transport(remote_address, host)
             .await?;
+        let control_message_limits = self.control_message_limits;
         let handshake = async move {
             let (send_stream, receive_stream) = transport_conn.open_bi().await?;
-            let mut moqt_receiver = BiStreamReceiver::new(receive_stream, ControlMessageDecoder);
+            let mut moqt_receiver = BiStreamReceiver::new(
+                receive_stream,
+                ControlMessageDecoder::new()
+                    .with_max_message_bytes(control_message_limits.max_message_bytes),
+            );
             let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
             let inner = SessionContextFactory::client(
                 transport_conn,
@@ -35,7 +50,12 @@ impl<T: TransportProtocol> SessionCreator<T> {
             )
             .await
             .inspect(|_| tracing::info!("Session is created."))?;
-            Ok(Session::<T>::new(moqt_receiver, inner, receiver))
+            Ok(Session::<T>::new(
+                moqt_receiver,
+                inner,
+                receiver,
+                control_message_limits,
+            ))
         };
         Ok(Connecting {
             inner: Box::pin(handshake),
@@ -44,19 +64,52 @@ impl<T: TransportProtocol> SessionCreator<T> {
 
     pub(crate) async fn accept_new_connection(&mut self) -> anyhow::Result<Connecting<T>> {
         let transport_conn = self.transport_creator.accept_new_transport().await?;
+        let session_slot = match &self.session_slots {
+            Some(session_slots) => match session_slots.clone().try_acquire_owned() {
+                Ok(session_slot) => Some(session_slot),
+                Err(_) => {
+                    // Draft-14 has no overload code; see `Session::refuse`.
+                    transport_conn.close(
+                        TerminationErrorCode::InternalError as u32,
+                        "too many sessions; retry later",
+                    );
+                    anyhow::bail!("session limit reached; connection refused");
+                }
+            },
+            None => None,
+        };
+        let setup_deadline = self
+            .setup_timeout
+            .map(|setup_timeout| tokio::time::Instant::now() + setup_timeout);
+        let control_message_limits = self.control_message_limits;
         let handshake = async move {
-            let (send_stream, receive_stream) = transport_conn.accept_bi().await?;
-            let mut moqt_receiver = BiStreamReceiver::new(receive_stream, ControlMessageDecoder);
+            let accept_bi = transport_conn.accept_bi();
+            let Some(control_stream) =
+                SessionContextFactory::before_deadline(setup_deadline, accept_bi).await
+            else {
+                SessionContextFactory::close_on_setup_timeout(&transport_conn);
+                anyhow::bail!("control stream was not opened before the setup deadline");
+            };
+            let (send_stream, receive_stream) = control_stream?;
+            let mut moqt_receiver = BiStreamReceiver::new(
+                receive_stream,
+                ControlMessageDecoder::new()
+                    .with_max_message_bytes(control_message_limits.max_message_bytes),
+            );
             let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
             let inner = SessionContextFactory::server(
                 transport_conn,
                 send_stream,
                 &mut moqt_receiver,
                 sender,
+                setup_deadline,
             )
             .await
             .inspect(|_| tracing::info!("Session is established."))?;
-            Ok(Session::<T>::new(moqt_receiver, inner, receiver))
+            Ok(
+                Session::<T>::new(moqt_receiver, inner, receiver, control_message_limits)
+                    .with_session_slot(session_slot),
+            )
         };
         Ok(Connecting {
             inner: Box::pin(handshake),
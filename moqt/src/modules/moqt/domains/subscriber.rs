@@ -1,19 +1,23 @@
+mod bandwidth_probe;
+mod track_status;
+
 use std::sync::Arc;
 
 use anyhow::bail;
 use tracing::Instrument;
 
 use crate::{
-    DatagramReceiver, FetchOption, Location, SubscribeOption, SubscriberInitiatedSubscription,
-    Subscription,
+    DatagramReceiver, FetchOption, Location, SubscribeOption, SubscribeUpdateOption,
+    SubscriberInitiatedSubscription, Subscription,
     modules::moqt::{
         control_plane::{
             control_messages::{
                 control_message_type::ControlMessageType,
                 messages::{
-                    fetch::Fetch, fetch::FetchParams, subscribe::Subscribe,
-                    subscribe_namespace::SubscribeNamespace, unsubscribe::Unsubscribe,
-                    unsubscribe_namespace::UnsubscribeNamespace,
+                    fetch::Fetch, fetch::FetchParams, fetch_cancel::FetchCancel,
+                    publish_namespace_cancel::PublishNamespaceCancel, subscribe::Subscribe,
+                    subscribe_namespace::SubscribeNamespace, subscribe_update::SubscribeUpdate,
+                    unsubscribe::Unsubscribe, unsubscribe_namespace::UnsubscribeNamespace,
                 },
             },
             enums::ResponseMessage,
@@ -51,7 +55,7 @@ impl<T: TransportProtocol> Subscriber<T> {
     pub async fn subscribe_namespace(&self, namespace: String) -> anyhow::Result<()> {
         let vec_namespace: Vec<String> = namespace.split('/').map(|s| s.to_string()).collect();
         let (sender, receiver) = tokio::sync::oneshot::channel::<ResponseMessage>();
-        let request_id = self.session.get_request_id();
+        let request_id = self.session.get_request_id().await?;
         let _registered_sender = self.session.register_response_sender(
             request_id,
             sender,
@@ -105,8 +109,9 @@ impl<T: TransportProtocol> Subscriber<T> {
     ) -> anyhow::Result<Subscription> {
         let vec_namespace = track_namespace.split('/').map(|s| s.to_string()).collect();
         let filter_type = option.filter_type;
+        let subscriber_priority = option.subscriber_priority;
         let (sender, receiver) = tokio::sync::oneshot::channel::<ResponseMessage>();
-        let request_id = self.session.get_request_id();
+        let request_id = self.session.get_request_id().await?;
         let _registered_sender = self.session.register_response_sender(
             request_id,
             sender,
@@ -122,6 +127,7 @@ impl<T: TransportProtocol> Subscriber<T> {
             filter_type: option.filter_type,
             authorization_tokens: vec![],
             delivery_timeout: None,
+            max_cache_duration: option.max_cache_duration,
         };
         self.session
             .send_stream
@@ -158,6 +164,7 @@ impl<T: TransportProtocol> Subscriber<T> {
                             track_namespace,
                             track_name,
                             message,
+                            subscriber_priority,
                             filter_type,
                         ),
                     ))
@@ -191,7 +198,7 @@ impl<T: TransportProtocol> Subscriber<T> {
         option: FetchOption,
     ) -> anyhow::Result<FetchHandle> {
         let vec_namespace = track_namespace.split('/').map(|s| s.to_string()).collect();
-        let request_id = self.session.get_request_id();
+        let request_id = self.session.get_request_id().await?;
 
         let (fetch_stream_tx, fetch_stream_rx) =
             tokio::sync::mpsc::unbounded_channel::<IncomingObject<T>>();
@@ -208,13 +215,12 @@ impl<T: TransportProtocol> Subscriber<T> {
 
         let (fetch_message_tx, fetch_message_rx) =
             tokio::sync::oneshot::channel::<ResponseMessage>();
-        // A late FETCH_OK is discarded (its data stream is dropped by
-        // FetchNotifier); see LateResponseAction::Discard for why no
-        // FETCH_CANCEL is sent yet.
+        // A late FETCH_OK is withdrawn with FETCH_CANCEL; any data stream
+        // that still arrives is dropped by FetchNotifier.
         let _registered_sender = self.session.register_response_sender(
             request_id,
             fetch_message_tx,
-            LateResponseAction::Discard,
+            LateResponseAction::FetchCancel,
         );
         let fetch = Fetch {
             request_id,
@@ -285,7 +291,7 @@ impl<T: TransportProtocol> Subscriber<T> {
         joining_start: u64,
         option: FetchOption,
     ) -> anyhow::Result<FetchHandle> {
-        let request_id = self.session.get_request_id();
+        let request_id = self.session.get_request_id().await?;
 
         let (fetch_stream_tx, fetch_stream_rx) =
             tokio::sync::mpsc::unbounded_channel::<IncomingObject<T>>();
@@ -302,13 +308,12 @@ impl<T: TransportProtocol> Subscriber<T> {
 
         let (fetch_message_tx, fetch_message_rx) =
             tokio::sync::oneshot::channel::<ResponseMessage>();
-        // A late FETCH_OK is discarded (its data stream is dropped by
-        // FetchNotifier); see LateResponseAction::Discard for why no
-        // FETCH_CANCEL is sent yet.
+        // A late FETCH_OK is withdrawn with FETCH_CANCEL; any data stream
+        // that still arrives is dropped by FetchNotifier.
         let _registered_sender = self.session.register_response_sender(
             request_id,
             fetch_message_tx,
-            LateResponseAction::Discard,
+            LateResponseAction::FetchCancel,
         );
         let fetch = Fetch {
             request_id,
@@ -352,6 +357,57 @@ impl<T: TransportProtocol> Subscriber<T> {
         }
     }
 
+    /// Tells the publisher to stop delivering objects for an accepted FETCH
+    /// and drops any fetch stream that has not been accepted yet.
+    #[tracing::instrument(
+        level = "info",
+        name = "moqt.subscriber.fetch_cancel",
+        skip_all,
+        fields(request_id = %request_id)
+    )]
+    pub async fn fetch_cancel(&self, request_id: u64) -> anyhow::Result<()> {
+        self.remove_pending_fetch(request_id).await;
+        let fetch_cancel = FetchCancel { request_id };
+        self.session
+            .send_stream
+            .send(ControlMessageType::FetchCancel, fetch_cancel.encode())
+            .await?;
+        Ok(())
+    }
+
+    /// Sends SUBSCRIBE_UPDATE for the subscription created by
+    /// `subscription_request_id`. There is no response; the publisher applies
+    /// the new range, priority and forward state to the live subscription.
+    #[tracing::instrument(
+        level = "info",
+        name = "moqt.subscriber.subscribe_update",
+        skip_all,
+        fields(subscription_request_id = %subscription_request_id, subscriber_priority = option.subscriber_priority, forward = option.forward)
+    )]
+    pub async fn subscribe_update(
+        &self,
+        subscription_request_id: u64,
+        option: SubscribeUpdateOption,
+    ) -> anyhow::Result<()> {
+        let subscribe_update = SubscribeUpdate {
+            request_id: self.session.get_request_id().await?,
+            subscription_request_id,
+            start_location: option.start_location,
+            end_group: option.end_group.map_or(0, |end_group| end_group + 1),
+            subscriber_priority: option.subscriber_priority,
+            forward: option.forward,
+            delivery_timeout: None,
+        };
+        self.session
+            .send_stream
+            .send(
+                ControlMessageType::SubscribeUpdate,
+                subscribe_update.encode(),
+            )
+            .await?;
+        Ok(())
+    }
+
     #[tracing::instrument(
         level = "info",
         name = "moqt.subscriber.unsubscribe",
@@ -388,6 +444,33 @@ impl<T: TransportProtocol> Subscriber<T> {
         Ok(())
     }
 
+    /// Withdraws acceptance of a namespace the peer published, so it stops
+    /// routing subscriptions for it here.
+    #[tracing::instrument(
+        level = "info",
+        name = "moqt.subscriber.cancel_publish_namespace",
+        skip_all,
+        fields(namespace = %namespace)
+    )]
+    pub async fn cancel_publish_namespace(
+        &self,
+        namespace: String,
+        error_code: u64,
+        reason_phrase: String,
+    ) -> anyhow::Result<()> {
+        let vec_namespace = namespace.split('/').map(|s| s.to_string()).collect();
+        let publish_namespace_cancel =
+            PublishNamespaceCancel::new(vec_namespace, error_code, reason_phrase);
+        self.session
+            .send_stream
+            .send(
+                ControlMessageType::PublishNamespaceCancel,
+                publish_namespace_cancel.encode(),
+            )
+            .await?;
+        Ok(())
+    }
+
     #[tracing::instrument(
         level = "info",
         name = "moqt.subscriber.accept_fetch_receiver",
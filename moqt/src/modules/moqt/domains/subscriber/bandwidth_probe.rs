@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use anyhow::bail;
+
+use crate::{
+    BANDWIDTH_PROBE_NAMESPACE, BandwidthEstimate, BandwidthProbeMeter, DataReceiver,
+    SubgroupObject, SubscribeOption, bandwidth_probe_track_name,
+    modules::moqt::protocol::TransportProtocol,
+};
+
+use super::Subscriber;
+
+impl<T: TransportProtocol> Subscriber<T> {
+    /// Subscribes to the relay's `size_bytes` probe track, measures how fast
+    /// it arrives and unsubscribes. Use
+    /// [`BandwidthEstimate::safe_bitrate_bps`] to pick the first quality
+    /// level before subscribing to media.
+    #[tracing::instrument(
+        level = "info",
+        name = "moqt.subscriber.probe_bandwidth",
+        skip_all,
+        fields(size_bytes = size_bytes)
+    )]
+    pub async fn probe_bandwidth(
+        &mut self,
+        size_bytes: u64,
+        timeout: Duration,
+    ) -> anyhow::Result<BandwidthEstimate> {
+        let subscription = self
+            .subscribe(
+                BANDWIDTH_PROBE_NAMESPACE.to_string(),
+                bandwidth_probe_track_name(size_bytes),
+                SubscribeOption::default(),
+            )
+            .await?;
+        let measured = tokio::time::timeout(timeout, async {
+            let DataReceiver::Stream(mut factory) =
+                self.accept_data_receiver(&subscription).await?
+            else {
+                bail!("bandwidth probe must be delivered on a subgroup stream");
+            };
+            let mut receiver = factory.next().await?;
+            let started_at = tokio::time::Instant::now();
+            let mut meter = BandwidthProbeMeter::new();
+            while meter.received_bytes() < size_bytes {
+                let Some(object) = receiver.receive_object().await? else {
+                    break;
+                };
+                let payload_len = match &object.field.subgroup_object {
+                    SubgroupObject::Payload { data, .. } => data.len(),
+                    SubgroupObject::Status { .. } => 0,
+                };
+                meter.record(payload_len, started_at.elapsed());
+            }
+            Ok(meter)
+        })
+        .await;
+        if let Err(error) = self.unsubscribe(subscription.request_id()).await {
+            tracing::warn!(?error, "failed to unsubscribe bandwidth probe");
+        }
+        let meter = match measured {
+            Ok(meter) => meter?,
+            Err(_) => bail!("bandwidth probe timed out after {:?}", timeout),
+        };
+        tracing::info!(
+            received_bytes = meter.received_bytes(),
+            "bandwidth probe finished"
+        );
+        meter
+            .estimate()
+            .ok_or_else(|| anyhow::anyhow!("bandwidth probe delivered too little data to measure"))
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::bail;
+
+use crate::{
+    modules::moqt::{
+        control_plane::{
+            control_messages::{
+                control_message_type::ControlMessageType,
+                messages::{track_status::TrackStatus, track_status_request::TrackStatusRequest},
+            },
+            enums::ResponseMessage,
+        },
+        domains::session_context::LateResponseAction,
+        protocol::TransportProtocol,
+    },
+    wire::RequestError,
+};
+
+use super::Subscriber;
+
+impl<T: TransportProtocol> Subscriber<T> {
+    /// Asks the peer for a track's current state without subscribing to it.
+    #[tracing::instrument(
+        level = "info",
+        name = "moqt.subscriber.track_status",
+        skip_all,
+        fields(track_namespace = %track_namespace, track_name = %track_name)
+    )]
+    pub async fn track_status(
+        &self,
+        track_namespace: String,
+        track_name: String,
+    ) -> anyhow::Result<TrackStatus> {
+        let vec_namespace = track_namespace.split('/').map(|s| s.to_string()).collect();
+        let (sender, receiver) = tokio::sync::oneshot::channel::<ResponseMessage>();
+        let request_id = self.session.get_request_id().await?;
+        let _registered_sender =
+            self.session
+                .register_response_sender(request_id, sender, LateResponseAction::Discard);
+        let track_status_request = TrackStatusRequest {
+            request_id,
+            track_namespace: vec_namespace,
+            track_name,
+            authorization_tokens: vec![],
+        };
+        self.session
+            .send_stream
+            .send(
+                ControlMessageType::TrackStatusRequest,
+                track_status_request.encode(),
+            )
+            .await?;
+        let response = self.session.await_response(receiver).await?;
+        match response {
+            ResponseMessage::TrackStatus(message) => {
+                if request_id != message.request_id {
+                    bail!("Protocol violation")
+                } else {
+                    tracing::info!("Track status");
+                    Ok(message)
+                }
+            }
+            ResponseMessage::TrackStatusError(request_id, error_code, reason_phrase) => {
+                tracing::info!("Track status error");
+                Err(RequestError {
+                    request_id,
+                    error_code,
+                    reason_phrase,
+                }
+                .into())
+            }
+            _ => bail!("Protocol violation"),
+        }
+    }
+}
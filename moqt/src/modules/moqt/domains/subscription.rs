@@ -61,6 +61,30 @@ impl Subscription {
         }
     }
 
+    /// MAX_CACHE_DURATION (ms) the publisher granted in SUBSCRIBE_OK, if any.
+    pub fn max_cache_duration(&self) -> Option<u64> {
+        match self {
+            Self::PublisherInitiated(_) => None,
+            Self::SubscriberInitiated(subscription) => subscription.max_cache_duration,
+        }
+    }
+
+    /// Subscriber priority at the time the subscription was established.
+    pub fn subscriber_priority(&self) -> u8 {
+        match self {
+            Self::PublisherInitiated(subscription) => subscription.subscriber_priority,
+            Self::SubscriberInitiated(subscription) => subscription.subscriber_priority,
+        }
+    }
+
+    /// DELIVERY_TIMEOUT (ms) the subscriber asked for, if any.
+    pub fn delivery_timeout(&self) -> Option<u64> {
+        match self {
+            Self::PublisherInitiated(subscription) => subscription.delivery_timeout,
+            Self::SubscriberInitiated(subscription) => subscription.delivery_timeout,
+        }
+    }
+
     pub fn filter_type(&self) -> FilterType {
         match self {
             Self::PublisherInitiated(subscription) => subscription.filter_type,
@@ -78,8 +102,10 @@ pub struct SubscriberInitiatedSubscription {
     pub expires: u64,
     pub group_order: GroupOrder,
     pub content_exists: ContentExists,
+    pub subscriber_priority: u8,
     pub filter_type: FilterType,
     pub delivery_timeout: Option<u64>,
+    pub max_cache_duration: Option<u64>,
 }
 
 impl SubscriberInitiatedSubscription {
@@ -87,6 +113,7 @@ impl SubscriberInitiatedSubscription {
         track_namespace: String,
         track_name: String,
         subscribe_ok: SubscribeOk,
+        subscriber_priority: u8,
         filter_type: FilterType,
     ) -> Self {
         Self {
@@ -97,8 +124,10 @@ impl SubscriberInitiatedSubscription {
             expires: subscribe_ok.expires,
             group_order: subscribe_ok.group_order,
             content_exists: subscribe_ok.content_exists,
+            subscriber_priority,
             filter_type,
             delivery_timeout: None,
+            max_cache_duration: subscribe_ok.max_duration,
         }
     }
 
@@ -114,8 +143,10 @@ impl SubscriberInitiatedSubscription {
             expires: 0,
             group_order: handler.group_order,
             content_exists: ContentExists::False,
+            subscriber_priority: handler.subscriber_priority,
             filter_type: handler.filter_type,
             delivery_timeout: handler.delivery_timeout,
+            max_cache_duration: handler.max_cache_duration,
         }
     }
 }
@@ -151,7 +182,7 @@ impl PublisherInitiatedSubscription {
             subscriber_priority: publish_ok.subscriber_priority,
             forward: publish_ok.forward,
             filter_type: publish_ok.filter_type,
-            delivery_timeout: None,
+            delivery_timeout: publish_ok.delivery_timeout,
         }
     }
 
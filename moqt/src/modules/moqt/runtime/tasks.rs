@@ -1,3 +1,4 @@
+pub(crate) mod bi_stream_reject_task;
 pub(crate) mod control_message_receive_task;
 pub(crate) mod datagram_receive_task;
 pub(crate) mod disconnect_watch_task;
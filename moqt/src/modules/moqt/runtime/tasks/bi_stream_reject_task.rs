@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use tracing::{Instrument, Span};
+
+use crate::{
+    TransportProtocol,
+    modules::{
+        moqt::{
+            control_plane::constants::TerminationErrorCode,
+            domains::session_context::SessionContext,
+        },
+        transport::transport_connection::TransportConnection,
+    },
+};
+
+/// Watches for bidirectional streams after the control stream. Draft-14
+/// uses no other bidirectional stream, so the first one closes the session
+/// with PROTOCOL_VIOLATION instead of sitting unread in the transport.
+pub(crate) struct BiStreamRejectTask;
+
+impl BiStreamRejectTask {
+    pub(crate) fn run<T: TransportProtocol>(
+        context: Arc<SessionContext<T>>,
+        stream_span: Span,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::task::Builder::new()
+            .name("Bi Stream Rejecter")
+            .spawn(
+                async move {
+                    match context.transport_connection.accept_bi().await {
+                        Ok(_) => {
+                            tracing::error!(
+                                "Protocol violation: peer opened a second bidirectional stream; closing session"
+                            );
+                            context.close_with_error(
+                                TerminationErrorCode::ProtocolViolation,
+                                "unexpected bidirectional stream",
+                            );
+                        }
+                        Err(_) => {
+                            tracing::debug!("stopped watching for bidirectional streams");
+                        }
+                    }
+                }
+                .instrument(stream_span),
+            )
+            .unwrap()
+    }
+}
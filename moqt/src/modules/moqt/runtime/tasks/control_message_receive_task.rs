@@ -1,5 +1,6 @@
 use std::sync::{Arc, Weak};
 
+use tokio::time::Instant;
 use tracing::{Instrument, Span};
 
 use crate::{
@@ -7,19 +8,26 @@ use crate::{
     modules::moqt::{
         control_plane::{
             constants::TerminationErrorCode,
+            control_message_limits::MessageRateWindow,
+            control_message_observer::ControlMessageDirection,
             enums::ResponseMessage,
             handler::{
-                fetch_handler::FetchHandler, publish_handler::PublishHandler,
+                fetch_cancel_handler::FetchCancelHandler, fetch_handler::FetchHandler,
+                publish_done_handler::PublishDoneHandler, publish_handler::PublishHandler,
+                publish_namespace_cancel_handler::PublishNamespaceCancelHandler,
                 publish_namespace_done_handler::PublishNamespaceDoneHandler,
                 publish_namespace_handler::PublishNamespaceHandler,
                 subscribe_handler::SubscribeHandler,
                 subscribe_namespace_handler::SubscribeNamespaceHandler,
+                subscribe_update_handler::SubscribeUpdateHandler,
+                track_status_request_handler::TrackStatusRequestHandler,
                 unsubscribe_handler::UnsubscribeHandler,
                 unsubscribe_namespace_handler::UnsubscribeNamespaceHandler,
             },
         },
         data_plane::stream::{
-            received_message::ReceivedMessage, stream_receiver::BiStreamReceiver,
+            received_message::ReceivedMessage,
+            stream_receiver::{BiStreamReceiver, StreamReceiveError},
         },
         domains::session_context::{InflightRequest, SessionContext},
     },
@@ -36,6 +44,7 @@ impl ControlMessageReceiveTask {
     pub(crate) fn run<T: TransportProtocol>(
         mut receive_stream: BiStreamReceiver<T>,
         session_context: Weak<SessionContext<T>>,
+        mut rate_window: MessageRateWindow,
         receiver_span: Span,
     ) -> tokio::task::JoinHandle<()> {
         tokio::task::Builder::new()
@@ -53,11 +62,57 @@ impl ControlMessageReceiveTask {
                                     tracing::info!("Stream ended.");
                                     break;
                                 }
+                                Err(error @ StreamReceiveError::Decode(_)) => {
+                                    tracing::error!(%error, "Protocol violation: undecodable control message; closing session");
+                                    session.close_with_error(
+                                        TerminationErrorCode::ProtocolViolation,
+                                        "malformed or oversized control message",
+                                    );
+                                    break;
+                                }
                                 Err(error) => {
                                     tracing::info!(%error, "Stream closed.");
                                     break;
                                 }
                             };
+                            if !rate_window.admit(Instant::now()) {
+                                tracing::error!("Protocol violation: control message rate exceeded; closing session");
+                                session.close_with_error(
+                                    TerminationErrorCode::ProtocolViolation,
+                                    "control message rate exceeded",
+                                );
+                                break;
+                            }
+
+                            if let Some(message_type) = received_message.message_type() {
+                                session
+                                    .send_stream
+                                    .observe(ControlMessageDirection::Received, message_type);
+                            }
+
+                            match &received_message {
+                                ReceivedMessage::MaxRequestId(max_request_id) => {
+                                    session.raise_peer_request_limit(max_request_id.request_id);
+                                    continue;
+                                }
+                                ReceivedMessage::RequestsBlocked(requests_blocked) => {
+                                    session
+                                        .unblock_peer_requests(requests_blocked.maximum_request_id)
+                                        .await;
+                                    continue;
+                                }
+                                _ => {}
+                            }
+                            if let Some(request_id) = received_message.peer_request_id() {
+                                session.observe_peer_request_id(request_id).await;
+                            }
+
+                            if session.is_draining()
+                                && let ReceivedMessage::Subscribe(subscribe) = &received_message
+                            {
+                                session.reject_subscribe_while_draining(subscribe.request_id).await;
+                                continue;
+                            }
 
                             match Self::resolve_message(session.clone(), received_message) {
                                 DepacketizeResult::SessionEvent(event) => {
@@ -130,11 +185,25 @@ impl ControlMessageReceiveTask {
                 let subscribe_handler = SubscribeHandler::new(session.clone(), subscribe);
                 DepacketizeResult::SessionEvent(SessionEvent::<T>::Subscribe(subscribe_handler))
             }
+            ReceivedMessage::SubscribeUpdate(subscribe_update) => {
+                tracing::debug!("Event: Subscribe update");
+                let subscribe_update_handler = SubscribeUpdateHandler::new(subscribe_update);
+                DepacketizeResult::SessionEvent(SessionEvent::<T>::SubscribeUpdate(
+                    subscribe_update_handler,
+                ))
+            }
             ReceivedMessage::Unsubscribe(unsubscribe) => {
                 tracing::debug!("Event: Unsubscribe");
                 let unsubscribe_handler = UnsubscribeHandler::new(session.clone(), unsubscribe);
                 DepacketizeResult::SessionEvent(SessionEvent::<T>::Unsubscribe(unsubscribe_handler))
             }
+            ReceivedMessage::PublishDone(publish_done) => {
+                tracing::debug!("Event: Publish done");
+                let publish_done_handler = PublishDoneHandler::new(publish_done);
+                DepacketizeResult::SessionEvent(SessionEvent::<T>::PublishDone(
+                    publish_done_handler,
+                ))
+            }
             ReceivedMessage::SubscribeOk(subscribe_ok) => {
                 tracing::debug!("Event: Subscribe ok");
                 let request_id = subscribe_ok.request_id;
@@ -185,6 +254,14 @@ impl ControlMessageReceiveTask {
                     publish_namespace_done_handler,
                 ))
             }
+            ReceivedMessage::PublishNamespaceCancel(publish_namespace_cancel) => {
+                tracing::debug!("Event: Publish namespace cancel");
+                let publish_namespace_cancel_handler =
+                    PublishNamespaceCancelHandler::new(publish_namespace_cancel);
+                DepacketizeResult::SessionEvent(SessionEvent::<T>::PublishNamespaceCancel(
+                    publish_namespace_cancel_handler,
+                ))
+            }
             ReceivedMessage::PublishNamespaceOk(publish_namespace_ok) => {
                 tracing::debug!("Event: Publish namespace ok");
                 let request_id = publish_namespace_ok.request_id;
@@ -251,6 +328,40 @@ impl ControlMessageReceiveTask {
                 );
                 DepacketizeResult::ResponseMessage(fetch_error.request_id, response)
             }
+            ReceivedMessage::FetchCancel(fetch_cancel) => {
+                tracing::debug!("Event: Fetch cancel");
+                let fetch_cancel_handler = FetchCancelHandler::new(fetch_cancel);
+                DepacketizeResult::SessionEvent(SessionEvent::<T>::FetchCancel(
+                    fetch_cancel_handler,
+                ))
+            }
+            ReceivedMessage::GoAway(go_away) => {
+                tracing::info!(new_session_uri = %go_away.new_session_uri, "Event: GoAway");
+                DepacketizeResult::SessionEvent(SessionEvent::<T>::GoAway(go_away.new_session_uri))
+            }
+            ReceivedMessage::TrackStatusRequest(track_status_request) => {
+                tracing::debug!("Event: Track status request");
+                let track_status_request_handler =
+                    TrackStatusRequestHandler::new(session.clone(), track_status_request);
+                DepacketizeResult::SessionEvent(SessionEvent::<T>::TrackStatusRequest(
+                    track_status_request_handler,
+                ))
+            }
+            ReceivedMessage::TrackStatus(track_status) => {
+                tracing::debug!("Event: Track status");
+                let request_id = track_status.request_id;
+                let response = ResponseMessage::TrackStatus(track_status);
+                DepacketizeResult::ResponseMessage(request_id, response)
+            }
+            ReceivedMessage::TrackStatusError(track_status_error) => {
+                tracing::debug!("Event: Track status error");
+                let response = ResponseMessage::TrackStatusError(
+                    track_status_error.request_id,
+                    track_status_error.error_code,
+                    track_status_error.reason_phrase,
+                );
+                DepacketizeResult::ResponseMessage(track_status_error.request_id, response)
+            }
             _ => todo!(),
         }
     }
@@ -1,9 +1,12 @@
+pub(crate) mod connection_stats;
 pub(crate) mod crypto_provider;
 pub(crate) mod dual;
 pub(crate) mod quic;
 pub(crate) mod read_error;
+pub(crate) mod server_certificate;
 pub(crate) mod transport_connection;
 pub(crate) mod transport_connection_creator;
 pub(crate) mod transport_receive_stream;
 pub(crate) mod transport_send_stream;
+pub(crate) mod transport_tuning;
 pub(crate) mod webtransport;
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+/// A sample of one connection's QUIC path, to tell whether a slow session
+/// is down to the network or to the endpoint. Packet counters are totals
+/// since the connection was established.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    pub rtt: Duration,
+    /// Bytes the congestion controller currently lets in flight.
+    pub congestion_window: u64,
+    pub sent_packets: u64,
+    pub lost_packets: u64,
+    /// Datagrams the session could not hand to QUIC: too large, not
+    /// supported by the peer, or the connection was lost. Datagrams QUIC
+    /// discards from its own send queue are not counted.
+    pub dropped_datagrams: u64,
+}
+
+impl ConnectionStats {
+    pub(crate) fn from_quinn(connection: &quinn::Connection) -> Self {
+        let path = connection.stats().path;
+        Self {
+            rtt: path.rtt,
+            congestion_window: path.cwnd,
+            sent_packets: path.sent_packets,
+            lost_packets: path.lost_packets,
+            dropped_datagrams: 0,
+        }
+    }
+
+    /// Lost packets per thousand sent.
+    pub fn loss_permille(&self) -> u64 {
+        (self.lost_packets * 1000)
+            .checked_div(self.sent_packets)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loss_is_zero_before_anything_was_sent() {
+        // Arrange
+        let idle = ConnectionStats::default();
+        let lossy = ConnectionStats {
+            sent_packets: 2000,
+            lost_packets: 5,
+            ..ConnectionStats::default()
+        };
+
+        // Act / Assert
+        assert_eq!(idle.loss_permille(), 0);
+        assert_eq!(lossy.loss_permille(), 2);
+    }
+}
@@ -3,8 +3,8 @@ use async_trait::async_trait;
 use super::dual_receive_stream::DualReceiveStream;
 use super::dual_send_stream::DualSendStream;
 use crate::modules::transport::{
-    quic::quic_connection::QUICConnection, transport_connection::TransportConnection,
-    webtransport::wt_connection::WtConnection,
+    connection_stats::ConnectionStats, quic::quic_connection::QUICConnection,
+    transport_connection::TransportConnection, webtransport::wt_connection::WtConnection,
 };
 
 #[derive(Debug)]
@@ -95,4 +95,11 @@ impl TransportConnection for DualConnection {
             DualConnection::WebTransport(c) => c.close(code, reason),
         }
     }
+
+    fn stats(&self) -> ConnectionStats {
+        match self {
+            DualConnection::Quic(c) => c.stats(),
+            DualConnection::WebTransport(c) => c.stats(),
+        }
+    }
 }
@@ -4,20 +4,19 @@ use std::{
 };
 
 use async_trait::async_trait;
-use quinn::rustls::{
-    self,
-    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
-};
 
 use super::dual_connection::DualConnection;
 use crate::modules::transport::{
-    crypto_provider::install_default_crypto_provider, quic::quic_connection::QUICConnection,
+    quic::quic_connection::QUICConnection,
+    server_certificate::{CertificateReloader, server_config},
     transport_connection_creator::TransportConnectionCreator,
+    transport_tuning::TransportTuning,
     webtransport::wt_connection::WtConnection,
 };
 
 pub struct DualProtocolCreator {
     endpoint: quinn::Endpoint,
+    certificate_reloader: CertificateReloader,
 }
 
 #[async_trait]
@@ -37,50 +36,41 @@ impl TransportConnectionCreator for DualProtocolCreator {
         key_path: &str,
         port_num: u16,
         keep_alive_sec: u64,
+        tuning: &TransportTuning,
+        reuse_port: bool,
     ) -> anyhow::Result<Self> {
-        install_default_crypto_provider();
-
-        // 証明書を同期的に読み込む
-        let cert = CertificateDer::pem_file_iter(cert_path)
-            .inspect_err(|e| tracing::error!("Opening certificate file failed: {:?}", e))?
-            .collect::<Result<Vec<_>, _>>()
-            .inspect_err(|e| tracing::error!("Parsing certificates failed: {:?}", e))?;
-
-        // 秘密鍵を同期的に読み込む
-        let key = PrivateKeyDer::from_pem_file(key_path)
-            .inspect_err(|e| tracing::error!("Creating private key failed: {:?}", e.to_string()))?;
-
-        let mut server_crypto = rustls::ServerConfig::builder()
-            .with_no_client_auth()
-            .with_single_cert(cert, key)
-            .inspect_err(|e| tracing::error!("server config failed: {:?}", e.to_string()))?;
-
         // ALPN を2つ登録（WebTransport + QUIC）
-        server_crypto.alpn_protocols = vec![
+        let alpn_protocols = vec![
             web_transport_quinn::ALPN.as_bytes().to_vec(), // h3
             b"moq-00".to_vec(),
         ];
-        server_crypto.key_log = Arc::new(rustls::KeyLogFile::new());
-
-        let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(
-            quinn::crypto::rustls::QuicServerConfig::try_from(server_crypto)?,
-        ));
         let mut transport_config = quinn::TransportConfig::default();
         let keep_alive_sec = std::time::Duration::from_secs(keep_alive_sec);
         transport_config.keep_alive_interval(Some(keep_alive_sec));
         transport_config.max_concurrent_uni_streams(100000u32.into());
-        transport_config.send_window(64 * 1024);
-        transport_config.packet_threshold(5);
-        transport_config.stream_receive_window(quinn::VarInt::from_u32(1024 * 1024));
-
-        let transport_arc = Arc::new(transport_config);
-        server_config.transport_config(transport_arc);
-
+        tuning.apply(&mut transport_config);
+        let transport_config = Arc::new(transport_config);
+
+        let server_config = server_config(
+            cert_path,
+            key_path,
+            &alpn_protocols,
+            transport_config.clone(),
+        )?;
         let address = SocketAddr::from((Ipv6Addr::UNSPECIFIED, port_num));
-        let endpoint = quinn::Endpoint::server(server_config, address)?;
+        let endpoint = tuning.server_endpoint(server_config, address, reuse_port)?;
         tracing::info!("Server ready! for Dual Protocol: {:?}", address);
 
-        Ok(DualProtocolCreator { endpoint })
+        let certificate_reloader =
+            CertificateReloader::new(endpoint.clone(), alpn_protocols, transport_config);
+        Ok(DualProtocolCreator {
+            endpoint,
+            certificate_reloader,
+        })
+    }
+
+    fn certificate_reloader(&self) -> Option<CertificateReloader> {
+        Some(self.certificate_reloader.clone())
     }
 
     async fn create_new_transport(
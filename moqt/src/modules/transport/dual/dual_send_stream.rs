@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 
 use crate::modules::transport::transport_send_stream::TransportSendError;
 use crate::modules::transport::{
@@ -22,6 +22,13 @@ impl TransportSendStream for DualSendStream {
         }
     }
 
+    async fn send_chunks(&mut self, chunks: &mut [Bytes]) -> Result<(), TransportSendError> {
+        match self {
+            DualSendStream::Quic(s) => s.send_chunks(chunks).await,
+            DualSendStream::WebTransport(s) => s.send_chunks(chunks).await,
+        }
+    }
+
     async fn close(&mut self) -> Result<(), TransportSendError> {
         match self {
             DualSendStream::Quic(s) => s.close().await,
@@ -35,4 +42,11 @@ impl TransportSendStream for DualSendStream {
             DualSendStream::WebTransport(s) => s.reset(error_code).await,
         }
     }
+
+    fn set_priority(&self, priority: i32) -> Result<(), TransportSendError> {
+        match self {
+            DualSendStream::Quic(s) => s.set_priority(priority),
+            DualSendStream::WebTransport(s) => s.set_priority(priority),
+        }
+    }
 }
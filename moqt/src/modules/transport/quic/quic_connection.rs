@@ -2,6 +2,7 @@ use anyhow::bail;
 use async_trait::async_trait;
 use bytes::BytesMut;
 
+use crate::modules::transport::connection_stats::ConnectionStats;
 use crate::modules::transport::quic::quic_receive_stream::QUICReceiveStream;
 use crate::modules::transport::quic::quic_send_stream::QUICSendStream;
 use crate::modules::transport::transport_connection::TransportConnection;
@@ -78,4 +79,8 @@ impl TransportConnection for QUICConnection {
             }
         }
     }
+
+    fn stats(&self) -> ConnectionStats {
+        ConnectionStats::from_quinn(&self.connection)
+    }
 }
@@ -7,64 +7,40 @@ use std::{
 
 use quinn::rustls::{
     self,
-    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    pki_types::{CertificateDer, pem::PemObject},
 };
-use quinn::{self, TransportConfig, VarInt};
+use quinn::{self, TransportConfig};
 
 use crate::modules::transport::{
     crypto_provider::install_default_crypto_provider,
     quic::{quic_connection::QUICConnection, skip_certd_validation::SkipVerification},
+    server_certificate::{CertificateReloader, server_config},
     transport_connection_creator::TransportConnectionCreator,
+    transport_tuning::TransportTuning,
 };
 
 pub struct QUICConnectionCreator {
     endpoint: quinn::Endpoint,
+    /// Set for server endpoints.
+    certificate_reloader: Option<CertificateReloader>,
 }
 
 impl QUICConnectionCreator {
-    fn config_builder(
-        cert_path: &str,
-        key_path: &str,
-        keep_alive_sec: u64,
-    ) -> anyhow::Result<quinn::ServerConfig> {
-        install_default_crypto_provider();
-
-        let cert = CertificateDer::pem_file_iter(cert_path)
-            .inspect_err(|e| tracing::error!("Opening certificate file failed: {:?}", e))?
-            .collect::<Result<Vec<_>, _>>()
-            .inspect_err(|e| tracing::error!("Parsing certificates failed: {:?}", e))?;
-        let key = PrivateKeyDer::from_pem_file(key_path)
-            .inspect_err(|e| tracing::error!("Creating private key failed: {:?}", e.to_string()))?;
-        let mut server_crypto = rustls::ServerConfig::builder()
-            .with_no_client_auth()
-            .with_single_cert(cert, key)
-            .inspect_err(|e| tracing::error!("server config failed: {:?}", e.to_string()))?;
-        let alpn = &[b"moq-00"];
-        server_crypto.alpn_protocols = alpn.iter().map(|&x| x.into()).collect();
-        server_crypto.key_log = Arc::new(rustls::KeyLogFile::new());
-
-        let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(
-            quinn::crypto::rustls::QuicServerConfig::try_from(server_crypto)?,
-        ));
+    fn transport_config(keep_alive_sec: u64, tuning: &TransportTuning) -> Arc<TransportConfig> {
         let mut transport_config = TransportConfig::default();
         let keep_alive_sec = std::time::Duration::from_secs(keep_alive_sec);
         transport_config.keep_alive_interval(Some(keep_alive_sec));
         // 単方向ストリーム数を100000に設定
         transport_config.max_concurrent_uni_streams(100000u32.into());
-        // initial_max_stream_data_uniと同義。デフォルトは65,536 バイト (64KB) 大きくするとACKを待たずに送信するため、輻輳が発生する可能性が高まる
-        transport_config.send_window(64 * 1024);
         // パケロス判定して再送を要求するまでの時間(RTTの倍数)を指定する。小さくすると再送が増える Default(RFC推奨値): 1.125
         // transport_config.time_threshold(1.5);
-        // パケロス判定して再送を要求するまでのパケット間隔を指定する。小さくすると再送が増える Default(RFC推奨値): 3
-        transport_config.packet_threshold(5);
-        transport_config.stream_receive_window(VarInt::from_u32(1024 * 1024)); // initial_max_stream_data_uniと同義。デフォルトは65,536 バイト (64KB)なので1MBにする
+        // パケロス判定して再送を要求するまでのパケット間隔(packet_threshold)は TransportTuning で指定する。小さくすると再送が増える Default(RFC推奨値): 3
+        // send_window / receive_window / stream_receive_window / packet_threshold / congestion controller
+        tuning.apply(&mut transport_config);
 
         tracing::warn!("datagram setting: {:?}", transport_config);
 
-        let transport_arc = Arc::new(transport_config);
-        server_config.transport_config(transport_arc);
-
-        Ok(server_config)
+        Arc::new(transport_config)
     }
 
     fn create_client(port_num: u16, mut config: rustls::ClientConfig) -> anyhow::Result<Self> {
@@ -81,7 +57,10 @@ impl QUICConnectionCreator {
         endpoint.set_default_client_config(client_config);
 
         tracing::info!("Client ready! for QUIC: {:?}", address);
-        Ok(QUICConnectionCreator { endpoint })
+        Ok(QUICConnectionCreator {
+            endpoint,
+            certificate_reloader: None,
+        })
     }
 }
 
@@ -128,12 +107,30 @@ impl TransportConnectionCreator for QUICConnectionCreator {
         key_path: &str,
         port_num: u16,
         keep_alive_sec: u64,
+        tuning: &TransportTuning,
+        reuse_port: bool,
     ) -> anyhow::Result<Self> {
-        let server_config = Self::config_builder(cert_path, key_path, keep_alive_sec)?;
+        let alpn_protocols = vec![b"moq-00".to_vec()];
+        let transport_config = Self::transport_config(keep_alive_sec, tuning);
+        let server_config = server_config(
+            cert_path,
+            key_path,
+            &alpn_protocols,
+            transport_config.clone(),
+        )?;
         let address = SocketAddr::from((Ipv6Addr::UNSPECIFIED, port_num));
-        let endpoint = quinn::Endpoint::server(server_config, address)?;
+        let endpoint = tuning.server_endpoint(server_config, address, reuse_port)?;
         tracing::info!("Server ready! for QUIC: {:?}", address);
-        Ok(QUICConnectionCreator { endpoint })
+        let certificate_reloader =
+            CertificateReloader::new(endpoint.clone(), alpn_protocols, transport_config);
+        Ok(QUICConnectionCreator {
+            endpoint,
+            certificate_reloader: Some(certificate_reloader),
+        })
+    }
+
+    fn certificate_reloader(&self) -> Option<CertificateReloader> {
+        self.certificate_reloader.clone()
     }
 
     async fn create_new_transport(
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use quinn::{self};
 
 use crate::modules::transport::transport_send_stream::{TransportSendError, TransportSendStream};
@@ -18,6 +18,13 @@ impl TransportSendStream for QUICSendStream {
             .map_err(quic_write_error_to_transport_send_error)
     }
 
+    async fn send_chunks(&mut self, chunks: &mut [Bytes]) -> Result<(), TransportSendError> {
+        self.send_stream
+            .write_all_chunks(chunks)
+            .await
+            .map_err(quic_write_error_to_transport_send_error)
+    }
+
     async fn close(&mut self) -> Result<(), TransportSendError> {
         self.send_stream
             .finish()
@@ -34,6 +41,12 @@ impl TransportSendStream for QUICSendStream {
             .reset(error_code)
             .map_err(|_| TransportSendError::ClosedStream)
     }
+
+    fn set_priority(&self, priority: i32) -> Result<(), TransportSendError> {
+        self.send_stream
+            .set_priority(priority)
+            .map_err(|_| TransportSendError::ClosedStream)
+    }
 }
 
 fn quic_write_error_to_transport_send_error(error: quinn::WriteError) -> TransportSendError {
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use quinn::rustls::{
+    self,
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+};
+
+use crate::modules::transport::crypto_provider::install_default_crypto_provider;
+
+/// quinn server config for the PEM certificate chain and key at the given
+/// paths, offering `alpn_protocols`.
+pub(crate) fn server_config(
+    cert_path: &str,
+    key_path: &str,
+    alpn_protocols: &[Vec<u8>],
+    transport_config: Arc<quinn::TransportConfig>,
+) -> anyhow::Result<quinn::ServerConfig> {
+    install_default_crypto_provider();
+
+    let cert = CertificateDer::pem_file_iter(cert_path)
+        .inspect_err(|e| tracing::error!("Opening certificate file failed: {:?}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .inspect_err(|e| tracing::error!("Parsing certificates failed: {:?}", e))?;
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .inspect_err(|e| tracing::error!("Creating private key failed: {:?}", e.to_string()))?;
+    let mut server_crypto = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(cert, key)
+        .inspect_err(|e| tracing::error!("server config failed: {:?}", e.to_string()))?;
+    server_crypto.alpn_protocols = alpn_protocols.to_vec();
+    server_crypto.key_log = Arc::new(rustls::KeyLogFile::new());
+
+    let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(
+        quinn::crypto::rustls::QuicServerConfig::try_from(server_crypto)?,
+    ));
+    server_config.transport_config(transport_config);
+    Ok(server_config)
+}
+
+/// Handle that swaps a running server endpoint's certificate. New
+/// connections use the files read at the last reload; established
+/// sessions keep the certificate they were accepted with.
+#[derive(Clone)]
+pub struct CertificateReloader {
+    endpoint: quinn::Endpoint,
+    alpn_protocols: Vec<Vec<u8>>,
+    transport_config: Arc<quinn::TransportConfig>,
+}
+
+impl CertificateReloader {
+    pub(crate) fn new(
+        endpoint: quinn::Endpoint,
+        alpn_protocols: Vec<Vec<u8>>,
+        transport_config: Arc<quinn::TransportConfig>,
+    ) -> Self {
+        Self {
+            endpoint,
+            alpn_protocols,
+            transport_config,
+        }
+    }
+
+    /// Re-reads the PEM files. On error the endpoint keeps serving the
+    /// previous certificate.
+    pub fn reload(&self, cert_path: &str, key_path: &str) -> anyhow::Result<()> {
+        let server_config = server_config(
+            cert_path,
+            key_path,
+            &self.alpn_protocols,
+            self.transport_config.clone(),
+        )?;
+        self.endpoint.set_server_config(Some(server_config));
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for CertificateReloader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CertificateReloader")
+            .field("local_addr", &self.endpoint.local_addr().ok())
+            .finish()
+    }
+}
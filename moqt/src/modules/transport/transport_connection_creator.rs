@@ -1,6 +1,9 @@
 use std::net::SocketAddr;
 
-use crate::modules::transport::transport_connection::TransportConnection;
+use crate::modules::transport::{
+    server_certificate::CertificateReloader, transport_connection::TransportConnection,
+    transport_tuning::TransportTuning,
+};
 use async_trait::async_trait;
 
 #[async_trait]
@@ -18,9 +21,16 @@ pub(crate) trait TransportConnectionCreator: Send + Sync + 'static {
         key_path: &str,
         port_num: u16,
         keep_alive_sec: u64,
+        tuning: &TransportTuning,
+        reuse_port: bool,
     ) -> anyhow::Result<Self>
     where
         Self: Sized;
+    /// Swaps the certificate of a server endpoint. `None` for clients and
+    /// transports that cannot reload.
+    fn certificate_reloader(&self) -> Option<CertificateReloader> {
+        None
+    }
     async fn create_new_transport(
         &self,
         remote_address: SocketAddr,
@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 
 use async_trait::async_trait;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -29,6 +29,11 @@ pub enum TransportSendError {
 #[async_trait]
 pub(crate) trait TransportSendStream: Send + Sync + 'static + Debug {
     async fn send(&mut self, buffer: &BytesMut) -> Result<(), TransportSendError>;
+    /// Writes `chunks` in order without copying them into one buffer.
+    async fn send_chunks(&mut self, chunks: &mut [Bytes]) -> Result<(), TransportSendError>;
     async fn close(&mut self) -> Result<(), TransportSendError>;
     async fn reset(&mut self, error_code: u64) -> Result<(), TransportSendError>;
+    /// Sets the scheduling priority of this stream relative to others on the
+    /// same connection. Higher values are sent first.
+    fn set_priority(&self, priority: i32) -> Result<(), TransportSendError>;
 }
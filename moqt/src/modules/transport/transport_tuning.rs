@@ -0,0 +1,234 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use quinn::{
+    EndpointConfig, TransportConfig, VarInt,
+    congestion::{BbrConfig, CubicConfig, NewRenoConfig},
+};
+
+/// Congestion controller of a server endpoint's QUIC connections.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CongestionController {
+    #[default]
+    Cubic,
+    NewReno,
+    Bbr,
+}
+
+impl std::str::FromStr for CongestionController {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "cubic" => Ok(Self::Cubic),
+            "new_reno" | "newreno" => Ok(Self::NewReno),
+            "bbr" => Ok(Self::Bbr),
+            _ => anyhow::bail!("unknown congestion controller `{value}`"),
+        }
+    }
+}
+
+/// QUIC flow and congestion control of a server endpoint. Raw QUIC and the
+/// dual endpoint apply it; the WebTransport-only endpoint keeps the
+/// library defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransportTuning {
+    /// Bytes sent but not yet acknowledged across all streams of a connection.
+    /// Larger values send more before waiting for ACKs, at the risk of
+    /// congestion.
+    pub send_window: u64,
+    /// Bytes a peer may send across all streams of a connection before they
+    /// are read.
+    pub receive_window: u64,
+    /// Bytes a peer may send on one stream before it is read.
+    pub stream_receive_window: u32,
+    /// Packets acknowledged after one before it is declared lost. Higher
+    /// values tolerate more reordering at the cost of slower loss recovery;
+    /// RFC 9002 recommends 3.
+    pub packet_threshold: u32,
+    /// Largest UDP payload the endpoint accepts, 1200 to 65527.
+    pub max_udp_payload_size: u16,
+    pub congestion_controller: CongestionController,
+}
+
+impl Default for TransportTuning {
+    fn default() -> Self {
+        Self {
+            send_window: 64 * 1024,
+            receive_window: VarInt::MAX.into_inner(),
+            stream_receive_window: 1024 * 1024,
+            packet_threshold: 5,
+            max_udp_payload_size: 1472,
+            congestion_controller: CongestionController::default(),
+        }
+    }
+}
+
+impl TransportTuning {
+    /// Rejects values quinn would refuse when the endpoint is created.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        EndpointConfig::default()
+            .max_udp_payload_size(self.max_udp_payload_size)
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "max_udp_payload_size {} is outside 1200..=65527",
+                    self.max_udp_payload_size
+                )
+            })?;
+        VarInt::from_u64(self.receive_window)
+            .map_err(|_| anyhow::anyhow!("receive_window {} is too large", self.receive_window))?;
+        if self.packet_threshold < 3 {
+            anyhow::bail!(
+                "packet_threshold {} is below the RFC 9002 minimum of 3",
+                self.packet_threshold
+            );
+        }
+        Ok(())
+    }
+
+    pub(crate) fn apply(&self, transport_config: &mut TransportConfig) {
+        transport_config.send_window(self.send_window);
+        transport_config
+            .receive_window(VarInt::from_u64(self.receive_window).unwrap_or(VarInt::MAX));
+        transport_config.stream_receive_window(VarInt::from_u32(self.stream_receive_window));
+        transport_config.packet_threshold(self.packet_threshold);
+        match self.congestion_controller {
+            CongestionController::Cubic => {
+                transport_config.congestion_controller_factory(Arc::new(CubicConfig::default()))
+            }
+            CongestionController::NewReno => {
+                transport_config.congestion_controller_factory(Arc::new(NewRenoConfig::default()))
+            }
+            CongestionController::Bbr => {
+                transport_config.congestion_controller_factory(Arc::new(BbrConfig::default()))
+            }
+        };
+    }
+
+    /// Binds a server endpoint accepting UDP payloads up to
+    /// `max_udp_payload_size`. With `reuse_port` the socket is bound with
+    /// SO_REUSEPORT, so several endpoints can share the port.
+    pub(crate) fn server_endpoint(
+        &self,
+        server_config: quinn::ServerConfig,
+        address: SocketAddr,
+        reuse_port: bool,
+    ) -> anyhow::Result<quinn::Endpoint> {
+        self.validate()?;
+        let mut endpoint_config = EndpointConfig::default();
+        endpoint_config
+            .max_udp_payload_size(self.max_udp_payload_size)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        let runtime =
+            quinn::default_runtime().ok_or_else(|| anyhow::anyhow!("no async runtime found"))?;
+        let socket = if reuse_port {
+            bind_reuse_port(address)?
+        } else {
+            std::net::UdpSocket::bind(address)?
+        };
+        Ok(quinn::Endpoint::new(
+            endpoint_config,
+            Some(server_config),
+            socket,
+            runtime,
+        )?)
+    }
+}
+
+#[cfg(unix)]
+fn bind_reuse_port(address: SocketAddr) -> anyhow::Result<std::net::UdpSocket> {
+    let socket = socket2::Socket::new(
+        socket2::Domain::for_address(address),
+        socket2::Type::DGRAM,
+        Some(socket2::Protocol::UDP),
+    )?;
+    socket.set_reuse_port(true)?;
+    socket.bind(&address.into())?;
+    Ok(socket.into())
+}
+
+#[cfg(not(unix))]
+fn bind_reuse_port(_address: SocketAddr) -> anyhow::Result<std::net::UdpSocket> {
+    anyhow::bail!("SO_REUSEPORT is not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    mod success {
+        use crate::modules::transport::transport_tuning::{CongestionController, TransportTuning};
+
+        #[test]
+        fn default_tuning_is_valid() {
+            // execution / validation
+            assert!(TransportTuning::default().validate().is_ok());
+        }
+
+        #[test]
+        fn congestion_controller_from_name() {
+            // execution / validation
+            assert_eq!(
+                "bbr".parse::<CongestionController>().unwrap(),
+                CongestionController::Bbr
+            );
+            assert_eq!(
+                "new_reno".parse::<CongestionController>().unwrap(),
+                CongestionController::NewReno
+            );
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn reuse_port_sockets_share_a_port() {
+            // setup
+            let first = super::super::bind_reuse_port("127.0.0.1:0".parse().unwrap()).unwrap();
+            let address = first.local_addr().unwrap();
+
+            // execution
+            let second = super::super::bind_reuse_port(address);
+
+            // validation
+            assert_eq!(second.unwrap().local_addr().unwrap(), address);
+        }
+    }
+
+    mod failure {
+        use crate::modules::transport::transport_tuning::{CongestionController, TransportTuning};
+
+        #[test]
+        fn out_of_range_values_are_rejected() {
+            // setup
+            let tuning = TransportTuning::default();
+
+            // execution / validation
+            assert!(
+                TransportTuning {
+                    max_udp_payload_size: 1199,
+                    ..tuning
+                }
+                .validate()
+                .is_err()
+            );
+            assert!(
+                TransportTuning {
+                    receive_window: u64::MAX,
+                    ..tuning
+                }
+                .validate()
+                .is_err()
+            );
+            assert!(
+                TransportTuning {
+                    packet_threshold: 2,
+                    ..tuning
+                }
+                .validate()
+                .is_err()
+            );
+        }
+
+        #[test]
+        fn unknown_congestion_controller() {
+            // execution / validation
+            assert!("vegas".parse::<CongestionController>().is_err());
+        }
+    }
+}
@@ -2,7 +2,9 @@ use async_trait::async_trait;
 
 use super::wt_receive_stream::WtReceiveStream;
 use super::wt_send_stream::WtSendStream;
-use crate::modules::transport::transport_connection::TransportConnection;
+use crate::modules::transport::{
+    connection_stats::ConnectionStats, transport_connection::TransportConnection,
+};
 
 #[derive(Debug)]
 pub struct WtConnection {
@@ -69,4 +71,9 @@ impl TransportConnection for WtConnection {
             }
         }
     }
+
+    fn stats(&self) -> ConnectionStats {
+        // The session derefs to its QUIC connection.
+        ConnectionStats::from_quinn(&self.session)
+    }
 }
@@ -13,7 +13,7 @@ use super::wt_connection::WtConnection;
 use crate::modules::transport::{
     crypto_provider::install_default_crypto_provider,
     quic::skip_certd_validation::SkipVerification,
-    transport_connection_creator::TransportConnectionCreator,
+    transport_connection_creator::TransportConnectionCreator, transport_tuning::TransportTuning,
 };
 
 enum WtEndpoint {
@@ -96,7 +96,12 @@ impl TransportConnectionCreator for WtConnectionCreator {
         key_path: &str,
         port_num: u16,
         _keep_alive_sec: u64,
+        _tuning: &TransportTuning,
+        reuse_port: bool,
     ) -> anyhow::Result<Self> {
+        if reuse_port {
+            anyhow::bail!("the WebTransport server binds a single endpoint per port");
+        }
         install_default_crypto_provider();
 
         // 証明書を同期的に読み込む
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 
 use crate::modules::transport::transport_send_stream::{TransportSendError, TransportSendStream};
 
@@ -17,6 +17,13 @@ impl TransportSendStream for WtSendStream {
             .map_err(webtransport_write_error_to_transport_send_error)
     }
 
+    async fn send_chunks(&mut self, chunks: &mut [Bytes]) -> Result<(), TransportSendError> {
+        self.send_stream
+            .write_all_chunks(chunks)
+            .await
+            .map_err(webtransport_write_error_to_transport_send_error)
+    }
+
     async fn close(&mut self) -> Result<(), TransportSendError> {
         self.send_stream
             .finish()
@@ -32,6 +39,12 @@ impl TransportSendStream for WtSendStream {
             .reset(error_code)
             .map_err(|_| TransportSendError::ClosedStream)
     }
+
+    fn set_priority(&self, priority: i32) -> Result<(), TransportSendError> {
+        self.send_stream
+            .set_priority(priority)
+            .map_err(|_| TransportSendError::ClosedStream)
+    }
 }
 
 fn webtransport_write_error_to_transport_send_error(
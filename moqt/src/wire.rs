@@ -5,6 +5,7 @@ use std::io::Cursor;
 pub use crate::modules::extensions::buf_get_ext::BufGetExt;
 pub use crate::modules::extensions::buf_put_ext::BufPutExt;
 pub use crate::modules::moqt::control_plane::constants::MOQ_TRANSPORT_VERSION;
+pub use crate::modules::moqt::control_plane::version::ProtocolVersion;
 pub use crate::modules::moqt::control_plane::control_messages::control_message_type::ControlMessageType;
 pub use crate::modules::moqt::control_plane::control_messages::messages::client_setup::ClientSetup;
 pub use crate::modules::moqt::control_plane::control_messages::messages::namespace_ok::NamespaceOk;
@@ -16,19 +17,33 @@ pub use crate::modules::moqt::control_plane::control_messages::messages::paramet
 pub use crate::modules::moqt::control_plane::control_messages::messages::parameters::setup_parameters::SetupParameter;
 pub use crate::modules::moqt::control_plane::control_messages::messages::fetch::Fetch;
 pub use crate::modules::moqt::control_plane::control_messages::messages::fetch::FetchParams;
+pub use crate::modules::moqt::control_plane::control_messages::messages::fetch_cancel::FetchCancel;
+pub use crate::modules::moqt::control_plane::control_messages::messages::go_away::GoAway;
+pub use crate::modules::moqt::control_plane::control_messages::messages::max_request_id::MaxRequestId;
 pub use crate::modules::moqt::control_plane::control_messages::messages::fetch_ok::FetchOk;
 pub use crate::modules::moqt::data_plane::object::fetch::FetchHeader;
 pub use crate::modules::moqt::data_plane::object::fetch::FetchObject;
 pub use crate::modules::moqt::data_plane::object::fetch::FetchObjectField;
 pub use crate::modules::moqt::control_plane::control_messages::messages::publish::Publish;
+pub use crate::modules::moqt::control_plane::control_messages::messages::publish_done::PublishDone;
 pub use crate::modules::moqt::control_plane::control_messages::messages::publish_namespace::PublishNamespace;
+pub use crate::modules::moqt::control_plane::control_messages::messages::publish_namespace_cancel::PublishNamespaceCancel;
 pub use crate::modules::moqt::control_plane::control_messages::messages::publish_namespace_done::PublishNamespaceDone;
 pub use crate::modules::moqt::control_plane::control_messages::messages::publish_ok::PublishOk;
 pub use crate::modules::moqt::control_plane::control_messages::messages::request_error::RequestError;
+pub use crate::modules::moqt::control_plane::control_messages::messages::requests_blocked::RequestsBlocked;
 pub use crate::modules::moqt::control_plane::control_messages::messages::server_setup::ServerSetup;
 pub use crate::modules::moqt::control_plane::control_messages::messages::subscribe::Subscribe;
 pub use crate::modules::moqt::control_plane::control_messages::messages::subscribe_namespace::SubscribeNamespace;
 pub use crate::modules::moqt::control_plane::control_messages::messages::subscribe_ok::SubscribeOk;
+pub use crate::modules::moqt::control_plane::control_messages::messages::subscribe_update::SubscribeUpdate;
+pub use crate::modules::moqt::control_plane::control_messages::messages::track_status::TrackStatus;
+pub use crate::modules::moqt::control_plane::control_messages::messages::track_status_request::TrackStatusRequest;
+pub use crate::modules::moqt::control_plane::control_messages::messages::unsubscribe_namespace::UnsubscribeNamespace;
+pub use crate::modules::moqt::data_plane::bandwidth_probe::{
+    BANDWIDTH_PROBE_NAMESPACE, BANDWIDTH_PROBE_OBJECT_SIZE, BandwidthEstimate, BandwidthProbeMeter,
+    bandwidth_probe_track_name, parse_bandwidth_probe_track_name,
+};
 pub use crate::modules::moqt::data_plane::object::datagram_field::DatagramField;
 pub use crate::modules::moqt::data_plane::object::datagram_field::ObjectDatagramPayload;
 pub use crate::modules::moqt::data_plane::object::decode_error::DecodeError;
@@ -38,6 +53,9 @@ pub use crate::modules::moqt::data_plane::object::object_status::ObjectStatus;
 pub use crate::modules::moqt::data_plane::object::subgroup::{
     SubgroupHeader, SubgroupHeaderType, SubgroupId, SubgroupObject, SubgroupObjectField,
 };
+pub use crate::modules::moqt::data_plane::object::subgroup_stream_object::{
+    SubgroupStreamContext, SubgroupStreamObject,
+};
 
 pub type PublishNamespaceOk = NamespaceOk;
 pub type SubscribeNamespaceOk = NamespaceOk;
@@ -46,6 +64,7 @@ pub type SubscribeNamespaceError = RequestError;
 pub type PublishError = RequestError;
 pub type SubscribeError = RequestError;
 pub type FetchError = RequestError;
+pub type TrackStatusError = RequestError;
 
 pub fn encode_control_message(message_type: ControlMessageType, payload: BytesMut) -> BytesMut {
     let mut buf = BytesMut::new();
@@ -56,6 +75,15 @@ pub fn encode_control_message(message_type: ControlMessageType, payload: BytesMu
     buf
 }
 
+/// Message Length of the control message at the start of `buf`, once its
+/// header has arrived, so a frame can be judged before its payload is
+/// buffered.
+pub(crate) fn control_message_payload_length(buf: &[u8]) -> Option<usize> {
+    let mut cursor = Cursor::new(buf);
+    cursor.try_get_varint().ok()?;
+    cursor.try_get_u16().ok().map(usize::from)
+}
+
 pub fn take_control_message(
     buf: &mut BytesMut,
 ) -> anyhow::Result<Option<(ControlMessageType, BytesMut)>> {
@@ -1,3 +1,56 @@
+mod authorizer;
+mod cache_limits;
+mod config_file;
+mod egress_tuning;
+mod namespace_acl;
+mod namespace_policy;
+mod rate_limits;
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context;
+
+use crate::logging::LogFormat;
+
+pub use authorizer::{AuthorizationRequest, Authorizer};
+pub use cache_limits::CacheLimits;
+pub use egress_tuning::{EgressTuning, ForwardingPreference, QueueOverflowPolicy};
+pub use namespace_acl::{AclRole, NamespaceAclRule, NamespaceAclTable};
+pub use namespace_policy::{NamespacePolicy, NamespacePolicyTable};
+pub use rate_limits::{OverLimitAction, RateLimits};
+
+pub(crate) const DEFAULT_KEEP_ALIVE_INTERVAL_SEC: u64 = 15;
+const DEFAULT_TLS_POLL_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_SETUP_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_CONTROL_MAX_MESSAGE_BYTES: usize = 16 * 1024;
+const DEFAULT_CONTROL_MAX_MESSAGES_PER_SEC: u32 = 500;
+
+fn comma_separated(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Splits a `host:port` parent relay address.
+pub(crate) fn parent_relay_address(value: &str) -> anyhow::Result<(String, u16)> {
+    let (host, port) = value
+        .rsplit_once(':')
+        .with_context(|| format!("parent relay `{value}` is not host:port"))?;
+    let port = port
+        .parse()
+        .with_context(|| format!("invalid parent relay port in `{value}`"))?;
+    Ok((host.to_string(), port))
+}
+
+/// `0` turns the interval or timeout off.
+fn optional_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
 #[derive(Clone, Debug)]
 pub struct RelayConfig {
     pub relay_id: String,
@@ -5,6 +58,76 @@ pub struct RelayConfig {
     pub port: u16,
     pub inner_port: u16,
     pub redis_url: Option<String>,
+    /// `host:port` of a parent relay's inner endpoint. Tracks and namespaces
+    /// this relay has no publisher for are requested from it, so relays can
+    /// be chained into a distribution tree without a route registry.
+    pub parent_relay: Option<String>,
+    /// New Session URI sent in GOAWAY on shutdown; empty means reconnect to
+    /// the same URI.
+    pub goaway_uri: String,
+    pub drain_timeout: Duration,
+    /// Per-subscription billing records are appended here as JSON lines.
+    pub billing_file: Option<PathBuf>,
+    /// Per-subscription billing records are POSTed here as JSON.
+    pub billing_webhook_url: Option<String>,
+    /// Largest bandwidth probe track the relay serves; 0 disables probing.
+    pub bandwidth_probe_max_bytes: u64,
+    /// Per-namespace overrides of the subscriber priority and group order
+    /// requested in SUBSCRIBE.
+    pub namespace_policies: NamespacePolicyTable,
+    /// Checks PUBLISH_NAMESPACE, SUBSCRIBE and SUBSCRIBE_NAMESPACE from
+    /// clients. The callback is set in code; `from_env` loads the namespace
+    /// ACL from `RELAY_NAMESPACE_ACL`.
+    pub authorizer: Authorizer,
+    /// Per-session qlog files of MoQ events (control messages, forwarded
+    /// objects, FETCH cache lookups) are written here.
+    pub qlog_dir: Option<PathBuf>,
+    /// Stdout tracing filter; `None` leaves it to `RELAY_STDOUT_FILTER` /
+    /// `RUST_LOG`.
+    pub log_filter: Option<String>,
+    /// Stdout log format, from `RELAY_LOG_FORMAT` (`text`, the default, or
+    /// `json`).
+    pub log_format: LogFormat,
+    /// TLS certificate and key in PEM. Without them the relay binary uses
+    /// a self-signed pair it generates under `relay/keys`.
+    pub tls_cert_path: Option<PathBuf>,
+    pub tls_key_path: Option<PathBuf>,
+    /// How often the certificate and key files are checked for a renewal,
+    /// which is then served to new connections; `None` only reloads on
+    /// [`RelayServer::reload_certificates`](crate::RelayServer::reload_certificates).
+    pub tls_poll_interval: Option<Duration>,
+    pub keep_alive_interval_sec: u64,
+    /// Connections that have not sent CLIENT_SETUP this long after the QUIC
+    /// handshake are closed with CONTROL_MESSAGE_TIMEOUT.
+    pub setup_timeout: Option<Duration>,
+    /// Client sessions, handshaking or established, served at once; further
+    /// connections are closed on accept. Peer relays are not counted.
+    pub max_client_sessions: Option<usize>,
+    /// Endpoints the client port is bound with, sharing it through
+    /// SO_REUSEPORT, each with its own QUIC driver and accept loop. Clients
+    /// whose address changes mid-session may be dropped when above 1.
+    pub accept_workers: usize,
+    /// Largest control message and control message rate accepted from a
+    /// client; a client past them is closed with PROTOCOL_VIOLATION. Peer
+    /// relays get the size limit only.
+    pub control_message_limits: moqt::ControlMessageLimits,
+    /// QUIC windows, loss detection, UDP payload size and congestion
+    /// controller of both endpoints.
+    pub transport: moqt::TransportTuning,
+    pub cache_limits: CacheLimits,
+    /// Byte rates of what publishers send, per session and per track.
+    pub rate_limits: RateLimits,
+    /// Delivery to subscribers: forwarding conversion, start point, stall
+    /// detection and the per-subscription send queue.
+    pub egress: EgressTuning,
+    /// Tracks in `record_namespaces` are recorded here as they flow through
+    /// the relay; `None` records nothing.
+    pub record_dir: Option<PathBuf>,
+    /// Namespace prefixes to record.
+    pub record_namespaces: Vec<String>,
+    /// Recordings the relay binary announces and serves on start, via
+    /// [`RelayServer::replay_recording`](crate::RelayServer::replay_recording).
+    pub replay_files: Vec<PathBuf>,
 }
 
 impl RelayConfig {
@@ -23,13 +146,145 @@ impl RelayConfig {
             .transpose()?
             .unwrap_or(port + 1);
         let redis_url = std::env::var("REDIS_URL").ok();
-
-        Ok(Self {
+        let parent_relay = std::env::var("RELAY_PARENT").ok();
+        let goaway_uri = std::env::var("RELAY_GOAWAY_URI").unwrap_or_default();
+        let drain_timeout = std::env::var("RELAY_DRAIN_TIMEOUT_SEC")
+            .ok()
+            .map(|value| value.parse::<u64>())
+            .transpose()?
+            .map_or(Duration::from_secs(10), Duration::from_secs);
+        let billing_file = std::env::var("RELAY_BILLING_FILE").ok().map(PathBuf::from);
+        let billing_webhook_url = std::env::var("RELAY_BILLING_WEBHOOK_URL").ok();
+        let qlog_dir = std::env::var("RELAY_QLOG_DIR").ok().map(PathBuf::from);
+        let log_format = std::env::var("RELAY_LOG_FORMAT")
+            .ok()
+            .map(|value| value.parse::<LogFormat>())
+            .transpose()?
+            .unwrap_or_default();
+        let tls_cert_path = std::env::var("RELAY_TLS_CERT_PATH").ok().map(PathBuf::from);
+        let tls_key_path = std::env::var("RELAY_TLS_KEY_PATH").ok().map(PathBuf::from);
+        let tls_poll_interval = std::env::var("RELAY_TLS_POLL_INTERVAL_SEC")
+            .ok()
+            .map(|value| value.parse::<u64>())
+            .transpose()?
+            .map_or(Some(DEFAULT_TLS_POLL_INTERVAL), optional_secs);
+        let setup_timeout = std::env::var("RELAY_SETUP_TIMEOUT_SEC")
+            .ok()
+            .map(|value| value.parse::<u64>())
+            .transpose()?
+            .map_or(Some(DEFAULT_SETUP_TIMEOUT), optional_secs);
+        let max_client_sessions = std::env::var("RELAY_MAX_CLIENT_SESSIONS")
+            .ok()
+            .map(|value| value.parse::<usize>())
+            .transpose()?;
+        let accept_workers = std::env::var("RELAY_ACCEPT_WORKERS")
+            .ok()
+            .map(|value| value.parse::<usize>())
+            .transpose()?
+            .unwrap_or(1);
+        let control_message_limits = moqt::ControlMessageLimits {
+            max_message_bytes: std::env::var("RELAY_CONTROL_MAX_MESSAGE_BYTES")
+                .ok()
+                .map(|value| value.parse::<usize>())
+                .transpose()?
+                .unwrap_or(DEFAULT_CONTROL_MAX_MESSAGE_BYTES),
+            max_messages_per_sec: std::env::var("RELAY_CONTROL_MAX_MESSAGES_PER_SEC")
+                .ok()
+                .map(|value| value.parse::<u32>())
+                .transpose()?
+                .unwrap_or(DEFAULT_CONTROL_MAX_MESSAGES_PER_SEC),
+        };
+        let bandwidth_probe_max_bytes = std::env::var("RELAY_BANDWIDTH_PROBE_MAX_BYTES")
+            .ok()
+            .map(|value| value.parse::<u64>())
+            .transpose()?
+            .unwrap_or(0);
+        let namespace_policies = std::env::var("RELAY_NAMESPACE_POLICIES")
+            .ok()
+            .map(|value| NamespacePolicyTable::parse(&value))
+            .transpose()?
+            .unwrap_or_default();
+        let namespace_acl = std::env::var("RELAY_NAMESPACE_ACL")
+            .ok()
+            .map(|value| NamespaceAclTable::parse(&value))
+            .transpose()?
+            .unwrap_or_default();
+        let record_dir = std::env::var("RELAY_RECORD_DIR").ok().map(PathBuf::from);
+        let record_namespaces = std::env::var("RELAY_RECORD_NAMESPACES")
+            .map(|value| comma_separated(&value).map(str::to_string).collect())
+            .unwrap_or_default();
+        let replay_files = std::env::var("RELAY_REPLAY_FILES")
+            .map(|value| comma_separated(&value).map(PathBuf::from).collect())
+            .unwrap_or_default();
+        let config = Self {
             relay_id,
             advertise_host,
             port,
             inner_port,
             redis_url,
-        })
+            parent_relay,
+            goaway_uri,
+            drain_timeout,
+            billing_file,
+            billing_webhook_url,
+            bandwidth_probe_max_bytes,
+            namespace_policies,
+            authorizer: Authorizer::default().with_namespace_acl(namespace_acl),
+            qlog_dir,
+            log_filter: None,
+            log_format,
+            tls_cert_path,
+            tls_key_path,
+            tls_poll_interval,
+            keep_alive_interval_sec: DEFAULT_KEEP_ALIVE_INTERVAL_SEC,
+            setup_timeout,
+            max_client_sessions,
+            accept_workers,
+            control_message_limits,
+            transport: moqt::TransportTuning::default(),
+            cache_limits: CacheLimits::from_env()?,
+            rate_limits: RateLimits::from_env()?,
+            egress: EgressTuning::from_env()?,
+            record_dir,
+            record_namespaces,
+            replay_files,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads a TOML file over [`from_env`](Self::from_env): settings the
+    /// file leaves out keep their environment or default value. Top-level
+    /// keys match the field names and the rest sit in sections such as
+    /// `[tls]` or `[cache]`; `relay/relay.example.toml` lists every key.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read relay config {}", path.display()))?;
+        let mut config = Self::from_env()?;
+        config_file::RelayConfigFile::parse(&contents)
+            .and_then(|file| file.apply(&mut config))
+            .with_context(|| format!("invalid relay config {}", path.display()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(parent_relay) = &self.parent_relay {
+            parent_relay_address(parent_relay)?;
+        }
+        if self.record_dir.is_none() && !self.record_namespaces.is_empty() {
+            anyhow::bail!("set the recording directory to record namespaces");
+        }
+        if self.billing_file.is_some() && self.billing_webhook_url.is_some() {
+            anyhow::bail!("set only one of the billing file and the billing webhook URL");
+        }
+        if self.tls_cert_path.is_some() != self.tls_key_path.is_some() {
+            anyhow::bail!("set both or neither of the TLS certificate and key paths");
+        }
+        self.transport
+            .validate()
+            .context("invalid transport tuning")?;
+        Ok(())
     }
 }
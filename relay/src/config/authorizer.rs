@@ -0,0 +1,154 @@
+use std::{fmt, sync::Arc};
+
+use super::namespace_acl::{NamespaceAclTable, UNAUTHORIZED};
+
+/// A client request the relay asks the [`Authorizer`] about, with the
+/// AUTHORIZATION TOKEN parameter it carried, if any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorizationRequest<'a> {
+    PublishNamespace {
+        track_namespace: &'a str,
+        authorization_token: Option<&'a str>,
+    },
+    Subscribe {
+        track_namespace: &'a str,
+        track_name: &'a str,
+        authorization_token: Option<&'a str>,
+    },
+    SubscribeNamespace {
+        track_namespace_prefix: &'a str,
+        authorization_token: Option<&'a str>,
+    },
+}
+
+impl AuthorizationRequest<'_> {
+    pub fn authorization_token(&self) -> Option<&str> {
+        match self {
+            Self::PublishNamespace {
+                authorization_token,
+                ..
+            }
+            | Self::Subscribe {
+                authorization_token,
+                ..
+            }
+            | Self::SubscribeNamespace {
+                authorization_token,
+                ..
+            } => *authorization_token,
+        }
+    }
+}
+
+type AuthorizeFn = dyn Fn(&AuthorizationRequest<'_>) -> bool + Send + Sync;
+
+/// Decides whether a client may publish a namespace, subscribe to a track or
+/// subscribe to a namespace prefix: first the namespace ACL, then the
+/// callback. A denied request is answered with the matching `*_ERROR`, the
+/// ACL rule's error code or UNAUTHORIZED. Requests arriving from peer relays
+/// are not checked again. Without either every request is allowed.
+#[derive(Clone, Default)]
+pub struct Authorizer {
+    callback: Option<Arc<AuthorizeFn>>,
+    namespace_acl: Arc<NamespaceAclTable>,
+}
+
+impl Authorizer {
+    pub fn new(
+        callback: impl Fn(&AuthorizationRequest<'_>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            callback: Some(Arc::new(callback)),
+            namespace_acl: Arc::default(),
+        }
+    }
+
+    pub fn with_namespace_acl(mut self, namespace_acl: NamespaceAclTable) -> Self {
+        self.namespace_acl = Arc::new(namespace_acl);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.callback.is_some() || !self.namespace_acl.is_empty()
+    }
+
+    /// `Err` with the error code to answer a denied request with.
+    pub(crate) fn authorize(&self, request: &AuthorizationRequest<'_>) -> Result<(), u64> {
+        self.namespace_acl.check(request)?;
+        if self
+            .callback
+            .as_ref()
+            .is_none_or(|callback| callback(request))
+        {
+            Ok(())
+        } else {
+            Err(UNAUTHORIZED)
+        }
+    }
+}
+
+impl fmt::Debug for Authorizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Authorizer")
+            .field("callback", &self.callback.is_some())
+            .field("namespace_acl", &self.namespace_acl)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn without_callback_every_request_is_allowed() {
+        // Arrange
+        let authorizer = Authorizer::default();
+        let request = AuthorizationRequest::SubscribeNamespace {
+            track_namespace_prefix: "live",
+            authorization_token: None,
+        };
+
+        // Act
+        let result = authorizer.authorize(&request);
+
+        // Assert
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn callback_sees_the_request_token() {
+        // Arrange
+        let authorizer = Authorizer::new(|request| request.authorization_token() == Some("secret"));
+        let subscribe = |authorization_token| AuthorizationRequest::Subscribe {
+            track_namespace: "live",
+            track_name: "video",
+            authorization_token,
+        };
+
+        // Act / Assert
+        assert_eq!(authorizer.authorize(&subscribe(Some("secret"))), Ok(()));
+        assert_eq!(
+            authorizer.authorize(&subscribe(Some("guess"))),
+            Err(UNAUTHORIZED)
+        );
+        assert_eq!(authorizer.authorize(&subscribe(None)), Err(UNAUTHORIZED));
+    }
+
+    #[test]
+    fn namespace_acl_is_checked_before_the_callback() {
+        // Arrange
+        let authorizer = Authorizer::new(|_| true).with_namespace_acl(
+            NamespaceAclTable::parse("live/*,subscribe=viewer,error=0x4").unwrap(),
+        );
+        let subscribe = |authorization_token| AuthorizationRequest::Subscribe {
+            track_namespace: "live/a",
+            track_name: "video",
+            authorization_token,
+        };
+
+        // Act / Assert
+        assert_eq!(authorizer.authorize(&subscribe(Some("viewer"))), Ok(()));
+        assert_eq!(authorizer.authorize(&subscribe(None)), Err(0x4));
+    }
+}
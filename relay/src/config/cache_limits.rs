@@ -0,0 +1,102 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
+use crate::modules::relay::cache::{
+    disk_tier::{DEFAULT_DISK_RETENTION_SECS, DiskTier},
+    duration::duration_from_env,
+    eviction_job::DEFAULT_TTL_SECS,
+    quota::{CacheQuotaConfig, DEFAULT_SESSION_QUOTA_BYTES},
+};
+
+use super::egress_tuning::env_value;
+
+/// How long and how much the relay caches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheLimits {
+    /// How long objects stay cached; also the MAX_CACHE_DURATION granted to
+    /// downstream subscribers.
+    pub ttl: Duration,
+    /// Bytes cached per (upstream session, tenant) budget; 0 is unlimited.
+    pub session_quota_bytes: u64,
+    /// Per-tenant overrides of `session_quota_bytes`, the tenant being the
+    /// first element of the track namespace.
+    pub tenant_quota_bytes: HashMap<String, u64>,
+    /// Bytes cached per track; 0 is unlimited.
+    pub track_quota_bytes: u64,
+    /// Bytes cached across all tracks; 0 is unlimited. Over any budget the
+    /// oldest whole groups are evicted first.
+    pub global_quota_bytes: u64,
+    /// Directory evicted groups are spilled to, so FETCH can still serve
+    /// them; `None` keeps the cache in memory only.
+    pub disk_dir: Option<PathBuf>,
+    /// How long spilled groups stay on disk.
+    pub disk_retention: Duration,
+}
+
+impl Default for CacheLimits {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(DEFAULT_TTL_SECS),
+            session_quota_bytes: DEFAULT_SESSION_QUOTA_BYTES,
+            tenant_quota_bytes: HashMap::new(),
+            track_quota_bytes: 0,
+            global_quota_bytes: 0,
+            disk_dir: None,
+            disk_retention: Duration::from_secs(DEFAULT_DISK_RETENTION_SECS),
+        }
+    }
+}
+
+impl CacheLimits {
+    /// Reads `RELAY_CACHE_TTL_SECS` (default 30),
+    /// `RELAY_CACHE_SESSION_QUOTA_BYTES` (default 256 MiB),
+    /// `RELAY_CACHE_TENANT_QUOTA_BYTES` (`tenant=bytes,tenant=bytes`),
+    /// `RELAY_CACHE_TRACK_QUOTA_BYTES` and `RELAY_CACHE_GLOBAL_QUOTA_BYTES`
+    /// (default 0, unlimited), `RELAY_CACHE_DISK_DIR` (unset, no disk tier)
+    /// and `RELAY_CACHE_DISK_RETENTION_SECS` (default 300). A byte quota that
+    /// does not parse fails startup rather than lifting the cap.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let bytes_from_env = |name: &str, default: u64| {
+            env_value(name, |value| Ok(value.parse::<u64>()?)).map(|bytes| bytes.unwrap_or(default))
+        };
+        let session_quota_bytes = bytes_from_env(
+            "RELAY_CACHE_SESSION_QUOTA_BYTES",
+            DEFAULT_SESSION_QUOTA_BYTES,
+        )?;
+        let tenant_quota_bytes = std::env::var("RELAY_CACHE_TENANT_QUOTA_BYTES")
+            .map(|value| CacheQuotaConfig::parse_tenant_overrides(&value))
+            .unwrap_or_default();
+        Ok(Self {
+            ttl: duration_from_env("RELAY_CACHE_TTL_SECS", DEFAULT_TTL_SECS),
+            session_quota_bytes,
+            tenant_quota_bytes,
+            track_quota_bytes: bytes_from_env("RELAY_CACHE_TRACK_QUOTA_BYTES", 0)?,
+            global_quota_bytes: bytes_from_env("RELAY_CACHE_GLOBAL_QUOTA_BYTES", 0)?,
+            disk_dir: std::env::var_os("RELAY_CACHE_DISK_DIR")
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+            disk_retention: duration_from_env(
+                "RELAY_CACHE_DISK_RETENTION_SECS",
+                DEFAULT_DISK_RETENTION_SECS,
+            ),
+        })
+    }
+
+    pub(crate) fn quota_config(&self) -> CacheQuotaConfig {
+        CacheQuotaConfig::new(self.session_quota_bytes, self.tenant_quota_bytes.clone())
+            .with_track_quota_bytes(self.track_quota_bytes)
+            .with_global_quota_bytes(self.global_quota_bytes)
+    }
+
+    /// Opens the disk tier. A directory that cannot be prepared leaves the
+    /// cache in memory only rather than failing startup.
+    pub(crate) fn disk_tier(&self) -> Option<Arc<DiskTier>> {
+        let dir = self.disk_dir.as_ref()?;
+        match DiskTier::open(dir, self.disk_retention) {
+            Ok(disk_tier) => Some(disk_tier),
+            Err(error) => {
+                tracing::error!(%error, dir = %dir.display(), "Failed to open cache disk tier");
+                None
+            }
+        }
+    }
+}
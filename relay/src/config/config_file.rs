@@ -0,0 +1,448 @@
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use super::{NamespaceAclTable, NamespacePolicyTable, RelayConfig};
+
+/// TOML layout of [`RelayConfig::from_file`]. Every setting is optional and
+/// unknown keys are rejected so a typo does not silently do nothing.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct RelayConfigFile {
+    relay_id: Option<String>,
+    advertise_host: Option<String>,
+    port: Option<u16>,
+    inner_port: Option<u16>,
+    redis_url: Option<String>,
+    parent_relay: Option<String>,
+    goaway_uri: Option<String>,
+    drain_timeout_sec: Option<u64>,
+    billing_file: Option<PathBuf>,
+    billing_webhook_url: Option<String>,
+    bandwidth_probe_max_bytes: Option<u64>,
+    qlog_dir: Option<PathBuf>,
+    /// Stdout tracing filter, e.g. `relay=debug,moqt=info`.
+    log_filter: Option<String>,
+    /// `text` or `json`.
+    log_format: Option<String>,
+    /// `0` turns the setup timeout off.
+    setup_timeout_sec: Option<u64>,
+    max_client_sessions: Option<usize>,
+    accept_workers: Option<usize>,
+    #[serde(default)]
+    tls: TlsSection,
+    #[serde(default)]
+    control: ControlSection,
+    #[serde(default)]
+    transport: TransportSection,
+    #[serde(default)]
+    cache: CacheSection,
+    #[serde(default)]
+    rate_limit: RateLimitSection,
+    #[serde(default)]
+    egress: EgressSection,
+    #[serde(default)]
+    recording: RecordingSection,
+    #[serde(default)]
+    auth: AuthSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TlsSection {
+    cert_path: Option<PathBuf>,
+    key_path: Option<PathBuf>,
+    /// `0` turns polling for renewed files off.
+    poll_interval_sec: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ControlSection {
+    max_message_bytes: Option<usize>,
+    /// `0` turns the rate limit off.
+    max_messages_per_sec: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransportSection {
+    keep_alive_interval_sec: Option<u64>,
+    send_window: Option<u64>,
+    receive_window: Option<u64>,
+    stream_receive_window: Option<u32>,
+    packet_threshold: Option<u32>,
+    max_udp_payload_size: Option<u16>,
+    /// `cubic`, `new_reno` or `bbr`.
+    congestion_controller: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CacheSection {
+    ttl_secs: Option<u64>,
+    session_quota_bytes: Option<u64>,
+    tenant_quota_bytes: Option<HashMap<String, u64>>,
+    track_quota_bytes: Option<u64>,
+    global_quota_bytes: Option<u64>,
+    disk_dir: Option<PathBuf>,
+    disk_retention_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateLimitSection {
+    session_bytes_per_sec: Option<u64>,
+    track_bytes_per_sec: Option<u64>,
+    /// `pace` or `drop`.
+    over_limit: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EgressSection {
+    /// `as_published`, `subgroup` or `datagram`.
+    client_forwarding: Option<String>,
+    client_keyframe_start: Option<bool>,
+    /// `0` turns stall detection off.
+    stall_ms: Option<u64>,
+    /// `0` leaves the send queue unbounded.
+    queue_groups: Option<usize>,
+    /// `pause`, `drop_oldest` or `disconnect`.
+    queue_overflow: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecordingSection {
+    dir: Option<PathBuf>,
+    /// Namespace prefixes to record.
+    namespaces: Option<Vec<String>>,
+    /// Recordings to replay on start.
+    replay: Option<Vec<PathBuf>>,
+}
+
+/// Tables in the same syntax as their environment variables.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AuthSection {
+    namespace_acl: Option<String>,
+    namespace_policies: Option<String>,
+}
+
+impl RelayConfigFile {
+    pub(super) fn parse(contents: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// Overwrites the settings of `config` the file sets.
+    pub(super) fn apply(self, config: &mut RelayConfig) -> anyhow::Result<()> {
+        fn set<T>(target: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *target = value;
+            }
+        }
+
+        set(&mut config.relay_id, self.relay_id);
+        set(&mut config.advertise_host, self.advertise_host);
+        set(&mut config.port, self.port);
+        set(&mut config.inner_port, self.inner_port);
+        set(&mut config.redis_url, self.redis_url.map(Some));
+        set(&mut config.parent_relay, self.parent_relay.map(Some));
+        set(&mut config.goaway_uri, self.goaway_uri);
+        set(
+            &mut config.drain_timeout,
+            self.drain_timeout_sec.map(Duration::from_secs),
+        );
+        set(&mut config.billing_file, self.billing_file.map(Some));
+        set(
+            &mut config.billing_webhook_url,
+            self.billing_webhook_url.map(Some),
+        );
+        set(
+            &mut config.bandwidth_probe_max_bytes,
+            self.bandwidth_probe_max_bytes,
+        );
+        set(&mut config.qlog_dir, self.qlog_dir.map(Some));
+        set(&mut config.log_filter, self.log_filter.map(Some));
+        if let Some(format) = self.log_format {
+            config.log_format = format.parse()?;
+        }
+        set(
+            &mut config.setup_timeout,
+            self.setup_timeout_sec.map(super::optional_secs),
+        );
+        set(
+            &mut config.max_client_sessions,
+            self.max_client_sessions.map(Some),
+        );
+        set(&mut config.accept_workers, self.accept_workers);
+
+        set(&mut config.tls_cert_path, self.tls.cert_path.map(Some));
+        set(&mut config.tls_key_path, self.tls.key_path.map(Some));
+        set(
+            &mut config.tls_poll_interval,
+            self.tls.poll_interval_sec.map(super::optional_secs),
+        );
+
+        set(
+            &mut config.control_message_limits.max_message_bytes,
+            self.control.max_message_bytes,
+        );
+        set(
+            &mut config.control_message_limits.max_messages_per_sec,
+            self.control.max_messages_per_sec,
+        );
+
+        set(
+            &mut config.keep_alive_interval_sec,
+            self.transport.keep_alive_interval_sec,
+        );
+        set(
+            &mut config.transport.send_window,
+            self.transport.send_window,
+        );
+        set(
+            &mut config.transport.receive_window,
+            self.transport.receive_window,
+        );
+        set(
+            &mut config.transport.stream_receive_window,
+            self.transport.stream_receive_window,
+        );
+        set(
+            &mut config.transport.packet_threshold,
+            self.transport.packet_threshold,
+        );
+        set(
+            &mut config.transport.max_udp_payload_size,
+            self.transport.max_udp_payload_size,
+        );
+        if let Some(name) = self.transport.congestion_controller {
+            config.transport.congestion_controller = name.parse()?;
+        }
+
+        set(
+            &mut config.cache_limits.ttl,
+            self.cache.ttl_secs.map(Duration::from_secs),
+        );
+        set(
+            &mut config.cache_limits.session_quota_bytes,
+            self.cache.session_quota_bytes,
+        );
+        set(
+            &mut config.cache_limits.tenant_quota_bytes,
+            self.cache.tenant_quota_bytes,
+        );
+        set(
+            &mut config.cache_limits.track_quota_bytes,
+            self.cache.track_quota_bytes,
+        );
+        set(
+            &mut config.cache_limits.global_quota_bytes,
+            self.cache.global_quota_bytes,
+        );
+        set(
+            &mut config.cache_limits.disk_dir,
+            self.cache.disk_dir.map(Some),
+        );
+        set(
+            &mut config.cache_limits.disk_retention,
+            self.cache.disk_retention_secs.map(Duration::from_secs),
+        );
+
+        set(
+            &mut config.rate_limits.session_bytes_per_sec,
+            self.rate_limit.session_bytes_per_sec,
+        );
+        set(
+            &mut config.rate_limits.track_bytes_per_sec,
+            self.rate_limit.track_bytes_per_sec,
+        );
+        if let Some(action) = self.rate_limit.over_limit {
+            config.rate_limits.over_limit = action.parse()?;
+        }
+
+        if let Some(preference) = self.egress.client_forwarding {
+            config.egress.client_forwarding = preference.parse()?;
+        }
+        set(
+            &mut config.egress.client_keyframe_start,
+            self.egress.client_keyframe_start,
+        );
+        set(
+            &mut config.egress.stall_threshold,
+            self.egress
+                .stall_ms
+                .map(super::egress_tuning::stall_threshold),
+        );
+        set(&mut config.egress.queue_groups, self.egress.queue_groups);
+        if let Some(policy) = self.egress.queue_overflow {
+            config.egress.queue_overflow = policy.parse()?;
+        }
+
+        set(&mut config.record_dir, self.recording.dir.map(Some));
+        set(&mut config.record_namespaces, self.recording.namespaces);
+        set(&mut config.replay_files, self.recording.replay);
+
+        if let Some(value) = self.auth.namespace_acl {
+            let namespace_acl =
+                NamespaceAclTable::parse(&value).context("invalid [auth] namespace_acl")?;
+            config.authorizer = config.authorizer.clone().with_namespace_acl(namespace_acl);
+        }
+        if let Some(value) = self.auth.namespace_policies {
+            config.namespace_policies =
+                NamespacePolicyTable::parse(&value).context("invalid [auth] namespace_policies")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(contents: &str) -> anyhow::Result<RelayConfig> {
+        let mut config = RelayConfig::from_env()?;
+        RelayConfigFile::parse(contents)?.apply(&mut config)?;
+        Ok(config)
+    }
+
+    #[test]
+    fn file_settings_overwrite_the_environment() {
+        // Arrange
+        let contents = r#"
+            port = 5433
+            parent_relay = "relay-parent.example.com:4434"
+            log_filter = "relay=debug"
+            log_format = "json"
+            setup_timeout_sec = 5
+            max_client_sessions = 1000
+            accept_workers = 4
+
+            [tls]
+            cert_path = "/etc/relay/cert.pem"
+            key_path = "/etc/relay/key.pem"
+            poll_interval_sec = 0
+
+            [control]
+            max_messages_per_sec = 0
+
+            [transport]
+            send_window = 131072
+            packet_threshold = 3
+            max_udp_payload_size = 1350
+            congestion_controller = "bbr"
+
+            [cache]
+            ttl_secs = 60
+            tenant_quota_bytes = { tenant-a = 1024 }
+            global_quota_bytes = 1073741824
+            disk_dir = "/var/cache/relay"
+
+            [rate_limit]
+            session_bytes_per_sec = 1250000
+            over_limit = "drop"
+
+            [egress]
+            client_forwarding = "datagram"
+            client_keyframe_start = false
+            stall_ms = 250
+            queue_overflow = "drop_oldest"
+
+            [recording]
+            dir = "/var/lib/relay/recordings"
+            namespaces = ["live/"]
+            replay = ["/var/lib/relay/recordings/live_room_video.moqrec"]
+
+            [auth]
+            namespace_acl = "*,publish=-"
+        "#;
+
+        // Act
+        let config = apply(contents).unwrap();
+
+        // Assert
+        assert_eq!(config.port, 5433);
+        assert_eq!(
+            config.parent_relay.as_deref(),
+            Some("relay-parent.example.com:4434")
+        );
+        assert_eq!(config.log_filter.as_deref(), Some("relay=debug"));
+        assert_eq!(config.log_format, crate::LogFormat::Json);
+        assert_eq!(config.setup_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(config.max_client_sessions, Some(1000));
+        assert_eq!(config.accept_workers, 4);
+        assert_eq!(
+            config.tls_cert_path,
+            Some(PathBuf::from("/etc/relay/cert.pem"))
+        );
+        assert_eq!(config.tls_poll_interval, None);
+        assert_eq!(config.control_message_limits.max_messages_per_sec, 0);
+        assert_eq!(config.control_message_limits.max_message_bytes, 16 * 1024);
+        assert_eq!(config.transport.send_window, 131072);
+        assert_eq!(
+            config.transport.stream_receive_window,
+            moqt::TransportTuning::default().stream_receive_window
+        );
+        assert_eq!(config.transport.packet_threshold, 3);
+        assert_eq!(config.transport.max_udp_payload_size, 1350);
+        assert_eq!(
+            config.transport.congestion_controller,
+            moqt::CongestionController::Bbr
+        );
+        assert_eq!(config.cache_limits.ttl, Duration::from_secs(60));
+        assert_eq!(config.cache_limits.tenant_quota_bytes["tenant-a"], 1024);
+        assert_eq!(config.cache_limits.global_quota_bytes, 1073741824);
+        assert_eq!(
+            config.cache_limits.disk_dir,
+            Some(PathBuf::from("/var/cache/relay"))
+        );
+        assert_eq!(config.rate_limits.session_bytes_per_sec, 1250000);
+        assert_eq!(config.rate_limits.track_bytes_per_sec, 0);
+        assert_eq!(
+            config.rate_limits.over_limit,
+            crate::OverLimitAction::DropOldGroups
+        );
+        assert_eq!(
+            config.egress.client_forwarding,
+            crate::ForwardingPreference::Datagram
+        );
+        assert!(!config.egress.client_keyframe_start);
+        assert_eq!(
+            config.egress.stall_threshold,
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(config.egress.queue_groups, 64);
+        assert_eq!(
+            config.egress.queue_overflow,
+            crate::QueueOverflowPolicy::DropOldestGroup
+        );
+        assert_eq!(config.record_namespaces, vec!["live/".to_string()]);
+        assert_eq!(config.replay_files.len(), 1);
+        assert!(config.authorizer.is_enabled());
+    }
+
+    #[test]
+    fn example_file_is_accepted() {
+        // Act
+        let config = apply(include_str!("../../relay.example.toml")).unwrap();
+
+        // Assert
+        assert_eq!(config.egress, crate::EgressTuning::default());
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        // Act / Assert
+        assert!(apply("prot = 4433").is_err());
+        assert!(apply("[transport]\ncongestion_controller = \"vegas\"").is_err());
+        assert!(apply("log_format = \"logfmt\"").is_err());
+        assert!(apply("[rate_limit]\nover_limit = \"queue\"").is_err());
+        assert!(apply("[egress]\nqueue_overflow = \"abc\"").is_err());
+        assert!(apply("[egress]\nstall_ms = \"x\"").is_err());
+        assert!(apply("[auth]\nnamespace_acl = \"a/*,publish\"").is_err());
+        assert!(apply("port = \"4433\"").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use anyhow::Context;
+
+const DEFAULT_QUEUE_GROUPS: usize = 64;
+
+/// How a downstream subscriber receives a track's objects, regardless of how
+/// the upstream publisher sent them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ForwardingPreference {
+    /// Datagrams stay datagrams and subgroup streams stay streams.
+    #[default]
+    AsPublished,
+    /// Datagram groups are delivered on one subgroup stream each.
+    Subgroup,
+    /// Subgroup stream objects are delivered as datagrams.
+    Datagram,
+}
+
+impl std::str::FromStr for ForwardingPreference {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "as_published" => Ok(Self::AsPublished),
+            "subgroup" | "stream" => Ok(Self::Subgroup),
+            "datagram" => Ok(Self::Datagram),
+            _ => anyhow::bail!("unknown forwarding preference `{value}`"),
+        }
+    }
+}
+
+/// What a subscription's group sender does with a group that would take it
+/// past its bound of groups in flight.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueOverflowPolicy {
+    /// Stop taking groups from the scheduler until one finishes. Nothing is
+    /// lost: the scheduler waits, and once its track events lag it
+    /// reschedules from the cache.
+    #[default]
+    Pause,
+    /// Give up the oldest group, whether in flight or the incoming one; its
+    /// streams are reset and it ends with an END_OF_GROUP marker.
+    DropOldestGroup,
+    /// End the subscription with PUBLISH_DONE TOO_FAR_BEHIND.
+    Disconnect,
+}
+
+impl std::str::FromStr for QueueOverflowPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "pause" => Ok(Self::Pause),
+            "drop_oldest" | "drop_oldest_group" => Ok(Self::DropOldestGroup),
+            "disconnect" => Ok(Self::Disconnect),
+            _ => anyhow::bail!("unknown egress queue overflow policy `{value}`"),
+        }
+    }
+}
+
+/// How the relay delivers tracks to its subscribers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EgressTuning {
+    /// Delivery applied to client subscribers. Relay peers always get the
+    /// track as published, so a cascade never converts twice.
+    pub client_forwarding: ForwardingPreference,
+    /// Start client subscriptions at the newest group's first object rather
+    /// than the draft's Next Group Start. Relay peers keep the draft's
+    /// behaviour so a cascade does not replay groups.
+    pub client_keyframe_start: bool,
+    /// How long a write to a downstream subgroup stream may block before the
+    /// stream is given up in favour of a newer group; `None` never gives up.
+    pub stall_threshold: Option<Duration>,
+    /// Groups one subscription may have in flight; 0 is unbounded.
+    pub queue_groups: usize,
+    pub queue_overflow: QueueOverflowPolicy,
+}
+
+impl Default for EgressTuning {
+    fn default() -> Self {
+        Self {
+            client_forwarding: ForwardingPreference::default(),
+            client_keyframe_start: true,
+            stall_threshold: None,
+            queue_groups: DEFAULT_QUEUE_GROUPS,
+            queue_overflow: QueueOverflowPolicy::default(),
+        }
+    }
+}
+
+impl EgressTuning {
+    /// Reads `RELAY_CLIENT_FORWARDING_PREFERENCE` (`as_published`, the
+    /// default, `subgroup` or `datagram`), `RELAY_CLIENT_KEYFRAME_START`
+    /// (default on), `RELAY_EGRESS_STALL_MS` (default 0, off),
+    /// `RELAY_EGRESS_QUEUE_GROUPS` (default 64, 0 unbounded) and
+    /// `RELAY_EGRESS_QUEUE_OVERFLOW` (`pause`, the default, `drop_oldest` or
+    /// `disconnect`). A value that does not parse fails startup.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let default = Self::default();
+        Ok(Self {
+            client_forwarding: env_value("RELAY_CLIENT_FORWARDING_PREFERENCE", str::parse)?
+                .unwrap_or(default.client_forwarding),
+            client_keyframe_start: env_value("RELAY_CLIENT_KEYFRAME_START", parse_switch)?
+                .unwrap_or(default.client_keyframe_start),
+            stall_threshold: env_value("RELAY_EGRESS_STALL_MS", |value| Ok(value.parse::<u64>()?))?
+                .map_or(default.stall_threshold, stall_threshold),
+            queue_groups: env_value("RELAY_EGRESS_QUEUE_GROUPS", |value| {
+                Ok(value.parse::<usize>()?)
+            })?
+            .unwrap_or(default.queue_groups),
+            queue_overflow: env_value("RELAY_EGRESS_QUEUE_OVERFLOW", str::parse)?
+                .unwrap_or(default.queue_overflow),
+        })
+    }
+}
+
+/// Parses variable `name` when it is set, naming it in the error.
+pub(super) fn env_value<T>(
+    name: &str,
+    parse: impl FnOnce(&str) -> anyhow::Result<T>,
+) -> anyhow::Result<Option<T>> {
+    std::env::var(name)
+        .ok()
+        .map(|value| parse(&value).with_context(|| format!("invalid {name} `{value}`")))
+        .transpose()
+}
+
+/// `1`, `true`, `yes` or `on`, and `0`, `false`, `no` or `off`.
+fn parse_switch(value: &str) -> anyhow::Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => anyhow::bail!("expected on or off"),
+    }
+}
+
+/// `stall_ms` as a threshold; 0 turns stall detection off.
+pub(super) fn stall_threshold(stall_ms: u64) -> Option<Duration> {
+    (stall_ms > 0).then(|| Duration::from_millis(stall_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarding_preferences_parse_known_values_only() {
+        // Arrange / Act / Assert
+        assert_eq!(
+            "Subgroup".parse::<ForwardingPreference>().unwrap(),
+            ForwardingPreference::Subgroup
+        );
+        assert_eq!(
+            "datagram".parse::<ForwardingPreference>().unwrap(),
+            ForwardingPreference::Datagram
+        );
+        assert!("bogus".parse::<ForwardingPreference>().is_err());
+    }
+
+    #[test]
+    fn overflow_policies_parse_known_values_only() {
+        // Arrange / Act / Assert
+        assert_eq!(
+            "drop_oldest".parse::<QueueOverflowPolicy>().unwrap(),
+            QueueOverflowPolicy::DropOldestGroup
+        );
+        assert_eq!(
+            "disconnect".parse::<QueueOverflowPolicy>().unwrap(),
+            QueueOverflowPolicy::Disconnect
+        );
+        assert!("abc".parse::<QueueOverflowPolicy>().is_err());
+    }
+
+    #[test]
+    fn switches_accept_on_and_off_spellings_only() {
+        // Arrange / Act / Assert
+        assert!(parse_switch("on").unwrap());
+        assert!(!parse_switch("0").unwrap());
+        assert!(!parse_switch("False").unwrap());
+        assert!(parse_switch("maybe").is_err());
+    }
+}
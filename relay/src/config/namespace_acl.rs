@@ -0,0 +1,222 @@
+use anyhow::{Context, bail};
+
+use super::{authorizer::AuthorizationRequest, namespace_policy::specificity};
+
+/// Draft-14 UNAUTHORIZED, the same value in every `*_ERROR` the ACL answers.
+pub(crate) const UNAUTHORIZED: u64 = 0x1;
+
+/// Who may act on a namespace in one role. `Anyone` is also what a rule
+/// leaves a role it does not mention.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AclRole {
+    #[default]
+    Anyone,
+    Nobody,
+    /// Requests whose AUTHORIZATION TOKEN value is one of these.
+    Tokens(Vec<String>),
+}
+
+impl AclRole {
+    fn parse(value: &str) -> Self {
+        match value {
+            "*" => Self::Anyone,
+            "-" => Self::Nobody,
+            tokens => Self::Tokens(
+                tokens
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|token| !token.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+        }
+    }
+
+    fn allows(&self, authorization_token: Option<&str>) -> bool {
+        match self {
+            Self::Anyone => true,
+            Self::Nobody => false,
+            Self::Tokens(tokens) => {
+                authorization_token.is_some_and(|token| tokens.iter().any(|t| t == token))
+            }
+        }
+    }
+}
+
+/// Access rule of a namespace pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamespaceAclRule {
+    /// PUBLISH_NAMESPACE.
+    pub publish: AclRole,
+    /// SUBSCRIBE and SUBSCRIBE_NAMESPACE.
+    pub subscribe: AclRole,
+    /// Error code of a denied request. Defaults to UNAUTHORIZED; e.g.
+    /// TRACK_DOES_NOT_EXIST (0x4) hides that the namespace exists.
+    pub error_code: u64,
+}
+
+impl Default for NamespaceAclRule {
+    fn default() -> Self {
+        Self {
+            publish: AclRole::Anyone,
+            subscribe: AclRole::Anyone,
+            error_code: UNAUTHORIZED,
+        }
+    }
+}
+
+/// Namespace access rules keyed by pattern, matched like
+/// [`NamespacePolicyTable`](super::NamespacePolicyTable): `sports/*`,
+/// `*` or an exact namespace, the most specific pattern winning. A
+/// namespace no rule matches is open to everyone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceAclTable {
+    rules: Vec<(String, NamespaceAclRule)>,
+}
+
+impl NamespaceAclTable {
+    /// Parses `RELAY_NAMESPACE_ACL`: `;`-separated entries of a pattern
+    /// followed by `,key=value` settings, e.g.
+    /// `*,publish=-;tenant-a/*,publish=tok-a1|tok-a2,subscribe=tok-a-view,error=4`.
+    /// `publish` / `subscribe` take `*` (anyone), `-` (nobody) or
+    /// `|`-separated tokens; `error` is a decimal or `0x` error code.
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let mut rules = Vec::new();
+        for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let mut fields = entry.split(',').map(str::trim);
+            let pattern = fields.next().unwrap_or_default().to_string();
+            if pattern.is_empty() {
+                bail!("namespace ACL rule `{entry}` has no pattern");
+            }
+            let mut rule = NamespaceAclRule::default();
+            for field in fields {
+                let (key, value) = field
+                    .split_once('=')
+                    .with_context(|| format!("namespace ACL setting `{field}` has no value"))?;
+                let (key, value) = (key.trim(), value.trim());
+                match key {
+                    "publish" => rule.publish = AclRole::parse(value),
+                    "subscribe" => rule.subscribe = AclRole::parse(value),
+                    "error" => rule.error_code = parse_error_code(value)?,
+                    _ => bail!("unknown namespace ACL setting `{key}` for `{pattern}`"),
+                }
+            }
+            if matches!(&rule.publish, AclRole::Tokens(tokens) if tokens.is_empty())
+                || matches!(&rule.subscribe, AclRole::Tokens(tokens) if tokens.is_empty())
+            {
+                bail!("namespace ACL rule for `{pattern}` lists no tokens");
+            }
+            rules.push((pattern, rule));
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    fn lookup(&self, track_namespace: &str) -> Option<&NamespaceAclRule> {
+        self.rules
+            .iter()
+            .filter_map(|(pattern, rule)| {
+                specificity(pattern, track_namespace).map(|rank| (rank, rule))
+            })
+            .max_by_key(|(rank, _)| *rank)
+            .map(|(_, rule)| rule)
+    }
+
+    /// `Err` with the rule's error code when the request is denied. A
+    /// SUBSCRIBE_NAMESPACE prefix is checked as a namespace, so a rule on
+    /// `a/b/*` does not cover a subscription to the prefix `a`.
+    pub(crate) fn check(&self, request: &AuthorizationRequest<'_>) -> Result<(), u64> {
+        let (track_namespace, is_publish) = match request {
+            AuthorizationRequest::PublishNamespace {
+                track_namespace, ..
+            } => (*track_namespace, true),
+            AuthorizationRequest::Subscribe {
+                track_namespace, ..
+            } => (*track_namespace, false),
+            AuthorizationRequest::SubscribeNamespace {
+                track_namespace_prefix,
+                ..
+            } => (*track_namespace_prefix, false),
+        };
+        let Some(rule) = self.lookup(track_namespace) else {
+            return Ok(());
+        };
+        let role = if is_publish {
+            &rule.publish
+        } else {
+            &rule.subscribe
+        };
+        if role.allows(request.authorization_token()) {
+            Ok(())
+        } else {
+            Err(rule.error_code)
+        }
+    }
+}
+
+fn parse_error_code(value: &str) -> anyhow::Result<u64> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .with_context(|| format!("namespace ACL error code `{value}` is not a number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn publish(track_namespace: &str, token: Option<&'static str>) -> Result<(), u64> {
+        table().check(&AuthorizationRequest::PublishNamespace {
+            track_namespace,
+            authorization_token: token,
+        })
+    }
+
+    fn subscribe(track_namespace: &str, token: Option<&'static str>) -> Result<(), u64> {
+        table().check(&AuthorizationRequest::Subscribe {
+            track_namespace,
+            track_name: "video",
+            authorization_token: token,
+        })
+    }
+
+    fn table() -> NamespaceAclTable {
+        NamespaceAclTable::parse(
+            "*,publish=-; tenant-a/*,publish=tok-a|tok-b,subscribe=view-a,error=0x4; public/*",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn publishing_needs_a_listed_token() {
+        // Act / Assert
+        assert_eq!(publish("tenant-a/live", Some("tok-b")), Ok(()));
+        assert_eq!(publish("tenant-a/live", Some("view-a")), Err(0x4));
+        assert_eq!(publish("tenant-a/live", None), Err(0x4));
+        assert_eq!(publish("tenant-b", Some("tok-a")), Err(UNAUTHORIZED));
+        assert_eq!(publish("public/news", None), Ok(()));
+    }
+
+    #[test]
+    fn roles_a_rule_omits_are_open() {
+        // Act / Assert
+        assert_eq!(subscribe("tenant-b", None), Ok(()));
+        assert_eq!(subscribe("tenant-a", Some("view-a")), Ok(()));
+        assert_eq!(subscribe("tenant-a", Some("tok-a")), Err(0x4));
+    }
+
+    #[test]
+    fn malformed_rules_are_rejected() {
+        // Arrange / Act / Assert
+        assert!(NamespaceAclTable::parse("a/*,publish").is_err());
+        assert!(NamespaceAclTable::parse("a/*,publish=|").is_err());
+        assert!(NamespaceAclTable::parse("a/*,error=denied").is_err());
+        assert!(NamespaceAclTable::parse("a/*,role=admin").is_err());
+        assert!(NamespaceAclTable::parse(",publish=-").is_err());
+        assert!(NamespaceAclTable::parse("").unwrap().is_empty());
+    }
+}
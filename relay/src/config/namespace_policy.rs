@@ -0,0 +1,179 @@
+use anyhow::{Context, bail};
+
+/// Operator overrides applied to SUBSCRIBE requests under a namespace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespacePolicy {
+    /// Replaces the requested group order.
+    pub group_order: Option<moqt::GroupOrder>,
+    /// Replaces the requested subscriber priority.
+    pub subscriber_priority: Option<u8>,
+    /// Lowest (most urgent) subscriber priority a subscriber may ask for.
+    pub min_subscriber_priority: Option<u8>,
+    /// Highest (least urgent) subscriber priority; a lower bound on urgency,
+    /// which is how a namespace gets a priority boost.
+    pub max_subscriber_priority: Option<u8>,
+}
+
+impl NamespacePolicy {
+    pub(crate) fn subscriber_priority(&self, requested: u8) -> u8 {
+        if let Some(priority) = self.subscriber_priority {
+            return priority;
+        }
+        requested.clamp(
+            self.min_subscriber_priority.unwrap_or(u8::MIN),
+            self.max_subscriber_priority.unwrap_or(u8::MAX),
+        )
+    }
+
+    pub(crate) fn group_order(&self, requested: moqt::GroupOrder) -> moqt::GroupOrder {
+        self.group_order.unwrap_or(requested)
+    }
+}
+
+/// Namespace policies keyed by pattern. `sports/*` matches `sports` and
+/// everything under it, `*` matches every namespace, anything else matches
+/// exactly. The most specific matching pattern wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespacePolicyTable {
+    policies: Vec<(String, NamespacePolicy)>,
+}
+
+impl NamespacePolicyTable {
+    /// Parses `RELAY_NAMESPACE_POLICIES`: `;`-separated entries of a pattern
+    /// followed by `,key=value` settings, e.g.
+    /// `sports/*,group_order=descending,max_priority=32;news,priority=128`.
+    /// Keys are `group_order` (`ascending` / `descending`), `priority`,
+    /// `min_priority` and `max_priority`.
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let mut policies = Vec::new();
+        for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let mut fields = entry.split(',').map(str::trim);
+            let pattern = fields.next().unwrap_or_default().to_string();
+            if pattern.is_empty() {
+                bail!("namespace policy `{entry}` has no pattern");
+            }
+            let mut policy = NamespacePolicy::default();
+            for field in fields {
+                let (key, value) = field
+                    .split_once('=')
+                    .with_context(|| format!("namespace policy setting `{field}` has no value"))?;
+                let (key, value) = (key.trim(), value.trim());
+                match key {
+                    "group_order" => {
+                        policy.group_order = Some(match value.to_ascii_lowercase().as_str() {
+                            "ascending" => moqt::GroupOrder::Ascending,
+                            "descending" => moqt::GroupOrder::Descending,
+                            _ => bail!("unknown group order `{value}` for `{pattern}`"),
+                        })
+                    }
+                    "priority" => policy.subscriber_priority = Some(parse_priority(value)?),
+                    "min_priority" => policy.min_subscriber_priority = Some(parse_priority(value)?),
+                    "max_priority" => policy.max_subscriber_priority = Some(parse_priority(value)?),
+                    _ => bail!("unknown namespace policy setting `{key}` for `{pattern}`"),
+                }
+            }
+            if let (Some(min), Some(max)) = (
+                policy.min_subscriber_priority,
+                policy.max_subscriber_priority,
+            ) && min > max
+            {
+                bail!("min_priority {min} exceeds max_priority {max} for `{pattern}`");
+            }
+            policies.push((pattern, policy));
+        }
+        Ok(Self { policies })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// Policy of the most specific pattern matching `track_namespace`.
+    pub(crate) fn lookup(&self, track_namespace: &str) -> Option<&NamespacePolicy> {
+        self.policies
+            .iter()
+            .filter_map(|(pattern, policy)| {
+                specificity(pattern, track_namespace).map(|rank| (rank, policy))
+            })
+            .max_by_key(|(rank, _)| *rank)
+            .map(|(_, policy)| policy)
+    }
+}
+
+fn parse_priority(value: &str) -> anyhow::Result<u8> {
+    value
+        .parse()
+        .with_context(|| format!("subscriber priority `{value}` is not 0-255"))
+}
+
+/// `None` when `pattern` does not match; otherwise a rank where longer
+/// prefixes beat shorter ones and an exact match beats a wildcard.
+pub(super) fn specificity(pattern: &str, track_namespace: &str) -> Option<(usize, bool)> {
+    if pattern == "*" {
+        return Some((0, false));
+    }
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => {
+            let matches = track_namespace == prefix
+                || track_namespace
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/'));
+            matches.then_some((prefix.len(), false))
+        }
+        None => (pattern == track_namespace).then_some((pattern.len(), true)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_specific_pattern_wins() {
+        // Arrange
+        let table = NamespacePolicyTable::parse(
+            "*,priority=200; sports/*,group_order=descending; sports/live,priority=1",
+        )
+        .unwrap();
+
+        // Act / Assert
+        assert_eq!(table.lookup("news").unwrap().subscriber_priority, Some(200));
+        assert_eq!(
+            table.lookup("sports/soccer").unwrap().group_order,
+            Some(moqt::GroupOrder::Descending)
+        );
+        assert_eq!(
+            table.lookup("sports/live").unwrap().subscriber_priority,
+            Some(1)
+        );
+        assert!(table.lookup("sportsball").unwrap().group_order.is_none());
+    }
+
+    #[test]
+    fn requested_priority_is_overridden_or_clamped() {
+        // Arrange
+        let table = NamespacePolicyTable::parse(
+            "sports/*,max_priority=32;news/*,min_priority=64,max_priority=128;ads,priority=255",
+        )
+        .unwrap();
+
+        // Act / Assert
+        assert_eq!(
+            table.lookup("sports/a").unwrap().subscriber_priority(200),
+            32
+        );
+        assert_eq!(table.lookup("sports/a").unwrap().subscriber_priority(8), 8);
+        assert_eq!(table.lookup("news/a").unwrap().subscriber_priority(0), 64);
+        assert_eq!(table.lookup("ads").unwrap().subscriber_priority(0), 255);
+    }
+
+    #[test]
+    fn malformed_policies_are_rejected() {
+        // Arrange / Act / Assert
+        assert!(NamespacePolicyTable::parse("sports/*,group_order=sideways").is_err());
+        assert!(NamespacePolicyTable::parse("sports/*,priority=300").is_err());
+        assert!(NamespacePolicyTable::parse("sports/*,boost").is_err());
+        assert!(NamespacePolicyTable::parse("a,min_priority=9,max_priority=3").is_err());
+        assert!(NamespacePolicyTable::parse("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+use super::egress_tuning::env_value;
+
+/// What ingress does with objects of a publisher over its byte rate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverLimitAction {
+    /// Hold objects back until the rate allows them, which stalls the
+    /// publisher's streams through QUIC flow control.
+    #[default]
+    Pace,
+    /// Pace the newest group, but cut short any older group that would have
+    /// to wait, so a publisher over its rate falls behind by whole groups
+    /// rather than by latency.
+    DropOldGroups,
+}
+
+impl std::str::FromStr for OverLimitAction {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "pace" => Ok(Self::Pace),
+            "drop" | "drop_old_groups" => Ok(Self::DropOldGroups),
+            _ => anyhow::bail!("unknown over-limit action `{value}`"),
+        }
+    }
+}
+
+/// Byte rates publishers may send into the relay, counted in object
+/// payload bytes. Datagrams over a rate are dropped whatever the action,
+/// since holding them back would only make them late.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Bytes per second across all tracks of one publisher session; 0 is
+    /// unlimited.
+    pub session_bytes_per_sec: u64,
+    /// Bytes per second of one track; 0 is unlimited.
+    pub track_bytes_per_sec: u64,
+    pub over_limit: OverLimitAction,
+}
+
+impl RateLimits {
+    /// Reads `RELAY_SESSION_RATE_LIMIT_BYTES_PER_SEC` and
+    /// `RELAY_TRACK_RATE_LIMIT_BYTES_PER_SEC` (default 0, unlimited) and
+    /// `RELAY_RATE_LIMIT_ACTION` (`pace`, the default, or `drop`). A value
+    /// that does not parse fails startup rather than lifting the limit.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let bytes_from_env = |name: &str| {
+            env_value(name, |value| Ok(value.parse::<u64>()?)).map(Option::unwrap_or_default)
+        };
+        Ok(Self {
+            session_bytes_per_sec: bytes_from_env("RELAY_SESSION_RATE_LIMIT_BYTES_PER_SEC")?,
+            track_bytes_per_sec: bytes_from_env("RELAY_TRACK_RATE_LIMIT_BYTES_PER_SEC")?,
+            over_limit: env_value("RELAY_RATE_LIMIT_ACTION", str::parse)?.unwrap_or_default(),
+        })
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.session_bytes_per_sec > 0 || self.track_bytes_per_sec > 0
+    }
+}
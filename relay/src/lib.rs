@@ -1,10 +1,18 @@
 mod config;
 mod logging;
-pub use config::RelayConfig;
-pub use logging::{LoggingGuards, init_logging};
+pub use config::{
+    AclRole, AuthorizationRequest, Authorizer, CacheLimits, EgressTuning, ForwardingPreference,
+    NamespaceAclRule, NamespaceAclTable, NamespacePolicy, NamespacePolicyTable, OverLimitAction,
+    QueueOverflowPolicy, RateLimits, RelayConfig,
+};
+pub use logging::{
+    LogFormat, LoggingGuards, init_logging, init_logging_with_filter, init_logging_with_format,
+};
 pub mod modules;
 mod relay_server;
 
+pub use modules::relay_events::RelayEvent;
+
 pub use relay_server::server::RelayServer;
 use tokio::sync::oneshot::Receiver;
 
@@ -27,8 +35,9 @@ pub fn run_relay_server<T: moqt::TransportProtocol>(
             let handler = server.spawn_client_transport::<T>(port);
 
             shutdown_signal.await.ok();
-            drop(handler);
             tracing::info!("Relay server shutting down");
+            server.shutdown("", std::time::Duration::ZERO).await;
+            drop(handler);
         })
         .unwrap()
 }
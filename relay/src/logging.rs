@@ -1,17 +1,52 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use anyhow::{Context, Result};
-use opentelemetry::global;
-use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::trace::{Link, SpanKind, TraceId, TracerProvider as _};
+use opentelemetry::{KeyValue, global};
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::Resource;
 use opentelemetry_sdk::logs::{BatchLogProcessor, SdkLoggerProvider};
 use opentelemetry_sdk::propagation::TraceContextPropagator;
-use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::trace::{
+    Sampler, SamplingDecision, SamplingResult, SdkTracerProvider, ShouldSample,
+};
 use tracing_subscriber::filter::filter_fn;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, Layer, Registry, fmt};
 
+/// Target of the per-object data-plane spans. They are debug spans, off
+/// unless `RELAY_OTEL_OBJECT_SAMPLE_EVERY` enables them for the trace export.
+pub(crate) const OBJECT_SPAN_TARGET: &str = "relay::object";
+
+/// Format of the stdout log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines with ANSI colours.
+    #[default]
+    Text,
+    /// One JSON object per line for log pipelines such as Loki or ELK. Event
+    /// fields (`session_id`, `track_alias`, `group_id`, `object_id`, ...) sit
+    /// at the top level next to `timestamp`, `level`, `target` and
+    /// `message`; the fields of the innermost span, e.g. its `track_key`,
+    /// are under `span`.
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!("unknown log format `{value}`; expected text or json"),
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 enum OtlpProtocol {
     Grpc,
@@ -49,6 +84,67 @@ fn otel_is_enabled() -> bool {
     !env_flag_is_enabled("OTEL_SDK_DISABLED")
 }
 
+/// Reads `RELAY_OTEL_OBJECT_SAMPLE_EVERY` (default 0, no object spans):
+/// export one in this many per-object spans.
+fn otel_object_sample_every() -> Result<u64> {
+    let Ok(value) = std::env::var("RELAY_OTEL_OBJECT_SAMPLE_EVERY") else {
+        return Ok(0);
+    };
+    value
+        .parse()
+        .with_context(|| format!("invalid RELAY_OTEL_OBJECT_SAMPLE_EVERY: {value}"))
+}
+
+/// The SDK's default parent-based sampling, except that only one in
+/// `every` per-object span is kept: a busy track forwards thousands of
+/// objects a second, and a sample is enough to see where time goes.
+#[derive(Clone, Debug)]
+struct ObjectSpanSampler {
+    every: u64,
+    seen: Arc<AtomicU64>,
+    inner: Sampler,
+}
+
+impl ObjectSpanSampler {
+    fn new(every: u64) -> Self {
+        Self {
+            every,
+            seen: Arc::new(AtomicU64::new(0)),
+            inner: Sampler::ParentBased(Box::new(Sampler::AlwaysOn)),
+        }
+    }
+
+    fn is_object_span(name: &str) -> bool {
+        name.starts_with("relay.dataplane.") && name.ends_with(".object")
+    }
+}
+
+impl ShouldSample for ObjectSpanSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&opentelemetry::Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let mut result =
+            self.inner
+                .should_sample(parent_context, trace_id, name, span_kind, attributes, links);
+        if Self::is_object_span(name)
+            && (self.every == 0
+                || !self
+                    .seen
+                    .fetch_add(1, Ordering::Relaxed)
+                    .is_multiple_of(self.every))
+        {
+            result.decision = SamplingDecision::Drop;
+        }
+        result
+    }
+}
+
 fn otel_protocol() -> Result<OtlpProtocol> {
     let protocol =
         std::env::var("OTEL_EXPORTER_OTLP_PROTOCOL").unwrap_or_else(|_| "grpc".to_string());
@@ -152,9 +248,17 @@ fn append_directive_if_missing(filter: &mut String, directive: &str) {
     }
 }
 
-fn tracing_filter(filter_env: &str, default_directives: &[&str]) -> EnvFilter {
-    let mut filter = std::env::var(filter_env)
-        .or_else(|_| std::env::var("RUST_LOG"))
+/// `configured` (from the config file or command line) takes precedence over
+/// `filter_env` and `RUST_LOG`.
+fn tracing_filter(
+    filter_env: &str,
+    configured: Option<&str>,
+    default_directives: &[&str],
+) -> EnvFilter {
+    let mut filter = configured
+        .map(str::to_string)
+        .or_else(|| std::env::var(filter_env).ok())
+        .or_else(|| std::env::var("RUST_LOG").ok())
         .unwrap_or_default();
 
     for directive in default_directives {
@@ -171,6 +275,7 @@ fn tracing_filter(filter_env: &str, default_directives: &[&str]) -> EnvFilter {
 fn build_tracer_provider(
     service_name: &str,
     protocol: OtlpProtocol,
+    object_sample_every: u64,
 ) -> Result<Option<SdkTracerProvider>> {
     if !otel_is_enabled() {
         return Ok(None);
@@ -189,6 +294,7 @@ fn build_tracer_provider(
 
     let provider = SdkTracerProvider::builder()
         .with_batch_exporter(exporter)
+        .with_sampler(ObjectSpanSampler::new(object_sample_every))
         .with_resource(otel_resource(service_name))
         .build();
 
@@ -223,18 +329,54 @@ fn build_logger_provider(
 }
 
 pub fn init_logging(default_service_name: &str) -> Result<LoggingGuards> {
-    let stdout_layer = fmt::layer()
-        .with_writer(std::io::stdout)
-        .with_ansi(true)
-        .with_line_number(true)
-        .with_filter(tracing_filter(
-            "RELAY_STDOUT_FILTER",
-            &["relay=info", "moqt=info"],
-        ));
+    init_logging_with_filter(default_service_name, None)
+}
+
+/// Like [`init_logging`], with the stdout filter (e.g. `relay=debug`) set by
+/// the relay configuration instead of `RELAY_STDOUT_FILTER`.
+pub fn init_logging_with_filter(
+    default_service_name: &str,
+    stdout_filter: Option<&str>,
+) -> Result<LoggingGuards> {
+    init_logging_with_format(default_service_name, stdout_filter, LogFormat::Text)
+}
+
+/// Like [`init_logging_with_filter`], writing stdout in `log_format`.
+pub fn init_logging_with_format(
+    default_service_name: &str,
+    stdout_filter: Option<&str>,
+    log_format: LogFormat,
+) -> Result<LoggingGuards> {
+    let stdout_layer = match log_format {
+        LogFormat::Text => fmt::layer()
+            .with_writer(std::io::stdout)
+            .with_ansi(true)
+            .with_line_number(true)
+            .boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .with_writer(std::io::stdout)
+            .with_line_number(true)
+            .boxed(),
+    }
+    .with_filter(tracing_filter(
+        "RELAY_STDOUT_FILTER",
+        stdout_filter,
+        &["relay=info", "moqt=info"],
+    ));
 
     let service_name = otel_service_name(default_service_name);
     let protocol = otel_protocol()?;
-    let tracer_provider = build_tracer_provider(&service_name, protocol)?;
+    let object_sample_every = otel_object_sample_every()?;
+    let tracer_provider = build_tracer_provider(&service_name, protocol, object_sample_every)?;
+    let object_span_directive = format!("{OBJECT_SPAN_TARGET}=debug");
+    let mut otel_trace_directives = vec!["relay=info", "moqt=info"];
+    if object_sample_every > 0 {
+        otel_trace_directives.push(&object_span_directive);
+    }
     let logger_provider = build_logger_provider(&service_name, protocol)?;
 
     let otel_trace_layer = tracer_provider.as_ref().map(|provider| {
@@ -247,13 +389,15 @@ pub fn init_logging(default_service_name: &str) -> Result<LoggingGuards> {
             .with_filter(filter_fn(|metadata| metadata.is_span()))
             .with_filter(tracing_filter(
                 "RELAY_OTEL_FILTER",
-                &["relay=info", "moqt=info"],
+                None,
+                &otel_trace_directives,
             ))
     });
 
     let otel_log_layer = logger_provider.as_ref().map(|provider| {
         OpenTelemetryTracingBridge::new(provider).with_filter(tracing_filter(
             "RELAY_LOG_FILTER",
+            None,
             &["relay=info", "moqt=info"],
         ))
     });
@@ -274,7 +418,9 @@ pub fn init_logging(default_service_name: &str) -> Result<LoggingGuards> {
         service_name = %service_name,
         relay_hostname = %relay_hostname(),
         relay_id = %relay_id(),
+        ?log_format,
         otel_enabled = tracer_provider.is_some(),
+        otel_object_sample_every = object_sample_every,
         otlp_protocol = %otel_protocol_name(protocol),
         otlp_endpoint = %otel_endpoint(protocol),
         otlp_logs_endpoint = %otel_logs_endpoint(protocol),
@@ -286,3 +432,47 @@ pub fn init_logging(default_service_name: &str) -> Result<LoggingGuards> {
         logger_provider,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decide(sampler: &ObjectSpanSampler, name: &str) -> SamplingDecision {
+        sampler
+            .should_sample(None, TraceId::from(1), name, &SpanKind::Internal, &[], &[])
+            .decision
+    }
+
+    #[test]
+    fn object_spans_are_sampled_one_in_every() {
+        // Arrange
+        let sampler = ObjectSpanSampler::new(3);
+
+        // Act
+        let objects: Vec<_> = (0..6)
+            .map(|_| decide(&sampler, "relay.dataplane.egress.object"))
+            .collect();
+        let stream = decide(&sampler, "relay.dataplane.egress.stream");
+
+        // Assert
+        let kept = objects
+            .iter()
+            .filter(|decision| **decision == SamplingDecision::RecordAndSample)
+            .count();
+        assert_eq!(kept, 2);
+        assert_eq!(objects[0], SamplingDecision::RecordAndSample);
+        assert_eq!(stream, SamplingDecision::RecordAndSample);
+    }
+
+    #[test]
+    fn zero_drops_every_object_span() {
+        // Arrange
+        let sampler = ObjectSpanSampler::new(0);
+
+        // Act / Assert
+        assert_eq!(
+            decide(&sampler, "relay.dataplane.ingress.object"),
+            SamplingDecision::Drop
+        );
+    }
+}
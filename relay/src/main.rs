@@ -3,10 +3,32 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use clap::Parser;
 use rcgen::{CertifiedKey, generate_simple_self_signed};
 
 const CERT_DIR: &str = "keys";
 
+#[derive(Parser, Debug)]
+#[command(about = "MOQT relay server")]
+struct Args {
+    /// TOML configuration file; settings it leaves out come from the
+    /// environment.
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Client-facing port; overrides the configuration.
+    #[arg(long)]
+    port: Option<u16>,
+
+    /// Inter-relay port; overrides the configuration.
+    #[arg(long)]
+    inner_port: Option<u16>,
+
+    /// Stdout tracing filter, e.g. `relay=debug`; overrides the configuration.
+    #[arg(long)]
+    log_filter: Option<String>,
+}
+
 fn cert_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(CERT_DIR)
 }
@@ -48,28 +70,72 @@ pub fn create_certs_for_test_if_needed() -> anyhow::Result<()> {
     }
 }
 
+/// Waits for Ctrl+C, reloading the TLS certificate on every SIGHUP.
+#[cfg(unix)]
+async fn wait_for_shutdown(server: &relay::RelayServer) -> anyhow::Result<()> {
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+    loop {
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => return Ok(result?),
+            _ = hangup.recv() => server.reload_certificates(),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_shutdown(_server: &relay::RelayServer) -> anyhow::Result<()> {
+    Ok(tokio::signal::ctrl_c().await?)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let _logging = relay::init_logging("relay")?;
-    create_certs_for_test_if_needed()?;
-    let config = relay::RelayConfig::from_env()?;
+    let args = Args::parse();
+    let mut config = match &args.config {
+        Some(path) => relay::RelayConfig::from_file(path)?,
+        None => relay::RelayConfig::from_env()?,
+    };
+    if let Some(port) = args.port {
+        config.port = port;
+    }
+    if let Some(inner_port) = args.inner_port {
+        config.inner_port = inner_port;
+    }
+    if args.log_filter.is_some() {
+        config.log_filter = args.log_filter;
+    }
 
-    let key_path = get_key_path().to_str().unwrap().to_string();
-    let cert_path = get_cert_path().to_str().unwrap().to_string();
+    let _logging =
+        relay::init_logging_with_format("relay", config.log_filter.as_deref(), config.log_format)?;
+    let (key_path, cert_path) = match (&config.tls_key_path, &config.tls_cert_path) {
+        (Some(key_path), Some(cert_path)) => (key_path.clone(), cert_path.clone()),
+        _ => {
+            create_certs_for_test_if_needed()?;
+            (get_key_path(), get_cert_path())
+        }
+    };
+    let key_path = key_path.to_str().unwrap().to_string();
+    let cert_path = cert_path.to_str().unwrap().to_string();
 
     let server = relay::RelayServer::new_with_config(&key_path, &cert_path, config.clone()).await?;
     let _client_handler = server.spawn_client_transport::<moqt::DUAL>(config.port);
     let _inner_handler = server.spawn_inner_transport::<moqt::QUIC>(config.inner_port);
+    let replay_addr = std::net::SocketAddr::from(([127, 0, 0, 1], config.port));
+    for path in &config.replay_files {
+        server.replay_recording(path, replay_addr).await?;
+    }
 
     tracing::info!(
         port = config.port,
         inner_port = config.inner_port,
         "Relay server started with external QUIC + WebTransport and inner QUIC"
     );
-    tracing::info!("Ctrl+C to shutdown");
+    tracing::info!("Ctrl+C to shutdown, SIGHUP to reload the TLS certificate");
 
-    tokio::signal::ctrl_c().await?;
+    wait_for_shutdown(&server).await?;
     tracing::info!("Shutdown signal received. Closing...");
+    server
+        .shutdown(&config.goaway_uri, config.drain_timeout)
+        .await;
     tracing::info!("Relay server gracefully shutdown.");
     Ok(())
 }
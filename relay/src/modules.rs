@@ -1,10 +1,19 @@
+pub(crate) mod billing;
+pub(crate) mod certificate_watcher;
+pub(crate) mod connection_stats;
 pub(crate) mod control_message_forwarder;
 pub(crate) mod core;
 pub(crate) mod enums;
+pub(crate) mod error;
 pub(crate) mod event_handler;
 pub(crate) mod event_resolver;
+pub(crate) mod idle_reaper;
 pub(crate) mod inter_relay;
+pub(crate) mod qlog;
+pub(crate) mod recording;
 pub(crate) mod relay;
+pub(crate) mod relay_events;
+pub(crate) mod resource_monitor;
 pub(crate) mod route_registry;
 pub(crate) mod sequences;
 pub(crate) mod session_event;
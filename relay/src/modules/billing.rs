@@ -0,0 +1,55 @@
+pub(crate) mod exporter;
+pub(crate) mod file_sink;
+pub(crate) mod noop_sink;
+pub(crate) mod usage;
+pub(crate) mod webhook_sink;
+
+pub(crate) use exporter::BillingExporter;
+pub(crate) use file_sink::FileBillingSink;
+pub(crate) use noop_sink::NoopBillingSink;
+pub(crate) use usage::SubscriptionUsage;
+pub(crate) use webhook_sink::WebhookBillingSink;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::modules::types::SessionId;
+
+/// Usage of one downstream subscription, emitted when it ends
+/// (UNSUBSCRIBE, session close or relay shutdown).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct BillingRecord {
+    pub(crate) session_id: SessionId,
+    pub(crate) request_id: u64,
+    pub(crate) track_namespace: String,
+    pub(crate) track_name: String,
+    /// Objects delivered, excluding subgroup headers.
+    pub(crate) objects: u64,
+    /// Object payload bytes delivered.
+    pub(crate) bytes: u64,
+    pub(crate) started_at_unix_ms: u64,
+    pub(crate) ended_at_unix_ms: u64,
+    pub(crate) duration_ms: u64,
+}
+
+/// Destination for billing records; selected from `RelayConfig`.
+#[async_trait]
+pub(crate) trait BillingSink: Send + Sync {
+    async fn export(&self, record: &BillingRecord) -> anyhow::Result<()>;
+}
+
+/// A finished record for tests; only `request_id` varies.
+#[cfg(test)]
+pub(crate) fn test_record(request_id: u64) -> BillingRecord {
+    BillingRecord {
+        session_id: 1,
+        request_id,
+        track_namespace: "live".to_string(),
+        track_name: "video".to_string(),
+        objects: 3,
+        bytes: 300,
+        started_at_unix_ms: 1_000,
+        ended_at_unix_ms: 3_000,
+        duration_ms: 2_000,
+    }
+}
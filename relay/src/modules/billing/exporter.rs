@@ -0,0 +1,104 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::mpsc;
+
+use super::{BillingRecord, BillingSink};
+
+/// How long `shutdown` waits for queued records to reach the sink before
+/// the export task is aborted.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Owns the task that hands finished billing records to the sink, so slow
+/// sinks (e.g. a webhook) never block the egress command loop. The task ends
+/// once every sender of `record_receiver` is gone and the queue is drained.
+pub(crate) struct BillingExporter {
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl BillingExporter {
+    pub(crate) fn run(
+        sink: Arc<dyn BillingSink>,
+        mut record_receiver: mpsc::UnboundedReceiver<BillingRecord>,
+    ) -> Self {
+        let join_handle = tokio::spawn(async move {
+            while let Some(record) = record_receiver.recv().await {
+                if let Err(error) = sink.export(&record).await {
+                    tracing::warn!(
+                        ?error,
+                        session_id = record.session_id,
+                        request_id = record.request_id,
+                        "failed to export billing record"
+                    );
+                }
+            }
+        });
+        Self { join_handle }
+    }
+
+    /// Waits for the queued records to be exported. Call after every record
+    /// sender is dropped; the task is aborted if the sink does not keep up
+    /// within `DRAIN_TIMEOUT`.
+    pub(crate) async fn shutdown(&mut self) {
+        if tokio::time::timeout(DRAIN_TIMEOUT, &mut self.join_handle)
+            .await
+            .is_err()
+        {
+            tracing::warn!("billing exporter did not drain in time; dropping queued records");
+            self.join_handle.abort();
+        }
+    }
+}
+
+impl Drop for BillingExporter {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::modules::billing::test_record as record;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        records: Mutex<Vec<BillingRecord>>,
+    }
+
+    #[async_trait]
+    impl BillingSink for RecordingSink {
+        async fn export(&self, record: &BillingRecord) -> anyhow::Result<()> {
+            // Yield so records stay queued while shutdown starts.
+            tokio::task::yield_now().await;
+            self.records.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_exports_records_queued_before_the_senders_closed() {
+        // Arrange
+        let sink = Arc::new(RecordingSink::default());
+        let (record_sender, record_receiver) = mpsc::unbounded_channel();
+        let mut exporter = BillingExporter::run(sink.clone(), record_receiver);
+        for request_id in 0..3 {
+            record_sender.send(record(request_id)).unwrap();
+        }
+        // Act: the last sender goes away, then the exporter drains
+        drop(record_sender);
+        exporter.shutdown().await;
+        // Assert: nothing queued was lost
+        let exported: Vec<u64> = sink
+            .records
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|record| record.request_id)
+            .collect();
+        assert_eq!(exported, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+
+use super::{BillingRecord, BillingSink};
+
+/// Appends one JSON object per line to a local file.
+pub(crate) struct FileBillingSink {
+    file: tokio::sync::Mutex<tokio::fs::File>,
+}
+
+impl FileBillingSink {
+    pub(crate) async fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        Ok(Self {
+            file: tokio::sync::Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl BillingSink for FileBillingSink {
+    async fn export(&self, record: &BillingRecord) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let mut file = self.file.lock().await;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileBillingSink;
+    use crate::modules::billing::{BillingSink, test_record as record};
+
+    #[tokio::test]
+    async fn export_appends_one_json_line_per_record() {
+        // Arrange
+        let path = std::env::temp_dir().join(format!(
+            "relay-billing-{}-{:?}.jsonl",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = tokio::fs::remove_file(&path).await;
+        let sink = FileBillingSink::open(&path).await.expect("open sink");
+        // Act
+        sink.export(&record(0)).await.expect("export first");
+        sink.export(&record(2)).await.expect("export second");
+        // Assert
+        let contents = tokio::fs::read_to_string(&path).await.expect("read file");
+        let _ = tokio::fs::remove_file(&path).await;
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("\"request_id\":0"));
+        assert!(lines[1].contains("\"request_id\":2"));
+        assert!(lines[1].contains("\"bytes\":300"));
+    }
+}
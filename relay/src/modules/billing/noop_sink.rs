@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use super::{BillingRecord, BillingSink};
+
+#[derive(Debug)]
+pub(crate) struct NoopBillingSink;
+
+#[async_trait]
+impl BillingSink for NoopBillingSink {
+    async fn export(&self, _record: &BillingRecord) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use super::BillingRecord;
+use crate::modules::{idle_reaper::SessionActivity, types::SessionId};
+
+/// Counters for one downstream subscription, shared by its egress send tasks.
+#[derive(Debug)]
+pub(crate) struct SubscriptionUsage {
+    session_id: SessionId,
+    request_id: u64,
+    track_namespace: String,
+    track_name: String,
+    objects: AtomicU64,
+    bytes: AtomicU64,
+    /// Data streams opened; reported in PUBLISH_DONE, not billed.
+    streams: AtomicU64,
+    started_at: Instant,
+    started_at_unix_ms: u64,
+    /// The subscriber session's activity, kept fresh by delivered objects.
+    activity: Option<Arc<SessionActivity>>,
+}
+
+impl SubscriptionUsage {
+    pub(crate) fn new(
+        session_id: SessionId,
+        request_id: u64,
+        track_namespace: String,
+        track_name: String,
+    ) -> Self {
+        Self {
+            session_id,
+            request_id,
+            track_namespace,
+            track_name,
+            objects: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            streams: AtomicU64::new(0),
+            started_at: Instant::now(),
+            started_at_unix_ms: unix_ms(SystemTime::now()),
+            activity: None,
+        }
+    }
+
+    pub(crate) fn with_activity(mut self, activity: Option<Arc<SessionActivity>>) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    pub(crate) fn session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    pub(crate) fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    pub(crate) fn track_namespace(&self) -> &str {
+        &self.track_namespace
+    }
+
+    pub(crate) fn track_name(&self) -> &str {
+        &self.track_name
+    }
+
+    pub(crate) fn record_object(&self, payload_len: usize) {
+        self.objects.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(payload_len as u64, Ordering::Relaxed);
+        if let Some(activity) = &self.activity {
+            activity.touch();
+        }
+    }
+
+    pub(crate) fn record_stream(&self) {
+        self.streams.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stream_count(&self) -> u64 {
+        self.streams.load(Ordering::Relaxed)
+    }
+
+    /// Snapshot of the counters as a record ending now.
+    pub(crate) fn to_record(&self) -> BillingRecord {
+        let duration_ms = self.started_at.elapsed().as_millis() as u64;
+        BillingRecord {
+            session_id: self.session_id,
+            request_id: self.request_id,
+            track_namespace: self.track_namespace.clone(),
+            track_name: self.track_name.clone(),
+            objects: self.objects.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            started_at_unix_ms: self.started_at_unix_ms,
+            ended_at_unix_ms: self.started_at_unix_ms + duration_ms,
+            duration_ms,
+        }
+    }
+}
+
+fn unix_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubscriptionUsage;
+
+    #[test]
+    fn to_record_sums_objects_and_payload_bytes() {
+        // Arrange
+        let usage = SubscriptionUsage::new(7, 4, "live".to_string(), "audio".to_string());
+        // Act
+        usage.record_object(100);
+        usage.record_object(0);
+        usage.record_object(28);
+        let record = usage.to_record();
+        // Assert
+        assert_eq!((record.session_id, record.request_id), (7, 4));
+        assert_eq!((record.objects, record.bytes), (3, 128));
+        assert_eq!(
+            record.ended_at_unix_ms - record.started_at_unix_ms,
+            record.duration_ms
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+
+use super::{BillingRecord, BillingSink};
+
+/// POSTs each record as a JSON body to an operator-provided endpoint.
+pub(crate) struct WebhookBillingSink {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookBillingSink {
+    pub(crate) fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl BillingSink for WebhookBillingSink {
+    async fn export(&self, record: &BillingRecord) -> anyhow::Result<()> {
+        self.client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(record)?)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// Where an endpoint's certificate comes from and when to read it again:
+/// every `poll_interval` if the files changed, and whenever
+/// `reload_requests` is notified (SIGHUP). Stops with `shutdown`, releasing
+/// the endpoint.
+#[derive(Clone, Debug)]
+pub(crate) struct CertificateWatch {
+    pub(crate) cert_path: PathBuf,
+    pub(crate) key_path: PathBuf,
+    pub(crate) poll_interval: Option<Duration>,
+    pub(crate) reload_requests: Arc<Notify>,
+    pub(crate) shutdown: CancellationToken,
+}
+
+/// Modification times of the certificate and key. Renewal tools replace
+/// the files or their symlinks, either of which changes these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamps {
+    cert: Option<SystemTime>,
+    key: Option<SystemTime>,
+}
+
+impl FileStamps {
+    fn read(watch: &CertificateWatch) -> Self {
+        Self {
+            cert: modified(&watch.cert_path),
+            key: modified(&watch.key_path),
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Swaps the endpoint's certificate when the files change. Only new
+/// connections see the new certificate; established sessions are untouched.
+pub(crate) fn spawn_certificate_watcher(
+    reloader: moqt::CertificateReloader,
+    watch: CertificateWatch,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut loaded = FileStamps::read(&watch);
+        let mut poll = watch.poll_interval.map(|interval| {
+            let mut poll = tokio::time::interval(interval);
+            poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            poll
+        });
+        loop {
+            let requested = tokio::select! {
+                _ = tick(poll.as_mut()) => false,
+                _ = watch.reload_requests.notified() => true,
+                _ = watch.shutdown.cancelled() => return,
+            };
+            let current = FileStamps::read(&watch);
+            if !requested && current == loaded {
+                continue;
+            }
+            match reload(&reloader, &watch) {
+                Ok(()) => {
+                    tracing::info!(
+                        ?reloader,
+                        cert_path = %watch.cert_path.display(),
+                        "TLS certificate reloaded"
+                    );
+                    loaded = current;
+                }
+                // A renewal may have written the certificate but not yet the
+                // key; `loaded` stays put so the next poll tries again.
+                Err(error) => tracing::warn!(
+                    ?reloader,
+                    %error,
+                    "Failed to reload TLS certificate; keeping the previous one"
+                ),
+            }
+        }
+    })
+}
+
+/// Never completes without a poll interval, leaving only reload requests.
+async fn tick(poll: Option<&mut tokio::time::Interval>) {
+    match poll {
+        Some(poll) => {
+            poll.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+fn reload(reloader: &moqt::CertificateReloader, watch: &CertificateWatch) -> anyhow::Result<()> {
+    let cert_path = watch
+        .cert_path
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("certificate path is not UTF-8"))?;
+    let key_path = watch
+        .key_path
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("key path is not UTF-8"))?;
+    reloader.reload(cert_path, key_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_certificate(dir: &Path) -> CertificateWatch {
+        let rcgen::CertifiedKey { cert, signing_key } =
+            rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        std::fs::create_dir_all(dir).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, cert.pem()).unwrap();
+        std::fs::write(&key_path, signing_key.serialize_pem()).unwrap();
+        CertificateWatch {
+            cert_path,
+            key_path,
+            poll_interval: None,
+            reload_requests: Arc::new(Notify::new()),
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("relay-{name}-{}", std::process::id()))
+    }
+
+    fn server(watch: &CertificateWatch) -> moqt::Endpoint<moqt::QUIC> {
+        moqt::Endpoint::<moqt::QUIC>::create_server(&moqt::ServerConfig {
+            port: 0,
+            cert_path: watch.cert_path.to_str().unwrap().to_string(),
+            key_path: watch.key_path.to_str().unwrap().to_string(),
+            keep_alive_interval_sec: 15,
+            transport: moqt::TransportTuning::default(),
+            setup_timeout: None,
+            max_sessions: None,
+            accept_workers: 1,
+            control_message_limits: moqt::ControlMessageLimits::default(),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn renewed_certificate_is_loaded() {
+        // Arrange
+        let dir = test_dir("certificate-renewed");
+        let watch = write_certificate(&dir);
+        let endpoint = server(&watch);
+        let reloader = endpoint
+            .certificate_reloader()
+            .expect("QUIC server reloads");
+        let renewed = write_certificate(&dir);
+
+        // Act
+        let result = reload(&reloader, &renewed);
+
+        // Assert
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn unreadable_certificate_is_rejected() {
+        // Arrange
+        let dir = test_dir("certificate-unreadable");
+        let watch = write_certificate(&dir);
+        let endpoint = server(&watch);
+        let reloader = endpoint
+            .certificate_reloader()
+            .expect("QUIC server reloads");
+        std::fs::write(&watch.key_path, "not a key").unwrap();
+
+        // Act
+        let result = reload(&reloader, &watch);
+
+        // Assert
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rewriting_a_file_changes_its_stamp() {
+        // Arrange
+        let dir = test_dir("certificate-stamps");
+        let watch = write_certificate(&dir);
+        let before = FileStamps::read(&watch);
+        let later = SystemTime::now() + Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&watch.cert_path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        // Act
+        let after = FileStamps::read(&watch);
+
+        // Assert
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_ne!(before, after);
+        assert_eq!(before.key, after.key);
+    }
+}
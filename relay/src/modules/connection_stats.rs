@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::modules::{
+    relay::cache::duration::duration_from_env, session_repository::SessionRepository,
+};
+
+/// Every `RELAY_CONNECTION_STATS_SECS` (default 10, `0` = off), samples the
+/// QUIC path of every session, records it on the session span and logs it
+/// as `connection stats`, so a slow subscriber can be told apart from a
+/// slow network.
+pub(crate) fn spawn_connection_stats_job(
+    session_repo: Arc<tokio::sync::Mutex<SessionRepository>>,
+) -> Option<JoinHandle<()>> {
+    let interval = duration_from_env("RELAY_CONNECTION_STATS_SECS", 10);
+    if interval.is_zero() {
+        return None;
+    }
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let sessions = session_repo.lock().await.sessions_with_spans();
+            for (session_id, session, session_span) in sessions {
+                let stats = session.connection_stats();
+                let rtt_ms = stats.rtt.as_millis() as u64;
+                session_span.record("rtt_ms", rtt_ms);
+                session_span.record("congestion_window", stats.congestion_window);
+                session_span.record("lost_packets", stats.lost_packets);
+                session_span.record("dropped_datagrams", stats.dropped_datagrams);
+                session_span.in_scope(|| {
+                    tracing::info!(
+                        %session_id,
+                        rtt_ms,
+                        congestion_window = stats.congestion_window,
+                        sent_packets = stats.sent_packets,
+                        lost_packets = stats.lost_packets,
+                        loss_permille = stats.loss_permille(),
+                        dropped_datagrams = stats.dropped_datagrams,
+                        "connection stats"
+                    )
+                });
+            }
+        }
+    }))
+}
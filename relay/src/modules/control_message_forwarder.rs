@@ -203,6 +203,32 @@ impl ControlMessageForwarder {
         subscriber.send_unsubscribe(subscribe_id).await
     }
 
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.control_message_forwarder.subscribe_namespace",
+        skip_all,
+        fields(session_id = %session_id, track_namespace_prefix = %track_namespace_prefix)
+    )]
+    pub(crate) async fn subscribe_namespace(
+        &self,
+        session_id: SessionId,
+        track_namespace_prefix: String,
+    ) -> anyhow::Result<()> {
+        let subscriber = self.repository.lock().await.subscriber(session_id);
+        let Some(subscriber) = subscriber else {
+            tracing::error!("No subscriber");
+            return Err(anyhow::anyhow!("No subscriber"));
+        };
+        tracing::info!(
+            "Forwarded SUBSCRIBE_NAMESPACE '{}' to session:{}",
+            track_namespace_prefix,
+            session_id
+        );
+        subscriber
+            .send_subscribe_namespace(track_namespace_prefix)
+            .await
+    }
+
     #[tracing::instrument(
         level = "info",
         name = "relay.control_message_forwarder.unsubscribe_namespace",
@@ -14,6 +14,21 @@ impl DataObject {
         }
     }
 
+    /// Payload bytes carried by this object; headers and status objects count as zero.
+    pub(crate) fn payload_len(&self) -> usize {
+        match self {
+            Self::SubgroupHeader(_) => 0,
+            Self::SubgroupObject(field) => match &field.subgroup_object {
+                moqt::SubgroupObject::Payload { data, .. } => data.len(),
+                moqt::SubgroupObject::Status { .. } => 0,
+            },
+            Self::ObjectDatagram(datagram) => match datagram.field.payload() {
+                moqt::ObjectDatagramPayload::Payload(payload) => payload.len(),
+                moqt::ObjectDatagramPayload::Status(_) => 0,
+            },
+        }
+    }
+
     /// Resolves the absolute object_id of this object within its ingest stream.
     /// `prev_object_id` is the resolved object_id of the previous object on the same
     /// stream (`None` at the start of a subgroup or right after its header).
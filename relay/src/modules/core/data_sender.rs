@@ -9,6 +9,24 @@ use crate::modules::core::data_object::DataObject;
 pub(crate) trait DataSender: 'static + Send + Sync {
     async fn send_object(&mut self, object: DataObject) -> anyhow::Result<()>;
 
+    /// Orders this sender against the session's other data streams. Senders
+    /// without transport priority (datagrams, fetch) ignore it.
+    async fn set_priority(
+        &mut self,
+        _subscriber_priority: u8,
+        _publisher_priority: u8,
+        _group_order: moqt::GroupOrder,
+        _group_id: u64,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Abandons the stream with `error_code`. Senders without a stream
+    /// (datagrams) have nothing to reset.
+    async fn reset(&mut self, _error_code: u64) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
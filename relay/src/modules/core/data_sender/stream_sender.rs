@@ -43,6 +43,46 @@ impl<T: moqt::TransportProtocol> StreamSender<T> {
         }
     }
 
+    pub(crate) async fn set_priority(
+        &mut self,
+        subscriber_priority: u8,
+        publisher_priority: u8,
+        group_order: moqt::GroupOrder,
+        group_id: u64,
+    ) -> anyhow::Result<()> {
+        match self.inner.as_ref() {
+            Some(SenderInner::Uninitialized(sender)) => {
+                sender
+                    .set_priority(
+                        subscriber_priority,
+                        publisher_priority,
+                        group_order,
+                        group_id,
+                    )
+                    .await
+            }
+            Some(SenderInner::HeaderSent(sender)) => {
+                sender
+                    .set_priority(
+                        subscriber_priority,
+                        publisher_priority,
+                        group_order,
+                        group_id,
+                    )
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) async fn reset(&mut self, error_code: u64) -> anyhow::Result<()> {
+        match self.inner.as_ref() {
+            Some(SenderInner::Uninitialized(sender)) => sender.reset(error_code).await,
+            Some(SenderInner::HeaderSent(sender)) => sender.reset(error_code).await,
+            None => Ok(()),
+        }
+    }
+
     pub(crate) async fn close(&mut self) -> anyhow::Result<()> {
         match self.inner.as_mut() {
             Some(SenderInner::Uninitialized(sender)) => sender.close().await,
@@ -58,6 +98,27 @@ impl<T: moqt::TransportProtocol> DataSender for StreamSender<T> {
         self.send(object).await
     }
 
+    async fn set_priority(
+        &mut self,
+        subscriber_priority: u8,
+        publisher_priority: u8,
+        group_order: moqt::GroupOrder,
+        group_id: u64,
+    ) -> anyhow::Result<()> {
+        StreamSender::set_priority(
+            self,
+            subscriber_priority,
+            publisher_priority,
+            group_order,
+            group_id,
+        )
+        .await
+    }
+
+    async fn reset(&mut self, error_code: u64) -> anyhow::Result<()> {
+        StreamSender::reset(self, error_code).await
+    }
+
     async fn close(&mut self) -> anyhow::Result<()> {
         StreamSender::close(self).await
     }
@@ -0,0 +1,17 @@
+pub(crate) trait PublishNamespaceCancelHandler: 'static + Send + Sync {
+    fn track_namespace(&self) -> &str;
+    fn error_code(&self) -> u64;
+    fn reason_phrase(&self) -> &str;
+}
+
+impl PublishNamespaceCancelHandler for moqt::PublishNamespaceCancelHandler {
+    fn track_namespace(&self) -> &str {
+        self.track_namespace()
+    }
+    fn error_code(&self) -> u64 {
+        self.error_code
+    }
+    fn reason_phrase(&self) -> &str {
+        &self.reason_phrase
+    }
+}
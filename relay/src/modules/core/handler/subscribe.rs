@@ -10,20 +10,31 @@ pub(crate) trait SubscribeHandler: 'static + Send + Sync {
     fn subscribe_id(&self) -> u64;
     fn track_namespace(&self) -> &str;
     fn track_name(&self) -> &str;
-    fn _subscriber_priority(&self) -> u8;
-    fn _group_order(&self) -> GroupOrder;
+    fn subscriber_priority(&self) -> u8;
+    fn group_order(&self) -> GroupOrder;
+    /// Replaces the requested values with the ones the relay will serve,
+    /// so SUBSCRIBE_OK and the downstream subscription carry them.
+    fn override_preferences(&mut self, subscriber_priority: u8, group_order: GroupOrder);
     fn _forward(&self) -> bool;
     fn _filter_type(&self) -> FilterType;
-    fn _authorization_token(&self) -> Option<String>;
-    fn _max_cache_duration(&self) -> Option<u64>;
+    fn authorization_token(&self) -> Option<String>;
+    fn max_cache_duration(&self) -> Option<u64>;
     fn _delivery_timeout(&self) -> Option<u64>;
     fn allocate_track_alias(&self) -> u64;
+    fn release_track_alias(&self, track_alias: u64);
     async fn ok_with_track_alias(
         &self,
         track_alias: u64,
         expires: u64,
         content_exists: ContentExists,
-    ) -> Result<(), moqt::TransportSendError>;
+    ) -> Result<(), moqt::SubscribeOkError>;
+    async fn ok_with_max_cache_duration(
+        &self,
+        track_alias: u64,
+        expires: u64,
+        content_exists: ContentExists,
+        max_cache_duration: Option<u64>,
+    ) -> Result<(), moqt::SubscribeOkError>;
     async fn error(&self, code: u64, reason_phrase: String)
     -> Result<(), moqt::TransportSendError>;
     fn to_downstream_subscription(&self, track_alias: u64) -> DownstreamSubscription;
@@ -40,22 +51,26 @@ impl<T: moqt::TransportProtocol> SubscribeHandler for moqt::SubscribeHandler<T>
     fn track_name(&self) -> &str {
         &self.track_name
     }
-    fn _subscriber_priority(&self) -> u8 {
+    fn subscriber_priority(&self) -> u8 {
         self.subscriber_priority
     }
-    fn _group_order(&self) -> GroupOrder {
+    fn group_order(&self) -> GroupOrder {
         GroupOrder::from(self.group_order)
     }
+    fn override_preferences(&mut self, subscriber_priority: u8, group_order: GroupOrder) {
+        self.subscriber_priority = subscriber_priority;
+        self.group_order = group_order.as_moqt();
+    }
     fn _forward(&self) -> bool {
         self.forward
     }
     fn _filter_type(&self) -> FilterType {
         FilterType::from(self.filter_type)
     }
-    fn _authorization_token(&self) -> Option<String> {
+    fn authorization_token(&self) -> Option<String> {
         self.authorization_token.clone()
     }
-    fn _max_cache_duration(&self) -> Option<u64> {
+    fn max_cache_duration(&self) -> Option<u64> {
         self.max_cache_duration
     }
     fn _delivery_timeout(&self) -> Option<u64> {
@@ -65,12 +80,16 @@ impl<T: moqt::TransportProtocol> SubscribeHandler for moqt::SubscribeHandler<T>
         moqt::SubscribeHandler::allocate_track_alias(self)
     }
 
+    fn release_track_alias(&self, track_alias: u64) {
+        moqt::SubscribeHandler::release_track_alias(self, track_alias)
+    }
+
     async fn ok_with_track_alias(
         &self,
         track_alias: u64,
         expires: u64,
         content_exists: ContentExists,
-    ) -> Result<(), moqt::TransportSendError> {
+    ) -> Result<(), moqt::SubscribeOkError> {
         moqt::SubscribeHandler::ok_with_track_alias(
             self,
             track_alias,
@@ -80,6 +99,23 @@ impl<T: moqt::TransportProtocol> SubscribeHandler for moqt::SubscribeHandler<T>
         .await
     }
 
+    async fn ok_with_max_cache_duration(
+        &self,
+        track_alias: u64,
+        expires: u64,
+        content_exists: ContentExists,
+        max_cache_duration: Option<u64>,
+    ) -> Result<(), moqt::SubscribeOkError> {
+        moqt::SubscribeHandler::ok_with_max_cache_duration(
+            self,
+            track_alias,
+            expires,
+            content_exists.as_moqt(),
+            max_cache_duration,
+        )
+        .await
+    }
+
     async fn error(
         &self,
         code: u64,
@@ -3,6 +3,7 @@ use async_trait::async_trait;
 #[async_trait]
 pub(crate) trait SubscribeNamespaceHandler: 'static + Send + Sync {
     fn track_namespace_prefix(&self) -> &str;
+    fn authorization_token(&self) -> Option<String>;
     async fn ok(&self) -> Result<(), moqt::TransportSendError>;
     async fn error(&self, code: u64, reason_phrase: String)
     -> Result<(), moqt::TransportSendError>;
@@ -14,6 +15,10 @@ impl<T: moqt::TransportProtocol> SubscribeNamespaceHandler for moqt::SubscribeNa
         &self.track_namespace_prefix
     }
 
+    fn authorization_token(&self) -> Option<String> {
+        self.authorization_token.clone()
+    }
+
     async fn ok(&self) -> Result<(), moqt::TransportSendError> {
         self.ok().await
     }
@@ -0,0 +1,34 @@
+pub(crate) trait SubscribeUpdateHandler: 'static + Send + Sync {
+    fn subscription_request_id(&self) -> u64;
+    fn start_location(&self) -> moqt::Location;
+    fn end_group(&self) -> Option<u64>;
+    fn subscriber_priority(&self) -> u8;
+    fn forward(&self) -> bool;
+    fn delivery_timeout(&self) -> Option<u64>;
+}
+
+impl SubscribeUpdateHandler for moqt::SubscribeUpdateHandler {
+    fn subscription_request_id(&self) -> u64 {
+        self.subscription_request_id()
+    }
+
+    fn start_location(&self) -> moqt::Location {
+        self.start_location
+    }
+
+    fn end_group(&self) -> Option<u64> {
+        self.end_group
+    }
+
+    fn subscriber_priority(&self) -> u8 {
+        self.subscriber_priority
+    }
+
+    fn forward(&self) -> bool {
+        self.forward
+    }
+
+    fn delivery_timeout(&self) -> Option<u64> {
+        self.delivery_timeout
+    }
+}
@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+
+use crate::modules::enums::{ContentExists, GroupOrder};
+
+#[async_trait]
+pub(crate) trait TrackStatusHandler: 'static + Send + Sync {
+    fn request_id(&self) -> u64;
+    fn track_namespace(&self) -> &str;
+    fn track_name(&self) -> &str;
+    async fn ok(
+        &self,
+        group_order: GroupOrder,
+        content_exists: ContentExists,
+    ) -> Result<(), moqt::TransportSendError>;
+    async fn error(&self, code: u64, reason_phrase: String)
+    -> Result<(), moqt::TransportSendError>;
+}
+
+#[async_trait]
+impl<T: moqt::TransportProtocol> TrackStatusHandler for moqt::TrackStatusRequestHandler<T> {
+    fn request_id(&self) -> u64 {
+        self.request_id()
+    }
+
+    fn track_namespace(&self) -> &str {
+        &self.track_namespace
+    }
+
+    fn track_name(&self) -> &str {
+        &self.track_name
+    }
+
+    async fn ok(
+        &self,
+        group_order: GroupOrder,
+        content_exists: ContentExists,
+    ) -> Result<(), moqt::TransportSendError> {
+        self.ok(group_order.as_moqt(), content_exists.as_moqt())
+            .await
+    }
+
+    async fn error(
+        &self,
+        code: u64,
+        reason_phrase: String,
+    ) -> Result<(), moqt::TransportSendError> {
+        self.error(code, reason_phrase).await
+    }
+}
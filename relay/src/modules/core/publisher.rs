@@ -14,6 +14,13 @@ use crate::modules::core::{
 pub(crate) trait Publisher: 'static + Send + Sync {
     async fn send_publish_namespace(&self, namespaces: String) -> anyhow::Result<()>;
     async fn send_publish_namespace_done(&self, namespace: String) -> anyhow::Result<()>;
+    async fn send_publish_done(
+        &self,
+        request_id: u64,
+        status_code: u64,
+        stream_count: u64,
+        reason_phrase: String,
+    ) -> anyhow::Result<()>;
     async fn send_publish(
         &self,
         track_namespace: String,
@@ -26,6 +33,9 @@ pub(crate) trait Publisher: 'static + Send + Sync {
     fn new_datagram(&self, downstream_subscription: &DownstreamSubscription)
     -> Box<dyn DataSender>;
     async fn new_fetch_sender(&self, request_id: u64) -> anyhow::Result<Box<dyn FetchSender>>;
+    /// Returns a downstream subscription's alias to the session once nothing
+    /// is sent on it anymore.
+    fn release_track_alias(&self, track_alias: u64);
 }
 
 #[async_trait]
@@ -38,6 +48,17 @@ impl<T: moqt::TransportProtocol> Publisher for moqt::Publisher<T> {
         self.publish_namespace_done(namespace).await
     }
 
+    async fn send_publish_done(
+        &self,
+        request_id: u64,
+        status_code: u64,
+        stream_count: u64,
+        reason_phrase: String,
+    ) -> anyhow::Result<()> {
+        self.publish_done(request_id, status_code, stream_count, reason_phrase)
+            .await
+    }
+
     async fn send_publish(
         &self,
         track_namespace: String,
@@ -73,4 +94,8 @@ impl<T: moqt::TransportProtocol> Publisher for moqt::Publisher<T> {
         let sender = self.create_fetch_stream(request_id).await?;
         Ok(Box::new(sender))
     }
+
+    fn release_track_alias(&self, track_alias: u64) {
+        moqt::Publisher::release_track_alias(self, track_alias)
+    }
 }
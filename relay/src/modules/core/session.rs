@@ -1,7 +1,10 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
 
-use crate::modules::core::{
-    publisher::Publisher, session_event::MoqtSessionEvent, subscriber::Subscriber,
+use crate::modules::{
+    core::{publisher::Publisher, session_event::MoqtSessionEvent, subscriber::Subscriber},
+    error::RelayError,
 };
 
 #[async_trait]
@@ -9,6 +12,12 @@ pub(crate) trait Session: 'static + Send + Sync {
     fn as_publisher(&self) -> Box<dyn Publisher>;
     fn as_subscriber(&self) -> Box<dyn Subscriber>;
     async fn receive_moqt_session_event(&self) -> anyhow::Result<MoqtSessionEvent>;
+    async fn go_away(&self, new_session_uri: &str) -> anyhow::Result<()>;
+    async fn drain(&self, timeout: Duration) -> bool;
+    fn close(&self);
+    /// Closes the session with the termination code of `error`.
+    fn terminate(&self, error: &RelayError);
+    fn connection_stats(&self) -> moqt::ConnectionStats;
 }
 
 #[async_trait]
@@ -30,6 +39,9 @@ impl<T: moqt::TransportProtocol> Session for moqt::Session<T> {
             moqt::SessionEvent::PublishNamespaceDone(publish_namespace_done_handler) => {
                 MoqtSessionEvent::PublishNamespaceDone(Box::new(publish_namespace_done_handler))
             }
+            moqt::SessionEvent::PublishNamespaceCancel(publish_namespace_cancel_handler) => {
+                MoqtSessionEvent::PublishNamespaceCancel(Box::new(publish_namespace_cancel_handler))
+            }
             moqt::SessionEvent::SubscribeNameSpace(subscribe_namespace_handler) => {
                 MoqtSessionEvent::SubscribeNamespace(Box::new(subscribe_namespace_handler))
             }
@@ -42,15 +54,50 @@ impl<T: moqt::TransportProtocol> Session for moqt::Session<T> {
             moqt::SessionEvent::Subscribe(subscribe_handler) => {
                 MoqtSessionEvent::Subscribe(Box::new(subscribe_handler))
             }
+            moqt::SessionEvent::SubscribeUpdate(subscribe_update_handler) => {
+                MoqtSessionEvent::SubscribeUpdate(Box::new(subscribe_update_handler))
+            }
             moqt::SessionEvent::Unsubscribe(unsubscribe_handler) => {
                 MoqtSessionEvent::Unsubscribe(Box::new(unsubscribe_handler))
             }
+            moqt::SessionEvent::PublishDone(publish_done_handler) => {
+                MoqtSessionEvent::PublishDone(Box::new(publish_done_handler))
+            }
             moqt::SessionEvent::Disconnected() => MoqtSessionEvent::Disconnected(),
             moqt::SessionEvent::ProtocolViolation() => MoqtSessionEvent::ProtocolViolation(),
             moqt::SessionEvent::Fetch(fetch_handler) => {
                 MoqtSessionEvent::Fetch(Box::new(fetch_handler))
             }
+            moqt::SessionEvent::FetchCancel(fetch_cancel_handler) => {
+                MoqtSessionEvent::FetchCancel(Box::new(fetch_cancel_handler))
+            }
+            moqt::SessionEvent::TrackStatusRequest(track_status_request_handler) => {
+                MoqtSessionEvent::TrackStatus(Box::new(track_status_request_handler))
+            }
+            moqt::SessionEvent::GoAway(new_session_uri) => {
+                MoqtSessionEvent::GoAway(new_session_uri)
+            }
         };
         Ok(result)
     }
+
+    async fn go_away(&self, new_session_uri: &str) -> anyhow::Result<()> {
+        moqt::Session::go_away(self, new_session_uri).await
+    }
+
+    async fn drain(&self, timeout: Duration) -> bool {
+        moqt::Session::drain(self, timeout).await
+    }
+
+    fn close(&self) {
+        moqt::Session::close(self)
+    }
+
+    fn terminate(&self, error: &RelayError) {
+        moqt::Session::terminate(self, error.code() as u32, &error.to_string())
+    }
+
+    fn connection_stats(&self) -> moqt::ConnectionStats {
+        moqt::Session::connection_stats(self)
+    }
 }
@@ -1,19 +1,29 @@
 use crate::modules::core::handler::{
-    fetch::FetchHandler, publish::PublishHandler, publish_namespace::PublishNamespaceHandler,
+    fetch::FetchHandler, fetch_cancel::FetchCancelHandler, publish::PublishHandler,
+    publish_done::PublishDoneHandler, publish_namespace::PublishNamespaceHandler,
+    publish_namespace_cancel::PublishNamespaceCancelHandler,
     publish_namespace_done::PublishNamespaceDoneHandler, subscribe::SubscribeHandler,
-    subscribe_namespace::SubscribeNamespaceHandler, unsubscribe::UnsubscribeHandler,
+    subscribe_namespace::SubscribeNamespaceHandler, subscribe_update::SubscribeUpdateHandler,
+    track_status::TrackStatusHandler, unsubscribe::UnsubscribeHandler,
     unsubscribe_namespace::UnsubscribeNamespaceHandler,
 };
 
 pub(crate) enum MoqtSessionEvent {
     PublishNamespace(Box<dyn PublishNamespaceHandler>),
     PublishNamespaceDone(Box<dyn PublishNamespaceDoneHandler>),
+    PublishNamespaceCancel(Box<dyn PublishNamespaceCancelHandler>),
     SubscribeNamespace(Box<dyn SubscribeNamespaceHandler>),
     UnsubscribeNamespace(Box<dyn UnsubscribeNamespaceHandler>),
     Publish(Box<dyn PublishHandler>),
     Subscribe(Box<dyn SubscribeHandler>),
+    SubscribeUpdate(Box<dyn SubscribeUpdateHandler>),
     Unsubscribe(Box<dyn UnsubscribeHandler>),
+    PublishDone(Box<dyn PublishDoneHandler>),
     Fetch(Box<dyn FetchHandler>),
+    FetchCancel(Box<dyn FetchCancelHandler>),
+    TrackStatus(Box<dyn TrackStatusHandler>),
+    /// The peer sent GOAWAY with this New Session URI.
+    GoAway(String),
     Disconnected(),
     ProtocolViolation(),
 }
@@ -23,12 +33,18 @@ impl std::fmt::Debug for MoqtSessionEvent {
         let name = match self {
             MoqtSessionEvent::PublishNamespace(_) => "PublishNamespace",
             MoqtSessionEvent::PublishNamespaceDone(_) => "PublishNamespaceDone",
+            MoqtSessionEvent::PublishNamespaceCancel(_) => "PublishNamespaceCancel",
             MoqtSessionEvent::SubscribeNamespace(_) => "SubscribeNamespace",
             MoqtSessionEvent::UnsubscribeNamespace(_) => "UnsubscribeNamespace",
             MoqtSessionEvent::Publish(_) => "Publish",
             MoqtSessionEvent::Subscribe(_) => "Subscribe",
+            MoqtSessionEvent::SubscribeUpdate(_) => "SubscribeUpdate",
             MoqtSessionEvent::Unsubscribe(_) => "Unsubscribe",
+            MoqtSessionEvent::PublishDone(_) => "PublishDone",
             MoqtSessionEvent::Fetch(_) => "Fetch",
+            MoqtSessionEvent::FetchCancel(_) => "FetchCancel",
+            MoqtSessionEvent::TrackStatus(_) => "TrackStatus",
+            MoqtSessionEvent::GoAway(_) => "GoAway",
             MoqtSessionEvent::Disconnected() => "Disconnected",
             MoqtSessionEvent::ProtocolViolation() => "ProtocolViolation",
         };
@@ -15,6 +15,7 @@ pub(crate) trait Subscriber: 'static + Send + Sync {
         option: SubscribeOption,
     ) -> anyhow::Result<UpstreamSubscription>;
     async fn send_unsubscribe(&self, subscribe_id: u64) -> anyhow::Result<()>;
+    async fn send_subscribe_namespace(&self, namespace: String) -> anyhow::Result<()>;
     async fn send_unsubscribe_namespace(&self, namespace: String) -> anyhow::Result<()>;
     async fn create_data_receiver(
         &mut self,
@@ -53,6 +54,7 @@ impl<T: moqt::TransportProtocol> Subscriber for moqt::Subscriber<T> {
             group_order: option.group_order.as_moqt(),
             forward: option.forward,
             filter_type: option.filter_type.as_moqt(),
+            max_cache_duration: None,
         };
         let moqt_sub = self.subscribe(track_namespace, track_name, option).await?;
         Ok(UpstreamSubscription::from(moqt_sub))
@@ -68,6 +70,16 @@ impl<T: moqt::TransportProtocol> Subscriber for moqt::Subscriber<T> {
         self.unsubscribe(subscribe_id).await
     }
 
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.subscriber.send_subscribe_namespace",
+        skip_all,
+        fields(namespace = %namespace)
+    )]
+    async fn send_subscribe_namespace(&self, namespace: String) -> anyhow::Result<()> {
+        self.subscribe_namespace(namespace).await
+    }
+
     #[tracing::instrument(
         level = "info",
         name = "relay.subscriber.send_unsubscribe_namespace",
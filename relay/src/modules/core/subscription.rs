@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::modules::enums::{ContentExists, FilterType, GroupOrder};
 
 #[derive(Clone)]
@@ -43,6 +45,10 @@ impl UpstreamSubscription {
         ContentExists::from(self.inner.content_exists())
     }
 
+    pub(crate) fn max_cache_duration(&self) -> Option<u64> {
+        self.inner.max_cache_duration()
+    }
+
     pub(crate) fn publish_accept_options(&self) -> Option<(u8, FilterType)> {
         match &self.inner {
             moqt::Subscription::PublisherInitiated(subscription) => Some((
@@ -84,6 +90,14 @@ impl DownstreamSubscription {
     pub(crate) fn group_order(&self) -> GroupOrder {
         GroupOrder::from(self.inner.group_order())
     }
+
+    pub(crate) fn subscriber_priority(&self) -> u8 {
+        self.inner.subscriber_priority()
+    }
+
+    pub(crate) fn delivery_timeout(&self) -> Option<Duration> {
+        self.inner.delivery_timeout().map(Duration::from_millis)
+    }
 }
 
 impl From<moqt::Subscription> for DownstreamSubscription {
@@ -125,6 +125,63 @@ pub(crate) enum SubscribeErrorCode {
     ExpiredAuthToken = 0x12,
 }
 
+// https://www.ietf.org/archive/id/draft-ietf-moq-transport-14.html#section-9.24
+// PUBLISH_NAMESPACE_ERROR and SUBSCRIBE_NAMESPACE_ERROR codes shared by both.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub(crate) enum NamespaceErrorCode {
+    InternalError = 0x0,
+    Unauthorized = 0x1,
+    Timeout = 0x2,
+    NotSupported = 0x3,
+    MalformedAuthToken = 0x10,
+    ExpiredAuthToken = 0x12,
+}
+
+// https://www.ietf.org/archive/id/draft-ietf-moq-transport-14.html#section-9.12
+// PUBLISH_DONE status codes.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub(crate) enum PublishDoneStatusCode {
+    InternalError = 0x0,
+    Unauthorized = 0x1,
+    TrackEnded = 0x2,
+    SubscriptionEnded = 0x3,
+    GoingAway = 0x4,
+    Expired = 0x5,
+    TooFarBehind = 0x6,
+    MalformedTrack = 0x7,
+}
+
+// https://www.ietf.org/archive/id/draft-ietf-moq-transport-14.html
+// RESET_STREAM error codes for subgroup streams.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub(crate) enum StreamResetCode {
+    InternalError = 0x0,
+    Cancelled = 0x1,
+    DeliveryTimeout = 0x2,
+    SessionClosed = 0x3,
+}
+
+// https://www.ietf.org/archive/id/draft-ietf-moq-transport-14.html#section-13.1.1
+// Session termination error codes.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub(crate) enum TerminationErrorCode {
+    NoError = 0x0,
+    InternalError = 0x1,
+    Unauthorized = 0x2,
+    ProtocolViolation = 0x3,
+    TooManyRequests = 0x7,
+    GoawayTimeout = 0x10,
+    ControlMessageTimeout = 0x11,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) enum ContentExists {
     False,
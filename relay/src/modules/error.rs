@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use crate::modules::enums::TerminationErrorCode;
+
+/// Why the relay ends a session, with the termination code the peer sees.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum RelayError {
+    /// Refused under resource pressure. Draft-14 has no overload code, so
+    /// the reason tells the peer it may retry.
+    #[error("relay overloaded; retry later")]
+    Overloaded,
+    #[error("idle for {0:?}")]
+    Idle(Duration),
+    /// The peer sent a message the draft says must close the session.
+    #[error("protocol violation: {0}")]
+    ProtocolViolation(String),
+    /// Handling the session's control messages failed inside the relay.
+    #[error("session handling failed: {0}")]
+    Internal(String),
+}
+
+impl RelayError {
+    pub(crate) fn code(&self) -> TerminationErrorCode {
+        match self {
+            Self::Overloaded | Self::Internal(_) => TerminationErrorCode::InternalError,
+            Self::Idle(_) => TerminationErrorCode::NoError,
+            Self::ProtocolViolation(_) => TerminationErrorCode::ProtocolViolation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_an_idle_close_is_not_an_error() {
+        // Arrange
+        let errors = [
+            RelayError::Overloaded,
+            RelayError::Idle(Duration::from_secs(60)),
+            RelayError::Internal("worker panicked".to_string()),
+            RelayError::ProtocolViolation("bad joining fetch".to_string()),
+        ];
+
+        // Act
+        let codes: Vec<_> = errors.iter().map(RelayError::code).collect();
+
+        // Assert
+        assert_eq!(
+            codes,
+            vec![
+                TerminationErrorCode::InternalError,
+                TerminationErrorCode::NoError,
+                TerminationErrorCode::InternalError,
+                TerminationErrorCode::ProtocolViolation,
+            ]
+        );
+    }
+}
@@ -2,28 +2,39 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
+use crate::config::{Authorizer, NamespacePolicyTable};
 use crate::modules::{
     control_message_forwarder::ControlMessageForwarder,
+    enums::PublishDoneStatusCode,
+    error::RelayError,
     inter_relay::InterRelayConnectionManager,
     relay::{
         cache::store::TrackCacheStore, egress::coordinator::EgressCommand,
         ingress::ingress_coordinator::IngressCommand,
     },
+    relay_events::{RelayEvent, RelayEvents},
     route_registry::RelayRouteRegistry,
     sequences::{
         CascadingRelayContext,
+        authorization::Authorization,
+        bandwidth_probe::BandwidthProbe,
         fetch::Fetch,
+        fetch_cancel::FetchCancel,
         publish::Publish,
+        publish_done::PublishDone,
         publish_namespace::PublishNamespace,
+        publish_namespace_cancel::PublishNamespaceCancel,
         publish_namespace_done::PublishNamespaceDone,
         subscribe::Subscribe,
         subscribe_namespace::SubscribeNameSpace,
+        subscribe_update::SubscribeUpdate,
         tables::{
             hashmap_table::InMemoryLocalPubSubDirectory,
             table::{
                 LocalPubSubDirectory, RemovedSessionSubscriptions, UpstreamSubscriptionOrigin,
             },
         },
+        track_status::TrackStatus,
         unsubscribe::Unsubscribe,
         unsubscribe_namespace::UnsubscribeNamespace,
         upstream_serializer::UpstreamCreationSerializer,
@@ -51,6 +62,11 @@ struct WorkerDeps {
     upstream_publisher_resolver: Arc<UpstreamPublisherResolver>,
     cache_store: Arc<TrackCacheStore>,
     upstream_serializer: UpstreamCreationSerializer,
+    /// Largest bandwidth probe served; 0 disables probing.
+    bandwidth_probe_max_bytes: u64,
+    namespace_policies: Arc<NamespacePolicyTable>,
+    authorizer: Authorizer,
+    events: RelayEvents,
 }
 
 impl EventHandler {
@@ -64,6 +80,10 @@ impl EventHandler {
         inter_relay_connection_manager: Arc<InterRelayConnectionManager>,
         upstream_publisher_resolver: Arc<UpstreamPublisherResolver>,
         cache_store: Arc<TrackCacheStore>,
+        bandwidth_probe_max_bytes: u64,
+        namespace_policies: Arc<NamespacePolicyTable>,
+        authorizer: Authorizer,
+        events: RelayEvents,
     ) -> Self {
         let relay_session_event_handler = Self::create_relay_session_event_handler(
             repo,
@@ -74,6 +94,10 @@ impl EventHandler {
             inter_relay_connection_manager,
             upstream_publisher_resolver,
             cache_store,
+            bandwidth_probe_max_bytes,
+            namespace_policies,
+            authorizer,
+            events,
         );
         Self {
             relay_session_event_handler,
@@ -90,6 +114,10 @@ impl EventHandler {
         inter_relay_connection_manager: Arc<InterRelayConnectionManager>,
         upstream_publisher_resolver: Arc<UpstreamPublisherResolver>,
         cache_store: Arc<TrackCacheStore>,
+        bandwidth_probe_max_bytes: u64,
+        namespace_policies: Arc<NamespacePolicyTable>,
+        authorizer: Authorizer,
+        events: RelayEvents,
     ) -> tokio::task::JoinHandle<()> {
         tokio::task::Builder::new()
             .name("Relay Session Event Handler")
@@ -111,18 +139,31 @@ impl EventHandler {
                 // of the exiting worker, so the reader can remove stale entries.
                 let mut workers: tokio::task::JoinSet<SessionId> =
                     tokio::task::JoinSet::new();
+                // A panicked worker yields no session_id, only its task id.
+                let mut worker_sessions: HashMap<tokio::task::Id, SessionId> = HashMap::new();
 
                 loop {
                     tokio::select! {
                         // Reap finished workers and remove their sender map entries.
-                        result = workers.join_next(), if !workers.is_empty() => {
+                        result = workers.join_next_with_id(), if !workers.is_empty() => {
                             match result {
-                                Some(Ok(session_id)) => {
+                                Some(Ok((task_id, session_id))) => {
+                                    worker_sessions.remove(&task_id);
                                     sender_map.remove(&session_id);
                                     tracing::debug!(session_id, "session worker exited");
                                 }
                                 Some(Err(error)) => {
-                                    tracing::error!(?error, "session worker panicked");
+                                    let Some(session_id) = worker_sessions.remove(&error.id()) else {
+                                        tracing::error!(?error, "session worker panicked");
+                                        continue;
+                                    };
+                                    sender_map.remove(&session_id);
+                                    tracing::error!(session_id, ?error, "session worker panicked; terminating its session");
+                                    // Only this session goes; its disconnect
+                                    // starts a fresh worker that cleans it up.
+                                    if let Some(session) = repo.lock().await.session(session_id) {
+                                        session.terminate(&RelayError::Internal(error.to_string()));
+                                    }
                                 }
                                 None => {}
                             }
@@ -139,12 +180,21 @@ impl EventHandler {
                             let session_id = match &event {
                                 SessionEvent::PublishNameSpace(id, _)
                                 | SessionEvent::PublishNamespaceDone(id, _)
+                                | SessionEvent::PublishNamespaceCancel(id, _)
                                 | SessionEvent::SubscribeNameSpace(id, _)
                                 | SessionEvent::UnsubscribeNameSpace(id, _)
                                 | SessionEvent::Publish(id, _)
                                 | SessionEvent::Subscribe(id, _)
+                                | SessionEvent::SubscribeUpdate(id, _)
                                 | SessionEvent::Unsubscribe(id, _)
+                                | SessionEvent::PublishDone(id, _)
                                 | SessionEvent::Fetch(id, _)
+                                | SessionEvent::FetchCancel(id, _)
+                                | SessionEvent::TrackStatus(id, _)
+                                | SessionEvent::GoAway(id, _)
+                                | SessionEvent::SubscriptionRangeComplete(id, ..)
+                                | SessionEvent::SubscriberTooFarBehind(id, _)
+                                | SessionEvent::SubscriptionExpired(id, _)
                                 | SessionEvent::Disconnected(id)
                                 | SessionEvent::ProtocolViolation(id) => *id,
                             };
@@ -164,8 +214,13 @@ impl EventHandler {
                                     upstream_publisher_resolver: upstream_publisher_resolver.clone(),
                                     cache_store: cache_store.clone(),
                                     upstream_serializer: upstream_serializer.clone(),
+                                    bandwidth_probe_max_bytes,
+                                    namespace_policies: namespace_policies.clone(),
+                                    authorizer: authorizer.clone(),
+                                    events: events.clone(),
                                 };
-                                workers.spawn(Self::session_worker(session_id, rx, deps));
+                                let worker = workers.spawn(Self::session_worker(session_id, rx, deps));
+                                worker_sessions.insert(worker.id(), session_id);
                                 tx
                             });
 
@@ -209,6 +264,10 @@ impl EventHandler {
             upstream_publisher_resolver,
             cache_store,
             upstream_serializer,
+            bandwidth_probe_max_bytes,
+            namespace_policies,
+            authorizer,
+            events,
         } = deps;
 
         while let Some(event) = rx.recv().await {
@@ -238,6 +297,7 @@ impl EventHandler {
                         &control_message_forwarder,
                         &ingress_sender,
                         &egress_sender,
+                        cache_store.as_ref(),
                         route_registry.as_ref(),
                         inter_relay_connection_manager.as_ref(),
                     )
@@ -247,10 +307,17 @@ impl EventHandler {
                 continue;
             };
             let event_span = Self::session_event_span(&session_span, &event);
+            let admitted = Authorization {}
+                .admit(session_id, &authorizer, &control_message_forwarder, &event)
+                .instrument(event_span.clone())
+                .await;
+            if !admitted {
+                continue;
+            }
 
             match event {
                 SessionEvent::PublishNameSpace(session_id, handler) => {
-                    PublishNamespace {}
+                    let announced = PublishNamespace {}
                         .handle(
                             session_id,
                             &session_span,
@@ -265,6 +332,12 @@ impl EventHandler {
                         )
                         .instrument(event_span)
                         .await;
+                    if announced {
+                        events.emit(RelayEvent::NamespaceAnnounced {
+                            session_id,
+                            track_namespace: handler.track_namespace().to_string(),
+                        });
+                    }
                 }
                 SessionEvent::PublishNamespaceDone(session_id, handler) => {
                     PublishNamespaceDone {}
@@ -273,6 +346,9 @@ impl EventHandler {
                             &session_span,
                             local_pub_sub_directory.as_ref(),
                             &control_message_forwarder,
+                            &ingress_sender,
+                            &egress_sender,
+                            cache_store.as_ref(),
                             CascadingRelayContext {
                                 route_registry: route_registry.as_ref(),
                                 inter_relay_connection_manager: inter_relay_connection_manager
@@ -290,7 +366,11 @@ impl EventHandler {
                             &session_span,
                             local_pub_sub_directory.as_ref(),
                             &control_message_forwarder,
-                            route_registry.as_ref(),
+                            CascadingRelayContext {
+                                route_registry: route_registry.as_ref(),
+                                inter_relay_connection_manager: inter_relay_connection_manager
+                                    .as_ref(),
+                            },
                             handler.as_ref(),
                         )
                         .instrument(event_span)
@@ -331,8 +411,29 @@ impl EventHandler {
                         .instrument(event_span)
                         .await;
                 }
+                SessionEvent::Subscribe(session_id, handler)
+                    if bandwidth_probe_max_bytes > 0
+                        && BandwidthProbe::is_probe_request(handler.as_ref()) =>
+                {
+                    BandwidthProbe {}
+                        .handle(
+                            session_id,
+                            &session_span,
+                            &egress_sender,
+                            bandwidth_probe_max_bytes,
+                            handler,
+                        )
+                        .instrument(event_span)
+                        .await;
+                }
                 SessionEvent::Subscribe(session_id, handler) => {
-                    Subscribe {}
+                    let started = RelayEvent::SubscriptionStarted {
+                        session_id,
+                        subscribe_id: handler.subscribe_id(),
+                        track_namespace: handler.track_namespace().to_string(),
+                        track_name: handler.track_name().to_string(),
+                    };
+                    let subscribed = Subscribe {}
                         .handle(
                             session_id,
                             &session_span,
@@ -343,6 +444,34 @@ impl EventHandler {
                             upstream_publisher_resolver.as_ref(),
                             &cache_store,
                             &upstream_serializer,
+                            &namespace_policies,
+                            handler,
+                        )
+                        .instrument(event_span)
+                        .await;
+                    if subscribed {
+                        events.emit(started);
+                    }
+                }
+                SessionEvent::PublishNamespaceCancel(session_id, handler) => {
+                    PublishNamespaceCancel {}
+                        .handle(
+                            session_id,
+                            &session_span,
+                            local_pub_sub_directory.as_ref(),
+                            handler.as_ref(),
+                        )
+                        .instrument(event_span)
+                        .await;
+                }
+                SessionEvent::SubscribeUpdate(session_id, handler) => {
+                    SubscribeUpdate {}
+                        .handle(
+                            session_id,
+                            &session_span,
+                            local_pub_sub_directory.as_ref(),
+                            &egress_sender,
+                            &namespace_policies,
                             handler,
                         )
                         .instrument(event_span)
@@ -357,11 +486,26 @@ impl EventHandler {
                             &control_message_forwarder,
                             &ingress_sender,
                             &egress_sender,
+                            &cache_store,
                             handler,
                         )
                         .instrument(event_span)
                         .await;
                 }
+                SessionEvent::PublishDone(session_id, handler) => {
+                    PublishDone {}
+                        .handle(
+                            session_id,
+                            &session_span,
+                            local_pub_sub_directory.as_ref(),
+                            &ingress_sender,
+                            &egress_sender,
+                            cache_store.as_ref(),
+                            handler.as_ref(),
+                        )
+                        .instrument(event_span)
+                        .await;
+                }
                 SessionEvent::Fetch(session_id, handler) => {
                     Fetch {}
                         .handle(
@@ -377,6 +521,84 @@ impl EventHandler {
                         .instrument(event_span)
                         .await;
                 }
+                SessionEvent::FetchCancel(session_id, handler) => {
+                    FetchCancel {}
+                        .handle(session_id, &session_span, &egress_sender, handler)
+                        .instrument(event_span)
+                        .await;
+                }
+                SessionEvent::TrackStatus(session_id, handler) => {
+                    TrackStatus {}
+                        .handle(
+                            session_id,
+                            &session_span,
+                            local_pub_sub_directory.as_ref(),
+                            &cache_store,
+                            upstream_publisher_resolver.as_ref(),
+                            handler,
+                        )
+                        .instrument(event_span)
+                        .await;
+                }
+                SessionEvent::GoAway(session_id, new_session_uri) => {
+                    // FIXME: migrating upstream subscriptions to the new session
+                    // needs ingest from multiple publishers (see
+                    // upstream_publisher_resolver); until then the relay keeps
+                    // the current session until the peer closes it.
+                    event_span.in_scope(|| {
+                        tracing::info!(
+                            session_id,
+                            %new_session_uri,
+                            "Peer sent GOAWAY; keeping session until it closes"
+                        );
+                    });
+                }
+                SessionEvent::SubscriptionRangeComplete(session_id, subscribe_id, end_group) => {
+                    Unsubscribe {}
+                        .complete_range(
+                            session_id,
+                            &session_span,
+                            subscribe_id,
+                            end_group,
+                            local_pub_sub_directory.as_ref(),
+                            &control_message_forwarder,
+                            &ingress_sender,
+                            &egress_sender,
+                            &cache_store,
+                        )
+                        .instrument(event_span)
+                        .await;
+                }
+                SessionEvent::SubscriberTooFarBehind(session_id, subscribe_id) => {
+                    Unsubscribe {}
+                        .too_far_behind(
+                            session_id,
+                            &session_span,
+                            subscribe_id,
+                            local_pub_sub_directory.as_ref(),
+                            &control_message_forwarder,
+                            &ingress_sender,
+                            &egress_sender,
+                            &cache_store,
+                        )
+                        .instrument(event_span)
+                        .await;
+                }
+                SessionEvent::SubscriptionExpired(session_id, subscribe_id) => {
+                    Unsubscribe {}
+                        .expired(
+                            session_id,
+                            &session_span,
+                            subscribe_id,
+                            local_pub_sub_directory.as_ref(),
+                            &control_message_forwarder,
+                            &ingress_sender,
+                            &egress_sender,
+                            &cache_store,
+                        )
+                        .instrument(event_span)
+                        .await;
+                }
                 SessionEvent::Disconnected(session_id) => {
                     let disconnected_span = tracing::info_span!(
                         parent: &event_span,
@@ -391,6 +613,7 @@ impl EventHandler {
                             &control_message_forwarder,
                             &ingress_sender,
                             &egress_sender,
+                            cache_store.as_ref(),
                             route_registry.as_ref(),
                             inter_relay_connection_manager.as_ref(),
                         )
@@ -413,6 +636,7 @@ impl EventHandler {
                             &control_message_forwarder,
                             &ingress_sender,
                             &egress_sender,
+                            cache_store.as_ref(),
                             route_registry.as_ref(),
                             inter_relay_connection_manager.as_ref(),
                         )
@@ -426,6 +650,7 @@ impl EventHandler {
             // Exit after processing a terminal event: the session is cleaned
             // up and no further events for it are meaningful.
             if is_terminal {
+                events.emit(RelayEvent::SessionClosed { session_id });
                 break;
             }
         }
@@ -449,6 +674,13 @@ impl EventHandler {
                 event = "PublishNamespaceDone",
                 track_namespace = %handler.track_namespace(),
             ),
+            SessionEvent::PublishNamespaceCancel(session_id, handler) => tracing::info_span!(
+                parent: session_span,
+                "relay.session.event",
+                session_id = %session_id,
+                event = "PublishNamespaceCancel",
+                track_namespace = %handler.track_namespace(),
+            ),
             SessionEvent::SubscribeNameSpace(session_id, handler) => tracing::info_span!(
                 parent: session_span,
                 "relay.session.event",
@@ -481,6 +713,13 @@ impl EventHandler {
                 track_namespace = %handler.track_namespace(),
                 track_name = %handler.track_name(),
             ),
+            SessionEvent::SubscribeUpdate(session_id, handler) => tracing::info_span!(
+                parent: session_span,
+                "relay.session.event",
+                session_id = %session_id,
+                event = "SubscribeUpdate",
+                subscription_request_id = handler.subscription_request_id(),
+            ),
             SessionEvent::Unsubscribe(session_id, handler) => tracing::info_span!(
                 parent: session_span,
                 "relay.session.event",
@@ -488,6 +727,14 @@ impl EventHandler {
                 event = "Unsubscribe",
                 subscribe_id = handler.subscribe_id(),
             ),
+            SessionEvent::PublishDone(session_id, handler) => tracing::info_span!(
+                parent: session_span,
+                "relay.session.event",
+                session_id = %session_id,
+                event = "PublishDone",
+                request_id = handler.request_id(),
+                status_code = handler.status_code(),
+            ),
             SessionEvent::Disconnected(session_id) => tracing::info_span!(
                 parent: session_span,
                 "relay.session.event",
@@ -501,6 +748,55 @@ impl EventHandler {
                 event = "Fetch",
                 request_id = handler.request_id(),
             ),
+            SessionEvent::FetchCancel(session_id, handler) => tracing::info_span!(
+                parent: session_span,
+                "relay.session.event",
+                session_id = %session_id,
+                event = "FetchCancel",
+                request_id = handler.request_id(),
+            ),
+            SessionEvent::TrackStatus(session_id, handler) => tracing::info_span!(
+                parent: session_span,
+                "relay.session.event",
+                session_id = %session_id,
+                event = "TrackStatus",
+                request_id = handler.request_id(),
+                track_namespace = %handler.track_namespace(),
+                track_name = %handler.track_name(),
+            ),
+            SessionEvent::GoAway(session_id, new_session_uri) => tracing::info_span!(
+                parent: session_span,
+                "relay.session.event",
+                session_id = %session_id,
+                event = "GoAway",
+                new_session_uri = %new_session_uri,
+            ),
+            SessionEvent::SubscriptionRangeComplete(session_id, subscribe_id, end_group) => {
+                tracing::info_span!(
+                    parent: session_span,
+                    "relay.session.event",
+                    session_id = %session_id,
+                    event = "SubscriptionRangeComplete",
+                    subscribe_id = subscribe_id,
+                    end_group = end_group,
+                )
+            }
+            SessionEvent::SubscriberTooFarBehind(session_id, subscribe_id) => {
+                tracing::info_span!(
+                    parent: session_span,
+                    "relay.session.event",
+                    session_id = %session_id,
+                    event = "SubscriberTooFarBehind",
+                    subscribe_id = subscribe_id,
+                )
+            }
+            SessionEvent::SubscriptionExpired(session_id, subscribe_id) => tracing::info_span!(
+                parent: session_span,
+                "relay.session.event",
+                session_id = %session_id,
+                event = "SubscriptionExpired",
+                subscribe_id = subscribe_id,
+            ),
             SessionEvent::ProtocolViolation(session_id) => tracing::info_span!(
                 parent: session_span,
                 "relay.session.event",
@@ -521,6 +817,7 @@ impl EventHandler {
         control_message_forwarder: &ControlMessageForwarder,
         ingress_sender: &mpsc::Sender<IngressCommand>,
         egress_sender: &mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
         route_registry: &dyn RelayRouteRegistry,
         inter_relay_connection_manager: &InterRelayConnectionManager,
     ) {
@@ -532,6 +829,7 @@ impl EventHandler {
             control_message_forwarder,
             ingress_sender,
             egress_sender,
+            cache_store,
             route_registry,
             inter_relay_connection_manager,
         )
@@ -551,10 +849,36 @@ impl EventHandler {
         control_message_forwarder: &ControlMessageForwarder,
         ingress_sender: &mpsc::Sender<IngressCommand>,
         egress_sender: &mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
         route_registry: &dyn RelayRouteRegistry,
         inter_relay_connection_manager: &InterRelayConnectionManager,
     ) {
-        for removed_downstream in removed.downstream_subscriptions {
+        // Other sessions' subscriptions fed by the removed session lost their
+        // upstream: end them with PUBLISH_DONE instead of leaving them waiting.
+        let (ended_by_upstream, own_subscriptions): (Vec<_>, Vec<_>) = removed
+            .downstream_subscriptions
+            .into_iter()
+            .partition(|downstream| {
+                downstream.downstream_session_id != removed_session_id
+                    && downstream.upstream_key.publisher_session_id == removed_session_id
+            });
+        PublishDone::end_downstream(
+            removed_session_id,
+            RemovedSessionSubscriptions {
+                downstream_subscriptions: ended_by_upstream,
+                upstream_track_keys: removed.upstream_track_keys,
+                ..Default::default()
+            },
+            PublishDoneStatusCode::GoingAway as u64,
+            "upstream publisher disconnected",
+            table,
+            ingress_sender,
+            egress_sender,
+            cache_store,
+        )
+        .await;
+
+        for removed_downstream in own_subscriptions {
             if egress_sender
                 .send(EgressCommand::StopReader {
                     subscriber_session_id: removed_downstream.downstream_session_id,
@@ -591,15 +915,12 @@ impl EventHandler {
 
                 Self::stop_ingress_track(
                     ingress_sender,
-                    removed_downstream.track_key,
+                    removed_downstream.track_key.clone(),
                     removed_downstream.upstream_key.publisher_session_id,
                 )
                 .await;
             }
-        }
-
-        for track_key in removed.upstream_track_keys {
-            Self::stop_ingress_track(ingress_sender, track_key, removed_session_id).await;
+            Unsubscribe::release_track_cache(table, cache_store, &removed_downstream, None).await;
         }
 
         // TODO(deadlock-core): iteration 4 — make upstream join/remove atomic across sessions
@@ -786,6 +1107,39 @@ mod tests {
         assert_eq!(processed, vec![Ev::Terminal]);
     }
 
+    /// A panicking worker yields no session_id, so the reader must find the
+    /// session to terminate through the worker's task id.
+    #[tokio::test]
+    async fn panicked_worker_is_traced_back_to_its_session() {
+        let mut workers: tokio::task::JoinSet<u64> = tokio::task::JoinSet::new();
+        let mut worker_sessions = std::collections::HashMap::new();
+        for session_id in [7u64, 8] {
+            let worker = workers.spawn(async move {
+                if session_id == 7 {
+                    panic!("handler bug");
+                }
+                session_id
+            });
+            worker_sessions.insert(worker.id(), session_id);
+        }
+
+        let mut panicked = Vec::new();
+        let mut exited = Vec::new();
+        while let Some(result) = workers.join_next_with_id().await {
+            match result {
+                Ok((_, session_id)) => exited.push(session_id),
+                Err(error) => panicked.push(worker_sessions[&error.id()]),
+            }
+        }
+
+        assert_eq!(
+            panicked,
+            vec![7],
+            "only the panicking session is terminated"
+        );
+        assert_eq!(exited, vec![8]);
+    }
+
     /// Single-session events must be processed in FIFO order.
     #[tokio::test]
     async fn single_session_events_are_fifo() {
@@ -13,6 +13,9 @@ impl RelaySessionEventResolver {
             MoqtSessionEvent::PublishNamespaceDone(handler) => {
                 SessionEvent::PublishNamespaceDone(session_id, handler)
             }
+            MoqtSessionEvent::PublishNamespaceCancel(handler) => {
+                SessionEvent::PublishNamespaceCancel(session_id, handler)
+            }
             MoqtSessionEvent::SubscribeNamespace(handler) => {
                 SessionEvent::SubscribeNameSpace(session_id, handler)
             }
@@ -21,10 +24,25 @@ impl RelaySessionEventResolver {
             }
             MoqtSessionEvent::Publish(handler) => SessionEvent::Publish(session_id, handler),
             MoqtSessionEvent::Subscribe(handler) => SessionEvent::Subscribe(session_id, handler),
+            MoqtSessionEvent::SubscribeUpdate(handler) => {
+                SessionEvent::SubscribeUpdate(session_id, handler)
+            }
             MoqtSessionEvent::Unsubscribe(handler) => {
                 SessionEvent::Unsubscribe(session_id, handler)
             }
+            MoqtSessionEvent::PublishDone(handler) => {
+                SessionEvent::PublishDone(session_id, handler)
+            }
             MoqtSessionEvent::Fetch(handler) => SessionEvent::Fetch(session_id, handler),
+            MoqtSessionEvent::FetchCancel(handler) => {
+                SessionEvent::FetchCancel(session_id, handler)
+            }
+            MoqtSessionEvent::TrackStatus(handler) => {
+                SessionEvent::TrackStatus(session_id, handler)
+            }
+            MoqtSessionEvent::GoAway(new_session_uri) => {
+                SessionEvent::GoAway(session_id, new_session_uri)
+            }
             MoqtSessionEvent::Disconnected() => SessionEvent::Disconnected(session_id),
             MoqtSessionEvent::ProtocolViolation() => SessionEvent::ProtocolViolation(session_id),
         }
@@ -0,0 +1,112 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::{
+    task::JoinHandle,
+    time::{Instant, MissedTickBehavior},
+};
+
+use crate::modules::{
+    error::RelayError,
+    relay::{
+        cache::{duration::duration_from_env, store::TrackCacheStore},
+        notifications::track_notifier::ObjectNotifyProducerMap,
+    },
+    session_repository::SessionRepository,
+};
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// When a session last sent a control message or had an object ingested
+/// from or delivered to it. Shared by its event forwarder and the ingress
+/// and egress tasks of its tracks.
+#[derive(Debug)]
+pub(crate) struct SessionActivity {
+    started_at: Instant,
+    /// Milliseconds after `started_at` of the last activity.
+    last_active_ms: AtomicU64,
+}
+
+impl SessionActivity {
+    pub(crate) fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            last_active_ms: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn touch(&self) {
+        let now_ms = self.started_at.elapsed().as_millis() as u64;
+        self.last_active_ms.fetch_max(now_ms, Ordering::Relaxed);
+    }
+
+    pub(crate) fn idle_for(&self) -> Duration {
+        let last_active = Duration::from_millis(self.last_active_ms.load(Ordering::Relaxed));
+        self.started_at.elapsed().saturating_sub(last_active)
+    }
+}
+
+/// Every `SWEEP_INTERVAL`, closes client sessions idle for
+/// `RELAY_IDLE_SESSION_TIMEOUT_SECS` (default 0, never) and forgets the
+/// track notifiers of tracks nobody publishes or subscribes to anymore.
+/// Relay peers are never closed for idleness: a quiet cascade link is
+/// still wanted for the next subscription.
+pub(crate) fn spawn_idle_reaper_job(
+    session_repo: Arc<tokio::sync::Mutex<SessionRepository>>,
+    cache_store: Arc<TrackCacheStore>,
+    object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
+) -> JoinHandle<()> {
+    let session_timeout = duration_from_env("RELAY_IDLE_SESSION_TIMEOUT_SECS", 0);
+    if !session_timeout.is_zero() {
+        tracing::info!(?session_timeout, "closing idle client sessions");
+    }
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if !session_timeout.is_zero() {
+                let idle = session_repo
+                    .lock()
+                    .await
+                    .idle_client_sessions(session_timeout);
+                for (session_id, session, idle_for) in idle {
+                    tracing::info!(%session_id, ?idle_for, "closing idle session");
+                    // The disconnect that follows removes its state as usual.
+                    session.terminate(&RelayError::Idle(idle_for));
+                }
+            }
+            let pruned =
+                object_notify_producer_map.prune(|track_key| cache_store.get(track_key).is_some());
+            if pruned > 0 {
+                tracing::debug!(pruned, "forgot notifiers of idle tracks");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn touch_resets_the_idle_time() {
+        // Arrange
+        let activity = SessionActivity::new();
+        tokio::time::advance(Duration::from_secs(30)).await;
+        let idle_before = activity.idle_for();
+
+        // Act
+        activity.touch();
+        tokio::time::advance(Duration::from_secs(2)).await;
+
+        // Assert
+        assert_eq!(idle_before, Duration::from_secs(30));
+        assert_eq!(activity.idle_for(), Duration::from_secs(2));
+    }
+}
@@ -3,6 +3,7 @@ use std::{net::SocketAddr, sync::Arc};
 use dashmap::DashMap;
 
 use crate::modules::{
+    qlog,
     route_registry::RelayInfo,
     session_event::SessionEvent,
     session_repository::SessionRepository,
@@ -19,20 +20,29 @@ pub(crate) struct InterRelayConnectionManager {
     repo: Arc<tokio::sync::Mutex<SessionRepository>>,
     session_event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent>,
     sessions: DashMap<String, SessionId>,
+    /// Relay this one hangs off in a distribution tree; asked for whatever
+    /// has no publisher here.
+    parent_relay: Option<RelayInfo>,
 }
 
 impl InterRelayConnectionManager {
     pub(crate) fn new(
         repo: Arc<tokio::sync::Mutex<SessionRepository>>,
         session_event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent>,
+        parent_relay: Option<RelayInfo>,
     ) -> Self {
         Self {
             repo,
             session_event_sender,
             sessions: DashMap::new(),
+            parent_relay,
         }
     }
 
+    pub(crate) fn parent_relay(&self) -> Option<&RelayInfo> {
+        self.parent_relay.as_ref()
+    }
+
     pub(crate) async fn get_or_connect(&self, relay: &RelayInfo) -> anyhow::Result<SessionId> {
         if let Some(session_id) = self.sessions.get(&relay.relay_id)
             && self.repo.lock().await.has_session(*session_id)
@@ -48,6 +58,7 @@ impl InterRelayConnectionManager {
         })?;
         let connecting = endpoint.connect(remote_address, &relay.host).await?;
         let session = connecting.await?;
+        qlog::observe_session(session_id, &session);
         let relay_hostname = relay_hostname();
         let session_span = tracing::info_span!(
             "relay.inter_relay.session",
@@ -58,6 +69,10 @@ impl InterRelayConnectionManager {
             relay_hostname = %relay_hostname,
             relay_host = %relay.host,
             relay_port = relay.port,
+            rtt_ms = tracing::field::Empty,
+            congestion_window = tracing::field::Empty,
+            lost_packets = tracing::field::Empty,
+            dropped_datagrams = tracing::field::Empty,
         );
 
         self.repo
@@ -0,0 +1,284 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    path::PathBuf,
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use moqt::{ControlMessageDirection, wire::ControlMessageType};
+use serde_json::{Value, json};
+use tokio::{io::AsyncWriteExt, sync::mpsc, task::JoinHandle};
+
+use crate::modules::types::SessionId;
+
+/// JSON-SEQ record separator; every qlog record starts with it.
+const RECORD_SEPARATOR: u8 = 0x1e;
+
+/// Process-wide like the tracing subscriber: events are recorded from
+/// session intake, the event handler and egress tasks alike. Unset unless
+/// the relay was started with a qlog directory.
+static WRITER: OnceLock<QlogWriter> = OnceLock::new();
+
+struct QlogEvent {
+    session_id: SessionId,
+    unix_ms: f64,
+    name: &'static str,
+    data: Value,
+}
+
+enum QlogCommand {
+    Event(QlogEvent),
+    CloseSession(SessionId),
+}
+
+/// Writes MoQ events as qlog 0.3 JSON-SEQ, one `session-<id>.sqlog` file
+/// per session, from a single background task so recording never blocks.
+#[derive(Clone)]
+pub(crate) struct QlogWriter {
+    sender: mpsc::UnboundedSender<QlogCommand>,
+}
+
+impl QlogWriter {
+    pub(crate) fn spawn(dir: PathBuf) -> (Self, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let join_handle = tokio::spawn(write_loop(dir, receiver));
+        (Self { sender }, join_handle)
+    }
+
+    pub(crate) fn record(&self, session_id: SessionId, name: &'static str, data: Value) {
+        let _ = self.sender.send(QlogCommand::Event(QlogEvent {
+            session_id,
+            unix_ms: unix_ms(SystemTime::now()),
+            name,
+            data,
+        }));
+    }
+
+    pub(crate) fn close_session(&self, session_id: SessionId) {
+        let _ = self.sender.send(QlogCommand::CloseSession(session_id));
+    }
+}
+
+/// Starts the process-wide writer. Later calls keep the first one.
+pub(crate) fn spawn_qlog_writer(dir: PathBuf) -> Option<JoinHandle<()>> {
+    if WRITER.get().is_some() {
+        return None;
+    }
+    tracing::info!(dir = %dir.display(), "Writing qlog files");
+    let (writer, join_handle) = QlogWriter::spawn(dir);
+    WRITER.set(writer).ok()?;
+    Some(join_handle)
+}
+
+/// Records an event for `session_id`. `data` is only built when qlog is on.
+pub(crate) fn record(session_id: SessionId, name: &'static str, data: impl FnOnce() -> Value) {
+    if let Some(writer) = WRITER.get() {
+        writer.record(session_id, name, data());
+    }
+}
+
+/// Flushes and closes the session's file once the session is gone.
+pub(crate) fn close_session(session_id: SessionId) {
+    if let Some(writer) = WRITER.get() {
+        writer.close_session(session_id);
+    }
+}
+
+/// Records the control messages `session` sends and receives from now on.
+pub(crate) fn observe_session<T: moqt::TransportProtocol>(
+    session_id: SessionId,
+    session: &moqt::Session<T>,
+) {
+    let Some(writer) = WRITER.get().cloned() else {
+        return;
+    };
+    session.set_control_message_observer(std::sync::Arc::new(move |direction, message_type| {
+        writer.record(
+            session_id,
+            control_message_event_name(direction),
+            control_message_data(message_type),
+        );
+    }));
+}
+
+fn control_message_event_name(direction: ControlMessageDirection) -> &'static str {
+    match direction {
+        ControlMessageDirection::Sent => "moqt:control_message_created",
+        ControlMessageDirection::Received => "moqt:control_message_parsed",
+    }
+}
+
+fn control_message_data(message_type: ControlMessageType) -> Value {
+    json!({
+        "message_type": format!("{message_type:?}"),
+        "message_type_value": u8::from(message_type),
+    })
+}
+
+async fn write_loop(dir: PathBuf, mut receiver: mpsc::UnboundedReceiver<QlogCommand>) {
+    if let Err(error) = tokio::fs::create_dir_all(&dir).await {
+        tracing::error!(%error, dir = %dir.display(), "Failed to create qlog directory");
+        return;
+    }
+    let mut files: HashMap<SessionId, SessionFile> = HashMap::new();
+    while let Some(command) = receiver.recv().await {
+        match command {
+            QlogCommand::Event(event) => {
+                let session_id = event.session_id;
+                let file = match files.entry(session_id) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        match SessionFile::create(&dir, session_id, event.unix_ms).await {
+                            Ok(file) => entry.insert(file),
+                            Err(error) => {
+                                tracing::warn!(%error, session_id, "Failed to create qlog file");
+                                continue;
+                            }
+                        }
+                    }
+                };
+                // Flush whenever the queue runs dry so a crash loses little.
+                let written = match file.write_event(&event).await {
+                    Ok(()) if receiver.is_empty() => file.file.flush().await.map_err(Into::into),
+                    result => result,
+                };
+                if let Err(error) = written {
+                    tracing::warn!(%error, session_id, "Failed to write qlog event");
+                    files.remove(&session_id);
+                }
+            }
+            QlogCommand::CloseSession(session_id) => {
+                if let Some(mut file) = files.remove(&session_id) {
+                    let _ = file.file.flush().await;
+                }
+            }
+        }
+    }
+    for file in files.values_mut() {
+        let _ = file.file.flush().await;
+    }
+}
+
+struct SessionFile {
+    file: tokio::io::BufWriter<tokio::fs::File>,
+    reference_unix_ms: f64,
+}
+
+impl SessionFile {
+    async fn create(
+        dir: &std::path::Path,
+        session_id: SessionId,
+        reference_unix_ms: f64,
+    ) -> anyhow::Result<Self> {
+        let path = dir.join(format!("session-{session_id}.sqlog"));
+        let file = tokio::fs::File::create(&path).await?;
+        let mut session_file = Self {
+            file: tokio::io::BufWriter::new(file),
+            reference_unix_ms,
+        };
+        let header = json!({
+            "qlog_version": "0.3",
+            "qlog_format": "JSON-SEQ",
+            "title": format!("moqt relay session {session_id}"),
+            "trace": {
+                "vantage_point": { "type": "server" },
+                "common_fields": {
+                    "group_id": session_id.to_string(),
+                    "time_format": "relative",
+                    "reference_time": reference_unix_ms,
+                },
+            },
+        });
+        session_file.write_record(&header).await?;
+        Ok(session_file)
+    }
+
+    async fn write_event(&mut self, event: &QlogEvent) -> anyhow::Result<()> {
+        let record = json!({
+            "time": event.unix_ms - self.reference_unix_ms,
+            "name": event.name,
+            "data": event.data,
+        });
+        self.write_record(&record).await
+    }
+
+    async fn write_record(&mut self, record: &Value) -> anyhow::Result<()> {
+        let mut bytes = vec![RECORD_SEPARATOR];
+        serde_json::to_writer(&mut bytes, record)?;
+        bytes.push(b'\n');
+        self.file.write_all(&bytes).await?;
+        Ok(())
+    }
+}
+
+fn unix_ms(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0.0, |duration| duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_records(contents: &str) -> Vec<Value> {
+        contents
+            .split(RECORD_SEPARATOR as char)
+            .filter(|record| !record.is_empty())
+            .map(|record| serde_json::from_str(record.trim_end()).expect("json record"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn events_are_written_per_session_after_a_header() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!(
+            "relay-qlog-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        let (writer, join_handle) = QlogWriter::spawn(dir.clone());
+
+        // Act
+        writer.record(
+            7,
+            control_message_event_name(ControlMessageDirection::Received),
+            control_message_data(ControlMessageType::Subscribe),
+        );
+        writer.record(8, "relay:fetch_cache_lookup", json!({ "hit": true }));
+        writer.record(
+            7,
+            control_message_event_name(ControlMessageDirection::Sent),
+            control_message_data(ControlMessageType::SubscribeOk),
+        );
+        writer.close_session(7);
+        drop(writer);
+        join_handle.await.expect("writer task");
+
+        // Assert
+        let session_7 = tokio::fs::read_to_string(dir.join("session-7.sqlog"))
+            .await
+            .expect("read session 7");
+        let session_8 = tokio::fs::read_to_string(dir.join("session-8.sqlog"))
+            .await
+            .expect("read session 8");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        let records = read_records(&session_7);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["qlog_format"], "JSON-SEQ");
+        assert_eq!(records[1]["name"], "moqt:control_message_parsed");
+        assert_eq!(records[1]["data"]["message_type"], "Subscribe");
+        assert_eq!(records[2]["name"], "moqt:control_message_created");
+        assert_eq!(records[2]["data"]["message_type_value"], 0x04);
+        assert!(records[2]["time"].as_f64().unwrap() >= 0.0);
+        assert_eq!(read_records(&session_8).len(), 2);
+    }
+
+    #[test]
+    fn recording_without_a_writer_does_not_build_the_event() {
+        // Act / Assert
+        record(1, "relay:fetch_cache_lookup", || {
+            unreachable!("event data is built only when qlog is enabled")
+        });
+    }
+}
@@ -0,0 +1,6 @@
+pub(crate) mod container;
+pub(crate) mod recorder;
+pub(crate) mod replayer;
+
+pub(crate) use recorder::spawn_recorder;
+pub(crate) use replayer::serve_recording;
@@ -0,0 +1,309 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::{Context, bail};
+use bytes::{Buf, BufMut, BytesMut};
+use tokio::{io::AsyncWriteExt, time::Instant};
+
+use crate::modules::{relay::cache::disk_tier::encode_stored_object, types::TrackKey};
+
+const MAGIC: &[u8; 8] = b"MOQTREC1";
+
+/// Appends a track's groups to a recording file.
+///
+/// Layout: `MAGIC`, the track namespace and name (u32 length + UTF-8 each),
+/// then one record per group: its offset from the first group in ms (u64),
+/// its object count (u32) and every object as a FETCH object field (u32
+/// length + bytes), which keeps subgroup ids, priorities and extension
+/// headers. Each group is flushed once written, so a crash loses at most the
+/// group being written.
+pub(crate) struct RecordingWriter {
+    file: tokio::io::BufWriter<tokio::fs::File>,
+    first_group_at: Option<Instant>,
+}
+
+impl RecordingWriter {
+    pub(crate) async fn create(path: &Path, track_key: &TrackKey) -> anyhow::Result<Self> {
+        let file = tokio::fs::File::create(path)
+            .await
+            .with_context(|| format!("failed to create recording {}", path.display()))?;
+        let mut header = BytesMut::new();
+        header.put_slice(MAGIC);
+        put_string(&mut header, &track_key.track_namespace);
+        put_string(&mut header, &track_key.track_name);
+        let mut writer = Self {
+            file: tokio::io::BufWriter::new(file),
+            first_group_at: None,
+        };
+        writer.file.write_all(&header).await?;
+        writer.file.flush().await?;
+        Ok(writer)
+    }
+
+    /// Writes a group whose first object arrived at `arrived_at`; replay
+    /// paces groups by these arrival times.
+    pub(crate) async fn append_group(
+        &mut self,
+        arrived_at: Instant,
+        objects: &[moqt::FetchObjectField],
+    ) -> anyhow::Result<()> {
+        let first_group_at = *self.first_group_at.get_or_insert(arrived_at);
+        let offset = arrived_at.saturating_duration_since(first_group_at);
+        let mut record = BytesMut::new();
+        record.put_u64(u64::try_from(offset.as_millis()).unwrap_or(u64::MAX));
+        record.put_u32(u32::try_from(objects.len())?);
+        for object in objects {
+            let encoded = encode_stored_object(object);
+            record.put_u32(u32::try_from(encoded.len())?);
+            record.put_slice(&encoded);
+        }
+        self.file.write_all(&record).await?;
+        self.file.flush().await?;
+        Ok(())
+    }
+}
+
+fn put_string(buf: &mut BytesMut, value: &str) {
+    buf.put_u32(value.len() as u32);
+    buf.put_slice(value.as_bytes());
+}
+
+fn get_string(buf: &mut BytesMut) -> anyhow::Result<String> {
+    let bytes = get_chunk(buf)?;
+    Ok(String::from_utf8(bytes.to_vec())?)
+}
+
+fn get_chunk(buf: &mut BytesMut) -> anyhow::Result<BytesMut> {
+    if buf.remaining() < 4 {
+        bail!("truncated length");
+    }
+    let len = buf.get_u32() as usize;
+    if buf.remaining() < len {
+        bail!("truncated chunk");
+    }
+    Ok(buf.split_to(len))
+}
+
+#[derive(Debug)]
+pub(crate) struct RecordedGroup {
+    /// Time since the first group arrived.
+    pub(crate) offset: Duration,
+    /// FETCH object fields in object id order.
+    pub(crate) objects: Vec<moqt::FetchObjectField>,
+}
+
+impl RecordedGroup {
+    fn group_id(&self) -> Option<u64> {
+        self.objects.first().map(|object| object.group_id)
+    }
+}
+
+/// A recording read back into memory for replay.
+#[derive(Debug)]
+pub(crate) struct Recording {
+    pub(crate) track_key: TrackKey,
+    /// Groups in recording order, which is ascending group id.
+    pub(crate) groups: Vec<RecordedGroup>,
+}
+
+impl Recording {
+    pub(crate) async fn read(path: &Path) -> anyhow::Result<Self> {
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read recording {}", path.display()))?;
+        Self::decode(BytesMut::from(&bytes[..]))
+            .with_context(|| format!("invalid recording {}", path.display()))
+    }
+
+    /// A group cut short by a crash ends the recording; the groups before
+    /// it are kept.
+    pub(crate) fn decode(mut buf: BytesMut) -> anyhow::Result<Self> {
+        if buf.remaining() < MAGIC.len() || &buf.split_to(MAGIC.len())[..] != MAGIC {
+            bail!("not a MoQT recording");
+        }
+        let track_namespace = get_string(&mut buf)?;
+        let track_name = get_string(&mut buf)?;
+        let mut groups = Vec::new();
+        while buf.has_remaining() {
+            match Self::decode_group(&mut buf) {
+                Ok(group) => groups.push(group),
+                Err(error) => {
+                    tracing::warn!(%error, groups = groups.len(), "recording ends mid-group");
+                    break;
+                }
+            }
+        }
+        Ok(Self {
+            track_key: TrackKey::new(track_namespace, track_name),
+            groups,
+        })
+    }
+
+    fn decode_group(buf: &mut BytesMut) -> anyhow::Result<RecordedGroup> {
+        if buf.remaining() < 12 {
+            bail!("truncated group record");
+        }
+        let offset = Duration::from_millis(buf.get_u64());
+        let count = buf.get_u32();
+        let mut objects = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let mut chunk = get_chunk(buf)?;
+            let object = moqt::FetchObjectField::decode(&mut chunk)
+                .map_err(|error| anyhow::anyhow!("invalid object: {error:?}"))?;
+            objects.push(object);
+        }
+        Ok(RecordedGroup { offset, objects })
+    }
+
+    /// Location of the last recorded object.
+    pub(crate) fn largest_location(&self) -> Option<moqt::Location> {
+        self.groups
+            .iter()
+            .rev()
+            .find_map(|group| group.objects.last())
+            .map(|object| moqt::Location {
+                group_id: object.group_id,
+                object_id: object.object_id,
+            })
+    }
+
+    /// Objects in [start, end) in the requested group order; an `end` with
+    /// object id 0 takes the whole end group, as in FETCH.
+    pub(crate) fn objects_in(
+        &self,
+        start: moqt::Location,
+        end: moqt::Location,
+        group_order: moqt::GroupOrder,
+    ) -> Vec<moqt::FetchObjectField> {
+        let in_range = |object: &&moqt::FetchObjectField| {
+            let location = (object.group_id, object.object_id);
+            location >= (start.group_id, start.object_id)
+                && (object.group_id < end.group_id
+                    || (object.group_id == end.group_id
+                        && (end.object_id == 0 || object.object_id < end.object_id)))
+        };
+        let mut groups: Vec<&RecordedGroup> = self
+            .groups
+            .iter()
+            .filter(|group| {
+                group
+                    .group_id()
+                    .is_some_and(|group_id| start.group_id <= group_id && group_id <= end.group_id)
+            })
+            .collect();
+        if matches!(group_order, moqt::GroupOrder::Descending) {
+            groups.reverse();
+        }
+        groups
+            .into_iter()
+            .flat_map(|group| group.objects.iter().filter(in_range).cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    fn object(group_id: u64, object_id: u64, payload: &'static [u8]) -> moqt::FetchObjectField {
+        moqt::FetchObjectField::new(
+            group_id,
+            0,
+            object_id,
+            128,
+            moqt::ExtensionHeaders::default(),
+            moqt::FetchObject::Payload(Bytes::from_static(payload)),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn written_groups_are_read_back_with_their_offsets() {
+        // Arrange
+        let path = std::env::temp_dir().join(format!("relay-recording-{}", std::process::id()));
+        let track_key = TrackKey::new("live/room", "video");
+        let mut writer = RecordingWriter::create(&path, &track_key).await.unwrap();
+        let first = Instant::now();
+
+        // Act
+        writer
+            .append_group(first, &[object(0, 0, b"key"), object(0, 1, b"delta")])
+            .await
+            .unwrap();
+        writer
+            .append_group(first + Duration::from_millis(500), &[object(1, 0, b"key")])
+            .await
+            .unwrap();
+        drop(writer);
+        let recording = Recording::read(&path).await.unwrap();
+
+        // Assert
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(recording.track_key, track_key);
+        assert_eq!(recording.groups.len(), 2);
+        assert_eq!(recording.groups[1].offset, Duration::from_millis(500));
+        assert_eq!(recording.groups[0].objects[1], object(0, 1, b"delta"));
+        assert_eq!(
+            recording.largest_location(),
+            Some(moqt::Location {
+                group_id: 1,
+                object_id: 0
+            })
+        );
+    }
+
+    #[test]
+    fn truncated_group_ends_the_recording() {
+        // Arrange
+        let mut buf = BytesMut::new();
+        buf.put_slice(MAGIC);
+        put_string(&mut buf, "live");
+        put_string(&mut buf, "video");
+        buf.put_u64(0);
+        buf.put_u32(1);
+        let encoded = object(0, 0, b"key").encode();
+        buf.put_u32(encoded.len() as u32);
+        buf.put_slice(&encoded);
+        buf.put_u64(40);
+        buf.put_u32(2);
+
+        // Act
+        let recording = Recording::decode(buf).unwrap();
+
+        // Assert
+        assert_eq!(recording.groups.len(), 1);
+    }
+
+    #[test]
+    fn objects_in_range_honour_the_end_location() {
+        // Arrange
+        let recording = Recording {
+            track_key: TrackKey::new("live", "video"),
+            groups: (0..3)
+                .map(|group_id| RecordedGroup {
+                    offset: Duration::ZERO,
+                    objects: vec![object(group_id, 0, b"a"), object(group_id, 1, b"b")],
+                })
+                .collect(),
+        };
+
+        // Act
+        let objects = recording.objects_in(
+            moqt::Location {
+                group_id: 0,
+                object_id: 1,
+            },
+            moqt::Location {
+                group_id: 2,
+                object_id: 1,
+            },
+            moqt::GroupOrder::Descending,
+        );
+
+        // Assert
+        let locations: Vec<(u64, u64)> = objects
+            .iter()
+            .map(|object| (object.group_id, object.object_id))
+            .collect();
+        assert_eq!(locations, vec![(2, 0), (1, 0), (1, 1), (0, 1)]);
+    }
+}
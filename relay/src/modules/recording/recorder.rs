@@ -0,0 +1,269 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::modules::{
+    recording::container::RecordingWriter,
+    relay::cache::{store::TrackCacheStore, track_cache::TrackCache},
+    types::TrackKey,
+};
+
+const RECORD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Extension of recording files.
+pub(crate) const RECORDING_EXTENSION: &str = "moqrec";
+
+/// One track being recorded. A new upstream replaces the track's cache, and
+/// its recording starts a new file since group ids may start over.
+struct TrackRecording {
+    cache: Arc<TrackCache>,
+    writer: Option<RecordingWriter>,
+    last_group_id: Option<u64>,
+}
+
+/// Records tracks in `namespaces` (prefixes) as they flow through the relay:
+/// once a second, the groups cached since the last pass that are complete
+/// are appended to the track's recording in `dir`. A track is only recorded
+/// while something subscribes to it through the relay.
+pub(crate) struct Recorder {
+    cache_store: Arc<TrackCacheStore>,
+    dir: PathBuf,
+    namespaces: Vec<String>,
+    tracks: HashMap<TrackKey, TrackRecording>,
+}
+
+impl Recorder {
+    pub(crate) fn new(
+        cache_store: Arc<TrackCacheStore>,
+        dir: PathBuf,
+        namespaces: Vec<String>,
+    ) -> Self {
+        Self {
+            cache_store,
+            dir,
+            namespaces,
+            tracks: HashMap::new(),
+        }
+    }
+
+    fn is_recorded(&self, track_key: &TrackKey) -> bool {
+        self.namespaces
+            .iter()
+            .any(|prefix| track_key.track_namespace.starts_with(prefix.as_str()))
+    }
+
+    pub(crate) async fn record_once(&mut self) {
+        let tracks: Vec<(TrackKey, Arc<TrackCache>)> = self
+            .cache_store
+            .snapshot()
+            .into_iter()
+            .filter(|(track_key, _)| self.is_recorded(track_key))
+            .collect();
+        self.tracks
+            .retain(|track_key, _| tracks.iter().any(|(key, _)| key == track_key));
+        for (track_key, cache) in tracks {
+            let recording =
+                self.tracks
+                    .entry(track_key.clone())
+                    .or_insert_with(|| TrackRecording {
+                        cache: cache.clone(),
+                        writer: None,
+                        last_group_id: None,
+                    });
+            if !Arc::ptr_eq(&recording.cache, &cache) {
+                *recording = TrackRecording {
+                    cache: cache.clone(),
+                    writer: None,
+                    last_group_id: None,
+                };
+            }
+            if let Err(error) = record_track(&self.dir, &track_key, recording).await {
+                tracing::warn!(%track_key, %error, "Failed to record track");
+            }
+        }
+    }
+}
+
+async fn record_track(
+    dir: &Path,
+    track_key: &TrackKey,
+    recording: &mut TrackRecording,
+) -> anyhow::Result<()> {
+    let cache = recording.cache.clone();
+    for group_id in cache.stream_group_ids_after(recording.last_group_id).await {
+        // Groups are written in order, so a group still being received
+        // holds back the ones after it.
+        if cache.is_stream_group_open(group_id).await {
+            break;
+        }
+        recording.last_group_id = Some(group_id);
+        let Some(objects) = cache.complete_group_objects(group_id).await else {
+            tracing::debug!(%track_key, group_id, "Skipping incomplete group");
+            continue;
+        };
+        let arrived_at = cache
+            .stream_group_inserted_at(group_id)
+            .await
+            .unwrap_or_else(tokio::time::Instant::now);
+        let writer = match &mut recording.writer {
+            Some(writer) => writer,
+            None => {
+                let path = dir.join(recording_file_name(track_key));
+                let writer = RecordingWriter::create(&path, track_key).await?;
+                tracing::info!(%track_key, path = %path.display(), "Recording track");
+                recording.writer.insert(writer)
+            }
+        };
+        writer.append_group(arrived_at, &objects).await?;
+    }
+    Ok(())
+}
+
+/// `<namespace>_<track>-<unix ms>.moqrec`, with anything but ASCII
+/// alphanumerics, `-` and `.` replaced so any namespace is a safe file name.
+fn recording_file_name(track_key: &TrackKey) -> String {
+    let name: String = format!("{}_{}", track_key.track_namespace, track_key.track_name)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let started_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    format!("{name}-{started_ms}.{RECORDING_EXTENSION}")
+}
+
+pub(crate) fn spawn_recorder(
+    cache_store: Arc<TrackCacheStore>,
+    dir: PathBuf,
+    namespaces: Vec<String>,
+) -> anyhow::Result<JoinHandle<()>> {
+    std::fs::create_dir_all(&dir)?;
+    tracing::info!(dir = %dir.display(), ?namespaces, "Recording namespaces");
+    let mut recorder = Recorder::new(cache_store, dir, namespaces);
+    Ok(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(RECORD_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            recorder.record_once().await;
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use moqt::{ExtensionHeaders, SubgroupHeader, SubgroupId, SubgroupObject, SubgroupObjectField};
+
+    use super::*;
+    use crate::modules::{
+        core::data_object::DataObject, recording::container::Recording,
+        relay::types::StreamSubgroupId,
+    };
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("relay-{name}-{}", std::process::id()))
+    }
+
+    async fn ingest_group(cache: &TrackCache, group_id: u64, objects: u64, close: bool) {
+        let subgroup = StreamSubgroupId::Value(0);
+        let header = SubgroupHeader::new(0, group_id, SubgroupId::Value(0), 0, false, false);
+        let message_type = header.message_type;
+        cache
+            .append_live_stream_object(
+                group_id,
+                &subgroup,
+                None,
+                DataObject::SubgroupHeader(header),
+            )
+            .await;
+        for object_id in 0..objects {
+            let object = DataObject::SubgroupObject(SubgroupObjectField {
+                message_type,
+                object_id_delta: 0,
+                extension_headers: ExtensionHeaders::default(),
+                subgroup_object: SubgroupObject::new_payload(format!("o{object_id}").into()),
+            });
+            cache
+                .append_live_stream_object(group_id, &subgroup, Some(object_id), object)
+                .await;
+        }
+        if close {
+            cache.close_stream_subgroup(group_id, &subgroup).await;
+        }
+    }
+
+    fn recordings(dir: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|entries| entries.map(|entry| entry.unwrap().path()).collect())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn closed_groups_of_selected_namespaces_are_recorded() {
+        // Arrange
+        let dir = test_dir("recorder-closed-groups");
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache_store = Arc::new(TrackCacheStore::new());
+        let recorded = cache_store.get_or_create(&TrackKey::new("live/room", "video"));
+        let ignored = cache_store.get_or_create(&TrackKey::new("chat/room", "text"));
+        ingest_group(&recorded, 0, 2, true).await;
+        ingest_group(&recorded, 1, 1, false).await;
+        ingest_group(&ignored, 0, 1, true).await;
+        let mut recorder = Recorder::new(cache_store, dir.clone(), vec!["live/".to_string()]);
+
+        // Act
+        recorder.record_once().await;
+        let files = recordings(&dir);
+        let recording = Recording::read(&files[0]).await.unwrap();
+
+        // Assert
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(files.len(), 1);
+        assert_eq!(recording.track_key, TrackKey::new("live/room", "video"));
+        assert_eq!(recording.groups.len(), 1);
+        assert_eq!(recording.groups[0].objects.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn group_is_recorded_once_it_closes() {
+        // Arrange
+        let dir = test_dir("recorder-late-close");
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache_store = Arc::new(TrackCacheStore::new());
+        let cache = cache_store.get_or_create(&TrackKey::new("live/room", "video"));
+        ingest_group(&cache, 0, 1, true).await;
+        ingest_group(&cache, 1, 1, false).await;
+        let mut recorder = Recorder::new(cache_store, dir.clone(), vec!["live/".to_string()]);
+        recorder.record_once().await;
+
+        // Act
+        cache
+            .close_stream_subgroup(1, &StreamSubgroupId::Value(0))
+            .await;
+        recorder.record_once().await;
+        recorder.record_once().await;
+        let files = recordings(&dir);
+        let recording = Recording::read(&files[0]).await.unwrap();
+
+        // Assert
+        let _ = std::fs::remove_dir_all(&dir);
+        let group_ids: Vec<u64> = recording
+            .groups
+            .iter()
+            .map(|group| group.objects[0].group_id)
+            .collect();
+        assert_eq!(group_ids, vec![0, 1]);
+    }
+}
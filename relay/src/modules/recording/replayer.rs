@@ -0,0 +1,273 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
+
+use moqt::{
+    ClientConfig, ContentExists, Endpoint, FetchHandler, FetchObject, FetchObjectField, Publisher,
+    QUIC, SubgroupId, SubgroupObject, SubscribeHandler, Subscription, wire::FetchParams,
+};
+use tokio::{task::JoinHandle, time::Instant};
+use tokio_util::sync::CancellationToken;
+
+use crate::modules::{
+    enums::{FetchErrorCode, PublishDoneStatusCode, SubscribeErrorCode},
+    recording::container::{RecordedGroup, Recording},
+};
+
+/// The relay ends its downstream subscriptions as soon as PUBLISH_DONE
+/// arrives, without waiting for the streams it counts, so the last group's
+/// streams get this long to land first.
+const PUBLISH_DONE_DELAY: Duration = Duration::from_secs(1);
+
+/// Publishes a recording through the relay at `relay_addr` as if its
+/// publisher were live again: the namespace is announced from a loopback
+/// session, each SUBSCRIBE plays the recording back paced by its original
+/// group timing, and standalone FETCHes are served from it at once. Runs
+/// until `shutdown` or the session ends.
+pub(crate) async fn serve_recording(
+    recording: Arc<Recording>,
+    relay_addr: SocketAddr,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    let endpoint = Endpoint::<QUIC>::create_client(&ClientConfig {
+        port: 0,
+        verify_certificate: false,
+    })?;
+    let session = endpoint.connect(relay_addr, "localhost").await?.await?;
+    let track_key = &recording.track_key;
+    session
+        .publisher()
+        .publish_namespace(track_key.track_namespace.clone())
+        .await?;
+    tracing::info!(%track_key, groups = recording.groups.len(), "Replaying recording");
+
+    let mut playbacks: HashMap<u64, JoinHandle<()>> = HashMap::new();
+    let result = loop {
+        let event = tokio::select! {
+            _ = shutdown.cancelled() => break Ok(()),
+            event = session.receive_event() => event,
+        };
+        match event {
+            Ok(moqt::SessionEvent::Subscribe(handler)) => {
+                playbacks.retain(|_, playback| !playback.is_finished());
+                if let Some(playback) =
+                    start_playback(&recording, session.publisher(), handler).await
+                {
+                    playbacks.insert(playback.0, playback.1);
+                }
+            }
+            Ok(moqt::SessionEvent::Unsubscribe(handler)) => {
+                if let Some(playback) = playbacks.remove(&handler.subscribe_id()) {
+                    playback.abort();
+                }
+            }
+            Ok(moqt::SessionEvent::Fetch(handler)) => {
+                tokio::spawn(serve_fetch(recording.clone(), session.publisher(), handler));
+            }
+            Ok(moqt::SessionEvent::Disconnected()) => {
+                break Err(anyhow::anyhow!("relay closed the replay session"));
+            }
+            Ok(_) => {}
+            Err(error) => break Err(error),
+        }
+    };
+    for playback in playbacks.into_values() {
+        playback.abort();
+    }
+    result
+}
+
+/// Answers a SUBSCRIBE and spawns its playback; `None` if it was refused.
+async fn start_playback(
+    recording: &Arc<Recording>,
+    publisher: Publisher<QUIC>,
+    handler: SubscribeHandler<QUIC>,
+) -> Option<(u64, JoinHandle<()>)> {
+    let track_key = &recording.track_key;
+    if handler.track_namespace != track_key.track_namespace
+        || handler.track_name != track_key.track_name
+    {
+        let _ = handler
+            .error(
+                SubscribeErrorCode::TrackDoesNotExist as u64,
+                "track is not in the recording".to_string(),
+            )
+            .await;
+        return None;
+    }
+    // Playback always starts from the first group, so there is no content
+    // to report yet.
+    let track_alias = match handler.ok(0, ContentExists::False).await {
+        Ok(track_alias) => track_alias,
+        Err(error) => {
+            tracing::warn!(%track_key, ?error, "Failed to accept replay subscription");
+            return None;
+        }
+    };
+    let request_id = handler.request_id();
+    let subscription = handler.into_subscription(track_alias);
+    let recording = recording.clone();
+    let playback = tokio::spawn(async move {
+        if let Err(error) = play(&recording, &publisher, &subscription, request_id).await {
+            tracing::warn!(track_key = %recording.track_key, %error, "Replay playback failed");
+        }
+        publisher.release_track_alias(track_alias);
+    });
+    Some((request_id, playback))
+}
+
+async fn play(
+    recording: &Recording,
+    publisher: &Publisher<QUIC>,
+    subscription: &Subscription,
+    request_id: u64,
+) -> anyhow::Result<()> {
+    let factory = publisher.create_stream(subscription);
+    let started = Instant::now();
+    let mut stream_count = 0;
+    for group in &recording.groups {
+        tokio::time::sleep_until(started + group.offset).await;
+        for objects in subgroups(group).into_values() {
+            send_subgroup(&factory, &objects).await?;
+            stream_count += 1;
+        }
+    }
+    tracing::debug!(track_key = %recording.track_key, stream_count, "Replay finished");
+    tokio::time::sleep(PUBLISH_DONE_DELAY).await;
+    publisher
+        .publish_done(
+            request_id,
+            PublishDoneStatusCode::TrackEnded as u64,
+            stream_count,
+            "recording ended".to_string(),
+        )
+        .await
+}
+
+fn subgroups(group: &RecordedGroup) -> BTreeMap<u64, Vec<&FetchObjectField>> {
+    let mut subgroups: BTreeMap<u64, Vec<&FetchObjectField>> = BTreeMap::new();
+    for object in &group.objects {
+        subgroups
+            .entry(object.subgroup_id)
+            .or_default()
+            .push(object);
+    }
+    subgroups
+}
+
+async fn send_subgroup(
+    factory: &moqt::StreamDataSenderFactory<QUIC>,
+    objects: &[&FetchObjectField],
+) -> anyhow::Result<()> {
+    let Some(first) = objects.first() else {
+        return Ok(());
+    };
+    let has_extensions = objects
+        .iter()
+        .any(|object| !object.extension_headers.key_value_pairs.is_empty());
+    let sender = factory.next().await?;
+    let header = sender.create_header(
+        first.group_id,
+        SubgroupId::Value(first.subgroup_id),
+        first.publisher_priority,
+        false,
+        has_extensions,
+    );
+    let mut stream = sender.send_header(header).await?;
+    let mut previous_object_id: Option<u64> = None;
+    for object in objects {
+        let object_id_delta = match previous_object_id {
+            Some(previous) => object.object_id - previous - 1,
+            None => object.object_id,
+        };
+        let subgroup_object = match &object.fetch_object {
+            FetchObject::Payload(payload) => SubgroupObject::new_payload(payload.clone()),
+            FetchObject::Status(status) => SubgroupObject::new_status(u8::from(*status) as u64),
+        };
+        let field = stream.create_object_field(
+            object_id_delta,
+            object.extension_headers.clone(),
+            subgroup_object,
+        );
+        stream.send(field).await?;
+        previous_object_id = Some(object.object_id);
+    }
+    stream.close().await
+}
+
+async fn serve_fetch(
+    recording: Arc<Recording>,
+    publisher: Publisher<QUIC>,
+    handler: FetchHandler<QUIC>,
+) {
+    let track_key = &recording.track_key;
+    let FetchParams::Standalone {
+        track_namespace,
+        track_name,
+        start_location,
+        end_location,
+    } = &handler.fetch.fetch_params
+    else {
+        // Joining FETCHes are answered by the relay from its cache.
+        let _ = handler
+            .error(
+                FetchErrorCode::NotSupported as u64,
+                "joining fetch is not supported by replay".to_string(),
+            )
+            .await;
+        return;
+    };
+    if track_namespace.join("/") != track_key.track_namespace || *track_name != track_key.track_name
+    {
+        let _ = handler
+            .error(
+                FetchErrorCode::TrackDoesNotExist as u64,
+                "track is not in the recording".to_string(),
+            )
+            .await;
+        return;
+    }
+    let objects = recording.objects_in(*start_location, *end_location, handler.group_order);
+    let Some(last) = objects
+        .iter()
+        .map(|object| (object.group_id, object.object_id))
+        .max()
+    else {
+        let _ = handler
+            .error(
+                FetchErrorCode::NoObjects as u64,
+                "no recorded objects in range".to_string(),
+            )
+            .await;
+        return;
+    };
+    let last = moqt::Location {
+        group_id: last.0,
+        object_id: last.1,
+    };
+    let end_of_track = recording.largest_location() == Some(last);
+    // Like the relay's own FETCH_OK, the end location is just past the last
+    // object served.
+    let end = moqt::Location {
+        group_id: last.group_id,
+        object_id: last.object_id + 1,
+    };
+    if let Err(error) = handler.ok(end_of_track, end).await {
+        tracing::warn!(%track_key, ?error, "Failed to accept replay fetch");
+        return;
+    }
+    let result = async {
+        let sender = publisher.create_fetch_stream(handler.request_id).await?;
+        for object in objects {
+            sender.send(object).await?;
+        }
+        sender.close().await
+    }
+    .await;
+    if let Err(error) = result {
+        tracing::warn!(%track_key, %error, "Failed to serve replay fetch");
+    }
+}
@@ -1,6 +1,10 @@
+pub(crate) mod disk_tier;
 pub(crate) mod duration;
 pub(crate) mod eviction_job;
 pub(crate) mod group_cache;
 pub(crate) mod known_ranges;
+pub(crate) mod metrics;
+pub(crate) mod quota;
 pub(crate) mod store;
 pub(crate) mod track_cache;
+pub(crate) mod transit;
@@ -0,0 +1,217 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use bytes::BytesMut;
+use tokio::time::Instant;
+
+use crate::modules::types::TrackKey;
+
+pub(crate) const DEFAULT_DISK_RETENTION_SECS: u64 = 300;
+
+/// Encodes an object for a segment or recording. On the wire a
+/// zero-length payload is followed by a status, so an empty object is
+/// stored as the Normal status it decodes to.
+pub(crate) fn encode_stored_object(object: &moqt::FetchObjectField) -> BytesMut {
+    match &object.fetch_object {
+        moqt::FetchObject::Payload(payload) if payload.is_empty() => {
+            let mut object = object.clone();
+            object.fetch_object = moqt::FetchObject::Status(moqt::ObjectStatus::Normal);
+            object.encode()
+        }
+        _ => object.encode(),
+    }
+}
+
+/// Second cache tier: groups evicted from memory are written to one segment
+/// file each and kept for `retention`, so FETCH and late joiners can still
+/// be served from them. The index lives in memory, so segments never outlive
+/// the process; `open` clears what a previous run left behind.
+#[derive(Debug)]
+pub(crate) struct DiskTier {
+    segments_dir: PathBuf,
+    retention: Duration,
+    next_track: AtomicU64,
+}
+
+impl DiskTier {
+    pub(crate) fn open(dir: &Path, retention: Duration) -> anyhow::Result<Arc<Self>> {
+        let segments_dir = dir.join("segments");
+        match std::fs::remove_dir_all(&segments_dir) {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+        std::fs::create_dir_all(&segments_dir)?;
+        tracing::info!(
+            dir = %segments_dir.display(),
+            retention_secs = retention.as_secs(),
+            "Spilling evicted cache groups to disk"
+        );
+        Ok(Arc::new(Self {
+            segments_dir,
+            retention,
+            next_track: AtomicU64::new(0),
+        }))
+    }
+
+    /// A fresh segment directory for one track cache. Directories are
+    /// numbered rather than named after the track so any namespace is safe.
+    pub(crate) fn track_segments(&self, track_key: &TrackKey) -> TrackSegments {
+        let index = self.next_track.fetch_add(1, Ordering::Relaxed);
+        let dir = self.segments_dir.join(format!("track-{index}"));
+        tracing::debug!(%track_key, dir = %dir.display(), "track segment directory");
+        TrackSegments {
+            dir,
+            retention: self.retention,
+            groups: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SpilledGroup {
+    spilled_at: Instant,
+    /// Object ids in the segment, ascending.
+    object_ids: Vec<u64>,
+}
+
+/// Spilled groups of one track. Dropping it deletes the track's segments.
+#[derive(Debug)]
+pub(crate) struct TrackSegments {
+    dir: PathBuf,
+    retention: Duration,
+    groups: Mutex<BTreeMap<u64, SpilledGroup>>,
+}
+
+impl TrackSegments {
+    fn group_path(&self, group_id: u64) -> PathBuf {
+        self.dir.join(format!("{group_id}.seg"))
+    }
+
+    /// Writes the group as FETCH object fields in object id order. The group
+    /// only becomes visible to readers once the file is complete.
+    pub(crate) async fn write_group(
+        &self,
+        group_id: u64,
+        objects: &[moqt::FetchObjectField],
+    ) -> anyhow::Result<()> {
+        let mut bytes = BytesMut::new();
+        for object in objects {
+            bytes.extend_from_slice(&encode_stored_object(object));
+        }
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(self.group_path(group_id), &bytes).await?;
+        let object_ids = objects.iter().map(|object| object.object_id).collect();
+        self.groups.lock().unwrap().insert(
+            group_id,
+            SpilledGroup {
+                spilled_at: Instant::now(),
+                object_ids,
+            },
+        );
+        Ok(())
+    }
+
+    /// Reads a spilled group back; `None` when it is not on disk.
+    pub(crate) async fn read_group(
+        &self,
+        group_id: u64,
+    ) -> anyhow::Result<Option<Vec<moqt::FetchObjectField>>> {
+        if !self.contains(group_id) {
+            return Ok(None);
+        }
+        let mut bytes = BytesMut::from(&tokio::fs::read(self.group_path(group_id)).await?[..]);
+        let mut objects = Vec::new();
+        while !bytes.is_empty() {
+            let object = moqt::FetchObjectField::decode(&mut bytes).map_err(|error| {
+                anyhow::anyhow!("corrupt segment for group {group_id}: {error:?}")
+            })?;
+            objects.push(object);
+        }
+        Ok(Some(objects))
+    }
+
+    pub(crate) fn contains(&self, group_id: u64) -> bool {
+        self.groups.lock().unwrap().contains_key(&group_id)
+    }
+
+    pub(crate) fn group_ids(&self, first: u64, last: u64) -> Vec<u64> {
+        self.groups
+            .lock()
+            .unwrap()
+            .range(first..=last)
+            .map(|(&group_id, _)| group_id)
+            .collect()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.groups.lock().unwrap().is_empty()
+    }
+
+    /// Location of the last object on disk.
+    pub(crate) fn largest_location(&self) -> Option<moqt::Location> {
+        self.groups
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find_map(|(&group_id, group)| {
+                group.object_ids.last().map(|&object_id| moqt::Location {
+                    group_id,
+                    object_id,
+                })
+            })
+    }
+
+    pub(crate) fn has_object_in_range(
+        &self,
+        group_id: u64,
+        start: u64,
+        end_exclusive: Option<u64>,
+    ) -> bool {
+        self.groups
+            .lock()
+            .unwrap()
+            .get(&group_id)
+            .is_some_and(|group| {
+                group.object_ids.iter().any(|&object_id| {
+                    object_id >= start && end_exclusive.is_none_or(|end| object_id < end)
+                })
+            })
+    }
+
+    /// Deletes groups older than the retention and returns their ids.
+    pub(crate) fn expire(&self) -> Vec<u64> {
+        let expired: Vec<u64> = {
+            let mut groups = self.groups.lock().unwrap();
+            let expired: Vec<u64> = groups
+                .iter()
+                .filter(|(_, group)| group.spilled_at.elapsed() > self.retention)
+                .map(|(&group_id, _)| group_id)
+                .collect();
+            for group_id in &expired {
+                groups.remove(group_id);
+            }
+            expired
+        };
+        for &group_id in &expired {
+            if let Err(error) = std::fs::remove_file(self.group_path(group_id)) {
+                tracing::warn!(%error, group_id, "Failed to delete cache segment");
+            }
+        }
+        expired
+    }
+}
+
+impl Drop for TrackSegments {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
@@ -2,20 +2,35 @@ use std::sync::Arc;
 
 use tokio::{task::JoinHandle, time::MissedTickBehavior};
 
-use crate::modules::relay::cache::{duration::duration_from_env, store::TrackCacheStore};
+use crate::modules::{
+    relay::cache::{duration::duration_from_env, store::TrackCacheStore},
+    resource_monitor,
+};
 
-const DEFAULT_TTL_SECS: u64 = 30;
+pub(crate) const DEFAULT_TTL_SECS: u64 = 30;
 const DEFAULT_INTERVAL_SECS: u64 = 5;
 
 pub(crate) fn spawn_cache_eviction_job(cache_store: Arc<TrackCacheStore>) -> JoinHandle<()> {
-    let ttl = duration_from_env("RELAY_CACHE_TTL_SECS", DEFAULT_TTL_SECS);
+    let ttl = cache_store.ttl();
     let interval = duration_from_env("RELAY_CACHE_EVICT_INTERVAL_SECS", DEFAULT_INTERVAL_SECS);
+    let quota_config = cache_store.quota_config().clone();
     tokio::spawn(async move {
         let mut ticker = tokio::time::interval(interval);
         ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
         loop {
             ticker.tick().await;
             cache_store.evict(ttl).await;
+            // Under resource pressure the same budgets are enforced, only smaller.
+            let percent = resource_monitor::pressure().cache_budget_percent();
+            if percent < 100 {
+                cache_store
+                    .enforce_quotas(&quota_config.scaled(percent))
+                    .await;
+            } else {
+                cache_store.enforce_quotas(&quota_config).await;
+            }
+            cache_store.report_transit();
+            cache_store.report_cache_metrics().await;
         }
     })
 }
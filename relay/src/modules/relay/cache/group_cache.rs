@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, btree_map::Entry},
     ops::Range,
     sync::{
         Arc,
@@ -70,36 +70,45 @@ impl GroupCache {
     // FIXME: §8.1 also requires treating a duplicate whose payload/subgroup/priority
     // differs (or an invalid status transition) as Malformed (MUST). Not implemented;
     // we only ignore duplicates (first-wins).
-    pub(crate) async fn append(&self, object_id: Option<u64>, object: Arc<DataObject>) {
-        match object_id {
+    /// Returns false when `object` duplicates one already cached and was
+    /// ignored.
+    pub(crate) async fn append(&self, object_id: Option<u64>, object: Arc<DataObject>) -> bool {
+        let inserted = match object_id {
             // No object_id = subgroup header. Keep the first one (first-wins).
             None => {
                 let mut header = self.header.write().await;
-                if header.is_none() {
+                let inserted = header.is_none();
+                if inserted {
                     *header = Some(object);
                 }
+                inserted
             }
             // A later object with the same object_id is ignored (draft-14 §8.1 dedup).
-            Some(object_id) => {
-                self.objects
-                    .write()
-                    .await
-                    .entry(object_id)
-                    .or_insert((Instant::now(), object));
-            }
+            Some(object_id) => match self.objects.write().await.entry(object_id) {
+                Entry::Vacant(entry) => {
+                    entry.insert((Instant::now(), object));
+                    true
+                }
+                Entry::Occupied(_) => false,
+            },
+        };
+        if inserted {
+            self.notify.notify_waiters();
         }
-        self.notify.notify_waiters();
+        inserted
     }
 
-    pub(crate) async fn evict_expired_objects(&self, ttl: Duration) -> Option<Range<u64>> {
+    pub(crate) async fn evict_expired_objects(&self, ttl: Duration) -> Option<ExpiredObjects> {
         let mut removed_start = None;
         let mut removed_end = None;
+        let mut count = 0;
         self.objects
             .write()
             .await
             .retain(|&object_id, (inserted, _)| {
                 let keep = inserted.elapsed() <= ttl;
                 if !keep {
+                    count += 1;
                     removed_start =
                         Some(removed_start.map_or(object_id, |start: u64| start.min(object_id)));
                     removed_end =
@@ -109,7 +118,10 @@ impl GroupCache {
             });
         removed_start
             .zip(removed_end)
-            .map(|(start, end)| start..end.saturating_add(1))
+            .map(|(start, end)| ExpiredObjects {
+                ids: start..end.saturating_add(1),
+                count,
+            })
     }
 
     pub(crate) async fn is_evictable(&self) -> bool {
@@ -124,6 +136,31 @@ impl GroupCache {
         self.lifecycle() != SubgroupLifecycle::AwaitingCloseSignal
     }
 
+    /// Whether live ingest may still append to this entry.
+    pub(crate) fn is_awaiting_close(&self) -> bool {
+        self.lifecycle() == SubgroupLifecycle::AwaitingCloseSignal
+    }
+
+    /// Payload bytes held by the cached objects.
+    pub(crate) async fn byte_size(&self) -> usize {
+        self.objects
+            .read()
+            .await
+            .values()
+            .map(|(_, object)| object.payload_len())
+            .sum()
+    }
+
+    /// Insertion time of the oldest cached object, if any.
+    pub(crate) async fn oldest_inserted_at(&self) -> Option<Instant> {
+        self.objects
+            .read()
+            .await
+            .values()
+            .map(|(inserted, _)| *inserted)
+            .min()
+    }
+
     pub(crate) async fn header(&self) -> Option<Arc<DataObject>> {
         self.header.read().await.clone()
     }
@@ -170,11 +207,24 @@ impl GroupCache {
         &self,
         object_id: u64,
     ) -> Option<(u64, Arc<DataObject>)> {
+        self.timed_object_from_or_wait(object_id)
+            .await
+            .map(|(id, _, object)| (id, object))
+    }
+
+    /// [`Self::object_from_or_wait`], also returning when the object entered
+    /// the cache. Live egress uses it to measure relay transit time.
+    pub(crate) async fn timed_object_from_or_wait(
+        &self,
+        object_id: u64,
+    ) -> Option<(u64, Instant, Arc<DataObject>)> {
         loop {
             // Create notified() before the check to avoid a race between it and the wait.
             let notified = self.notify.notified();
-            if let Some((&id, (_, object))) = self.objects.read().await.range(object_id..).next() {
-                return Some((id, object.clone()));
+            if let Some((&id, (inserted, object))) =
+                self.objects.read().await.range(object_id..).next()
+            {
+                return Some((id, *inserted, object.clone()));
             }
             if self.is_closed() {
                 return None;
@@ -214,6 +264,48 @@ impl GroupCache {
     }
 }
 
+/// What one TTL pass removed from a group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExpiredObjects {
+    /// Smallest to largest removed object id; objects in between may remain.
+    pub(crate) ids: Range<u64>,
+    pub(crate) count: u64,
+}
+
+/// A reader's position in one group cache. It holds the cache itself, so
+/// reading on never looks the group up in its track again; a reader that
+/// has caught up waits on the cache's notify for the next append.
+pub(crate) struct GroupCursor {
+    /// `None` when the group was not cached; the cursor is then at its end.
+    group: Option<Arc<GroupCache>>,
+    next_object_id: u64,
+}
+
+impl GroupCursor {
+    pub(crate) fn new(group: Option<Arc<GroupCache>>, object_id: u64) -> Self {
+        Self {
+            group,
+            next_object_id: object_id,
+        }
+    }
+
+    /// The first object id not yet returned by [`Self::next`].
+    pub(crate) fn position(&self) -> u64 {
+        self.next_object_id
+    }
+
+    /// [`GroupCache::timed_object_from_or_wait`] from the cursor's position,
+    /// moving past the object returned. Cancel-safe: the position only
+    /// moves once an object is returned.
+    pub(crate) async fn next(&mut self) -> Option<(u64, Instant, Arc<DataObject>)> {
+        let group = self.group.as_ref()?;
+        let (id, inserted_at, object) =
+            group.timed_object_from_or_wait(self.next_object_id).await?;
+        self.next_object_id = id + 1;
+        Some((id, inserted_at, object))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,11 +349,14 @@ mod tests {
     async fn append_same_object_id_keeps_first() {
         // Arrange: two objects with the same object_id but distinct payloads
         let cache = GroupCache::new(SubgroupLifecycle::AwaitingCloseSignal);
-        cache.append(Some(0), payload_object(b"first")).await;
-        cache.append(Some(0), payload_object(b"second")).await;
+        let first = cache.append(Some(0), payload_object(b"first")).await;
+        let second = cache.append(Some(0), payload_object(b"second")).await;
         // Act
         let snapshot = cache.objects_snapshot().await;
-        // Assert: only the first object survives (first-wins dedup)
+        // Assert: only the first object survives (first-wins dedup), and the
+        // second is reported as a duplicate
+        assert!(first);
+        assert!(!second);
         assert_eq!(snapshot.len(), 1);
         let (id, object) = &snapshot[0];
         assert_eq!(*id, 0);
@@ -309,6 +404,30 @@ mod tests {
         assert_eq!(id, 5);
     }
 
+    #[tokio::test]
+    async fn cursor_follows_appends_until_the_group_closes() {
+        // Arrange: a cursor that has read the only cached object
+        let cache = Arc::new(GroupCache::new(SubgroupLifecycle::AwaitingCloseSignal));
+        cache.append(Some(0), payload_object(b"o0")).await;
+        let mut cursor = GroupCursor::new(Some(cache.clone()), 0);
+        let first = cursor.next().await.map(|(id, _, _)| id);
+        // Act: the next read waits until object 2 arrives, then the group closes
+        let reader = tokio::spawn(async move {
+            let second = cursor.next().await.map(|(id, _, _)| id);
+            let end = cursor.next().await.map(|(id, _, _)| id);
+            (second, end, cursor.position())
+        });
+        tokio::task::yield_now().await;
+        cache.append(Some(2), payload_object(b"o2")).await;
+        cache.mark_end_of_group();
+        let (second, end, position) = reader.await.unwrap();
+        // Assert
+        assert_eq!(first, Some(0));
+        assert_eq!(second, Some(2));
+        assert_eq!(end, None);
+        assert_eq!(position, 3);
+    }
+
     #[tokio::test(start_paused = true)]
     async fn evict_removes_objects_older_than_ttl() {
         // Arrange: object 0 at t=0, object 1 at t=6s, TTL=10s
@@ -0,0 +1,99 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Cache counters of one track, for sizing `RELAY_CACHE_TTL_SECS`: what
+/// went in, what FETCH found or missed, and what aged out before anyone
+/// read it. Counters cover one reporting window and are reset by
+/// [`CacheMetrics::take_snapshot`].
+#[derive(Debug, Default)]
+pub(crate) struct CacheMetrics {
+    stream_inserts: AtomicU64,
+    datagram_inserts: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    expired: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct CacheMetricsSnapshot {
+    pub(crate) stream_inserts: u64,
+    pub(crate) datagram_inserts: u64,
+    /// FETCH ranges served from the cache.
+    pub(crate) hits: u64,
+    /// FETCH ranges the cache could not serve in full.
+    pub(crate) misses: u64,
+    /// Objects dropped by TTL expiry.
+    pub(crate) expired: u64,
+}
+
+impl CacheMetrics {
+    pub(crate) fn record_stream_insert(&self) {
+        self.stream_inserts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_datagram_insert(&self) {
+        self.datagram_inserts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_expired(&self, objects: u64) {
+        self.expired.fetch_add(objects, Ordering::Relaxed);
+    }
+
+    /// Returns the current window and starts a new one.
+    pub(crate) fn take_snapshot(&self) -> CacheMetricsSnapshot {
+        CacheMetricsSnapshot {
+            stream_inserts: self.stream_inserts.swap(0, Ordering::Relaxed),
+            datagram_inserts: self.datagram_inserts.swap(0, Ordering::Relaxed),
+            hits: self.hits.swap(0, Ordering::Relaxed),
+            misses: self.misses.swap(0, Ordering::Relaxed),
+            expired: self.expired.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+impl CacheMetricsSnapshot {
+    pub(crate) fn is_idle(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Share of FETCH ranges served from the cache, in percent; `None`
+    /// without any FETCH in the window.
+    pub(crate) fn hit_ratio_percent(&self) -> Option<u64> {
+        (self.hits * 100).checked_div(self.hits + self.misses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_starts_a_new_window() {
+        // Arrange
+        let metrics = CacheMetrics::default();
+        metrics.record_stream_insert();
+        metrics.record_hit();
+        metrics.record_hit();
+        metrics.record_hit();
+        metrics.record_miss();
+        metrics.record_expired(4);
+
+        // Act
+        let first = metrics.take_snapshot();
+        let second = metrics.take_snapshot();
+
+        // Assert
+        assert_eq!(first.stream_inserts, 1);
+        assert_eq!(first.expired, 4);
+        assert_eq!(first.hit_ratio_percent(), Some(75));
+        assert!(second.is_idle());
+        assert_eq!(second.hit_ratio_percent(), None);
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::modules::types::TrackKey;
+
+pub(crate) const DEFAULT_SESSION_QUOTA_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Cache byte budgets: one per track, one per upstream session and one for
+/// the whole cache.
+///
+/// A session budget is keyed by (upstream session, tenant), where the tenant
+/// is the first element of the track namespace. Its limit is the tenant
+/// override if one is configured, otherwise the default. A limit of `0`
+/// disables the quota.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CacheQuotaConfig {
+    default_session_quota_bytes: u64,
+    tenant_quota_bytes: HashMap<String, u64>,
+    track_quota_bytes: u64,
+    global_quota_bytes: u64,
+}
+
+impl CacheQuotaConfig {
+    pub(crate) fn new(
+        default_session_quota_bytes: u64,
+        tenant_quota_bytes: HashMap<String, u64>,
+    ) -> Self {
+        Self {
+            default_session_quota_bytes,
+            tenant_quota_bytes,
+            track_quota_bytes: 0,
+            global_quota_bytes: 0,
+        }
+    }
+
+    pub(crate) fn with_track_quota_bytes(mut self, track_quota_bytes: u64) -> Self {
+        self.track_quota_bytes = track_quota_bytes;
+        self
+    }
+
+    pub(crate) fn with_global_quota_bytes(mut self, global_quota_bytes: u64) -> Self {
+        self.global_quota_bytes = global_quota_bytes;
+        self
+    }
+
+    /// Byte limit of any single track; `None` means unlimited.
+    pub(crate) fn track_quota(&self) -> Option<u64> {
+        (self.track_quota_bytes > 0).then_some(self.track_quota_bytes)
+    }
+
+    /// Byte limit of the whole cache; `None` means unlimited.
+    pub(crate) fn global_quota(&self) -> Option<u64> {
+        (self.global_quota_bytes > 0).then_some(self.global_quota_bytes)
+    }
+
+    /// Byte limit for one session's tracks under `tenant`; `None` means unlimited.
+    pub(crate) fn quota_for(&self, tenant: &str) -> Option<u64> {
+        let quota = self
+            .tenant_quota_bytes
+            .get(tenant)
+            .copied()
+            .unwrap_or(self.default_session_quota_bytes);
+        (quota > 0).then_some(quota)
+    }
+
+    /// The same budgets cut to `percent` of their limits, used while the
+    /// relay is under resource pressure. Unlimited budgets stay unlimited.
+    pub(crate) fn scaled(&self, percent: u64) -> Self {
+        let scale = |bytes: u64| match bytes {
+            0 => 0,
+            bytes => (bytes.saturating_mul(percent) / 100).max(1),
+        };
+        Self {
+            default_session_quota_bytes: scale(self.default_session_quota_bytes),
+            tenant_quota_bytes: self
+                .tenant_quota_bytes
+                .iter()
+                .map(|(tenant, bytes)| (tenant.clone(), scale(*bytes)))
+                .collect(),
+            track_quota_bytes: scale(self.track_quota_bytes),
+            global_quota_bytes: scale(self.global_quota_bytes),
+        }
+    }
+
+    pub(crate) fn tenant_of(track_key: &TrackKey) -> &str {
+        track_key
+            .track_namespace
+            .split('/')
+            .next()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn parse_tenant_overrides(value: &str) -> HashMap<String, u64> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let parsed = entry
+                    .split_once('=')
+                    .and_then(|(tenant, bytes)| Some((tenant.trim(), bytes.trim().parse().ok()?)));
+                if parsed.is_none() {
+                    tracing::warn!(entry, "ignoring malformed tenant cache quota override");
+                }
+                parsed.map(|(tenant, bytes)| (tenant.to_string(), bytes))
+            })
+            .collect()
+    }
+}
+
+/// Cumulative counters describing how hard the cache quotas are pushing back.
+#[derive(Debug, Default)]
+pub(crate) struct CacheQuotaMetrics {
+    over_quota_budgets: AtomicU64,
+    unresolved_budgets: AtomicU64,
+    evicted_groups: AtomicU64,
+    evicted_bytes: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct CacheQuotaSnapshot {
+    /// Budgets found over their limit at the start of an enforcement pass.
+    pub(crate) over_quota_budgets: u64,
+    /// Budgets still over their limit after eviction ran out of candidates.
+    pub(crate) unresolved_budgets: u64,
+    pub(crate) evicted_groups: u64,
+    pub(crate) evicted_bytes: u64,
+}
+
+impl CacheQuotaMetrics {
+    pub(crate) fn record_over_quota(&self) {
+        self.over_quota_budgets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_unresolved(&self) {
+        self.unresolved_budgets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_eviction(&self, freed_bytes: usize) {
+        self.evicted_groups.fetch_add(1, Ordering::Relaxed);
+        self.evicted_bytes
+            .fetch_add(freed_bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> CacheQuotaSnapshot {
+        CacheQuotaSnapshot {
+            over_quota_budgets: self.over_quota_budgets.load(Ordering::Relaxed),
+            unresolved_budgets: self.unresolved_budgets.load(Ordering::Relaxed),
+            evicted_groups: self.evicted_groups.load(Ordering::Relaxed),
+            evicted_bytes: self.evicted_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tenant_override_takes_precedence_over_default() {
+        // Arrange
+        let config = CacheQuotaConfig::new(100, HashMap::from([("big".to_string(), 1000)]));
+        // Act / Assert
+        assert_eq!(config.quota_for("big"), Some(1000));
+        assert_eq!(config.quota_for("other"), Some(100));
+    }
+
+    #[test]
+    fn zero_quota_means_unlimited() {
+        // Arrange: unlimited by default, but one tenant is capped
+        let config = CacheQuotaConfig::new(0, HashMap::from([("capped".to_string(), 10)]));
+        // Act / Assert
+        assert_eq!(config.quota_for("any"), None);
+        assert_eq!(config.quota_for("capped"), Some(10));
+    }
+
+    #[test]
+    fn scaled_quota_shrinks_limits_but_keeps_unlimited() {
+        // Arrange
+        let config = CacheQuotaConfig::new(0, HashMap::from([("capped".to_string(), 1000)]))
+            .with_global_quota_bytes(4000);
+        // Act
+        let scaled = config.scaled(25);
+        // Assert
+        assert_eq!(scaled.quota_for("any"), None);
+        assert_eq!(scaled.quota_for("capped"), Some(250));
+        assert_eq!(scaled.track_quota(), None);
+        assert_eq!(scaled.global_quota(), Some(1000));
+    }
+
+    #[test]
+    fn parse_tenant_overrides_skips_malformed_entries() {
+        // Arrange
+        let value = "alpha=10, beta = 20,broken,gamma=x,";
+        // Act
+        let overrides = CacheQuotaConfig::parse_tenant_overrides(value);
+        // Assert
+        assert_eq!(
+            overrides,
+            HashMap::from([("alpha".to_string(), 10), ("beta".to_string(), 20)])
+        );
+    }
+
+    #[test]
+    fn tenant_is_first_namespace_element() {
+        // Arrange
+        let track_key = TrackKey::new("acme/room/alice", "video");
+        // Act / Assert
+        assert_eq!(CacheQuotaConfig::tenant_of(&track_key), "acme");
+    }
+}
@@ -1,20 +1,108 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use dashmap::DashMap;
 
-use crate::modules::{relay::cache::track_cache::TrackCache, types::TrackKey};
+use crate::modules::{
+    relay::cache::{
+        disk_tier::DiskTier,
+        quota::{CacheQuotaConfig, CacheQuotaMetrics, DEFAULT_SESSION_QUOTA_BYTES},
+        track_cache::{TrackCache, quota_eviction::QuotaEvictionCandidate},
+    },
+    types::{SessionId, TrackKey},
+};
 
 pub(crate) struct TrackCacheStore {
     caches: DashMap<TrackKey, Arc<TrackCache>>,
+    /// Upstream session each track's cache usage is charged to.
+    owners: DashMap<TrackKey, SessionId>,
+    quota_metrics: CacheQuotaMetrics,
+    /// How long objects stay cached; also the MAX_CACHE_DURATION the relay
+    /// grants downstream subscribers.
+    ttl: Duration,
+    /// Relay transit time above which live egress logs a warning.
+    transit_warn_threshold: Option<Duration>,
+    /// Byte budgets the eviction job enforces.
+    quota_config: CacheQuotaConfig,
+    /// Where evicted groups are spilled; `None` keeps the cache in memory.
+    disk_tier: Option<Arc<DiskTier>>,
+}
+
+/// What a quota budget covers.
+#[derive(Debug)]
+enum QuotaScope {
+    Track(TrackKey),
+    Session {
+        session_id: SessionId,
+        tenant: String,
+    },
+    Global,
+}
+
+impl std::fmt::Display for QuotaScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Track(track_key) => write!(f, "track {track_key}"),
+            Self::Session { session_id, tenant } => {
+                write!(f, "session {session_id} tenant {tenant}")
+            }
+            Self::Global => write!(f, "global"),
+        }
+    }
+}
+
+/// Tracks charged to one quota budget.
+struct QuotaBudget {
+    scope: QuotaScope,
+    tracks: Vec<Arc<TrackCache>>,
 }
 
 impl TrackCacheStore {
+    #[cfg(test)]
     pub(crate) fn new() -> Self {
+        Self::with_ttl(Duration::from_secs(super::eviction_job::DEFAULT_TTL_SECS))
+    }
+
+    pub(crate) fn with_ttl(ttl: Duration) -> Self {
         Self {
             caches: DashMap::new(),
+            owners: DashMap::new(),
+            quota_metrics: CacheQuotaMetrics::default(),
+            ttl,
+            transit_warn_threshold: None,
+            quota_config: CacheQuotaConfig::new(DEFAULT_SESSION_QUOTA_BYTES, HashMap::new()),
+            disk_tier: None,
         }
     }
 
+    pub(crate) fn with_disk_tier(mut self, disk_tier: Option<Arc<DiskTier>>) -> Self {
+        self.disk_tier = disk_tier;
+        self
+    }
+
+    pub(crate) fn with_quota_config(mut self, quota_config: CacheQuotaConfig) -> Self {
+        self.quota_config = quota_config;
+        self
+    }
+
+    pub(crate) fn with_transit_warn_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.transit_warn_threshold = threshold;
+        self
+    }
+
+    pub(crate) fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub(crate) fn quota_config(&self) -> &CacheQuotaConfig {
+        &self.quota_config
+    }
+
+    /// Charges the track's cache usage to `publisher_session_id`. The latest
+    /// ingest to start wins, matching the publisher that feeds the cache.
+    pub(crate) fn set_owner(&self, track_key: &TrackKey, publisher_session_id: SessionId) {
+        self.owners.insert(track_key.clone(), publisher_session_id);
+    }
+
     pub(crate) fn get(&self, track_key: &TrackKey) -> Option<Arc<TrackCache>> {
         // clone the Arc to drop the Ref and release the DashMap shard lock
         self.caches.get(track_key).map(|v| v.clone())
@@ -23,10 +111,48 @@ impl TrackCacheStore {
     pub(crate) fn get_or_create(&self, track_key: &TrackKey) -> Arc<TrackCache> {
         self.caches
             .entry(track_key.clone())
-            .or_insert_with(|| Arc::new(TrackCache::new()))
+            .or_insert_with(|| {
+                let segments = self
+                    .disk_tier
+                    .as_ref()
+                    .map(|disk_tier| disk_tier.track_segments(track_key));
+                Arc::new(
+                    TrackCache::with_transit_warn_threshold(self.transit_warn_threshold)
+                        .with_segments(segments),
+                )
+            })
             .clone()
     }
 
+    /// Every cached track, copied out so no shard lock is held while the
+    /// caller awaits.
+    pub(crate) fn snapshot(&self) -> Vec<(TrackKey, Arc<TrackCache>)> {
+        self.caches
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    /// Drops the track's cache at once, e.g. when its upstream ended. Holders
+    /// of the old `Arc` keep their copy; the next ingest starts fresh.
+    pub(crate) fn purge(&self, track_key: &TrackKey) {
+        if self.caches.remove(track_key).is_some() {
+            tracing::info!(%track_key, "purged track cache");
+        }
+        self.owners.remove(track_key);
+    }
+
+    /// Drops the track's groups below `group_id` ahead of their TTL, e.g.
+    /// once the last subscriber's range has been delivered. Groups live
+    /// ingest still writes are kept.
+    pub(crate) async fn delete_groups_before(&self, track_key: &TrackKey, group_id: u64) {
+        let Some(track) = self.get(track_key) else {
+            return;
+        };
+        let freed_bytes = track.delete_groups_before(group_id).await;
+        tracing::info!(%track_key, group_id, freed_bytes, "deleted delivered groups");
+    }
+
     pub(crate) async fn evict(&self, ttl: Duration) {
         // Snapshot handles so per-track eviction runs without holding a shard lock.
         let entries: Vec<(TrackKey, Arc<TrackCache>)> = self
@@ -54,18 +180,201 @@ impl TrackCacheStore {
         //   within the gap, leaving fresh objects behind at count == 1;
         //   re-checking content under the shard lock keeps them.
         for key in empty_keys {
-            self.caches.remove_if(&key, |_, track| {
+            let removed = self.caches.remove_if(&key, |_, track| {
                 Arc::strong_count(track) == 1 && track.is_empty_sync()
             });
+            if removed.is_some() {
+                self.owners.remove(&key);
+            }
+        }
+    }
+
+    /// Logs each track's relay transit histogram for the window since the
+    /// last report. Tracks that sent nothing are skipped.
+    pub(crate) fn report_transit(&self) {
+        for entry in self.caches.iter() {
+            let snapshot = entry.value().transit().take_snapshot();
+            if snapshot.count == 0 {
+                continue;
+            }
+            tracing::info!(
+                track_key = %entry.key(),
+                objects = snapshot.count,
+                mean_us = snapshot.mean_micros(),
+                p50_ms = snapshot.quantile_upper_bound_ms(0.5),
+                p99_ms = snapshot.quantile_upper_bound_ms(0.99),
+                max_us = snapshot.max_micros,
+                over_threshold = snapshot.over_threshold,
+                buckets = ?snapshot.buckets,
+                "relay transit time"
+            );
+        }
+    }
+
+    /// Logs each track's cache counters for the window since the last report
+    /// with the bytes it holds now. Tracks with no activity are skipped.
+    pub(crate) async fn report_cache_metrics(&self) {
+        for (track_key, track) in self.snapshot() {
+            let metrics = track.metrics().take_snapshot();
+            if metrics.is_idle() {
+                continue;
+            }
+            let occupancy_bytes = track.byte_size().await;
+            tracing::info!(
+                %track_key,
+                stream_inserts = metrics.stream_inserts,
+                datagram_inserts = metrics.datagram_inserts,
+                fetch_hits = metrics.hits,
+                fetch_misses = metrics.misses,
+                fetch_hit_ratio_percent = ?metrics.hit_ratio_percent(),
+                expired_objects = metrics.expired,
+                occupancy_bytes,
+                "track cache metrics"
+            );
+        }
+    }
+
+    /// Evicts the oldest groups of every budget over its quota until it fits:
+    /// each track on its own, then each session's tracks, so one flooding
+    /// publisher only ever loses its own cache, then the whole cache.
+    pub(crate) async fn enforce_quotas(&self, config: &CacheQuotaConfig) {
+        let tracks = self.snapshot();
+        if let Some(quota) = config.track_quota() {
+            for (track_key, track) in &tracks {
+                let budget = QuotaBudget {
+                    scope: QuotaScope::Track(track_key.clone()),
+                    tracks: vec![track.clone()],
+                };
+                self.enforce_budget(&budget, quota as usize).await;
+            }
+        }
+        for budget in self.session_budgets() {
+            let QuotaScope::Session { tenant, .. } = &budget.scope else {
+                continue;
+            };
+            let Some(quota) = config.quota_for(tenant) else {
+                continue;
+            };
+            self.enforce_budget(&budget, quota as usize).await;
+        }
+        if let Some(quota) = config.global_quota() {
+            let budget = QuotaBudget {
+                scope: QuotaScope::Global,
+                tracks: tracks.into_iter().map(|(_, track)| track).collect(),
+            };
+            self.enforce_budget(&budget, quota as usize).await;
         }
     }
+
+    fn session_budgets(&self) -> Vec<QuotaBudget> {
+        // Snapshot ownership first so no owners shard lock is held while
+        // reading the cache map.
+        let owners: Vec<(TrackKey, SessionId)> = self
+            .owners
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        let mut budgets = HashMap::<(SessionId, String), Vec<Arc<TrackCache>>>::new();
+        for (track_key, session_id) in owners {
+            let Some(track) = self.get(&track_key) else {
+                continue;
+            };
+            let tenant = CacheQuotaConfig::tenant_of(&track_key).to_string();
+            budgets.entry((session_id, tenant)).or_default().push(track);
+        }
+        budgets
+            .into_iter()
+            .map(|((session_id, tenant), tracks)| QuotaBudget {
+                scope: QuotaScope::Session { session_id, tenant },
+                tracks,
+            })
+            .collect()
+    }
+
+    async fn enforce_budget(&self, budget: &QuotaBudget, quota: usize) {
+        let mut used = 0;
+        for track in &budget.tracks {
+            used += track.byte_size().await;
+        }
+        if used <= quota {
+            return;
+        }
+
+        self.quota_metrics.record_over_quota();
+        let used_before = used;
+        let mut evicted_groups = 0u64;
+        while used > quota {
+            let Some((track, group_id)) = Self::oldest_quota_candidate(&budget.tracks).await else {
+                // Only groups live ingest is still writing remain.
+                self.quota_metrics.record_unresolved();
+                tracing::warn!(
+                    scope = %budget.scope,
+                    quota_bytes = quota,
+                    used_bytes = used,
+                    "cache quota exceeded with no evictable group left"
+                );
+                break;
+            };
+            let freed = track.evict_group(group_id).await;
+            self.quota_metrics.record_eviction(freed);
+            evicted_groups += 1;
+            used = used.saturating_sub(freed);
+        }
+
+        let metrics = self.quota_metrics.snapshot();
+        tracing::info!(
+            scope = %budget.scope,
+            quota_bytes = quota,
+            used_bytes_before = used_before,
+            used_bytes_after = used,
+            evicted_groups,
+            evicted_groups_total = metrics.evicted_groups,
+            evicted_bytes_total = metrics.evicted_bytes,
+            over_quota_budgets_total = metrics.over_quota_budgets,
+            "cache quota enforced"
+        );
+    }
+
+    /// The group with the oldest object across all tracks of a budget.
+    async fn oldest_quota_candidate(tracks: &[Arc<TrackCache>]) -> Option<(Arc<TrackCache>, u64)> {
+        let mut oldest = None;
+        for track in tracks {
+            let Some(candidate) = track.quota_eviction_candidate().await else {
+                continue;
+            };
+            let is_older =
+                oldest
+                    .as_ref()
+                    .is_none_or(|(_, current): &(_, QuotaEvictionCandidate)| {
+                        candidate.oldest_inserted_at < current.oldest_inserted_at
+                    });
+            if is_older {
+                oldest = Some((track.clone(), candidate));
+            }
+        }
+        oldest.map(|(track, candidate)| (track, candidate.group_id))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::relay::cache::quota::CacheQuotaSnapshot;
     use std::time::Duration;
 
+    #[test]
+    fn get_or_create_applies_transit_warn_threshold() {
+        // Arrange
+        let store =
+            TrackCacheStore::new().with_transit_warn_threshold(Some(Duration::from_millis(5)));
+        let key = TrackKey::new("ns", "track");
+        // Act
+        let track = store.get_or_create(&key);
+        // Assert
+        assert!(track.transit().record(Duration::from_millis(6)));
+        assert!(!track.transit().record(Duration::from_millis(5)));
+    }
+
     #[tokio::test(start_paused = true)]
     async fn evict_removes_unreferenced_track() {
         // Arrange: a track held only by the store (the returned Arc is dropped immediately)
@@ -125,6 +434,178 @@ mod tests {
         assert!(store.get(&key).is_none());
     }
 
+    async fn fill_group(track: &TrackCache, group_id: u64, payload_len: usize, close: bool) {
+        use crate::modules::{core::data_object::DataObject, relay::types::StreamSubgroupId};
+        use moqt::{
+            ExtensionHeaders, SubgroupHeader, SubgroupId, SubgroupObject, SubgroupObjectField,
+        };
+
+        let subgroup = StreamSubgroupId::Value(0);
+        let header = SubgroupHeader::new(0, group_id, SubgroupId::Value(0), 0, false, false);
+        let message_type = header.message_type;
+        track
+            .append_live_stream_object(
+                group_id,
+                &subgroup,
+                None,
+                DataObject::SubgroupHeader(header),
+            )
+            .await;
+        track
+            .append_live_stream_object(
+                group_id,
+                &subgroup,
+                Some(0),
+                DataObject::SubgroupObject(SubgroupObjectField {
+                    message_type,
+                    object_id_delta: 0,
+                    extension_headers: ExtensionHeaders::default(),
+                    subgroup_object: SubgroupObject::new_payload(bytes::Bytes::from(vec![
+                        0;
+                        payload_len
+                    ])),
+                }),
+            )
+            .await;
+        if close {
+            track.close_stream_subgroup(group_id, &subgroup).await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn enforce_quotas_evicts_oldest_groups_of_offending_session_only() {
+        // Arrange: session 1 floods three 60-byte groups, session 2 holds one,
+        // and every session may keep 100 bytes.
+        let store = TrackCacheStore::new();
+        let flooding_key = TrackKey::new("tenant/flood", "track");
+        let quiet_key = TrackKey::new("tenant/quiet", "track");
+        let flooding = store.get_or_create(&flooding_key);
+        let quiet = store.get_or_create(&quiet_key);
+        store.set_owner(&flooding_key, 1);
+        store.set_owner(&quiet_key, 2);
+        fill_group(&quiet, 0, 60, true).await;
+        for group_id in 0..3 {
+            tokio::time::advance(Duration::from_secs(1)).await;
+            fill_group(&flooding, group_id, 60, true).await;
+        }
+        let config = CacheQuotaConfig::new(100, HashMap::new());
+
+        // Act
+        store.enforce_quotas(&config).await;
+
+        // Assert: only the flooding session's oldest groups are gone
+        assert!(!flooding.has_stream_group(0).await);
+        assert!(!flooding.has_stream_group(1).await);
+        assert!(flooding.has_stream_group(2).await);
+        assert!(quiet.has_stream_group(0).await);
+        let metrics = store.quota_metrics.snapshot();
+        assert_eq!(metrics.over_quota_budgets, 1);
+        assert_eq!(metrics.evicted_groups, 2);
+        assert_eq!(metrics.evicted_bytes, 120);
+    }
+
+    #[tokio::test]
+    async fn enforce_quotas_keeps_groups_still_written_by_live_ingest() {
+        // Arrange: a single open live group already over the quota
+        let store = TrackCacheStore::new();
+        let key = TrackKey::new("tenant/live", "track");
+        let track = store.get_or_create(&key);
+        store.set_owner(&key, 1);
+        fill_group(&track, 0, 200, false).await;
+        let config = CacheQuotaConfig::new(100, HashMap::new());
+
+        // Act
+        store.enforce_quotas(&config).await;
+
+        // Assert: the open group survives and the pressure is reported
+        assert!(track.has_stream_group(0).await);
+        let metrics = store.quota_metrics.snapshot();
+        assert_eq!(metrics.unresolved_budgets, 1);
+        assert_eq!(metrics.evicted_groups, 0);
+    }
+
+    #[tokio::test]
+    async fn enforce_quotas_applies_tenant_override() {
+        // Arrange: the default would evict, but this tenant may keep 1000 bytes
+        let store = TrackCacheStore::new();
+        let key = TrackKey::new("premium/room", "track");
+        let track = store.get_or_create(&key);
+        store.set_owner(&key, 1);
+        fill_group(&track, 0, 200, true).await;
+        fill_group(&track, 1, 200, true).await;
+        let config = CacheQuotaConfig::new(100, HashMap::from([("premium".to_string(), 1000)]));
+
+        // Act
+        store.enforce_quotas(&config).await;
+
+        // Assert
+        assert!(track.has_stream_group(0).await);
+        assert_eq!(
+            store.quota_metrics.snapshot(),
+            CacheQuotaSnapshot::default()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn enforce_quotas_caps_a_single_track() {
+        // Arrange: one session, no session quota, but each track may keep
+        // 100 bytes
+        let store = TrackCacheStore::new();
+        let busy_key = TrackKey::new("tenant/room", "video");
+        let idle_key = TrackKey::new("tenant/room", "audio");
+        let busy = store.get_or_create(&busy_key);
+        let idle = store.get_or_create(&idle_key);
+        store.set_owner(&busy_key, 1);
+        store.set_owner(&idle_key, 1);
+        fill_group(&idle, 0, 60, true).await;
+        for group_id in 0..3 {
+            tokio::time::advance(Duration::from_secs(1)).await;
+            fill_group(&busy, group_id, 60, true).await;
+        }
+        let config = CacheQuotaConfig::new(0, HashMap::new()).with_track_quota_bytes(100);
+
+        // Act
+        store.enforce_quotas(&config).await;
+
+        // Assert: the busy track keeps its newest group, the idle one is untouched
+        assert!(!busy.has_stream_group(0).await);
+        assert!(!busy.has_stream_group(1).await);
+        assert!(busy.has_stream_group(2).await);
+        assert!(idle.has_stream_group(0).await);
+        assert_eq!(store.quota_metrics.snapshot().evicted_groups, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn enforce_quotas_evicts_oldest_groups_across_the_whole_cache() {
+        // Arrange: two sessions within their own quotas, 240 bytes in total
+        // against a 150-byte global cap
+        let store = TrackCacheStore::new();
+        let first_key = TrackKey::new("a/room", "track");
+        let second_key = TrackKey::new("b/room", "track");
+        let first = store.get_or_create(&first_key);
+        let second = store.get_or_create(&second_key);
+        store.set_owner(&first_key, 1);
+        store.set_owner(&second_key, 2);
+        fill_group(&first, 0, 60, true).await;
+        tokio::time::advance(Duration::from_secs(1)).await;
+        fill_group(&second, 0, 60, true).await;
+        tokio::time::advance(Duration::from_secs(1)).await;
+        fill_group(&first, 1, 60, true).await;
+        tokio::time::advance(Duration::from_secs(1)).await;
+        fill_group(&second, 1, 60, true).await;
+        let config = CacheQuotaConfig::new(1000, HashMap::new()).with_global_quota_bytes(150);
+
+        // Act
+        store.enforce_quotas(&config).await;
+
+        // Assert: the two oldest groups go, whichever session owns them
+        assert!(!first.has_stream_group(0).await);
+        assert!(!second.has_stream_group(0).await);
+        assert!(first.has_stream_group(1).await);
+        assert!(second.has_stream_group(1).await);
+        assert_eq!(store.quota_metrics.snapshot().evicted_bytes, 120);
+    }
+
     #[tokio::test]
     async fn evict_keeps_referenced_track() {
         // Arrange: a track someone else still holds (simulating an active ingress/egress)
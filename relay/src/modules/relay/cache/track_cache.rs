@@ -1,3 +1,6 @@
+pub(crate) mod quota_eviction;
+mod spill;
+
 use std::{
     collections::BTreeMap,
     sync::{
@@ -7,14 +10,17 @@ use std::{
     time::Duration,
 };
 
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, time::Instant};
 
 use crate::modules::{
     core::data_object::DataObject,
     relay::{
         cache::{
-            group_cache::{GroupCache, SubgroupLifecycle},
+            disk_tier::TrackSegments,
+            group_cache::{GroupCache, GroupCursor, SubgroupLifecycle},
             known_ranges::KnownRanges,
+            metrics::CacheMetrics,
+            transit::TransitHistogram,
         },
         types::StreamSubgroupId,
     },
@@ -26,6 +32,11 @@ pub(crate) struct TrackCache {
     known_ranges: RwLock<KnownRanges>,
     live_ingest_count: AtomicUsize,
     eviction_generation: AtomicU64,
+    transit: TransitHistogram,
+    metrics: CacheMetrics,
+    /// Groups spilled to disk; `None` without a disk tier. Their known
+    /// ranges stay in place, so they count as cached for FETCH.
+    segments: Option<TrackSegments>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -37,16 +48,29 @@ pub(crate) enum FetchRangeResolution {
 }
 
 impl TrackCache {
+    #[cfg(test)]
     pub(crate) fn new() -> Self {
+        Self::with_transit_warn_threshold(None)
+    }
+
+    pub(crate) fn with_transit_warn_threshold(transit_warn_threshold: Option<Duration>) -> Self {
         Self {
             stream_groups: RwLock::new(BTreeMap::new()),
             datagram_groups: RwLock::new(BTreeMap::new()),
             known_ranges: RwLock::new(KnownRanges::default()),
             live_ingest_count: AtomicUsize::new(0),
             eviction_generation: AtomicU64::new(0),
+            transit: TransitHistogram::new(transit_warn_threshold),
+            metrics: CacheMetrics::default(),
+            segments: None,
         }
     }
 
+    pub(crate) fn with_segments(mut self, segments: Option<TrackSegments>) -> Self {
+        self.segments = segments;
+        self
+    }
+
     async fn ensure_stream_subgroup(
         &self,
         group_id: u64,
@@ -96,20 +120,26 @@ impl TrackCache {
         subgroup_id: &StreamSubgroupId,
         object_id: Option<u64>,
         object: DataObject,
-    ) {
+    ) -> bool {
         let group = self
             .ensure_stream_subgroup(group_id, subgroup_id, SubgroupLifecycle::NoCloseSignal)
             .await;
-        group.append(object_id, Arc::new(object)).await;
+        let inserted = group.append(object_id, Arc::new(object)).await;
+        if inserted && object_id.is_some() {
+            self.metrics.record_stream_insert();
+        }
+        inserted
     }
 
+    /// Returns false when the object was already cached, e.g. resent by a
+    /// publisher after reconnecting, and was ignored.
     pub(crate) async fn append_live_stream_object(
         &self,
         group_id: u64,
         subgroup_id: &StreamSubgroupId,
         object_id: Option<u64>,
         object: DataObject,
-    ) {
+    ) -> bool {
         let group = self
             .ensure_stream_subgroup(
                 group_id,
@@ -117,8 +147,11 @@ impl TrackCache {
                 SubgroupLifecycle::AwaitingCloseSignal,
             )
             .await;
-        group.append(object_id, Arc::new(object)).await;
+        if !group.append(object_id, Arc::new(object)).await {
+            return false;
+        }
         if let Some(object_id) = object_id {
+            self.metrics.record_stream_insert();
             self.known_ranges.write().await.insert(
                 moqt::Location {
                     group_id,
@@ -130,21 +163,28 @@ impl TrackCache {
                 },
             );
         }
+        true
     }
 
+    /// Returns whether the datagram was cached; one already cached is
+    /// ignored.
     pub(crate) async fn append_datagram_object(
         &self,
         group_id: u64,
         object_id: Option<u64>,
         object: DataObject,
-    ) {
+    ) -> bool {
         // Datagrams have no subgroup header, so a resolved object_id is always expected.
         let Some(object_id) = object_id else {
             tracing::error!(group_id, "unexpected: datagram object without object_id");
-            return;
+            return false;
         };
         let group = self.ensure_datagram_group(group_id).await;
-        group.append(Some(object_id), Arc::new(object)).await;
+        let inserted = group.append(Some(object_id), Arc::new(object)).await;
+        if inserted {
+            self.metrics.record_datagram_insert();
+        }
+        inserted
     }
 
     pub(crate) async fn close_stream_subgroup(
@@ -183,7 +223,7 @@ impl TrackCache {
     }
 
     pub(crate) async fn evict(&self, ttl: Duration) {
-        let mut removed_any = false;
+        let mut removed_any = self.spill_expired_groups(ttl).await;
         // Snapshot group handles so per-group eviction runs without holding the map lock.
         let stream_groups: Vec<(u64, StreamSubgroupId, Arc<GroupCache>)> = {
             let groups = self.stream_groups.read().await;
@@ -200,17 +240,18 @@ impl TrackCache {
         for (group_id, subgroup_id, group) in stream_groups {
             if let Some(evicted_objects) = group.evict_expired_objects(ttl).await {
                 removed_any = true;
+                self.metrics.record_expired(evicted_objects.count);
                 // Coverage is track-level, while eviction reports object IDs per subgroup.
                 // Removing this range may under-claim if another subgroup still has fresh
                 // objects with the same IDs, but it never over-claims deleted knowledge.
                 self.known_ranges.write().await.remove_range(
                     moqt::Location {
                         group_id,
-                        object_id: evicted_objects.start,
+                        object_id: evicted_objects.ids.start,
                     },
                     moqt::Location {
                         group_id,
-                        object_id: evicted_objects.end,
+                        object_id: evicted_objects.ids.end,
                     },
                 );
             }
@@ -239,8 +280,9 @@ impl TrackCache {
         };
         let mut removable_datagrams: Vec<u64> = Vec::new();
         for (group_id, group) in datagram_groups {
-            if group.evict_expired_objects(ttl).await.is_some() {
+            if let Some(evicted_objects) = group.evict_expired_objects(ttl).await {
                 removed_any = true;
+                self.metrics.record_expired(evicted_objects.count);
             }
             if group.is_evictable().await {
                 removable_datagrams.push(group_id);
@@ -258,8 +300,47 @@ impl TrackCache {
         }
     }
 
+    /// Drops `group_id` from memory ahead of its TTL once no subscriber
+    /// needs it, returning the payload bytes freed. A group live ingest may
+    /// still append to is kept, as in quota eviction. Copies already spilled
+    /// to the disk tier age out with its retention.
+    pub(crate) async fn delete_group(&self, group_id: u64) -> usize {
+        let caches = self.group_caches(group_id).await;
+        if caches.is_empty() || caches.iter().any(|cache| cache.is_awaiting_close()) {
+            return 0;
+        }
+        self.remove_group(group_id, false).await
+    }
+
+    /// [`Self::delete_group`] for every group below `group_id`.
+    pub(crate) async fn delete_groups_before(&self, group_id: u64) -> usize {
+        let mut group_ids: Vec<u64> = self
+            .stream_groups
+            .read()
+            .await
+            .range(..group_id)
+            .map(|(&id, _)| id)
+            .collect();
+        group_ids.extend(
+            self.datagram_groups
+                .read()
+                .await
+                .range(..group_id)
+                .map(|(&id, _)| id),
+        );
+        group_ids.sort_unstable();
+        group_ids.dedup();
+        let mut freed = 0;
+        for group_id in group_ids {
+            freed += self.delete_group(group_id).await;
+        }
+        freed
+    }
+
     pub(crate) async fn is_empty(&self) -> bool {
-        self.stream_groups.read().await.is_empty() && self.datagram_groups.read().await.is_empty()
+        self.stream_groups.read().await.is_empty()
+            && self.datagram_groups.read().await.is_empty()
+            && self.segments_empty()
     }
 
     /// Non-blocking `is_empty` for the eviction `remove_if` closure, which is
@@ -279,10 +360,26 @@ impl TrackCache {
                 .try_read()
                 .map(|groups| groups.is_empty())
                 .unwrap_or(false)
+            && self.segments_empty()
+    }
+
+    fn segments_empty(&self) -> bool {
+        self.segments
+            .as_ref()
+            .is_none_or(|segments| segments.is_empty())
     }
 
     /// Returns the Largest Location as defined in the MoQT spec.
     pub(crate) async fn largest_location(&self) -> Option<moqt::Location> {
+        let in_memory = self.largest_location_in_memory().await;
+        let on_disk = self
+            .segments
+            .as_ref()
+            .and_then(|segments| segments.largest_location());
+        in_memory.max(on_disk)
+    }
+
+    async fn largest_location_in_memory(&self) -> Option<moqt::Location> {
         let groups = {
             let groups = self.stream_groups.read().await;
             groups
@@ -329,29 +426,35 @@ impl TrackCache {
         group.header_or_wait().await
     }
 
-    pub(crate) async fn stream_object_from_or_wait(
+    /// A cursor into one stream subgroup from `object_id` on; at its end
+    /// right away when the subgroup is not cached.
+    pub(crate) async fn stream_cursor(
         &self,
         group_id: u64,
         subgroup_id: &StreamSubgroupId,
         object_id: u64,
-    ) -> Option<(u64, Arc<DataObject>)> {
+    ) -> GroupCursor {
         let group = self
             .stream_groups
             .read()
             .await
             .get(&group_id)
             .and_then(|subgroups| subgroups.get(subgroup_id))
-            .cloned()?;
-        group.object_from_or_wait(object_id).await
+            .cloned();
+        GroupCursor::new(group, object_id)
     }
 
-    pub(crate) async fn datagram_object_from_or_wait(
-        &self,
-        group_id: u64,
-        object_id: u64,
-    ) -> Option<(u64, Arc<DataObject>)> {
-        let group = self.datagram_groups.read().await.get(&group_id).cloned()?;
-        group.object_from_or_wait(object_id).await
+    pub(crate) async fn datagram_cursor(&self, group_id: u64, object_id: u64) -> GroupCursor {
+        let group = self.datagram_groups.read().await.get(&group_id).cloned();
+        GroupCursor::new(group, object_id)
+    }
+
+    pub(crate) fn transit(&self) -> &TransitHistogram {
+        &self.transit
+    }
+
+    pub(crate) fn metrics(&self) -> &CacheMetrics {
+        &self.metrics
     }
 
     pub(crate) async fn has_stream_group(&self, group_id: u64) -> bool {
@@ -509,6 +612,9 @@ impl TrackCache {
     }
 
     async fn stream_group_is_closed(&self, group_id: u64) -> bool {
+        if self.is_spilled(group_id) {
+            return true;
+        }
         let caches = self.stream_group_caches(group_id).await;
         !caches.is_empty() && Self::all_subgroups_closed_for_group(&caches).await
     }
@@ -553,6 +659,11 @@ impl TrackCache {
             } else {
                 None
             };
+            if let Some(segments) = &self.segments
+                && segments.has_object_in_range(group_id, start_object_id, end_exclusive)
+            {
+                return true;
+            }
             for cache in caches {
                 if cache
                     .has_object_in_range(start_object_id, end_exclusive)
@@ -592,6 +703,14 @@ impl TrackCache {
                 })
                 .collect()
         };
+        if let Some(segments) = &self.segments {
+            // A spilled group is served from its segment; any entry a later
+            // fetch fill re-created in memory is ignored.
+            let spilled = segments.group_ids(start.group_id, end.group_id);
+            groups_in_range.retain(|(group_id, _)| !spilled.contains(group_id));
+            groups_in_range.extend(spilled.into_iter().map(|group_id| (group_id, Vec::new())));
+            groups_in_range.sort_by_key(|(group_id, _)| *group_id);
+        }
         if matches!(group_order, moqt::GroupOrder::Descending) {
             groups_in_range.reverse();
         }
@@ -609,6 +728,10 @@ impl TrackCache {
         // is checked before each wait.
         let mut fetch_objects = Vec::new();
         for (group_id, caches) in groups_in_range {
+            if self.is_spilled(group_id) {
+                fetch_objects.extend(self.load_spilled_objects(group_id, start, end).await);
+                continue;
+            }
             let known_prefix_end =
                 self.known_ranges
                     .read()
@@ -698,6 +821,104 @@ impl TrackCache {
         fetch_objects
     }
 
+    /// The group's stream objects as FETCH fields in object id order, once
+    /// nothing will be added to it: every subgroup is past live ingest and
+    /// the whole group lies within the known ranges, so absence is final.
+    /// `None` otherwise.
+    pub(crate) async fn complete_group_objects(
+        &self,
+        group_id: u64,
+    ) -> Option<Vec<moqt::FetchObjectField>> {
+        let caches = self.stream_group_caches(group_id).await;
+        if caches.is_empty() || caches.iter().any(|cache| cache.is_awaiting_close()) {
+            return None;
+        }
+        let group_location = moqt::Location {
+            group_id,
+            object_id: 0,
+        };
+        if !self.covers(group_location, group_location).await {
+            return None;
+        }
+
+        let mut objects = Vec::new();
+        for cache in caches {
+            let Some(header) = cache.header().await else {
+                continue;
+            };
+            let DataObject::SubgroupHeader(header) = header.as_ref() else {
+                continue;
+            };
+            let mut next_object_id = 0;
+            while let Some((object_id, object)) = cache.first_object_from(next_object_id).await {
+                Self::push_fetch_object(
+                    &mut objects,
+                    group_id,
+                    header.subgroup_id.resolve(),
+                    header.publisher_priority,
+                    object_id,
+                    object,
+                );
+                let Some(next) = object_id.checked_add(1) else {
+                    break;
+                };
+                next_object_id = next;
+            }
+        }
+        objects.sort_by_key(|object| object.object_id);
+        Some(objects)
+    }
+
+    /// Whether the group's first object (object 0) is in memory, on a
+    /// subgroup stream or as a datagram.
+    pub(crate) async fn has_group_start(&self, group_id: u64) -> bool {
+        let mut caches = self.stream_group_caches(group_id).await;
+        if let Some(datagrams) = self.datagram_groups.read().await.get(&group_id) {
+            caches.push(datagrams.clone());
+        }
+        for cache in caches {
+            if cache
+                .first_object_from(0)
+                .await
+                .is_some_and(|(object_id, _)| object_id == 0)
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether live ingest may still write to any subgroup of the group.
+    pub(crate) async fn is_stream_group_open(&self, group_id: u64) -> bool {
+        self.stream_group_caches(group_id)
+            .await
+            .iter()
+            .any(|cache| cache.is_awaiting_close())
+    }
+
+    /// Stream group ids in memory above `after`, ascending.
+    pub(crate) async fn stream_group_ids_after(&self, after: Option<u64>) -> Vec<u64> {
+        let groups = self.stream_groups.read().await;
+        match after {
+            Some(after) => groups
+                .range(after.saturating_add(1)..)
+                .map(|(&group_id, _)| group_id)
+                .collect(),
+            None => groups.keys().copied().collect(),
+        }
+    }
+
+    /// When the group's oldest cached stream object arrived.
+    pub(crate) async fn stream_group_inserted_at(&self, group_id: u64) -> Option<Instant> {
+        let mut oldest: Option<Instant> = None;
+        for cache in self.stream_group_caches(group_id).await {
+            if let Some(inserted) = cache.oldest_inserted_at().await {
+                oldest = Some(oldest.map_or(inserted, |oldest| oldest.min(inserted)));
+            }
+        }
+        oldest
+    }
+
     fn push_fetch_object(
         group_objects: &mut Vec<moqt::FetchObjectField>,
         group_id: u64,
@@ -853,6 +1074,25 @@ mod tests {
         assert!(!cache.has_stream_group(0).await);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn metrics_count_new_objects_and_expired_ones() {
+        // Arrange: three objects, one of them resent, then all expire
+        let ttl = Duration::from_secs(10);
+        let cache = TrackCache::new();
+        let subgroup = StreamSubgroupId::Value(0);
+        fill_group(&cache, 0, 3).await;
+        cache
+            .append_live_stream_object(0, &subgroup, Some(1), make_object(0))
+            .await;
+        tokio::time::advance(Duration::from_secs(11)).await;
+        // Act
+        cache.evict(ttl).await;
+        let metrics = cache.metrics().take_snapshot();
+        // Assert: the header and the resent copy are not inserts
+        assert_eq!(metrics.stream_inserts, 3);
+        assert_eq!(metrics.expired, 3);
+    }
+
     #[tokio::test(start_paused = true)]
     async fn evict_reclaims_empty_fetch_fill_leftover() {
         // Arrange: a fetch fill wrote only a header before failing; the entry
@@ -1526,6 +1766,26 @@ mod tests {
         objects.iter().map(|o| (o.group_id, o.object_id)).collect()
     }
 
+    #[tokio::test]
+    async fn delete_groups_before_keeps_live_and_newer_groups() {
+        // Arrange: groups 0 and 2 are closed, group 1 is still being ingested
+        let cache = TrackCache::new();
+        let subgroup = StreamSubgroupId::Value(0);
+        for group_id in 0..3 {
+            fill_group_with_ids(&cache, group_id, &[0, 1]).await;
+        }
+        cache.close_stream_subgroup(0, &subgroup).await;
+        cache.close_stream_subgroup(2, &subgroup).await;
+
+        // Act
+        cache.delete_groups_before(2).await;
+
+        // Assert
+        assert!(!cache.has_stream_group(0).await);
+        assert!(cache.has_stream_group(1).await);
+        assert!(cache.has_stream_group(2).await);
+    }
+
     #[tokio::test]
     async fn resolve_fetch_range_returns_not_covered_for_empty_cache() {
         // Arrange: empty cache
@@ -1961,4 +2221,73 @@ mod tests {
             vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]
         );
     }
+    fn spilling_cache(name: &str, retention: Duration) -> (TrackCache, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("relay-{name}-{}", std::process::id()));
+        let disk_tier =
+            crate::modules::relay::cache::disk_tier::DiskTier::open(&dir, retention).unwrap();
+        let track_key = crate::modules::types::TrackKey::new("ns", "track");
+        let cache = TrackCache::new().with_segments(Some(disk_tier.track_segments(&track_key)));
+        (cache, dir)
+    }
+
+    #[tokio::test]
+    async fn evicted_group_is_served_from_disk() {
+        // Arrange
+        let (cache, dir) = spilling_cache("disk-tier-served", Duration::from_secs(300));
+        let subgroup = StreamSubgroupId::Value(0);
+        fill_group_with_ids(&cache, 0, &[0, 1]).await;
+        cache.close_stream_subgroup(0, &subgroup).await;
+        fill_group_with_ids(&cache, 1, &[0]).await;
+        cache.close_stream_subgroup(1, &subgroup).await;
+        let start = moqt::Location {
+            group_id: 0,
+            object_id: 0,
+        };
+        let end = moqt::Location {
+            group_id: 1,
+            object_id: 0,
+        };
+
+        // Act
+        cache.evict_group(0).await;
+        let resolution = cache.resolve_fetch_range(start, end).await;
+        let objects = cache.get_fetch_objects(start, end).await;
+
+        // Assert
+        drop(cache);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(
+            resolution,
+            FetchRangeResolution::Serve { end_location: end }
+        );
+        assert_eq!(object_ids(&objects), vec![(0, 0), (0, 1), (1, 0)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn spilled_group_is_forgotten_after_retention() {
+        // Arrange
+        let ttl = Duration::from_secs(10);
+        let (cache, dir) = spilling_cache("disk-tier-retention", Duration::from_secs(60));
+        let subgroup = StreamSubgroupId::Value(0);
+        fill_group_with_ids(&cache, 0, &[0]).await;
+        cache.close_stream_subgroup(0, &subgroup).await;
+        let group = moqt::Location {
+            group_id: 0,
+            object_id: 0,
+        };
+
+        // Act
+        tokio::time::advance(Duration::from_secs(11)).await;
+        cache.evict(ttl).await;
+        let spilled = !cache.has_stream_group(0).await && cache.covers(group, group).await;
+        tokio::time::advance(Duration::from_secs(61)).await;
+        cache.evict(ttl).await;
+
+        // Assert
+        let forgotten = cache.is_empty().await && !cache.covers(group, group).await;
+        drop(cache);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(spilled);
+        assert!(forgotten);
+    }
 }
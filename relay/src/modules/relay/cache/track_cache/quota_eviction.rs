@@ -0,0 +1,116 @@
+use std::sync::{Arc, atomic::Ordering as AtomicOrdering};
+
+use tokio::time::Instant;
+
+use crate::modules::relay::cache::{group_cache::GroupCache, track_cache::TrackCache};
+
+/// The oldest group of a track that quota enforcement may evict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct QuotaEvictionCandidate {
+    pub(crate) group_id: u64,
+    /// Insertion time of the group's oldest object; `None` for a group that
+    /// holds no objects (it frees no bytes but is still the oldest).
+    pub(crate) oldest_inserted_at: Option<Instant>,
+}
+
+impl TrackCache {
+    /// Payload bytes held by every stream and datagram group of this track.
+    pub(crate) async fn byte_size(&self) -> usize {
+        let mut total = 0;
+        for group in self.all_group_caches().await {
+            total += group.byte_size().await;
+        }
+        total
+    }
+
+    /// The lowest group id whose entries are no longer written by live ingest.
+    /// Groups live ingest may still append to are skipped: dropping them would
+    /// let a late object resurrect a header-less entry.
+    pub(crate) async fn quota_eviction_candidate(&self) -> Option<QuotaEvictionCandidate> {
+        let mut group_ids: Vec<u64> = self.stream_groups.read().await.keys().copied().collect();
+        group_ids.extend(self.datagram_groups.read().await.keys().copied());
+        group_ids.sort_unstable();
+        group_ids.dedup();
+
+        for group_id in group_ids {
+            let caches = self.group_caches(group_id).await;
+            if caches.iter().any(|cache| cache.is_awaiting_close()) {
+                continue;
+            }
+            let mut oldest_inserted_at = None;
+            for cache in &caches {
+                if let Some(inserted) = cache.oldest_inserted_at().await {
+                    oldest_inserted_at = Some(
+                        oldest_inserted_at.map_or(inserted, |oldest: Instant| oldest.min(inserted)),
+                    );
+                }
+            }
+            return Some(QuotaEvictionCandidate {
+                group_id,
+                oldest_inserted_at,
+            });
+        }
+        None
+    }
+
+    /// Drops every stream subgroup and datagram entry of `group_id` and
+    /// returns the payload bytes freed. With a disk tier the group is
+    /// spilled first and stays available to FETCH.
+    pub(crate) async fn evict_group(&self, group_id: u64) -> usize {
+        let spilled = self.spill_group(group_id).await;
+        self.remove_group(group_id, spilled).await
+    }
+
+    /// Removes the group from memory. Its known range is kept only when the
+    /// group's objects are still on disk.
+    pub(super) async fn remove_group(&self, group_id: u64, keep_known_range: bool) -> usize {
+        let stream_subgroups = self.stream_groups.write().await.remove(&group_id);
+        let datagram_group = self.datagram_groups.write().await.remove(&group_id);
+        if stream_subgroups.is_none() && datagram_group.is_none() {
+            return 0;
+        }
+
+        let mut freed = 0;
+        for group in stream_subgroups
+            .into_iter()
+            .flat_map(|subgroups| subgroups.into_values())
+            .chain(datagram_group)
+        {
+            freed += group.byte_size().await;
+        }
+        if !keep_known_range {
+            // `{group, 0}` as an end location covers the whole group.
+            let group_location = moqt::Location {
+                group_id,
+                object_id: 0,
+            };
+            self.known_ranges
+                .write()
+                .await
+                .remove_range(group_location, group_location);
+        }
+        self.eviction_generation
+            .fetch_add(1, AtomicOrdering::Relaxed);
+        freed
+    }
+
+    pub(super) async fn group_caches(&self, group_id: u64) -> Vec<Arc<GroupCache>> {
+        let mut caches = self.stream_group_caches(group_id).await;
+        if let Some(group) = self.datagram_groups.read().await.get(&group_id) {
+            caches.push(group.clone());
+        }
+        caches
+    }
+
+    async fn all_group_caches(&self) -> Vec<Arc<GroupCache>> {
+        let mut caches: Vec<Arc<GroupCache>> = self
+            .stream_groups
+            .read()
+            .await
+            .values()
+            .flat_map(|subgroups| subgroups.values().cloned())
+            .collect();
+        caches.extend(self.datagram_groups.read().await.values().cloned());
+        caches
+    }
+}
@@ -0,0 +1,103 @@
+use std::{sync::atomic::Ordering as AtomicOrdering, time::Duration};
+
+use crate::modules::relay::cache::track_cache::TrackCache;
+
+impl TrackCache {
+    pub(super) fn is_spilled(&self, group_id: u64) -> bool {
+        self.segments
+            .as_ref()
+            .is_some_and(|segments| segments.contains(group_id))
+    }
+
+    /// Writes the group to the disk tier if it is complete (see
+    /// [`TrackCache::complete_group_objects`]). Returns whether the group is
+    /// on disk now.
+    pub(super) async fn spill_group(&self, group_id: u64) -> bool {
+        let Some(segments) = &self.segments else {
+            return false;
+        };
+        let Some(objects) = self.complete_group_objects(group_id).await else {
+            return false;
+        };
+        match segments.write_group(group_id, &objects).await {
+            Ok(()) => {
+                tracing::debug!(group_id, objects = objects.len(), "spilled cache group");
+                true
+            }
+            Err(error) => {
+                tracing::warn!(%error, group_id, "Failed to spill cache group; dropping it");
+                false
+            }
+        }
+    }
+
+    /// Moves groups whose oldest object outlived the TTL to disk as a whole,
+    /// and forgets segments past the disk retention. Returns whether
+    /// anything left the cache.
+    pub(super) async fn spill_expired_groups(&self, ttl: Duration) -> bool {
+        let Some(segments) = &self.segments else {
+            return false;
+        };
+        let mut changed = false;
+        for group_id in segments.expire() {
+            let group_location = moqt::Location {
+                group_id,
+                object_id: 0,
+            };
+            self.known_ranges
+                .write()
+                .await
+                .remove_range(group_location, group_location);
+            changed = true;
+        }
+        if changed {
+            self.eviction_generation
+                .fetch_add(1, AtomicOrdering::Relaxed);
+        }
+
+        let group_ids: Vec<u64> = self.stream_groups.read().await.keys().copied().collect();
+        for group_id in group_ids {
+            let mut expired = false;
+            for cache in self.stream_group_caches(group_id).await {
+                expired |= cache
+                    .oldest_inserted_at()
+                    .await
+                    .is_some_and(|inserted| inserted.elapsed() > ttl);
+            }
+            if expired && self.spill_group(group_id).await {
+                self.remove_group(group_id, true).await;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Reads a spilled group for FETCH, keeping the objects in
+    /// [start, end). A lost segment yields nothing, like an evicted group.
+    pub(super) async fn load_spilled_objects(
+        &self,
+        group_id: u64,
+        start: moqt::Location,
+        end: moqt::Location,
+    ) -> Vec<moqt::FetchObjectField> {
+        let Some(segments) = &self.segments else {
+            return Vec::new();
+        };
+        let objects = match segments.read_group(group_id).await {
+            Ok(objects) => objects.unwrap_or_default(),
+            Err(error) => {
+                tracing::warn!(%error, group_id, "Failed to load spilled cache group");
+                return Vec::new();
+            }
+        };
+        objects
+            .into_iter()
+            .filter(|object| {
+                (group_id != start.group_id || object.object_id >= start.object_id)
+                    && (group_id != end.group_id
+                        || end.object_id == 0
+                        || object.object_id < end.object_id)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::time::Duration;
+
+const DEFAULT_WARN_THRESHOLD_MS: u64 = 100;
+
+/// Upper bounds (inclusive, in milliseconds) of the histogram buckets. One
+/// more bucket after the last bound collects everything slower.
+const BUCKET_BOUNDS_MS: [u64; 10] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000];
+
+/// Reads `RELAY_TRANSIT_WARN_MS` (default 100). `0` disables the warning.
+pub(crate) fn transit_warn_threshold_from_env() -> Option<Duration> {
+    let millis = std::env::var("RELAY_TRANSIT_WARN_MS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_WARN_THRESHOLD_MS);
+    (millis > 0).then(|| Duration::from_millis(millis))
+}
+
+/// Relay transit time of one track: from an object entering the cache to a
+/// live forwarder finishing its send. Counters cover one reporting window and
+/// are reset by [`TransitHistogram::take_snapshot`].
+#[derive(Debug)]
+pub(crate) struct TransitHistogram {
+    warn_threshold: Option<Duration>,
+    buckets: [AtomicU64; BUCKET_BOUNDS_MS.len() + 1],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+    max_micros: AtomicU64,
+    over_threshold: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct TransitSnapshot {
+    pub(crate) buckets: [u64; BUCKET_BOUNDS_MS.len() + 1],
+    pub(crate) count: u64,
+    pub(crate) sum_micros: u64,
+    pub(crate) max_micros: u64,
+    /// Sends slower than the warning threshold.
+    pub(crate) over_threshold: u64,
+}
+
+impl TransitHistogram {
+    pub(crate) fn new(warn_threshold: Option<Duration>) -> Self {
+        Self {
+            warn_threshold,
+            buckets: Default::default(),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+            max_micros: AtomicU64::new(0),
+            over_threshold: AtomicU64::new(0),
+        }
+    }
+
+    /// Records one send. Returns `true` when it took longer than the warning
+    /// threshold, so the caller can log it with its own context.
+    pub(crate) fn record(&self, transit: Duration) -> bool {
+        let micros = u64::try_from(transit.as_micros()).unwrap_or(u64::MAX);
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|&bound_ms| micros <= bound_ms * 1000)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(micros, Ordering::Relaxed);
+        self.max_micros.fetch_max(micros, Ordering::Relaxed);
+
+        let slow = self
+            .warn_threshold
+            .is_some_and(|threshold| transit > threshold);
+        if slow {
+            self.over_threshold.fetch_add(1, Ordering::Relaxed);
+        }
+        slow
+    }
+
+    /// Returns the current window and starts a new one.
+    pub(crate) fn take_snapshot(&self) -> TransitSnapshot {
+        TransitSnapshot {
+            buckets: std::array::from_fn(|i| self.buckets[i].swap(0, Ordering::Relaxed)),
+            count: self.count.swap(0, Ordering::Relaxed),
+            sum_micros: self.sum_micros.swap(0, Ordering::Relaxed),
+            max_micros: self.max_micros.swap(0, Ordering::Relaxed),
+            over_threshold: self.over_threshold.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+impl TransitSnapshot {
+    pub(crate) fn mean_micros(&self) -> u64 {
+        self.sum_micros.checked_div(self.count).unwrap_or(0)
+    }
+
+    /// Upper bound of the bucket holding the `quantile` (0.0..=1.0), in
+    /// milliseconds. Falls back to the observed maximum past the last bucket.
+    pub(crate) fn quantile_upper_bound_ms(&self, quantile: f64) -> u64 {
+        let rank = ((self.count as f64) * quantile).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, &bucket) in self.buckets.iter().enumerate() {
+            seen += bucket;
+            if seen >= rank {
+                return BUCKET_BOUNDS_MS
+                    .get(i)
+                    .copied()
+                    .unwrap_or(self.max_micros.div_ceil(1000));
+            }
+        }
+        self.max_micros.div_ceil(1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_places_samples_in_inclusive_buckets() {
+        // Arrange
+        let histogram = TransitHistogram::new(None);
+        // Act
+        histogram.record(Duration::from_micros(500));
+        histogram.record(Duration::from_millis(1));
+        histogram.record(Duration::from_millis(7));
+        histogram.record(Duration::from_secs(3));
+        let snapshot = histogram.take_snapshot();
+        // Assert
+        assert_eq!(snapshot.buckets[0], 2);
+        assert_eq!(snapshot.buckets[3], 1);
+        assert_eq!(snapshot.buckets[BUCKET_BOUNDS_MS.len()], 1);
+        assert_eq!(snapshot.count, 4);
+        assert_eq!(snapshot.max_micros, 3_000_000);
+    }
+
+    #[test]
+    fn record_reports_sends_over_threshold() {
+        // Arrange
+        let histogram = TransitHistogram::new(Some(Duration::from_millis(50)));
+        // Act
+        let fast = histogram.record(Duration::from_millis(50));
+        let slow = histogram.record(Duration::from_millis(51));
+        // Assert
+        assert!(!fast);
+        assert!(slow);
+        assert_eq!(histogram.take_snapshot().over_threshold, 1);
+    }
+
+    #[test]
+    fn take_snapshot_starts_a_new_window() {
+        // Arrange
+        let histogram = TransitHistogram::new(None);
+        histogram.record(Duration::from_millis(3));
+        // Act
+        histogram.take_snapshot();
+        let next = histogram.take_snapshot();
+        // Assert
+        assert_eq!(next, TransitSnapshot::default());
+    }
+
+    #[test]
+    fn quantile_uses_bucket_upper_bound() {
+        // Arrange
+        let histogram = TransitHistogram::new(None);
+        for _ in 0..98 {
+            histogram.record(Duration::from_micros(800));
+        }
+        histogram.record(Duration::from_millis(40));
+        histogram.record(Duration::from_millis(1500));
+        // Act
+        let snapshot = histogram.take_snapshot();
+        // Assert
+        assert_eq!(snapshot.quantile_upper_bound_ms(0.5), 1);
+        assert_eq!(snapshot.quantile_upper_bound_ms(0.99), 50);
+        assert_eq!(snapshot.quantile_upper_bound_ms(1.0), 1500);
+    }
+}
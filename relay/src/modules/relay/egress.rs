@@ -1,4 +1,9 @@
+pub(crate) mod congestion;
 pub(crate) mod coordinator;
+pub(crate) mod fetch_forwarder;
+pub(crate) mod forwarding;
 pub(crate) mod group_sender;
+pub(crate) mod probe_forwarder;
 pub(crate) mod runner;
 pub(crate) mod scheduler;
+pub(crate) mod send_queue;
@@ -0,0 +1,294 @@
+use tokio::{sync::watch, time::Duration};
+
+use crate::modules::core::{data_object::DataObject, data_sender::DataSender};
+
+pub(crate) enum SendOutcome {
+    Sent,
+    Failed(anyhow::Error),
+    /// The write blocked past the stall threshold while a newer group was
+    /// scheduled; the object may be partly written.
+    Stalled,
+    /// The group was dropped from the subscription's full send queue.
+    Evicted,
+    /// The subscription was stopped; the object may be partly written.
+    Cancelled,
+}
+
+/// What a send task needs to notice that its subscriber cannot keep up, or
+/// no longer wants the track: the stall threshold, the newest group the
+/// subscription has scheduled, the groups its send queue has dropped, and
+/// whether the subscription was stopped.
+#[derive(Clone)]
+pub(crate) struct CongestionWatch {
+    stall_threshold: Option<Duration>,
+    newest_group: watch::Receiver<u64>,
+    /// Groups below this one were evicted from the send queue.
+    evicted_before: watch::Receiver<u64>,
+    stopped: watch::Receiver<bool>,
+}
+
+impl CongestionWatch {
+    pub(crate) fn new(
+        stall_threshold: Option<Duration>,
+        newest_group: watch::Receiver<u64>,
+        evicted_before: watch::Receiver<u64>,
+        stopped: watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            stall_threshold,
+            newest_group,
+            evicted_before,
+            stopped,
+        }
+    }
+
+    /// Resolves once the subscription has been stopped by UNSUBSCRIBE or
+    /// PUBLISH_DONE.
+    pub(crate) async fn stopped(&self) {
+        let mut stopped = self.stopped.clone();
+        if stopped.wait_for(|stopped| *stopped).await.is_err() {
+            // Nobody can stop this sender; it ends with its group.
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Resolves once `group_id` has been evicted from the send queue.
+    pub(crate) async fn evicted(&self, group_id: u64) {
+        let mut evicted_before = self.evicted_before.clone();
+        if evicted_before
+            .wait_for(|before| *before > group_id)
+            .await
+            .is_err()
+        {
+            // The subscription is going away; its sender ends the task.
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Sends `object` of `group_id`, giving up once the write has blocked
+    /// for the stall threshold and a newer group is waiting behind it, once
+    /// the group is evicted, or once the subscription is stopped. A write
+    /// with no newer group waits as long as it takes, since skipping would
+    /// not bring the subscriber any closer to live.
+    pub(crate) async fn send(
+        &self,
+        sender: &mut dyn DataSender,
+        object: DataObject,
+        group_id: u64,
+    ) -> SendOutcome {
+        let mut newest_group = self.newest_group.clone();
+        let stall_threshold = self.stall_threshold;
+        let stalled = async move {
+            let Some(stall_threshold) = stall_threshold else {
+                return std::future::pending::<()>().await;
+            };
+            tokio::time::sleep(stall_threshold).await;
+            if newest_group
+                .wait_for(|newest| *newest > group_id)
+                .await
+                .is_err()
+            {
+                std::future::pending::<()>().await;
+            }
+        };
+        tokio::select! {
+            result = sender.send_object(object) => match result {
+                Ok(()) => SendOutcome::Sent,
+                Err(error) => SendOutcome::Failed(error),
+            },
+            () = stalled => SendOutcome::Stalled,
+            () = self.evicted(group_id) => SendOutcome::Evicted,
+            () = self.stopped() => SendOutcome::Cancelled,
+        }
+    }
+}
+
+/// A subgroup a send task reset because its subscriber fell behind.
+#[derive(Debug)]
+pub(crate) struct SkippedGroup {
+    pub(crate) header: moqt::SubgroupHeader,
+    /// First object the subscriber did not fully receive.
+    pub(crate) next_object_id: u64,
+    /// First object sent on the skipped stream, which a
+    /// `FirstObjectIdDelta` header takes its Subgroup ID from.
+    pub(crate) first_object_id: u64,
+}
+
+impl SkippedGroup {
+    /// The header of the marker stream. A `FirstObjectIdDelta` header
+    /// would take the marker's object id as Subgroup ID, so the skipped
+    /// stream's resolved id is written out instead.
+    pub(crate) fn marker_header(&self) -> moqt::SubgroupHeader {
+        match self.header.subgroup_id {
+            moqt::SubgroupId::FirstObjectIdDelta => {
+                self.header.with_subgroup_id(self.first_object_id)
+            }
+            _ => self.header.clone(),
+        }
+    }
+
+    /// The END_OF_GROUP status that tells the subscriber the group ends
+    /// where its delivery stopped, sent on a fresh stream after
+    /// `marker_header`.
+    pub(crate) fn end_of_group_marker(&self) -> DataObject {
+        DataObject::SubgroupObject(moqt::SubgroupObjectField {
+            message_type: self.marker_header().message_type,
+            object_id_delta: self.next_object_id,
+            extension_headers: moqt::ExtensionHeaders::default(),
+            subgroup_object: moqt::SubgroupObject::new_status(
+                moqt::wire::ObjectStatus::EndOfGroup as u64,
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sender whose writes never complete, like a full send window.
+    struct BlockedSender;
+
+    #[async_trait::async_trait]
+    impl DataSender for BlockedSender {
+        async fn send_object(&mut self, _object: DataObject) -> anyhow::Result<()> {
+            std::future::pending().await
+        }
+    }
+
+    struct OpenSender;
+
+    #[async_trait::async_trait]
+    impl DataSender for OpenSender {
+        async fn send_object(&mut self, _object: DataObject) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn header(group_id: u64) -> moqt::SubgroupHeader {
+        moqt::SubgroupHeader::new(1, group_id, moqt::SubgroupId::Value(0), 0, false, false)
+    }
+
+    fn watch_with_newest(newest: u64) -> (watch::Sender<u64>, CongestionWatch) {
+        let (sender, receiver) = watch::channel(newest);
+        (
+            sender,
+            CongestionWatch::new(
+                Some(Duration::from_millis(100)),
+                receiver,
+                watch::channel(0).1,
+                watch::channel(false).1,
+            ),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn blocked_write_is_given_up_once_a_newer_group_waits() {
+        // Arrange
+        let (_newest, congestion) = watch_with_newest(4);
+
+        // Act
+        let outcome = congestion
+            .send(&mut BlockedSender, DataObject::SubgroupHeader(header(3)), 3)
+            .await;
+
+        // Assert
+        assert!(matches!(outcome, SendOutcome::Stalled));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn blocked_write_of_the_newest_group_keeps_waiting() {
+        // Arrange
+        let (newest, congestion) = watch_with_newest(3);
+
+        // Act
+        let waited = tokio::time::timeout(
+            Duration::from_secs(5),
+            congestion.send(&mut BlockedSender, DataObject::SubgroupHeader(header(3)), 3),
+        )
+        .await;
+
+        // Assert
+        assert!(waited.is_err());
+        drop(newest);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn blocked_write_of_an_evicted_group_is_given_up() {
+        // Arrange
+        let (evicted_before, evicted_receiver) = watch::channel(0);
+        let congestion = CongestionWatch::new(
+            None,
+            watch::channel(0).1,
+            evicted_receiver,
+            watch::channel(false).1,
+        );
+
+        // Act
+        let mut sender = BlockedSender;
+        let send = congestion.send(&mut sender, DataObject::SubgroupHeader(header(3)), 3);
+        evicted_before.send_replace(4);
+        let outcome = send.await;
+
+        // Assert
+        assert!(matches!(outcome, SendOutcome::Evicted));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn blocked_write_of_a_stopped_subscription_is_cancelled() {
+        // Arrange
+        let (stopped, stopped_receiver) = watch::channel(false);
+        let congestion = CongestionWatch::new(
+            None,
+            watch::channel(0).1,
+            watch::channel(0).1,
+            stopped_receiver,
+        );
+
+        // Act
+        let mut sender = BlockedSender;
+        let send = congestion.send(&mut sender, DataObject::SubgroupHeader(header(3)), 3);
+        stopped.send_replace(true);
+        let outcome = send.await;
+
+        // Assert
+        assert!(matches!(outcome, SendOutcome::Cancelled));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn write_that_completes_is_sent() {
+        // Arrange
+        let (_newest, congestion) = watch_with_newest(9);
+
+        // Act
+        let outcome = congestion
+            .send(&mut OpenSender, DataObject::SubgroupHeader(header(3)), 3)
+            .await;
+
+        // Assert
+        assert!(matches!(outcome, SendOutcome::Sent));
+    }
+
+    #[test]
+    fn marker_ends_the_group_at_the_first_undelivered_object() {
+        // Arrange
+        let skipped = SkippedGroup {
+            header: header(3),
+            next_object_id: 7,
+            first_object_id: 0,
+        };
+
+        // Act
+        let marker = skipped.end_of_group_marker();
+
+        // Assert
+        let DataObject::SubgroupObject(field) = marker else {
+            panic!("marker is a subgroup object");
+        };
+        assert_eq!(field.resolve_object_id(None), 7);
+        assert_eq!(
+            field.subgroup_object,
+            moqt::SubgroupObject::new_status(moqt::wire::ObjectStatus::EndOfGroup as u64)
+        );
+    }
+}
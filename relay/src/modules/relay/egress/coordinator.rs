@@ -1,20 +1,31 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use tokio::{
-    sync::{mpsc, oneshot},
+    sync::{mpsc, oneshot, watch},
     task::JoinHandle,
 };
 use tracing::{Instrument, Span};
 
-use crate::modules::{
-    core::subscription::DownstreamSubscription,
-    relay::{
-        cache::{store::TrackCacheStore, track_cache::TrackCache},
-        egress::runner::EgressRunner,
-        notifications::track_notifier::ObjectNotifyProducerMap,
+use crate::{
+    EgressTuning, ForwardingPreference,
+    modules::{
+        billing::{BillingRecord, SubscriptionUsage},
+        core::{publisher::Publisher, subscription::DownstreamSubscription},
+        relay::{
+            cache::{store::TrackCacheStore, track_cache::TrackCache},
+            egress::{
+                fetch_forwarder::FetchStreamForwarder,
+                probe_forwarder::ProbeStreamForwarder,
+                runner::EgressRunner,
+                scheduler::{DeliveryWindow, SchedulerEnd},
+                send_queue::SendQueueLimits,
+            },
+            notifications::track_notifier::ObjectNotifyProducerMap,
+        },
+        session_event::SessionEvent,
+        session_repository::SessionRepository,
+        types::{SessionId, TrackKey},
     },
-    session_repository::SessionRepository,
-    types::{SessionId, TrackKey},
 };
 
 pub(crate) struct EgressStartRequest {
@@ -29,6 +40,8 @@ pub(crate) struct EgressStartRequest {
     /// From LargestLocation of SUBSCRIBE_OK
     /// None means that no content has been delivered yet.
     pub(crate) largest_location: Option<moqt::Location>,
+    /// The `expires` advertised in SUBSCRIBE_OK; `None` never expires.
+    pub(crate) expires: Option<Duration>,
 }
 
 pub(crate) struct EgressFetchRequest {
@@ -40,17 +53,91 @@ pub(crate) struct EgressFetchRequest {
     pub(crate) group_order: moqt::GroupOrder,
 }
 
+pub(crate) struct EgressProbeRequest {
+    pub(crate) subscriber_session_id: SessionId,
+    pub(crate) downstream_subscribe_id: u64,
+    pub(crate) downstream_subscription: DownstreamSubscription,
+    pub(crate) size_bytes: u64,
+}
+
 pub(crate) enum EgressCommand {
     StartReader(Box<EgressStartRequest>),
     StopReader {
         subscriber_session_id: SessionId,
         downstream_subscribe_id: u64,
     },
+    /// Stops the reader because its upstream ended and tells the subscriber
+    /// with PUBLISH_DONE.
+    EndReader {
+        subscriber_session_id: SessionId,
+        downstream_subscribe_id: u64,
+        status_code: u64,
+        reason_phrase: String,
+    },
     StartFetch(EgressFetchRequest),
+    StopFetch {
+        subscriber_session_id: SessionId,
+        request_id: u64,
+    },
+    StartProbe(EgressProbeRequest),
+    /// Applies a SUBSCRIBE_UPDATE to a running reader.
+    UpdateReader {
+        subscriber_session_id: SessionId,
+        downstream_subscribe_id: u64,
+        delivery_window: DeliveryWindow,
+    },
+}
+
+/// How long a stopped runner gets to reset its open streams before it is
+/// aborted.
+const STOP_GRACE: Duration = Duration::from_secs(1);
+
+/// How long `EgressCoordinator::shutdown` waits for the command loop to stop
+/// its runners before the loop is aborted.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+struct EgressRunnerHandle {
+    join_handle: JoinHandle<()>,
+    usage: Arc<SubscriptionUsage>,
+    delivery_window: watch::Sender<DeliveryWindow>,
+    stop: watch::Sender<bool>,
+    /// The subscriber session's publisher, to give `track_alias` back once
+    /// the runner has stopped sending on it.
+    alias_owner: Arc<dyn Publisher>,
+    track_alias: u64,
+}
+
+impl EgressRunnerHandle {
+    /// Stops the runner and reports what it delivered. The runner resets
+    /// its open subgroup streams with CANCELLED, so the subscriber does not
+    /// mistake a cut-off subgroup for a complete one; a runner stuck past
+    /// `STOP_GRACE` is aborted. The track alias is released only after
+    /// that, so it is not recycled while objects still go out under it.
+    fn stop(self, billing_sender: &mpsc::UnboundedSender<BillingRecord>) {
+        self.stop.send_replace(true);
+        let mut join_handle = self.join_handle;
+        let alias_owner = self.alias_owner;
+        let track_alias = self.track_alias;
+        tokio::spawn(async move {
+            if tokio::time::timeout(STOP_GRACE, &mut join_handle)
+                .await
+                .is_err()
+            {
+                tracing::debug!("egress runner did not stop in time; aborting");
+                join_handle.abort();
+                let _ = join_handle.await;
+            }
+            alias_owner.release_track_alias(track_alias);
+        });
+        if billing_sender.send(self.usage.to_record()).is_err() {
+            tracing::debug!("billing exporter is gone; dropping billing record");
+        }
+    }
 }
 
 pub(crate) struct EgressCoordinator {
     command_sender: mpsc::Sender<EgressCommand>,
+    shutdown_sender: watch::Sender<bool>,
     command_runner: tokio::task::JoinHandle<()>,
 }
 
@@ -59,13 +146,23 @@ impl EgressCoordinator {
         session_repo: Arc<tokio::sync::Mutex<SessionRepository>>,
         cache_store: Arc<TrackCacheStore>,
         object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
+        billing_sender: mpsc::UnboundedSender<BillingRecord>,
+        session_event_sender: mpsc::UnboundedSender<SessionEvent>,
+        tuning: EgressTuning,
     ) -> Self {
         let (command_sender, mut command_receiver) = mpsc::channel::<EgressCommand>(512);
+        let (shutdown_sender, mut shutdown_receiver) = watch::channel(false);
 
         let command_runner = tokio::spawn(async move {
-            let mut runners = HashMap::<(SessionId, u64), JoinHandle<()>>::new();
+            let mut runners = HashMap::<(SessionId, u64), EgressRunnerHandle>::new();
+            let mut fetch_runners = HashMap::<(SessionId, u64), JoinHandle<()>>::new();
+            let mut probe_runners = HashMap::<(SessionId, u64), JoinHandle<()>>::new();
             loop {
-                let Some(command) = command_receiver.recv().await else {
+                let command = tokio::select! {
+                    command = command_receiver.recv() => command,
+                    _ = shutdown_receiver.wait_for(|shutdown| *shutdown) => None,
+                };
+                let Some(command) = command else {
                     break;
                 };
                 match command {
@@ -76,12 +173,14 @@ impl EgressCoordinator {
                             request.downstream_subscribe_id,
                         );
                         if let Some(existing) = runners.remove(&runner_key) {
-                            existing.abort();
+                            existing.stop(&billing_sender);
                         }
                         if let Some(handle) = Self::spawn_runner(
                             session_repo.clone(),
                             cache_store.clone(),
                             object_notify_producer_map.clone(),
+                            tuning,
+                            session_event_sender.clone(),
                             request,
                         )
                         .await
@@ -93,25 +192,102 @@ impl EgressCoordinator {
                         subscriber_session_id,
                         downstream_subscribe_id,
                     } => {
+                        let runner_key = (subscriber_session_id, downstream_subscribe_id);
+                        if let Some(handle) = runners.remove(&runner_key) {
+                            handle.stop(&billing_sender);
+                        }
+                        if let Some(handle) = probe_runners.remove(&runner_key) {
+                            handle.abort();
+                        }
+                    }
+                    EgressCommand::EndReader {
+                        subscriber_session_id,
+                        downstream_subscribe_id,
+                        status_code,
+                        reason_phrase,
+                    } => {
+                        let runner_key = (subscriber_session_id, downstream_subscribe_id);
+                        let stream_count = match runners.remove(&runner_key) {
+                            Some(handle) => {
+                                let stream_count = handle.usage.stream_count();
+                                handle.stop(&billing_sender);
+                                stream_count
+                            }
+                            None => 0,
+                        };
+                        Self::spawn_publish_done(
+                            session_repo.clone(),
+                            subscriber_session_id,
+                            downstream_subscribe_id,
+                            status_code,
+                            stream_count,
+                            reason_phrase,
+                        )
+                        .await;
+                    }
+                    EgressCommand::UpdateReader {
+                        subscriber_session_id,
+                        downstream_subscribe_id,
+                        delivery_window,
+                    } => match runners.get(&(subscriber_session_id, downstream_subscribe_id)) {
+                        Some(handle) => {
+                            handle.delivery_window.send_replace(delivery_window);
+                        }
+                        None => {
+                            tracing::warn!(
+                                subscriber_session_id = %subscriber_session_id,
+                                downstream_subscribe_id,
+                                "no egress runner to update"
+                            );
+                        }
+                    },
+                    EgressCommand::StartFetch(request) => {
+                        // Fetch deliveries end on their own; drop the finished
+                        // ones so the map only holds cancellable deliveries.
+                        fetch_runners.retain(|_, handle| !handle.is_finished());
+                        let runner_key = (request.subscriber_session_id, request.request_id);
                         if let Some(handle) =
-                            runners.remove(&(subscriber_session_id, downstream_subscribe_id))
+                            Self::spawn_fetch_delivery(session_repo.clone(), request).await
+                        {
+                            fetch_runners.insert(runner_key, handle);
+                        }
+                    }
+                    EgressCommand::StopFetch {
+                        subscriber_session_id,
+                        request_id,
+                    } => {
+                        if let Some(handle) =
+                            fetch_runners.remove(&(subscriber_session_id, request_id))
                         {
                             handle.abort();
                         }
                     }
-                    EgressCommand::StartFetch(request) => {
-                        Self::spawn_fetch_delivery(session_repo.clone(), request).await;
+                    EgressCommand::StartProbe(request) => {
+                        probe_runners.retain(|_, handle| !handle.is_finished());
+                        let runner_key = (
+                            request.subscriber_session_id,
+                            request.downstream_subscribe_id,
+                        );
+                        if let Some(handle) =
+                            Self::spawn_probe_delivery(session_repo.clone(), request).await
+                        {
+                            probe_runners.insert(runner_key, handle);
+                        }
                     }
                 }
             }
 
             for (_, handle) in runners {
+                handle.stop(&billing_sender);
+            }
+            for (_, handle) in fetch_runners.into_iter().chain(probe_runners) {
                 handle.abort();
             }
         });
 
         Self {
             command_sender,
+            shutdown_sender,
             command_runner,
         }
     }
@@ -120,10 +296,70 @@ impl EgressCoordinator {
         self.command_sender.clone()
     }
 
+    /// Stops every live runner, which hands its final billing record to the
+    /// exporter, and waits for the command loop to end so its billing sender
+    /// is dropped. The loop is aborted if it is still running after
+    /// `SHUTDOWN_GRACE`.
+    pub(crate) async fn shutdown(&mut self) {
+        self.shutdown_sender.send_replace(true);
+        if tokio::time::timeout(SHUTDOWN_GRACE, &mut self.command_runner)
+            .await
+            .is_err()
+        {
+            tracing::warn!("egress command loop did not stop in time; aborting");
+            self.command_runner.abort();
+        }
+    }
+
+    /// Sends PUBLISH_DONE off the command loop so a slow control stream does
+    /// not hold up other egress commands.
+    async fn spawn_publish_done(
+        session_repo: Arc<tokio::sync::Mutex<SessionRepository>>,
+        subscriber_session_id: SessionId,
+        downstream_subscribe_id: u64,
+        status_code: u64,
+        stream_count: u64,
+        reason_phrase: String,
+    ) {
+        let publisher = session_repo.lock().await.publisher(subscriber_session_id);
+        let Some(publisher) = publisher else {
+            tracing::debug!(
+                subscriber_session_id = %subscriber_session_id,
+                "subscriber session gone before PUBLISH_DONE"
+            );
+            return;
+        };
+        tokio::spawn(async move {
+            match publisher
+                .send_publish_done(
+                    downstream_subscribe_id,
+                    status_code,
+                    stream_count,
+                    reason_phrase,
+                )
+                .await
+            {
+                Ok(()) => tracing::info!(
+                    subscriber_session_id = %subscriber_session_id,
+                    downstream_subscribe_id,
+                    status_code,
+                    stream_count,
+                    "sent PUBLISH_DONE"
+                ),
+                Err(error) => tracing::warn!(
+                    ?error,
+                    subscriber_session_id = %subscriber_session_id,
+                    downstream_subscribe_id,
+                    "failed to send PUBLISH_DONE"
+                ),
+            }
+        });
+    }
+
     async fn spawn_fetch_delivery(
         session_repo: Arc<tokio::sync::Mutex<SessionRepository>>,
         request: EgressFetchRequest,
-    ) {
+    ) -> Option<JoinHandle<()>> {
         // publisher = relay's outbound handle to the session that issued FETCH
         let publisher = session_repo
             .lock()
@@ -131,48 +367,83 @@ impl EgressCoordinator {
             .publisher(request.subscriber_session_id);
         let Some(publisher) = publisher else {
             tracing::error!("session not found for fetch");
-            return;
+            return None;
         };
 
-        let cache = request.cache.clone();
+        let fetch_span = tracing::info_span!(
+            "relay.dataplane.egress.fetch",
+            subscriber_session_id = %request.subscriber_session_id,
+            request_id = request.request_id,
+        );
+        let forwarder = FetchStreamForwarder::new(request, publisher);
 
-        tokio::spawn(async move {
-            let sender = match publisher.new_fetch_sender(request.request_id).await {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::error!(?e, "failed to create fetch sender");
-                    return;
-                }
-            };
-            let objects = cache
-                .get_fetch_objects_with_group_order(
-                    request.start_location,
-                    request.end_location,
-                    request.group_order,
-                )
-                .await;
-            for object in objects {
-                if let Err(e) = sender.send(object).await {
-                    tracing::error!(?e, "failed to send fetch object");
-                    return;
+        Some(tokio::spawn(
+            async move {
+                if let Err(e) = forwarder.run().await {
+                    tracing::error!(?e, "fetch forwarder finished with error");
                 }
             }
-            if let Err(e) = sender.close().await {
-                tracing::error!(?e, "failed to close fetch stream");
-            }
-        });
+            .instrument(fetch_span),
+        ))
     }
 
-    async fn spawn_runner(
+    async fn spawn_probe_delivery(
         session_repo: Arc<tokio::sync::Mutex<SessionRepository>>,
-        cache_store: Arc<TrackCacheStore>,
-        object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
-        request: EgressStartRequest,
+        request: EgressProbeRequest,
     ) -> Option<JoinHandle<()>> {
         let publisher = session_repo
             .lock()
             .await
             .publisher(request.subscriber_session_id);
+        let Some(publisher) = publisher else {
+            tracing::error!("session not found for bandwidth probe");
+            return None;
+        };
+
+        let probe_span = tracing::info_span!(
+            "relay.dataplane.egress.probe",
+            subscriber_session_id = %request.subscriber_session_id,
+            downstream_subscribe_id = request.downstream_subscribe_id,
+            size_bytes = request.size_bytes,
+        );
+        let forwarder = ProbeStreamForwarder::new(request, publisher);
+
+        Some(tokio::spawn(
+            async move {
+                if let Err(e) = forwarder.run().await {
+                    tracing::warn!(?e, "bandwidth probe finished with error");
+                }
+            }
+            .instrument(probe_span),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn spawn_runner(
+        session_repo: Arc<tokio::sync::Mutex<SessionRepository>>,
+        cache_store: Arc<TrackCacheStore>,
+        object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
+        tuning: EgressTuning,
+        session_event_sender: mpsc::UnboundedSender<SessionEvent>,
+        request: EgressStartRequest,
+    ) -> Option<EgressRunnerHandle> {
+        let (publisher, forwarding, keyframe_start, activity) = {
+            let session_repo = session_repo.lock().await;
+            // Relay peers get tracks as published, from the draft's start,
+            // so cascades never convert or replay.
+            let is_client = session_repo.is_client_session(request.subscriber_session_id);
+            let forwarding = if is_client {
+                tuning.client_forwarding
+            } else {
+                ForwardingPreference::AsPublished
+            };
+            (
+                session_repo.publisher(request.subscriber_session_id),
+                forwarding,
+                is_client && tuning.client_keyframe_start,
+                session_repo.activity(request.subscriber_session_id),
+            )
+        };
         let Some(publisher) = publisher else {
             tracing::error!("subscriber session not found for egress start");
             let _ = request
@@ -180,6 +451,8 @@ impl EgressCoordinator {
                 .send(Err(anyhow::anyhow!("subscriber session not found")));
             return None;
         };
+        // Shared with the handle, which releases the alias after the runner.
+        let publisher: Arc<dyn Publisher> = Arc::from(publisher);
 
         let cache = cache_store.get_or_create(&request.track_key);
         let latest_info_sender = object_notify_producer_map.get_or_create(&request.track_key);
@@ -195,24 +468,105 @@ impl EgressCoordinator {
             track_name = %request.track_name,
         );
 
+        let usage = Arc::new(
+            SubscriptionUsage::new(
+                request.subscriber_session_id,
+                request.downstream_subscribe_id,
+                request.track_namespace,
+                request.track_name,
+            )
+            .with_activity(activity),
+        );
+        let (delivery_window, delivery_window_receiver) = watch::channel(
+            DeliveryWindow::for_filter(&request.downstream_subscription.filter_type()),
+        );
+        let (stop, stop_receiver) = watch::channel(false);
+        let subscriber_session_id = request.subscriber_session_id;
+        let downstream_subscribe_id = request.downstream_subscribe_id;
+        let expires = request.expires;
         let runner = EgressRunner::new(
             request.track_key,
             cache,
             latest_info_sender,
-            publisher,
+            publisher.clone(),
             request.downstream_subscription.clone(),
             request.ready_sender,
             request.largest_location,
-        );
+            usage.clone(),
+            delivery_window_receiver,
+            forwarding,
+            tuning.stall_threshold,
+            SendQueueLimits {
+                max_groups: tuning.queue_groups,
+                overflow: tuning.queue_overflow,
+            },
+            keyframe_start,
+        )
+        .with_stop_signal(stop_receiver);
 
-        Some(tokio::spawn(
+        let join_handle = tokio::spawn(
             async move {
-                if let Err(e) = runner.run().await {
-                    tracing::error!(?e, "egress runner finished with error");
+                let run = runner.run();
+                tokio::pin!(run);
+                // On expiry the control plane ends the subscription like an
+                // UNSUBSCRIBE, so the runner is stopped and resets its
+                // streams rather than being dropped mid-send.
+                let end = match expires {
+                    Some(expires) => tokio::select! {
+                        end = &mut run => end,
+                        _ = tokio::time::sleep(expires) => {
+                            tracing::info!(expires_ms = expires.as_millis() as u64, "subscription expired");
+                            let event = SessionEvent::SubscriptionExpired(
+                                subscriber_session_id,
+                                downstream_subscribe_id,
+                            );
+                            if session_event_sender.send(event).is_err() {
+                                tracing::warn!("session event channel closed before subscription expiry");
+                            }
+                            run.await
+                        }
+                    },
+                    None => run.await,
+                };
+                match end {
+                    // The control plane ends the subscription like an
+                    // UNSUBSCRIBE, then answers with PUBLISH_DONE.
+                    Ok(SchedulerEnd::RangeComplete { end_group }) => {
+                        tracing::info!(end_group, "subscription range delivered");
+                        let event = SessionEvent::SubscriptionRangeComplete(
+                            subscriber_session_id,
+                            downstream_subscribe_id,
+                            end_group,
+                        );
+                        if session_event_sender.send(event).is_err() {
+                            tracing::warn!("session event channel closed before range completion");
+                        }
+                    }
+                    Ok(SchedulerEnd::TooFarBehind) => {
+                        let event = SessionEvent::SubscriberTooFarBehind(
+                            subscriber_session_id,
+                            downstream_subscribe_id,
+                        );
+                        if session_event_sender.send(event).is_err() {
+                            tracing::warn!(
+                                "session event channel closed before ending a lagging subscription"
+                            );
+                        }
+                    }
+                    Ok(SchedulerEnd::TrackClosed | SchedulerEnd::Unsubscribed) => {}
+                    Err(e) => tracing::error!(?e, "egress runner finished with error"),
                 }
             }
             .instrument(egress_track_span),
-        ))
+        );
+        Some(EgressRunnerHandle {
+            join_handle,
+            usage,
+            delivery_window,
+            stop,
+            alias_owner: publisher,
+            track_alias,
+        })
     }
 }
 
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+
+use crate::modules::{core::publisher::Publisher, relay::cache::track_cache::TrackCache};
+
+use super::coordinator::EgressFetchRequest;
+
+/// Serves one FETCH from the track cache on a single FETCH_HEADER stream.
+///
+/// The objects in [start_location, end_location) are read in the requested
+/// group order and written to a new unidirectional stream; the stream is
+/// closed once the range is exhausted. Groups spilled to the disk tier are
+/// loaded from their segments on the way.
+pub(crate) struct FetchStreamForwarder {
+    request_id: u64,
+    cache: Arc<TrackCache>,
+    publisher: Box<dyn Publisher>,
+    start_location: moqt::Location,
+    end_location: moqt::Location,
+    group_order: moqt::GroupOrder,
+}
+
+impl FetchStreamForwarder {
+    pub(crate) fn new(request: EgressFetchRequest, publisher: Box<dyn Publisher>) -> Self {
+        Self {
+            request_id: request.request_id,
+            cache: request.cache,
+            publisher,
+            start_location: request.start_location,
+            end_location: request.end_location,
+            group_order: request.group_order,
+        }
+    }
+
+    pub(crate) async fn run(self) -> anyhow::Result<()> {
+        let sender = self
+            .publisher
+            .new_fetch_sender(self.request_id)
+            .await
+            .context("failed to create fetch sender")?;
+        let objects = self
+            .cache
+            .get_fetch_objects_with_group_order(
+                self.start_location,
+                self.end_location,
+                self.group_order,
+            )
+            .await;
+        tracing::debug!(objects = objects.len(), "forwarding cached fetch range");
+        for object in objects {
+            sender
+                .send(object)
+                .await
+                .context("failed to send fetch object")?;
+        }
+        sender
+            .close()
+            .await
+            .context("failed to close fetch stream")?;
+        Ok(())
+    }
+}
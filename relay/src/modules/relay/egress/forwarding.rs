@@ -0,0 +1,233 @@
+use crate::modules::core::data_object::DataObject;
+
+/// Header of the subgroup stream carrying a datagram group. Datagrams map to
+/// subgroup 0; extensions are always allowed since later datagrams may carry
+/// them.
+pub(crate) fn subgroup_header_for_datagrams(
+    track_alias: u64,
+    group_id: u64,
+    first: &moqt::ObjectDatagram,
+) -> moqt::SubgroupHeader {
+    moqt::SubgroupHeader::new(
+        track_alias,
+        group_id,
+        moqt::SubgroupId::None,
+        first.field.publisher_priority(),
+        true,
+        false,
+    )
+}
+
+/// Re-encodes a datagram as the next object on the stream opened with
+/// `header`. `prev_object_id` is the last object sent on that stream.
+pub(crate) fn datagram_to_subgroup_object(
+    header: &moqt::SubgroupHeader,
+    datagram: &moqt::ObjectDatagram,
+    prev_object_id: Option<u64>,
+    object_id: u64,
+) -> DataObject {
+    let subgroup_object = match datagram.field.payload() {
+        moqt::ObjectDatagramPayload::Payload(payload) => moqt::SubgroupObject::new_payload(payload),
+        moqt::ObjectDatagramPayload::Status(status) => {
+            moqt::SubgroupObject::new_status(u8::from(status) as u64)
+        }
+    };
+    let object_id_delta = match prev_object_id {
+        Some(prev_object_id) => object_id - prev_object_id - 1,
+        None => object_id,
+    };
+    DataObject::SubgroupObject(moqt::SubgroupObjectField {
+        message_type: header.message_type,
+        object_id_delta,
+        extension_headers: datagram
+            .field
+            .extension_headers()
+            .cloned()
+            .unwrap_or_default(),
+        subgroup_object,
+    })
+}
+
+/// Re-encodes a subgroup stream object as a datagram. Returns `None` for
+/// status codes a datagram cannot carry.
+pub(crate) fn subgroup_object_to_datagram(
+    track_alias: u64,
+    header: &moqt::SubgroupHeader,
+    field: &moqt::SubgroupObjectField,
+    object_id: u64,
+) -> Option<DataObject> {
+    let publisher_priority = header.publisher_priority;
+    let has_extensions = !field.extension_headers.key_value_pairs.is_empty();
+    let datagram_field = match &field.subgroup_object {
+        moqt::SubgroupObject::Payload { data, .. } if has_extensions => {
+            moqt::DatagramField::Payload0x01 {
+                object_id,
+                publisher_priority,
+                extension_headers: field.extension_headers.clone(),
+                payload: data.clone(),
+            }
+        }
+        moqt::SubgroupObject::Payload { data, .. } => moqt::DatagramField::Payload0x00 {
+            object_id,
+            publisher_priority,
+            payload: data.clone(),
+        },
+        moqt::SubgroupObject::Status { code, .. } => {
+            let status = u8::try_from(*code)
+                .ok()
+                .and_then(|code| moqt::ObjectStatus::try_from(code).ok())?;
+            if has_extensions {
+                moqt::DatagramField::Status0x21 {
+                    object_id,
+                    publisher_priority,
+                    extension_headers: field.extension_headers.clone(),
+                    status,
+                }
+            } else {
+                moqt::DatagramField::Status0x20 {
+                    object_id,
+                    publisher_priority,
+                    status,
+                }
+            }
+        }
+    };
+    Some(DataObject::ObjectDatagram(moqt::ObjectDatagram::new(
+        track_alias,
+        header.group_id,
+        datagram_field,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn datagram(object_id: u64, payload: &'static [u8]) -> moqt::ObjectDatagram {
+        moqt::ObjectDatagram::new(
+            3,
+            7,
+            moqt::DatagramField::Payload0x00 {
+                object_id,
+                publisher_priority: 5,
+                payload: Bytes::from_static(payload),
+            },
+        )
+    }
+
+    #[test]
+    fn datagrams_become_subgroup_objects_with_id_deltas() {
+        // Arrange: datagrams 0 and 3 of group 7, 1 and 2 were lost
+        let first = datagram(0, b"a");
+        let header = subgroup_header_for_datagrams(9, 7, &first);
+
+        // Act
+        let first_object = datagram_to_subgroup_object(&header, &first, None, 0);
+        let next_object = datagram_to_subgroup_object(&header, &datagram(3, b"b"), Some(0), 3);
+
+        // Assert
+        assert_eq!(header.group_id, 7);
+        assert_eq!(header.publisher_priority, 5);
+        let DataObject::SubgroupObject(first_field) = first_object else {
+            panic!("expected a subgroup object");
+        };
+        let DataObject::SubgroupObject(next_field) = next_object else {
+            panic!("expected a subgroup object");
+        };
+        assert_eq!(first_field.resolve_object_id(None), 0);
+        assert_eq!(next_field.resolve_object_id(Some(0)), 3);
+        assert_eq!(
+            next_field.subgroup_object,
+            moqt::SubgroupObject::new_payload(Bytes::from_static(b"b"))
+        );
+    }
+
+    #[test]
+    fn subgroup_objects_become_datagrams_on_the_downstream_alias() {
+        // Arrange
+        let header = moqt::SubgroupHeader::new(1, 4, moqt::SubgroupId::Value(2), 9, false, false);
+        let field = moqt::SubgroupObjectField {
+            message_type: header.message_type,
+            object_id_delta: 0,
+            extension_headers: moqt::ExtensionHeaders::default(),
+            subgroup_object: moqt::SubgroupObject::new_payload(Bytes::from_static(b"x")),
+        };
+
+        // Act
+        let converted = subgroup_object_to_datagram(8, &header, &field, 6).unwrap();
+
+        // Assert
+        let DataObject::ObjectDatagram(datagram) = converted else {
+            panic!("expected a datagram");
+        };
+        assert_eq!(datagram.track_alias, 8);
+        assert_eq!(datagram.group_id, 4);
+        assert_eq!(datagram.field.object_id(), Some(6));
+        assert_eq!(datagram.field.publisher_priority(), 9);
+    }
+
+    #[test]
+    fn extension_headers_survive_both_conversions() {
+        // Arrange: a LOC capture timestamp plus a header the relay does not know
+        let extension_headers = moqt::ExtensionHeaders::new(vec![
+            moqt::KeyValuePair {
+                key: 2,
+                value: moqt::VariantType::Even(1_700_000_000),
+            },
+            moqt::KeyValuePair {
+                key: 0x3b,
+                value: moqt::VariantType::Odd(Bytes::from_static(&[0xde, 0xad])),
+            },
+        ]);
+        let datagram = moqt::ObjectDatagram::new(
+            3,
+            7,
+            moqt::DatagramField::Payload0x01 {
+                object_id: 0,
+                publisher_priority: 5,
+                extension_headers: extension_headers.clone(),
+                payload: Bytes::from_static(b"a"),
+            },
+        );
+        let header = subgroup_header_for_datagrams(9, 7, &datagram);
+
+        // Act
+        let DataObject::SubgroupObject(field) =
+            datagram_to_subgroup_object(&header, &datagram, None, 0)
+        else {
+            panic!("expected a subgroup object");
+        };
+        let DataObject::ObjectDatagram(round_trip) =
+            subgroup_object_to_datagram(3, &header, &field, 0).unwrap()
+        else {
+            panic!("expected a datagram");
+        };
+
+        // Assert
+        assert_eq!(field.extension_headers.encode(), extension_headers.encode());
+        assert_eq!(
+            round_trip.field.extension_headers().unwrap().encode(),
+            extension_headers.encode()
+        );
+    }
+
+    #[test]
+    fn unknown_status_codes_are_not_converted() {
+        // Arrange
+        let header = moqt::SubgroupHeader::new(1, 4, moqt::SubgroupId::None, 0, false, false);
+        let field = moqt::SubgroupObjectField {
+            message_type: header.message_type,
+            object_id_delta: 0,
+            extension_headers: moqt::ExtensionHeaders::default(),
+            subgroup_object: moqt::SubgroupObject::new_status(0x99),
+        };
+
+        // Act
+        let converted = subgroup_object_to_datagram(8, &header, &field, 0);
+
+        // Assert
+        assert!(converted.is_none());
+    }
+}
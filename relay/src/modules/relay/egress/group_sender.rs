@@ -1,36 +1,83 @@
-use std::sync::Arc;
+mod datagram_send;
+mod datagrams_as_stream;
+mod dispatch;
+mod metrics;
+mod stream_end;
+mod stream_options;
+mod stream_send;
 
-use tokio::{sync::mpsc, task::JoinSet};
-use tracing::{Instrument, Span};
+use std::{sync::Arc, time::Duration};
 
+use crate::ForwardingPreference;
 use crate::modules::{
+    billing::SubscriptionUsage,
     core::{
-        data_sender::{DataSender, stream_sender_factory::StreamSenderFactory},
-        publisher::Publisher,
+        data_sender::stream_sender_factory::StreamSenderFactory, publisher::Publisher,
         subscription::DownstreamSubscription,
     },
-    relay::{cache::track_cache::TrackCache, types::StreamSubgroupId},
+    relay::cache::track_cache::TrackCache,
     types::TrackKey,
 };
+use tokio::{
+    sync::{mpsc, watch},
+    task::JoinSet,
+};
 
-use super::scheduler::GroupSendTask;
+use super::{
+    congestion::{CongestionWatch, SkippedGroup},
+    scheduler::{DeliveryWindow, GroupSendTask},
+    send_queue::{Admission, SendQueue, SendQueueLimits},
+};
+use stream_options::StreamOptions;
 
 /// Receives `GroupSendTask` entries and spawns per-group send tasks.
 pub(crate) struct GroupSender {
     track_key: TrackKey,
     cache: Arc<TrackCache>,
-    publisher: Box<dyn Publisher>,
+    publisher: Arc<dyn Publisher>,
     downstream_subscription: DownstreamSubscription,
     receiver: mpsc::Receiver<GroupSendTask>,
+    usage: Arc<SubscriptionUsage>,
+    forwarding: ForwardingPreference,
+    delivery_window: watch::Receiver<DeliveryWindow>,
+    stall_threshold: Option<Duration>,
+    queue_limits: SendQueueLimits,
+    /// Newest group scheduled so far, which stalled older streams yield to.
+    newest_group: watch::Sender<u64>,
+    /// Groups below this one were evicted from the send queue.
+    evicted_before: watch::Sender<u64>,
+    /// Set when the subscription is stopped; open streams are reset.
+    stopped: watch::Receiver<bool>,
 }
 
+/// How [`GroupSender::run`] ended.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum SenderEnd {
+    /// The scheduler stopped and every send finished.
+    Drained,
+    /// The send queue overflowed under the disconnect policy.
+    QueueOverflowed,
+    /// The subscription was stopped and every open stream reset.
+    Stopped,
+}
+
+/// Send tasks of one subscription; a stream task that gave up its group
+/// returns it for an END_OF_GROUP marker.
+type SendTasks = JoinSet<Option<SkippedGroup>>;
+
 impl GroupSender {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         track_key: TrackKey,
         cache: Arc<TrackCache>,
-        publisher: Box<dyn Publisher>,
+        publisher: Arc<dyn Publisher>,
         downstream_subscription: DownstreamSubscription,
         receiver: mpsc::Receiver<GroupSendTask>,
+        usage: Arc<SubscriptionUsage>,
+        forwarding: ForwardingPreference,
+        delivery_window: watch::Receiver<DeliveryWindow>,
+        stall_threshold: Option<Duration>,
+        queue_limits: SendQueueLimits,
     ) -> Self {
         Self {
             track_key,
@@ -38,197 +85,149 @@ impl GroupSender {
             publisher,
             downstream_subscription,
             receiver,
+            usage,
+            forwarding,
+            delivery_window,
+            stall_threshold,
+            queue_limits,
+            newest_group: watch::channel(0).0,
+            evicted_before: watch::channel(0).0,
+            stopped: watch::channel(false).1,
         }
     }
 
-    pub(crate) async fn run(mut self) {
+    /// Stops sending once `stopped` turns true: no new group starts, and
+    /// every stream still open is reset with CANCELLED.
+    pub(crate) fn with_stop_signal(mut self, stopped: watch::Receiver<bool>) -> Self {
+        self.stopped = stopped;
+        self
+    }
+
+    /// Options for tasks started now. Priority and delivery timeout are the
+    /// latest SUBSCRIBE_UPDATE's, else the ones the subscription started with.
+    fn stream_options(&self) -> StreamOptions {
+        let window = *self.delivery_window.borrow();
+        StreamOptions {
+            subscriber_priority: window
+                .subscriber_priority
+                .unwrap_or_else(|| self.downstream_subscription.subscriber_priority()),
+            group_order: self.downstream_subscription.group_order().as_moqt(),
+            delivery_timeout: window
+                .delivery_timeout
+                .or_else(|| self.downstream_subscription.delivery_timeout()),
+        }
+    }
+
+    fn congestion_watch(&self) -> CongestionWatch {
+        CongestionWatch::new(
+            self.stall_threshold,
+            self.newest_group.subscribe(),
+            self.evicted_before.subscribe(),
+            self.stopped.clone(),
+        )
+    }
+
+    pub(crate) async fn run(mut self) -> SenderEnd {
         let mut stream_factory: Option<Box<dyn StreamSenderFactory>> = None;
-        let mut joinset = JoinSet::<()>::new();
-        let track_alias = self.downstream_subscription.track_alias();
+        let mut joinset = SendTasks::new();
+        let mut queue = SendQueue::new(self.queue_limits);
+        let mut end = SenderEnd::Drained;
+        let stop_watch = self.congestion_watch();
+        let mut scheduling = true;
 
         loop {
             tokio::select! {
-                Some(req) = self.receiver.recv() => {
-                    match req {
-                        GroupSendTask::Stream {
-                            group_id,
-                            subgroup_id,
-                            object_id,
-                        } => {
-                            let factory = stream_factory
-                                .get_or_insert_with(|| self.publisher.new_stream_factory(&self.downstream_subscription));
-                            let span = tracing::info_span!(
-                                "relay.dataplane.egress.stream",
-                                track_key = %self.track_key,
-                                track_alias = track_alias,
-                                group_id = group_id,
-                                subgroup_id = tracing::field::debug(&subgroup_id),
-                                object_id = object_id,
-                                object_count = tracing::field::Empty,
-                                end_reason = tracing::field::Empty,
-                            );
-                            // Opening the stream awaits peer stream credit; if the
-                            // subscriber stops granting streams this blocks the
-                            // whole GroupSender, so leave a trace before it.
-                            match async {
-                                tracing::debug!("opening egress uni stream");
-                                factory.next().await
-                            }
-                            .instrument(span.clone())
-                            .await
-                            {
-                                Ok(sender) => {
-                                    joinset.spawn(Self::send_stream_task(
-                                        track_alias,
-                                        group_id,
-                                        subgroup_id,
-                                        object_id,
-                                        self.track_key.clone(),
-                                        self.cache.clone(),
-                                        sender,
-                                    ).instrument(span));
-                                }
-                                Err(e) => {
-                                    span.record("object_count", 0u64);
-                                    span.record("end_reason", "open_failed");
-                                    tracing::error!(?e, "failed to open stream sender");
+                // Send tasks see the same signal and reset their streams;
+                // the loop only waits for them to finish.
+                () = stop_watch.stopped(), if end != SenderEnd::Stopped && (scheduling || !joinset.is_empty()) => {
+                    tracing::debug!(track_key = %self.track_key, in_flight = joinset.len(), "egress subscription stopped; resetting open streams");
+                    end = SenderEnd::Stopped;
+                }
+                req = self.receiver.recv(), if scheduling && !queue.is_paused() && end != SenderEnd::Stopped => {
+                    let Some(req) = req else {
+                        scheduling = false;
+                        continue;
+                    };
+                    let group_id = req.group_id();
+                    self.newest_group.send_if_modified(|newest| {
+                        let newer = group_id > *newest;
+                        if newer {
+                            *newest = group_id;
+                        }
+                        newer
+                    });
+                    match queue.admit(group_id) {
+                        Admission::Accept => {}
+                        Admission::Evict(oldest) => {
+                            tracing::debug!(group_id = oldest, "egress send queue full; dropping oldest group");
+                            self.evicted_before.send_if_modified(|before| {
+                                let newer = oldest + 1 > *before;
+                                if newer {
+                                    *before = oldest + 1;
                                 }
-                            }
+                                newer
+                            });
                         }
-                        GroupSendTask::Datagram {
-                            group_id,
-                            object_id,
-                        } => {
-                            let sender = self.publisher.new_datagram(&self.downstream_subscription);
-                            joinset.spawn(Self::send_datagram_task(
-                                track_alias,
+                        Admission::DropIncoming => {
+                            tracing::debug!(group_id, "egress send queue full; dropping incoming group");
+                            continue;
+                        }
+                        Admission::Disconnect => {
+                            tracing::warn!(
+                                track_key = %self.track_key,
                                 group_id,
-                                object_id,
-                                self.cache.clone(),
-                                sender,
-                            ));
+                                "egress send queue full; subscriber too far behind"
+                            );
+                            end = SenderEnd::QueueOverflowed;
+                            break;
                         }
                     }
+                    self.start(req, &mut stream_factory, &mut joinset, &mut queue).await;
                 }
-                Some(result) = joinset.join_next() => {
-                    if let Err(e) = result {
-                        tracing::error!("egress send task panicked: {:?}", e);
+                Some(result) = joinset.join_next_with_id() => {
+                    match result {
+                        Ok((task_id, None)) => queue.finished(task_id),
+                        Ok((task_id, Some(_))) if end == SenderEnd::Stopped => queue.finished(task_id),
+                        Ok((task_id, Some(skipped))) => {
+                            queue.finished(task_id);
+                            self.start_end_of_group_marker(skipped, &mut stream_factory, &mut joinset).await;
+                        }
+                        Err(e) => {
+                            queue.finished(e.id());
+                            tracing::error!("egress send task panicked: {:?}", e);
+                        }
                     }
                 }
                 else => break,
             }
         }
+        queue.report(&self.track_key);
+        end
     }
+}
 
-    async fn send_stream_task(
-        track_alias: u64,
-        group_id: u64,
-        subgroup_id: StreamSubgroupId,
-        object_id: u64,
-        track_key: TrackKey,
-        cache: Arc<TrackCache>,
-        mut sender: Box<dyn DataSender>,
-    ) {
-        let span = Span::current();
-        let mut object_count = 0u64;
-        let Some(header) = cache
-            .get_stream_header_or_wait(group_id, &subgroup_id)
-            .await
-        else {
-            span.record("object_count", object_count);
-            span.record("end_reason", "header_unavailable");
-            tracing::warn!(
-                track_key = %track_key,
-                track_alias,
-                group_id,
-                subgroup_id = ?subgroup_id,
-                "stream egress task ended before subgroup header became available"
-            );
-            return;
-        };
-        tracing::debug!(
-            track_key = %track_key,
-            track_alias,
-            group_id,
-            subgroup_id = ?subgroup_id,
-            "egress sending subgroup header"
-        );
-        if let Err(error) = sender.send_object((*header).clone()).await {
-            span.record("object_count", object_count);
-            span.record("end_reason", "send_header_failed");
-            tracing::error!(
-                ?error,
-                track_key = %track_key,
-                track_alias,
-                group_id,
-                subgroup_id = ?subgroup_id,
-                "failed to send subgroup header"
-            );
-            return;
-        }
+/// Records what a send task wrote to its stream and the code it reset the
+/// stream with.
+#[cfg(test)]
+#[derive(Clone, Default)]
+struct RecordingSender {
+    objects: Arc<std::sync::Mutex<Vec<crate::modules::core::data_object::DataObject>>>,
+    reset_code: Arc<std::sync::Mutex<Option<u64>>>,
+}
 
-        let mut cursor = object_id;
-        while let Some((id, object)) = cache
-            .stream_object_from_or_wait(group_id, &subgroup_id, cursor)
-            .await
-        {
-            tracing::debug!(
-                track_key = %track_key,
-                track_alias,
-                group_id,
-                subgroup_id = ?subgroup_id,
-                object_id = id,
-                "egress sending subgroup object"
-            );
-            if sender.send_object((*object).clone()).await.is_err() {
-                span.record("object_count", object_count);
-                span.record("end_reason", "send_object_failed");
-                tracing::error!(
-                    track_key = %track_key,
-                    track_alias,
-                    group_id,
-                    subgroup_id = ?subgroup_id,
-                    object_id = id,
-                    "failed to send subgroup object"
-                );
-                return;
-            }
-            object_count += 1;
-            cursor = id + 1;
-        }
-        span.record("object_count", object_count);
-        span.record("end_reason", "cache_closed");
-        if let Err(error) = sender.close().await {
-            tracing::warn!(
-                ?error,
-                track_key = %track_key,
-                track_alias,
-                group_id,
-                subgroup_id = ?subgroup_id,
-                "failed to close egress stream sender"
-            );
-        }
+#[cfg(test)]
+#[async_trait::async_trait]
+impl crate::modules::core::data_sender::DataSender for RecordingSender {
+    async fn send_object(
+        &mut self,
+        object: crate::modules::core::data_object::DataObject,
+    ) -> anyhow::Result<()> {
+        self.objects.lock().unwrap().push(object);
+        Ok(())
     }
 
-    async fn send_datagram_task(
-        track_alias: u64,
-        group_id: u64,
-        object_id: u64,
-        cache: Arc<TrackCache>,
-        mut sender: Box<dyn DataSender>,
-    ) {
-        let mut cursor = object_id;
-        while let Some((id, object)) = cache.datagram_object_from_or_wait(group_id, cursor).await {
-            tracing::debug!(
-                track_alias,
-                group_id,
-                object_id = id,
-                "egress sending datagram object"
-            );
-            if sender.send_object((*object).clone()).await.is_err() {
-                return;
-            }
-            cursor = id + 1;
-        }
+    async fn reset(&mut self, error_code: u64) -> anyhow::Result<()> {
+        *self.reset_code.lock().unwrap() = Some(error_code);
+        Ok(())
     }
 }
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use tracing::Instrument;
+
+use crate::modules::{
+    billing::SubscriptionUsage,
+    core::{data_object::DataObject, data_sender::DataSender},
+    relay::{
+        cache::track_cache::TrackCache, egress::forwarding::subgroup_object_to_datagram,
+        types::StreamSubgroupId,
+    },
+};
+
+use super::{
+    GroupSender,
+    metrics::{ForwardedAs, object_span, record_forwarded},
+    stream_options::StreamOptions,
+};
+
+impl GroupSender {
+    pub(super) async fn send_datagram_task(
+        track_alias: u64,
+        group_id: u64,
+        object_id: u64,
+        options: StreamOptions,
+        cache: Arc<TrackCache>,
+        mut sender: Box<dyn DataSender>,
+        usage: Arc<SubscriptionUsage>,
+    ) {
+        let mut cursor = cache.datagram_cursor(group_id, object_id).await;
+        let mut warned_slow = false;
+        while let Some((id, inserted_at, object)) = cursor.next().await {
+            if options.is_expired(inserted_at) {
+                // A late datagram is as good as lost; later ones may be fresh.
+                tracing::debug!(
+                    track_alias,
+                    group_id,
+                    object_id = id,
+                    "datagram exceeded delivery timeout; dropped"
+                );
+                continue;
+            }
+            tracing::debug!(
+                track_alias,
+                group_id,
+                object_id = id,
+                "egress sending datagram object"
+            );
+            let object_span = object_span(&usage, group_id, id, object.payload_len());
+            if sender
+                .send_object((*object).clone())
+                .instrument(object_span)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            let transit = inserted_at.elapsed();
+            if cache.transit().record(transit) && !warned_slow {
+                warned_slow = true;
+                tracing::warn!(
+                    track_alias,
+                    group_id,
+                    object_id = id,
+                    transit_ms = transit.as_millis() as u64,
+                    "relay transit time exceeded warning threshold"
+                );
+            }
+            record_forwarded(
+                &usage,
+                ForwardedAs::Datagram,
+                group_id,
+                id,
+                object.payload_len(),
+            );
+        }
+    }
+
+    /// Forwards one subgroup stream as datagrams, for subscribers that prefer
+    /// datagrams. Objects a datagram cannot carry are skipped.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn send_stream_as_datagrams_task(
+        track_alias: u64,
+        group_id: u64,
+        subgroup_id: StreamSubgroupId,
+        object_id: u64,
+        options: StreamOptions,
+        cache: Arc<TrackCache>,
+        mut sender: Box<dyn DataSender>,
+        usage: Arc<SubscriptionUsage>,
+    ) {
+        let Some(header) = cache
+            .get_stream_header_or_wait(group_id, &subgroup_id)
+            .await
+        else {
+            return;
+        };
+        let DataObject::SubgroupHeader(header) = header.as_ref() else {
+            tracing::error!(
+                track_alias,
+                group_id,
+                "cached stream header is not a subgroup header"
+            );
+            return;
+        };
+
+        let mut cursor = cache.stream_cursor(group_id, &subgroup_id, object_id).await;
+        let mut warned_send = false;
+        while let Some((id, inserted_at, object)) = cursor.next().await {
+            if options.is_expired(inserted_at) {
+                continue;
+            }
+            let DataObject::SubgroupObject(field) = object.as_ref() else {
+                continue;
+            };
+            let Some(datagram) = subgroup_object_to_datagram(track_alias, header, field, id) else {
+                tracing::debug!(
+                    track_alias,
+                    group_id,
+                    object_id = id,
+                    "object has no datagram form; skipped"
+                );
+                continue;
+            };
+            // A datagram that does not go out is lost like any other; keep
+            // the rest of the group flowing.
+            if let Err(error) = sender.send_object(datagram).await {
+                if !warned_send {
+                    warned_send = true;
+                    tracing::warn!(
+                        ?error,
+                        track_alias,
+                        group_id,
+                        object_id = id,
+                        "failed to send converted datagram"
+                    );
+                }
+                continue;
+            }
+            cache.transit().record(inserted_at.elapsed());
+            record_forwarded(
+                &usage,
+                ForwardedAs::Datagram,
+                group_id,
+                id,
+                object.payload_len(),
+            );
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use tracing::Span;
+
+use crate::modules::{
+    billing::SubscriptionUsage,
+    core::{data_object::DataObject, data_sender::DataSender},
+    relay::{
+        cache::track_cache::TrackCache,
+        egress::{
+            congestion::{CongestionWatch, SendOutcome, SkippedGroup},
+            forwarding::{datagram_to_subgroup_object, subgroup_header_for_datagrams},
+        },
+    },
+};
+
+use super::{
+    GroupSender,
+    metrics::{ForwardedAs, record_forwarded},
+    stream_options::StreamOptions,
+};
+
+impl GroupSender {
+    /// Forwards one datagram group on a single subgroup stream, for
+    /// subscribers that prefer streams. The stream ends when the group does.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn send_datagrams_as_stream_task(
+        track_alias: u64,
+        group_id: u64,
+        object_id: u64,
+        options: StreamOptions,
+        congestion: CongestionWatch,
+        cache: Arc<TrackCache>,
+        mut sender: Box<dyn DataSender>,
+        usage: Arc<SubscriptionUsage>,
+    ) -> Option<SkippedGroup> {
+        let span = Span::current();
+        let mut object_count = 0u64;
+        let mut header = None;
+        let mut prev_object_id = None;
+        let mut first_object_id = None;
+        let mut cursor = cache.datagram_cursor(group_id, object_id).await;
+        loop {
+            let next = tokio::select! {
+                next = cursor.next() => next,
+                () = congestion.evicted(group_id) => {
+                    let Some(header) = &header else {
+                        Self::reset_evicted_stream(sender.as_mut(), &span, group_id).await;
+                        return None;
+                    };
+                    return Self::skip_group(
+                        sender.as_mut(),
+                        &span,
+                        object_count,
+                        header,
+                        first_object_id,
+                        cursor.position(),
+                        "queue_overflow",
+                    )
+                    .await;
+                }
+                () = congestion.stopped() => {
+                    Self::reset_stopped_stream(sender.as_mut(), &span, object_count, group_id).await;
+                    return None;
+                }
+            };
+            let Some((id, inserted_at, object)) = next else {
+                break;
+            };
+            if options.is_expired(inserted_at) {
+                if header.is_none() {
+                    // Nothing sent yet; a later datagram may still be fresh.
+                    continue;
+                }
+                Self::reset_expired_stream(sender.as_mut(), &span, object_count, group_id, id)
+                    .await;
+                return None;
+            }
+            let DataObject::ObjectDatagram(datagram) = object.as_ref() else {
+                continue;
+            };
+            let header = match &header {
+                Some(header) => header,
+                None => {
+                    let first = subgroup_header_for_datagrams(track_alias, group_id, datagram);
+                    Self::apply_priority(
+                        sender.as_mut(),
+                        options,
+                        first.publisher_priority,
+                        group_id,
+                    )
+                    .await;
+                    if let Err(error) = sender
+                        .send_object(DataObject::SubgroupHeader(first.clone()))
+                        .await
+                    {
+                        span.record("object_count", object_count);
+                        span.record("end_reason", "send_header_failed");
+                        tracing::error!(
+                            ?error,
+                            track_alias,
+                            group_id,
+                            "failed to send subgroup header"
+                        );
+                        return None;
+                    }
+                    header.insert(first)
+                }
+            };
+            first_object_id.get_or_insert(id);
+            let converted = datagram_to_subgroup_object(header, datagram, prev_object_id, id);
+            match congestion.send(sender.as_mut(), converted, group_id).await {
+                SendOutcome::Sent => {}
+                SendOutcome::Failed(error) => {
+                    span.record("object_count", object_count);
+                    span.record("end_reason", "send_object_failed");
+                    tracing::error!(
+                        ?error,
+                        track_alias,
+                        group_id,
+                        object_id = id,
+                        "failed to send subgroup object"
+                    );
+                    return None;
+                }
+                SendOutcome::Stalled => {
+                    return Self::skip_group(
+                        sender.as_mut(),
+                        &span,
+                        object_count,
+                        header,
+                        first_object_id,
+                        id,
+                        "congestion_skipped",
+                    )
+                    .await;
+                }
+                SendOutcome::Cancelled => {
+                    Self::reset_stopped_stream(sender.as_mut(), &span, object_count, group_id)
+                        .await;
+                    return None;
+                }
+                SendOutcome::Evicted => {
+                    return Self::skip_group(
+                        sender.as_mut(),
+                        &span,
+                        object_count,
+                        header,
+                        first_object_id,
+                        id,
+                        "queue_overflow",
+                    )
+                    .await;
+                }
+            }
+            cache.transit().record(inserted_at.elapsed());
+            record_forwarded(
+                &usage,
+                ForwardedAs::Stream,
+                group_id,
+                id,
+                object.payload_len(),
+            );
+            object_count += 1;
+            prev_object_id = Some(id);
+        }
+        span.record("object_count", object_count);
+        span.record("end_reason", "cache_closed");
+        if let Err(error) = sender.close().await {
+            tracing::warn!(
+                ?error,
+                track_alias,
+                group_id,
+                "failed to close egress stream sender"
+            );
+        }
+        None
+    }
+}
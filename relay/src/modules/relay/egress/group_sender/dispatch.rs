@@ -0,0 +1,203 @@
+use tracing::{Instrument, Span};
+
+use crate::ForwardingPreference;
+use crate::modules::{
+    core::data_sender::{DataSender, stream_sender_factory::StreamSenderFactory},
+    relay::egress::{
+        congestion::{CongestionWatch, SkippedGroup},
+        scheduler::GroupSendTask,
+        send_queue::SendQueue,
+    },
+};
+
+use super::{GroupSender, SendTasks};
+
+impl GroupSender {
+    /// Spawns the send task of an admitted group, converting between
+    /// datagrams and subgroup streams as the forwarding preference asks.
+    pub(super) async fn start(
+        &self,
+        req: GroupSendTask,
+        stream_factory: &mut Option<Box<dyn StreamSenderFactory>>,
+        joinset: &mut SendTasks,
+        queue: &mut SendQueue,
+    ) {
+        let track_alias = self.downstream_subscription.track_alias();
+        let group_id = req.group_id();
+        let handle = match (req, self.forwarding) {
+            (
+                GroupSendTask::Stream {
+                    subgroup_id,
+                    object_id,
+                    ..
+                },
+                ForwardingPreference::Datagram,
+            ) => {
+                let sender = self.publisher.new_datagram(&self.downstream_subscription);
+                let task = Self::send_stream_as_datagrams_task(
+                    track_alias,
+                    group_id,
+                    subgroup_id,
+                    object_id,
+                    self.stream_options(),
+                    self.cache.clone(),
+                    sender,
+                    self.usage.clone(),
+                );
+                joinset.spawn(Self::evictable(task, self.congestion_watch(), group_id))
+            }
+            (
+                GroupSendTask::Stream {
+                    subgroup_id,
+                    object_id,
+                    ..
+                },
+                _,
+            ) => {
+                let span = self.stream_span(group_id, format!("{subgroup_id:?}"), object_id);
+                let Some(sender) = self.open_stream(stream_factory, &span).await else {
+                    return;
+                };
+                joinset.spawn(
+                    Self::send_stream_task(
+                        track_alias,
+                        group_id,
+                        subgroup_id,
+                        object_id,
+                        self.stream_options(),
+                        self.congestion_watch(),
+                        self.track_key.clone(),
+                        self.cache.clone(),
+                        sender,
+                        self.usage.clone(),
+                    )
+                    .instrument(span),
+                )
+            }
+            (GroupSendTask::Datagram { object_id, .. }, ForwardingPreference::Subgroup) => {
+                let span = self.stream_span(group_id, "from_datagrams".to_string(), object_id);
+                let Some(sender) = self.open_stream(stream_factory, &span).await else {
+                    return;
+                };
+                joinset.spawn(
+                    Self::send_datagrams_as_stream_task(
+                        track_alias,
+                        group_id,
+                        object_id,
+                        self.stream_options(),
+                        self.congestion_watch(),
+                        self.cache.clone(),
+                        sender,
+                        self.usage.clone(),
+                    )
+                    .instrument(span),
+                )
+            }
+            (GroupSendTask::Datagram { object_id, .. }, _) => {
+                let sender = self.publisher.new_datagram(&self.downstream_subscription);
+                let task = Self::send_datagram_task(
+                    track_alias,
+                    group_id,
+                    object_id,
+                    self.stream_options(),
+                    self.cache.clone(),
+                    sender,
+                    self.usage.clone(),
+                );
+                joinset.spawn(Self::evictable(task, self.congestion_watch(), group_id))
+            }
+        };
+        queue.started(handle.id(), group_id);
+    }
+
+    /// Ends a skipped group for the subscriber on a new stream for its
+    /// subgroup; see [`Self::send_end_of_group_marker_task`]. The marker is
+    /// not counted against the send queue.
+    pub(super) async fn start_end_of_group_marker(
+        &self,
+        skipped: SkippedGroup,
+        stream_factory: &mut Option<Box<dyn StreamSenderFactory>>,
+        joinset: &mut SendTasks,
+    ) {
+        let span = self.stream_span(
+            skipped.header.group_id,
+            "end_of_group_marker".to_string(),
+            skipped.next_object_id,
+        );
+        let Some(sender) = self.open_stream(stream_factory, &span).await else {
+            return;
+        };
+        let task = Self::send_end_of_group_marker_task(skipped, self.stream_options(), sender);
+        joinset.spawn(
+            async move {
+                task.await;
+                None
+            }
+            .instrument(span),
+        );
+    }
+
+    /// Runs a datagram send task until it ends, its group is evicted or the
+    /// subscription is stopped. Datagrams have no stream to reset, so either
+    /// just stops the task.
+    pub(super) async fn evictable(
+        task: impl Future<Output = ()>,
+        congestion: CongestionWatch,
+        group_id: u64,
+    ) -> Option<SkippedGroup> {
+        tokio::select! {
+            () = task => {}
+            () = congestion.evicted(group_id) => {}
+            () = congestion.stopped() => {}
+        }
+        None
+    }
+
+    pub(super) fn stream_span(&self, group_id: u64, subgroup_id: String, object_id: u64) -> Span {
+        tracing::info_span!(
+            "relay.dataplane.egress.stream",
+            subscriber_session_id = self.usage.session_id(),
+            track_key = %self.track_key,
+            track_alias = self.downstream_subscription.track_alias(),
+            group_id = group_id,
+            subgroup_id = subgroup_id,
+            object_id = object_id,
+            object_count = tracing::field::Empty,
+            end_reason = tracing::field::Empty,
+        )
+    }
+
+    /// Opens the next uni stream of the subscription, creating its stream
+    /// factory on first use.
+    pub(super) async fn open_stream(
+        &self,
+        stream_factory: &mut Option<Box<dyn StreamSenderFactory>>,
+        span: &Span,
+    ) -> Option<Box<dyn DataSender>> {
+        let factory = stream_factory.get_or_insert_with(|| {
+            self.publisher
+                .new_stream_factory(&self.downstream_subscription)
+        });
+        // Opening the stream awaits peer stream credit; if the subscriber
+        // stops granting streams this blocks the whole GroupSender, so leave
+        // a trace before it.
+        match async {
+            tracing::debug!("opening egress uni stream");
+            factory.next().await
+        }
+        .instrument(span.clone())
+        .await
+        {
+            Ok(sender) => {
+                self.usage.record_stream();
+                Some(sender)
+            }
+            Err(e) => {
+                span.record("object_count", 0u64);
+                span.record("end_reason", "open_failed");
+                span.in_scope(|| tracing::error!(?e, "failed to open stream sender"));
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use tracing::Span;
+
+use crate::logging::OBJECT_SPAN_TARGET;
+use crate::modules::{billing::SubscriptionUsage, qlog};
+
+/// How an object left the relay, which may differ from how it arrived.
+#[derive(Clone, Copy)]
+pub(super) enum ForwardedAs {
+    Stream,
+    Datagram,
+}
+
+pub(super) fn record_forwarded(
+    usage: &SubscriptionUsage,
+    forwarded_as: ForwardedAs,
+    group_id: u64,
+    object_id: u64,
+    payload_len: usize,
+) {
+    usage.record_object(payload_len);
+    let name = match forwarded_as {
+        ForwardedAs::Stream => "moqt:subgroup_object_created",
+        ForwardedAs::Datagram => "moqt:object_datagram_created",
+    };
+    qlog::record(usage.session_id(), name, || {
+        serde_json::json!({
+            "request_id": usage.request_id(),
+            "group_id": group_id,
+            "object_id": object_id,
+            "object_payload_length": payload_len,
+        })
+    });
+}
+
+/// Span of one forwarded object, exported for a sample of objects when
+/// `RELAY_OTEL_OBJECT_SAMPLE_EVERY` is set. Carries the subscription's
+/// session and track, which OTLP backends do not inherit from parent spans.
+pub(super) fn object_span(
+    usage: &SubscriptionUsage,
+    group_id: u64,
+    object_id: u64,
+    payload_bytes: usize,
+) -> Span {
+    tracing::debug_span!(
+        target: OBJECT_SPAN_TARGET,
+        "relay.dataplane.egress.object",
+        subscriber_session_id = usage.session_id(),
+        downstream_subscribe_id = usage.request_id(),
+        track_namespace = %usage.track_namespace(),
+        track_name = %usage.track_name(),
+        group_id,
+        object_id,
+        payload_bytes,
+    )
+}
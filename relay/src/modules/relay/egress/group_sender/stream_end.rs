@@ -0,0 +1,287 @@
+use std::time::Duration;
+
+use tracing::Span;
+
+use crate::modules::{
+    core::{data_object::DataObject, data_sender::DataSender},
+    enums::StreamResetCode,
+    relay::egress::congestion::SkippedGroup,
+};
+
+use super::{GroupSender, stream_options::StreamOptions};
+
+impl GroupSender {
+    /// Ends a stream whose next object missed the subscriber's
+    /// DELIVERY_TIMEOUT. The rest of the subgroup would only arrive later
+    /// still, so the stream is reset instead of drained.
+    pub(super) async fn reset_expired_stream(
+        sender: &mut dyn DataSender,
+        span: &Span,
+        object_count: u64,
+        group_id: u64,
+        object_id: u64,
+    ) {
+        span.record("object_count", object_count);
+        span.record("end_reason", "delivery_timeout");
+        tracing::debug!(
+            group_id,
+            object_id,
+            "object exceeded delivery timeout; resetting stream"
+        );
+        if let Err(error) = sender.reset(StreamResetCode::DeliveryTimeout as u64).await {
+            tracing::warn!(?error, group_id, "failed to reset expired egress stream");
+        }
+    }
+
+    /// Gives up a group the subscriber cannot keep up with: its write
+    /// stalled behind a newer group, or the send queue evicted it. The
+    /// stream is reset so its buffered data stops competing with newer
+    /// groups, and the group is handed back for an END_OF_GROUP marker.
+    pub(super) async fn skip_group(
+        sender: &mut dyn DataSender,
+        span: &Span,
+        object_count: u64,
+        header: &moqt::SubgroupHeader,
+        first_object_id: Option<u64>,
+        object_id: u64,
+        end_reason: &'static str,
+    ) -> Option<SkippedGroup> {
+        span.record("object_count", object_count);
+        span.record("end_reason", end_reason);
+        tracing::info!(
+            group_id = header.group_id,
+            object_id,
+            end_reason,
+            "downstream subscriber behind; skipping ahead"
+        );
+        if let Err(error) = sender.reset(StreamResetCode::Cancelled as u64).await {
+            tracing::warn!(
+                ?error,
+                group_id = header.group_id,
+                "failed to reset skipped egress stream"
+            );
+        }
+        Some(SkippedGroup {
+            header: header.clone(),
+            next_object_id: object_id,
+            first_object_id: first_object_id.unwrap_or(object_id),
+        })
+    }
+
+    /// Resets a stream of a stopped subscription, cancelling its pending
+    /// write, so the subscriber does not take the cut-off subgroup for a
+    /// complete one.
+    pub(super) async fn reset_stopped_stream(
+        sender: &mut dyn DataSender,
+        span: &Span,
+        object_count: u64,
+        group_id: u64,
+    ) {
+        span.record("object_count", object_count);
+        span.record("end_reason", "unsubscribed");
+        if let Err(error) = sender.reset(StreamResetCode::Cancelled as u64).await {
+            tracing::warn!(?error, group_id, "failed to reset stopped egress stream");
+        }
+    }
+
+    /// Resets a stream whose subscriber lags while the relay is under
+    /// critical resource pressure: a lagging subscriber is what keeps groups
+    /// and send buffers alive, so it goes first.
+    pub(super) async fn reset_shed_stream(
+        sender: &mut dyn DataSender,
+        span: &Span,
+        object_count: u64,
+        group_id: u64,
+        transit: Duration,
+    ) {
+        span.record("object_count", object_count);
+        span.record("end_reason", "shed_slow_subscriber");
+        tracing::warn!(
+            group_id,
+            transit_ms = transit.as_millis() as u64,
+            "shedding slow subscriber stream under resource pressure"
+        );
+        if let Err(error) = sender.reset(StreamResetCode::Cancelled as u64).await {
+            tracing::warn!(?error, group_id, "failed to reset shed egress stream");
+        }
+    }
+
+    /// Resets a stream evicted before anything was sent on it; with nothing
+    /// delivered there is no group end to mark.
+    pub(super) async fn reset_evicted_stream(
+        sender: &mut dyn DataSender,
+        span: &Span,
+        group_id: u64,
+    ) {
+        span.record("object_count", 0u64);
+        span.record("end_reason", "queue_overflow");
+        if let Err(error) = sender.reset(StreamResetCode::Cancelled as u64).await {
+            tracing::warn!(?error, group_id, "failed to reset evicted egress stream");
+        }
+    }
+
+    /// Tells the subscriber where a skipped group ended: its header and an
+    /// END_OF_GROUP status at the first object it did not receive. The
+    /// skipped stream was reset, which discards what it still buffered, so
+    /// the marker goes on a new stream for the same (group, subgroup), with
+    /// the Subgroup ID written out so it does not follow the marker's object
+    /// id. A subscriber merges the two by object id, keeping the first
+    /// header: the relay's stream reader caches the marker after the objects
+    /// delivered before the reset, and the wasm client reads each stream on
+    /// its own.
+    pub(super) async fn send_end_of_group_marker_task(
+        skipped: SkippedGroup,
+        options: StreamOptions,
+        mut sender: Box<dyn DataSender>,
+    ) {
+        let span = Span::current();
+        let group_id = skipped.header.group_id;
+        Self::apply_priority(
+            sender.as_mut(),
+            options,
+            skipped.header.publisher_priority,
+            group_id,
+        )
+        .await;
+        let marker = skipped.end_of_group_marker();
+        let sent = match sender
+            .send_object(DataObject::SubgroupHeader(skipped.marker_header()))
+            .await
+        {
+            Ok(()) => sender.send_object(marker).await,
+            Err(error) => Err(error),
+        };
+        if let Err(error) = sent {
+            span.record("end_reason", "send_object_failed");
+            tracing::warn!(?error, group_id, "failed to send end-of-group marker");
+            return;
+        }
+        span.record("object_count", 1u64);
+        span.record("end_reason", "end_of_group_marker");
+        if let Err(error) = sender.close().await {
+            tracing::warn!(?error, group_id, "failed to close egress stream sender");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::modules::relay::{
+        cache::track_cache::TrackCache,
+        egress::group_sender::{RecordingSender, stream_options::test_options},
+        types::StreamSubgroupId,
+    };
+
+    fn payload_object(delta: u64) -> DataObject {
+        DataObject::SubgroupObject(moqt::SubgroupObjectField {
+            message_type: moqt::SubgroupHeader::new(
+                1,
+                3,
+                moqt::SubgroupId::Value(0),
+                0,
+                false,
+                false,
+            )
+            .message_type,
+            object_id_delta: delta,
+            extension_headers: moqt::ExtensionHeaders::default(),
+            subgroup_object: moqt::SubgroupObject::new_payload(Bytes::from_static(b"x")),
+        })
+    }
+
+    #[tokio::test]
+    async fn relay_subscriber_appends_the_marker_stream_to_the_reset_subgroup() {
+        // Arrange: a subscribing relay cached objects 0 and 1 of the
+        // subgroup before its stream was reset
+        let header = moqt::SubgroupHeader::new(1, 3, moqt::SubgroupId::Value(0), 0, false, false);
+        let subgroup_id = StreamSubgroupId::Value(0);
+        let cache = TrackCache::new();
+        let first_stream = [
+            DataObject::SubgroupHeader(header.clone()),
+            payload_object(0),
+            payload_object(0),
+        ];
+        let mut prev_object_id = None;
+        for object in first_stream {
+            let object_id = object.resolve_absolute_object_id(prev_object_id);
+            prev_object_id = object_id.or(prev_object_id);
+            cache
+                .append_live_stream_object(3, &subgroup_id, object_id, object)
+                .await;
+        }
+        cache.close_stream_subgroup(3, &subgroup_id).await;
+        let sender = RecordingSender::default();
+
+        // Act: the marker goes out on a second stream, read like any other
+        GroupSender::send_end_of_group_marker_task(
+            SkippedGroup {
+                header,
+                next_object_id: 2,
+                first_object_id: 0,
+            },
+            test_options(None),
+            Box::new(sender.clone()),
+        )
+        .await;
+        let second_stream = sender.objects.lock().unwrap().clone();
+        let mut prev_object_id = None;
+        for object in second_stream {
+            let object_id = object.resolve_absolute_object_id(prev_object_id);
+            prev_object_id = object_id.or(prev_object_id);
+            cache
+                .append_live_stream_object(3, &subgroup_id, object_id, object)
+                .await;
+        }
+
+        // Assert: the subgroup ends with END_OF_GROUP right after the
+        // objects delivered on the first stream
+        let mut cursor = cache.stream_cursor(3, &subgroup_id, 0).await;
+        let mut object_ids = Vec::new();
+        let mut last = None;
+        while let Some((object_id, _, object)) = cursor.next().await {
+            object_ids.push(object_id);
+            last = Some(object);
+        }
+        assert_eq!(object_ids, vec![0, 1, 2]);
+        let Some(DataObject::SubgroupObject(field)) = last.as_deref() else {
+            panic!("expected a subgroup object");
+        };
+        assert_eq!(
+            field.subgroup_object,
+            moqt::SubgroupObject::new_status(moqt::wire::ObjectStatus::EndOfGroup as u64)
+        );
+    }
+
+    #[tokio::test]
+    async fn marker_stream_keeps_a_first_object_id_delta_subgroup() {
+        // Arrange: the skipped stream took its Subgroup ID from object 4,
+        // its first object
+        let header =
+            moqt::SubgroupHeader::new(1, 3, moqt::SubgroupId::FirstObjectIdDelta, 0, false, false);
+        let sender = RecordingSender::default();
+
+        // Act
+        GroupSender::send_end_of_group_marker_task(
+            SkippedGroup {
+                header,
+                next_object_id: 6,
+                first_object_id: 4,
+            },
+            test_options(None),
+            Box::new(sender.clone()),
+        )
+        .await;
+
+        // Assert: the marker stream names subgroup 4 outright instead of
+        // letting the marker at object 6 define it
+        let objects = sender.objects.lock().unwrap().clone();
+        let [DataObject::SubgroupHeader(marker_header), marker] = objects.as_slice() else {
+            panic!("expected a header and the marker");
+        };
+        assert_eq!(marker_header.subgroup_id, moqt::SubgroupId::Value(4));
+        assert_eq!(marker.resolve_absolute_object_id(None), Some(6));
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::modules::core::data_sender::DataSender;
+
+use super::GroupSender;
+
+/// Subscription settings a send task fixes when it starts. The subscriber
+/// priority and group order order its stream against the session's others,
+/// besides the publisher priority and group id the stream carries.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct StreamOptions {
+    pub(super) subscriber_priority: u8,
+    pub(super) group_order: moqt::GroupOrder,
+    pub(super) delivery_timeout: Option<Duration>,
+}
+
+impl StreamOptions {
+    /// Whether an object cached at `inserted_at` can no longer arrive within
+    /// the subscriber's DELIVERY_TIMEOUT.
+    pub(super) fn is_expired(&self, inserted_at: Instant) -> bool {
+        self.delivery_timeout
+            .is_some_and(|timeout| inserted_at.elapsed() > timeout)
+    }
+}
+
+impl GroupSender {
+    /// A stream the transport cannot prioritize still delivers, so a failure
+    /// here is only logged.
+    pub(super) async fn apply_priority(
+        sender: &mut dyn DataSender,
+        options: StreamOptions,
+        publisher_priority: u8,
+        group_id: u64,
+    ) {
+        if let Err(error) = sender
+            .set_priority(
+                options.subscriber_priority,
+                publisher_priority,
+                options.group_order,
+                group_id,
+            )
+            .await
+        {
+            tracing::warn!(
+                ?error,
+                subscriber_priority = options.subscriber_priority,
+                publisher_priority,
+                group_id,
+                "failed to set egress stream priority"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+pub(super) fn test_options(delivery_timeout: Option<Duration>) -> StreamOptions {
+    StreamOptions {
+        subscriber_priority: 128,
+        group_order: moqt::GroupOrder::Ascending,
+        delivery_timeout,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn objects_expire_only_past_the_delivery_timeout() {
+        // Arrange
+        let inserted_at = Instant::now() - Duration::from_millis(50);
+
+        // Act / Assert
+        assert!(test_options(Some(Duration::from_millis(10))).is_expired(inserted_at));
+        assert!(!test_options(Some(Duration::from_secs(10))).is_expired(inserted_at));
+        assert!(!test_options(None).is_expired(inserted_at));
+    }
+}
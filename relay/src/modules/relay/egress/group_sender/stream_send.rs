@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use tracing::{Instrument, Span};
+
+use crate::modules::{
+    billing::SubscriptionUsage,
+    core::{data_object::DataObject, data_sender::DataSender},
+    relay::{
+        cache::track_cache::TrackCache,
+        egress::congestion::{CongestionWatch, SendOutcome, SkippedGroup},
+        types::StreamSubgroupId,
+    },
+    resource_monitor,
+    types::TrackKey,
+};
+
+use super::{
+    GroupSender,
+    metrics::{ForwardedAs, object_span, record_forwarded},
+    stream_options::StreamOptions,
+};
+
+impl GroupSender {
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn send_stream_task(
+        track_alias: u64,
+        group_id: u64,
+        subgroup_id: StreamSubgroupId,
+        object_id: u64,
+        options: StreamOptions,
+        congestion: CongestionWatch,
+        track_key: TrackKey,
+        cache: Arc<TrackCache>,
+        mut sender: Box<dyn DataSender>,
+        usage: Arc<SubscriptionUsage>,
+    ) -> Option<SkippedGroup> {
+        let span = Span::current();
+        let mut object_count = 0u64;
+        let header = tokio::select! {
+            header = cache.get_stream_header_or_wait(group_id, &subgroup_id) => header,
+            () = congestion.evicted(group_id) => {
+                Self::reset_evicted_stream(sender.as_mut(), &span, group_id).await;
+                return None;
+            }
+            () = congestion.stopped() => {
+                Self::reset_stopped_stream(sender.as_mut(), &span, object_count, group_id).await;
+                return None;
+            }
+        };
+        let Some(header) = header else {
+            span.record("object_count", object_count);
+            span.record("end_reason", "header_unavailable");
+            tracing::warn!(
+                track_key = %track_key,
+                track_alias,
+                group_id,
+                subgroup_id = ?subgroup_id,
+                "stream egress task ended before subgroup header became available"
+            );
+            return None;
+        };
+        if let DataObject::SubgroupHeader(subgroup_header) = header.as_ref() {
+            Self::apply_priority(
+                sender.as_mut(),
+                options,
+                subgroup_header.publisher_priority,
+                group_id,
+            )
+            .await;
+        }
+        tracing::debug!(
+            track_key = %track_key,
+            track_alias,
+            group_id,
+            subgroup_id = ?subgroup_id,
+            "egress sending subgroup header"
+        );
+        if let Err(error) = sender.send_object((*header).clone()).await {
+            span.record("object_count", object_count);
+            span.record("end_reason", "send_header_failed");
+            tracing::error!(
+                ?error,
+                track_key = %track_key,
+                track_alias,
+                group_id,
+                subgroup_id = ?subgroup_id,
+                "failed to send subgroup header"
+            );
+            return None;
+        }
+
+        let DataObject::SubgroupHeader(subgroup_header) = header.as_ref() else {
+            return None;
+        };
+        let mut cursor = cache.stream_cursor(group_id, &subgroup_id, object_id).await;
+        let mut warned_slow = false;
+        let mut first_object_id = None;
+        loop {
+            let next = tokio::select! {
+                next = cursor.next() => next,
+                () = congestion.evicted(group_id) => {
+                    return Self::skip_group(
+                        sender.as_mut(),
+                        &span,
+                        object_count,
+                        subgroup_header,
+                        first_object_id,
+                        cursor.position(),
+                        "queue_overflow",
+                    )
+                    .await;
+                }
+                () = congestion.stopped() => {
+                    Self::reset_stopped_stream(sender.as_mut(), &span, object_count, group_id).await;
+                    return None;
+                }
+            };
+            let Some((id, inserted_at, object)) = next else {
+                break;
+            };
+            if options.is_expired(inserted_at) {
+                Self::reset_expired_stream(sender.as_mut(), &span, object_count, group_id, id)
+                    .await;
+                return None;
+            }
+            tracing::debug!(
+                track_key = %track_key,
+                track_alias,
+                group_id,
+                subgroup_id = ?subgroup_id,
+                object_id = id,
+                "egress sending subgroup object"
+            );
+            first_object_id.get_or_insert(id);
+            let object_span = object_span(&usage, group_id, id, object.payload_len());
+            match congestion
+                .send(sender.as_mut(), (*object).clone(), group_id)
+                .instrument(object_span)
+                .await
+            {
+                SendOutcome::Sent => {}
+                SendOutcome::Failed(error) => {
+                    span.record("object_count", object_count);
+                    span.record("end_reason", "send_object_failed");
+                    tracing::error!(
+                        ?error,
+                        track_key = %track_key,
+                        track_alias,
+                        group_id,
+                        subgroup_id = ?subgroup_id,
+                        object_id = id,
+                        "failed to send subgroup object"
+                    );
+                    return None;
+                }
+                SendOutcome::Stalled => {
+                    return Self::skip_group(
+                        sender.as_mut(),
+                        &span,
+                        object_count,
+                        subgroup_header,
+                        first_object_id,
+                        id,
+                        "congestion_skipped",
+                    )
+                    .await;
+                }
+                SendOutcome::Cancelled => {
+                    Self::reset_stopped_stream(sender.as_mut(), &span, object_count, group_id)
+                        .await;
+                    return None;
+                }
+                SendOutcome::Evicted => {
+                    return Self::skip_group(
+                        sender.as_mut(),
+                        &span,
+                        object_count,
+                        subgroup_header,
+                        first_object_id,
+                        id,
+                        "queue_overflow",
+                    )
+                    .await;
+                }
+            }
+            let transit = inserted_at.elapsed();
+            let slow = cache.transit().record(transit);
+            if slow && resource_monitor::pressure().sheds_slow_subscribers() {
+                Self::reset_shed_stream(sender.as_mut(), &span, object_count, group_id, transit)
+                    .await;
+                return None;
+            }
+            if slow && !warned_slow {
+                // Once per stream: a slow subscriber would otherwise log every object.
+                warned_slow = true;
+                tracing::warn!(
+                    track_key = %track_key,
+                    track_alias,
+                    group_id,
+                    subgroup_id = ?subgroup_id,
+                    object_id = id,
+                    transit_ms = transit.as_millis() as u64,
+                    "relay transit time exceeded warning threshold"
+                );
+            }
+            record_forwarded(
+                &usage,
+                ForwardedAs::Stream,
+                group_id,
+                id,
+                object.payload_len(),
+            );
+            object_count += 1;
+        }
+        span.record("object_count", object_count);
+        span.record("end_reason", "cache_closed");
+        if let Err(error) = sender.close().await {
+            tracing::warn!(
+                ?error,
+                track_key = %track_key,
+                track_alias,
+                group_id,
+                subgroup_id = ?subgroup_id,
+                "failed to close egress stream sender"
+            );
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::watch;
+
+    use super::*;
+    use crate::modules::{
+        enums::StreamResetCode,
+        relay::egress::group_sender::{RecordingSender, stream_options::test_options as options},
+    };
+
+    #[tokio::test]
+    async fn stopped_subscription_resets_its_open_stream() {
+        // Arrange
+        let sender = RecordingSender::default();
+        let (stop, stopped) = watch::channel(false);
+        let congestion =
+            CongestionWatch::new(None, watch::channel(0).1, watch::channel(0).1, stopped);
+        // A live subgroup with only its header: the task waits for objects.
+        let cache = Arc::new(TrackCache::new());
+        let subgroup_id = StreamSubgroupId::Value(0);
+        cache
+            .append_live_stream_object(
+                3,
+                &subgroup_id,
+                None,
+                DataObject::SubgroupHeader(moqt::SubgroupHeader::new(
+                    1,
+                    3,
+                    moqt::SubgroupId::Value(0),
+                    0,
+                    false,
+                    false,
+                )),
+            )
+            .await;
+        let task = GroupSender::send_stream_task(
+            1,
+            3,
+            subgroup_id,
+            0,
+            options(None),
+            congestion,
+            TrackKey::new("ns", "track"),
+            cache,
+            Box::new(sender.clone()),
+            Arc::new(SubscriptionUsage::new(
+                7,
+                4,
+                "ns".to_string(),
+                "track".to_string(),
+            )),
+        );
+
+        // Act
+        stop.send_replace(true);
+        let skipped = task.await;
+
+        // Assert
+        assert!(skipped.is_none());
+        assert_eq!(
+            *sender.reset_code.lock().unwrap(),
+            Some(StreamResetCode::Cancelled as u64)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::Context;
+use bytes::Bytes;
+
+use crate::modules::core::{
+    data_object::DataObject, publisher::Publisher, subscription::DownstreamSubscription,
+};
+
+use super::coordinator::EgressProbeRequest;
+
+/// Sends a bandwidth probe track: `size_bytes` of filler in group 0 on one
+/// subgroup stream, split into `moqt::BANDWIDTH_PROBE_OBJECT_SIZE` objects,
+/// then closes the stream. Clients time the arrivals to size their first
+/// subscription.
+pub(crate) struct ProbeStreamForwarder {
+    publisher: Box<dyn Publisher>,
+    downstream_subscription: DownstreamSubscription,
+    size_bytes: u64,
+}
+
+impl ProbeStreamForwarder {
+    pub(crate) fn new(request: EgressProbeRequest, publisher: Box<dyn Publisher>) -> Self {
+        Self {
+            publisher,
+            downstream_subscription: request.downstream_subscription,
+            size_bytes: request.size_bytes,
+        }
+    }
+
+    pub(crate) async fn run(self) -> anyhow::Result<()> {
+        let mut sender = self
+            .publisher
+            .new_stream_factory(&self.downstream_subscription)
+            .next()
+            .await
+            .context("failed to open probe stream")?;
+        let header = moqt::SubgroupHeader::new(
+            self.downstream_subscription.track_alias(),
+            0,
+            moqt::SubgroupId::None,
+            0,
+            false,
+            true,
+        );
+        let message_type = header.message_type;
+        sender
+            .send_object(DataObject::SubgroupHeader(header))
+            .await
+            .context("failed to send probe header")?;
+
+        let filler = Bytes::from(vec![0u8; moqt::BANDWIDTH_PROBE_OBJECT_SIZE]);
+        let mut remaining = self.size_bytes;
+        while remaining > 0 {
+            let len = remaining.min(moqt::BANDWIDTH_PROBE_OBJECT_SIZE as u64) as usize;
+            let field = moqt::SubgroupObjectField {
+                message_type,
+                object_id_delta: 0,
+                extension_headers: moqt::ExtensionHeaders::default(),
+                subgroup_object: moqt::SubgroupObject::new_payload(filler.slice(..len)),
+            };
+            sender
+                .send_object(DataObject::SubgroupObject(field))
+                .await
+                .context("failed to send probe object")?;
+            remaining -= len as u64;
+        }
+        tracing::debug!(size_bytes = self.size_bytes, "bandwidth probe sent");
+        sender.close().await.context("failed to close probe stream")
+    }
+}
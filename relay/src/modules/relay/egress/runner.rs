@@ -1,34 +1,56 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 
-use crate::modules::{
-    core::{publisher::Publisher, subscription::DownstreamSubscription},
-    relay::{cache::track_cache::TrackCache, notifications::track_event::TrackEvent},
-    types::TrackKey,
+use crate::{
+    ForwardingPreference,
+    modules::{
+        billing::SubscriptionUsage,
+        core::{publisher::Publisher, subscription::DownstreamSubscription},
+        relay::{cache::track_cache::TrackCache, notifications::track_event::TrackEvent},
+        types::TrackKey,
+    },
 };
 
-use super::{group_sender::GroupSender, scheduler::EgressScheduler};
+use super::{
+    group_sender::{GroupSender, SenderEnd},
+    scheduler::{DeliveryWindow, EgressScheduler, SchedulerEnd},
+    send_queue::SendQueueLimits,
+};
 
 pub(crate) struct EgressRunner {
     track_key: TrackKey,
     cache: Arc<TrackCache>,
     latest_info_sender: broadcast::Sender<TrackEvent>,
-    publisher: Box<dyn Publisher>,
+    publisher: Arc<dyn Publisher>,
     downstream_subscription: DownstreamSubscription,
     ready_sender: oneshot::Sender<anyhow::Result<()>>,
     largest_location: Option<moqt::Location>,
+    usage: Arc<SubscriptionUsage>,
+    delivery_window: watch::Receiver<DeliveryWindow>,
+    forwarding: ForwardingPreference,
+    stall_threshold: Option<Duration>,
+    queue_limits: SendQueueLimits,
+    keyframe_start: bool,
+    stopped: watch::Receiver<bool>,
 }
 
 impl EgressRunner {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         track_key: TrackKey,
         cache: Arc<TrackCache>,
         latest_info_sender: broadcast::Sender<TrackEvent>,
-        publisher: Box<dyn Publisher>,
+        publisher: Arc<dyn Publisher>,
         downstream_subscription: DownstreamSubscription,
         ready_sender: oneshot::Sender<anyhow::Result<()>>,
         largest_location: Option<moqt::Location>,
+        usage: Arc<SubscriptionUsage>,
+        delivery_window: watch::Receiver<DeliveryWindow>,
+        forwarding: ForwardingPreference,
+        stall_threshold: Option<Duration>,
+        queue_limits: SendQueueLimits,
+        keyframe_start: bool,
     ) -> Self {
         Self {
             track_key,
@@ -38,10 +60,26 @@ impl EgressRunner {
             downstream_subscription,
             ready_sender,
             largest_location,
+            usage,
+            delivery_window,
+            forwarding,
+            stall_threshold,
+            queue_limits,
+            keyframe_start,
+            stopped: watch::channel(false).1,
         }
     }
 
-    pub(crate) async fn run(self) -> anyhow::Result<()> {
+    /// Resets the subscription's open streams once `stopped` turns true.
+    pub(crate) fn with_stop_signal(mut self, stopped: watch::Receiver<bool>) -> Self {
+        self.stopped = stopped;
+        self
+    }
+
+    /// Runs until the scheduler stops and every scheduled send has finished,
+    /// until the send queue overflows under the disconnect policy, or until
+    /// the subscription is stopped and its open streams reset.
+    pub(crate) async fn run(self) -> anyhow::Result<SchedulerEnd> {
         let (sender, receiver) = mpsc::channel(64);
 
         let filter_type = self.downstream_subscription.filter_type();
@@ -54,16 +92,41 @@ impl EgressRunner {
             sender,
             self.ready_sender,
             self.largest_location,
-        );
+        )
+        .with_delivery_window(self.delivery_window.clone())
+        .with_keyframe_start(self.keyframe_start);
         let group_sender = GroupSender::new(
             self.track_key,
             self.cache,
             self.publisher,
             self.downstream_subscription,
             receiver,
-        );
+            self.usage,
+            self.forwarding,
+            self.delivery_window,
+            self.stall_threshold,
+            self.queue_limits,
+        )
+        .with_stop_signal(self.stopped);
 
-        tokio::join!(scheduler.run(), group_sender.run());
-        Ok(())
+        let scheduler = scheduler.run();
+        let group_sender = group_sender.run();
+        tokio::pin!(scheduler, group_sender);
+        // A sender giving up on the subscriber ends it at once; otherwise the
+        // scheduled sends finish after the scheduler stops.
+        let end = tokio::select! {
+            end = &mut scheduler => end,
+            sender_end = &mut group_sender => return Ok(match sender_end {
+                SenderEnd::QueueOverflowed => SchedulerEnd::TooFarBehind,
+                SenderEnd::Stopped => SchedulerEnd::Unsubscribed,
+                // Not expected while the scheduler holds the task channel.
+                SenderEnd::Drained => scheduler.await,
+            }),
+        };
+        match group_sender.await {
+            SenderEnd::Drained => Ok(end),
+            SenderEnd::QueueOverflowed => Ok(SchedulerEnd::TooFarBehind),
+            SenderEnd::Stopped => Ok(SchedulerEnd::Unsubscribed),
+        }
     }
 }
@@ -1,6 +1,6 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
-use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 
 use crate::modules::{
     enums::{FilterType, GroupOrder},
@@ -78,6 +78,12 @@ pub(crate) enum GroupSendTask {
 }
 
 impl GroupSendTask {
+    pub(crate) fn group_id(&self) -> u64 {
+        match self {
+            Self::Stream { group_id, .. } | Self::Datagram { group_id, .. } => *group_id,
+        }
+    }
+
     fn key(&self) -> GroupSendTaskKey {
         match self {
             Self::Stream {
@@ -113,6 +119,82 @@ impl StartLocationProgress {
     }
 }
 
+/// Range, forward and priority state a SUBSCRIBE_UPDATE can change while the
+/// runner is live. It gates groups as they open; streams already scheduled run
+/// to the end of their group with the priority they opened with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct DeliveryWindow {
+    pub(crate) start_location: Option<moqt::Location>,
+    pub(crate) end_group: Option<u64>,
+    pub(crate) forward: bool,
+    /// `None` keeps the priority from the original SUBSCRIBE or PUBLISH_OK.
+    pub(crate) subscriber_priority: Option<u8>,
+    /// `None` keeps the DELIVERY_TIMEOUT from the original SUBSCRIBE or
+    /// PUBLISH_OK.
+    pub(crate) delivery_timeout: Option<Duration>,
+}
+
+impl Default for DeliveryWindow {
+    fn default() -> Self {
+        Self {
+            start_location: None,
+            end_group: None,
+            forward: true,
+            subscriber_priority: None,
+            delivery_timeout: None,
+        }
+    }
+}
+
+impl DeliveryWindow {
+    /// Window a subscription starts with: an AbsoluteRange filter bounds it
+    /// at its End Group.
+    pub(crate) fn for_filter(filter_type: &FilterType) -> Self {
+        let end_group = match filter_type {
+            FilterType::AbsoluteRange { end_group, .. } => Some(*end_group),
+            _ => None,
+        };
+        Self {
+            end_group,
+            ..Self::default()
+        }
+    }
+
+    /// Whether `group_id` lies beyond the End Group, so the range is over
+    /// once such a group exists.
+    fn is_past_end(&self, group_id: u64) -> bool {
+        self.end_group.is_some_and(|end_group| group_id > end_group)
+    }
+
+    /// First object to deliver in `group_id`, or `None` when the group falls
+    /// outside the window.
+    fn first_object_id(&self, group_id: u64) -> Option<u64> {
+        if !self.forward || self.end_group.is_some_and(|end_group| group_id > end_group) {
+            return None;
+        }
+        match self.start_location {
+            Some(start) if group_id < start.group_id => None,
+            Some(start) if group_id == start.group_id => Some(start.object_id),
+            _ => Some(0),
+        }
+    }
+}
+
+/// Why a scheduler stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SchedulerEnd {
+    /// The track's event channel closed.
+    TrackClosed,
+    /// A group past the End Group exists; everything in range is scheduled.
+    RangeComplete { end_group: u64 },
+    /// Raised by the runner, not the scheduler: the group sender's queue
+    /// overflowed under the disconnect policy.
+    TooFarBehind,
+    /// Raised by the runner: the subscription was stopped and its open
+    /// streams reset.
+    Unsubscribed,
+}
+
 /// Watches track events and decides which egress units to schedule and when.
 pub(crate) struct EgressScheduler {
     cache: Arc<TrackCache>,
@@ -124,6 +206,8 @@ pub(crate) struct EgressScheduler {
     /// Largest Object at SUBSCRIBE processing time; `None` when no content
     /// has been delivered yet.
     largest_location: Option<moqt::Location>,
+    delivery_window: watch::Receiver<DeliveryWindow>,
+    keyframe_start: bool,
 }
 
 impl EgressScheduler {
@@ -144,28 +228,98 @@ impl EgressScheduler {
             sender,
             ready_sender: Some(ready_sender),
             largest_location,
+            delivery_window: watch::channel(DeliveryWindow::default()).1,
+            keyframe_start: false,
         }
     }
 
-    pub(crate) async fn run(mut self) {
+    /// Starts a Next Group Start subscription at the beginning of the
+    /// current group when it is still cached; see
+    /// [`keyframe_start_location`](Self::keyframe_start_location).
+    pub(crate) fn with_keyframe_start(mut self, keyframe_start: bool) -> Self {
+        self.keyframe_start = keyframe_start;
+        self
+    }
+
+    /// Start of the Largest Object's group, for a Next Group Start
+    /// subscriber (LatestGroup, 0x1, on the wire) with keyframe start on.
+    /// A group begins with a keyframe on video tracks, so starting there
+    /// lets a player render at once instead of waiting for the next group.
+    /// `None` when the group's first object has left the cache (or never
+    /// reached it), which leaves the draft's start in place.
+    async fn keyframe_start_location(&self) -> Option<moqt::Location> {
+        if !self.keyframe_start || !matches!(self.filter_type, FilterType::NextGroupStart) {
+            return None;
+        }
+        let largest = self.largest_location?;
+        self.cache
+            .has_group_start(largest.group_id)
+            .await
+            .then_some(moqt::Location {
+                group_id: largest.group_id,
+                object_id: 0,
+            })
+    }
+
+    /// Follows `delivery_window` instead of delivering every group from the
+    /// filter start on.
+    pub(crate) fn with_delivery_window(
+        mut self,
+        delivery_window: watch::Receiver<DeliveryWindow>,
+    ) -> Self {
+        self.delivery_window = delivery_window;
+        self
+    }
+
+    /// Combines the filter start with the current delivery window.
+    fn admit(&self, progress: &mut StartLocationProgress, group_id: u64) -> Option<u64> {
+        let window_object_id = self.delivery_window.borrow().first_object_id(group_id)?;
+        let object_id = progress.accept(group_id)?;
+        Some(object_id.max(window_object_id))
+    }
+
+    /// Schedules until the track closes or, for a bounded window, until the
+    /// range is complete. Returning drops the task sender, so `GroupSender`
+    /// finishes the streams in flight and stops.
+    pub(crate) async fn run(mut self) -> SchedulerEnd {
         let mut receiver = self.latest_info_sender.subscribe();
         let mut scheduled = HashSet::<GroupSendTaskKey>::new();
 
-        let start = resolve_start_location(&self.filter_type, &self.largest_location);
+        let start = match self.keyframe_start_location().await {
+            Some(start) => {
+                tracing::debug!(
+                    group_id = start.group_id,
+                    "starting at the current group's keyframe"
+                );
+                start
+            }
+            None => resolve_start_location(&self.filter_type, &self.largest_location),
+        };
         self.schedule_cached_objects(&start, &mut scheduled).await;
         let mut progress = StartLocationProgress {
             start_group_id: start.group_id,
             start_object_id: Some(start.object_id),
         };
         self.notify_ready(Ok(()));
+        // The start is clamped past the subscribe-time Largest Object, so a
+        // start beyond the End Group means the range is already in the past.
+        if self.is_past_end(start.group_id) {
+            return self.range_complete();
+        }
 
         loop {
             match receiver.recv().await {
+                Ok(
+                    TrackEvent::StreamOpened { group_id, .. }
+                    | TrackEvent::DatagramOpened { group_id },
+                ) if self.is_past_end(group_id) => {
+                    return self.range_complete();
+                }
                 Ok(TrackEvent::StreamOpened {
                     group_id,
                     subgroup_id,
                 }) => {
-                    if let Some(object_id) = progress.accept(group_id) {
+                    if let Some(object_id) = self.admit(&mut progress, group_id) {
                         let sent = self
                             .schedule_subgroup_objects(
                                 group_id,
@@ -180,7 +334,7 @@ impl EgressScheduler {
                     }
                 }
                 Ok(TrackEvent::DatagramOpened { group_id }) => {
-                    if let Some(object_id) = progress.accept(group_id) {
+                    if let Some(object_id) = self.admit(&mut progress, group_id) {
                         let sent = self
                             .schedule_datagrams(group_id, object_id, &mut scheduled)
                             .await;
@@ -193,11 +347,22 @@ impl EgressScheduler {
                 Err(broadcast::error::RecvError::Lagged(n)) => {
                     tracing::warn!(n, "egress scheduler receiver lagged");
                 }
-                Err(broadcast::error::RecvError::Closed) => break,
+                Err(broadcast::error::RecvError::Closed) => return SchedulerEnd::TrackClosed,
             }
         }
     }
 
+    fn is_past_end(&self, group_id: u64) -> bool {
+        self.delivery_window.borrow().is_past_end(group_id)
+    }
+
+    /// Only reached once `is_past_end` held, so the window has an End Group.
+    fn range_complete(&self) -> SchedulerEnd {
+        SchedulerEnd::RangeComplete {
+            end_group: self.delivery_window.borrow().end_group.unwrap_or_default(),
+        }
+    }
+
     fn notify_ready(&mut self, result: anyhow::Result<()>) {
         if let Some(sender) = self.ready_sender.take() {
             let _ = sender.send(result);
@@ -212,9 +377,6 @@ impl EgressScheduler {
         group_id: u64,
         scheduled: &mut HashSet<GroupSendTaskKey>,
     ) {
-        if matches!(self.group_order, GroupOrder::Descending) {
-            return;
-        }
         self.schedule_cached_objects(
             &moqt::Location {
                 group_id: group_id + 1,
@@ -226,10 +388,11 @@ impl EgressScheduler {
     }
 
     /// Schedules consecutive cached groups starting at the filter Start
-    /// Location.
+    /// Location, oldest first or, for `Descending`, newest first.
     ///
     /// With starts clamped to the subscribe-time Largest Object this never
-    /// replays the past; what it covers is delivery that events cannot:
+    /// replays the past beyond a keyframe start's current group; what it
+    /// covers is delivery that events cannot:
     /// the rest of the group already open at the Start Location (its open
     /// event predates this scheduler), groups arriving between the
     /// subscribe-time snapshot and event subscription, and lag recovery.
@@ -238,25 +401,37 @@ impl EgressScheduler {
         start: &moqt::Location,
         scheduled: &mut HashSet<GroupSendTaskKey>,
     ) {
+        let mut groups = Vec::new();
         let mut next = start.group_id;
         while self.cache.has_stream_group(next).await || self.cache.has_datagram_group(next).await {
-            let object_id = if next == start.group_id {
+            groups.push(next);
+            next += 1;
+        }
+        if matches!(self.group_order, GroupOrder::Descending) {
+            groups.reverse();
+        }
+
+        for group_id in groups {
+            let object_id = if group_id == start.group_id {
                 start.object_id
             } else {
                 0
             };
-            for subgroup_id in self.cache.stream_subgroups(next).await {
+            let window_object_id = self.delivery_window.borrow().first_object_id(group_id);
+            let Some(window_object_id) = window_object_id else {
+                continue;
+            };
+            let object_id = object_id.max(window_object_id);
+            for subgroup_id in self.cache.stream_subgroups(group_id).await {
                 let _ = self
-                    .schedule_subgroup_objects(next, subgroup_id, object_id, scheduled)
+                    .schedule_subgroup_objects(group_id, subgroup_id, object_id, scheduled)
                     .await;
             }
-            if self.cache.has_datagram_group(next).await {
-                let _ = self.schedule_datagrams(next, object_id, scheduled).await;
-            }
-            if matches!(self.group_order, GroupOrder::Descending) {
-                return;
+            if self.cache.has_datagram_group(group_id).await {
+                let _ = self
+                    .schedule_datagrams(group_id, object_id, scheduled)
+                    .await;
             }
-            next += 1;
         }
     }
 
@@ -392,6 +567,111 @@ mod tests {
         handle.abort();
     }
 
+    // Groups cached between the subscribe-time snapshot and the scheduler
+    // start go out newest first for a descending subscriber.
+    #[tokio::test]
+    async fn descending_order_schedules_cached_groups_newest_first() {
+        // Arrange
+        let cache = Arc::new(TrackCache::new());
+        let subgroup = StreamSubgroupId::Value(0);
+        for group_id in 0..3 {
+            let mut prev = None;
+            append_stream(&cache, group_id, &subgroup, &mut prev, make_header()).await;
+            append_stream(&cache, group_id, &subgroup, &mut prev, make_object(0)).await;
+        }
+        let (info_tx, _info_rx) = broadcast::channel(16);
+        let (task_tx, mut task_rx) = mpsc::channel(16);
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let scheduler = EgressScheduler::new(
+            cache,
+            info_tx,
+            FilterType::LargestObject,
+            GroupOrder::Descending,
+            task_tx,
+            ready_tx,
+            None,
+        );
+
+        // Act
+        let handle = tokio::spawn(scheduler.run());
+        ready_rx
+            .await
+            .expect("scheduler should signal readiness")
+            .expect("scheduler should start");
+
+        // Assert
+        let mut group_ids = Vec::new();
+        while let Ok(task) = task_rx.try_recv() {
+            match task {
+                GroupSendTask::Stream { group_id, .. } => group_ids.push(group_id),
+                _ => panic!("expected a Stream task"),
+            }
+        }
+        assert_eq!(group_ids, vec![2, 1, 0]);
+        handle.abort();
+    }
+
+    async fn first_task_for_next_group_start(keyframe_start: bool) -> Option<(u64, u64)> {
+        let cache = Arc::new(TrackCache::new());
+        let subgroup = StreamSubgroupId::Value(0);
+        for group_id in 0..2 {
+            let mut prev = None;
+            append_stream(&cache, group_id, &subgroup, &mut prev, make_header()).await;
+            append_stream(&cache, group_id, &subgroup, &mut prev, make_object(0)).await;
+        }
+        let (info_tx, _info_rx) = broadcast::channel(16);
+        let (task_tx, mut task_rx) = mpsc::channel(16);
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let scheduler = EgressScheduler::new(
+            cache,
+            info_tx,
+            FilterType::NextGroupStart,
+            GroupOrder::Ascending,
+            task_tx,
+            ready_tx,
+            Some(moqt::Location {
+                group_id: 1,
+                object_id: 0,
+            }),
+        )
+        .with_keyframe_start(keyframe_start);
+        let handle = tokio::spawn(scheduler.run());
+        ready_rx
+            .await
+            .expect("scheduler should signal readiness")
+            .expect("scheduler should start");
+        let task = task_rx.try_recv().ok().map(|task| match task {
+            GroupSendTask::Stream {
+                group_id,
+                object_id,
+                ..
+            } => (group_id, object_id),
+            _ => panic!("expected a Stream task"),
+        });
+        handle.abort();
+        task
+    }
+
+    // A player cannot decode from the middle of a group, so a client's
+    // LatestGroup subscription starts with the current group's keyframe.
+    #[tokio::test]
+    async fn keyframe_start_replays_the_current_group_from_its_first_object() {
+        // Arrange / Act
+        let task = first_task_for_next_group_start(true).await;
+
+        // Assert
+        assert_eq!(task, Some((1, 0)));
+    }
+
+    #[tokio::test]
+    async fn next_group_start_without_keyframe_start_waits_for_the_next_group() {
+        // Arrange / Act
+        let task = first_task_for_next_group_start(false).await;
+
+        // Assert
+        assert_eq!(task, None);
+    }
+
     // Subscriptions only deliver newly published or received objects;
     // objects from the past are retrieved with FETCH (§9.7). An
     // AbsoluteStart in the past is therefore raised to just after the
@@ -571,4 +851,122 @@ mod tests {
         }
         handle.abort();
     }
+
+    #[tokio::test]
+    async fn delivery_window_update_gates_groups_opened_afterwards() {
+        // Arrange
+        let cache = Arc::new(TrackCache::new());
+        let (info_tx, _info_rx) = broadcast::channel(16);
+        let (task_tx, mut task_rx) = mpsc::channel(16);
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let (window_tx, window_rx) = watch::channel(DeliveryWindow {
+            forward: false,
+            ..DeliveryWindow::default()
+        });
+        let scheduler = EgressScheduler::new(
+            cache,
+            info_tx.clone(),
+            FilterType::LargestObject,
+            GroupOrder::Ascending,
+            task_tx,
+            ready_tx,
+            None,
+        )
+        .with_delivery_window(window_rx);
+        let handle = tokio::spawn(scheduler.run());
+        ready_rx
+            .await
+            .expect("scheduler should signal readiness")
+            .expect("scheduler should start");
+        let open = |group_id| TrackEvent::StreamOpened {
+            group_id,
+            subgroup_id: StreamSubgroupId::Value(0),
+        };
+
+        // Act
+        info_tx
+            .send(open(1))
+            .expect("event should reach the scheduler");
+        tokio::task::yield_now().await;
+        window_tx.send_replace(DeliveryWindow {
+            start_location: Some(moqt::Location {
+                group_id: 3,
+                object_id: 2,
+            }),
+            end_group: Some(3),
+            ..DeliveryWindow::default()
+        });
+        for group_id in 2..5 {
+            info_tx
+                .send(open(group_id))
+                .expect("event should reach the scheduler");
+        }
+
+        // Assert
+        let task = task_rx.recv().await.expect("a task should be scheduled");
+        match task {
+            GroupSendTask::Stream {
+                group_id,
+                object_id,
+                ..
+            } => {
+                assert_eq!(group_id, 3);
+                assert_eq!(object_id, 2);
+            }
+            _ => panic!("expected a Stream task"),
+        }
+        tokio::task::yield_now().await;
+        assert!(
+            task_rx.try_recv().is_err(),
+            "groups outside the window must not be scheduled"
+        );
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn absolute_range_completes_when_a_group_past_the_end_opens() {
+        // Arrange
+        let filter_type = FilterType::AbsoluteRange {
+            location: crate::modules::enums::Location {
+                group_id: 1,
+                object_id: 0,
+            },
+            end_group: 2,
+        };
+        let (info_tx, _info_rx) = broadcast::channel(16);
+        let (task_tx, mut task_rx) = mpsc::channel(16);
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let (_window_tx, window_rx) = watch::channel(DeliveryWindow::for_filter(&filter_type));
+        let scheduler = EgressScheduler::new(
+            Arc::new(TrackCache::new()),
+            info_tx.clone(),
+            filter_type,
+            GroupOrder::Ascending,
+            task_tx,
+            ready_tx,
+            None,
+        )
+        .with_delivery_window(window_rx);
+        let handle = tokio::spawn(scheduler.run());
+        ready_rx
+            .await
+            .expect("scheduler should signal readiness")
+            .expect("scheduler should start");
+
+        // Act
+        for group_id in 1..4 {
+            info_tx
+                .send(TrackEvent::DatagramOpened { group_id })
+                .expect("event should reach the scheduler");
+        }
+        let end = handle.await.expect("scheduler should not panic");
+
+        // Assert
+        assert_eq!(end, SchedulerEnd::RangeComplete { end_group: 2 });
+        let mut scheduled_groups = Vec::new();
+        while let Ok(GroupSendTask::Datagram { group_id, .. }) = task_rx.try_recv() {
+            scheduled_groups.push(group_id);
+        }
+        assert_eq!(scheduled_groups, vec![1, 2]);
+    }
 }
@@ -0,0 +1,220 @@
+use std::collections::{BTreeMap, HashMap};
+
+use tokio::task;
+
+use crate::{QueueOverflowPolicy, modules::types::TrackKey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SendQueueLimits {
+    /// Groups one subscription may have in flight; 0 is unbounded.
+    pub(crate) max_groups: usize,
+    pub(crate) overflow: QueueOverflowPolicy,
+}
+
+/// What to do with a group offered to a full queue.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Admission {
+    Accept,
+    /// Accept it after giving up this older group in flight.
+    Evict(u64),
+    /// Drop the offered group: everything in flight is newer.
+    DropIncoming,
+    Disconnect,
+}
+
+/// Groups one downstream subscription has in flight, bounded by
+/// [`SendQueueLimits`], with the counters reported when it ends.
+pub(crate) struct SendQueue {
+    limits: SendQueueLimits,
+    /// Send tasks per group in flight; a group can have several subgroups.
+    groups: BTreeMap<u64, usize>,
+    tasks: HashMap<task::Id, u64>,
+    high_water: usize,
+    /// Groups dropped or refused, or times the queue paused.
+    overflows: u64,
+    paused: bool,
+}
+
+impl SendQueue {
+    pub(crate) fn new(limits: SendQueueLimits) -> Self {
+        Self {
+            limits,
+            groups: BTreeMap::new(),
+            tasks: HashMap::new(),
+            high_water: 0,
+            overflows: 0,
+            paused: false,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.limits.max_groups > 0 && self.groups.len() >= self.limits.max_groups
+    }
+
+    /// Whether the sender should stop taking groups for now.
+    pub(crate) fn is_paused(&mut self) -> bool {
+        let paused = self.limits.overflow == QueueOverflowPolicy::Pause && self.is_full();
+        if paused && !self.paused {
+            self.overflows += 1;
+        }
+        self.paused = paused;
+        paused
+    }
+
+    /// Decides on a group about to be sent. A group already in flight is
+    /// always accepted, since another of its subgroups costs no new slot.
+    pub(crate) fn admit(&mut self, group_id: u64) -> Admission {
+        if self.groups.contains_key(&group_id) || !self.is_full() {
+            return Admission::Accept;
+        }
+        if self.limits.overflow == QueueOverflowPolicy::Pause {
+            // Not offered while paused.
+            return Admission::Accept;
+        }
+        self.overflows += 1;
+        if self.limits.overflow == QueueOverflowPolicy::Disconnect {
+            return Admission::Disconnect;
+        }
+        match self.groups.first_key_value() {
+            Some((&oldest, _)) if oldest < group_id => {
+                self.groups.remove(&oldest);
+                Admission::Evict(oldest)
+            }
+            _ => Admission::DropIncoming,
+        }
+    }
+
+    pub(crate) fn started(&mut self, task_id: task::Id, group_id: u64) {
+        self.tasks.insert(task_id, group_id);
+        *self.groups.entry(group_id).or_default() += 1;
+        self.high_water = self.high_water.max(self.groups.len());
+    }
+
+    pub(crate) fn finished(&mut self, task_id: task::Id) {
+        let Some(group_id) = self.tasks.remove(&task_id) else {
+            return;
+        };
+        // An evicted group has already left `groups`.
+        if let Some(count) = self.groups.get_mut(&group_id) {
+            *count -= 1;
+            if *count == 0 {
+                self.groups.remove(&group_id);
+            }
+        }
+    }
+
+    pub(crate) fn report(&self, track_key: &TrackKey) {
+        if self.overflows > 0 {
+            tracing::warn!(
+                %track_key,
+                high_water_groups = self.high_water,
+                overflows = self.overflows,
+                policy = ?self.limits.overflow,
+                "egress send queue overflowed"
+            );
+        } else {
+            tracing::debug!(
+                %track_key,
+                high_water_groups = self.high_water,
+                "egress send queue finished"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(max_groups: usize, overflow: QueueOverflowPolicy) -> SendQueue {
+        SendQueue::new(SendQueueLimits {
+            max_groups,
+            overflow,
+        })
+    }
+
+    /// Task ids only come from spawned tasks.
+    async fn task_ids(count: usize) -> Vec<task::Id> {
+        let mut ids = Vec::new();
+        for _ in 0..count {
+            ids.push(tokio::spawn(async {}).id());
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn full_queue_pauses_until_a_group_finishes() {
+        // Arrange
+        let mut queue = queue(2, QueueOverflowPolicy::Pause);
+        let ids = task_ids(2).await;
+        queue.started(ids[0], 1);
+        queue.started(ids[1], 2);
+
+        // Act
+        let paused = queue.is_paused();
+        queue.finished(ids[0]);
+
+        // Assert
+        assert!(paused);
+        assert!(!queue.is_paused());
+        assert_eq!(queue.high_water, 2);
+        assert_eq!(queue.overflows, 1);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_evicts_the_oldest_group_in_flight() {
+        // Arrange
+        let mut queue = queue(2, QueueOverflowPolicy::DropOldestGroup);
+        let ids = task_ids(3).await;
+        queue.started(ids[0], 4);
+        queue.started(ids[1], 5);
+
+        // Act
+        let newer = queue.admit(6);
+        queue.started(ids[2], 6);
+        let older = queue.admit(3);
+
+        // Assert
+        assert_eq!(newer, Admission::Evict(4));
+        assert_eq!(older, Admission::DropIncoming);
+        assert_eq!(queue.overflows, 2);
+    }
+
+    #[tokio::test]
+    async fn another_subgroup_of_a_group_in_flight_is_accepted() {
+        // Arrange
+        let mut queue = queue(1, QueueOverflowPolicy::Disconnect);
+        let ids = task_ids(1).await;
+        queue.started(ids[0], 7);
+
+        // Act / Assert
+        assert_eq!(queue.admit(7), Admission::Accept);
+        assert_eq!(queue.admit(8), Admission::Disconnect);
+    }
+
+    #[tokio::test]
+    async fn evicted_group_does_not_come_back_when_its_tasks_finish() {
+        // Arrange
+        let mut queue = queue(1, QueueOverflowPolicy::DropOldestGroup);
+        let ids = task_ids(2).await;
+        queue.started(ids[0], 1);
+        queue.admit(2);
+        queue.started(ids[1], 2);
+
+        // Act
+        queue.finished(ids[0]);
+
+        // Assert
+        assert_eq!(queue.groups.keys().copied().collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn zero_groups_is_unbounded() {
+        // Arrange
+        let mut queue = queue(0, QueueOverflowPolicy::Disconnect);
+
+        // Act / Assert
+        assert_eq!(queue.admit(1), Admission::Accept);
+        assert!(!queue.is_paused());
+    }
+}
@@ -1,5 +1,6 @@
 pub(crate) mod datagram_reader;
 pub(crate) mod fetch_ingest;
 pub(crate) mod ingress_coordinator;
+pub(crate) mod rate_limiter;
 pub(crate) mod stream_ingress_task;
 pub(crate) mod stream_reader;
@@ -6,9 +6,11 @@ use tokio::{
 };
 
 use crate::modules::{
-    core::data_receiver::datagram_receiver::DatagramReceiver,
+    core::{data_object::DataObject, data_receiver::datagram_receiver::DatagramReceiver},
+    idle_reaper::SessionActivity,
     relay::{
         cache::store::TrackCacheStore,
+        ingress::rate_limiter::{IngressRateLimiter, TrackRateLimiter},
         notifications::{track_event::TrackEvent, track_notifier::ObjectNotifyProducerMap},
     },
     types::{SessionId, TrackKey},
@@ -18,6 +20,8 @@ pub(crate) struct DatagramReceiveStart {
     pub(crate) track_key: TrackKey,
     pub(crate) publisher_session_id: SessionId,
     pub(crate) receiver: Box<dyn DatagramReceiver>,
+    /// The publisher session's activity, kept fresh by ingested objects.
+    pub(crate) activity: Option<Arc<SessionActivity>>,
 }
 
 pub(crate) enum DatagramReceiveCommand {
@@ -37,6 +41,7 @@ impl DatagramReader {
         mut receiver: mpsc::Receiver<DatagramReceiveCommand>,
         cache_store: Arc<TrackCacheStore>,
         object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
+        rate_limiter: Arc<IngressRateLimiter>,
     ) -> Self {
         let join_handle = tokio::spawn(async move {
             let mut joinset = tokio::task::JoinSet::new();
@@ -46,7 +51,7 @@ impl DatagramReader {
                     Some(command) = receiver.recv() => {
                         match command {
                             DatagramReceiveCommand::Start(cmd) => {
-                                let DatagramReceiveStart { track_key, publisher_session_id, receiver } = cmd;
+                                let DatagramReceiveStart { track_key, publisher_session_id, receiver, activity } = cmd;
                                 // draft-14 §8.2 Multiple Publishers: for now keep the first publisher and
                                 // ignore later ones. FIXME: GOAWAY migration needs ingesting from multiple
                                 // publishers with per-object dedup (SHOULD); first-writer-wins is a stopgap.
@@ -57,9 +62,11 @@ impl DatagramReader {
 
                                 let (stop_sender, stop_receiver) = watch::channel(false);
                                 stop_senders.insert(track_key.clone(), (stop_sender, publisher_session_id));
+                                cache_store.set_owner(&track_key, publisher_session_id);
 
                                 let cache_store = cache_store.clone();
                                 let sender_map = object_notify_producer_map.clone();
+                                let track_rate_limiter = rate_limiter.track(&track_key, publisher_session_id);
                                 joinset.spawn(async move {
                                     Self::read_loop(
                                         track_key.clone(),
@@ -67,6 +74,8 @@ impl DatagramReader {
                                         stop_receiver,
                                         cache_store,
                                         sender_map,
+                                        track_rate_limiter,
+                                        activity,
                                     )
                                     .await;
                                     track_key
@@ -108,9 +117,12 @@ impl DatagramReader {
         mut stop_receiver: watch::Receiver<bool>,
         cache_store: Arc<TrackCacheStore>,
         object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
+        rate_limiter: Option<Arc<TrackRateLimiter>>,
+        activity: Option<Arc<SessionActivity>>,
     ) {
         let mut current_group_id: Option<u64> = None;
         let mut prev_object_id: Option<u64> = None;
+        let mut duplicate_datagrams = 0u64;
         let cache = cache_store.get_or_create(&track_key);
         cache.begin_live_ingest();
         let notify = object_notify_producer_map.get_or_create(&track_key);
@@ -120,7 +132,7 @@ impl DatagramReader {
                     if let Some(group_id) = current_group_id {
                         cache.close_datagram_group(group_id).await;
                     }
-                    tracing::info!(%track_key, "datagram reader stopped");
+                    tracing::info!(%track_key, duplicate_datagrams, "datagram reader stopped");
                     cache.end_live_ingest();
                     return;
                 }
@@ -129,6 +141,18 @@ impl DatagramReader {
 
             match receive_result {
                 Ok(object) => {
+                    if let Some(activity) = &activity {
+                        activity.touch();
+                    }
+                    // Status datagrams end groups, so only payloads count
+                    // against the rate.
+                    if ends_group(&object).is_none()
+                        && let Some(rate_limiter) = &rate_limiter
+                        && !rate_limiter.try_admit(object.payload_len())
+                    {
+                        tracing::trace!(%track_key, "dropping datagram over the rate limit");
+                        continue;
+                    }
                     let group_id = object.group_id().or(current_group_id).unwrap_or(0);
                     if current_group_id != Some(group_id) {
                         if let Some(old_group) = current_group_id {
@@ -140,16 +164,33 @@ impl DatagramReader {
                     }
                     let object_id = object.resolve_absolute_object_id(prev_object_id);
                     prev_object_id = object_id;
-                    cache
+                    let ends_group = ends_group(&object);
+                    if !cache
                         .append_datagram_object(group_id, object_id, object)
-                        .await;
+                        .await
+                        && object_id.is_some()
+                    {
+                        // Resent, typically after the publisher reconnected;
+                        // subscribers already have the cached copy.
+                        duplicate_datagrams += 1;
+                        if duplicate_datagrams == 1 {
+                            tracing::info!(%track_key, group_id, ?object_id, "ignoring datagrams the publisher already sent");
+                        }
+                    }
+                    // Close the group now rather than when the next one opens,
+                    // so egress forwards the status and finishes the group.
+                    if let Some(end_reason) = ends_group {
+                        tracing::debug!(%track_key, group_id, end_reason, "datagram group ended");
+                        cache.close_datagram_group(group_id).await;
+                        let _ = notify.send(TrackEvent::EndOfGroup);
+                    }
                 }
                 Err(_) => {
                     // Ensure the last group is closed before exiting.
                     if let Some(group_id) = current_group_id {
                         cache.close_datagram_group(group_id).await;
                     }
-                    tracing::debug!(%track_key, "datagram receiver ended");
+                    tracing::debug!(%track_key, duplicate_datagrams, "datagram receiver ended");
                     cache.end_live_ingest();
                     return;
                 }
@@ -158,8 +199,131 @@ impl DatagramReader {
     }
 }
 
+/// Why `object` is the last datagram of its group, if it is.
+fn ends_group(object: &DataObject) -> Option<&'static str> {
+    let DataObject::ObjectDatagram(datagram) = object else {
+        return None;
+    };
+    if !datagram.field.ends_group() {
+        return None;
+    }
+    Some(match datagram.field.payload() {
+        moqt::ObjectDatagramPayload::Status(moqt::wire::ObjectStatus::EndOfTrack) => "end_of_track",
+        _ => "end_of_group",
+    })
+}
+
 impl Drop for DatagramReader {
     fn drop(&mut self) {
         self.join_handle.abort();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::time::Duration;
+
+    use bytes::Bytes;
+    use moqt::{DatagramField, ObjectDatagram, wire::ObjectStatus};
+
+    use super::*;
+
+    // Yields the scripted datagrams, then waits like an idle session.
+    struct ScriptedDatagramReceiver {
+        objects: VecDeque<DataObject>,
+    }
+
+    #[async_trait::async_trait]
+    impl DatagramReceiver for ScriptedDatagramReceiver {
+        async fn receive_object(&mut self) -> anyhow::Result<DataObject> {
+            if let Some(object) = self.objects.pop_front() {
+                return Ok(object);
+            }
+            std::future::pending::<()>().await;
+            unreachable!()
+        }
+    }
+
+    fn make_payload(group_id: u64, object_id: u64) -> DataObject {
+        DataObject::ObjectDatagram(ObjectDatagram::new(
+            0,
+            group_id,
+            DatagramField::Payload0x04 {
+                object_id,
+                publisher_priority: 0,
+                payload: Bytes::from(vec![1]),
+            },
+        ))
+    }
+
+    fn make_status(group_id: u64, object_id: u64, status: ObjectStatus) -> DataObject {
+        DataObject::ObjectDatagram(ObjectDatagram::new(
+            0,
+            group_id,
+            DatagramField::Status0x20 {
+                object_id,
+                publisher_priority: 0,
+                status,
+            },
+        ))
+    }
+
+    async fn assert_group_ends_with_status(status: ObjectStatus) {
+        let track_key = TrackKey::new("ns", "track");
+        let cache_store = Arc::new(TrackCacheStore::new());
+        let notify_map = Arc::new(ObjectNotifyProducerMap::new());
+        let mut event_receiver = notify_map.get_or_create(&track_key).subscribe();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let receiver = ScriptedDatagramReceiver {
+            objects: VecDeque::from([make_payload(3, 0), make_status(3, 1, status)]),
+        };
+
+        let read_task = tokio::spawn(DatagramReader::read_loop(
+            track_key.clone(),
+            Box::new(receiver),
+            stop_receiver,
+            cache_store.clone(),
+            notify_map.clone(),
+            None,
+            None,
+        ));
+
+        let cache = cache_store.get_or_create(&track_key);
+        let (status_id, _, _) = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                if let Some(found) = cache.datagram_cursor(3, 1).await.next().await {
+                    return found;
+                }
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("status datagram should be cached");
+        assert_eq!(status_id, 1);
+        let mut cursor = cache.datagram_cursor(3, 2).await;
+        let closed = tokio::time::timeout(Duration::from_secs(1), cursor.next())
+            .await
+            .expect("group should be closed without waiting for the next group");
+        assert!(closed.is_none());
+        assert!(matches!(
+            event_receiver.try_recv(),
+            Ok(TrackEvent::DatagramOpened { group_id: 3 })
+        ));
+        assert!(matches!(
+            event_receiver.try_recv(),
+            Ok(TrackEvent::EndOfGroup)
+        ));
+        read_task.abort();
+    }
+
+    #[tokio::test]
+    async fn end_of_group_status_closes_datagram_group() {
+        assert_group_ends_with_status(ObjectStatus::EndOfGroup).await;
+    }
+
+    #[tokio::test]
+    async fn end_of_track_status_closes_datagram_group() {
+        assert_group_ends_with_status(ObjectStatus::EndOfTrack).await;
+    }
+}
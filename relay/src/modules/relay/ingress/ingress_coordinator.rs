@@ -11,6 +11,7 @@ use crate::modules::{
         cache::store::TrackCacheStore,
         ingress::{
             datagram_reader::{DatagramReader, DatagramReceiveCommand, DatagramReceiveStart},
+            rate_limiter::IngressRateLimiter,
             stream_ingress_task::{StreamIngressCommand, StreamIngressTask, StreamReceiveStart},
         },
         notifications::track_notifier::ObjectNotifyProducerMap,
@@ -48,6 +49,7 @@ impl IngressCoordinator {
         session_repo: Arc<tokio::sync::Mutex<SessionRepository>>,
         cache_store: Arc<TrackCacheStore>,
         object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
+        rate_limiter: Arc<IngressRateLimiter>,
     ) -> Self {
         let (stream_tx, stream_rx) = mpsc::channel::<StreamIngressCommand>(64);
         let (datagram_tx, datagram_rx) = mpsc::channel::<DatagramReceiveCommand>(64);
@@ -55,9 +57,14 @@ impl IngressCoordinator {
             stream_rx,
             cache_store.clone(),
             object_notify_producer_map.clone(),
+            rate_limiter.clone(),
+        );
+        let datagram_reader = DatagramReader::run(
+            datagram_rx,
+            cache_store,
+            object_notify_producer_map,
+            rate_limiter,
         );
-        let datagram_reader =
-            DatagramReader::run(datagram_rx, cache_store, object_notify_producer_map);
 
         let (command_sender, mut command_receiver) = mpsc::channel::<IngressCommand>(512);
         let session_repo_for_runner = session_repo;
@@ -83,7 +90,7 @@ impl IngressCoordinator {
                             track_name = %command.track_name,
                             "ingress start command received"
                         );
-                        let (subscriber, publisher_session_span) = {
+                        let (subscriber, publisher_session_span, activity) = {
                             let session_repo = session_repo_for_runner.lock().await;
                             let Some(subscriber) = session_repo.subscriber(command.publisher_session_id) else {
                                 tracing::info!(%track_key, "publisher session not found for subscription");
@@ -92,7 +99,8 @@ impl IngressCoordinator {
                             let publisher_session_span = session_repo
                                 .session_span(command.publisher_session_id)
                                 .unwrap_or_else(|| command.parent_span.clone());
-                            (subscriber, publisher_session_span)
+                            let activity = session_repo.activity(command.publisher_session_id);
+                            (subscriber, publisher_session_span, activity)
                         };
                         tracing::info!(%track_key, "upstream subscriber found; spawning data receiver task");
                         let stream_tx = stream_tx.clone();
@@ -161,6 +169,7 @@ impl IngressCoordinator {
                                             publisher_session_id: command.publisher_session_id,
                                             factory,
                                             track_span: dataplane_track_span,
+                                            activity,
                                         }))
                                         .await
                                         .is_ok()
@@ -176,6 +185,7 @@ impl IngressCoordinator {
                                             track_key: track_key.clone(),
                                             publisher_session_id: command.publisher_session_id,
                                             receiver: datagram_receiver,
+                                            activity,
                                         }))
                                         .await
                                         .is_ok()
@@ -0,0 +1,294 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::{
+    OverLimitAction, RateLimits,
+    modules::types::{SessionId, TrackKey},
+};
+
+/// Token bucket holding up to one second of its rate. Taking more than it
+/// holds leaves it in debt, which later objects wait out.
+#[derive(Debug)]
+struct TokenBucket {
+    bytes_per_sec: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec: bytes_per_sec as f64,
+            tokens: bytes_per_sec as f64,
+            updated_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.bytes_per_sec).min(self.bytes_per_sec);
+        self.updated_at = now;
+    }
+
+    /// How long until `bytes` could be taken without debt.
+    fn shortfall(&mut self, bytes: f64, now: Instant) -> Duration {
+        self.refill(now);
+        if self.tokens >= bytes {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((bytes - self.tokens) / self.bytes_per_sec)
+        }
+    }
+
+    fn take(&mut self, bytes: f64) {
+        self.tokens -= bytes;
+    }
+}
+
+type SharedBucket = Arc<Mutex<TokenBucket>>;
+
+/// Enforces [`RateLimits`] on what publishers send: one bucket per
+/// publisher session, shared by its tracks, and one per track ingest.
+#[derive(Debug)]
+pub(crate) struct IngressRateLimiter {
+    limits: RateLimits,
+    sessions: Mutex<HashMap<SessionId, SharedBucket>>,
+}
+
+impl IngressRateLimiter {
+    pub(crate) fn new(limits: RateLimits) -> Arc<Self> {
+        if limits.is_enabled() {
+            tracing::info!(?limits, "Rate limiting publishers");
+        }
+        Arc::new(Self {
+            limits,
+            sessions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Limiter of one track ingest; `None` when no rate is configured.
+    pub(crate) fn track(
+        self: &Arc<Self>,
+        track_key: &TrackKey,
+        publisher_session_id: SessionId,
+    ) -> Option<Arc<TrackRateLimiter>> {
+        if !self.limits.is_enabled() {
+            return None;
+        }
+        let session = (self.limits.session_bytes_per_sec > 0).then(|| {
+            self.sessions
+                .lock()
+                .unwrap()
+                .entry(publisher_session_id)
+                .or_insert_with(|| {
+                    Arc::new(Mutex::new(TokenBucket::new(
+                        self.limits.session_bytes_per_sec,
+                    )))
+                })
+                .clone()
+        });
+        let track = (self.limits.track_bytes_per_sec > 0)
+            .then(|| Mutex::new(TokenBucket::new(self.limits.track_bytes_per_sec)));
+        Some(Arc::new(TrackRateLimiter {
+            limiter: self.clone(),
+            track_key: track_key.clone(),
+            publisher_session_id,
+            session,
+            track,
+            newest_group: AtomicU64::new(0),
+        }))
+    }
+}
+
+/// Rate limit of one track ingest, shared by the readers of its streams.
+#[derive(Debug)]
+pub(crate) struct TrackRateLimiter {
+    limiter: Arc<IngressRateLimiter>,
+    track_key: TrackKey,
+    publisher_session_id: SessionId,
+    session: Option<SharedBucket>,
+    track: Option<Mutex<TokenBucket>>,
+    /// Newest group id seen plus one; 0 before the first group.
+    newest_group: AtomicU64,
+}
+
+impl TrackRateLimiter {
+    pub(crate) fn group_opened(&self, group_id: u64) {
+        self.newest_group
+            .fetch_max(group_id.saturating_add(1), Ordering::Relaxed);
+    }
+
+    fn is_old_group(&self, group_id: u64) -> bool {
+        group_id.saturating_add(1) < self.newest_group.load(Ordering::Relaxed)
+    }
+
+    fn shortfall(&self, bytes: f64, now: Instant) -> Duration {
+        let session = self
+            .session
+            .as_ref()
+            .map(|bucket| bucket.lock().unwrap().shortfall(bytes, now));
+        let track = self
+            .track
+            .as_ref()
+            .map(|bucket| bucket.lock().unwrap().shortfall(bytes, now));
+        session.into_iter().chain(track).max().unwrap_or_default()
+    }
+
+    fn take(&self, bytes: f64) {
+        if let Some(bucket) = &self.session {
+            bucket.lock().unwrap().take(bytes);
+        }
+        if let Some(bucket) = &self.track {
+            bucket.lock().unwrap().take(bytes);
+        }
+    }
+
+    /// Admits `bytes` of a stream object in `group_id`, waiting while the
+    /// rate is exceeded. Returns false, without waiting, when the object
+    /// should be dropped instead: under [`OverLimitAction::DropOldGroups`]
+    /// for a group older than the newest one on the track.
+    pub(crate) async fn admit(&self, group_id: u64, bytes: usize) -> bool {
+        let bytes = bytes as f64;
+        let wait = self.shortfall(bytes, Instant::now());
+        if !wait.is_zero() {
+            if self.limiter.limits.over_limit == OverLimitAction::DropOldGroups
+                && self.is_old_group(group_id)
+            {
+                return false;
+            }
+            tracing::trace!(track_key = %self.track_key, ?wait, "pacing publisher over its rate");
+        }
+        self.take(bytes);
+        tokio::time::sleep(wait).await;
+        true
+    }
+
+    /// Admits `bytes` of a datagram if the rate allows them now.
+    pub(crate) fn try_admit(&self, bytes: usize) -> bool {
+        let bytes = bytes as f64;
+        if !self.shortfall(bytes, Instant::now()).is_zero() {
+            return false;
+        }
+        self.take(bytes);
+        true
+    }
+}
+
+impl Drop for TrackRateLimiter {
+    fn drop(&mut self) {
+        // Forget the session's bucket with its last track.
+        let Some(bucket) = self.session.take() else {
+            return;
+        };
+        let mut sessions = self.limiter.sessions.lock().unwrap();
+        if Arc::strong_count(&bucket) == 2 {
+            sessions.remove(&self.publisher_session_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(
+        session_bytes_per_sec: u64,
+        track_bytes_per_sec: u64,
+        over_limit: OverLimitAction,
+    ) -> Arc<IngressRateLimiter> {
+        IngressRateLimiter::new(RateLimits {
+            session_bytes_per_sec,
+            track_bytes_per_sec,
+            over_limit,
+        })
+    }
+
+    #[test]
+    fn no_limit_configured_needs_no_track_limiter() {
+        // Arrange
+        let limiter = limiter(0, 0, OverLimitAction::Pace);
+
+        // Act
+        let track = limiter.track(&TrackKey::new("live", "video"), 1);
+
+        // Assert
+        assert!(track.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn objects_over_the_rate_are_paced() {
+        // Arrange
+        let limiter = limiter(0, 1000, OverLimitAction::Pace);
+        let track = limiter.track(&TrackKey::new("live", "video"), 1).unwrap();
+        let started = Instant::now();
+
+        // Act
+        let burst = track.admit(0, 1000).await;
+        let burst_done = started.elapsed();
+        let paced = track.admit(0, 500).await;
+
+        // Assert
+        assert!(burst && paced);
+        assert_eq!(burst_done, Duration::ZERO);
+        assert_eq!(started.elapsed(), Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tracks_of_a_session_share_its_rate() {
+        // Arrange
+        let limiter = limiter(1000, 0, OverLimitAction::Pace);
+        let video = limiter.track(&TrackKey::new("live", "video"), 1).unwrap();
+        let audio = limiter.track(&TrackKey::new("live", "audio"), 1).unwrap();
+        let other = limiter.track(&TrackKey::new("live", "video"), 2).unwrap();
+        video.admit(0, 1000).await;
+
+        // Act / Assert
+        assert!(!audio.try_admit(100));
+        assert!(other.try_admit(100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn old_groups_are_dropped_instead_of_paced() {
+        // Arrange
+        let limiter = limiter(0, 1000, OverLimitAction::DropOldGroups);
+        let track = limiter.track(&TrackKey::new("live", "video"), 1).unwrap();
+        track.group_opened(0);
+        track.admit(0, 1000).await;
+        track.group_opened(1);
+        let started = Instant::now();
+
+        // Act
+        let old = track.admit(0, 500).await;
+        let newest = track.admit(1, 500).await;
+
+        // Assert
+        assert!(!old);
+        assert!(newest);
+        assert_eq!(started.elapsed(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn session_bucket_is_forgotten_with_its_last_track() {
+        // Arrange
+        let limiter = limiter(1000, 0, OverLimitAction::Pace);
+        let video = limiter.track(&TrackKey::new("live", "video"), 1).unwrap();
+        let audio = limiter.track(&TrackKey::new("live", "audio"), 1).unwrap();
+
+        // Act
+        drop(video);
+        let after_first = limiter.sessions.lock().unwrap().len();
+        drop(audio);
+
+        // Assert
+        assert_eq!(after_first, 1);
+        assert!(limiter.sessions.lock().unwrap().is_empty());
+    }
+}
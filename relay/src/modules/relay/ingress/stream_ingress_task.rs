@@ -8,9 +8,13 @@ use tracing::{Instrument, Span};
 
 use crate::modules::{
     core::data_receiver::stream_receiver::StreamReceiverFactory,
+    idle_reaper::SessionActivity,
     relay::{
         cache::store::TrackCacheStore,
-        ingress::stream_reader::{StreamOpened, StreamReader},
+        ingress::{
+            rate_limiter::{IngressRateLimiter, TrackRateLimiter},
+            stream_reader::{StreamOpened, StreamReader},
+        },
         notifications::track_notifier::ObjectNotifyProducerMap,
     },
     types::{SessionId, TrackKey},
@@ -21,6 +25,8 @@ pub(crate) struct StreamReceiveStart {
     pub(crate) publisher_session_id: SessionId,
     pub(crate) factory: Box<dyn StreamReceiverFactory>,
     pub(crate) track_span: Span,
+    /// The publisher session's activity, kept fresh by ingested objects.
+    pub(crate) activity: Option<Arc<SessionActivity>>,
 }
 
 pub(crate) enum StreamIngressCommand {
@@ -41,6 +47,7 @@ impl StreamIngressTask {
         mut receiver: mpsc::Receiver<StreamIngressCommand>,
         cache_store: Arc<TrackCacheStore>,
         object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
+        rate_limiter: Arc<IngressRateLimiter>,
     ) -> Self {
         let (opened_tx, opened_rx) = mpsc::channel::<StreamOpened>(64);
         let stream_reader =
@@ -54,7 +61,7 @@ impl StreamIngressTask {
                     Some(command) = receiver.recv() => {
                         match command {
                             StreamIngressCommand::Start(cmd) => {
-                                let StreamReceiveStart { track_key, publisher_session_id, factory, track_span } = cmd;
+                                let StreamReceiveStart { track_key, publisher_session_id, factory, track_span, activity } = cmd;
                                 // draft-14 §8.2 Multiple Publishers: for now keep the first publisher and
                                 // ignore later ones. FIXME: GOAWAY migration needs ingesting from multiple
                                 // publishers with per-object dedup (SHOULD); first-writer-wins is a stopgap.
@@ -65,6 +72,7 @@ impl StreamIngressTask {
 
                                 let (stop_sender, stop_receiver) = watch::channel(false);
                                 stop_senders.insert(track_key.clone(), (stop_sender, publisher_session_id));
+                                cache_store.set_owner(&track_key, publisher_session_id);
 
                                 let span = tracing::debug_span!(
                                     parent: &track_span,
@@ -73,6 +81,7 @@ impl StreamIngressTask {
                                 );
                                 let opened_tx = opened_tx.clone();
                                 let cache_store = cache_store.clone();
+                                let track_rate_limiter = rate_limiter.track(&track_key, publisher_session_id);
                                 joinset.spawn(async move {
                                     let cache = cache_store.get_or_create(&track_key);
                                     cache.begin_live_ingest();
@@ -82,6 +91,8 @@ impl StreamIngressTask {
                                         opened_tx,
                                         track_span,
                                         stop_receiver,
+                                        track_rate_limiter,
+                                        activity,
                                     )
                                     .await;
                                     cache.end_live_ingest();
@@ -127,6 +138,8 @@ impl StreamIngressTask {
         stream_tx: mpsc::Sender<StreamOpened>,
         track_span: Span,
         mut stop_receiver: watch::Receiver<bool>,
+        rate_limiter: Option<Arc<TrackRateLimiter>>,
+        activity: Option<Arc<SessionActivity>>,
     ) {
         loop {
             let receiver = tokio::select! {
@@ -145,6 +158,8 @@ impl StreamIngressTask {
                     receiver,
                     parent_span: track_span.clone(),
                     stop_receiver: stop_receiver.clone(),
+                    rate_limiter: rate_limiter.clone(),
+                    activity: activity.clone(),
                 })
                 .await
                 .is_err()
@@ -7,8 +7,10 @@ use tokio::{
 };
 use tracing::{Instrument, Span};
 
+use crate::logging::OBJECT_SPAN_TARGET;
 use crate::modules::{
     core::{data_object::DataObject, data_receiver::stream_receiver::StreamReceiver},
+    idle_reaper::SessionActivity,
     relay::{
         cache::store::TrackCacheStore,
         notifications::{track_event::TrackEvent, track_notifier::ObjectNotifyProducerMap},
@@ -17,11 +19,15 @@ use crate::modules::{
     types::TrackKey,
 };
 
+use super::rate_limiter::TrackRateLimiter;
+
 pub(crate) struct StreamOpened {
     pub(crate) track_key: TrackKey,
     pub(crate) receiver: Box<dyn StreamReceiver>,
     pub(crate) parent_span: Span,
     pub(crate) stop_receiver: watch::Receiver<bool>,
+    pub(crate) rate_limiter: Option<Arc<TrackRateLimiter>>,
+    pub(crate) activity: Option<Arc<SessionActivity>>,
 }
 
 pub(crate) struct StreamReader {
@@ -46,6 +52,7 @@ impl StreamReader {
                             group_id = tracing::field::Empty,
                             subgroup_id = tracing::field::Empty,
                             end_reason = tracing::field::Empty,
+                            duplicate_objects = tracing::field::Empty,
                         );
                         joinset.spawn(Self::read_loop(
                             cmd.track_key,
@@ -53,6 +60,8 @@ impl StreamReader {
                             cmd.stop_receiver,
                             cache_store.clone(),
                             object_notify_producer_map.clone(),
+                            cmd.rate_limiter,
+                            cmd.activity,
                         ).instrument(span));
                     }
                     Some(result) = joinset.join_next() => {
@@ -73,12 +82,15 @@ impl StreamReader {
         mut stop_receiver: watch::Receiver<bool>,
         cache_store: Arc<TrackCacheStore>,
         object_notify_producer_map: Arc<ObjectNotifyProducerMap>,
+        rate_limiter: Option<Arc<TrackRateLimiter>>,
+        activity: Option<Arc<SessionActivity>>,
     ) {
         let span = Span::current();
         let mut group_id = 0u64;
         let mut subgroup_id = StreamSubgroupId::None;
         let mut has_subgroup = false;
         let mut prev_object_id: Option<u64> = None;
+        let mut duplicate_objects = 0u64;
         let cache = cache_store.get_or_create(&track_key);
         let notify = object_notify_producer_map.get_or_create(&track_key);
         loop {
@@ -94,6 +106,9 @@ impl StreamReader {
                 }
                 result = receiver.receive_object() => result,
             };
+            if let Some(activity) = &activity {
+                activity.touch();
+            }
 
             match receive_result {
                 Ok(Some(DataObject::SubgroupHeader(header))) => {
@@ -103,6 +118,9 @@ impl StreamReader {
                     prev_object_id = None;
                     span.record("group_id", group_id);
                     span.record("subgroup_id", tracing::field::debug(&subgroup_id));
+                    if let Some(rate_limiter) = &rate_limiter {
+                        rate_limiter.group_opened(group_id);
+                    }
                     cache
                         .append_live_stream_object(
                             group_id,
@@ -133,11 +151,43 @@ impl StreamReader {
                         },
                         _ => None,
                     };
+                    if let Some(rate_limiter) = &rate_limiter
+                        && !rate_limiter.admit(group_id, object.payload_len()).await
+                    {
+                        // A newer group is already flowing: give up on this
+                        // one rather than delay the newer group behind it.
+                        span.record("end_reason", "rate_limited");
+                        tracing::warn!(%track_key, group_id, "dropping rest of group over the rate limit");
+                        cache.close_stream_subgroup(group_id, &subgroup_id).await;
+                        let _ = notify.send(TrackEvent::EndOfGroup);
+                        return;
+                    }
                     let object_id = object.resolve_absolute_object_id(prev_object_id);
                     prev_object_id = object_id;
-                    cache
+                    // The track is repeated because OTLP backends do not
+                    // inherit it from the track span.
+                    let object_span = tracing::debug_span!(
+                        target: OBJECT_SPAN_TARGET,
+                        "relay.dataplane.ingress.object",
+                        track_namespace = %track_key.track_namespace,
+                        track_name = %track_key.track_name,
+                        group_id,
+                        object_id,
+                        payload_bytes = object.payload_len(),
+                    );
+                    if !cache
                         .append_live_stream_object(group_id, &subgroup_id, object_id, object)
-                        .await;
+                        .instrument(object_span)
+                        .await
+                    {
+                        // Resent, typically after the publisher reconnected;
+                        // subscribers already have the cached copy.
+                        duplicate_objects += 1;
+                        span.record("duplicate_objects", duplicate_objects);
+                        if duplicate_objects == 1 {
+                            tracing::info!(%track_key, group_id, ?object_id, "ignoring objects the publisher already sent");
+                        }
+                    }
                     if let Some(end_reason) = end_reason {
                         span.record("end_reason", end_reason);
                         cache.close_stream_subgroup(group_id, &subgroup_id).await;
@@ -309,12 +359,12 @@ mod tests {
     async fn assert_subgroup_closed_after(env: &TestEnv, group_id: u64, last_object_id: u64) {
         let cache = env.cache_store.get_or_create(&env.track_key);
         let subgroup = StreamSubgroupId::Value(0);
-        let closed = tokio::time::timeout(
-            Duration::from_secs(1),
-            cache.stream_object_from_or_wait(group_id, &subgroup, last_object_id + 1),
-        )
-        .await
-        .expect("subgroup should be closed, not waiting for more objects");
+        let mut cursor = cache
+            .stream_cursor(group_id, &subgroup, last_object_id + 1)
+            .await;
+        let closed = tokio::time::timeout(Duration::from_secs(1), cursor.next())
+            .await
+            .expect("subgroup should be closed, not waiting for more objects");
         assert!(closed.is_none());
     }
 
@@ -337,6 +387,8 @@ mod tests {
             env.stop_receiver.clone(),
             env.cache_store.clone(),
             env.notify_map.clone(),
+            None,
+            None,
         )
         .await;
 
@@ -351,13 +403,14 @@ mod tests {
 
         let cache = env.cache_store.get_or_create(&env.track_key);
         let subgroup = StreamSubgroupId::Value(0);
-        let (payload_id, _) = cache
-            .stream_object_from_or_wait(0, &subgroup, 0)
+        let mut cursor = cache.stream_cursor(0, &subgroup, 0).await;
+        let (payload_id, _, _) = cursor
+            .next()
             .await
             .expect("payload object should be cached");
         assert_eq!(payload_id, 0);
-        let (status_id, _) = cache
-            .stream_object_from_or_wait(0, &subgroup, 1)
+        let (status_id, _, _) = cursor
+            .next()
             .await
             .expect("end-of-group status object should be cached");
         assert_eq!(status_id, 1);
@@ -380,6 +433,8 @@ mod tests {
             env.stop_receiver.clone(),
             env.cache_store.clone(),
             env.notify_map.clone(),
+            None,
+            None,
         )
         .await;
 
@@ -425,6 +480,8 @@ mod tests {
             env.stop_receiver.clone(),
             env.cache_store.clone(),
             env.notify_map.clone(),
+            None,
+            None,
         ));
 
         exhausted_receiver
@@ -468,6 +525,8 @@ mod tests {
             env.stop_receiver.clone(),
             env.cache_store.clone(),
             env.notify_map.clone(),
+            None,
+            None,
         )
         .await;
         // silence unused warnings for the fields this test does not exercise
@@ -476,11 +535,55 @@ mod tests {
         let cache = env.cache_store.get_or_create(&env.track_key);
         let subgroup = StreamSubgroupId::Value(0);
         let mut object_ids = Vec::new();
-        let mut cursor = 0;
-        while let Some((id, _)) = cache.stream_object_from_or_wait(0, &subgroup, cursor).await {
+        let mut cursor = cache.stream_cursor(0, &subgroup, 0).await;
+        while let Some((id, _, _)) = cursor.next().await {
             object_ids.push(id);
-            cursor = id + 1;
         }
         assert_eq!(object_ids, vec![0, 1, 3]);
     }
+
+    #[tokio::test]
+    async fn resent_objects_are_ignored_and_the_stream_goes_on() {
+        let mut env = make_env();
+        let scripted = |objects: Vec<DataObject>| ScriptedStreamReceiver {
+            objects: VecDeque::from(objects),
+            exhausted_sender: None,
+            terminal: TerminalOutcome::Fin,
+        };
+        // The publisher reconnects and resends ids 0 and 1 before id 2.
+        let first = scripted(vec![
+            make_header(0),
+            make_payload_object(0),
+            make_payload_object(0),
+        ]);
+        let resent = scripted(vec![
+            make_header(0),
+            make_payload_object(0),
+            make_payload_object(0),
+            make_payload_object(0),
+        ]);
+
+        for receiver in [first, resent] {
+            StreamReader::read_loop(
+                env.track_key.clone(),
+                Box::new(receiver),
+                env.stop_receiver.clone(),
+                env.cache_store.clone(),
+                env.notify_map.clone(),
+                None,
+                None,
+            )
+            .await;
+        }
+        let _ = (&env.stop_sender, &mut env.event_receiver);
+
+        let cache = env.cache_store.get_or_create(&env.track_key);
+        let subgroup = StreamSubgroupId::Value(0);
+        let mut object_ids = Vec::new();
+        let mut cursor = cache.stream_cursor(0, &subgroup, 0).await;
+        while let Some((id, _, _)) = cursor.next().await {
+            object_ids.push(id);
+        }
+        assert_eq!(object_ids, vec![0, 1, 2]);
+    }
 }
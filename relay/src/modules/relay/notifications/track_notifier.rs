@@ -22,4 +22,41 @@ impl ObjectNotifyProducerMap {
             .or_insert_with(|| tokio::sync::broadcast::channel(256).0)
             .clone()
     }
+
+    /// Forgets the notifiers nobody listens to of tracks `is_live` no
+    /// longer knows, returning how many. Every ingress and egress task takes
+    /// the track cache before its notifier and keeps the cache while it
+    /// runs, so a track without a cache has no task left holding a sender.
+    pub(crate) fn prune(&self, is_live: impl Fn(&TrackKey) -> bool) -> usize {
+        let before = self.map.len();
+        self.map
+            .retain(|track_key, sender| sender.receiver_count() > 0 || is_live(track_key));
+        before.saturating_sub(self.map.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_keeps_live_and_listened_to_tracks() {
+        // Arrange
+        let map = ObjectNotifyProducerMap::new();
+        let live = TrackKey::new("live", "video");
+        let listened = TrackKey::new("live", "audio");
+        let idle = TrackKey::new("live", "chat");
+        map.get_or_create(&live);
+        let _receiver = map.get_or_create(&listened).subscribe();
+        map.get_or_create(&idle);
+
+        // Act
+        let pruned = map.prune(|track_key| *track_key == live);
+
+        // Assert
+        assert_eq!(pruned, 1);
+        assert!(map.map.contains_key(&live));
+        assert!(map.map.contains_key(&listened));
+        assert!(!map.map.contains_key(&idle));
+    }
 }
@@ -0,0 +1,90 @@
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber; one that falls further behind skips
+/// ahead with `RecvError::Lagged`.
+const EVENT_CAPACITY: usize = 1024;
+
+/// What happened on the relay, for a service embedding it to react to
+/// without patching the relay. Received from
+/// [`RelayServer::subscribe_events`](crate::RelayServer::subscribe_events).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayEvent {
+    /// A client or peer relay completed setup on one of the relay's
+    /// endpoints. `relay_id` is set for peer relays that identified
+    /// themselves.
+    SessionEstablished {
+        session_id: u64,
+        relay_id: Option<String>,
+    },
+    /// A session's PUBLISH_NAMESPACE was accepted.
+    NamespaceAnnounced {
+        session_id: u64,
+        track_namespace: String,
+    },
+    /// A session's SUBSCRIBE was answered with SUBSCRIBE_OK.
+    SubscriptionStarted {
+        session_id: u64,
+        subscribe_id: u64,
+        track_namespace: String,
+        track_name: String,
+    },
+    /// A session disconnected or was closed, and its state is cleaned up.
+    SessionClosed { session_id: u64 },
+}
+
+/// Fans [`RelayEvent`]s out to every embedder subscribed. Emitting never
+/// blocks, and costs nothing when nobody listens.
+#[derive(Clone)]
+pub(crate) struct RelayEvents {
+    sender: broadcast::Sender<RelayEvent>,
+}
+
+impl RelayEvents {
+    pub(crate) fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { sender }
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<RelayEvent> {
+        self.sender.subscribe()
+    }
+
+    pub(crate) fn emit(&self, event: RelayEvent) {
+        // Fails only when nobody is subscribed.
+        let _ = self.sender.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_reach_subscribers_in_order() {
+        // Arrange
+        let events = RelayEvents::new();
+        events.emit(RelayEvent::SessionClosed { session_id: 1 });
+        let mut receiver = events.subscribe();
+
+        // Act
+        events.emit(RelayEvent::SessionEstablished {
+            session_id: 2,
+            relay_id: None,
+        });
+        events.emit(RelayEvent::SessionClosed { session_id: 2 });
+
+        // Assert
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            RelayEvent::SessionEstablished {
+                session_id: 2,
+                relay_id: None,
+            }
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            RelayEvent::SessionClosed { session_id: 2 }
+        );
+        assert!(receiver.try_recv().is_err());
+    }
+}
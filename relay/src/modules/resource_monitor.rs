@@ -0,0 +1,275 @@
+use std::{
+    sync::atomic::{AtomicU8, Ordering},
+    time::Duration,
+};
+
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::modules::relay::cache::duration::duration_from_env;
+
+const DEFAULT_INTERVAL_SECS: u64 = 5;
+const DEFAULT_ELEVATED_PERCENT: u64 = 80;
+
+/// Process-wide, like the resources it describes. Written only by the
+/// monitor job; read by session intake, cache eviction and egress.
+static PRESSURE: AtomicU8 = AtomicU8::new(PressureLevel::Normal as u8);
+
+/// How close the process is to its resource limits. Load shedding is
+/// progressive: `Elevated` refuses new sessions and tightens cache budgets,
+/// `Critical` also sheds streams of subscribers that are falling behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub(crate) enum PressureLevel {
+    Normal = 0,
+    Elevated = 1,
+    Critical = 2,
+}
+
+impl PressureLevel {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Normal,
+            1 => Self::Elevated,
+            _ => Self::Critical,
+        }
+    }
+
+    /// Share of the configured cache quotas the eviction job enforces.
+    pub(crate) fn cache_budget_percent(self) -> u64 {
+        match self {
+            Self::Normal => 100,
+            Self::Elevated => 50,
+            Self::Critical => 25,
+        }
+    }
+
+    pub(crate) fn refuses_sessions(self) -> bool {
+        self >= Self::Elevated
+    }
+
+    pub(crate) fn sheds_slow_subscribers(self) -> bool {
+        self == Self::Critical
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Elevated => "elevated",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// Current pressure level as last computed by the monitor job.
+pub(crate) fn pressure() -> PressureLevel {
+    PressureLevel::from_u8(PRESSURE.load(Ordering::Relaxed))
+}
+
+/// One reading of the process resources. A field is `None` where the
+/// platform does not expose it (`/proc` is Linux only).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ResourceUsage {
+    pub(crate) rss_bytes: Option<u64>,
+    pub(crate) open_fds: Option<u64>,
+    pub(crate) alive_tasks: u64,
+}
+
+impl ResourceUsage {
+    fn sample() -> Self {
+        Self {
+            rss_bytes: read_rss_bytes(),
+            open_fds: std::fs::read_dir("/proc/self/fd")
+                .ok()
+                .map(|entries| entries.count() as u64),
+            alive_tasks: tokio::runtime::Handle::current()
+                .metrics()
+                .num_alive_tasks() as u64,
+        }
+    }
+}
+
+/// Limits the monitor measures usage against. An unset limit is not watched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ResourceLimits {
+    pub(crate) max_rss_bytes: Option<u64>,
+    pub(crate) max_open_fds: Option<u64>,
+    pub(crate) max_alive_tasks: Option<u64>,
+    /// Usage, in percent of a limit, from which the relay is `Elevated`.
+    /// Reaching the limit itself is `Critical`.
+    pub(crate) elevated_percent: u64,
+}
+
+impl ResourceLimits {
+    /// Reads `RELAY_RESOURCE_MAX_RSS_BYTES`, `RELAY_RESOURCE_MAX_OPEN_FDS`
+    /// (default: the soft `RLIMIT_NOFILE`), `RELAY_RESOURCE_MAX_TASKS` and
+    /// `RELAY_RESOURCE_ELEVATED_PERCENT` (default 80).
+    pub(crate) fn from_env() -> Self {
+        let read = |var: &str| {
+            std::env::var(var)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .filter(|limit| *limit > 0)
+        };
+        Self {
+            max_rss_bytes: read("RELAY_RESOURCE_MAX_RSS_BYTES"),
+            max_open_fds: read("RELAY_RESOURCE_MAX_OPEN_FDS").or_else(read_fd_limit),
+            max_alive_tasks: read("RELAY_RESOURCE_MAX_TASKS"),
+            elevated_percent: read("RELAY_RESOURCE_ELEVATED_PERCENT")
+                .unwrap_or(DEFAULT_ELEVATED_PERCENT)
+                .min(100),
+        }
+    }
+
+    /// Pressure of the resource closest to its limit.
+    pub(crate) fn level(&self, usage: &ResourceUsage) -> PressureLevel {
+        [
+            (usage.rss_bytes, self.max_rss_bytes),
+            (usage.open_fds, self.max_open_fds),
+            (Some(usage.alive_tasks), self.max_alive_tasks),
+        ]
+        .into_iter()
+        .filter_map(|(used, limit)| {
+            let percent = used?.saturating_mul(100) / limit?;
+            Some(if percent >= 100 {
+                PressureLevel::Critical
+            } else if percent >= self.elevated_percent {
+                PressureLevel::Elevated
+            } else {
+                PressureLevel::Normal
+            })
+        })
+        .max()
+        .unwrap_or(PressureLevel::Normal)
+    }
+}
+
+/// Samples process resources every `RELAY_RESOURCE_CHECK_INTERVAL_SECS`
+/// (default 5) and publishes the pressure level for [`pressure`].
+pub(crate) fn spawn_resource_monitor_job() -> JoinHandle<()> {
+    let limits = ResourceLimits::from_env();
+    let interval = duration_from_env("RELAY_RESOURCE_CHECK_INTERVAL_SECS", DEFAULT_INTERVAL_SECS);
+    tracing::info!(?limits, ?interval, "resource monitor started");
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval.max(Duration::from_secs(1)));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let usage = ResourceUsage::sample();
+            let level = limits.level(&usage);
+            let previous = PressureLevel::from_u8(PRESSURE.swap(level as u8, Ordering::Relaxed));
+            if level > previous {
+                tracing::warn!(
+                    pressure = level.as_str(),
+                    previous = previous.as_str(),
+                    ?usage,
+                    ?limits,
+                    "resource pressure rising; shedding load"
+                );
+            } else if level < previous {
+                tracing::info!(
+                    pressure = level.as_str(),
+                    previous = previous.as_str(),
+                    ?usage,
+                    "resource pressure easing"
+                );
+            }
+            tracing::debug!(
+                pressure = level.as_str(),
+                rss_bytes = ?usage.rss_bytes,
+                open_fds = ?usage.open_fds,
+                alive_tasks = usage.alive_tasks,
+                "relay resource usage"
+            );
+        }
+    })
+}
+
+fn read_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_rss_bytes(&status)
+}
+
+fn parse_rss_bytes(status: &str) -> Option<u64> {
+    let kib = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .split_whitespace()
+        .next()?
+        .parse::<u64>()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+fn read_fd_limit() -> Option<u64> {
+    let limits = std::fs::read_to_string("/proc/self/limits").ok()?;
+    parse_fd_limit(&limits)
+}
+
+/// Soft limit from the `Max open files` row; `unlimited` is no limit.
+fn parse_fd_limit(limits: &str) -> Option<u64> {
+    limits
+        .lines()
+        .find_map(|line| line.strip_prefix("Max open files"))?
+        .split_whitespace()
+        .next()?
+        .parse::<u64>()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> ResourceLimits {
+        ResourceLimits {
+            max_rss_bytes: Some(1000),
+            max_open_fds: Some(100),
+            max_alive_tasks: None,
+            elevated_percent: 80,
+        }
+    }
+
+    #[test]
+    fn the_resource_closest_to_its_limit_sets_the_level() {
+        // Arrange
+        let usage = |rss, fds| ResourceUsage {
+            rss_bytes: Some(rss),
+            open_fds: Some(fds),
+            alive_tasks: 1_000_000,
+        };
+
+        // Act / Assert: tasks have no limit, so they never count
+        assert_eq!(limits().level(&usage(500, 10)), PressureLevel::Normal);
+        assert_eq!(limits().level(&usage(850, 10)), PressureLevel::Elevated);
+        assert_eq!(limits().level(&usage(500, 100)), PressureLevel::Critical);
+    }
+
+    #[test]
+    fn unreadable_resources_do_not_raise_pressure() {
+        // Arrange
+        let usage = ResourceUsage::default();
+
+        // Act
+        let level = limits().level(&usage);
+
+        // Assert
+        assert_eq!(level, PressureLevel::Normal);
+    }
+
+    #[test]
+    fn proc_files_are_parsed() {
+        // Arrange
+        let status = "Name:\trelay\nVmRSS:\t    1820 kB\nThreads:\t4\n";
+        let limits = "Max open files            20000                40000                files\n";
+
+        // Act / Assert
+        assert_eq!(parse_rss_bytes(status), Some(1820 * 1024));
+        assert_eq!(parse_fd_limit(limits), Some(20000));
+        assert_eq!(
+            parse_fd_limit(
+                "Max open files            unlimited            unlimited            files"
+            ),
+            None
+        );
+    }
+}
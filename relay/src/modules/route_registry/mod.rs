@@ -60,6 +60,9 @@ pub(crate) enum RegisterNamespaceSubscriberError {
 
 #[async_trait]
 pub(crate) trait RelayRouteRegistry: Send + Sync {
+    /// Advertises this relay's status to its peers; a `Draining` relay is
+    /// no longer chosen as the upstream for new subscriptions.
+    async fn set_relay_status(&self, status: RouteStatus) -> anyhow::Result<()>;
     async fn register_namespace_publisher(
         &self,
         track_namespace: &str,
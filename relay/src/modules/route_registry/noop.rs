@@ -10,6 +10,10 @@ pub(crate) struct NoopRelayRouteRegistry;
 
 #[async_trait]
 impl RelayRouteRegistry for NoopRelayRouteRegistry {
+    async fn set_relay_status(&self, _status: RouteStatus) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     async fn register_namespace_publisher(
         &self,
         _track_namespace: &str,
@@ -188,6 +188,21 @@ impl RedisRelayRouteRegistry {
 
 #[async_trait]
 impl RelayRouteRegistry for RedisRelayRouteRegistry {
+    async fn set_relay_status(&self, status: RouteStatus) -> anyhow::Result<()> {
+        let mut connection = self.connection.clone();
+        let key = Self::relay_key(&self.relay.relay_id);
+        let _: () = connection
+            .hset_multiple(
+                &key,
+                &[
+                    ("status", status.as_str()),
+                    ("updated_at", &Self::now_millis().to_string()),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn register_namespace_publisher(
         &self,
         track_namespace: &str,
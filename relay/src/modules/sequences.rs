@@ -1,10 +1,17 @@
+pub(crate) mod authorization;
+pub(crate) mod bandwidth_probe;
 pub(crate) mod fetch;
+pub(crate) mod fetch_cancel;
 pub(crate) mod publish;
+pub(crate) mod publish_done;
 pub(crate) mod publish_namespace;
+pub(crate) mod publish_namespace_cancel;
 pub(crate) mod publish_namespace_done;
 pub(crate) mod subscribe;
 pub(crate) mod subscribe_namespace;
+pub(crate) mod subscribe_update;
 pub(crate) mod tables;
+pub(crate) mod track_status;
 pub(crate) mod unsubscribe;
 pub(crate) mod unsubscribe_namespace;
 pub(crate) mod upstream_serializer;
@@ -0,0 +1,267 @@
+use crate::{
+    config::{AuthorizationRequest, Authorizer},
+    modules::{
+        control_message_forwarder::ControlMessageForwarder, sequences::is_origin_client,
+        session_event::SessionEvent, types::SessionId,
+    },
+};
+
+const UNAUTHORIZED_REASON: &str = "unauthorized";
+
+pub(crate) struct Authorization {}
+
+impl Authorization {
+    /// Runs the authorizer on PUBLISH_NAMESPACE, SUBSCRIBE and
+    /// SUBSCRIBE_NAMESPACE from clients. A denied request is answered with
+    /// the authorizer's error code here and `false` tells the caller to drop
+    /// the event.
+    pub(crate) async fn admit(
+        &self,
+        session_id: SessionId,
+        authorizer: &Authorizer,
+        control_message_forwarder: &ControlMessageForwarder,
+        event: &SessionEvent,
+    ) -> bool {
+        if !authorizer.is_enabled() {
+            return true;
+        }
+        let token = match event {
+            SessionEvent::PublishNameSpace(_, handler) => handler.authorization_token(),
+            SessionEvent::Subscribe(_, handler) => handler.authorization_token(),
+            SessionEvent::SubscribeNameSpace(_, handler) => handler.authorization_token(),
+            _ => return true,
+        };
+        let authorization_token = token.as_deref();
+        let request = match event {
+            SessionEvent::PublishNameSpace(_, handler) => AuthorizationRequest::PublishNamespace {
+                track_namespace: handler.track_namespace(),
+                authorization_token,
+            },
+            SessionEvent::Subscribe(_, handler) => AuthorizationRequest::Subscribe {
+                track_namespace: handler.track_namespace(),
+                track_name: handler.track_name(),
+                authorization_token,
+            },
+            SessionEvent::SubscribeNameSpace(_, handler) => {
+                AuthorizationRequest::SubscribeNamespace {
+                    track_namespace_prefix: handler.track_namespace_prefix(),
+                    authorization_token,
+                }
+            }
+            _ => return true,
+        };
+        // Peer relays forward requests an edge relay has already authorized.
+        if !is_origin_client(session_id, control_message_forwarder).await {
+            return true;
+        }
+        let Err(error_code) = authorizer.authorize(&request) else {
+            return true;
+        };
+
+        tracing::warn!(
+            session_id,
+            error_code,
+            has_authorization_token = authorization_token.is_some(),
+            "Request is not authorized"
+        );
+        let reason_phrase = UNAUTHORIZED_REASON.to_string();
+        let result = match event {
+            SessionEvent::PublishNameSpace(_, handler) => {
+                handler.error(error_code, reason_phrase).await
+            }
+            SessionEvent::Subscribe(_, handler) => handler.error(error_code, reason_phrase).await,
+            SessionEvent::SubscribeNameSpace(_, handler) => {
+                handler.error(error_code, reason_phrase).await
+            }
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            tracing::error!(session_id, ?error, "Failed to send unauthorized error");
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::modules::{
+        core::{
+            handler::subscribe_namespace::SubscribeNamespaceHandler, publisher::Publisher,
+            session::Session, session_event::MoqtSessionEvent, subscriber::Subscriber,
+        },
+        enums::NamespaceErrorCode,
+        error::RelayError,
+        session_repository::SessionRepository,
+    };
+
+    const CLIENT_SESSION: SessionId = 1;
+    const RELAY_SESSION: SessionId = 2;
+
+    struct MockSubscribeNamespaceHandler {
+        authorization_token: Option<String>,
+        error_codes: Arc<Mutex<Vec<u64>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl SubscribeNamespaceHandler for MockSubscribeNamespaceHandler {
+        fn track_namespace_prefix(&self) -> &str {
+            "live"
+        }
+
+        fn authorization_token(&self) -> Option<String> {
+            self.authorization_token.clone()
+        }
+
+        async fn ok(&self) -> Result<(), moqt::TransportSendError> {
+            Ok(())
+        }
+
+        async fn error(
+            &self,
+            code: u64,
+            _reason_phrase: String,
+        ) -> Result<(), moqt::TransportSendError> {
+            self.error_codes.lock().unwrap().push(code);
+            Ok(())
+        }
+    }
+
+    struct MockSession;
+
+    #[async_trait::async_trait]
+    impl Session for MockSession {
+        fn as_publisher(&self) -> Box<dyn Publisher> {
+            unimplemented!("not used in authorization tests")
+        }
+
+        fn as_subscriber(&self) -> Box<dyn Subscriber> {
+            unimplemented!("not used in authorization tests")
+        }
+
+        async fn receive_moqt_session_event(&self) -> anyhow::Result<MoqtSessionEvent> {
+            std::future::pending().await
+        }
+
+        async fn go_away(&self, _new_session_uri: &str) -> anyhow::Result<()> {
+            unimplemented!("not used in authorization tests")
+        }
+
+        async fn drain(&self, _timeout: std::time::Duration) -> bool {
+            unimplemented!("not used in authorization tests")
+        }
+
+        fn close(&self) {
+            unimplemented!("not used in authorization tests")
+        }
+
+        fn terminate(&self, _error: &RelayError) {
+            unimplemented!("not used in authorization tests")
+        }
+
+        fn connection_stats(&self) -> moqt::ConnectionStats {
+            unimplemented!("not used in authorization tests")
+        }
+    }
+
+    async fn forwarder() -> ControlMessageForwarder {
+        let mut repository = SessionRepository::new();
+        let (session_event_sender, _session_event_receiver) = mpsc::unbounded_channel();
+        repository
+            .add_client(
+                CLIENT_SESSION,
+                Box::new(MockSession),
+                session_event_sender.clone(),
+                tracing::Span::none(),
+            )
+            .await;
+        repository
+            .add_relay(
+                RELAY_SESSION,
+                Box::new(MockSession),
+                session_event_sender,
+                tracing::Span::none(),
+                None,
+            )
+            .await;
+        ControlMessageForwarder {
+            repository: Arc::new(tokio::sync::Mutex::new(repository)),
+        }
+    }
+
+    fn subscribe_namespace(
+        session_id: SessionId,
+        authorization_token: Option<&str>,
+        error_codes: &Arc<Mutex<Vec<u64>>>,
+    ) -> SessionEvent {
+        SessionEvent::SubscribeNameSpace(
+            session_id,
+            Box::new(MockSubscribeNamespaceHandler {
+                authorization_token: authorization_token.map(str::to_string),
+                error_codes: error_codes.clone(),
+            }),
+        )
+    }
+
+    fn authorizer() -> Authorizer {
+        Authorizer::new(|request| request.authorization_token() == Some("secret"))
+    }
+
+    #[tokio::test]
+    async fn denied_client_request_is_answered_unauthorized() {
+        // Arrange
+        let forwarder = forwarder().await;
+        let error_codes = Arc::new(Mutex::new(Vec::new()));
+        let event = subscribe_namespace(CLIENT_SESSION, Some("guess"), &error_codes);
+
+        // Act
+        let admitted = Authorization {}
+            .admit(CLIENT_SESSION, &authorizer(), &forwarder, &event)
+            .await;
+
+        // Assert
+        assert!(!admitted);
+        assert_eq!(
+            *error_codes.lock().unwrap(),
+            vec![NamespaceErrorCode::Unauthorized as u64]
+        );
+    }
+
+    #[tokio::test]
+    async fn allowed_client_request_is_admitted() {
+        // Arrange
+        let forwarder = forwarder().await;
+        let error_codes = Arc::new(Mutex::new(Vec::new()));
+        let event = subscribe_namespace(CLIENT_SESSION, Some("secret"), &error_codes);
+
+        // Act
+        let admitted = Authorization {}
+            .admit(CLIENT_SESSION, &authorizer(), &forwarder, &event)
+            .await;
+
+        // Assert
+        assert!(admitted);
+        assert!(error_codes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn relay_peer_requests_are_not_checked_again() {
+        // Arrange
+        let forwarder = forwarder().await;
+        let error_codes = Arc::new(Mutex::new(Vec::new()));
+        let event = subscribe_namespace(RELAY_SESSION, None, &error_codes);
+
+        // Act
+        let admitted = Authorization {}
+            .admit(RELAY_SESSION, &authorizer(), &forwarder, &event)
+            .await;
+
+        // Assert
+        assert!(admitted);
+        assert!(error_codes.lock().unwrap().is_empty());
+    }
+}
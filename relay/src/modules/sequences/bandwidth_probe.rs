@@ -0,0 +1,130 @@
+use crate::modules::{
+    core::handler::subscribe::SubscribeHandler,
+    enums::{ContentExists, SubscribeErrorCode},
+    relay::egress::coordinator::{EgressCommand, EgressProbeRequest},
+    types::SessionId,
+};
+use tracing::Span;
+
+/// Answers SUBSCRIBE to `moqt::BANDWIDTH_PROBE_NAMESPACE` locally: the track
+/// name is the requested size, served as filler by the egress coordinator
+/// instead of being routed to a publisher.
+pub(crate) struct BandwidthProbe;
+
+/// SUBSCRIBE_ERROR (code, reason) for a probe size the relay will not serve.
+fn validate_probe_size(track_name: &str, max_bytes: u64) -> Result<u64, (u64, String)> {
+    let Some(size_bytes) = moqt::parse_bandwidth_probe_track_name(track_name) else {
+        return Err((
+            SubscribeErrorCode::TrackDoesNotExist as u64,
+            "Probe track name must be a positive byte count.".to_string(),
+        ));
+    };
+    if size_bytes > max_bytes {
+        return Err((
+            SubscribeErrorCode::InvalidRange as u64,
+            format!("Probe size exceeds the relay limit of {max_bytes} bytes."),
+        ));
+    }
+    Ok(size_bytes)
+}
+
+impl BandwidthProbe {
+    pub(crate) fn is_probe_request(handler: &dyn SubscribeHandler) -> bool {
+        handler.track_namespace() == moqt::BANDWIDTH_PROBE_NAMESPACE
+    }
+
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.bandwidth_probe",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id)
+    )]
+    pub(crate) async fn handle(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        max_bytes: u64,
+        handler: Box<dyn SubscribeHandler>,
+    ) {
+        let size_bytes = match validate_probe_size(handler.track_name(), max_bytes) {
+            Ok(size_bytes) => size_bytes,
+            Err((code, reason_phrase)) => {
+                tracing::warn!(
+                    subscribe_id = handler.subscribe_id(),
+                    track_name = %handler.track_name(),
+                    error_code = code,
+                    reason_phrase = %reason_phrase,
+                    "Rejecting bandwidth probe"
+                );
+                if let Err(error) = handler.error(code, reason_phrase).await {
+                    tracing::error!(?error, "failed to send SUBSCRIBE_ERROR");
+                }
+                return;
+            }
+        };
+
+        let track_alias = handler.allocate_track_alias();
+        if let Err(error) = handler
+            .ok_with_track_alias(track_alias, 0, ContentExists::False)
+            .await
+        {
+            tracing::error!(?error, "failed to send SUBSCRIBE_OK for bandwidth probe");
+            return;
+        }
+        tracing::info!(
+            subscribe_id = handler.subscribe_id(),
+            track_alias,
+            size_bytes,
+            "Serving bandwidth probe"
+        );
+        if egress_sender
+            .send(EgressCommand::StartProbe(EgressProbeRequest {
+                subscriber_session_id: session_id,
+                downstream_subscribe_id: handler.subscribe_id(),
+                downstream_subscription: handler.to_downstream_subscription(track_alias),
+                size_bytes,
+            }))
+            .await
+            .is_err()
+        {
+            tracing::error!("failed to send EgressProbeRequest");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_size_within_limit_is_accepted() {
+        // Arrange / Act
+        let result = validate_probe_size("65536", 1_048_576);
+        // Assert
+        assert_eq!(result, Ok(65_536));
+    }
+
+    #[test]
+    fn probe_size_over_limit_maps_to_invalid_range() {
+        // Arrange / Act
+        let result = validate_probe_size("2097152", 1_048_576);
+        // Assert
+        assert_eq!(
+            result.map_err(|(code, _)| code),
+            Err(SubscribeErrorCode::InvalidRange as u64)
+        );
+    }
+
+    #[test]
+    fn non_numeric_probe_name_maps_to_track_does_not_exist() {
+        // Arrange / Act
+        let result = validate_probe_size("video", 1_048_576);
+        // Assert
+        assert_eq!(
+            result.map_err(|(code, _)| code),
+            Err(SubscribeErrorCode::TrackDoesNotExist as u64)
+        );
+    }
+}
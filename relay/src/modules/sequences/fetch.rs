@@ -7,6 +7,8 @@ use crate::modules::{
     control_message_forwarder::ControlMessageForwarder,
     core::handler::fetch::FetchHandler,
     enums::FetchErrorCode,
+    error::RelayError,
+    qlog,
     relay::{
         cache::{
             store::TrackCacheStore,
@@ -72,19 +74,23 @@ enum FetchSource {
 enum FetchError {
     TrackNotFound,
     UnknownJoiningRequestId,
+    NotJoinable,
     NoObjectsPublished,
     InvalidRange,
     NoObjects,
 }
 
 impl FetchError {
-    fn code(&self) -> FetchErrorCode {
+    /// The FETCH_ERROR code, or the `RelayError` that closes the session
+    /// where §9.16.2 requires a PROTOCOL_VIOLATION instead.
+    fn code(&self) -> Result<FetchErrorCode, RelayError> {
         match self {
-            Self::TrackNotFound => FetchErrorCode::TrackDoesNotExist,
-            Self::UnknownJoiningRequestId => FetchErrorCode::InvalidJoiningRequestId,
-            Self::NoObjectsPublished => FetchErrorCode::InvalidRange,
-            Self::InvalidRange => FetchErrorCode::InvalidRange,
-            Self::NoObjects => FetchErrorCode::NoObjects,
+            Self::TrackNotFound => Ok(FetchErrorCode::TrackDoesNotExist),
+            Self::UnknownJoiningRequestId => Ok(FetchErrorCode::InvalidJoiningRequestId),
+            Self::NotJoinable => Err(RelayError::ProtocolViolation(self.reason().to_string())),
+            Self::NoObjectsPublished => Ok(FetchErrorCode::InvalidRange),
+            Self::InvalidRange => Ok(FetchErrorCode::InvalidRange),
+            Self::NoObjects => Ok(FetchErrorCode::NoObjects),
         }
     }
 
@@ -92,6 +98,7 @@ impl FetchError {
         match self {
             Self::TrackNotFound => "Track not found",
             Self::UnknownJoiningRequestId => "Unknown joining request id",
+            Self::NotJoinable => "Joined subscription is not a Largest Object subscription",
             Self::NoObjectsPublished => "No objects published",
             Self::InvalidRange => "Invalid fetch range",
             Self::NoObjects => "No objects in fetch range",
@@ -128,19 +135,24 @@ impl Fetch {
         {
             Ok(target) => target,
             Err(err) => {
-                let _ = handler
-                    .error(err.code() as u64, err.reason().to_string())
-                    .await;
+                Self::reject(session_id, forwarder, handler.as_ref(), err).await;
                 return;
             }
         };
 
-        let source = match self.resolve_fetch_source(&target, cache_store).await {
+        let source = self.resolve_fetch_source(&target, cache_store).await;
+        qlog::record(session_id, "relay:fetch_cache_lookup", || {
+            serde_json::json!({
+                "request_id": request_id,
+                "track_namespace": target.track_namespace,
+                "track_name": target.track_name,
+                "hit": matches!(source, Ok(FetchSource::Cache(_))),
+            })
+        });
+        let source = match source {
             Ok(source) => source,
             Err(err) => {
-                let _ = handler
-                    .error(err.code() as u64, err.reason().to_string())
-                    .await;
+                Self::reject(session_id, forwarder, handler.as_ref(), err).await;
                 return;
             }
         };
@@ -204,6 +216,7 @@ impl Fetch {
                 }
 
                 let cache = cache_store.get_or_create(&target.track_key);
+                cache_store.set_owner(&target.track_key, prepared.upstream_publisher_session_id);
                 let egress_start = EgressFetchRequest {
                     subscriber_session_id: session_id,
                     request_id,
@@ -230,6 +243,28 @@ impl Fetch {
         }
     }
 
+    /// Answers an unresolvable FETCH with FETCH_ERROR, or terminates the
+    /// session when the error is a protocol violation.
+    async fn reject(
+        session_id: SessionId,
+        forwarder: &ControlMessageForwarder,
+        handler: &dyn FetchHandler,
+        err: FetchError,
+    ) {
+        match err.code() {
+            Ok(code) => {
+                let _ = handler.error(code as u64, err.reason().to_string()).await;
+            }
+            Err(error) => {
+                tracing::warn!(session_id, %error, "Terminating session on invalid FETCH");
+                let session = forwarder.repository.lock().await.session(session_id);
+                if let Some(session) = session {
+                    session.terminate(&error);
+                }
+            }
+        }
+    }
+
     fn build_upstream_fetch(target: &FetchTarget, missing_range: LocationRange) -> UpstreamFetch {
         UpstreamFetch {
             track_namespace: target.track_namespace.clone(),
@@ -473,14 +508,18 @@ impl Fetch {
             .resolve_fetch_range(start_location, end_location)
             .await
         {
-            FetchRangeResolution::Serve { end_location } => FetchSource::Cache(CacheTarget {
-                cache,
-                start_location,
-                end_location,
-            }),
+            FetchRangeResolution::Serve { end_location } => {
+                cache.metrics().record_hit();
+                FetchSource::Cache(CacheTarget {
+                    cache,
+                    start_location,
+                    end_location,
+                })
+            }
             FetchRangeResolution::InvalidRange => return Err(FetchError::InvalidRange),
             FetchRangeResolution::NoObjects => return Err(FetchError::NoObjects),
             FetchRangeResolution::NotCovered => {
+                cache.metrics().record_miss();
                 tracing::debug!(
                     track_namespace = %target.track_namespace,
                     track_name = %target.track_name,
@@ -510,9 +549,6 @@ impl Fetch {
         joining_request_id: u64,
         table: &dyn LocalPubSubDirectory,
     ) -> Result<JoinedSubscriptionTarget, FetchError> {
-        // TODO: validate the joined Subscribe has Filter Type Largest Object;
-        // otherwise close the session with PROTOCOL_VIOLATION (§9.16.2).
-
         let Some(downstream_sub) =
             table.get_downstream_subscription(session_id, joining_request_id)
         else {
@@ -522,6 +558,15 @@ impl Fetch {
             );
             return Err(FetchError::UnknownJoiningRequestId);
         };
+        // §9.16.2 allows joining only a Largest Object subscription; any
+        // other filter is a PROTOCOL_VIOLATION that closes the session.
+        if !downstream_sub.joinable {
+            tracing::warn!(
+                joining_request_id,
+                "Joining fetch references a subscription without Largest Object filter"
+            );
+            return Err(FetchError::NotJoinable);
+        }
 
         let Some(active_upstream) = table.get_active_upstream_subscription(
             downstream_sub.upstream_key.publisher_session_id,
@@ -563,7 +608,7 @@ mod tests {
     use super::*;
     use crate::modules::{
         core::data_object::DataObject,
-        enums::ContentExists,
+        enums::{ContentExists, TerminationErrorCode},
         relay::types::StreamSubgroupId,
         sequences::tables::{
             hashmap_table::InMemoryLocalPubSubDirectory,
@@ -591,6 +636,7 @@ mod tests {
                 track_key,
                 expires: None,
                 content_exists: ContentExists::False,
+                max_cache_duration: None,
                 downstream_subscriber_count: 0,
                 origin: UpstreamSubscriptionOrigin::Subscribe,
             },
@@ -921,14 +967,38 @@ mod tests {
     async fn resolve_joined_subscription_unknown_request_id() {
         let table = InMemoryLocalPubSubDirectory::new();
         let result = Fetch.resolve_joined_subscription(1, 999, &table).await;
-        assert!(matches!(result, Err(FetchError::UnknownJoiningRequestId)));
+        let Err(err) = result else {
+            panic!("expected the joining fetch to be rejected");
+        };
+        assert!(matches!(err, FetchError::UnknownJoiningRequestId));
+        assert!(matches!(
+            err.code(),
+            Ok(FetchErrorCode::InvalidJoiningRequestId)
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolve_joined_subscription_rejects_non_largest_object_subscription() {
+        let table = InMemoryLocalPubSubDirectory::new();
+        let largest = moqt::Location {
+            group_id: 3,
+            object_id: 0,
+        };
+        let key = setup_upstream(&table, TrackKey::new("ns", "track"));
+        table.register_downstream_subscription(2, 100, key, Some(largest), false);
+        let result = Fetch.resolve_joined_subscription(2, 100, &table).await;
+        let Err(err) = result else {
+            panic!("expected the joining fetch to be rejected");
+        };
+        let termination = err.code().expect_err("expected a session termination");
+        assert_eq!(termination.code(), TerminationErrorCode::ProtocolViolation);
     }
 
     #[tokio::test]
     async fn resolve_joined_subscription_no_objects_published() {
         let table = InMemoryLocalPubSubDirectory::new();
         let key = setup_upstream(&table, TrackKey::new("ns", "track"));
-        table.register_downstream_subscription(2, 100, key, None);
+        table.register_downstream_subscription(2, 100, key, None, true);
         // No objects in cache either, so NoObjectsPublished.
         let result = Fetch.resolve_joined_subscription(2, 100, &table).await;
         assert!(matches!(result, Err(FetchError::NoObjectsPublished)));
@@ -942,7 +1012,7 @@ mod tests {
             object_id: 5,
         };
         let key = setup_upstream(&table, TrackKey::new("ns", "track"));
-        table.register_downstream_subscription(2, 100, key, Some(largest));
+        table.register_downstream_subscription(2, 100, key, Some(largest), true);
         let context = Fetch
             .resolve_joined_subscription(2, 100, &table)
             .await
@@ -973,6 +1043,7 @@ mod tests {
                 group_id: 1,
                 object_id: 1,
             }),
+            true,
         );
         fill_group_with_ids(&cache_store, &track_key, 1, &[0, 1]).await;
 
@@ -1026,6 +1097,7 @@ mod tests {
                 group_id: 2,
                 object_id: 3,
             }),
+            true,
         );
 
         let (_, source) = resolve_target_and_source(
@@ -1076,6 +1148,7 @@ mod tests {
                 group_id: 1,
                 object_id: 1,
             }),
+            true,
         );
 
         let (_, source) = resolve_target_and_source(
@@ -1125,6 +1198,7 @@ mod tests {
                 group_id: 1,
                 object_id: 1,
             }),
+            true,
         );
 
         let result = resolve_target_and_source(
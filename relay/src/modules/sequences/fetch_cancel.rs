@@ -0,0 +1,48 @@
+use tracing::Span;
+
+use crate::modules::{
+    core::handler::fetch_cancel::FetchCancelHandler, relay::egress::coordinator::EgressCommand,
+    types::SessionId,
+};
+
+pub(crate) struct FetchCancel;
+
+impl FetchCancel {
+    /// Stops delivering a FETCH the downstream subscriber no longer wants.
+    ///
+    /// Only cache-served delivery is stopped here. An upstream fill that is
+    /// still in flight keeps populating the cache; if it completes after the
+    /// cancel, the resulting fetch stream is dropped by the subscriber.
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.fetch_cancel",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id)
+    )]
+    pub(crate) async fn handle(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        handler: Box<dyn FetchCancelHandler>,
+    ) {
+        let request_id = handler.request_id();
+        tracing::info!(
+            session_id = %session_id,
+            request_id = %request_id,
+            "SequenceHandler::fetch_cancel"
+        );
+
+        if egress_sender
+            .send(EgressCommand::StopFetch {
+                subscriber_session_id: session_id,
+                request_id,
+            })
+            .await
+            .is_err()
+        {
+            tracing::error!("Failed to send EgressStopFetchRequest.");
+        }
+    }
+}
@@ -225,6 +225,7 @@ impl Publish {
             track_key,
             expires: None,
             content_exists: subscription.content_exists(),
+            max_cache_duration: handler._max_cache_duration(),
             downstream_subscriber_count: 0,
             origin: UpstreamSubscriptionOrigin::Publish,
         };
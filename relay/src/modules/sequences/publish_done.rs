@@ -0,0 +1,223 @@
+use crate::modules::{
+    core::handler::publish_done::PublishDoneHandler,
+    relay::{
+        cache::store::TrackCacheStore, egress::coordinator::EgressCommand,
+        ingress::ingress_coordinator::IngressCommand,
+    },
+    sequences::tables::table::{EndedUpstream, LocalPubSubDirectory, RemovedSessionSubscriptions},
+    types::SessionId,
+};
+use tracing::Span;
+
+/// Handles PUBLISH_DONE from an upstream publisher, and ends the downstream
+/// side of any upstream that went away: each downstream subscriber gets its
+/// own PUBLISH_DONE, ingest stops and the track's cache is purged. Without
+/// this, downstream subscribers would wait for objects that never come.
+pub(crate) struct PublishDone;
+
+impl PublishDone {
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.publish_done",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id)
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn handle(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        table: &dyn LocalPubSubDirectory,
+        ingress_sender: &tokio::sync::mpsc::Sender<IngressCommand>,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
+        handler: &dyn PublishDoneHandler,
+    ) {
+        let request_id = handler.request_id();
+        tracing::info!(
+            session_id = %session_id,
+            request_id,
+            status_code = handler.status_code(),
+            stream_count = handler.stream_count(),
+            reason_phrase = %handler.reason_phrase(),
+            "SequenceHandler::PublishDone"
+        );
+
+        let removed =
+            table.remove_upstream_subscriptions(session_id, EndedUpstream::RequestId(request_id));
+        if removed.upstream_track_keys.is_empty() {
+            tracing::warn!(
+                session_id = %session_id,
+                request_id,
+                "upstream subscription not found for PUBLISH_DONE"
+            );
+            return;
+        }
+
+        // The upstream's status code and reason describe the track itself, so
+        // they are relayed as is.
+        Self::end_downstream(
+            session_id,
+            removed,
+            handler.status_code(),
+            handler.reason_phrase(),
+            table,
+            ingress_sender,
+            egress_sender,
+            cache_store,
+        )
+        .await;
+    }
+
+    /// Ends everything fed by upstream subscriptions that were removed from
+    /// `table` (see [`LocalPubSubDirectory::remove_upstream_subscriptions`]).
+    /// A track's cache is kept while another publisher still feeds it.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn end_downstream(
+        publisher_session_id: SessionId,
+        removed: RemovedSessionSubscriptions,
+        status_code: u64,
+        reason_phrase: &str,
+        table: &dyn LocalPubSubDirectory,
+        ingress_sender: &tokio::sync::mpsc::Sender<IngressCommand>,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
+    ) {
+        for downstream in removed.downstream_subscriptions {
+            if egress_sender
+                .send(EgressCommand::EndReader {
+                    subscriber_session_id: downstream.downstream_session_id,
+                    downstream_subscribe_id: downstream.downstream_subscribe_id,
+                    status_code,
+                    reason_phrase: reason_phrase.to_string(),
+                })
+                .await
+                .is_err()
+            {
+                tracing::error!(
+                    session_id = downstream.downstream_session_id,
+                    subscribe_id = downstream.downstream_subscribe_id,
+                    "failed to send egress end request"
+                );
+            }
+        }
+
+        for track_key in removed.upstream_track_keys {
+            if ingress_sender
+                .send(IngressCommand::StopTrack {
+                    track_key: track_key.clone(),
+                    publisher_session_id,
+                })
+                .await
+                .is_err()
+            {
+                tracing::error!(%track_key, "failed to send ingress stop request");
+            }
+            if table
+                .find_active_upstream_subscriptions(
+                    &track_key.track_namespace,
+                    &track_key.track_name,
+                )
+                .is_empty()
+            {
+                cache_store.purge(&track_key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::modules::{
+        enums::ContentExists,
+        sequences::tables::{
+            hashmap_table::InMemoryLocalPubSubDirectory,
+            table::{
+                ActiveUpstreamSubscription, UpstreamSubscriptionKey, UpstreamSubscriptionOrigin,
+            },
+        },
+        types::TrackKey,
+    };
+
+    const PUBLISHER_SESSION: SessionId = 1;
+    const SUBSCRIBER_SESSION: SessionId = 2;
+
+    fn register_fed_track(table: &InMemoryLocalPubSubDirectory) {
+        let upstream_key = UpstreamSubscriptionKey {
+            publisher_session_id: PUBLISHER_SESSION,
+            track_namespace: "ns".to_string(),
+            track_name: "track".to_string(),
+        };
+        table.register_upstream_subscription(
+            upstream_key.clone(),
+            ActiveUpstreamSubscription {
+                upstream_request_id: 7,
+                track_key: TrackKey::new("ns", "track"),
+                expires: None,
+                content_exists: ContentExists::False,
+                max_cache_duration: None,
+                downstream_subscriber_count: 1,
+                origin: UpstreamSubscriptionOrigin::Subscribe,
+            },
+        );
+        table.register_downstream_subscription(SUBSCRIBER_SESSION, 3, upstream_key, None, true);
+    }
+
+    #[tokio::test]
+    async fn end_downstream_ends_readers_stops_ingest_and_purges_cache() {
+        // Arrange
+        let table = InMemoryLocalPubSubDirectory::new();
+        register_fed_track(&table);
+        let cache_store = TrackCacheStore::new();
+        let track_key = TrackKey::new("ns", "track");
+        cache_store.get_or_create(&track_key);
+        let (ingress_sender, mut ingress_receiver) = mpsc::channel(4);
+        let (egress_sender, mut egress_receiver) = mpsc::channel(4);
+        let removed =
+            table.remove_upstream_subscriptions(PUBLISHER_SESSION, EndedUpstream::RequestId(7));
+
+        // Act
+        PublishDone::end_downstream(
+            PUBLISHER_SESSION,
+            removed,
+            0x4,
+            "gone",
+            &table,
+            &ingress_sender,
+            &egress_sender,
+            &cache_store,
+        )
+        .await;
+
+        // Assert
+        match egress_receiver.try_recv().unwrap() {
+            EgressCommand::EndReader {
+                subscriber_session_id,
+                downstream_subscribe_id,
+                status_code,
+                reason_phrase,
+            } => {
+                assert_eq!(subscriber_session_id, SUBSCRIBER_SESSION);
+                assert_eq!(downstream_subscribe_id, 3);
+                assert_eq!(status_code, 0x4);
+                assert_eq!(reason_phrase, "gone");
+            }
+            _ => panic!("expected EndReader"),
+        }
+        match ingress_receiver.try_recv().unwrap() {
+            IngressCommand::StopTrack {
+                track_key: stopped,
+                publisher_session_id,
+            } => {
+                assert_eq!(stopped, track_key);
+                assert_eq!(publisher_session_id, PUBLISHER_SESSION);
+            }
+            _ => panic!("expected StopTrack"),
+        }
+        assert!(cache_store.get(&track_key).is_none());
+    }
+}
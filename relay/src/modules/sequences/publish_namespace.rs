@@ -14,6 +14,7 @@ use tracing::Span;
 pub(crate) struct PublishNamespace;
 
 impl PublishNamespace {
+    /// Returns whether the namespace was accepted with PUBLISH_NAMESPACE_OK.
     #[tracing::instrument(
         level = "info",
         name = "relay.sequence.publish_namespace",
@@ -29,7 +30,7 @@ impl PublishNamespace {
         forwarder: &ControlMessageForwarder,
         cascading_relay_context: CascadingRelayContext<'_>,
         handler: &dyn PublishNamespaceHandler,
-    ) {
+    ) -> bool {
         let requested_track_namespace = handler.track_namespace();
         tracing::info!(
             session_id = %session_id,
@@ -48,7 +49,7 @@ impl PublishNamespace {
                 )
                 .await
         {
-            return;
+            return false;
         }
 
         let peer_kind = if is_origin {
@@ -58,11 +59,11 @@ impl PublishNamespace {
         };
         let Some(track_namespace) = self.register(session_id, table, peer_kind, handler).await
         else {
-            return;
+            return false;
         };
 
         if !self.response(handler).await {
-            return;
+            return false;
         }
 
         // The draft defines that the relay requires to send `PUBLISH_NAMESPACE` message to
@@ -82,6 +83,7 @@ impl PublishNamespace {
             )
             .await;
         }
+        true
     }
 
     #[tracing::instrument(
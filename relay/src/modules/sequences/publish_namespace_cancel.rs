@@ -0,0 +1,39 @@
+use crate::modules::{
+    core::handler::publish_namespace_cancel::PublishNamespaceCancelHandler,
+    sequences::tables::table::LocalPubSubDirectory, types::SessionId,
+};
+use tracing::Span;
+
+/// Handles PUBLISH_NAMESPACE_CANCEL from a downstream that no longer wants a
+/// namespace the relay announced to it. The session keeps its namespace
+/// subscription, but later PUBLISH_NAMESPACE(_DONE) and PUBLISH for the
+/// cancelled namespace are no longer forwarded to it.
+pub(crate) struct PublishNamespaceCancel;
+
+impl PublishNamespaceCancel {
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.publish_namespace_cancel",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id)
+    )]
+    pub(crate) async fn handle(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        table: &dyn LocalPubSubDirectory,
+        handler: &dyn PublishNamespaceCancelHandler,
+    ) {
+        let track_namespace = handler.track_namespace();
+        tracing::info!(
+            session_id = %session_id,
+            track_namespace = %track_namespace,
+            error_code = handler.error_code(),
+            reason_phrase = %handler.reason_phrase(),
+            "SequenceHandler::PublishNamespaceCancel"
+        );
+
+        table.cancel_namespace_for_subscriber(session_id, track_namespace.to_string());
+    }
+}
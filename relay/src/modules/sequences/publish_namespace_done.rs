@@ -3,9 +3,18 @@ use std::collections::HashSet;
 use crate::modules::{
     control_message_forwarder::ControlMessageForwarder,
     core::handler::publish_namespace_done::PublishNamespaceDoneHandler,
+    enums::PublishDoneStatusCode,
     inter_relay::InterRelayConnectionManager,
+    relay::{
+        cache::store::TrackCacheStore, egress::coordinator::EgressCommand,
+        ingress::ingress_coordinator::IngressCommand,
+    },
     route_registry::RelayRouteRegistry,
-    sequences::{CascadingRelayContext, tables::table::LocalPubSubDirectory},
+    sequences::{
+        CascadingRelayContext,
+        publish_done::PublishDone,
+        tables::table::{EndedUpstream, LocalPubSubDirectory},
+    },
     types::SessionId,
 };
 use tracing::Span;
@@ -20,12 +29,16 @@ impl PublishNamespaceDone {
         parent = session_span,
         fields(session_id = %session_id)
     )]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn handle(
         &self,
         session_id: SessionId,
         session_span: &Span,
         table: &dyn LocalPubSubDirectory,
         forwarder: &ControlMessageForwarder,
+        ingress_sender: &tokio::sync::mpsc::Sender<IngressCommand>,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
         cascading_relay_context: CascadingRelayContext<'_>,
         handler: &dyn PublishNamespaceDoneHandler,
     ) {
@@ -40,6 +53,23 @@ impl PublishNamespaceDone {
 
         Self::notify_local_subscribers(session_id, track_namespace, table, forwarder).await;
 
+        // Tracks under the withdrawn namespace will not produce more objects.
+        let removed = table
+            .remove_upstream_subscriptions(session_id, EndedUpstream::Namespace(track_namespace));
+        if !removed.upstream_track_keys.is_empty() {
+            PublishDone::end_downstream(
+                session_id,
+                removed,
+                PublishDoneStatusCode::TrackEnded as u64,
+                "track namespace withdrawn",
+                table,
+                ingress_sender,
+                egress_sender,
+                cache_store,
+            )
+            .await;
+        }
+
         if super::is_origin_client(session_id, forwarder).await && no_clients_remain {
             Self::withdraw_namespace_publication(
                 track_namespace,
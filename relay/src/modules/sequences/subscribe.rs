@@ -1,9 +1,10 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use crate::config::NamespacePolicyTable;
 use crate::modules::{
     control_message_forwarder::ControlMessageForwarder,
     core::handler::subscribe::SubscribeHandler,
-    enums::{ContentExists, Location, SubscribeErrorCode},
+    enums::{ContentExists, FilterType, GroupOrder, Location, SubscribeErrorCode},
     relay::{
         cache::store::TrackCacheStore,
         egress::coordinator::{EgressCommand, EgressStartRequest},
@@ -69,6 +70,60 @@ async fn resolve_subscribe_largest(
     }
 }
 
+/// MAX_CACHE_DURATION (ms) the relay can honour for a downstream subscriber:
+/// its own cache TTL, capped by whatever the upstream granted. Returned in
+/// SUBSCRIBE_OK regardless of the request so the subscriber learns the
+/// effective value instead of finding gaps when it FETCHes older objects.
+fn effective_max_cache_duration(relay_ttl: Duration, upstream: Option<u64>) -> u64 {
+    let relay_ttl_ms = u64::try_from(relay_ttl.as_millis()).unwrap_or(u64::MAX);
+    upstream.map_or(relay_ttl_ms, |upstream| upstream.min(relay_ttl_ms))
+}
+
+/// Rejects a filter the relay cannot serve before any upstream work, with
+/// the SUBSCRIBE_ERROR (code, reason) to answer.
+fn validate_filter(filter_type: &FilterType) -> Result<(), (u64, String)> {
+    if let FilterType::AbsoluteRange {
+        location,
+        end_group,
+    } = filter_type
+        && *end_group < location.group_id
+    {
+        return Err((
+            SubscribeErrorCode::InvalidRange as u64,
+            format!(
+                "End group {end_group} is before start group {}.",
+                location.group_id
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Replaces the requested subscriber priority and group order with what the
+/// operator's namespace policy allows, before anything is built from them.
+fn apply_namespace_policy(policies: &NamespacePolicyTable, handler: &mut dyn SubscribeHandler) {
+    let Some(policy) = policies.lookup(handler.track_namespace()) else {
+        return;
+    };
+    let requested_priority = handler.subscriber_priority();
+    let requested_order = handler.group_order().as_moqt();
+    let subscriber_priority = policy.subscriber_priority(requested_priority);
+    let group_order = policy.group_order(requested_order);
+    if (subscriber_priority, group_order) == (requested_priority, requested_order) {
+        return;
+    }
+    tracing::info!(
+        subscribe_id = handler.subscribe_id(),
+        track_namespace = %handler.track_namespace(),
+        requested_priority,
+        subscriber_priority,
+        requested_order = ?requested_order,
+        group_order = ?group_order,
+        "namespace policy overrides subscribe preferences"
+    );
+    handler.override_preferences(subscriber_priority, GroupOrder::from(group_order));
+}
+
 enum UpstreamSubscriptionError {
     PublisherNotFound,
     SubscribeFailed(anyhow::Error),
@@ -112,6 +167,7 @@ impl UpstreamSubscriptionError {
 }
 
 impl Subscribe {
+    /// Returns whether the subscription was accepted with SUBSCRIBE_OK.
     #[tracing::instrument(
         level = "info",
         name = "relay.sequence.subscribe",
@@ -131,8 +187,10 @@ impl Subscribe {
         upstream_publisher_resolver: &UpstreamPublisherResolver,
         cache_store: &Arc<TrackCacheStore>,
         upstream_serializer: &UpstreamCreationSerializer,
-        handler: Box<dyn SubscribeHandler>,
-    ) {
+        namespace_policies: &NamespacePolicyTable,
+        mut handler: Box<dyn SubscribeHandler>,
+    ) -> bool {
+        apply_namespace_policy(namespace_policies, handler.as_mut());
         let track_namespace = handler.track_namespace();
         let track_name = handler.track_name();
         tracing::info!(
@@ -142,6 +200,20 @@ impl Subscribe {
             "SequenceHandler::subscribe"
         );
 
+        if let Err((code, reason_phrase)) = validate_filter(&handler._filter_type()) {
+            if let Err(send_error) = self
+                .response_error(handler.as_ref(), code, reason_phrase)
+                .await
+            {
+                tracing::error!(
+                    subscribe_id = handler.subscribe_id(),
+                    error = ?send_error,
+                    "failed to send SUBSCRIBE_ERROR"
+                );
+            }
+            return false;
+        }
+
         let (upstream_key, active_upstream, largest_source) = match self
             .get_or_create_upstream_subscription(
                 session_id,
@@ -176,7 +248,7 @@ impl Subscribe {
                         "failed to send SUBSCRIBE_ERROR"
                     );
                 }
-                return;
+                return false;
             }
         };
 
@@ -190,7 +262,7 @@ impl Subscribe {
             cache_store,
             handler.as_ref(),
         )
-        .await;
+        .await
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -377,6 +449,7 @@ impl Subscribe {
             track_key,
             expires: subscription.expires(),
             content_exists: subscription.content_exists(),
+            max_cache_duration: subscription.max_cache_duration(),
             downstream_subscriber_count: 0,
             origin: UpstreamSubscriptionOrigin::Subscribe,
         };
@@ -431,7 +504,7 @@ impl Subscribe {
         egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
         cache_store: &Arc<TrackCacheStore>,
         handler: &dyn SubscribeHandler,
-    ) {
+    ) -> bool {
         let subscriber_track_alias = handler.allocate_track_alias();
 
         // Determined here so the Largest Location advertised in SUBSCRIBE_OK
@@ -454,6 +527,7 @@ impl Subscribe {
             handler.subscribe_id(),
             upstream_key.clone(),
             largest_location,
+            matches!(handler._filter_type(), FilterType::LargestObject),
         ) {
             tracing::error!(
                 subscribe_id = handler.subscribe_id(),
@@ -461,7 +535,8 @@ impl Subscribe {
                 track_name = %upstream_key.track_name,
                 "failed to register downstream subscription"
             );
-            return;
+            handler.release_track_alias(subscriber_track_alias);
+            return false;
         }
 
         let (ready_sender, ready_receiver) = tokio::sync::oneshot::channel();
@@ -476,6 +551,10 @@ impl Subscribe {
                 parent_span: Span::current(),
                 ready_sender,
                 largest_location,
+                expires: active_upstream
+                    .expires
+                    .filter(|expires| *expires > 0)
+                    .map(Duration::from_millis),
             })))
             .await
             .is_err()
@@ -486,7 +565,8 @@ impl Subscribe {
                 track_name = %upstream_key.track_name,
                 "failed to send EgressStartRequest"
             );
-            return;
+            handler.release_track_alias(subscriber_track_alias);
+            return false;
         }
         match ready_receiver.await {
             Ok(Ok(())) => {}
@@ -496,7 +576,7 @@ impl Subscribe {
                     subscribe_id = handler.subscribe_id(),
                     "failed to start egress runner"
                 );
-                return;
+                return false;
             }
             Err(error) => {
                 tracing::error!(
@@ -504,15 +584,28 @@ impl Subscribe {
                     subscribe_id = handler.subscribe_id(),
                     "egress runner readiness dropped"
                 );
-                return;
+                return false;
             }
         }
 
+        let max_cache_duration =
+            effective_max_cache_duration(cache_store.ttl(), active_upstream.max_cache_duration);
+        if let Some(requested) = handler.max_cache_duration()
+            && requested > max_cache_duration
+        {
+            tracing::warn!(
+                subscribe_id = handler.subscribe_id(),
+                requested_max_cache_duration = requested,
+                max_cache_duration,
+                "requested cache duration exceeds what the relay retains"
+            );
+        }
         if handler
-            .ok_with_track_alias(
+            .ok_with_max_cache_duration(
                 subscriber_track_alias,
                 active_upstream.expires.unwrap_or(0),
                 content_exists,
+                Some(max_cache_duration),
             )
             .await
             .is_err()
@@ -524,7 +617,7 @@ impl Subscribe {
             );
             // TODO: send_unsubscribe
             // TODO: close session
-            return;
+            return false;
         }
         tracing::info!(
             session_id = %session_id,
@@ -533,6 +626,7 @@ impl Subscribe {
             subscriber_track_alias = subscriber_track_alias,
             "downstream subscribe ok sent"
         );
+        true
     }
 
     #[tracing::instrument(
@@ -602,6 +696,32 @@ mod tests {
             .await;
     }
 
+    #[test]
+    fn effective_max_cache_duration_is_relay_ttl_without_upstream_value() {
+        // Arrange
+        let relay_ttl = Duration::from_secs(30);
+
+        // Act
+        let effective = effective_max_cache_duration(relay_ttl, None);
+
+        // Assert
+        assert_eq!(effective, 30_000);
+    }
+
+    #[test]
+    fn effective_max_cache_duration_takes_shorter_of_relay_and_upstream() {
+        // Arrange
+        let relay_ttl = Duration::from_secs(30);
+
+        // Act
+        let capped_by_upstream = effective_max_cache_duration(relay_ttl, Some(10_000));
+        let capped_by_relay = effective_max_cache_duration(relay_ttl, Some(60_000));
+
+        // Assert
+        assert_eq!(capped_by_upstream, 10_000);
+        assert_eq!(capped_by_relay, 30_000);
+    }
+
     #[test]
     fn upstream_timeout_maps_to_subscribe_error_timeout() {
         let error = UpstreamSubscriptionError::SubscribeFailed(anyhow::Error::new(
@@ -625,6 +745,34 @@ mod tests {
         assert_eq!(reason, "unauthorized");
     }
 
+    #[test]
+    fn absolute_range_ending_before_its_start_is_invalid_range() {
+        // Arrange
+        let start = Location {
+            group_id: 5,
+            object_id: 0,
+        };
+        let reversed = FilterType::AbsoluteRange {
+            location: start.clone(),
+            end_group: 4,
+        };
+        let single_group = FilterType::AbsoluteRange {
+            location: start,
+            end_group: 5,
+        };
+
+        // Act
+        let rejected = validate_filter(&reversed);
+        let accepted = validate_filter(&single_group);
+
+        // Assert
+        assert_eq!(
+            rejected.map_err(|(code, _)| code),
+            Err(SubscribeErrorCode::InvalidRange as u64)
+        );
+        assert!(accepted.is_ok());
+    }
+
     #[test]
     fn publisher_not_found_maps_to_track_does_not_exist() {
         let (code, _reason) =
@@ -689,4 +837,34 @@ mod tests {
             })
         );
     }
+
+    // A subscriber joining an already active upstream gets the cache's Largest
+    // Location; a track with nothing cached yet reports no content.
+    #[tokio::test]
+    async fn subscribe_largest_for_shared_upstream_comes_from_cache() {
+        // Arrange
+        let cache_store = TrackCacheStore::new();
+        let cached_key = TrackKey::new("live", "video");
+        let cache = cache_store.get_or_create(&cached_key);
+        append_one_object(&cache, 7).await;
+        let empty_key = TrackKey::new("live", "audio");
+
+        // Act
+        let cached =
+            resolve_subscribe_largest(&LargestObjectSource::LocalCache, &cached_key, &cache_store)
+                .await;
+        let empty =
+            resolve_subscribe_largest(&LargestObjectSource::LocalCache, &empty_key, &cache_store)
+                .await;
+
+        // Assert
+        assert_eq!(
+            cached,
+            Some(moqt::Location {
+                group_id: 7,
+                object_id: 0,
+            })
+        );
+        assert_eq!(empty, None);
+    }
 }
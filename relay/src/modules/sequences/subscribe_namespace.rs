@@ -1,8 +1,12 @@
 use crate::modules::{
     control_message_forwarder::ControlMessageForwarder,
     core::handler::subscribe_namespace::SubscribeNamespaceHandler,
+    inter_relay::InterRelayConnectionManager,
     route_registry::{RegisterNamespaceSubscriberError, RelayRouteRegistry, RouteStatus},
-    sequences::tables::table::{LocalPubSubDirectory, PeerKind},
+    sequences::{
+        CascadingRelayContext,
+        tables::table::{LocalPubSubDirectory, PeerKind},
+    },
     types::SessionId,
 };
 use tracing::Span;
@@ -23,9 +27,10 @@ impl SubscribeNameSpace {
         session_span: &Span,
         table: &dyn LocalPubSubDirectory,
         forwarder: &ControlMessageForwarder,
-        route_registry: &dyn RelayRouteRegistry,
+        cascading_relay_context: CascadingRelayContext<'_>,
         handler: &dyn SubscribeNamespaceHandler,
     ) {
+        let route_registry = cascading_relay_context.route_registry;
         let track_namespace_prefix = handler.track_namespace_prefix();
         tracing::info!(
             session_id = %session_id,
@@ -47,6 +52,14 @@ impl SubscribeNameSpace {
             table.unregister_subscribe_namespace(session_id, &track_namespace_prefix);
             return;
         }
+        if is_first_client {
+            self.subscribe_parent_relay(
+                &track_namespace_prefix,
+                forwarder,
+                cascading_relay_context.inter_relay_connection_manager,
+            )
+            .await;
+        }
         self.broadcast_to_subscribers(session_id, &track_namespace_prefix, forwarder, table)
             .await;
         self.notify_remote_publish_namespaces(
@@ -116,6 +129,45 @@ impl SubscribeNameSpace {
         }
     }
 
+    /// Asks the parent relay for the namespaces under the prefix. Its
+    /// PUBLISH_NAMESPACEs arrive on the inter-relay session and reach the
+    /// local subscribers like any relay-published namespace. A parent that
+    /// cannot be reached only costs the remote namespaces, so the local
+    /// subscription still succeeds.
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.subscribe_namespace.subscribe_parent_relay",
+        skip_all,
+        fields(track_namespace_prefix = %track_namespace_prefix)
+    )]
+    async fn subscribe_parent_relay(
+        &self,
+        track_namespace_prefix: &str,
+        forwarder: &ControlMessageForwarder,
+        inter_relay_connection_manager: &InterRelayConnectionManager,
+    ) {
+        let Some(parent_relay) = inter_relay_connection_manager.parent_relay() else {
+            return;
+        };
+        let result = async {
+            let session_id = inter_relay_connection_manager
+                .get_or_connect(parent_relay)
+                .await?;
+            forwarder
+                .subscribe_namespace(session_id, track_namespace_prefix.to_string())
+                .await
+        }
+        .await;
+        if let Err(err) = result {
+            tracing::warn!(
+                ?err,
+                relay_id = %parent_relay.relay_id,
+                track_namespace_prefix = %track_namespace_prefix,
+                "failed to forward SUBSCRIBE_NAMESPACE to parent relay"
+            );
+        }
+    }
+
     #[tracing::instrument(
         level = "info",
         name = "relay.sequence.subscribe_namespace.broadcast_to_subscribers",
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use crate::config::NamespacePolicyTable;
+use crate::modules::{
+    core::handler::subscribe_update::SubscribeUpdateHandler,
+    relay::egress::{coordinator::EgressCommand, scheduler::DeliveryWindow},
+    sequences::tables::table::{DownstreamSubscriptionUpdate, LocalPubSubDirectory},
+    types::SessionId,
+};
+use tracing::Span;
+
+/// Applies SUBSCRIBE_UPDATE to a downstream subscription: the directory keeps
+/// the new range and priority, and the running egress reader switches to the
+/// new delivery window. Upstream subscriptions are shared between downstream
+/// subscribers and are left untouched.
+pub(crate) struct SubscribeUpdate;
+
+/// An End Group before the Start Location's group selects nothing.
+fn is_valid_range(update: &DownstreamSubscriptionUpdate) -> bool {
+    update
+        .end_group
+        .is_none_or(|end_group| end_group >= update.start_location.group_id)
+}
+
+impl SubscribeUpdate {
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.subscribe_update",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id)
+    )]
+    pub(crate) async fn handle(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        table: &dyn LocalPubSubDirectory,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        namespace_policies: &NamespacePolicyTable,
+        handler: Box<dyn SubscribeUpdateHandler>,
+    ) {
+        let subscribe_id = handler.subscription_request_id();
+        // The namespace policy bounds updates as it bounds the SUBSCRIBE.
+        let subscriber_priority = table
+            .get_downstream_subscription(session_id, subscribe_id)
+            .and_then(|subscription| {
+                namespace_policies.lookup(&subscription.upstream_key.track_namespace)
+            })
+            .map_or(handler.subscriber_priority(), |policy| {
+                policy.subscriber_priority(handler.subscriber_priority())
+            });
+        let update = DownstreamSubscriptionUpdate {
+            start_location: handler.start_location(),
+            end_group: handler.end_group(),
+            subscriber_priority,
+            forward: handler.forward(),
+        };
+        if !is_valid_range(&update) {
+            tracing::warn!(
+                subscribe_id,
+                start_location = ?update.start_location,
+                end_group = ?update.end_group,
+                "ignoring SUBSCRIBE_UPDATE with End Group before Start Location"
+            );
+            return;
+        }
+        if !table.update_downstream_subscription(session_id, subscribe_id, update) {
+            tracing::warn!(
+                subscribe_id,
+                "active downstream subscription not found for SUBSCRIBE_UPDATE"
+            );
+            return;
+        }
+
+        if egress_sender
+            .send(EgressCommand::UpdateReader {
+                subscriber_session_id: session_id,
+                downstream_subscribe_id: subscribe_id,
+                delivery_window: DeliveryWindow {
+                    start_location: Some(update.start_location),
+                    end_group: update.end_group,
+                    forward: update.forward,
+                    subscriber_priority: Some(update.subscriber_priority),
+                    delivery_timeout: handler.delivery_timeout().map(Duration::from_millis),
+                },
+            })
+            .await
+            .is_err()
+        {
+            tracing::error!("failed to send EgressUpdateRequest");
+            return;
+        }
+
+        tracing::info!(
+            subscribe_id,
+            start_location = ?update.start_location,
+            end_group = ?update.end_group,
+            subscriber_priority = update.subscriber_priority,
+            forward = update.forward,
+            "downstream subscription updated"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(start_group_id: u64, end_group: Option<u64>) -> DownstreamSubscriptionUpdate {
+        DownstreamSubscriptionUpdate {
+            start_location: moqt::Location {
+                group_id: start_group_id,
+                object_id: 0,
+            },
+            end_group,
+            subscriber_priority: 128,
+            forward: true,
+        }
+    }
+
+    #[test]
+    fn open_ended_and_forward_ranges_are_valid() {
+        // Arrange / Act / Assert
+        assert!(is_valid_range(&update(5, None)));
+        assert!(is_valid_range(&update(5, Some(5))));
+    }
+
+    #[test]
+    fn end_group_before_start_is_invalid() {
+        // Arrange / Act
+        let valid = is_valid_range(&update(5, Some(4)));
+        // Assert
+        assert!(!valid);
+    }
+}
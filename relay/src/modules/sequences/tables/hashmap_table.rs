@@ -6,9 +6,9 @@ use tokio::sync::RwLock;
 use crate::modules::{
     core::handler::publish::PublishHandler,
     sequences::tables::table::{
-        ActiveUpstreamSubscription, DownstreamSubscription, LocalPubSubDirectory, PeerKind,
-        RemovedDownstreamSubscription, RemovedSessionSubscriptions, UpstreamSubscriptionKey,
-        UpstreamSubscriptionOrigin,
+        ActiveUpstreamSubscription, DownstreamSubscription, DownstreamSubscriptionUpdate,
+        EndedUpstream, LocalPubSubDirectory, PeerKind, RemovedDownstreamSubscription,
+        RemovedSessionSubscriptions, UpstreamSubscriptionKey, UpstreamSubscriptionOrigin,
     },
     types::{SessionId, TrackNamespace, TrackNamespacePrefix},
 };
@@ -24,6 +24,8 @@ pub(crate) struct InMemoryLocalPubSubDirectory {
      */
     pub(crate) publisher_namespaces: DashMap<TrackNamespace, DashMap<SessionId, PeerKind>>,
     pub(crate) subscriber_namespaces: DashMap<TrackNamespacePrefix, DashMap<SessionId, PeerKind>>,
+    /// Namespaces each session cancelled with PUBLISH_NAMESPACE_CANCEL.
+    pub(crate) cancelled_namespaces: DashMap<SessionId, DashSet<TrackNamespace>>,
     pub(crate) published_handlers: RwLock<Vec<(SessionId, Arc<dyn PublishHandler>)>>,
     pub(crate) track_alias_links: DashMap<(SessionId, u64, SessionId), u64>,
     pub(crate) active_upstream_subscriptions:
@@ -31,12 +33,73 @@ pub(crate) struct InMemoryLocalPubSubDirectory {
     pub(crate) downstream_subscriptions: DashMap<(SessionId, u64), DownstreamSubscription>,
 }
 
+impl InMemoryLocalPubSubDirectory {
+    /// Drops the session's cancellations under `track_namespace_prefix`, so a
+    /// fresh SUBSCRIBE_NAMESPACE (or its removal) starts from a clean slate.
+    fn clear_cancelled_namespaces(&self, session_id: SessionId, track_namespace_prefix: &str) {
+        if let Some(cancelled) = self.cancelled_namespaces.get(&session_id) {
+            cancelled
+                .retain(|track_namespace| !track_namespace.starts_with(track_namespace_prefix));
+        }
+        self.cancelled_namespaces
+            .remove_if(&session_id, |_, cancelled| cancelled.is_empty());
+    }
+
+    /// Removes the publisher session's upstream subscriptions accepted by
+    /// `matches`, together with the downstream subscriptions they feed.
+    fn drain_upstream_subscriptions(
+        &self,
+        publisher_session_id: SessionId,
+        matches: impl Fn(&ActiveUpstreamSubscription) -> bool,
+        removed: &mut RemovedSessionSubscriptions,
+    ) {
+        let upstream_subscriptions: Vec<_> = self
+            .active_upstream_subscriptions
+            .iter()
+            .filter_map(|entry| {
+                (entry.key().publisher_session_id == publisher_session_id && matches(entry.value()))
+                    .then_some((entry.key().clone(), entry.value().clone()))
+            })
+            .collect();
+        for (upstream_key, active_subscription) in upstream_subscriptions {
+            self.active_upstream_subscriptions.remove(&upstream_key);
+            removed
+                .upstream_track_keys
+                .push(active_subscription.track_key.clone());
+
+            let downstream_keys: Vec<_> = self
+                .downstream_subscriptions
+                .iter()
+                .filter_map(|entry| {
+                    (entry.value().upstream_key == upstream_key).then_some(*entry.key())
+                })
+                .collect();
+            for (downstream_session_id, downstream_subscribe_id) in downstream_keys {
+                self.downstream_subscriptions
+                    .remove(&(downstream_session_id, downstream_subscribe_id));
+                removed
+                    .downstream_subscriptions
+                    .push(RemovedDownstreamSubscription {
+                        downstream_session_id,
+                        downstream_subscribe_id,
+                        upstream_key: upstream_key.clone(),
+                        upstream_request_id: active_subscription.upstream_request_id,
+                        track_key: active_subscription.track_key.clone(),
+                        remaining_downstream_subscriber_count: 0,
+                        upstream_origin: active_subscription.origin,
+                    });
+            }
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
     fn new() -> Self {
         Self {
             publisher_namespaces: DashMap::new(),
             subscriber_namespaces: DashMap::new(),
+            cancelled_namespaces: DashMap::new(),
             published_handlers: RwLock::new(Vec::new()),
             track_alias_links: DashMap::new(),
             active_upstream_subscriptions: DashMap::new(),
@@ -96,6 +159,7 @@ impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
         for track_namespace_prefix in empty_prefixes {
             self.subscriber_namespaces.remove(&track_namespace_prefix);
         }
+        self.cancelled_namespaces.remove(&session_id);
 
         self.published_handlers
             .write()
@@ -133,44 +197,29 @@ impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
             }
         }
 
-        let upstream_subscriptions: Vec<_> = self
-            .active_upstream_subscriptions
-            .iter()
-            .filter_map(|entry| {
-                (entry.key().publisher_session_id == session_id)
-                    .then_some((entry.key().clone(), entry.value().clone()))
-            })
-            .collect();
-        for (upstream_key, active_subscription) in upstream_subscriptions {
-            self.active_upstream_subscriptions.remove(&upstream_key);
-            removed
-                .upstream_track_keys
-                .push(active_subscription.track_key.clone());
+        self.drain_upstream_subscriptions(session_id, |_| true, &mut removed);
 
-            let downstream_keys: Vec<_> = self
-                .downstream_subscriptions
-                .iter()
-                .filter_map(|entry| {
-                    (entry.value().upstream_key == upstream_key).then_some(*entry.key())
-                })
-                .collect();
-            for (downstream_session_id, downstream_subscribe_id) in downstream_keys {
-                self.downstream_subscriptions
-                    .remove(&(downstream_session_id, downstream_subscribe_id));
-                removed
-                    .downstream_subscriptions
-                    .push(RemovedDownstreamSubscription {
-                        downstream_session_id,
-                        downstream_subscribe_id,
-                        upstream_key: upstream_key.clone(),
-                        upstream_request_id: active_subscription.upstream_request_id,
-                        track_key: active_subscription.track_key.clone(),
-                        remaining_downstream_subscriber_count: 0,
-                        upstream_origin: active_subscription.origin,
-                    });
-            }
-        }
+        removed
+    }
 
+    fn remove_upstream_subscriptions(
+        &self,
+        publisher_session_id: SessionId,
+        ended: EndedUpstream<'_>,
+    ) -> RemovedSessionSubscriptions {
+        let mut removed = RemovedSessionSubscriptions::default();
+        self.drain_upstream_subscriptions(
+            publisher_session_id,
+            |subscription| match ended {
+                EndedUpstream::Namespace(track_namespace) => {
+                    subscription.track_key.track_namespace == track_namespace
+                }
+                EndedUpstream::RequestId(request_id) => {
+                    subscription.upstream_request_id == request_id
+                }
+            },
+            &mut removed,
+        );
         removed
     }
 
@@ -280,6 +329,7 @@ impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
         track_namespace_prefix: String,
         peer_kind: PeerKind,
     ) -> bool {
+        self.clear_cancelled_namespaces(session_id, &track_namespace_prefix);
         if let Some(sessions) = self.subscriber_namespaces.get_mut(&track_namespace_prefix) {
             let had_client = sessions
                 .iter()
@@ -311,6 +361,7 @@ impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
         session_id: SessionId,
         track_namespace_prefix: &str,
     ) -> bool {
+        self.clear_cancelled_namespaces(session_id, track_namespace_prefix);
         let Some(sessions) = self.subscriber_namespaces.get(track_namespace_prefix) else {
             return true;
         };
@@ -329,6 +380,19 @@ impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
         no_clients_remain
     }
 
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.local_pub_sub_directory.cancel_namespace_for_subscriber",
+        skip_all,
+        fields(session_id = %session_id, track_namespace = %track_namespace)
+    )]
+    fn cancel_namespace_for_subscriber(&self, session_id: SessionId, track_namespace: String) {
+        self.cancelled_namespaces
+            .entry(session_id)
+            .or_default()
+            .insert(track_namespace);
+    }
+
     #[tracing::instrument(
         level = "info",
         name = "relay.local_pub_sub_directory.register_publish",
@@ -360,6 +424,11 @@ impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
                     combined.insert(*session.key());
                 })
             });
+        combined.retain(|session_id| {
+            self.cancelled_namespaces
+                .get(session_id)
+                .is_none_or(|cancelled| !cancelled.contains(track_namespace))
+        });
         combined
     }
 
@@ -546,6 +615,7 @@ impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
         downstream_subscribe_id: u64,
         upstream_key: UpstreamSubscriptionKey,
         start_location: Option<moqt::Location>,
+        joinable: bool,
     ) -> bool {
         let Some(mut entry) = self.active_upstream_subscriptions.get_mut(&upstream_key) else {
             return false;
@@ -558,11 +628,29 @@ impl LocalPubSubDirectory for InMemoryLocalPubSubDirectory {
             DownstreamSubscription {
                 upstream_key,
                 start_location,
+                joinable,
+                last_update: None,
             },
         );
         true
     }
 
+    fn update_downstream_subscription(
+        &self,
+        downstream_session_id: SessionId,
+        downstream_subscribe_id: u64,
+        update: DownstreamSubscriptionUpdate,
+    ) -> bool {
+        let Some(mut entry) = self
+            .downstream_subscriptions
+            .get_mut(&(downstream_session_id, downstream_subscribe_id))
+        else {
+            return false;
+        };
+        entry.last_update = Some(update);
+        true
+    }
+
     fn remove_downstream_subscription(
         &self,
         downstream_session_id: SessionId,
@@ -734,6 +822,38 @@ mod tests {
         assert!(removed.publish_namespace_track_namespaces.is_empty());
     }
 
+    #[tokio::test]
+    async fn cancelled_namespace_is_excluded_from_namespace_subscribers() {
+        // Arrange: Two sessions subscribe to the same prefix.
+        let table = InMemoryLocalPubSubDirectory::new();
+        table.register_subscribe_namespace(1, "room/".to_string(), PeerKind::Client);
+        table.register_subscribe_namespace(2, "room/".to_string(), PeerKind::Client);
+
+        // Act: Session 1 cancels one namespace under the prefix.
+        table.cancel_namespace_for_subscriber(1, "room/member".to_string());
+
+        // Assert: Only the cancelled namespace stops reaching session 1.
+        let member_subscribers = table.get_namespace_subscribers("room/member");
+        assert!(!member_subscribers.contains(&1));
+        assert!(member_subscribers.contains(&2));
+        assert!(table.get_namespace_subscribers("room/other").contains(&1));
+    }
+
+    #[tokio::test]
+    async fn resubscribing_prefix_clears_cancelled_namespaces() {
+        // Arrange: Session 1 cancelled a namespace under its prefix.
+        let table = InMemoryLocalPubSubDirectory::new();
+        table.register_subscribe_namespace(1, "room/".to_string(), PeerKind::Client);
+        table.cancel_namespace_for_subscriber(1, "room/member".to_string());
+
+        // Act: Session 1 subscribes to the prefix again.
+        table.register_subscribe_namespace(1, "room/".to_string(), PeerKind::Client);
+
+        // Assert: The namespace reaches session 1 again.
+        assert!(table.get_namespace_subscribers("room/member").contains(&1));
+        assert!(table.cancelled_namespaces.get(&1).is_none());
+    }
+
     #[tokio::test]
     async fn register_subscribe_namespace_reports_only_the_first_client() {
         // Arrange: Start with a relay subscriber, which never owns the route.
@@ -983,6 +1103,7 @@ mod tests {
                 track_key: TrackKey::new("ns", "track"),
                 expires: None,
                 content_exists: ContentExists::False,
+                max_cache_duration: None,
                 downstream_subscriber_count: 0,
                 origin: UpstreamSubscriptionOrigin::Subscribe,
             },
@@ -996,7 +1117,8 @@ mod tests {
             2,
             100,
             upstream_key.clone(),
-            Some(largest)
+            Some(largest),
+            true
         ));
 
         let sub = table.get_downstream_subscription(2, 100).unwrap();
@@ -1025,18 +1147,63 @@ mod tests {
                 track_key: TrackKey::new("ns", "track"),
                 expires: None,
                 content_exists: ContentExists::False,
+                max_cache_duration: None,
                 downstream_subscriber_count: 0,
                 origin: UpstreamSubscriptionOrigin::Subscribe,
             },
         );
 
-        assert!(table.register_downstream_subscription(2, 100, upstream_key.clone(), None));
+        assert!(table.register_downstream_subscription(2, 100, upstream_key.clone(), None, true));
 
         let sub = table.get_downstream_subscription(2, 100).unwrap();
         assert_eq!(sub.upstream_key, upstream_key);
         assert!(sub.start_location.is_none());
     }
 
+    #[tokio::test]
+    async fn update_downstream_subscription_records_latest_update() {
+        // Arrange
+        let table = InMemoryLocalPubSubDirectory::new();
+        let upstream_key = UpstreamSubscriptionKey {
+            publisher_session_id: 1,
+            track_namespace: "ns".to_string(),
+            track_name: "track".to_string(),
+        };
+        table.register_upstream_subscription(
+            upstream_key.clone(),
+            ActiveUpstreamSubscription {
+                upstream_request_id: 1,
+                track_key: TrackKey::new("ns", "track"),
+                expires: None,
+                content_exists: ContentExists::False,
+                max_cache_duration: None,
+                downstream_subscriber_count: 0,
+                origin: UpstreamSubscriptionOrigin::Subscribe,
+            },
+        );
+        assert!(table.register_downstream_subscription(2, 100, upstream_key, None, true));
+        let update = DownstreamSubscriptionUpdate {
+            start_location: moqt::Location {
+                group_id: 4,
+                object_id: 0,
+            },
+            end_group: Some(9),
+            subscriber_priority: 16,
+            forward: false,
+        };
+
+        // Act
+        let updated = table.update_downstream_subscription(2, 100, update);
+        let unknown = table.update_downstream_subscription(2, 101, update);
+
+        // Assert
+        assert!(updated);
+        assert!(!unknown);
+        let sub = table.get_downstream_subscription(2, 100).unwrap();
+        assert_eq!(sub.last_update, Some(update));
+        assert!(sub.start_location.is_none());
+    }
+
     #[tokio::test]
     async fn finds_active_upstream_subscriptions_separately_from_publishers() {
         // Arrange: Register an active upstream subscription separately from publishers.
@@ -1054,6 +1221,7 @@ mod tests {
                 track_key: TrackKey::new("room/member", "video"),
                 expires: Some(30),
                 content_exists: ContentExists::False,
+                max_cache_duration: None,
                 downstream_subscriber_count: 1,
                 origin: UpstreamSubscriptionOrigin::Subscribe,
             },
@@ -1067,4 +1235,110 @@ mod tests {
         assert_eq!(active_subscriptions, vec![upstream_key.clone()]);
         assert_eq!(publisher_subscriptions, vec![upstream_key]);
     }
+
+    fn register_fed_track(
+        table: &InMemoryLocalPubSubDirectory,
+        track_namespace: &str,
+        upstream_request_id: u64,
+        downstream_session_id: SessionId,
+    ) {
+        let upstream_key = UpstreamSubscriptionKey {
+            publisher_session_id: 1,
+            track_namespace: track_namespace.to_string(),
+            track_name: "video".to_string(),
+        };
+        table.register_upstream_subscription(
+            upstream_key.clone(),
+            ActiveUpstreamSubscription {
+                upstream_request_id,
+                track_key: TrackKey::new(track_namespace, "video"),
+                expires: None,
+                content_exists: ContentExists::False,
+                max_cache_duration: None,
+                downstream_subscriber_count: 0,
+                origin: UpstreamSubscriptionOrigin::Subscribe,
+            },
+        );
+        assert!(table.register_downstream_subscription(
+            downstream_session_id,
+            upstream_request_id,
+            upstream_key,
+            None,
+            true
+        ));
+    }
+
+    #[tokio::test]
+    async fn remove_downstream_subscription_drops_upstream_with_the_last_subscriber() {
+        // Arrange: Two downstream subscribers share one upstream subscription.
+        let table = InMemoryLocalPubSubDirectory::new();
+        register_fed_track(&table, "room/a", 10, 2);
+        let upstream_key = UpstreamSubscriptionKey {
+            publisher_session_id: 1,
+            track_namespace: "room/a".to_string(),
+            track_name: "video".to_string(),
+        };
+        assert!(table.register_downstream_subscription(3, 5, upstream_key, None, true));
+
+        // Act: Both subscribers unsubscribe.
+        let first = table.remove_downstream_subscription(2, 10).unwrap();
+        let still_active = table.find_active_upstream_subscriptions("room/a", "video");
+        let last = table.remove_downstream_subscription(3, 5).unwrap();
+
+        // Assert: Only the last unsubscribe releases the upstream subscription.
+        assert_eq!(first.remaining_downstream_subscriber_count, 1);
+        assert_eq!(still_active.len(), 1);
+        assert_eq!(last.remaining_downstream_subscriber_count, 0);
+        assert_eq!(last.upstream_request_id, 10);
+        assert!(
+            table
+                .find_active_upstream_subscriptions("room/a", "video")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn remove_upstream_subscriptions_by_request_id_drains_fed_downstreams() {
+        // Arrange: Publisher 1 feeds two tracks to different subscribers.
+        let table = InMemoryLocalPubSubDirectory::new();
+        register_fed_track(&table, "room/a", 10, 2);
+        register_fed_track(&table, "room/b", 11, 3);
+
+        // Act: The publisher ends the first upstream subscription.
+        let removed = table.remove_upstream_subscriptions(1, EndedUpstream::RequestId(10));
+
+        // Assert: Only the ended track and its subscriber are removed.
+        assert_eq!(
+            removed.upstream_track_keys,
+            vec![TrackKey::new("room/a", "video")]
+        );
+        assert_eq!(removed.downstream_subscriptions.len(), 1);
+        assert_eq!(removed.downstream_subscriptions[0].downstream_session_id, 2);
+        assert!(table.get_downstream_subscription(2, 10).is_none());
+        assert!(table.get_downstream_subscription(3, 11).is_some());
+        assert!(
+            table
+                .find_active_upstream_subscriptions("room/a", "video")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn remove_upstream_subscriptions_by_namespace_ignores_other_publishers() {
+        // Arrange: Publisher 1 feeds a track in the withdrawn namespace.
+        let table = InMemoryLocalPubSubDirectory::new();
+        register_fed_track(&table, "room/a", 10, 2);
+
+        // Act: Another publisher withdraws the same namespace, then publisher 1 does.
+        let other = table.remove_upstream_subscriptions(4, EndedUpstream::Namespace("room/a"));
+        let removed = table.remove_upstream_subscriptions(1, EndedUpstream::Namespace("room/a"));
+
+        // Assert: Only the owning publisher's withdrawal ends the track.
+        assert!(other.upstream_track_keys.is_empty());
+        assert_eq!(
+            removed.upstream_track_keys,
+            vec![TrackKey::new("room/a", "video")]
+        );
+        assert_eq!(removed.downstream_subscriptions.len(), 1);
+    }
 }
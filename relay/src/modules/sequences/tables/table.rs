@@ -21,6 +21,8 @@ pub(crate) struct ActiveUpstreamSubscription {
     pub(crate) track_key: TrackKey,
     pub(crate) expires: Option<u64>,
     pub(crate) content_exists: ContentExists,
+    /// MAX_CACHE_DURATION (ms) the upstream granted, if it sent one.
+    pub(crate) max_cache_duration: Option<u64>,
     pub(crate) downstream_subscriber_count: usize,
     pub(crate) origin: UpstreamSubscriptionOrigin,
 }
@@ -45,6 +47,21 @@ pub(crate) struct DownstreamSubscription {
     pub(crate) upstream_key: UpstreamSubscriptionKey,
     /// The subscription's start location: the Largest Object Location at subscribe time.
     pub(crate) start_location: Option<moqt::Location>,
+    /// Subscribed with Filter Type Largest Object, the only kind a Joining
+    /// Fetch may reference.
+    pub(crate) joinable: bool,
+    /// Range and priority from the latest SUBSCRIBE_UPDATE; `None` while the
+    /// original SUBSCRIBE parameters still apply.
+    pub(crate) last_update: Option<DownstreamSubscriptionUpdate>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct DownstreamSubscriptionUpdate {
+    pub(crate) start_location: moqt::Location,
+    /// Last Group ID to deliver; `None` keeps the subscription open-ended.
+    pub(crate) end_group: Option<u64>,
+    pub(crate) subscriber_priority: u8,
+    pub(crate) forward: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -66,12 +83,30 @@ pub(crate) struct RemovedSessionSubscriptions {
     pub(crate) publish_namespace_track_namespaces: Vec<String>,
 }
 
+/// Which of a publisher session's upstream subscriptions ended without the
+/// session itself going away.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum EndedUpstream<'a> {
+    /// PUBLISH_NAMESPACE_DONE: every track in the namespace.
+    Namespace(&'a str),
+    /// PUBLISH_DONE: the subscription with this upstream Request ID.
+    RequestId(u64),
+}
+
 #[async_trait::async_trait]
 pub(crate) trait LocalPubSubDirectory: Send + Sync + 'static + Debug {
     fn new() -> Self
     where
         Self: Sized;
     async fn remove_session(&self, session_id: SessionId) -> RemovedSessionSubscriptions;
+    /// Drops the publisher session's matching upstream subscriptions along
+    /// with every downstream subscription fed by them. Only
+    /// `downstream_subscriptions` and `upstream_track_keys` are filled.
+    fn remove_upstream_subscriptions(
+        &self,
+        publisher_session_id: SessionId,
+        ended: EndedUpstream<'_>,
+    ) -> RemovedSessionSubscriptions;
     fn register_publish_namespace(
         &self,
         session_id: SessionId,
@@ -103,6 +138,10 @@ pub(crate) trait LocalPubSubDirectory: Send + Sync + 'static + Debug {
         session_id: SessionId,
         track_namespace_prefix: &str,
     ) -> bool;
+    /// Records a PUBLISH_NAMESPACE_CANCEL: the session stops counting as a
+    /// subscriber of this namespace even though one of its prefixes covers
+    /// it. Subscribing or unsubscribing a covering prefix clears it again.
+    fn cancel_namespace_for_subscriber(&self, session_id: SessionId, track_namespace: String);
     async fn register_publish(&self, session_id: SessionId, handler: Arc<dyn PublishHandler>);
     fn get_namespace_subscribers(&self, track_namespace: &str) -> DashSet<SessionId>;
     async fn get_subscribers(
@@ -157,6 +196,15 @@ pub(crate) trait LocalPubSubDirectory: Send + Sync + 'static + Debug {
         downstream_subscribe_id: u64,
         upstream_key: UpstreamSubscriptionKey,
         start_location: Option<moqt::Location>,
+        joinable: bool,
+    ) -> bool;
+    /// Records a SUBSCRIBE_UPDATE. Returns false when the subscription is
+    /// unknown to this session.
+    fn update_downstream_subscription(
+        &self,
+        downstream_session_id: SessionId,
+        downstream_subscribe_id: u64,
+        update: DownstreamSubscriptionUpdate,
     ) -> bool;
     fn remove_downstream_subscription(
         &self,
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use tracing::Span;
+
+use crate::modules::{
+    core::handler::track_status::TrackStatusHandler,
+    enums::{ContentExists, GroupOrder, Location, SubscribeErrorCode},
+    relay::cache::store::TrackCacheStore,
+    sequences::tables::table::LocalPubSubDirectory,
+    types::{SessionId, TrackKey},
+    upstream_publisher_resolver::UpstreamPublisherResolver,
+};
+
+pub(crate) struct TrackStatus;
+
+impl TrackStatus {
+    /// Answers TRACK_STATUS_REQUEST from relay state without subscribing.
+    ///
+    /// The Largest Location comes from the track cache. A track with no
+    /// cached objects but a resolvable publisher is reported as existing
+    /// with no content; anything else is TRACK_DOES_NOT_EXIST. The request is
+    /// not forwarded upstream, so a track whose objects have all been evicted
+    /// reports no content even if the publisher has sent some.
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.track_status",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id)
+    )]
+    pub(crate) async fn handle(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        table: &dyn LocalPubSubDirectory,
+        cache_store: &Arc<TrackCacheStore>,
+        upstream_publisher_resolver: &UpstreamPublisherResolver,
+        handler: Box<dyn TrackStatusHandler>,
+    ) {
+        let track_namespace = handler.track_namespace().to_string();
+        let track_name = handler.track_name().to_string();
+        tracing::info!(
+            session_id = %session_id,
+            request_id = handler.request_id(),
+            track_namespace = %track_namespace,
+            track_name = %track_name,
+            "SequenceHandler::track_status"
+        );
+
+        let track_key = TrackKey::new(&track_namespace, &track_name);
+        let largest_location = match cache_store.get(&track_key) {
+            Some(cache) => cache.largest_location().await,
+            None => None,
+        };
+        let content_exists = match largest_location {
+            Some(location) => ContentExists::True {
+                location: Location::from(location),
+            },
+            None => {
+                let publisher = match upstream_publisher_resolver
+                    .resolve(table, &track_namespace, &track_name)
+                    .await
+                {
+                    Ok(publisher) => publisher,
+                    Err(e) => {
+                        tracing::error!(?e, "Failed to resolve publisher for track status");
+                        let _ = handler
+                            .error(
+                                SubscribeErrorCode::InternalError as u64,
+                                "Failed to resolve publisher".to_string(),
+                            )
+                            .await;
+                        return;
+                    }
+                };
+                if publisher.is_none() {
+                    tracing::info!("Track status requested for unknown track");
+                    let _ = handler
+                        .error(
+                            SubscribeErrorCode::TrackDoesNotExist as u64,
+                            "Track not found".to_string(),
+                        )
+                        .await;
+                    return;
+                }
+                ContentExists::False
+            }
+        };
+
+        // The cache stores groups by id, so the relay reports ascending order.
+        if let Err(e) = handler.ok(GroupOrder::Ascending, content_exists).await {
+            tracing::error!(?e, "Failed to send TRACK_STATUS");
+        }
+    }
+}
@@ -1,8 +1,14 @@
 use crate::modules::{
     control_message_forwarder::ControlMessageForwarder,
     core::handler::unsubscribe::UnsubscribeHandler,
-    relay::{egress::coordinator::EgressCommand, ingress::ingress_coordinator::IngressCommand},
-    sequences::tables::table::{LocalPubSubDirectory, UpstreamSubscriptionOrigin},
+    enums::PublishDoneStatusCode,
+    relay::{
+        cache::store::TrackCacheStore, egress::coordinator::EgressCommand,
+        ingress::ingress_coordinator::IngressCommand,
+    },
+    sequences::tables::table::{
+        LocalPubSubDirectory, RemovedDownstreamSubscription, UpstreamSubscriptionOrigin,
+    },
     types::SessionId,
 };
 use tracing::Span;
@@ -26,6 +32,7 @@ impl Unsubscribe {
         forwarder: &ControlMessageForwarder,
         ingress_sender: &tokio::sync::mpsc::Sender<IngressCommand>,
         egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
         handler: Box<dyn UnsubscribeHandler>,
     ) {
         let subscribe_id = handler.subscribe_id();
@@ -35,6 +42,163 @@ impl Unsubscribe {
             "SequenceHandler::unsubscribe"
         );
 
+        Self::release(
+            session_id,
+            subscribe_id,
+            table,
+            forwarder,
+            ingress_sender,
+            egress_sender,
+            EgressCommand::StopReader {
+                subscriber_session_id: session_id,
+                downstream_subscribe_id: subscribe_id,
+            },
+            cache_store,
+            None,
+        )
+        .await;
+    }
+
+    /// Ends a downstream subscription whose AbsoluteRange egress has fully
+    /// delivered: it is released as on UNSUBSCRIBE, and the subscriber gets
+    /// PUBLISH_DONE with SUBSCRIPTION_ENDED. Groups up to `end_group` leave
+    /// the cache if no other subscriber is left.
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.subscription_range_complete",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id, subscribe_id)
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn complete_range(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        subscribe_id: u64,
+        end_group: u64,
+        table: &dyn LocalPubSubDirectory,
+        forwarder: &ControlMessageForwarder,
+        ingress_sender: &tokio::sync::mpsc::Sender<IngressCommand>,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
+    ) {
+        Self::release(
+            session_id,
+            subscribe_id,
+            table,
+            forwarder,
+            ingress_sender,
+            egress_sender,
+            EgressCommand::EndReader {
+                subscriber_session_id: session_id,
+                downstream_subscribe_id: subscribe_id,
+                status_code: PublishDoneStatusCode::SubscriptionEnded as u64,
+                reason_phrase: "subscription range complete".to_string(),
+            },
+            cache_store,
+            Some(end_group),
+        )
+        .await;
+    }
+
+    /// Ends a downstream subscription whose egress send queue overflowed
+    /// under the disconnect policy: it is released as on UNSUBSCRIBE, and
+    /// the subscriber gets PUBLISH_DONE with TOO_FAR_BEHIND.
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.subscriber_too_far_behind",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id, subscribe_id)
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn too_far_behind(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        subscribe_id: u64,
+        table: &dyn LocalPubSubDirectory,
+        forwarder: &ControlMessageForwarder,
+        ingress_sender: &tokio::sync::mpsc::Sender<IngressCommand>,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
+    ) {
+        Self::release(
+            session_id,
+            subscribe_id,
+            table,
+            forwarder,
+            ingress_sender,
+            egress_sender,
+            EgressCommand::EndReader {
+                subscriber_session_id: session_id,
+                downstream_subscribe_id: subscribe_id,
+                status_code: PublishDoneStatusCode::TooFarBehind as u64,
+                reason_phrase: "subscriber too far behind".to_string(),
+            },
+            cache_store,
+            None,
+        )
+        .await;
+    }
+
+    /// Ends a downstream subscription that outlived the `expires` its
+    /// SUBSCRIBE_OK advertised: it is released as on UNSUBSCRIBE, and the
+    /// subscriber gets PUBLISH_DONE with EXPIRED.
+    #[tracing::instrument(
+        level = "info",
+        name = "relay.sequence.subscription_expired",
+        skip_all,
+        parent = session_span,
+        fields(session_id = %session_id, subscribe_id)
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn expired(
+        &self,
+        session_id: SessionId,
+        session_span: &Span,
+        subscribe_id: u64,
+        table: &dyn LocalPubSubDirectory,
+        forwarder: &ControlMessageForwarder,
+        ingress_sender: &tokio::sync::mpsc::Sender<IngressCommand>,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        cache_store: &TrackCacheStore,
+    ) {
+        Self::release(
+            session_id,
+            subscribe_id,
+            table,
+            forwarder,
+            ingress_sender,
+            egress_sender,
+            EgressCommand::EndReader {
+                subscriber_session_id: session_id,
+                downstream_subscribe_id: subscribe_id,
+                status_code: PublishDoneStatusCode::Expired as u64,
+                reason_phrase: "subscription expired".to_string(),
+            },
+            cache_store,
+            None,
+        )
+        .await;
+    }
+
+    /// Removes the downstream subscription, stops its egress with
+    /// `egress_command`, and drops the upstream subscription and the track's
+    /// cache once no downstream subscriber is left.
+    #[allow(clippy::too_many_arguments)]
+    async fn release(
+        session_id: SessionId,
+        subscribe_id: u64,
+        table: &dyn LocalPubSubDirectory,
+        forwarder: &ControlMessageForwarder,
+        ingress_sender: &tokio::sync::mpsc::Sender<IngressCommand>,
+        egress_sender: &tokio::sync::mpsc::Sender<EgressCommand>,
+        egress_command: EgressCommand,
+        cache_store: &TrackCacheStore,
+        delivered_end_group: Option<u64>,
+    ) {
         let Some(removed) = table.remove_downstream_subscription(session_id, subscribe_id) else {
             tracing::warn!(
                 session_id = %session_id,
@@ -44,14 +208,7 @@ impl Unsubscribe {
             return;
         };
 
-        if egress_sender
-            .send(EgressCommand::StopReader {
-                subscriber_session_id: session_id,
-                downstream_subscribe_id: subscribe_id,
-            })
-            .await
-            .is_err()
-        {
+        if egress_sender.send(egress_command).await.is_err() {
             tracing::error!("Failed to send EgressStopRequest.");
         }
 
@@ -103,6 +260,40 @@ impl Unsubscribe {
                 );
             }
         }
+        Self::release_track_cache(table, cache_store, &removed, delivered_end_group).await;
+    }
+
+    /// Frees the cache of a track no downstream subscriber reads anymore
+    /// instead of waiting for its TTL: all of it once nothing feeds the
+    /// track, otherwise the groups up to a delivered range's End Group.
+    pub(crate) async fn release_track_cache(
+        table: &dyn LocalPubSubDirectory,
+        cache_store: &TrackCacheStore,
+        removed: &RemovedDownstreamSubscription,
+        delivered_end_group: Option<u64>,
+    ) {
+        let track_key = &removed.track_key;
+        let upstreams = table
+            .find_active_upstream_subscriptions(&track_key.track_namespace, &track_key.track_name);
+        let still_read = upstreams.iter().any(|upstream_key| {
+            table
+                .get_active_upstream_subscription(
+                    upstream_key.publisher_session_id,
+                    &upstream_key.track_namespace,
+                    &upstream_key.track_name,
+                )
+                .is_some_and(|upstream| upstream.downstream_subscriber_count > 0)
+        });
+        if still_read {
+            return;
+        }
+        if upstreams.is_empty() {
+            cache_store.purge(track_key);
+        } else if let Some(end_group) = delivered_end_group {
+            cache_store
+                .delete_groups_before(track_key, end_group.saturating_add(1))
+                .await;
+        }
     }
 }
 
@@ -115,12 +306,14 @@ mod tests {
     use super::*;
     use crate::modules::{
         core::{
-            data_receiver::fetch_receiver::UpstreamFetchReceiver,
+            data_object::DataObject, data_receiver::fetch_receiver::UpstreamFetchReceiver,
             data_receiver::receiver::DataReceiver, handler::publish::SubscribeOption,
             publisher::Publisher, session::Session, session_event::MoqtSessionEvent,
             subscriber::Subscriber, subscription::UpstreamSubscription,
         },
         enums::ContentExists,
+        error::RelayError,
+        relay::types::StreamSubgroupId,
         sequences::tables::{
             hashmap_table::InMemoryLocalPubSubDirectory,
             table::{ActiveUpstreamSubscription, UpstreamSubscriptionKey},
@@ -165,6 +358,10 @@ mod tests {
             Ok(())
         }
 
+        async fn send_subscribe_namespace(&self, _namespace: String) -> anyhow::Result<()> {
+            Ok(())
+        }
+
         async fn send_unsubscribe_namespace(&self, _namespace: String) -> anyhow::Result<()> {
             Ok(())
         }
@@ -214,6 +411,26 @@ mod tests {
         async fn receive_moqt_session_event(&self) -> anyhow::Result<MoqtSessionEvent> {
             std::future::pending().await
         }
+
+        async fn go_away(&self, _new_session_uri: &str) -> anyhow::Result<()> {
+            unimplemented!("not used in unsubscribe tests")
+        }
+
+        async fn drain(&self, _timeout: std::time::Duration) -> bool {
+            unimplemented!("not used in unsubscribe tests")
+        }
+
+        fn close(&self) {
+            unimplemented!("not used in unsubscribe tests")
+        }
+
+        fn terminate(&self, _error: &RelayError) {
+            unimplemented!("not used in unsubscribe tests")
+        }
+
+        fn connection_stats(&self) -> moqt::ConnectionStats {
+            unimplemented!("not used in unsubscribe tests")
+        }
     }
 
     struct TestContext {
@@ -223,6 +440,7 @@ mod tests {
         ingress_receiver: mpsc::Receiver<IngressCommand>,
         egress_sender: mpsc::Sender<EgressCommand>,
         egress_receiver: mpsc::Receiver<EgressCommand>,
+        cache_store: TrackCacheStore,
         unsubscribed_request_ids: Arc<Mutex<Vec<u64>>>,
     }
 
@@ -243,6 +461,7 @@ mod tests {
                 track_key: TrackKey::new("ns", "track"),
                 expires: None,
                 content_exists: ContentExists::False,
+                max_cache_duration: None,
                 downstream_subscriber_count: 0,
                 origin,
             },
@@ -253,6 +472,7 @@ mod tests {
                 *subscribe_id,
                 upstream_key.clone(),
                 None,
+                true,
             ));
         }
 
@@ -282,6 +502,7 @@ mod tests {
             ingress_receiver,
             egress_sender,
             egress_receiver,
+            cache_store: TrackCacheStore::new(),
             unsubscribed_request_ids,
         }
     }
@@ -295,6 +516,7 @@ mod tests {
                 &ctx.forwarder,
                 &ctx.ingress_sender,
                 &ctx.egress_sender,
+                &ctx.cache_store,
                 Box::new(MockUnsubscribeHandler { subscribe_id }),
             )
             .await;
@@ -332,6 +554,61 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn last_subscriber_purges_the_track_cache() {
+        // Arrange
+        let ctx = setup(UpstreamSubscriptionOrigin::Subscribe, &[(100, 10)]).await;
+        let track_key = TrackKey::new("ns", "track");
+        ctx.cache_store.get_or_create(&track_key);
+
+        // Act
+        run_unsubscribe(&ctx, 100, 10).await;
+
+        // Assert
+        assert!(ctx.cache_store.get(&track_key).is_none());
+    }
+
+    #[tokio::test]
+    async fn completed_range_of_a_published_track_deletes_the_delivered_groups() {
+        // Arrange: the publisher keeps pushing groups 0..4 after the range 0..=2
+        let ctx = setup(UpstreamSubscriptionOrigin::Publish, &[(100, 10)]).await;
+        let track_key = TrackKey::new("ns", "track");
+        let cache = ctx.cache_store.get_or_create(&track_key);
+        let subgroup = StreamSubgroupId::Value(0);
+        for group_id in 0..4 {
+            let header =
+                moqt::SubgroupHeader::new(1, group_id, moqt::SubgroupId::Value(0), 0, false, false);
+            cache
+                .append_live_stream_object(
+                    group_id,
+                    &subgroup,
+                    None,
+                    DataObject::SubgroupHeader(header),
+                )
+                .await;
+            cache.close_stream_subgroup(group_id, &subgroup).await;
+        }
+
+        // Act
+        Unsubscribe
+            .complete_range(
+                100,
+                &tracing::Span::none(),
+                10,
+                2,
+                &ctx.table,
+                &ctx.forwarder,
+                &ctx.ingress_sender,
+                &ctx.egress_sender,
+                &ctx.cache_store,
+            )
+            .await;
+
+        // Assert
+        assert!(!cache.has_stream_group(2).await);
+        assert!(cache.has_stream_group(3).await);
+    }
+
     #[tokio::test]
     async fn remaining_subscribers_keep_upstream_subscription() {
         let mut ctx = setup(
@@ -374,4 +651,94 @@ mod tests {
         assert!(ctx.unsubscribed_request_ids.lock().unwrap().is_empty());
         assert!(ctx.ingress_receiver.try_recv().is_err());
     }
+
+    #[tokio::test]
+    async fn completed_range_ends_reader_with_publish_done_and_releases_upstream() {
+        let mut ctx = setup(UpstreamSubscriptionOrigin::Subscribe, &[(100, 10)]).await;
+
+        Unsubscribe
+            .complete_range(
+                100,
+                &tracing::Span::none(),
+                10,
+                2,
+                &ctx.table,
+                &ctx.forwarder,
+                &ctx.ingress_sender,
+                &ctx.egress_sender,
+                &ctx.cache_store,
+            )
+            .await;
+
+        match ctx.egress_receiver.try_recv() {
+            Ok(EgressCommand::EndReader {
+                subscriber_session_id,
+                downstream_subscribe_id,
+                status_code,
+                ..
+            }) => {
+                assert_eq!(subscriber_session_id, 100);
+                assert_eq!(downstream_subscribe_id, 10);
+                assert_eq!(status_code, PublishDoneStatusCode::SubscriptionEnded as u64);
+            }
+            other => panic!("Expected EndReader, got {:?}", other.is_ok()),
+        }
+        assert_eq!(
+            *ctx.unsubscribed_request_ids.lock().unwrap(),
+            vec![UPSTREAM_REQUEST_ID]
+        );
+        assert!(ctx.table.get_downstream_subscription(100, 10).is_none());
+    }
+
+    #[tokio::test]
+    async fn lagging_subscriber_gets_publish_done_too_far_behind() {
+        let mut ctx = setup(UpstreamSubscriptionOrigin::Subscribe, &[(100, 10)]).await;
+
+        Unsubscribe
+            .too_far_behind(
+                100,
+                &tracing::Span::none(),
+                10,
+                &ctx.table,
+                &ctx.forwarder,
+                &ctx.ingress_sender,
+                &ctx.egress_sender,
+                &ctx.cache_store,
+            )
+            .await;
+
+        match ctx.egress_receiver.try_recv() {
+            Ok(EgressCommand::EndReader { status_code, .. }) => {
+                assert_eq!(status_code, PublishDoneStatusCode::TooFarBehind as u64);
+            }
+            other => panic!("Expected EndReader, got {:?}", other.is_ok()),
+        }
+        assert!(ctx.table.get_downstream_subscription(100, 10).is_none());
+    }
+
+    #[tokio::test]
+    async fn expired_subscription_gets_publish_done_expired() {
+        let mut ctx = setup(UpstreamSubscriptionOrigin::Subscribe, &[(100, 10)]).await;
+
+        Unsubscribe
+            .expired(
+                100,
+                &tracing::Span::none(),
+                10,
+                &ctx.table,
+                &ctx.forwarder,
+                &ctx.ingress_sender,
+                &ctx.egress_sender,
+                &ctx.cache_store,
+            )
+            .await;
+
+        match ctx.egress_receiver.try_recv() {
+            Ok(EgressCommand::EndReader { status_code, .. }) => {
+                assert_eq!(status_code, PublishDoneStatusCode::Expired as u64);
+            }
+            other => panic!("Expected EndReader, got {:?}", other.is_ok()),
+        }
+        assert!(ctx.table.get_downstream_subscription(100, 10).is_none());
+    }
 }
@@ -81,7 +81,15 @@ impl UnsubscribeNamespace {
             );
         }
 
-        let routes = Self::find_publisher_relays(track_namespace_prefix, route_registry).await;
+        let mut routes = Self::find_publisher_relays(track_namespace_prefix, route_registry).await;
+        // The parent relay was sent SUBSCRIBE_NAMESPACE by the first client.
+        if let Some(parent_relay) = inter_relay_connection_manager.parent_relay()
+            && !routes
+                .iter()
+                .any(|route| route.relay_id == parent_relay.relay_id)
+        {
+            routes.push(parent_relay.clone());
+        }
         for route in routes {
             let session_id = match inter_relay_connection_manager.get_or_connect(&route).await {
                 Ok(session_id) => session_id,
@@ -13,11 +13,8 @@ type TrackLockMap = DashMap<(String, String), Arc<Mutex<()>>>;
 /// track keys use independent locks and do not block each other.
 ///
 /// # Entry lifecycle
-/// Entries are left in place after the guard is dropped (option b).
-/// The map grows by the number of distinct tracks seen in this relay
-/// instance and can be GC-ed as a follow-up if needed.
-/// NOTE: for typical MoQT deployments the number of distinct tracks is
-/// bounded and small, so growth is not a concern in practice.
+/// An entry is removed when its last guard is dropped with no other task
+/// waiting on it, so the map only holds tracks being created right now.
 #[derive(Clone, Debug, Default)]
 pub(crate) struct UpstreamCreationSerializer {
     locks: Arc<TrackLockMap>,
@@ -32,18 +29,35 @@ impl UpstreamCreationSerializer {
 
     /// Acquire an exclusive async guard for the given track key.
     /// Returns an owned guard; dropping the guard releases the lock.
-    pub(crate) async fn lock(
-        &self,
-        track_namespace: &str,
-        track_name: &str,
-    ) -> OwnedMutexGuard<()> {
+    pub(crate) async fn lock(&self, track_namespace: &str, track_name: &str) -> TrackCreationGuard {
         let key = (track_namespace.to_owned(), track_name.to_owned());
         let mutex = self
             .locks
-            .entry(key)
+            .entry(key.clone())
             .or_insert_with(|| Arc::new(Mutex::new(())))
             .clone();
-        mutex.lock_owned().await
+        TrackCreationGuard {
+            guard: Some(mutex.lock_owned().await),
+            locks: self.locks.clone(),
+            key,
+        }
+    }
+}
+
+/// Exclusive hold on one track's upstream creation.
+pub(crate) struct TrackCreationGuard {
+    guard: Option<OwnedMutexGuard<()>>,
+    locks: Arc<TrackLockMap>,
+    key: (String, String),
+}
+
+impl Drop for TrackCreationGuard {
+    fn drop(&mut self) {
+        self.guard.take();
+        // A waiter holds its own clone of the mutex, taken under the same
+        // shard lock, so only an entry nobody waits on is removed.
+        self.locks
+            .remove_if(&self.key, |_, mutex| Arc::strong_count(mutex) == 1);
     }
 }
 
@@ -126,4 +140,27 @@ mod tests {
         );
         let _ = (end1, end2);
     }
+
+    /// The entry of a track goes away with its last guard, but not while
+    /// another task still waits for it.
+    #[tokio::test]
+    async fn entry_is_forgotten_once_no_task_holds_or_waits_for_it() {
+        let serializer = UpstreamCreationSerializer::new();
+
+        let first = serializer.lock("ns", "track").await;
+        let s2 = serializer.clone();
+        let waiter = tokio::spawn(async move {
+            let _guard = s2.lock("ns", "track").await;
+        });
+        tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
+        drop(first);
+        let len_while_waiting = serializer.locks.len();
+        waiter.await.unwrap();
+
+        assert_eq!(len_while_waiting, 1, "the waiter still needs the entry");
+        assert!(
+            serializer.locks.is_empty(),
+            "entry should be gone after the last guard"
+        );
+    }
 }
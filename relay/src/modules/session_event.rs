@@ -1,7 +1,10 @@
 use crate::modules::core::handler::{
-    fetch::FetchHandler, publish::PublishHandler, publish_namespace::PublishNamespaceHandler,
+    fetch::FetchHandler, fetch_cancel::FetchCancelHandler, publish::PublishHandler,
+    publish_done::PublishDoneHandler, publish_namespace::PublishNamespaceHandler,
+    publish_namespace_cancel::PublishNamespaceCancelHandler,
     publish_namespace_done::PublishNamespaceDoneHandler, subscribe::SubscribeHandler,
-    subscribe_namespace::SubscribeNamespaceHandler, unsubscribe::UnsubscribeHandler,
+    subscribe_namespace::SubscribeNamespaceHandler, subscribe_update::SubscribeUpdateHandler,
+    track_status::TrackStatusHandler, unsubscribe::UnsubscribeHandler,
     unsubscribe_namespace::UnsubscribeNamespaceHandler,
 };
 use crate::modules::types::SessionId;
@@ -9,12 +12,27 @@ use crate::modules::types::SessionId;
 pub(crate) enum SessionEvent {
     PublishNameSpace(SessionId, Box<dyn PublishNamespaceHandler>),
     PublishNamespaceDone(SessionId, Box<dyn PublishNamespaceDoneHandler>),
+    PublishNamespaceCancel(SessionId, Box<dyn PublishNamespaceCancelHandler>),
     SubscribeNameSpace(SessionId, Box<dyn SubscribeNamespaceHandler>),
     UnsubscribeNameSpace(SessionId, Box<dyn UnsubscribeNamespaceHandler>),
     Publish(SessionId, Box<dyn PublishHandler>),
     Subscribe(SessionId, Box<dyn SubscribeHandler>),
+    SubscribeUpdate(SessionId, Box<dyn SubscribeUpdateHandler>),
     Unsubscribe(SessionId, Box<dyn UnsubscribeHandler>),
+    PublishDone(SessionId, Box<dyn PublishDoneHandler>),
     Fetch(SessionId, Box<dyn FetchHandler>),
+    FetchCancel(SessionId, Box<dyn FetchCancelHandler>),
+    TrackStatus(SessionId, Box<dyn TrackStatusHandler>),
+    GoAway(SessionId, String),
+    /// Raised by egress, not the peer: every group of the downstream
+    /// subscription's AbsoluteRange, up to the End Group, has been delivered.
+    SubscriptionRangeComplete(SessionId, u64, u64),
+    /// Raised by egress: the downstream subscription's send queue overflowed
+    /// under the disconnect policy.
+    SubscriberTooFarBehind(SessionId, u64),
+    /// Raised by egress: the downstream subscription outlived the `expires`
+    /// its SUBSCRIBE_OK advertised.
+    SubscriptionExpired(SessionId, u64),
     Disconnected(SessionId),
     ProtocolViolation(SessionId),
 }
@@ -1,9 +1,16 @@
 use std::sync::Arc;
 
 use moqt::{Endpoint, TransportProtocol};
+use tokio_util::sync::CancellationToken;
 use tracing::Instrument;
 
 use crate::modules::{
+    certificate_watcher::{CertificateWatch, spawn_certificate_watcher},
+    core::session::Session,
+    error::RelayError,
+    qlog,
+    relay_events::{RelayEvent, RelayEvents},
+    resource_monitor,
     session_event::SessionEvent,
     session_repository::{SessionPeer, SessionRepository},
     types::generate_session_id,
@@ -16,7 +23,9 @@ fn relay_hostname() -> String {
 }
 
 pub struct SessionHandler {
-    join_handle: tokio::task::JoinHandle<()>,
+    /// One accept loop per endpoint bound to the port.
+    join_handles: Vec<tokio::task::JoinHandle<()>>,
+    certificate_watchers: Vec<tokio::task::JoinHandle<()>>,
 }
 
 impl SessionHandler {
@@ -24,21 +33,52 @@ impl SessionHandler {
         config: moqt::ServerConfig,
         repo: Arc<tokio::sync::Mutex<SessionRepository>>,
         relay_session_event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent>,
+        events: RelayEvents,
         accepted_peer: SessionPeer,
+        certificate_watch: CertificateWatch,
+        shutdown: CancellationToken,
     ) -> Self {
-        let endpoint = Endpoint::<T>::create_server(&config)
+        let endpoints = Endpoint::<T>::create_servers(&config)
             .inspect_err(|e| tracing::error!("failed to create server: {}", e))
             .unwrap();
-        let join_handle =
-            Self::create_joinhandle::<T>(endpoint, repo, relay_session_event_sender, accepted_peer);
-        Self { join_handle }
+        if endpoints.len() > 1 {
+            tracing::info!(
+                port = config.port,
+                accept_workers = endpoints.len(),
+                "Sharing the port across accept workers"
+            );
+        }
+        let certificate_watchers = endpoints
+            .iter()
+            .filter_map(Endpoint::certificate_reloader)
+            .map(|reloader| spawn_certificate_watcher(reloader, certificate_watch.clone()))
+            .collect();
+        let join_handles = endpoints
+            .into_iter()
+            .map(|endpoint| {
+                Self::create_joinhandle::<T>(
+                    endpoint,
+                    repo.clone(),
+                    relay_session_event_sender.clone(),
+                    events.clone(),
+                    accepted_peer.clone(),
+                    shutdown.clone(),
+                )
+            })
+            .collect();
+        Self {
+            join_handles,
+            certificate_watchers,
+        }
     }
 
     fn create_joinhandle<T: TransportProtocol>(
         mut endpoint: Endpoint<T>,
         repo: Arc<tokio::sync::Mutex<SessionRepository>>,
         relay_session_event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent>,
+        events: RelayEvents,
         accepted_peer: SessionPeer,
+        shutdown: CancellationToken,
     ) -> tokio::task::JoinHandle<()> {
         tokio::task::Builder::new()
             .spawn(async move {
@@ -54,8 +94,14 @@ impl SessionHandler {
                         session_peer = session_peer,
                         session_peer_relay_id = session_peer_relay_id,
                         relay_hostname = %relay_hostname,
+                        rtt_ms = tracing::field::Empty,
+                        congestion_window = tracing::field::Empty,
+                        lost_packets = tracing::field::Empty,
+                        dropped_datagrams = tracing::field::Empty,
                     );
-                    let connecting = async {
+                    // Stop accepting on shutdown; sessions already accepted
+                    // are drained by `RelayServer::shutdown`.
+                    let accept = async {
                         endpoint.accept().await.inspect_err(|error| {
                             if Self::is_endpoint_closing(error) {
                                 tracing::info!(%error, "transport endpoint closed");
@@ -64,8 +110,14 @@ impl SessionHandler {
                             }
                         })
                     }
-                    .instrument(session_span.clone())
-                    .await;
+                    .instrument(session_span.clone());
+                    let connecting = tokio::select! {
+                        _ = shutdown.cancelled() => {
+                            tracing::info!(peer = session_peer, "Stopped accepting sessions");
+                            break;
+                        }
+                        connecting = accept => connecting,
+                    };
                     let connecting = match connecting {
                         Ok(connecting) => connecting,
                         Err(error) => {
@@ -79,6 +131,7 @@ impl SessionHandler {
                     // Spawn per connection so a slow ClientSetup cannot block the accept loop.
                     let repo = repo.clone();
                     let relay_session_event_sender = relay_session_event_sender.clone();
+                    let events = events.clone();
                     let accepted_peer = accepted_peer.clone();
                     tokio::spawn(async move {
                         let session = async {
@@ -92,6 +145,18 @@ impl SessionHandler {
                             Ok(session) => session,
                             Err(_) => return,
                         };
+                        let pressure = resource_monitor::pressure();
+                        if pressure.refuses_sessions() {
+                            session_span.in_scope(|| {
+                                tracing::warn!(
+                                    ?pressure,
+                                    "refusing session under resource pressure"
+                                )
+                            });
+                            Session::terminate(&session, &RelayError::Overloaded);
+                            return;
+                        }
+                        qlog::observe_session(session_id, &session);
                         let session_add_span = tracing::info_span!(
                             parent: &session_span,
                             "relay.session_repository.add",
@@ -101,7 +166,7 @@ impl SessionHandler {
                         async {
                             tracing::info!("Session accepted");
                             let mut repo = repo.lock().await;
-                            match accepted_peer {
+                            match accepted_peer.clone() {
                                 SessionPeer::Client => {
                                     repo.add_client(
                                         session_id,
@@ -125,6 +190,10 @@ impl SessionHandler {
                         }
                         .instrument(session_add_span)
                         .await;
+                        events.emit(RelayEvent::SessionEstablished {
+                            session_id,
+                            relay_id: accepted_peer.relay_id().map(str::to_string),
+                        });
                     });
                 }
             })
@@ -141,7 +210,12 @@ impl SessionHandler {
 impl Drop for SessionHandler {
     fn drop(&mut self) {
         tracing::info!("Handle dropped.");
-        self.join_handle.abort();
+        for join_handle in &self.join_handles {
+            join_handle.abort();
+        }
+        for certificate_watcher in &self.certificate_watchers {
+            certificate_watcher.abort();
+        }
     }
 }
 
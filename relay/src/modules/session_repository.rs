@@ -1,4 +1,7 @@
-use std::sync::{Arc, Weak};
+use std::{
+    sync::{Arc, Weak},
+    time::Duration,
+};
 
 use dashmap::DashMap;
 use tracing::{Instrument, Span};
@@ -9,6 +12,8 @@ use crate::modules::{
         subscriber::Subscriber,
     },
     event_resolver::moqt_relay_event_resolver::RelaySessionEventResolver,
+    idle_reaper::SessionActivity,
+    qlog,
     session_event::SessionEvent,
     session_event_forward_task_registry::SessionEventForwardTaskRegistry,
     types::SessionId,
@@ -19,6 +24,10 @@ pub(crate) struct SessionRepository {
     sessions: DashMap<SessionId, Arc<dyn Session>>,
     session_spans: DashMap<SessionId, Span>,
     session_peers: DashMap<SessionId, SessionPeer>,
+    session_activities: DashMap<SessionId, Arc<SessionActivity>>,
+    /// New Session URI once the relay is shutting down; sessions accepted
+    /// after that are sent GOAWAY immediately.
+    going_away: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -77,6 +86,14 @@ fn log_session_event(event: &MoqtSessionEvent) {
                 "Received session event"
             );
         }
+        MoqtSessionEvent::PublishNamespaceCancel(handler) => {
+            tracing::info!(
+                event = "PublishNamespaceCancel",
+                track_namespace = %handler.track_namespace(),
+                error_code = handler.error_code(),
+                "Received session event"
+            );
+        }
         MoqtSessionEvent::SubscribeNamespace(handler) => {
             tracing::info!(
                 event = "SubscribeNamespace",
@@ -113,16 +130,27 @@ fn log_session_event(event: &MoqtSessionEvent) {
                 subscribe_id = handler.subscribe_id(),
                 track_namespace = %handler.track_namespace(),
                 track_name = %handler.track_name(),
-                subscriber_priority = handler._subscriber_priority(),
-                group_order = ?handler._group_order(),
+                subscriber_priority = handler.subscriber_priority(),
+                group_order = ?handler.group_order(),
                 forward = handler._forward(),
                 filter_type = %filter_type_label(&filter_type),
-                has_authorization_token = handler._authorization_token().is_some(),
-                max_cache_duration = ?handler._max_cache_duration(),
+                has_authorization_token = handler.authorization_token().is_some(),
+                max_cache_duration = ?handler.max_cache_duration(),
                 delivery_timeout = ?handler._delivery_timeout(),
                 "Received session event"
             );
         }
+        MoqtSessionEvent::SubscribeUpdate(handler) => {
+            tracing::info!(
+                event = "SubscribeUpdate",
+                subscription_request_id = handler.subscription_request_id(),
+                start_location = ?handler.start_location(),
+                end_group = ?handler.end_group(),
+                subscriber_priority = handler.subscriber_priority(),
+                forward = handler.forward(),
+                "Received session event"
+            );
+        }
         MoqtSessionEvent::Unsubscribe(handler) => {
             tracing::info!(
                 event = "Unsubscribe",
@@ -130,6 +158,14 @@ fn log_session_event(event: &MoqtSessionEvent) {
                 "Received session event"
             );
         }
+        MoqtSessionEvent::PublishDone(handler) => {
+            tracing::info!(
+                event = "PublishDone",
+                request_id = handler.request_id(),
+                status_code = handler.status_code(),
+                "Received session event"
+            );
+        }
         MoqtSessionEvent::Fetch(handler) => {
             tracing::info!(
                 event = "Fetch",
@@ -138,6 +174,29 @@ fn log_session_event(event: &MoqtSessionEvent) {
                 "Received session event"
             );
         }
+        MoqtSessionEvent::FetchCancel(handler) => {
+            tracing::info!(
+                event = "FetchCancel",
+                request_id = handler.request_id(),
+                "Received session event"
+            );
+        }
+        MoqtSessionEvent::TrackStatus(handler) => {
+            tracing::info!(
+                event = "TrackStatus",
+                request_id = handler.request_id(),
+                track_namespace = %handler.track_namespace(),
+                track_name = %handler.track_name(),
+                "Received session event"
+            );
+        }
+        MoqtSessionEvent::GoAway(new_session_uri) => {
+            tracing::info!(
+                event = "GoAway",
+                new_session_uri = %new_session_uri,
+                "Received session event"
+            );
+        }
         MoqtSessionEvent::Disconnected() => {
             tracing::info!(event = "Disconnected", "Received session event");
         }
@@ -154,6 +213,8 @@ impl SessionRepository {
             sessions: DashMap::new(),
             session_spans: DashMap::new(),
             session_peers: DashMap::new(),
+            session_activities: DashMap::new(),
+            going_away: None,
         }
     }
 
@@ -202,12 +263,20 @@ impl SessionRepository {
     ) {
         let arc_session: Arc<dyn Session> = Arc::from(session);
         tracing::info!(session_id = %session_id, peer = ?peer, "session peer classified");
+        if let Some(new_session_uri) = &self.going_away
+            && let Err(error) = arc_session.go_away(new_session_uri).await
+        {
+            tracing::warn!(session_id = %session_id, %error, "failed to send GOAWAY");
+        }
         self.sessions.insert(session_id, arc_session.clone());
         self.session_spans.insert(session_id, session_span.clone());
         self.session_peers.insert(session_id, peer);
+        let activity = Arc::new(SessionActivity::new());
+        self.session_activities.insert(session_id, activity.clone());
         self.start_session_event_forwarding(
             session_id,
             Arc::downgrade(&arc_session),
+            activity,
             relay_session_event_sender,
             session_span,
         );
@@ -217,7 +286,9 @@ impl SessionRepository {
         let session_removed = self.sessions.remove(&session_id).is_some();
         let session_span_removed = self.session_spans.remove(&session_id).is_some();
         let session_peer_removed = self.session_peers.remove(&session_id).is_some();
+        self.session_activities.remove(&session_id);
         self.session_event_forward_task_registry.remove(&session_id);
+        qlog::close_session(session_id);
         tracing::info!(
             session_id = %session_id,
             session_removed,
@@ -228,10 +299,26 @@ impl SessionRepository {
         );
     }
 
+    /// Marks the relay as going away and returns every current session so
+    /// the caller can send GOAWAY and drain them.
+    pub(crate) fn begin_go_away(&mut self, new_session_uri: &str) -> Vec<Arc<dyn Session>> {
+        self.going_away = Some(new_session_uri.to_string());
+        self.sessions
+            .iter()
+            .map(|session| session.value().clone())
+            .collect()
+    }
+
     pub(crate) fn session_span(&self, session_id: SessionId) -> Option<Span> {
         self.session_spans.get(&session_id).map(|span| span.clone())
     }
 
+    pub(crate) fn session(&self, session_id: SessionId) -> Option<Arc<dyn Session>> {
+        self.sessions
+            .get(&session_id)
+            .map(|session| session.value().clone())
+    }
+
     pub(crate) fn has_session(&self, session_id: SessionId) -> bool {
         self.sessions.contains_key(&session_id)
     }
@@ -246,10 +333,45 @@ impl SessionRepository {
         matches!(self.peer(session_id), Some(SessionPeer::Client))
     }
 
+    pub(crate) fn activity(&self, session_id: SessionId) -> Option<Arc<SessionActivity>> {
+        self.session_activities
+            .get(&session_id)
+            .map(|activity| activity.value().clone())
+    }
+
+    /// Client sessions with no activity for at least `timeout`, with how
+    /// long each has been idle.
+    pub(crate) fn idle_client_sessions(
+        &self,
+        timeout: Duration,
+    ) -> Vec<(SessionId, Arc<dyn Session>, Duration)> {
+        self.session_activities
+            .iter()
+            .filter(|entry| self.is_client_session(*entry.key()))
+            .filter_map(|entry| {
+                let idle_for = entry.value().idle_for();
+                let session = self.sessions.get(entry.key())?;
+                (idle_for >= timeout).then(|| (*entry.key(), session.value().clone(), idle_for))
+            })
+            .collect()
+    }
+
+    /// Every session with its span, for sampling connection statistics.
+    pub(crate) fn sessions_with_spans(&self) -> Vec<(SessionId, Arc<dyn Session>, Span)> {
+        self.sessions
+            .iter()
+            .filter_map(|entry| {
+                let span = self.session_spans.get(entry.key())?;
+                Some((*entry.key(), entry.value().clone(), span.clone()))
+            })
+            .collect()
+    }
+
     fn start_session_event_forwarding(
         &mut self,
         session_id: SessionId,
         session: Weak<dyn Session>,
+        activity: Arc<SessionActivity>,
         relay_session_event_sender: tokio::sync::mpsc::UnboundedSender<SessionEvent>,
         session_span: Span,
     ) {
@@ -267,6 +389,7 @@ impl SessionRepository {
                             let event = match session.receive_moqt_session_event().await {
                                 Ok(event) => {
                                     log_session_event(&event);
+                                    activity.touch();
                                     event
                                 }
                                 Err(e) => {
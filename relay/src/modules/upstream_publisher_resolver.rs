@@ -70,12 +70,18 @@ impl UpstreamPublisherResolver {
         track_namespace: &str,
         track_name: &str,
     ) -> anyhow::Result<Option<UpstreamSubscriptionKey>> {
-        let Some(relay) = self
+        // The route registry knows the relay a namespace is published on;
+        // failing that, a parent relay may have it further up the tree.
+        let relay = match self
             .route_registry
             .find_active_namespace_publisher(track_namespace)
             .await?
-        else {
-            return Ok(None);
+        {
+            Some(relay) => relay,
+            None => match self.inter_relay_connection_manager.parent_relay() {
+                Some(parent_relay) => parent_relay.clone(),
+                None => return Ok(None),
+            },
         };
 
         match self
@@ -135,6 +141,10 @@ mod tests {
 
     #[async_trait::async_trait]
     impl RelayRouteRegistry for StubRouteRegistry {
+        async fn set_relay_status(&self, _status: RouteStatus) -> anyhow::Result<()> {
+            unimplemented!("not used in resolver tests")
+        }
+
         async fn register_namespace_publisher(
             &self,
             _track_namespace: &str,
@@ -202,6 +212,7 @@ mod tests {
             Arc::new(InterRelayConnectionManager::new(
                 repository,
                 session_event_sender,
+                None,
             )),
         )
     }
@@ -1,38 +1,64 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::{self, UnboundedSender};
 
 use crate::modules::{
+    billing::{BillingExporter, BillingSink},
+    connection_stats::spawn_connection_stats_job,
     event_handler::EventHandler,
+    idle_reaper::spawn_idle_reaper_job,
     inter_relay::InterRelayConnectionManager,
+    qlog::spawn_qlog_writer,
+    recording::spawn_recorder,
     relay::{
-        cache::eviction_job::spawn_cache_eviction_job, egress::coordinator::EgressCoordinator,
-        ingress::ingress_coordinator::IngressCoordinator,
+        cache::eviction_job::spawn_cache_eviction_job,
+        egress::coordinator::EgressCoordinator,
+        ingress::{ingress_coordinator::IngressCoordinator, rate_limiter::IngressRateLimiter},
     },
-    route_registry::RelayRouteRegistry,
+    relay_events::RelayEvents,
+    resource_monitor::spawn_resource_monitor_job,
+    route_registry::{RelayInfo, RelayRouteRegistry},
     session_event::SessionEvent,
     session_repository::SessionRepository,
     upstream_publisher_resolver::UpstreamPublisherResolver,
 };
 use crate::relay_server::store::RelayStore;
+use crate::{Authorizer, EgressTuning, NamespacePolicyTable, RateLimits};
 
 pub(crate) struct RelayRuntime {
     _ingress: IngressCoordinator,
-    _egress: EgressCoordinator,
+    egress: EgressCoordinator,
     _manager: EventHandler,
-    _evict_job: tokio::task::JoinHandle<()>,
+    evict_job: tokio::task::JoinHandle<()>,
+    resource_monitor: tokio::task::JoinHandle<()>,
+    idle_reaper: tokio::task::JoinHandle<()>,
+    connection_stats: Option<tokio::task::JoinHandle<()>>,
+    billing: BillingExporter,
+    _qlog_writer: Option<tokio::task::JoinHandle<()>>,
+    recorder: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl RelayRuntime {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         repo: Arc<tokio::sync::Mutex<SessionRepository>>,
         store: &Arc<RelayStore>,
+        events: RelayEvents,
         route_registry: Arc<dyn RelayRouteRegistry>,
+        parent_relay: Option<RelayInfo>,
+        billing_sink: Arc<dyn BillingSink>,
+        bandwidth_probe_max_bytes: u64,
+        namespace_policies: Arc<NamespacePolicyTable>,
+        authorizer: Authorizer,
+        qlog_dir: Option<PathBuf>,
+        rate_limits: RateLimits,
+        egress_tuning: EgressTuning,
     ) -> (UnboundedSender<SessionEvent>, Self) {
         let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<SessionEvent>();
         let inter_relay_connection_manager = Arc::new(InterRelayConnectionManager::new(
             repo.clone(),
             sender.clone(),
+            parent_relay,
         ));
         let upstream_publisher_resolver = Arc::new(UpstreamPublisherResolver::new(
             route_registry.clone(),
@@ -42,12 +68,26 @@ impl RelayRuntime {
             repo.clone(),
             store.cache_store.clone(),
             store.object_notify_producer_map.clone(),
+            IngressRateLimiter::new(rate_limits),
         );
+        // The egress coordinator holds the only record sender, so the exporter
+        // drains and ends once the coordinator has stopped.
+        let (billing_sender, billing_receiver) = mpsc::unbounded_channel();
+        let billing = BillingExporter::run(billing_sink, billing_receiver);
         let egress = EgressCoordinator::new(
             repo.clone(),
             store.cache_store.clone(),
             store.object_notify_producer_map.clone(),
+            billing_sender,
+            sender.clone(),
+            egress_tuning,
         );
+        let idle_reaper = spawn_idle_reaper_job(
+            repo.clone(),
+            store.cache_store.clone(),
+            store.object_notify_producer_map.clone(),
+        );
+        let connection_stats = spawn_connection_stats_job(repo.clone());
         let manager = EventHandler::run(
             repo,
             receiver,
@@ -57,16 +97,64 @@ impl RelayRuntime {
             inter_relay_connection_manager,
             upstream_publisher_resolver,
             store.cache_store.clone(),
+            bandwidth_probe_max_bytes,
+            namespace_policies,
+            authorizer,
+            events,
         );
         let evict_job = spawn_cache_eviction_job(store.cache_store.clone());
+        let resource_monitor = spawn_resource_monitor_job();
+        let qlog_writer = qlog_dir.and_then(spawn_qlog_writer);
         (
             sender,
             Self {
                 _ingress: ingress,
-                _egress: egress,
+                egress,
                 _manager: manager,
-                _evict_job: evict_job,
+                evict_job,
+                resource_monitor,
+                idle_reaper,
+                connection_stats,
+                billing,
+                _qlog_writer: qlog_writer,
+                recorder: None,
             },
         )
     }
+
+    /// Records `namespaces` into `dir` until the relay stops.
+    pub(crate) fn start_recorder(
+        &mut self,
+        store: &Arc<RelayStore>,
+        dir: PathBuf,
+        namespaces: Vec<String>,
+    ) -> anyhow::Result<()> {
+        self.recorder = Some(spawn_recorder(store.cache_store.clone(), dir, namespaces)?);
+        Ok(())
+    }
+
+    /// Stops egress, so every live subscription emits its final billing
+    /// record, then waits for the exporter to hand the queued records to
+    /// the sink. Dropping the runtime without this aborts both tasks.
+    pub(crate) async fn shutdown(&mut self) {
+        self.egress.shutdown().await;
+        self.billing.shutdown().await;
+    }
+}
+
+impl Drop for RelayRuntime {
+    fn drop(&mut self) {
+        // The coordinators, event handler and billing exporter abort their own
+        // tasks if `shutdown` did not stop them first. The qlog writer is
+        // process-wide and keeps running.
+        self.evict_job.abort();
+        self.resource_monitor.abort();
+        self.idle_reaper.abort();
+        if let Some(connection_stats) = &self.connection_stats {
+            connection_stats.abort();
+        }
+        if let Some(recorder) = &self.recorder {
+            recorder.abort();
+        }
+    }
 }
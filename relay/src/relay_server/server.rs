@@ -1,12 +1,23 @@
-use std::sync::Arc;
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use moqt::ServerConfig;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{Notify, broadcast, mpsc::UnboundedSender};
+use tokio_util::sync::CancellationToken;
 
 use crate::relay_server::{runtime::RelayRuntime, store::RelayStore};
 use crate::{
-    RelayConfig,
+    Authorizer, CacheLimits, EgressTuning, NamespacePolicyTable, RateLimits, RelayConfig,
+    config::{DEFAULT_KEEP_ALIVE_INTERVAL_SEC, parent_relay_address},
     modules::{
+        billing::{BillingSink, FileBillingSink, NoopBillingSink, WebhookBillingSink},
+        certificate_watcher::CertificateWatch,
+        recording::{container::Recording, serve_recording},
+        relay_events::{RelayEvent, RelayEvents},
         route_registry::{
             NoopRelayRouteRegistry, RedisRelayRouteRegistry, RelayInfo, RelayRouteRegistry,
             RouteStatus,
@@ -17,19 +28,47 @@ use crate::{
     },
 };
 
+/// A running relay. Its endpoints are started with `spawn_*_transport`;
+/// [`shutdown`](Self::shutdown) stops them, drains the sessions and stops
+/// the relay's background tasks, so the relay can be embedded in a larger
+/// service.
 pub struct RelayServer {
     repo: Arc<tokio::sync::Mutex<SessionRepository>>,
     sender: UnboundedSender<SessionEvent>,
-    _store: Arc<RelayStore>,
-    _runtime: RelayRuntime,
+    store: Arc<RelayStore>,
+    runtime: RelayRuntime,
+    route_registry: Arc<dyn RelayRouteRegistry>,
+    events: RelayEvents,
     key_path: String,
     cert_path: String,
+    keep_alive_interval_sec: u64,
+    transport: moqt::TransportTuning,
+    tls_poll_interval: Option<Duration>,
+    setup_timeout: Option<Duration>,
+    max_client_sessions: Option<usize>,
+    accept_workers: usize,
+    control_message_limits: moqt::ControlMessageLimits,
+    certificate_reload_requests: Arc<Notify>,
+    shutdown_token: CancellationToken,
 }
 
 impl RelayServer {
     pub fn new(key_path: &str, cert_path: &str) -> Self {
         let route_registry: Arc<dyn RelayRouteRegistry> = Arc::new(NoopRelayRouteRegistry);
-        Self::new_with_route_registry(key_path, cert_path, route_registry)
+        Self::new_with_route_registry(
+            key_path,
+            cert_path,
+            route_registry,
+            None,
+            Arc::new(NoopBillingSink),
+            0,
+            Arc::new(NamespacePolicyTable::default()),
+            Authorizer::default(),
+            None,
+            &CacheLimits::default(),
+            RateLimits::default(),
+            EgressTuning::default(),
+        )
     }
 
     pub async fn new_with_config(
@@ -49,29 +88,114 @@ impl RelayServer {
         } else {
             Arc::new(NoopRelayRouteRegistry)
         };
-        Ok(Self::new_with_route_registry(
+        let parent_relay = match &config.parent_relay {
+            Some(address) => {
+                let (host, port) = parent_relay_address(address)?;
+                tracing::info!(parent_relay = %address, "Cascading from parent relay");
+                Some(RelayInfo {
+                    relay_id: format!("parent:{address}"),
+                    host,
+                    port,
+                    status: RouteStatus::Active,
+                })
+            }
+            None => None,
+        };
+        let billing_sink: Arc<dyn BillingSink> = if let Some(path) = config.billing_file {
+            tracing::info!(path = %path.display(), "Exporting billing records to file");
+            Arc::new(FileBillingSink::open(path).await?)
+        } else if let Some(url) = config.billing_webhook_url {
+            tracing::info!(%url, "Exporting billing records to webhook");
+            Arc::new(WebhookBillingSink::new(url))
+        } else {
+            Arc::new(NoopBillingSink)
+        };
+        if !config.namespace_policies.is_empty() {
+            tracing::info!(policies = ?config.namespace_policies, "Applying namespace policies");
+        }
+        if config.authorizer.is_enabled() {
+            tracing::info!(authorizer = ?config.authorizer, "Authorizing client requests");
+        }
+        let mut server = Self::new_with_route_registry(
             key_path,
             cert_path,
             route_registry,
-        ))
+            parent_relay,
+            billing_sink,
+            config.bandwidth_probe_max_bytes,
+            Arc::new(config.namespace_policies),
+            config.authorizer,
+            config.qlog_dir,
+            &config.cache_limits,
+            config.rate_limits,
+            config.egress,
+        );
+        server.keep_alive_interval_sec = config.keep_alive_interval_sec;
+        server.transport = config.transport;
+        server.tls_poll_interval = config.tls_poll_interval;
+        server.setup_timeout = config.setup_timeout;
+        server.max_client_sessions = config.max_client_sessions;
+        server.accept_workers = config.accept_workers;
+        server.control_message_limits = config.control_message_limits;
+        if let Some(dir) = config.record_dir {
+            server
+                .runtime
+                .start_recorder(&server.store, dir, config.record_namespaces)?;
+        }
+        Ok(server)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn new_with_route_registry(
         key_path: &str,
         cert_path: &str,
         route_registry: Arc<dyn RelayRouteRegistry>,
+        parent_relay: Option<RelayInfo>,
+        billing_sink: Arc<dyn BillingSink>,
+        bandwidth_probe_max_bytes: u64,
+        namespace_policies: Arc<NamespacePolicyTable>,
+        authorizer: Authorizer,
+        qlog_dir: Option<PathBuf>,
+        cache_limits: &CacheLimits,
+        rate_limits: RateLimits,
+        egress_tuning: EgressTuning,
     ) -> Self {
         let repo = Arc::new(tokio::sync::Mutex::new(SessionRepository::new()));
-        let store = RelayStore::new();
-        let (sender, runtime) = RelayRuntime::new(repo.clone(), &store, route_registry);
+        let store = RelayStore::new(cache_limits);
+        let events = RelayEvents::new();
+        let (sender, runtime) = RelayRuntime::new(
+            repo.clone(),
+            &store,
+            events.clone(),
+            route_registry.clone(),
+            parent_relay,
+            billing_sink,
+            bandwidth_probe_max_bytes,
+            namespace_policies,
+            authorizer,
+            qlog_dir,
+            rate_limits,
+            egress_tuning,
+        );
 
         Self {
             repo,
             sender,
-            _store: store,
-            _runtime: runtime,
+            store,
+            runtime,
+            route_registry,
+            events,
             key_path: key_path.to_string(),
             cert_path: cert_path.to_string(),
+            keep_alive_interval_sec: DEFAULT_KEEP_ALIVE_INTERVAL_SEC,
+            transport: moqt::TransportTuning::default(),
+            tls_poll_interval: None,
+            setup_timeout: None,
+            max_client_sessions: None,
+            accept_workers: 1,
+            control_message_limits: moqt::ControlMessageLimits::default(),
+            certificate_reload_requests: Arc::new(Notify::new()),
+            shutdown_token: CancellationToken::new(),
         }
     }
 
@@ -85,17 +209,134 @@ impl RelayServer {
             port,
             cert_path: self.cert_path.clone(),
             key_path: self.key_path.clone(),
-            keep_alive_interval_sec: 15,
+            keep_alive_interval_sec: self.keep_alive_interval_sec,
+            transport: self.transport,
+            setup_timeout: self.setup_timeout,
+            // Peer relays are never refused.
+            max_sessions: match accepted_peer {
+                SessionPeer::Client => self.max_client_sessions,
+                SessionPeer::Relay { .. } => None,
+            },
+            // Peer relays are few; one accept loop serves them.
+            accept_workers: match accepted_peer {
+                SessionPeer::Client => self.accept_workers,
+                SessionPeer::Relay { .. } => 1,
+            },
+            // A cascade link carries every subscription of its relay.
+            control_message_limits: match accepted_peer {
+                SessionPeer::Client => self.control_message_limits,
+                SessionPeer::Relay { .. } => moqt::ControlMessageLimits {
+                    max_messages_per_sec: 0,
+                    ..self.control_message_limits
+                },
+            },
+        };
+
+        let certificate_watch = CertificateWatch {
+            cert_path: self.cert_path.clone().into(),
+            key_path: self.key_path.clone().into(),
+            poll_interval: self.tls_poll_interval,
+            reload_requests: self.certificate_reload_requests.clone(),
+            shutdown: self.shutdown_token.clone(),
         };
 
         SessionHandler::run::<T>(
             server_config,
             self.repo.clone(),
             self.sender.clone(),
+            self.events.clone(),
             accepted_peer,
+            certificate_watch,
+            self.shutdown_token.clone(),
         )
     }
 
+    /// Sessions established and closed, namespaces announced and
+    /// subscriptions started on this relay, from now on. A receiver that
+    /// falls behind by more than its buffer skips the oldest events.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<RelayEvent> {
+        self.events.subscribe()
+    }
+
+    /// Re-reads the TLS certificate and key on every endpoint, e.g. on
+    /// SIGHUP after a renewal. Connections accepted afterwards use the new
+    /// certificate; established sessions keep running. A pair that fails to
+    /// load is logged and the previous certificate stays in use.
+    pub fn reload_certificates(&self) {
+        tracing::info!(cert_path = %self.cert_path, "Reloading TLS certificates");
+        self.certificate_reload_requests.notify_waiters();
+    }
+
+    /// Announces the track recorded at `path` through the relay listening
+    /// on `relay_addr` (normally this one's client port) and serves it to
+    /// subscribers, paced as recorded, and to FETCH until shutdown.
+    pub async fn replay_recording(
+        &self,
+        path: impl AsRef<Path>,
+        relay_addr: SocketAddr,
+    ) -> anyhow::Result<tokio::task::JoinHandle<()>> {
+        let recording = Arc::new(Recording::read(path.as_ref()).await?);
+        let shutdown = self.shutdown_token.clone();
+        Ok(tokio::spawn(async move {
+            let track_key = recording.track_key.clone();
+            if let Err(error) = serve_recording(recording, relay_addr, shutdown).await {
+                tracing::warn!(%track_key, %error, "Replay stopped");
+            }
+        }))
+    }
+
+    /// Gracefully shuts the relay down: stops the accept loops, marks the
+    /// relay as draining in the route registry, sends GOAWAY with
+    /// `new_session_uri` on every session, stops accepting new
+    /// SUBSCRIBEs, waits up to `drain_timeout` for in-flight subgroup
+    /// streams, then closes sessions, stops egress and flushes the final
+    /// billing records before the remaining background tasks are stopped.
+    pub async fn shutdown(mut self, new_session_uri: &str, drain_timeout: Duration) {
+        self.shutdown_token.cancel();
+        // Peer relays stop picking this one for new subscriptions while the
+        // sessions below drain.
+        if let Err(error) = self
+            .route_registry
+            .set_relay_status(RouteStatus::Draining)
+            .await
+        {
+            tracing::warn!(?error, "failed to mark the relay as draining");
+        }
+        let sessions = self.repo.lock().await.begin_go_away(new_session_uri);
+        tracing::info!(
+            sessions = sessions.len(),
+            new_session_uri,
+            "Sending GOAWAY to all sessions"
+        );
+        let mut drains = tokio::task::JoinSet::new();
+        for session in sessions {
+            if let Err(error) = session.go_away(new_session_uri).await {
+                tracing::warn!(%error, "failed to send GOAWAY");
+            }
+            drains.spawn(async move {
+                if !session.drain(drain_timeout).await {
+                    tracing::warn!("drain timed out with subgroup streams still open");
+                }
+                session.close();
+            });
+        }
+        while drains.join_next().await.is_some() {}
+        tracing::info!("All sessions drained and closed");
+        self.runtime.shutdown().await;
+    }
+
+    /// Waits for the embedding service to cancel `shutdown`, then shuts the
+    /// relay down like [`shutdown`](Self::shutdown).
+    pub async fn shutdown_on(
+        self,
+        shutdown: CancellationToken,
+        new_session_uri: &str,
+        drain_timeout: Duration,
+    ) {
+        shutdown.cancelled().await;
+        self.shutdown(new_session_uri, drain_timeout).await;
+    }
+
     pub fn spawn_client_transport<T: moqt::TransportProtocol>(&self, port: u16) -> SessionHandler {
         self.spawn_transport::<T>(port, SessionPeer::Client)
     }
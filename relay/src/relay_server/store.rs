@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
-use crate::modules::relay::{
-    cache::store::TrackCacheStore, notifications::track_notifier::ObjectNotifyProducerMap,
+use crate::{
+    CacheLimits,
+    modules::relay::{
+        cache::{store::TrackCacheStore, transit::transit_warn_threshold_from_env},
+        notifications::track_notifier::ObjectNotifyProducerMap,
+    },
 };
 
 pub(crate) struct RelayStore {
@@ -10,9 +14,14 @@ pub(crate) struct RelayStore {
 }
 
 impl RelayStore {
-    pub(crate) fn new() -> Arc<Self> {
+    pub(crate) fn new(cache_limits: &CacheLimits) -> Arc<Self> {
         Arc::new(Self {
-            cache_store: Arc::new(TrackCacheStore::new()),
+            cache_store: Arc::new(
+                TrackCacheStore::with_ttl(cache_limits.ttl)
+                    .with_transit_warn_threshold(transit_warn_threshold_from_env())
+                    .with_quota_config(cache_limits.quota_config())
+                    .with_disk_tier(cache_limits.disk_tier()),
+            ),
             object_notify_producer_map: Arc::new(ObjectNotifyProducerMap::new()),
         })
     }
@@ -0,0 +1,185 @@
+//! A relay configured with a parent relay must pull namespaces and tracks it
+//! has no publisher for from the parent and serve them to its own clients.
+
+use std::net::{SocketAddr, UdpSocket};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use moqt::{
+    ClientConfig, DataReceiver, Endpoint, ExtensionHeaders, FetchObject, FetchObjectField,
+    FilterType, GroupOrder, QUIC, Session, SessionEvent, Subgroup, SubscribeOption,
+};
+use rcgen::{CertifiedKey, generate_simple_self_signed};
+use relay::{RelayConfig, RelayServer};
+
+/// Grabs an ephemeral UDP port, then releases it so the relay can bind it.
+fn free_udp_port() -> u16 {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.local_addr().unwrap().port()
+}
+
+/// Writes a fresh self-signed cert/key into `dir`. Returns (key_path, cert_path).
+fn generate_certs(dir: &Path) -> (String, String) {
+    let CertifiedKey { cert, signing_key } =
+        generate_simple_self_signed(vec!["localhost".to_string(), "127.0.0.1".to_string()])
+            .unwrap();
+    std::fs::create_dir_all(dir).unwrap();
+    let cert_path = dir.join("cert.pem");
+    let key_path = dir.join("key.pem");
+    std::fs::write(&cert_path, cert.pem()).unwrap();
+    std::fs::write(&key_path, signing_key.serialize_pem()).unwrap();
+    (
+        key_path.to_string_lossy().into_owned(),
+        cert_path.to_string_lossy().into_owned(),
+    )
+}
+
+fn client_endpoint() -> Endpoint<QUIC> {
+    Endpoint::<QUIC>::create_client(&ClientConfig {
+        port: 0,
+        verify_certificate: false,
+    })
+    .unwrap()
+}
+
+fn put_string(buf: &mut BytesMut, value: &str) {
+    buf.put_u32(value.len() as u32);
+    buf.put_slice(value.as_bytes());
+}
+
+/// Writes a recording of `live/room` `video` with three groups of one
+/// object, 300 ms apart, in the relay's recording layout.
+fn write_recording(path: &Path) {
+    let mut buf = BytesMut::new();
+    buf.put_slice(b"MOQTREC1");
+    put_string(&mut buf, "live/room");
+    put_string(&mut buf, "video");
+    for group_id in 0..3u64 {
+        buf.put_u64(group_id * 300);
+        buf.put_u32(1);
+        let object = FetchObjectField::new(
+            group_id,
+            0,
+            0,
+            128,
+            ExtensionHeaders::default(),
+            FetchObject::Payload(Bytes::from(format!("g{group_id}"))),
+        )
+        .encode();
+        buf.put_u32(object.len() as u32);
+        buf.put_slice(&object);
+    }
+    std::fs::write(path, buf).unwrap();
+}
+
+async fn connect(endpoint: &Endpoint<QUIC>, remote: SocketAddr) -> Session<QUIC> {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        endpoint.connect(remote, "127.0.0.1").await?.await
+    })
+    .await
+    .expect("session timed out")
+    .expect("session failed")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn child_relay_serves_namespaces_and_tracks_of_its_parent() {
+    let parent_port = free_udp_port();
+    let parent_inner_port = free_udp_port();
+    let child_port = free_udp_port();
+    let parent_addr: SocketAddr = format!("127.0.0.1:{parent_port}").parse().unwrap();
+    let child_addr: SocketAddr = format!("127.0.0.1:{child_port}").parse().unwrap();
+    let dir = std::env::temp_dir().join(format!("relay-cascade-{parent_port}"));
+    let (key_path, cert_path) = generate_certs(&dir);
+    let recording_path = dir.join("live_room_video.moqrec");
+    write_recording(&recording_path);
+
+    let parent = RelayServer::new(&key_path, &cert_path);
+    let _parent_client = parent.spawn_client_transport::<QUIC>(parent_port);
+    let _parent_inner = parent.spawn_inner_transport::<QUIC>(parent_inner_port);
+    let _replay = parent
+        .replay_recording(&recording_path, parent_addr)
+        .await
+        .unwrap();
+    let mut config = RelayConfig::from_env().unwrap();
+    config.parent_relay = Some(format!("127.0.0.1:{parent_inner_port}"));
+    let child = RelayServer::new_with_config(&key_path, &cert_path, config)
+        .await
+        .unwrap();
+    let _child_client = child.spawn_client_transport::<QUIC>(child_port);
+    // Let the replay session announce its namespace on the parent.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let endpoint = client_endpoint();
+    let session = Arc::new(connect(&endpoint, child_addr).await);
+    let mut subscriber = session.subscriber();
+    // The relay waits for PUBLISH_NAMESPACE_OK, so announcements are
+    // accepted as they come in.
+    let (announced_sender, mut announced_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let events = session.clone();
+    let _announcements = tokio::spawn(async move {
+        while let Ok(event) = events.receive_event().await {
+            if let SessionEvent::PublishNamespace(handler) = event {
+                handler.ok().await.unwrap();
+                let _ = announced_sender.send(handler.track_namespace.clone());
+            }
+        }
+    });
+    subscriber
+        .subscribe_namespace("live".to_string())
+        .await
+        .expect("subscribe to the namespace prefix");
+    let announced = tokio::time::timeout(Duration::from_secs(5), announced_receiver.recv())
+        .await
+        .expect("the parent's namespace was not announced")
+        .unwrap();
+    assert_eq!(announced, "live/room");
+
+    let subscription = subscriber
+        .subscribe(
+            "live/room".to_string(),
+            "video".to_string(),
+            SubscribeOption {
+                subscriber_priority: 128,
+                group_order: GroupOrder::Ascending,
+                forward: true,
+                filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
+            },
+        )
+        .await
+        .expect("subscribe through the child relay");
+    let DataReceiver::Stream(mut factory) = subscriber
+        .accept_data_receiver(&subscription)
+        .await
+        .unwrap()
+    else {
+        panic!("replay uses subgroup streams");
+    };
+    // The first group may race the subscription being set up across both
+    // relays; the last one follows 600 ms after it.
+    let payload = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let mut stream = factory.next().await.unwrap();
+            let mut group_id = None;
+            let mut payload = None;
+            while let Ok(Some(received)) = stream.receive().await {
+                match received {
+                    Subgroup::Header(header) => group_id = Some(header.group_id),
+                    Subgroup::Object(object) => payload = Some(object.subgroup_object),
+                }
+            }
+            if group_id == Some(2) {
+                return payload;
+            }
+        }
+    })
+    .await
+    .expect("the parent's groups did not arrive");
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(
+        payload,
+        Some(moqt::SubgroupObject::new_payload(Bytes::from("g2")))
+    );
+}
@@ -0,0 +1,80 @@
+//! `RelayServer::shutdown` must stop the accept loop and close established
+//! sessions, so an embedding service can stop the relay without killing the
+//! process.
+
+use std::net::{SocketAddr, UdpSocket};
+use std::path::Path;
+use std::time::Duration;
+
+use moqt::{ClientConfig, Endpoint, QUIC};
+use rcgen::{CertifiedKey, generate_simple_self_signed};
+use relay::RelayServer;
+
+/// Grabs an ephemeral UDP port, then releases it so the relay can bind it.
+fn free_udp_port() -> u16 {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.local_addr().unwrap().port()
+}
+
+/// Writes a fresh self-signed cert/key into `dir`. Returns (key_path, cert_path).
+fn generate_certs(dir: &Path) -> (String, String) {
+    let CertifiedKey { cert, signing_key } =
+        generate_simple_self_signed(vec!["localhost".to_string(), "127.0.0.1".to_string()])
+            .unwrap();
+    std::fs::create_dir_all(dir).unwrap();
+    let cert_path = dir.join("cert.pem");
+    let key_path = dir.join("key.pem");
+    std::fs::write(&cert_path, cert.pem()).unwrap();
+    std::fs::write(&key_path, signing_key.serialize_pem()).unwrap();
+    (
+        key_path.to_string_lossy().into_owned(),
+        cert_path.to_string_lossy().into_owned(),
+    )
+}
+
+fn client_endpoint() -> Endpoint<QUIC> {
+    Endpoint::<QUIC>::create_client(&ClientConfig {
+        port: 0,
+        verify_certificate: false,
+    })
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn shutdown_stops_accepting_and_returns() {
+    let port = free_udp_port();
+    let remote: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+    let host = "127.0.0.1";
+
+    let cert_dir = std::env::temp_dir().join(format!("relay-graceful-shutdown-{port}"));
+    let (key_path, cert_path) = generate_certs(&cert_dir);
+
+    let server = RelayServer::new(&key_path, &cert_path);
+    // Held past the shutdown: the accept loop must stop on its own.
+    let _handler = server.spawn_client_transport::<QUIC>(port);
+
+    let endpoint = client_endpoint();
+    let _session = tokio::time::timeout(Duration::from_secs(2), async {
+        endpoint.connect(remote, host).await?.await
+    })
+    .await
+    .expect("session before shutdown timed out")
+    .expect("session before shutdown failed");
+
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        server.shutdown("", Duration::from_millis(100)),
+    )
+    .await
+    .expect("shutdown did not finish");
+
+    let late_endpoint = client_endpoint();
+    let late = tokio::time::timeout(Duration::from_secs(1), async {
+        late_endpoint.connect(remote, host).await?.await
+    })
+    .await;
+    assert!(
+        !matches!(late, Ok(Ok(_))),
+        "a session was established after shutdown"
+    );
+}
@@ -0,0 +1,182 @@
+//! A recording replayed through the relay must reach subscribers as a live
+//! track would, and FETCH must be served from it.
+
+use std::net::{SocketAddr, UdpSocket};
+use std::path::Path;
+use std::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use moqt::{
+    ClientConfig, DataReceiver, Endpoint, ExtensionHeaders, Fetch, FetchObject, FetchObjectField,
+    FetchOption, FilterType, GroupOrder, Location, QUIC, Session, Subgroup, SubscribeOption,
+};
+use rcgen::{CertifiedKey, generate_simple_self_signed};
+use relay::RelayServer;
+
+/// Grabs an ephemeral UDP port, then releases it so the relay can bind it.
+fn free_udp_port() -> u16 {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.local_addr().unwrap().port()
+}
+
+/// Writes a fresh self-signed cert/key into `dir`. Returns (key_path, cert_path).
+fn generate_certs(dir: &Path) -> (String, String) {
+    let CertifiedKey { cert, signing_key } =
+        generate_simple_self_signed(vec!["localhost".to_string(), "127.0.0.1".to_string()])
+            .unwrap();
+    std::fs::create_dir_all(dir).unwrap();
+    let cert_path = dir.join("cert.pem");
+    let key_path = dir.join("key.pem");
+    std::fs::write(&cert_path, cert.pem()).unwrap();
+    std::fs::write(&key_path, signing_key.serialize_pem()).unwrap();
+    (
+        key_path.to_string_lossy().into_owned(),
+        cert_path.to_string_lossy().into_owned(),
+    )
+}
+
+fn client_endpoint() -> Endpoint<QUIC> {
+    Endpoint::<QUIC>::create_client(&ClientConfig {
+        port: 0,
+        verify_certificate: false,
+    })
+    .unwrap()
+}
+
+fn put_string(buf: &mut BytesMut, value: &str) {
+    buf.put_u32(value.len() as u32);
+    buf.put_slice(value.as_bytes());
+}
+
+/// Writes a recording of `live/room` `video` with two groups of two objects,
+/// 300 ms apart, in the relay's recording layout.
+fn write_recording(path: &Path) {
+    let mut buf = BytesMut::new();
+    buf.put_slice(b"MOQTREC1");
+    put_string(&mut buf, "live/room");
+    put_string(&mut buf, "video");
+    for group_id in 0..2u64 {
+        buf.put_u64(group_id * 300);
+        buf.put_u32(2);
+        for object_id in 0..2 {
+            let object = FetchObjectField::new(
+                group_id,
+                0,
+                object_id,
+                128,
+                ExtensionHeaders::default(),
+                FetchObject::Payload(Bytes::from(format!("g{group_id}o{object_id}"))),
+            )
+            .encode();
+            buf.put_u32(object.len() as u32);
+            buf.put_slice(&object);
+        }
+    }
+    std::fs::write(path, buf).unwrap();
+}
+
+async fn connect(endpoint: &Endpoint<QUIC>, remote: SocketAddr) -> Session<QUIC> {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        endpoint.connect(remote, "127.0.0.1").await?.await
+    })
+    .await
+    .expect("session timed out")
+    .expect("session failed")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn recording_is_served_to_subscribers_and_fetch() {
+    let port = free_udp_port();
+    let remote: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+    let dir = std::env::temp_dir().join(format!("relay-replay-{port}"));
+    let (key_path, cert_path) = generate_certs(&dir);
+    let recording_path = dir.join("live_room_video.moqrec");
+    write_recording(&recording_path);
+
+    let server = RelayServer::new(&key_path, &cert_path);
+    let _handler = server.spawn_client_transport::<QUIC>(port);
+    let _replay = server
+        .replay_recording(&recording_path, remote)
+        .await
+        .unwrap();
+    // Let the replay session announce its namespace.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let endpoint = client_endpoint();
+    let session = connect(&endpoint, remote).await;
+    let mut subscriber = session.subscriber();
+    let subscription = subscriber
+        .subscribe(
+            "live/room".to_string(),
+            "video".to_string(),
+            SubscribeOption {
+                subscriber_priority: 128,
+                group_order: GroupOrder::Ascending,
+                forward: true,
+                filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
+            },
+        )
+        .await
+        .expect("subscribe to the replayed track");
+    let DataReceiver::Stream(mut factory) = subscriber
+        .accept_data_receiver(&subscription)
+        .await
+        .unwrap()
+    else {
+        panic!("replay uses subgroup streams");
+    };
+    // Group 0 is sent as soon as the replay accepts the SUBSCRIBE and may
+    // race the relay's downstream delivery; group 1 follows 300 ms later.
+    let last_group = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let mut stream = factory.next().await.unwrap();
+            let mut group_id = None;
+            let mut objects = 0;
+            while let Ok(Some(received)) = stream.receive().await {
+                match received {
+                    Subgroup::Header(header) => group_id = Some(header.group_id),
+                    Subgroup::Object(_) => objects += 1,
+                }
+            }
+            if group_id == Some(1) {
+                return objects;
+            }
+        }
+    })
+    .await
+    .expect("replayed groups did not arrive");
+    assert_eq!(last_group, 2);
+
+    let handle = subscriber
+        .fetch(
+            "live/room".to_string(),
+            "video".to_string(),
+            Location {
+                group_id: 0,
+                object_id: 0,
+            },
+            Location {
+                group_id: 1,
+                object_id: 0,
+            },
+            FetchOption::default(),
+        )
+        .await
+        .expect("fetch from the replayed track");
+    let mut receiver = subscriber.accept_fetch_receiver(&handle).await.unwrap();
+    let mut fetched = Vec::new();
+    loop {
+        match tokio::time::timeout(Duration::from_secs(5), receiver.receive())
+            .await
+            .expect("fetch stalled")
+        {
+            Ok(Fetch::Header(_)) => {}
+            Ok(Fetch::Object(object)) => fetched.push((object.group_id, object.object_id)),
+            Ok(Fetch::End) => break,
+            Err(error) => panic!("fetch failed: {error}"),
+        }
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(fetched, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+}
@@ -0,0 +1,109 @@
+//! A public relay must close connections that never send `ClientSetup` and
+//! refuse connections beyond its session limit.
+
+use std::net::{SocketAddr, UdpSocket};
+use std::path::Path;
+use std::time::Duration;
+
+use moqt::{ClientConfig, Endpoint, QUIC};
+use rcgen::{CertifiedKey, generate_simple_self_signed};
+use relay::{RelayConfig, RelayServer};
+
+/// Grabs an ephemeral UDP port, then releases it so the relay can bind it.
+fn free_udp_port() -> u16 {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.local_addr().unwrap().port()
+}
+
+/// Writes a fresh self-signed cert/key into `dir`. Returns (key_path, cert_path).
+fn generate_certs(dir: &Path) -> (String, String) {
+    let CertifiedKey { cert, signing_key } =
+        generate_simple_self_signed(vec!["localhost".to_string(), "127.0.0.1".to_string()])
+            .unwrap();
+    std::fs::create_dir_all(dir).unwrap();
+    let cert_path = dir.join("cert.pem");
+    let key_path = dir.join("key.pem");
+    std::fs::write(&cert_path, cert.pem()).unwrap();
+    std::fs::write(&key_path, signing_key.serialize_pem()).unwrap();
+    (
+        key_path.to_string_lossy().into_owned(),
+        cert_path.to_string_lossy().into_owned(),
+    )
+}
+
+fn client_endpoint() -> Endpoint<QUIC> {
+    Endpoint::<QUIC>::create_client(&ClientConfig {
+        port: 0,
+        verify_certificate: false,
+    })
+    .unwrap()
+}
+
+async fn relay_server(
+    name: &str,
+    port: u16,
+    setup_timeout: Option<Duration>,
+    max_client_sessions: Option<usize>,
+) -> RelayServer {
+    let cert_dir = std::env::temp_dir().join(format!("relay-{name}-{port}"));
+    let (key_path, cert_path) = generate_certs(&cert_dir);
+    let mut config = RelayConfig::from_env().unwrap();
+    config.setup_timeout = setup_timeout;
+    config.max_client_sessions = max_client_sessions;
+    RelayServer::new_with_config(&key_path, &cert_path, config)
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn connection_without_client_setup_is_closed() {
+    let port = free_udp_port();
+    let remote: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+    let server = relay_server(
+        "setup-timeout",
+        port,
+        Some(Duration::from_millis(200)),
+        None,
+    )
+    .await;
+    let _handler = server.spawn_client_transport::<QUIC>(port);
+
+    // Complete the QUIC handshake but hold back ClientSetup past the timeout.
+    let endpoint = client_endpoint();
+    let connecting = endpoint
+        .connect(remote, "127.0.0.1")
+        .await
+        .expect("QUIC handshake should succeed");
+    tokio::time::sleep(Duration::from_millis(600)).await;
+
+    let result = tokio::time::timeout(Duration::from_secs(2), connecting)
+        .await
+        .expect("closed connection should fail fast");
+    assert!(result.is_err(), "session established after setup timeout");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn connections_beyond_the_session_limit_are_refused() {
+    let port = free_udp_port();
+    let remote: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+    let server = relay_server("session-limit", port, None, Some(1)).await;
+    let _handler = server.spawn_client_transport::<QUIC>(port);
+
+    let endpoint_a = client_endpoint();
+    let _session_a = tokio::time::timeout(Duration::from_secs(2), async {
+        endpoint_a.connect(remote, "127.0.0.1").await?.await
+    })
+    .await
+    .expect("first session timed out")
+    .expect("first session failed");
+
+    let endpoint_b = client_endpoint();
+    let result = tokio::time::timeout(Duration::from_secs(2), async {
+        endpoint_b.connect(remote, "127.0.0.1").await?.await
+    })
+    .await;
+    assert!(
+        !matches!(result, Ok(Ok(_))),
+        "a session was established beyond the limit"
+    );
+}
@@ -21,4 +21,8 @@ pub struct Catalog {
     pub is_complete: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tracks: Option<Vec<Track>>,
+    /// Revision of this catalog; each [`CatalogDelta`](crate::CatalogDelta)
+    /// moves it forward by one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub catalog_sequence: Option<u64>,
 }
@@ -0,0 +1,512 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::catalog::Catalog;
+
+const CATALOG_SEQUENCE_KEY: &str = "catalogSequence";
+
+/// One JSON Patch (RFC 6902) operation. Only the operations produced by
+/// [`CatalogDelta::between`] are supported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+/// Changes from the catalog at `base_sequence` to the one at
+/// `catalog_sequence`, sent instead of re-sending the whole catalog.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogDelta {
+    pub base_sequence: u64,
+    pub catalog_sequence: u64,
+    pub patch: Vec<PatchOperation>,
+}
+
+#[derive(Debug)]
+pub enum CatalogDeltaError {
+    /// A catalog taking part in a delta has no `catalogSequence`.
+    MissingSequence,
+    /// The delta applies to another catalog revision.
+    SequenceMismatch {
+        expected: u64,
+        actual: Option<u64>,
+    },
+    /// A patch path does not resolve in the catalog.
+    InvalidPath(String),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CatalogDeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSequence => write!(f, "catalog has no catalogSequence"),
+            Self::SequenceMismatch { expected, actual } => write!(
+                f,
+                "delta applies to catalog sequence {expected}, have {actual:?}"
+            ),
+            Self::InvalidPath(path) => write!(f, "invalid patch path: {path}"),
+            Self::Json(error) => write!(f, "catalog json: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for CatalogDeltaError {}
+
+impl From<serde_json::Error> for CatalogDeltaError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl CatalogDelta {
+    /// Diffs two catalog revisions. Both must carry a `catalogSequence`.
+    pub fn between(base: &Catalog, target: &Catalog) -> Result<Self, CatalogDeltaError> {
+        let base_sequence = base
+            .catalog_sequence
+            .ok_or(CatalogDeltaError::MissingSequence)?;
+        let catalog_sequence = target
+            .catalog_sequence
+            .ok_or(CatalogDeltaError::MissingSequence)?;
+        let mut patch = Vec::new();
+        diff(
+            &catalog_value(base)?,
+            &catalog_value(target)?,
+            "",
+            &mut patch,
+        );
+        Ok(Self {
+            base_sequence,
+            catalog_sequence,
+            patch,
+        })
+    }
+
+    /// Applies this delta to `base`, which must be the catalog at
+    /// `base_sequence`.
+    pub fn apply_to(&self, base: &Catalog) -> Result<Catalog, CatalogDeltaError> {
+        if base.catalog_sequence != Some(self.base_sequence) {
+            return Err(CatalogDeltaError::SequenceMismatch {
+                expected: self.base_sequence,
+                actual: base.catalog_sequence,
+            });
+        }
+        let mut value = catalog_value(base)?;
+        for operation in &self.patch {
+            apply(&mut value, operation)?;
+        }
+        let mut catalog: Catalog = serde_json::from_value(value)?;
+        catalog.catalog_sequence = Some(self.catalog_sequence);
+        Ok(catalog)
+    }
+}
+
+/// The sequence number is carried by the delta itself, so it is left out
+/// of the diffed document.
+fn catalog_value(catalog: &Catalog) -> Result<Value, serde_json::Error> {
+    let mut value = serde_json::to_value(catalog)?;
+    if let Value::Object(map) = &mut value {
+        map.remove(CATALOG_SEQUENCE_KEY);
+    }
+    Ok(value)
+}
+
+/// One object on the catalog track: a full catalog or a delta.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CatalogObject {
+    Delta(CatalogDelta),
+    Full(Catalog),
+}
+
+impl CatalogObject {
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, CatalogDeltaError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>, CatalogDeltaError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+}
+
+/// What [`CatalogReceiver::receive`] did with an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogUpdate {
+    /// The current catalog changed.
+    Applied,
+    /// The object is older than the current catalog and was ignored.
+    Stale,
+    /// A delta cannot be applied (no base catalog, a missed revision or a
+    /// bad patch); fetch the full catalog again.
+    ResyncRequired,
+}
+
+/// Subscriber-side catalog state: applies full catalogs and deltas in
+/// sequence and detects gaps.
+#[derive(Debug, Default)]
+pub struct CatalogReceiver {
+    catalog: Option<Catalog>,
+}
+
+impl CatalogReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn catalog(&self) -> Option<&Catalog> {
+        self.catalog.as_ref()
+    }
+
+    pub fn receive(&mut self, object: CatalogObject) -> CatalogUpdate {
+        let current_sequence = self
+            .catalog
+            .as_ref()
+            .and_then(|catalog| catalog.catalog_sequence);
+        match object {
+            CatalogObject::Full(catalog) => {
+                if let (Some(current), Some(received)) =
+                    (current_sequence, catalog.catalog_sequence)
+                    && received < current
+                {
+                    return CatalogUpdate::Stale;
+                }
+                self.catalog = Some(catalog);
+                CatalogUpdate::Applied
+            }
+            CatalogObject::Delta(delta) => {
+                let (Some(current), Some(current_sequence)) = (&self.catalog, current_sequence)
+                else {
+                    return CatalogUpdate::ResyncRequired;
+                };
+                if delta.catalog_sequence <= current_sequence {
+                    return CatalogUpdate::Stale;
+                }
+                match delta.apply_to(current) {
+                    Ok(catalog) => {
+                        self.catalog = Some(catalog);
+                        CatalogUpdate::Applied
+                    }
+                    Err(_) => CatalogUpdate::ResyncRequired,
+                }
+            }
+        }
+    }
+}
+
+/// Appends the operations turning `base` into `target` at `path`. Arrays are
+/// compared element by element, so a changed track yields patches inside it.
+fn diff(base: &Value, target: &Value, path: &str, patch: &mut Vec<PatchOperation>) {
+    if base == target {
+        return;
+    }
+    match (base, target) {
+        (Value::Object(base), Value::Object(target)) => diff_objects(base, target, path, patch),
+        (Value::Array(base), Value::Array(target)) => {
+            for (index, (base, target)) in base.iter().zip(target).enumerate() {
+                diff(base, target, &format!("{path}/{index}"), patch);
+            }
+            for (index, value) in target.iter().enumerate().skip(base.len()) {
+                patch.push(PatchOperation::Add {
+                    path: format!("{path}/{index}"),
+                    value: value.clone(),
+                });
+            }
+            // Highest index first, so earlier removals do not shift later ones.
+            for index in (target.len()..base.len()).rev() {
+                patch.push(PatchOperation::Remove {
+                    path: format!("{path}/{index}"),
+                });
+            }
+        }
+        _ => patch.push(PatchOperation::Replace {
+            path: path.to_string(),
+            value: target.clone(),
+        }),
+    }
+}
+
+fn diff_objects(
+    base: &Map<String, Value>,
+    target: &Map<String, Value>,
+    path: &str,
+    patch: &mut Vec<PatchOperation>,
+) {
+    for (key, base_value) in base {
+        let child = format!("{path}/{}", escape_token(key));
+        match target.get(key) {
+            Some(target_value) => diff(base_value, target_value, &child, patch),
+            None => patch.push(PatchOperation::Remove { path: child }),
+        }
+    }
+    for (key, value) in target {
+        if !base.contains_key(key) {
+            patch.push(PatchOperation::Add {
+                path: format!("{path}/{}", escape_token(key)),
+                value: value.clone(),
+            });
+        }
+    }
+}
+
+fn apply(document: &mut Value, operation: &PatchOperation) -> Result<(), CatalogDeltaError> {
+    let path = match operation {
+        PatchOperation::Add { path, .. }
+        | PatchOperation::Remove { path }
+        | PatchOperation::Replace { path, .. } => path,
+    };
+    let invalid = || CatalogDeltaError::InvalidPath(path.clone());
+    let mut tokens = parse_pointer(path).ok_or_else(invalid)?;
+    let Some(last) = tokens.pop() else {
+        // The empty pointer names the whole document.
+        return match operation {
+            PatchOperation::Add { value, .. } | PatchOperation::Replace { value, .. } => {
+                *document = value.clone();
+                Ok(())
+            }
+            PatchOperation::Remove { .. } => Err(invalid()),
+        };
+    };
+    let mut parent = document;
+    for token in &tokens {
+        parent = match parent {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(items) => token
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get_mut(index)),
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+    }
+    match (parent, operation) {
+        (Value::Object(map), PatchOperation::Add { value, .. }) => {
+            map.insert(last, value.clone());
+        }
+        (Value::Object(map), PatchOperation::Remove { .. }) => {
+            map.remove(&last).ok_or_else(invalid)?;
+        }
+        (Value::Object(map), PatchOperation::Replace { value, .. }) => {
+            *map.get_mut(&last).ok_or_else(invalid)? = value.clone();
+        }
+        (Value::Array(items), PatchOperation::Add { value, .. }) => {
+            let index = if last == "-" {
+                items.len()
+            } else {
+                last.parse::<usize>().map_err(|_| invalid())?
+            };
+            if index > items.len() {
+                return Err(invalid());
+            }
+            items.insert(index, value.clone());
+        }
+        (Value::Array(items), PatchOperation::Remove { .. }) => {
+            let index = last.parse::<usize>().map_err(|_| invalid())?;
+            if index >= items.len() {
+                return Err(invalid());
+            }
+            items.remove(index);
+        }
+        (Value::Array(items), PatchOperation::Replace { value, .. }) => {
+            let index = last.parse::<usize>().map_err(|_| invalid())?;
+            *items.get_mut(index).ok_or_else(invalid)? = value.clone();
+        }
+        _ => return Err(invalid()),
+    }
+    Ok(())
+}
+
+/// Splits a JSON Pointer (RFC 6901) into unescaped reference tokens.
+fn parse_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/')?;
+    Some(
+        rest.split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{KnownPackaging, Packaging, Track};
+
+    fn track(name: &str, bitrate: u64) -> Track {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "packaging": "loc",
+            "isLive": true,
+            "bitrate": bitrate,
+        }))
+        .unwrap()
+    }
+
+    fn catalog(sequence: u64, tracks: Vec<Track>) -> Catalog {
+        Catalog {
+            version: Some(1),
+            delta_update: None,
+            add_tracks: None,
+            remove_tracks: None,
+            clone_tracks: None,
+            generated_at: None,
+            is_complete: Some(true),
+            tracks: Some(tracks),
+            catalog_sequence: Some(sequence),
+        }
+    }
+
+    #[test]
+    fn delta_carries_only_the_changed_fields() {
+        // Arrange
+        let base = catalog(3, vec![track("video", 1_000), track("audio", 64)]);
+        let target = catalog(4, vec![track("video", 2_000), track("audio", 64)]);
+
+        // Act
+        let delta = CatalogDelta::between(&base, &target).unwrap();
+
+        // Assert
+        assert_eq!(delta.base_sequence, 3);
+        assert_eq!(delta.catalog_sequence, 4);
+        assert_eq!(
+            delta.patch,
+            vec![PatchOperation::Replace {
+                path: "/tracks/0/bitrate".to_string(),
+                value: serde_json::json!(2_000),
+            }]
+        );
+    }
+
+    #[test]
+    fn applying_a_delta_reproduces_the_target() {
+        // Arrange: one track changes, one is added, the codec field goes away
+        let mut video = track("video", 1_000);
+        video.codec = Some("avc1.640028".to_string());
+        let base = catalog(0, vec![video, track("audio", 64), track("data", 1)]);
+        let mut target = catalog(1, vec![track("video", 1_500), track("audio", 64)]);
+        target.tracks.as_mut().unwrap()[1].packaging =
+            Packaging::Known(KnownPackaging::MediaTimeline);
+        let delta = CatalogDelta::between(&base, &target).unwrap();
+
+        // Act
+        let applied = delta.apply_to(&base).unwrap();
+
+        // Assert
+        assert_eq!(applied, target);
+    }
+
+    #[test]
+    fn delta_for_another_revision_is_rejected() {
+        // Arrange
+        let base = catalog(5, vec![track("video", 1_000)]);
+        let delta = CatalogDelta {
+            base_sequence: 6,
+            catalog_sequence: 7,
+            patch: vec![],
+        };
+
+        // Act
+        let result = delta.apply_to(&base);
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(CatalogDeltaError::SequenceMismatch {
+                expected: 6,
+                actual: Some(5)
+            })
+        ));
+    }
+
+    #[test]
+    fn catalog_objects_are_told_apart_on_the_wire() {
+        // Arrange
+        let full = CatalogObject::Full(catalog(1, vec![track("video", 1)]));
+        let delta = CatalogObject::Delta(CatalogDelta {
+            base_sequence: 1,
+            catalog_sequence: 2,
+            patch: vec![PatchOperation::Remove {
+                path: "/tracks/0".to_string(),
+            }],
+        });
+
+        // Act
+        let full_round_trip = CatalogObject::from_slice(&full.to_vec().unwrap()).unwrap();
+        let delta_round_trip = CatalogObject::from_slice(&delta.to_vec().unwrap()).unwrap();
+
+        // Assert
+        assert_eq!(full_round_trip, full);
+        assert_eq!(delta_round_trip, delta);
+    }
+
+    #[test]
+    fn receiver_applies_in_order_and_requests_resync_on_gap() {
+        // Arrange
+        let first = catalog(1, vec![track("video", 1)]);
+        let second = catalog(2, vec![track("video", 2)]);
+        let third = catalog(3, vec![track("video", 3)]);
+        let fourth = catalog(4, vec![track("video", 4)]);
+        let mut receiver = CatalogReceiver::new();
+
+        // Act
+        let orphan_delta = receiver.receive(CatalogObject::Delta(
+            CatalogDelta::between(&first, &second).unwrap(),
+        ));
+        let full = receiver.receive(CatalogObject::Full(first.clone()));
+        let in_order = receiver.receive(CatalogObject::Delta(
+            CatalogDelta::between(&first, &second).unwrap(),
+        ));
+        let replayed = receiver.receive(CatalogObject::Delta(
+            CatalogDelta::between(&first, &second).unwrap(),
+        ));
+        let gap = receiver.receive(CatalogObject::Delta(
+            CatalogDelta::between(&third, &fourth).unwrap(),
+        ));
+
+        // Assert
+        assert_eq!(orphan_delta, CatalogUpdate::ResyncRequired);
+        assert_eq!(full, CatalogUpdate::Applied);
+        assert_eq!(in_order, CatalogUpdate::Applied);
+        assert_eq!(replayed, CatalogUpdate::Stale);
+        assert_eq!(gap, CatalogUpdate::ResyncRequired);
+        assert_eq!(receiver.catalog(), Some(&second));
+    }
+
+    #[test]
+    fn pointer_tokens_are_escaped() {
+        // Arrange
+        let mut document = serde_json::json!({ "a/b": { "c~d": 1 } });
+        let patch = {
+            let mut patch = Vec::new();
+            diff(
+                &document.clone(),
+                &serde_json::json!({ "a/b": { "c~d": 2 } }),
+                "",
+                &mut patch,
+            );
+            patch
+        };
+
+        // Act
+        apply(&mut document, &patch[0]).unwrap();
+
+        // Assert
+        assert_eq!(
+            patch[0],
+            PatchOperation::Replace {
+                path: "/a~1b/c~0d".to_string(),
+                value: serde_json::json!(2),
+            }
+        );
+        assert_eq!(document, serde_json::json!({ "a/b": { "c~d": 2 } }));
+    }
+}
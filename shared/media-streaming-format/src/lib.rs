@@ -1,7 +1,11 @@
 pub mod catalog;
+pub mod delta;
 pub mod track;
 pub mod types;
 
 pub use catalog::Catalog;
+pub use delta::{
+    CatalogDelta, CatalogDeltaError, CatalogObject, CatalogReceiver, CatalogUpdate, PatchOperation,
+};
 pub use track::{Track, TrackRef};
 pub use types::{KnownPackaging, KnownTrackRole, Packaging, TrackRole};
@@ -34,6 +34,48 @@ impl LocHeader {
                 .collect(),
         }
     }
+
+    /// The first Capture Timestamp, in microseconds since the Unix epoch.
+    pub fn capture_timestamp(&self) -> Option<u64> {
+        self.extensions
+            .iter()
+            .find_map(|extension| match extension {
+                LocHeaderExtension::CaptureTimestamp(timestamp) => {
+                    Some(timestamp.micros_since_unix_epoch)
+                }
+                _ => None,
+            })
+    }
+
+    /// The first Video Frame Marking flags (RFC 9626).
+    pub fn video_frame_marking(&self) -> Option<u64> {
+        self.extensions
+            .iter()
+            .find_map(|extension| match extension {
+                LocHeaderExtension::VideoFrameMarking(marking) => Some(marking.flags),
+                _ => None,
+            })
+    }
+
+    /// The first Audio Level.
+    pub fn audio_level(&self) -> Option<u8> {
+        self.extensions
+            .iter()
+            .find_map(|extension| match extension {
+                LocHeaderExtension::AudioLevel(level) => Some(level.level),
+                _ => None,
+            })
+    }
+
+    /// The first Video Config, i.e. the codec's decoder configuration record.
+    pub fn video_config(&self) -> Option<&[u8]> {
+        self.extensions
+            .iter()
+            .find_map(|extension| match extension {
+                LocHeaderExtension::VideoConfig(config) => Some(config.data.as_slice()),
+                _ => None,
+            })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -106,6 +148,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn accessors_return_first_known_extension() {
+        let header = LocHeader {
+            extensions: vec![
+                LocHeaderExtension::Unknown(UnknownHeaderExtension {
+                    id: 8,
+                    value: LocHeaderValue::Even(1),
+                }),
+                LocHeaderExtension::CaptureTimestamp(CaptureTimestamp {
+                    micros_since_unix_epoch: 10,
+                }),
+                LocHeaderExtension::CaptureTimestamp(CaptureTimestamp {
+                    micros_since_unix_epoch: 20,
+                }),
+                LocHeaderExtension::VideoFrameMarking(VideoFrameMarking { flags: 0x20 }),
+                LocHeaderExtension::VideoConfig(VideoConfig { data: vec![1, 2] }),
+            ],
+        };
+        assert_eq!(header.capture_timestamp(), Some(10));
+        assert_eq!(header.video_frame_marking(), Some(0x20));
+        assert_eq!(header.video_config(), Some([1u8, 2].as_slice()));
+        assert_eq!(header.audio_level(), None);
+    }
+
     #[test]
     fn loc_header_preserves_order_and_unknown() {
         let header = LocHeader {
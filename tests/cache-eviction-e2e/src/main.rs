@@ -121,6 +121,7 @@ async fn subscribe_live(namespace: &str) -> anyhow::Result<Session<QUIC>> {
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
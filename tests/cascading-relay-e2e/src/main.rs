@@ -659,6 +659,7 @@ async fn subscribe_and_receive_ordered_objects(
         group_order: GroupOrder::Ascending,
         forward: true,
         filter_type: FilterType::LargestObject,
+        max_cache_duration: None,
     };
     tracing::info!(%track_namespace, %track_name, count, "subscriber sending SUBSCRIBE for ordered objects");
     let subscription = session
@@ -869,6 +870,7 @@ where
         group_order: GroupOrder::Ascending,
         forward: true,
         filter_type: FilterType::LargestObject,
+        max_cache_duration: None,
     };
     tracing::info!(
         %track_namespace,
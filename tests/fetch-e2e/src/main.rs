@@ -272,6 +272,7 @@ async fn carol(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -352,6 +353,7 @@ async fn bob(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -605,6 +607,7 @@ async fn run_cross_relay_standalone_fetch(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -690,6 +693,7 @@ async fn run_cross_relay_joining_fetch(relay_a_url: &str, relay_b_url: &str) ->
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -711,6 +715,7 @@ async fn run_cross_relay_joining_fetch(relay_a_url: &str, relay_b_url: &str) ->
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -768,6 +773,7 @@ async fn run_cross_relay_leading_gap_fetch(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -786,6 +792,7 @@ async fn run_cross_relay_leading_gap_fetch(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -928,6 +935,7 @@ async fn run_upstream_fetch_forwarding_data_not_found(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -975,6 +983,7 @@ async fn run_cross_relay_cold_cache_joining_fetch(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -996,6 +1005,7 @@ async fn run_cross_relay_cold_cache_joining_fetch(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -1032,6 +1042,7 @@ async fn run_cross_relay_cold_cache_joining_fetch(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -1133,6 +1144,7 @@ async fn run_cross_relay_live_joining_fetch(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
@@ -1152,6 +1164,7 @@ async fn run_cross_relay_live_joining_fetch(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;
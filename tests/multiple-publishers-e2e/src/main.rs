@@ -148,6 +148,7 @@ async fn bob(
                 group_order: GroupOrder::Ascending,
                 forward: true,
                 filter_type: FilterType::LargestObject,
+                max_cache_duration: None,
             },
         )
         .await?;